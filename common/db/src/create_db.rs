@@ -70,6 +70,16 @@ macro_rules! create_db {
   };
 }
 
+/// A cursor into a channel, used to observe its messages without consuming them.
+///
+/// Cursors don't affect the channel's consumer, which will still receive every message. If the
+/// consumer receives messages the cursor has yet to observe, the cursor skips past them.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ChannelCursor {
+  #[doc(hidden)]
+  pub next: u32,
+}
+
 #[macro_export]
 macro_rules! db_channel {
   ($db_name: ident {
@@ -113,6 +123,42 @@ macro_rules! db_channel {
           }
           res
         }
+
+        // Create a cursor positioned at the oldest message yet to be received
+        #[allow(dead_code)]
+        pub(crate) fn cursor(getter: &impl Get $(, $arg: $arg_type)*) -> $crate::ChannelCursor {
+          let messages_recvd = getter.get($field_name::key($($arg),*, 1)).map(|counter| {
+            u32::from_le_bytes(counter.try_into().unwrap())
+          }).unwrap_or(0);
+          $crate::ChannelCursor { next: messages_recvd }
+        }
+        // Observe the next message yet to be received, without receiving it, advancing the cursor
+        #[allow(dead_code)]
+        pub(crate) fn peek(
+          getter: &impl Get,
+          $($arg: $arg_type,)*
+          cursor: &mut $crate::ChannelCursor,
+        ) -> Option<$field_type> {
+          // Skip any messages received since this cursor was positioned, as they were deleted
+          let oldest = $field_name::cursor(getter, $($arg),*);
+          cursor.next = cursor.next.max(oldest.next);
+
+          let res = $field_name::get(getter, $($arg),*, cursor.next + 2);
+          if res.is_some() {
+            cursor.next += 1;
+          }
+          res
+        }
+        // All messages yet to be received, in the order they'll be received in
+        #[allow(dead_code)]
+        pub(crate) fn pending(getter: &impl Get $(, $arg: $arg_type)*) -> Vec<$field_type> {
+          let mut cursor = $field_name::cursor(getter, $($arg),*);
+          let mut res = vec![];
+          while let Some(message) = $field_name::peek(getter, $($arg,)* &mut cursor) {
+            res.push(message);
+          }
+          res
+        }
       }
     )*
   };
@@ -0,0 +1,36 @@
+use std::io::{self, Read};
+
+use zeroize::Zeroizing;
+
+use serai_env::keystore;
+
+// Create a keystore from an env file (`NAME=value` lines) read from stdin.
+//
+// Usage: `serai-keystore <path> < secrets.env`
+fn main() {
+  let path = std::env::args().nth(1).expect("usage: serai-keystore <path> < secrets.env");
+
+  let mut env_file = Zeroizing::new(String::new());
+  io::stdin().read_to_string(&mut env_file).expect("couldn't read the entries from stdin");
+  let mut entries = keystore::Entries::new();
+  for line in env_file.lines() {
+    if line.trim().is_empty() || line.starts_with('#') {
+      continue;
+    }
+    let (name, value) = line.split_once('=').expect("entry wasn't of the form NAME=value");
+    entries.insert(name.trim().to_string(), Zeroizing::new(value.to_string()));
+  }
+
+  let passphrase = keystore::passphrase().expect("couldn't read the passphrase");
+  if std::env::var("KEYSTORE_PASSPHRASE_FILE").is_err() {
+    let confirmation = Zeroizing::new(
+      rpassword::prompt_password("Confirm keystore passphrase: ")
+        .expect("couldn't read the passphrase"),
+    );
+    assert_eq!(passphrase, confirmation, "passphrases didn't match");
+  }
+
+  let keystore = keystore::seal(&entries, &passphrase).expect("couldn't create the keystore");
+  std::fs::write(&path, keystore).expect("couldn't write the keystore");
+  println!("wrote {} entries to {path}", entries.len());
+}
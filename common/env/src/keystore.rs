@@ -0,0 +1,139 @@
+use std::{collections::HashMap, io};
+
+use zeroize::Zeroizing;
+use rand_core::{RngCore, OsRng};
+
+use argon2::{Algorithm, Version, Params, Argon2};
+use chacha20poly1305::{
+  aead::{Aead, KeyInit, Payload},
+  Key, XNonce, XChaCha20Poly1305,
+};
+
+/*
+  An encrypted keystore, holding the secrets otherwise provided as environment variables.
+
+  A keystore file is the magic, a version byte, the Argon2id parameters (the memory cost in KiB,
+  the amount of iterations, and the degree of parallelism, each as a little-endian u32), a 16-byte
+  salt, and a 24-byte nonce, followed by the encryption (with XChaCha20-Poly1305) of the keystore's
+  entries. The encryption key is derived from a passphrase with Argon2id, using the parameters
+  specified. Everything prior to the nonce is authenticated as associated data, so the parameters
+  can't be downgraded without the keystore failing to open.
+
+  The entries are serialized as `NAME=value` lines, as would be written in an env file.
+*/
+
+const MAGIC: &[u8] = b"serai-keystore";
+const VERSION: u8 = 2;
+const PARAMS_LEN: usize = 3 * 4;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const AAD_LEN: usize = MAGIC.len() + 1 + PARAMS_LEN + SALT_LEN;
+const HEADER_LEN: usize = AAD_LEN + NONCE_LEN;
+
+/// The entries within a keystore.
+pub type Entries = HashMap<String, Zeroizing<String>>;
+
+fn invalid_data(msg: &'static str) -> io::Error {
+  io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn cipher(passphrase: &str, params: Params, salt: &[u8]) -> io::Result<XChaCha20Poly1305> {
+  let mut key = Zeroizing::new([0; 32]);
+  Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+    .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+    .map_err(|_| invalid_data("couldn't derive the keystore's key"))?;
+  Ok(XChaCha20Poly1305::new(Key::from_slice(key.as_ref())))
+}
+
+/// Encrypt the specified entries under the specified passphrase, returning the keystore file.
+pub fn seal(entries: &Entries, passphrase: &str) -> io::Result<Vec<u8>> {
+  let mut plaintext = Zeroizing::new(String::new());
+  // Sort the entries so the plaintext doesn't leak the HashMap's state
+  let mut names = entries.keys().collect::<Vec<_>>();
+  names.sort();
+  for name in names {
+    let value = &entries[name];
+    if name.is_empty() || name.contains(['=', '\n']) || value.contains('\n') {
+      Err(io::Error::new(io::ErrorKind::InvalidInput, "keystore entry couldn't be serialized"))?;
+    }
+    plaintext.push_str(name);
+    plaintext.push('=');
+    plaintext.push_str(value);
+    plaintext.push('\n');
+  }
+
+  let params = Params::default();
+  let mut salt = [0; SALT_LEN];
+  OsRng.fill_bytes(&mut salt);
+  let mut nonce = [0; NONCE_LEN];
+  OsRng.fill_bytes(&mut nonce);
+
+  let mut file = Vec::with_capacity(HEADER_LEN + plaintext.len() + 16);
+  file.extend(MAGIC);
+  file.push(VERSION);
+  file.extend(params.m_cost().to_le_bytes());
+  file.extend(params.t_cost().to_le_bytes());
+  file.extend(params.p_cost().to_le_bytes());
+  file.extend(salt);
+
+  let ciphertext = cipher(passphrase, params, &salt)?
+    .encrypt(XNonce::from_slice(&nonce), Payload { msg: plaintext.as_bytes(), aad: &file })
+    .map_err(|_| invalid_data("couldn't encrypt the keystore"))?;
+
+  file.extend(nonce);
+  file.extend(ciphertext);
+  Ok(file)
+}
+
+/// Decrypt a keystore file with the specified passphrase, returning its entries.
+pub fn open(file: &[u8], passphrase: &str) -> io::Result<Entries> {
+  if (file.len() < HEADER_LEN) || (&file[.. MAGIC.len()] != MAGIC) {
+    Err(invalid_data("file wasn't a keystore"))?;
+  }
+  if file[MAGIC.len()] != VERSION {
+    Err(invalid_data("keystore had an unsupported version"))?;
+  }
+  let (aad, rest) = file.split_at(AAD_LEN);
+  let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+  let (params, salt) = aad[(MAGIC.len() + 1) ..].split_at(PARAMS_LEN);
+  let param = |i: usize| u32::from_le_bytes(params[(i * 4) .. ((i + 1) * 4)].try_into().unwrap());
+  let params = Params::new(param(0), param(1), param(2), None)
+    .map_err(|_| invalid_data("keystore had invalid Argon2 parameters"))?;
+
+  let plaintext = Zeroizing::new(
+    cipher(passphrase, params, salt)?
+      .decrypt(XNonce::from_slice(nonce), Payload { msg: ciphertext, aad })
+      .map_err(|_| invalid_data("keystore passphrase was incorrect or the keystore was corrupt"))?,
+  );
+  let plaintext = std::str::from_utf8(&plaintext)
+    .map_err(|_| invalid_data("keystore entries weren't valid UTF-8"))?;
+
+  let mut entries = Entries::new();
+  for line in plaintext.lines() {
+    let (name, value) =
+      line.split_once('=').ok_or_else(|| invalid_data("keystore entry was malformed"))?;
+    entries.insert(name.to_string(), Zeroizing::new(value.to_string()));
+  }
+  Ok(entries)
+}
+
+/// Read the passphrase for a keystore.
+///
+/// If `KEYSTORE_PASSPHRASE_FILE` is set, the passphrase is read from that file (with any trailing
+/// newline removed). This allows the passphrase to be provided by a KMS or secret manager which
+/// mounts secrets as files. Else, the passphrase is prompted for on the terminal.
+pub fn passphrase() -> io::Result<Zeroizing<String>> {
+  if let Ok(path) = std::env::var("KEYSTORE_PASSPHRASE_FILE") {
+    let mut passphrase = Zeroizing::new(std::fs::read_to_string(path)?);
+    let len = passphrase.trim_end_matches(['\r', '\n']).len();
+    passphrase.truncate(len);
+    return Ok(passphrase);
+  }
+  rpassword::prompt_password("Keystore passphrase: ").map(Zeroizing::new)
+}
+
+/// Load the keystore at the specified path, obtaining its passphrase via `passphrase`.
+pub fn load(path: &str) -> io::Result<Entries> {
+  open(&std::fs::read(path)?, &passphrase()?)
+}
@@ -1,9 +1,39 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 
+/// An encrypted keystore for secrets.
+#[cfg(feature = "keystore")]
+pub mod keystore;
+
+// The keystore specified by `KEYSTORE_PATH`, loaded on first use
+#[cfg(feature = "keystore")]
+static KEYSTORE: std::sync::OnceLock<Option<keystore::Entries>> = std::sync::OnceLock::new();
+
 // Obtain a variable from the Serai environment/secret store.
+//
+// If `KEYSTORE_PATH` is set, variables present within the keystore are read from it, taking
+// precedence over the environment. This requires the `keystore` feature.
 pub fn var(variable: &str) -> Option<String> {
-  // TODO: Move this to a proper secret store
+  #[cfg(feature = "keystore")]
+  {
+    let keystore = KEYSTORE.get_or_init(|| {
+      let path = std::env::var("KEYSTORE_PATH").ok()?;
+      Some(
+        keystore::load(&path)
+          .unwrap_or_else(|e| panic!("couldn't load the keystore at {path}: {e}")),
+      )
+    });
+    if let Some(value) = keystore.as_ref().and_then(|keystore| keystore.get(variable)) {
+      return Some(value.to_string());
+    }
+  }
+  // Don't silently ignore a keystore if we weren't built with support for it
+  #[cfg(not(feature = "keystore"))]
+  assert!(
+    std::env::var_os("KEYSTORE_PATH").is_none(),
+    "KEYSTORE_PATH was set yet serai-env was built without the keystore feature"
+  );
+
   // TODO: Unset this variable
   std::env::var(variable).ok()
 }
@@ -0,0 +1,95 @@
+use core::time::Duration;
+use std::sync::{
+  atomic::{AtomicBool, Ordering},
+  Mutex,
+};
+
+use crate::http::Status;
+
+/*
+  A service is live if its process is running and no task has panicked. As a task panicking exits
+  the process (with `exit_on_panic`), the liveness probe solely fails while the process is exiting
+  (or if the probe can't be reached at all).
+
+  A service is ready if all of its checks passed. These are polled in the background by the
+  service, so probes are cheap to serve.
+*/
+
+/// How often services should poll the conditions for readiness.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How long services should wait for any individual check before considering it failed.
+pub const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The result of a check, by its name, with a description of what was observed.
+pub type Check = (&'static str, Result<String, String>);
+
+static PANICKED: AtomicBool = AtomicBool::new(false);
+
+// The result of each check from the most recent poll, or None if we have yet to poll
+static CHECKS: Mutex<Option<Vec<Check>>> = Mutex::new(None);
+
+/// Note a task panicked, failing the liveness probe.
+pub fn note_panic() {
+  PANICKED.store(true, Ordering::SeqCst);
+}
+
+/// Set the results of the most recent poll of the checks.
+pub fn set_checks(checks: Vec<Check>) {
+  for (name, result) in &checks {
+    if let Err(detail) = result {
+      log::debug!("readiness check {name} failed: {detail}");
+    }
+  }
+  *CHECKS.lock().unwrap() = Some(checks);
+}
+
+/// The liveness of the service, as the status, content type, and body of the response.
+pub fn live() -> (Status, &'static str, String) {
+  if PANICKED.load(Ordering::SeqCst) {
+    return (Status::ServiceUnavailable, "text/plain", "a task panicked".to_string());
+  }
+  (Status::Ok, "text/plain", "live".to_string())
+}
+
+/// The readiness of the service, with the result of each check.
+pub fn ready() -> (Status, serde_json::Value) {
+  let Some(checks) = CHECKS.lock().unwrap().clone() else {
+    return (
+      Status::ServiceUnavailable,
+      serde_json::json!({ "ready": false, "detail": "readiness has yet to be checked" }),
+    );
+  };
+  let ready = checks.iter().all(|(_, result)| result.is_ok());
+  let checks = checks
+    .iter()
+    .map(|(name, result)| {
+      let (passed, detail) = match result {
+        Ok(detail) => (true, detail),
+        Err(detail) => (false, detail),
+      };
+      serde_json::json!({ "name": name, "passed": passed, "detail": detail })
+    })
+    .collect::<Vec<_>>();
+  (
+    if ready { Status::Ok } else { Status::ServiceUnavailable },
+    serde_json::json!({ "ready": ready, "checks": checks }),
+  )
+}
+
+/// Serve the liveness and readiness probes on the specified port.
+#[cfg(feature = "export")]
+pub async fn serve(port: u16) {
+  log::info!("serving health probes on port {port}");
+  crate::http::serve_with_status(([0, 0, 0, 0], port).into(), |path: String| async move {
+    match path.as_str() {
+      "/health/live" => Some(live()),
+      "/health/ready" => {
+        let (status, body) = ready();
+        Some((status, "application/json", body.to_string()))
+      }
+      _ => None,
+    }
+  })
+  .await
+}
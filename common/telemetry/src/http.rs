@@ -0,0 +1,99 @@
+use core::{future::Future, time::Duration};
+use std::net::SocketAddr;
+
+use tokio::{
+  io::{AsyncReadExt, AsyncWriteExt},
+  net::{TcpStream, TcpListener},
+};
+
+// The maximum size of a request's head
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+
+/// Read the head (the request line and headers) of an HTTP request.
+///
+/// Returns None if the head was too large, wasn't sent in a timely manner, or the connection was
+/// closed.
+pub async fn read_head(socket: &mut TcpStream) -> Option<Vec<u8>> {
+  let mut request = vec![];
+  let mut buf = [0; 1024];
+  while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+    if request.len() > MAX_REQUEST_SIZE {
+      None?;
+    }
+    let read =
+      tokio::time::timeout(Duration::from_secs(5), socket.read(&mut buf)).await.ok()?.ok()?;
+    if read == 0 {
+      None?;
+    }
+    request.extend(&buf[.. read]);
+  }
+  Some(request)
+}
+
+/// The status of a successfully routed response.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Status {
+  Ok,
+  ServiceUnavailable,
+}
+
+impl Status {
+  fn line(self) -> &'static str {
+    match self {
+      Status::Ok => "200 OK",
+      Status::ServiceUnavailable => "503 Service Unavailable",
+    }
+  }
+}
+
+/// A minimal HTTP server, solely supporting GET requests, used for local introspection.
+///
+/// `handle` is called with the requested path, returning the content type and body of the
+/// response, or None if the path wasn't found.
+pub async fn serve<F, H>(addr: SocketAddr, handle: H)
+where
+  F: Send + Future<Output = Option<(&'static str, String)>>,
+  H: 'static + Send + Sync + Clone + Fn(String) -> F,
+{
+  serve_with_status(addr, move |path| {
+    let response = handle(path);
+    async move { response.await.map(|(content_type, body)| (Status::Ok, content_type, body)) }
+  })
+  .await
+}
+
+/// A minimal HTTP server, as `serve`, whose handler additionally specifies the status of each
+/// response.
+pub async fn serve_with_status<F, H>(addr: SocketAddr, handle: H)
+where
+  F: Send + Future<Output = Option<(Status, &'static str, String)>>,
+  H: 'static + Send + Sync + Clone + Fn(String) -> F,
+{
+  let server = TcpListener::bind(addr).await.unwrap();
+  loop {
+    let Ok((mut socket, _)) = server.accept().await else { continue };
+    let handle = handle.clone();
+    tokio::spawn(async move {
+      let Some(request) = read_head(&mut socket).await else { return };
+      let mut request_line = request.split(|b| *b == b' ');
+      let response = match (request_line.next(), request_line.next()) {
+        (Some(b"GET"), Some(path)) => {
+          match handle(String::from_utf8_lossy(path).into_owned()).await {
+            Some((status, content_type, body)) => format!(
+              "HTTP/1.1 {}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\
+             Connection: close\r\n\r\n{body}",
+              status.line(),
+              body.len()
+            ),
+            None => {
+              "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+            }
+          }
+        }
+        _ => "HTTP/1.1 405 Method Not Allowed\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+          .to_string(),
+      };
+      let _ = socket.write_all(response.as_bytes()).await;
+    });
+  }
+}
@@ -0,0 +1,34 @@
+#![cfg_attr(docsrs, feature(doc_cfg))]
+#![cfg_attr(docsrs, feature(doc_auto_cfg))]
+
+/// A minimal HTTP server, used to serve the metrics and health probes.
+pub mod http;
+
+/// A structured logger whose filter may be changed at runtime.
+pub mod logging;
+
+/// A registry of metrics, rendered in the Prometheus text exposition format.
+pub mod metrics;
+
+/// Liveness and readiness probes, intended for orchestration systems.
+pub mod health;
+
+/// A minimal WebSocket implementation, for both servers and clients.
+pub mod websocket;
+
+/// Override the panic handler with one which fails the liveness probe and exits the process.
+///
+/// Without this, a panicking tokio task would solely end that task, leaving the service running
+/// in an undefined state.
+pub fn exit_on_panic() {
+  let existing = std::panic::take_hook();
+  std::panic::set_hook(Box::new(move |panic| {
+    health::note_panic();
+    existing(panic);
+    const MSG: &str = "exiting the process due to a task panicking";
+    // This is also printed as logging may not have been initialized
+    println!("{MSG}");
+    log::error!("{MSG}");
+    std::process::exit(1);
+  }));
+}
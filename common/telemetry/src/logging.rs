@@ -0,0 +1,76 @@
+use std::{
+  io::{self, Write},
+  sync::RwLock,
+};
+
+use log::{
+  kv::{self, Key, Value, Source, VisitSource},
+  LevelFilter, Metadata, Record, Log,
+};
+use env_logger::{fmt::Formatter, filter};
+
+// The current filter, with the specification it was parsed from
+static FILTER: RwLock<Option<(String, filter::Filter)>> = RwLock::new(None);
+
+/// If a log with the specified metadata would be logged under the current filter.
+pub fn enabled(metadata: &Metadata) -> bool {
+  FILTER.read().unwrap().as_ref().is_some_and(|(_, filter)| filter.enabled(metadata))
+}
+
+// A logger whose filter may be changed at runtime
+struct Logger(env_logger::Logger);
+
+impl Log for Logger {
+  fn enabled(&self, metadata: &Metadata) -> bool {
+    enabled(metadata)
+  }
+
+  fn log(&self, record: &Record) {
+    if FILTER.read().unwrap().as_ref().is_some_and(|(_, filter)| filter.matches(record)) {
+      self.0.log(record);
+    }
+  }
+
+  fn flush(&self) {
+    self.0.flush();
+  }
+}
+
+// Write a record's key-value pairs as ` key=value`
+struct KeyValues<'a>(&'a mut Formatter);
+impl<'kvs> VisitSource<'kvs> for KeyValues<'_> {
+  fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), kv::Error> {
+    write!(self.0, " {key}={value}").map_err(|_| kv::Error::msg("couldn't write key-value pair"))
+  }
+}
+
+fn format(buf: &mut Formatter, record: &Record) -> io::Result<()> {
+  write!(buf, "[{} {:<5} {}] {}", buf.timestamp(), record.level(), record.target(), record.args())?;
+  record.key_values().visit(&mut KeyValues(buf)).map_err(|e| io::Error::other(e.to_string()))?;
+  writeln!(buf)
+}
+
+/// Initialize logging with the specified filter.
+///
+/// The filter uses the same syntax as `RUST_LOG` and may be changed with `set_filter`.
+pub fn init(spec: &str) {
+  set_filter(spec);
+  // The inner logger accepts everything, as we filter before passing records to it
+  let inner = env_logger::Builder::new().filter_level(LevelFilter::Trace).format(format).build();
+  log::set_boxed_logger(Box::new(Logger(inner))).expect("logger was already set");
+}
+
+/// The current filter.
+pub fn filter() -> Option<String> {
+  FILTER.read().unwrap().as_ref().map(|(spec, _)| spec.clone())
+}
+
+/// Change the filter at runtime.
+///
+/// Invalid directives within the filter are ignored, as they are with `RUST_LOG`.
+pub fn set_filter(spec: &str) {
+  let filter = filter::Builder::new().parse(spec).build();
+  log::set_max_level(filter.filter());
+  *FILTER.write().unwrap() = Some((spec.to_string(), filter));
+  log::info!(filter = spec; "set the log filter");
+}
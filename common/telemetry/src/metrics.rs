@@ -0,0 +1,206 @@
+use core::{fmt::Write, time::Duration};
+use std::{
+  sync::{Mutex, OnceLock},
+  collections::BTreeMap,
+};
+
+/// The upper bounds, in seconds, of the buckets for the durations observed.
+pub const DURATION_BUCKETS: [f64; 10] =
+  [1.0, 2.5, 5.0, 10.0, 20.0, 30.0, 60.0, 120.0, 300.0, 600.0];
+
+/// A histogram of durations, in seconds, bucketed by `DURATION_BUCKETS`.
+#[derive(Clone, Default, Debug)]
+pub struct Histogram {
+  buckets: [u64; DURATION_BUCKETS.len()],
+  count: u64,
+  sum: f64,
+}
+
+impl Histogram {
+  /// Observe a value.
+  pub fn observe(&mut self, value: f64) {
+    for (bucket, upper_bound) in self.buckets.iter_mut().zip(DURATION_BUCKETS) {
+      if value <= upper_bound {
+        *bucket += 1;
+      }
+    }
+    self.count += 1;
+    self.sum += value;
+  }
+
+  /// Write this histogram's series, with the specified name and labels.
+  ///
+  /// `labels` is the comma-separated labels shared by every series, such as `network="monero"`.
+  pub fn write(&self, res: &mut String, name: &str, labels: &str) {
+    let prefix = if labels.is_empty() { String::new() } else { format!("{labels},") };
+    for (count, upper_bound) in self.buckets.iter().zip(DURATION_BUCKETS) {
+      writeln!(res, "{name}_bucket{{{prefix}le=\"{upper_bound}\"}} {count}").unwrap();
+    }
+    writeln!(res, "{name}_bucket{{{prefix}le=\"+Inf\"}} {}", self.count).unwrap();
+    writeln!(res, "{} {}", series_name(&format!("{name}_sum"), labels), self.sum).unwrap();
+    writeln!(res, "{} {}", series_name(&format!("{name}_count"), labels), self.count).unwrap();
+  }
+}
+
+// The name of a series, with its labels if it has any
+fn series_name(name: &str, labels: &str) -> String {
+  if labels.is_empty() {
+    name.to_string()
+  } else {
+    format!("{name}{{{labels}}}")
+  }
+}
+
+/// Write the `HELP` and `TYPE` lines for a metric.
+pub fn write_header(res: &mut String, name: &str, help: &str, kind: &str) {
+  writeln!(res, "# HELP {name} {help}").unwrap();
+  writeln!(res, "# TYPE {name} {kind}").unwrap();
+}
+
+#[derive(Debug)]
+enum Series {
+  Gauge(BTreeMap<String, f64>),
+  Counter(BTreeMap<String, u64>),
+  Histogram(BTreeMap<String, Histogram>),
+}
+
+#[derive(Default)]
+struct Registry {
+  metrics: BTreeMap<&'static str, (&'static str, Series)>,
+  collectors: Vec<fn() -> String>,
+}
+
+fn registry() -> &'static Mutex<Registry> {
+  static REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+  REGISTRY.get_or_init(|| Mutex::new(Registry::default()))
+}
+
+fn format_labels(labels: &[(&str, &str)]) -> String {
+  labels.iter().map(|(name, value)| format!("{name}=\"{value}\"")).collect::<Vec<_>>().join(",")
+}
+
+// Update the series for a metric, creating the metric with `new` if it doesn't yet exist
+fn update(
+  name: &'static str,
+  help: &'static str,
+  new: fn() -> Series,
+  f: impl FnOnce(&mut Series),
+) {
+  if !cfg!(feature = "export") {
+    return;
+  }
+  let mut registry = registry().lock().unwrap();
+  f(&mut registry.metrics.entry(name).or_insert_with(|| (help, new())).1);
+}
+
+/// Set the value of a gauge.
+pub fn set_gauge(name: &'static str, help: &'static str, labels: &[(&str, &str)], value: f64) {
+  update(
+    name,
+    help,
+    || Series::Gauge(BTreeMap::new()),
+    |series| {
+      if let Series::Gauge(series) = series {
+        series.insert(format_labels(labels), value);
+      }
+    },
+  );
+}
+
+/// Stop reporting a gauge with the specified labels.
+pub fn remove_gauge(name: &'static str, labels: &[(&str, &str)]) {
+  if let Some((_, Series::Gauge(series))) = registry().lock().unwrap().metrics.get_mut(name) {
+    series.remove(&format_labels(labels));
+  }
+}
+
+/// Increment a counter.
+pub fn increment_counter(name: &'static str, help: &'static str, labels: &[(&str, &str)]) {
+  update(
+    name,
+    help,
+    || Series::Counter(BTreeMap::new()),
+    |series| {
+      if let Series::Counter(series) = series {
+        *series.entry(format_labels(labels)).or_insert(0) += 1;
+      }
+    },
+  );
+}
+
+/// Observe a duration with a histogram.
+pub fn observe_duration(
+  name: &'static str,
+  help: &'static str,
+  labels: &[(&str, &str)],
+  duration: Duration,
+) {
+  update(
+    name,
+    help,
+    || Series::Histogram(BTreeMap::new()),
+    |series| {
+      if let Series::Histogram(series) = series {
+        series.entry(format_labels(labels)).or_default().observe(duration.as_secs_f64());
+      }
+    },
+  );
+}
+
+/// Register a collector, rendering metrics in the Prometheus text exposition format, whose output
+/// will be included when the registry is rendered.
+///
+/// This is intended for services which track state beyond what's expressible with the registry.
+pub fn register_collector(collector: fn() -> String) {
+  registry().lock().unwrap().collectors.push(collector);
+}
+
+/// Render the metrics in the Prometheus text exposition format.
+pub fn render() -> String {
+  let mut res = String::new();
+  let collectors = {
+    let registry = registry().lock().unwrap();
+    for (name, (help, metric)) in &registry.metrics {
+      render_metric(&mut res, name, help, metric);
+    }
+    registry.collectors.clone()
+  };
+  // Collectors are called without holding the registry's lock, as they may record metrics
+  for collector in collectors {
+    res.push_str(&collector());
+  }
+  res
+}
+
+fn render_metric(res: &mut String, name: &str, help: &str, metric: &Series) {
+  match metric {
+    Series::Gauge(series) => {
+      write_header(res, name, help, "gauge");
+      for (labels, value) in series {
+        writeln!(res, "{} {value}", series_name(name, labels)).unwrap();
+      }
+    }
+    Series::Counter(series) => {
+      write_header(res, name, help, "counter");
+      for (labels, value) in series {
+        writeln!(res, "{} {value}", series_name(name, labels)).unwrap();
+      }
+    }
+    Series::Histogram(series) => {
+      write_header(res, name, help, "histogram");
+      for (labels, histogram) in series {
+        histogram.write(res, name, labels);
+      }
+    }
+  }
+}
+
+/// Serve the metrics over HTTP on the specified port.
+#[cfg(feature = "export")]
+pub async fn serve(port: u16) {
+  log::info!("serving metrics on port {port}");
+  crate::http::serve(([0, 0, 0, 0], port).into(), |path: String| async move {
+    (path == "/metrics").then(|| ("text/plain; version=0.0.4", render()))
+  })
+  .await
+}
@@ -0,0 +1,215 @@
+use rand_core::{RngCore, OsRng};
+
+use sha1::{Digest, Sha1};
+use base64ct::{Encoding, Base64};
+
+use tokio::{
+  io::{AsyncReadExt, AsyncWriteExt},
+  net::TcpStream,
+};
+
+// The GUID appended to a Sec-WebSocket-Key, per RFC 6455
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+// The maximum size of a response's head
+const MAX_RESPONSE_HEAD_SIZE: usize = 8 * 1024;
+
+/// The opcode of a continuation frame.
+pub const CONTINUATION: u8 = 0x0;
+/// The opcode of a text frame.
+pub const TEXT: u8 = 0x1;
+/// The opcode of a binary frame.
+pub const BINARY: u8 = 0x2;
+/// The opcode of a close frame.
+pub const CLOSE: u8 = 0x8;
+/// The opcode of a ping frame.
+pub const PING: u8 = 0x9;
+/// The opcode of a pong frame.
+pub const PONG: u8 = 0xA;
+
+/// A WebSocket frame.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Frame {
+  /// If this is the final frame of its message.
+  pub fin: bool,
+  /// The frame's opcode.
+  pub opcode: u8,
+  /// The frame's payload, unmasked.
+  pub payload: Vec<u8>,
+}
+
+/// The value for the Sec-WebSocket-Accept header, for the specified Sec-WebSocket-Key.
+pub fn accept(key: &str) -> String {
+  let mut hasher = Sha1::new();
+  hasher.update(key.trim().as_bytes());
+  hasher.update(WEBSOCKET_GUID.as_bytes());
+  Base64::encode_string(&hasher.finalize())
+}
+
+/// Encode a final WebSocket frame.
+///
+/// Frames sent by clients must be masked, while frames sent by servers must not be.
+pub fn encode_frame(opcode: u8, payload: &[u8], mask: Option<[u8; 4]>) -> Vec<u8> {
+  // FIN, as we never fragment frames
+  let mut frame = vec![0b1000_0000 | opcode];
+  let masked = if mask.is_some() { 0b1000_0000 } else { 0 };
+  if payload.len() < 126 {
+    frame.push(masked | u8::try_from(payload.len()).unwrap());
+  } else if let Ok(len) = u16::try_from(payload.len()) {
+    frame.push(masked | 126);
+    frame.extend(len.to_be_bytes());
+  } else {
+    frame.push(masked | 127);
+    frame.extend(u64::try_from(payload.len()).unwrap().to_be_bytes());
+  }
+  match mask {
+    Some(mask) => {
+      frame.extend(mask);
+      frame.extend(payload.iter().enumerate().map(|(i, byte)| byte ^ mask[i % 4]));
+    }
+    None => frame.extend(payload),
+  }
+  frame
+}
+
+/// Read a WebSocket frame.
+///
+/// `masked` is if the frame is expected to be masked, as frames from clients are. Returns None if
+/// the frame's masking wasn't as expected, its payload exceeded `max_size`, or the connection was
+/// closed.
+pub async fn read_frame(
+  socket: &mut (impl Unpin + AsyncReadExt),
+  masked: bool,
+  max_size: usize,
+) -> Option<Frame> {
+  let mut head = [0; 2];
+  socket.read_exact(&mut head).await.ok()?;
+  let fin = (head[0] & 0b1000_0000) != 0;
+  let opcode = head[0] & 0b0000_1111;
+  if ((head[1] & 0b1000_0000) != 0) != masked {
+    None?;
+  }
+  let len = match head[1] & 0b0111_1111 {
+    126 => u64::from(socket.read_u16().await.ok()?),
+    127 => socket.read_u64().await.ok()?,
+    len => u64::from(len),
+  };
+  let len = usize::try_from(len).ok().filter(|len| *len <= max_size)?;
+  let mut mask = [0; 4];
+  if masked {
+    socket.read_exact(&mut mask).await.ok()?;
+  }
+  let mut payload = vec![0; len];
+  socket.read_exact(&mut payload).await.ok()?;
+  for (i, byte) in payload.iter_mut().enumerate() {
+    *byte ^= mask[i % 4];
+  }
+  Some(Frame { fin, opcode, payload })
+}
+
+/// Split a `ws://` URL into the address to connect to and the path to request.
+///
+/// `wss://` isn't supported, as the services connected to are expected to be run alongside ours.
+pub fn parse_url(url: &str) -> Option<(String, String)> {
+  let url = url.strip_prefix("ws://")?;
+  let (address, path) = match url.find('/') {
+    Some(i) => (&url[.. i], &url[i ..]),
+    None => (url, "/"),
+  };
+  if address.is_empty() {
+    None?;
+  }
+  let has_port = !address.ends_with(']') &&
+    address.rsplit_once(':').is_some_and(|(_, port)| port.parse::<u16>().is_ok());
+  let address = if has_port { address.to_string() } else { format!("{address}:80") };
+  Some((address, path.to_string()))
+}
+
+/// A WebSocket connection, as a client.
+pub struct Client {
+  socket: TcpStream,
+  max_size: usize,
+}
+
+impl Client {
+  /// Connect to the WebSocket at the specified `ws://` URL.
+  ///
+  /// Messages larger than `max_size` will cause reading them to fail.
+  pub async fn connect(url: &str, max_size: usize) -> Result<Self, &'static str> {
+    let (address, path) = parse_url(url).ok_or("WebSocket URL wasn't a valid ws:// URL")?;
+    let mut socket =
+      TcpStream::connect(&address).await.map_err(|_| "couldn't connect to the WebSocket")?;
+
+    let mut key = [0; 16];
+    OsRng.fill_bytes(&mut key);
+    let key = Base64::encode_string(&key);
+    let request = format!(
+      "GET {path} HTTP/1.1\r\nHost: {address}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+       Sec-WebSocket-Key: {key}\r\nSec-WebSocket-Version: 13\r\n\r\n",
+    );
+    socket.write_all(request.as_bytes()).await.map_err(|_| "couldn't request the WebSocket")?;
+
+    // Read the response's head, byte by byte so we don't read past it
+    let mut response = vec![];
+    while !response.ends_with(b"\r\n\r\n") {
+      if response.len() > MAX_RESPONSE_HEAD_SIZE {
+        Err("the WebSocket response had too long of a head")?;
+      }
+      response.push(socket.read_u8().await.map_err(|_| "couldn't read the WebSocket response")?);
+    }
+    let response = String::from_utf8_lossy(&response);
+    let mut lines = response.split("\r\n");
+    if lines.next().and_then(|status| status.split(' ').nth(1)) != Some("101") {
+      Err("the WebSocket wasn't accepted")?;
+    }
+    let accepted = lines.any(|line| {
+      line.split_once(':').is_some_and(|(name, value)| {
+        name.trim().eq_ignore_ascii_case("sec-websocket-accept") && (value.trim() == accept(&key))
+      })
+    });
+    if !accepted {
+      Err("the WebSocket response had an invalid Sec-WebSocket-Accept")?;
+    }
+
+    Ok(Client { socket, max_size })
+  }
+
+  fn mask() -> [u8; 4] {
+    let mut mask = [0; 4];
+    OsRng.fill_bytes(&mut mask);
+    mask
+  }
+
+  /// Send a text message.
+  pub async fn send(&mut self, message: &str) -> Option<()> {
+    self.socket.write_all(&encode_frame(TEXT, message.as_bytes(), Some(Self::mask()))).await.ok()
+  }
+
+  /// Read the next text or binary message, responding to any pings.
+  ///
+  /// Returns None once the connection is closed.
+  pub async fn read(&mut self) -> Option<Vec<u8>> {
+    let mut message = vec![];
+    loop {
+      let frame = read_frame(&mut self.socket, false, self.max_size).await?;
+      match frame.opcode {
+        CONTINUATION | TEXT | BINARY => {
+          message.extend(frame.payload);
+          if message.len() > self.max_size {
+            None?;
+          }
+          if frame.fin {
+            return Some(message);
+          }
+        }
+        CLOSE => None?,
+        PING => {
+          let pong = encode_frame(PONG, &frame.payload, Some(Self::mask()));
+          self.socket.write_all(&pong).await.ok()?;
+        }
+        // Pongs, and reserved opcodes
+        _ => {}
+      }
+    }
+  }
+}
@@ -0,0 +1,318 @@
+use core::fmt::Write;
+
+use serai_client::{
+  primitives::EXTERNAL_NETWORKS,
+  validator_sets::primitives::{ExternalValidatorSet, Session},
+};
+
+use serai_db::{Get, Db};
+
+use serai_telemetry::logging;
+
+use crate::{
+  db::{ActiveTributaryDb, RetiredTributaryDb},
+  tributary::{
+    Topic, AttemptDb, SeraiDkgCompleted, DkgLocallyCompleted, LastHandledBlock,
+    TributaryBlockNumber, TributarySpec,
+  },
+  substrate::{ScanCosignFrom, IntendedCosign},
+  cosign_producer::CosignIntent,
+  cosign_evaluator::{CompositionAttestation, CosignReader},
+  epoch, metrics, CoordinatorSigner,
+};
+
+/// The status of a validator set's distributed key generation.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum DkgStatus {
+  /// The DKG is in progress, on the specified attempt.
+  InProgress { attempt: u32 },
+  /// We generated a key, yet it has yet to be set on Serai.
+  LocallyCompleted,
+  /// The key was set on Serai.
+  Completed,
+}
+
+pub(crate) fn dkg_status(getter: &impl Get, spec: &TributarySpec) -> DkgStatus {
+  if SeraiDkgCompleted::get(getter, spec.set()).is_some() {
+    return DkgStatus::Completed;
+  }
+  if DkgLocallyCompleted::get(getter, spec.genesis()).is_some() {
+    return DkgStatus::LocallyCompleted;
+  }
+  DkgStatus::InProgress {
+    attempt: AttemptDb::attempt(getter, spec.genesis(), Topic::Dkg).unwrap_or(0),
+  }
+}
+
+// The active Tributaries, sorted by their validator sets
+fn active_tributaries(getter: &impl Get) -> Vec<TributarySpec> {
+  let mut specs = ActiveTributaryDb::active_tributaries(getter)
+    .1
+    .into_iter()
+    .filter(|spec| RetiredTributaryDb::get(getter, spec.set()).is_none())
+    .collect::<Vec<_>>();
+  specs.sort_by_key(|spec| {
+    let ExternalValidatorSet { network, session } = spec.set();
+    (EXTERNAL_NETWORKS.iter().position(|n| *n == network), session.0)
+  });
+  specs
+}
+
+/// Report the active sessions, with the status of their Tributaries and DKGs.
+pub(crate) fn sessions_report(getter: &impl Get) -> String {
+  let mut res = "sessions:\n".to_string();
+  for spec in active_tributaries(getter) {
+    let genesis = spec.genesis();
+    let height = LastHandledBlock::get(getter, genesis)
+      .and_then(|block| TributaryBlockNumber::get(getter, block))
+      .unwrap_or(0);
+    let dkg = match dkg_status(getter, &spec) {
+      DkgStatus::InProgress { attempt } => format!("in progress (attempt {attempt})"),
+      DkgStatus::LocallyCompleted => "generated key, awaiting it being set on Serai".to_string(),
+      DkgStatus::Completed => "completed".to_string(),
+    };
+    writeln!(
+      res,
+      "  {:?} {:?}: genesis {}, {} validators, handled Tributary block {height}, DKG {dkg}",
+      spec.set().network,
+      spec.set().session,
+      hex::encode(genesis),
+      spec.validators().len(),
+    )
+    .unwrap();
+  }
+  res
+}
+
+/// Report the signing protocols our processors have yet to complete a round of.
+pub(crate) fn signing_report() -> String {
+  let mut res = "pending signing rounds:\n".to_string();
+  for round in metrics::signing_rounds_in_progress() {
+    writeln!(
+      res,
+      "  {:?} {} for {}s: {}",
+      round.network,
+      round.kind,
+      round.elapsed.as_secs(),
+      round.description
+    )
+    .unwrap();
+  }
+  res
+}
+
+/// Report the state of cosigning.
+///
+/// The status of a specific block may be requested with the `block` query parameter
+/// (`/cosign?block=...`).
+pub(crate) async fn cosign_report<D: Db>(
+  getter: &impl Get,
+  cosign_reader: &CosignReader<D>,
+  query: Option<&str>,
+) -> String {
+  let mut res = "cosigning:\n".to_string();
+  writeln!(res, "  latest cosigned block: {}", cosign_reader.latest_cosigned_block_number())
+    .unwrap();
+  if let Some((intended, skipped)) = IntendedCosign::get(getter) {
+    writeln!(res, "  latest intended cosign: {intended}").unwrap();
+    if let Some(skipped) = skipped {
+      writeln!(res, "  skipped cosign: {skipped}").unwrap();
+    }
+  }
+  writeln!(
+    res,
+    "  scanning for blocks to cosign from: {}",
+    ScanCosignFrom::get(getter).unwrap_or(1)
+  )
+  .unwrap();
+  if cosign_reader.distinct_chain_report().is_some() {
+    writeln!(res, "  A DISTINCT CHAIN WAS COSIGNED").unwrap();
+  }
+
+  let mut cosigns = cosign_reader.cosigns_to_rebroadcast().await;
+  cosigns.sort_by_key(|cosign| EXTERNAL_NETWORKS.iter().position(|n| *n == cosign.network));
+  for cosign in cosigns {
+    writeln!(
+      res,
+      "  latest cosign by {:?}: block {} ({})",
+      cosign.network,
+      cosign.block_number,
+      hex::encode(cosign.block)
+    )
+    .unwrap();
+  }
+
+  // Report which networks are holding up the next block to be cosigned
+  match cosign_reader.progress().await {
+    Some(progress) => {
+      if let Some(pending) = progress.pending_block {
+        writeln!(
+          res,
+          "  pending block {pending} cosigned with stake {} of {} ({} needed)",
+          progress.pending_block_stake, progress.total_stake, progress.needed_stake,
+        )
+        .unwrap();
+        for network in progress.networks {
+          let behind = network.latest_cosigned_block.map_or(true, |block| block < pending);
+          if network.stake.is_some() && behind {
+            writeln!(res, "  {:?} has yet to cosign block {pending}", network.network).unwrap();
+          }
+        }
+      }
+    }
+    None => {
+      writeln!(res, "  cosign progress unknown as stakes have yet to be fetched").unwrap();
+    }
+  }
+
+  for network in EXTERNAL_NETWORKS {
+    for intent in CosignIntent::pending(getter, network) {
+      writeln!(
+        res,
+        "  {:?} {:?} has yet to start cosigning block {}",
+        network, intent.set.session, intent.block_number,
+      )
+      .unwrap();
+    }
+  }
+
+  // Report the status of the requested block, or of the block we intend to cosign if it's pending
+  let block = query
+    .and_then(|query| query.split('&').find_map(|param| param.strip_prefix("block=")))
+    .and_then(|block| block.parse::<u64>().ok())
+    .or_else(|| {
+      IntendedCosign::get(getter)
+        .map(|(intended, _)| intended)
+        .filter(|intended| *intended > cosign_reader.latest_cosigned_block_number())
+    });
+  if let Some(block) = block {
+    match cosign_reader.block_cosign_status(block).await {
+      Some(status) => writeln!(
+        res,
+        "  block {block} cosigned by {:?} with stake {} of {} ({} more needed)",
+        status.cosigned_by, status.cosigned_stake, status.total_stake, status.remaining_stake,
+      )
+      .unwrap(),
+      None => {
+        writeln!(res, "  block {block} status unknown as stakes have yet to be fetched").unwrap()
+      }
+    }
+  }
+  res
+}
+
+/// Report the peers we're connected to.
+pub(crate) fn peers_report() -> String {
+  let mut peers = metrics::p2p_peers()
+    .into_iter()
+    .map(|(addr, nets)| {
+      let mut nets = nets.into_iter().collect::<Vec<_>>();
+      nets.sort_by_key(|net| EXTERNAL_NETWORKS.iter().position(|n| n == net));
+      (addr.to_string(), nets)
+    })
+    .collect::<Vec<_>>();
+  peers.sort();
+
+  let mut res = format!("connected peers ({}):\n", peers.len());
+  for (addr, nets) in peers {
+    writeln!(res, "  {addr}: {nets:?}").unwrap();
+  }
+  res
+}
+
+/// Report the log filter, first setting it if a new filter was specified.
+///
+/// The new filter is specified as the `filter` query parameter (`/log?filter=...`), using the same
+/// syntax as `RUST_LOG`. It isn't percent-decoded.
+pub(crate) fn log_report(query: Option<&str>) -> String {
+  if let Some(spec) = query.and_then(|query| {
+    query.split('&').find_map(|param| param.strip_prefix("filter=")).filter(|spec| !spec.is_empty())
+  }) {
+    logging::set_filter(spec);
+  }
+  format!("log filter: {}\n", logging::filter().unwrap_or_default())
+}
+
+/// Report what occurred during an epoch, as JSON.
+///
+/// The epoch is specified by its session as the `session` query parameter (`/epoch?session=...`),
+/// defaulting to the current epoch.
+pub(crate) fn epoch_report(getter: &impl Get, query: Option<&str>) -> String {
+  let session = query
+    .and_then(|query| query.split('&').find_map(|param| param.strip_prefix("session=")))
+    .and_then(|session| session.parse::<u32>().ok())
+    .map(Session);
+  match epoch::epoch_report(getter, session) {
+    Some(report) => report.to_json(getter).to_string(),
+    None => serde_json::Value::Null.to_string(),
+  }
+}
+
+/// Attest to a composition of the validator sets participating in cosigning, as JSON.
+///
+/// The composition is specified by its ID as the `id` query parameter (`/composition?id=...`),
+/// defaulting to the current composition.
+pub(crate) fn composition_attestation<D: Db>(
+  cosign_reader: &CosignReader<D>,
+  signer: &impl CoordinatorSigner,
+  query: Option<&str>,
+) -> String {
+  let id = query
+    .and_then(|query| query.split('&').find_map(|param| param.strip_prefix("id=")))
+    .and_then(|id| id.parse::<u32>().ok());
+  let composition = match id {
+    Some(id) => cosign_reader.cosigning_composition_by_id(id),
+    None => cosign_reader.cosigning_composition(),
+  };
+  match composition {
+    Some(composition) => CompositionAttestation::attest(signer, composition).to_json().to_string(),
+    None => serde_json::Value::Null.to_string(),
+  }
+}
+
+/// Serve the admin API on the specified port.
+///
+/// This is only bound to localhost, as it's intended for operators debugging their own node.
+pub(crate) async fn serve<D: Db, S: 'static + Clone + CoordinatorSigner>(
+  port: u16,
+  db: D,
+  cosign_reader: CosignReader<D>,
+  signer: S,
+) {
+  log::info!("serving the admin API on port {port}");
+  serai_telemetry::http::serve(([127, 0, 0, 1], port).into(), move |path: String| {
+    let db = db.clone();
+    let cosign_reader = cosign_reader.clone();
+    let signer = signer.clone();
+    async move {
+      let (path, query) = match path.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (path.as_str(), None),
+      };
+      let body = match path {
+        "/sessions" => sessions_report(&db),
+        "/signing" => signing_report(),
+        "/cosign" => cosign_report(&db, &cosign_reader, query).await,
+        "/peers" => peers_report(),
+        "/log" => log_report(query),
+        "/epoch" => return Some(("application/json", epoch_report(&db, query))),
+        "/composition" => {
+          return Some((
+            "application/json",
+            composition_attestation(&cosign_reader, &signer, query),
+          ))
+        }
+        "/" => [
+          sessions_report(&db),
+          signing_report(),
+          cosign_report(&db, &cosign_reader, None).await,
+          peers_report(),
+        ]
+        .join("\n"),
+        _ => None?,
+      };
+      Some(("text/plain; charset=utf-8", body))
+    }
+  })
+  .await
+}
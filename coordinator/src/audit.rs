@@ -0,0 +1,143 @@
+use std::{
+  sync::{OnceLock, Mutex},
+  io::{self, Write, BufRead, BufReader},
+  fs::{File, OpenOptions},
+};
+
+use blake2::{
+  digest::{consts::U32, Digest},
+  Blake2b,
+};
+
+/*
+  An append-only audit log of the decisions the coordinator makes which cause signatures to be
+  produced or used on behalf of this validator: the cosigns produced, the Substrate blocks
+  acknowledged (and accordingly the plans signed for them), the Batches signed, and the slash
+  reports signed.
+
+  Each entry is a line of JSON with its sequence number, the inputs which justified the decision,
+  and the hash of the prior entry. The entry's own hash commits to all of these, chaining the
+  entries, so an auditor can detect an entry being removed, modified, or reordered without trusting
+  the coordinator's DB. Entries don't include the time they were written, so the log is
+  deterministic to the decisions made.
+
+  Entries are written before the decision is committed to the DB. If the coordinator crashes after
+  writing an entry yet before committing, the decision may be recorded again once it's reattempted.
+*/
+
+pub(crate) const GENESIS: [u8; 32] = [0; 32];
+
+fn entry_hash(entry: &serde_json::Value) -> [u8; 32] {
+  // serde_json's maps are ordered by key, making this serialization canonical
+  Blake2b::<U32>::digest(serde_json::to_vec(entry).unwrap()).into()
+}
+
+/// Serialize an entry, returning its line (including the trailing newline) and its hash.
+pub(crate) fn entry(
+  seq: u64,
+  prev: [u8; 32],
+  decision: &str,
+  inputs: serde_json::Value,
+) -> (Vec<u8>, [u8; 32]) {
+  let mut entry = serde_json::json!({
+    "seq": seq,
+    "prev": hex::encode(prev),
+    "decision": decision,
+    "inputs": inputs,
+  });
+  let hash = entry_hash(&entry);
+  entry["hash"] = hex::encode(hash).into();
+
+  let mut line = serde_json::to_vec(&entry).unwrap();
+  line.push(b'\n');
+  (line, hash)
+}
+
+/// An error within an audit log.
+#[derive(Debug)]
+pub(crate) enum AuditLogError {
+  /// The audit log couldn't be read.
+  Io(io::Error),
+  /// The specified entry was malformed or didn't chain from the prior entry.
+  InvalidEntry(u64),
+}
+
+impl core::fmt::Display for AuditLogError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      AuditLogError::Io(e) => write!(f, "couldn't read the audit log: {e}"),
+      AuditLogError::InvalidEntry(entry) => write!(f, "entry {entry} was invalid"),
+    }
+  }
+}
+
+/// Verify an audit log, returning the amount of entries and the hash of the last entry.
+pub(crate) fn verify(log: impl io::Read) -> Result<(u64, [u8; 32]), AuditLogError> {
+  let mut entries = 0;
+  let mut prev = GENESIS;
+  for line in BufReader::new(log).lines() {
+    let line = line.map_err(AuditLogError::Io)?;
+    let invalid = AuditLogError::InvalidEntry(entries);
+    let Ok(serde_json::Value::Object(mut entry)) = serde_json::from_str(&line) else {
+      Err(invalid)?
+    };
+
+    let Some(hash) = entry.remove("hash") else { Err(invalid)? };
+    if (entry.get("seq").and_then(serde_json::Value::as_u64) != Some(entries)) ||
+      (entry.get("prev").and_then(serde_json::Value::as_str) != Some(hex::encode(prev).as_str())) ||
+      (hash.as_str() !=
+        Some(hex::encode(entry_hash(&serde_json::Value::Object(entry))).as_str()))
+    {
+      Err(invalid)?;
+    }
+
+    entries += 1;
+    prev = hex::decode(hash.as_str().unwrap()).unwrap().try_into().unwrap();
+  }
+  Ok((entries, prev))
+}
+
+struct AuditLog {
+  file: File,
+  entries: u64,
+  prev: [u8; 32],
+}
+
+// The audit log at `AUDIT_LOG_PATH`, if one was specified
+fn audit_log() -> Option<&'static Mutex<AuditLog>> {
+  static AUDIT_LOG: OnceLock<Option<Mutex<AuditLog>>> = OnceLock::new();
+  AUDIT_LOG
+    .get_or_init(|| {
+      let path = serai_env::var("AUDIT_LOG_PATH")?;
+      let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .read(true)
+        .open(&path)
+        .unwrap_or_else(|e| panic!("couldn't open the audit log at {path}: {e:?}"));
+      // Verify the existing log, so we continue the chain from its last entry
+      let (entries, prev) =
+        verify(&file).unwrap_or_else(|e| panic!("the audit log at {path} was invalid: {e}"));
+      log::info!("appending to the audit log at {path}, which has {entries} entries");
+      Some(Mutex::new(AuditLog { file, entries, prev }))
+    })
+    .as_ref()
+}
+
+/// Record a decision to the audit log, if one is being kept.
+///
+/// `inputs` should contain everything which justified the decision.
+pub(crate) fn record(decision: &str, inputs: serde_json::Value) {
+  let Some(audit_log) = audit_log() else { return };
+  let mut audit_log = audit_log.lock().unwrap();
+
+  let (line, hash) = entry(audit_log.entries, audit_log.prev, decision, inputs);
+  // If we can't record this decision, we can't make it
+  audit_log
+    .file
+    .write_all(&line)
+    .and_then(|()| audit_log.file.sync_data())
+    .expect("couldn't write to the audit log");
+  audit_log.entries += 1;
+  audit_log.prev = hash;
+}
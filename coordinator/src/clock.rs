@@ -0,0 +1,35 @@
+use core::time::Duration;
+
+use async_trait::async_trait;
+
+pub use tokio::time::Instant;
+
+/// A source of time for tasks which wait.
+///
+/// Tasks take a clock, instead of directly sleeping, so their timing can be tested without waiting
+/// in real time.
+#[async_trait]
+pub trait Clock: 'static + Send + Sync + Clone {
+  /// The current instant.
+  fn now(&self) -> Instant;
+  /// Sleep for the specified duration.
+  async fn sleep(&self, duration: Duration);
+}
+
+/// The tokio runtime's clock.
+///
+/// When the runtime's time is paused, as with `#[tokio::test(start_paused = true)]`, this clock is
+/// simulated. It only advances when explicitly advanced or when every task is waiting on a timer,
+/// at which point it immediately jumps to the next timer's deadline.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct TokioClock;
+
+#[async_trait]
+impl Clock for TokioClock {
+  fn now(&self) -> Instant {
+    Instant::now()
+  }
+  async fn sleep(&self, duration: Duration) {
+    tokio::time::sleep(duration).await
+  }
+}
@@ -1,55 +1,1016 @@
 use core::time::Duration;
 use std::{
-  sync::Arc,
+  sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+  },
   collections::{HashSet, HashMap},
 };
 
-use tokio::{
-  sync::{mpsc, Mutex, RwLock},
-  time::sleep,
-};
+use tokio::sync::{mpsc, Mutex, RwLock};
+
+use ciphersuite::{group::GroupEncoding, Ciphersuite, Ristretto};
+use schnorr::SchnorrSignature;
 
-use borsh::BorshSerialize;
-use sp_application_crypto::RuntimePublic;
+use borsh::{BorshSerialize, BorshDeserialize};
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
 use serai_client::{
-  primitives::{ExternalNetworkId, Signature, EXTERNAL_NETWORKS},
+  primitives::{ExternalNetworkId, EXTERNAL_NETWORKS},
   validator_sets::primitives::{ExternalValidatorSet, Session},
-  Serai, SeraiError, TemporalSerai,
+  Public, Block, Serai, SeraiError, TemporalSerai,
 };
 
 use serai_db::{Get, DbTxn, Db, create_db};
 
-use processor_messages::coordinator::cosign_block_msg;
-
+#[cfg(feature = "serde")]
+use crate::p2p::serde_hex;
 use crate::{
   p2p::{CosignedBlock, GossipMessageKind, P2p},
-  substrate::LatestCosignedBlock,
+  cosign_producer::verify_cosign_signature,
+  substrate::{ScanCosignFrom, IntendedCosign, LatestCosignedBlock},
+  clock::{Instant, Clock},
+  signer::verify_attestation,
+  metrics, logging, CoordinatorSigner,
 };
 
 create_db! {
   CosignDb {
-    ReceivedCosign: (set: ExternalValidatorSet, block: [u8; 32]) -> CosignedBlock,
     LatestCosign: (network: ExternalNetworkId) -> CosignedBlock,
+    // The first cosign by a validator set for a distinct chain, retained as evidence
+    DistinctChainCosign: (set: ExternalValidatorSet) -> DistinctCosign,
+    // Prior versions marked a validator set as on a distinct chain without retaining the cosign
+    // These markers are still read, as the validator set remains on a distinct chain, yet no
+    // longer written
+    // (Prior versions also wrote every received cosign under `ReceivedCosign`, which was never
+    // read and accordingly isn't declared here)
     DistinctChain: (set: ExternalValidatorSet) -> (),
+    LatestDistinctChainReport: () -> DistinctChainReport,
+    PendingCosigns: (network: ExternalNetworkId) -> Vec<CosignedBlock>,
+    // The compositions of the validator sets cosigning, by ID
+    CosigningCompositions: (id: u32) -> CosigningComposition,
+    // The latest cosigns within a composition, as of when it was superseded
+    CompositionCosigns: (id: u32) -> Vec<CosignedBlock>,
+    LatestCosigningComposition: () -> u32,
+  }
+}
+
+// The maximum amount of cosigns for not-yet-finalized blocks to buffer per network
+const MAX_PENDING_COSIGNS_PER_NETWORK: usize = 8;
+// How far ahead of our latest finalized block we'll buffer cosigns for (ten minutes of blocks)
+const MAX_PENDING_COSIGN_DISTANCE: u64 = 10 * 60 / 6;
+// The maximum amount of received cosigns to handle under a single DB transaction
+const MAX_COSIGN_BATCH_SIZE: usize = 64;
+// How often the watchdog checks if cosigning has stalled
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(60);
+// How often to rebroadcast cosigns while a block we intend to cosign has yet to be cosigned
+const PENDING_REBROADCAST_INTERVAL: Duration = Duration::from_secs(12);
+// How often to rebroadcast cosigns while we have yet to determine a block to cosign
+const REBROADCAST_INTERVAL: Duration = Duration::from_secs(60);
+// How often to decide if cosigns should be rebroadcast (once per block)
+const REBROADCAST_POLL_INTERVAL: Duration = Duration::from_secs(6);
+
+/// A composition of the validator sets participating in cosigning, with their cosigns.
+#[derive(Clone, PartialEq, Eq, Debug, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ArchivedComposition {
+  /// The composition.
+  pub composition: CosigningComposition,
+  /// The latest cosign by each validator set within the composition.
+  ///
+  /// For a composition which was superseded, these are the cosigns as of when it was superseded
+  /// (the cosigns at the session boundary).
+  pub cosigns: Vec<CosignedBlock>,
+}
+
+/// An archive of cosigns, enabling a fresh node to bootstrap its view of the cosigned chain
+/// without requesting everything from its peers.
+///
+/// Archives are not trusted. Upon import, each composition is verified against the composition
+/// derived from our chain as of its block, and each cosign is verified against the validator set
+/// with keys as of its block's parent, as with any other received cosign (yet without rejecting
+/// cosigns for being old).
+#[derive(Clone, PartialEq, Eq, Debug, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CosignArchive {
+  /// The compositions which have cosigned, in order.
+  pub compositions: Vec<ArchivedComposition>,
+}
+
+impl CosignArchive {
+  /// Export the compositions and cosigns present in the DB.
+  pub fn export(getter: &impl Get) -> CosignArchive {
+    let Some(latest) = LatestCosigningComposition::get(getter) else {
+      return CosignArchive { compositions: vec![] };
+    };
+    CosignArchive {
+      compositions: (0 ..= latest)
+        .filter_map(|id| CosigningCompositions::get(getter, id))
+        .map(|composition| {
+          let cosigns = if composition.id == latest {
+            composition
+              .sets
+              .iter()
+              .filter_map(|set| LatestCosign::get(getter, set.set.network))
+              .collect()
+          } else {
+            CompositionCosigns::get(getter, composition.id).unwrap_or(vec![])
+          };
+          ArchivedComposition { composition, cosigns }
+        })
+        .collect(),
+    }
+  }
+
+  /// Import this archive into a DB, returning the amount of cosigns imported.
+  ///
+  /// Composition IDs are local to a node, so an archive is solely imported into a DB which has
+  /// yet to observe a composition. Compositions and cosigns which fail verification are skipped,
+  /// with cosigns not bound to a session solely imported if `accept_unbound` is set.
+  pub async fn import(
+    &self,
+    db: &mut impl Db,
+    serai: &Serai,
+    accept_unbound: bool,
+  ) -> Result<usize, SeraiError> {
+    if LatestCosigningComposition::get(&*db).is_some() {
+      log::warn!(
+        target: logging::COSIGN,
+        "not importing the cosign archive as a cosigning composition was already observed"
+      );
+      return Ok(0);
+    }
+
+    let mut compositions = vec![];
+    for ArchivedComposition { composition, cosigns } in &self.compositions {
+      // Verify the composition is the one our chain had as of its block
+      let Some(header) = serai.header(composition.block).await? else {
+        log::warn!(target: logging::COSIGN, "archived composition had an unknown block");
+        continue;
+      };
+      if !serai.is_finalized(&header).await? {
+        log::warn!(target: logging::COSIGN, "archived composition had a non-finalized block");
+        continue;
+      }
+      let (_, sets) = cosigning_sets(serai, &serai.as_of(composition.block)).await?;
+      if sets != composition.sets {
+        log::warn!(
+          target: logging::COSIGN,
+          "archived composition was distinct from the composition as of its block"
+        );
+        continue;
+      }
+
+      let mut verified = vec![];
+      for cosign in cosigns {
+        // Archived cosigns must be for the validator sets within their composition
+        if !composition.sets.iter().any(|set| set.set.network == cosign.network) {
+          continue;
+        }
+        let Some(block) = serai.finalized_block_by_number(cosign.block_number).await? else {
+          continue;
+        };
+        if block.hash() != cosign.block {
+          log::warn!(
+            target: logging::COSIGN,
+            "archived cosign was for a distinct block at {}",
+            cosign.block_number
+          );
+          continue;
+        }
+        if verify_cosign(serai, &block, cosign, accept_unbound).await?.is_some() {
+          verified.push(*cosign);
+        }
+      }
+      compositions.push((composition.clone(), verified));
+    }
+
+    let Some(latest) = compositions.len().checked_sub(1) else { return Ok(0) };
+    let mut imported = 0;
+    let mut txn = db.txn();
+    for (i, (mut composition, cosigns)) in compositions.into_iter().enumerate() {
+      let id = u32::try_from(i).unwrap();
+      composition.id = id;
+      imported += cosigns.len();
+      if i == latest {
+        for cosign in &cosigns {
+          LatestCosign::set(&mut txn, cosign.network, cosign);
+        }
+      } else {
+        CompositionCosigns::set(&mut txn, id, &cosigns);
+      }
+      CosigningCompositions::set(&mut txn, id, &composition);
+    }
+    LatestCosigningComposition::set(&mut txn, &u32::try_from(latest).unwrap());
+    txn.commit();
+    Ok(imported)
+  }
+
+  /// Write this archive to a file.
+  ///
+  /// The file is written atomically, via writing to a temporary file which is then renamed.
+  pub fn write_to_file(&self, path: &str) -> std::io::Result<()> {
+    let temp = format!("{path}.tmp");
+    std::fs::write(&temp, borsh::to_vec(self).unwrap())?;
+    std::fs::rename(temp, path)
+  }
+
+  /// Read an archive from a file.
+  pub fn read_from_file(path: &str) -> std::io::Result<CosignArchive> {
+    borsh::from_slice(&std::fs::read(path)?)
+  }
+}
+
+/// The outcome of handling a cosign.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CosignOutcome {
+  /// The cosign was for a block at or below the latest cosign we have for its network.
+  Stale,
+  /// The cosign was for a block we have yet to finalize and was buffered.
+  Buffered,
+  /// The cosign was for a block too far ahead of our latest finalized block to be buffered, and
+  /// was dropped.
+  TooFarAhead,
+  /// The cosign was invalid.
+  Invalid,
+  /// The cosign was valid and accepted.
+  Accepted,
+  /// The cosign was valid yet for a block distinct from the one we finalized.
+  DistinctChain,
+}
+
+/// A cosign for a block distinct from the block we finalized with its number.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DistinctCosign {
+  /// The cosign.
+  pub cosign: CosignedBlock,
+  /// The hash of the block we finalized with this number.
+  #[cfg_attr(feature = "serde", serde(with = "serde_hex"))]
+  pub our_block: [u8; 32],
+}
+
+/// A validator set's position within a `DistinctChainReport`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DistinctChainSet {
+  /// The validator set.
+  pub set: ExternalValidatorSet,
+  /// The validator set's stake.
+  pub stake: u64,
+  /// If this validator set cosigned a distinct chain.
+  pub distinct: bool,
+  /// The cosign this validator set made for a distinct chain, if it made one.
+  ///
+  /// This may be `None` for a validator set on a distinct chain if the distinct chain was
+  /// detected by a prior version, which didn't retain the cosign.
+  pub distinct_cosign: Option<DistinctCosign>,
+}
+
+/// A report on the validator sets which have cosigned a chain distinct from ours.
+#[derive(Clone, PartialEq, Eq, Debug, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DistinctChainReport {
+  /// The hash of our latest finalized block, as of which the validator sets were determined.
+  #[cfg_attr(feature = "serde", serde(with = "serde_hex"))]
+  pub latest_block: [u8; 32],
+  /// The validator sets with keys, as of the latest finalized block.
+  pub sets: Vec<DistinctChainSet>,
+  /// The total stake of the validator sets.
+  pub total_stake: u64,
+  /// The stake of the validator sets which cosigned a distinct chain.
+  pub distinct_chain_stake: u64,
+  /// If enough stake cosigned a distinct chain for us to halt.
+  pub halted: bool,
+}
+
+enum Evaluation {
+  Outcome(CosignOutcome),
+  Valid {
+    set_with_keys: ExternalValidatorSet,
+    distinct: bool,
+    our_block: [u8; 32],
+    latest_block: [u8; 32],
+  },
+}
+
+async fn set_with_keys_fn(
+  serai: &TemporalSerai<'_>,
+  network: ExternalNetworkId,
+) -> Result<Option<ExternalValidatorSet>, SeraiError> {
+  let Some(latest_session) = serai.validator_sets().session(network.into()).await? else {
+    log::warn!(
+      target: logging::COSIGN,
+      "received cosign from {:?}, which doesn't yet have a session",
+      network
+    );
+    return Ok(None);
+  };
+  let prior_session = Session(latest_session.0.saturating_sub(1));
+  Ok(Some(
+    if serai
+      .validator_sets()
+      .keys(ExternalValidatorSet { network, session: prior_session })
+      .await?
+      .is_some()
+    {
+      ExternalValidatorSet { network, session: prior_session }
+    } else {
+      ExternalValidatorSet { network, session: latest_session }
+    },
+  ))
+}
+
+// The hash of the block a validator set's session began with
+async fn session_start_fn(
+  serai: &Serai,
+  temporal: &TemporalSerai<'_>,
+  set: ExternalValidatorSet,
+) -> Result<Option<[u8; 32]>, SeraiError> {
+  // This storage is a ValueQuery, so an absent value is the default of 0 (the genesis block)
+  let number =
+    temporal.validator_sets().session_begin_block(set.network.into(), set.session).await?;
+  Ok(serai.finalized_block_by_number(number.unwrap_or(0)).await?.map(|block| block.hash()))
+}
+
+// The stake of each network, and the validator sets participating in cosigning
+async fn cosigning_sets(
+  serai: &Serai,
+  temporal: &TemporalSerai<'_>,
+) -> Result<(HashMap<ExternalNetworkId, u64>, Vec<CosigningSet>), SeraiError> {
+  let mut stakes = HashMap::new();
+  let mut sets = vec![];
+  for network in EXTERNAL_NETWORKS {
+    // Use if this network has published a Batch for a short-circuit of if they've ever set a key
+    let set_key = temporal.in_instructions().last_batch_for_network(network).await?.is_some();
+    if set_key {
+      let stake = temporal
+        .validator_sets()
+        .total_allocated_stake(network.into())
+        .await?
+        .expect("network which published a batch didn't have a stake set")
+        .0;
+      stakes.insert(network, stake);
+
+      if let Some(set) = set_with_keys_fn(temporal, network).await? {
+        if let Some(key_pair) = temporal.validator_sets().keys(set).await? {
+          if let Some(session_start) = session_start_fn(serai, temporal, set).await? {
+            sets.push(CosigningSet { set, key: key_pair.0 .0, session_start, stake });
+          }
+        }
+      }
+    }
+  }
+  Ok((stakes, sets))
+}
+
+// Verify a cosign for a finalized block, returning the validator set which produced it
+//
+// This verifies the cosign against the validator set with keys as of the block's parent, yet
+// doesn't check the cosign is for this block. Cosigns not bound to a session are solely accepted
+// if `accept_unbound` is set.
+async fn verify_cosign(
+  serai: &Serai,
+  block: &Block,
+  cosign: &CosignedBlock,
+  accept_unbound: bool,
+) -> Result<Option<ExternalValidatorSet>, SeraiError> {
+  // Get the key for this network as of the prior block
+  // If we have two chains, this value may be different across chains depending on if one chain
+  // included the set_keys and one didn't
+  // Because set_keys will force a cosign, it will force detection of distinct blocks
+  // re: set_keys using keys prior to set_keys (assumed amenable to all)
+  let temporal = serai.as_of(block.header.parent_hash.into());
+
+  let Some(set_with_keys) = set_with_keys_fn(&temporal, cosign.network).await? else {
+    return Ok(None);
+  };
+  let Some(keys) = temporal.validator_sets().keys(set_with_keys).await? else {
+    log::warn!(target: logging::COSIGN, "received cosign for a block we didn't have keys for");
+    return Ok(None);
+  };
+
+  // A cosign must be bound to the session of the set with keys, as of this chain, preventing
+  // replays of cosigns from distinct chains which share session IDs
+  match cosign.session_start {
+    Some(session_start) => {
+      if session_start_fn(serai, &temporal, set_with_keys).await? != Some(session_start) {
+        log::warn!(
+          target: logging::COSIGN,
+          "received cosign bound to a session distinct from {:?}",
+          set_with_keys
+        );
+        return Ok(None);
+      }
+    }
+    None => {
+      if !accept_unbound {
+        log::warn!(target: logging::COSIGN, "received cosign which wasn't bound to a session");
+        return Ok(None);
+      }
+    }
+  }
+
+  if !verify_cosign_signature(&keys.0, cosign) {
+    log::warn!(target: logging::COSIGN, "received cosigned block with an invalid signature");
+    return Ok(None);
+  }
+
+  Ok(Some(set_with_keys))
+}
+
+fn needed_stake(total_stake: u64) -> u64 {
+  ((total_stake * 2) / 3) + 1
+}
+
+fn sufficient_stake(total_stake: u64, sum_stake: u64) -> bool {
+  (total_stake == 0) || (sum_stake > needed_stake(total_stake))
+}
+
+/// A validator set participating in cosigning.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CosigningSet {
+  /// The validator set.
+  pub set: ExternalValidatorSet,
+  /// The validator set's Substrate key, which its cosigns are signed with.
+  #[cfg_attr(feature = "serde", serde(with = "serde_hex"))]
+  pub key: [u8; 32],
+  /// The hash of the block the validator set's session began with.
+  #[cfg_attr(feature = "serde", serde(with = "serde_hex"))]
+  pub session_start: [u8; 32],
+  /// The stake of the validator set's network.
+  pub stake: u64,
+}
+
+/// The composition of the validator sets participating in cosigning.
+#[derive(Clone, PartialEq, Eq, Debug, BorshSerialize, BorshDeserialize)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CosigningComposition {
+  /// The ID of this composition, incremented whenever the composition changes.
+  pub id: u32,
+  /// The hash of the finalized block this composition was first observed as of.
+  #[cfg_attr(feature = "serde", serde(with = "serde_hex"))]
+  pub block: [u8; 32],
+  /// The validator sets participating in cosigning.
+  pub sets: Vec<CosigningSet>,
+  /// The total stake of the validator sets.
+  pub total_stake: u64,
+}
+
+impl CosigningComposition {
+  /// The composition as JSON.
+  pub fn to_json(&self) -> serde_json::Value {
+    serde_json::json!({
+      "id": self.id,
+      "block": hex::encode(self.block),
+      "sets": self.sets.iter().map(|set| serde_json::json!({
+        "network": format!("{:?}", set.set.network),
+        "session": set.set.session.0,
+        "key": hex::encode(set.key),
+        "session_start": hex::encode(set.session_start),
+        "stake": set.stake,
+      })).collect::<Vec<_>>(),
+      "total_stake": self.total_stake,
+    })
+  }
+}
+
+/// An attestation, signed by a coordinator's validator key, to the composition of the validator
+/// sets participating in cosigning as derived by that coordinator.
+///
+/// Operators may compare the attestations from multiple coordinators for the same composition ID,
+/// detecting divergence in how the composition was derived (its keys, stakes, or session start
+/// blocks) before it manifests as cosigns failing to verify.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CompositionAttestation {
+  /// The composition attested to.
+  pub composition: CosigningComposition,
+  /// The validator key of the coordinator which attested to the composition.
+  pub signer: <Ristretto as Ciphersuite>::G,
+  /// The signature over the composition.
+  pub signature: SchnorrSignature<Ristretto>,
+}
+
+impl CompositionAttestation {
+  /// Attest to a composition.
+  pub fn attest(signer: &impl CoordinatorSigner, composition: CosigningComposition) -> Self {
+    let signature = signer.sign_attestation(&borsh::to_vec(&composition).unwrap());
+    CompositionAttestation { composition, signer: signer.public_key(), signature }
+  }
+
+  /// Verify the signature over the composition.
+  #[allow(dead_code)] // Solely used by those comparing attestations, not by the coordinator itself
+  pub fn verify(&self) -> bool {
+    verify_attestation(self.signer, &borsh::to_vec(&self.composition).unwrap(), &self.signature)
+  }
+
+  /// The attestation as JSON.
+  pub fn to_json(&self) -> serde_json::Value {
+    serde_json::json!({
+      "composition": self.composition.to_json(),
+      "signer": hex::encode(self.signer.to_bytes()),
+      "signature": hex::encode(self.signature.serialize()),
+    })
+  }
+}
+
+// Retire the working state of the networks which are no longer within the cosigning composition
+//
+// This returns the networks retired, whose latest cosigns should no longer be considered. The prior
+// composition itself is retained, archiving which sets cosigned under it, as is any evidence of
+// its sets cosigning a distinct chain. The latest cosigns within the prior composition (the
+// cosigns at the session boundary) are also retained.
+pub(crate) fn retire_composition(
+  txn: &mut impl DbTxn,
+  prior: &CosigningComposition,
+  current: &CosigningComposition,
+) -> Vec<ExternalNetworkId> {
+  let cosigns =
+    prior.sets.iter().filter_map(|set| LatestCosign::get(&*txn, set.set.network)).collect();
+  CompositionCosigns::set(txn, prior.id, &cosigns);
+
+  let mut retired = vec![];
+  for set in &prior.sets {
+    let network = set.set.network;
+    if current.sets.iter().any(|set| set.set.network == network) {
+      continue;
+    }
+    LatestCosign::del(txn, network);
+    PendingCosigns::del(txn, network);
+    retired.push(network);
+  }
+  retired
+}
+
+/// An error when verifying a block was cosigned.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CosignVerificationError {
+  /// A cosign was for a block other than the block being verified.
+  DistinctBlock(ExternalNetworkId),
+  /// A cosign was by a network without a validator set in the composition.
+  UnknownNetwork(ExternalNetworkId),
+  /// Multiple cosigns were by the same network.
+  DuplicateNetwork(ExternalNetworkId),
+  /// A cosign was bound to a session other than its validator set's.
+  DistinctSession(ExternalNetworkId),
+  /// A cosign wasn't bound to a session, and unbound cosigns weren't accepted.
+  UnboundSession(ExternalNetworkId),
+  /// A cosign had an invalid signature.
+  InvalidSignature(ExternalNetworkId),
+  /// The cosigns weren't by sufficient stake.
+  InsufficientStake {
+    /// The stake which cosigned the block.
+    cosigned_stake: u64,
+    /// The stake which must be exceeded for the block to be considered cosigned.
+    needed_stake: u64,
+  },
+}
+
+/// Verify a block was sufficiently cosigned, returning the stake which cosigned it.
+///
+/// This is stateless, requiring neither a DB nor a connection to a Serai node, so services may
+/// verify cosigns without running a `CosignEvaluator`. The composition MUST be the composition of
+/// the validator sets cosigning as of the block's parent, as independently determined by the
+/// verifier (such as via the `CosignReader` of a trusted coordinator).
+///
+/// Legacy cosigns, which aren't bound to a session, may be replayed across distinct chains which
+/// share session IDs. They're solely accepted if `accept_unbound` is set, which should only be
+/// done while the network's nodes are upgrading.
+#[allow(dead_code)] // Solely used by services verifying cosigns, not by the coordinator itself
+pub fn verify_cosigned_block(
+  composition: &CosigningComposition,
+  block_number: u64,
+  block: [u8; 32],
+  cosigns: &[CosignedBlock],
+  accept_unbound: bool,
+) -> Result<u64, CosignVerificationError> {
+  let mut networks = HashSet::new();
+  let mut cosigned_stake = 0;
+  for cosign in cosigns {
+    if (cosign.block_number != block_number) || (cosign.block != block) {
+      Err(CosignVerificationError::DistinctBlock(cosign.network))?;
+    }
+    let Some(set) = composition.sets.iter().find(|set| set.set.network == cosign.network) else {
+      Err(CosignVerificationError::UnknownNetwork(cosign.network))?
+    };
+    if !networks.insert(cosign.network) {
+      Err(CosignVerificationError::DuplicateNetwork(cosign.network))?;
+    }
+    match cosign.session_start {
+      Some(session_start) => {
+        if session_start != set.session_start {
+          Err(CosignVerificationError::DistinctSession(cosign.network))?;
+        }
+      }
+      None => {
+        if !accept_unbound {
+          Err(CosignVerificationError::UnboundSession(cosign.network))?;
+        }
+      }
+    }
+    if !verify_cosign_signature(&Public(set.key), cosign) {
+      Err(CosignVerificationError::InvalidSignature(cosign.network))?;
+    }
+    cosigned_stake += set.stake;
+  }
+
+  if !sufficient_stake(composition.total_stake, cosigned_stake) {
+    Err(CosignVerificationError::InsufficientStake {
+      cosigned_stake,
+      needed_stake: needed_stake(composition.total_stake),
+    })?;
+  }
+  Ok(cosigned_stake)
+}
+
+// Buffer a cosign for a block we have yet to finalize
+//
+// As the cosign's block isn't finalized, the cosign can't be fully verified. It's solely buffered
+// if it's signed by its network's set within the current cosigning composition (as of our latest
+// finalized block), so peers can't fill the buffer with cosigns which will never be valid.
+//
+// This is also bounded in both the distance from our latest finalized block and the amount of
+// cosigns buffered per network. When full, the cosigns for the highest blocks are dropped, leaving
+// them to be rebroadcasted.
+pub(crate) fn buffer_cosign(
+  pending: &mut Vec<CosignedBlock>,
+  composition: Option<&CosigningComposition>,
+  latest_finalized: u64,
+  accept_unbound: bool,
+  cosign: CosignedBlock,
+) -> CosignOutcome {
+  let Some(set) = composition
+    .and_then(|composition| composition.sets.iter().find(|set| set.set.network == cosign.network))
+  else {
+    return CosignOutcome::Invalid;
+  };
+  let bound = match cosign.session_start {
+    Some(session_start) => session_start == set.session_start,
+    None => accept_unbound,
+  };
+  if !(bound && verify_cosign_signature(&Public(set.key), &cosign)) {
+    return CosignOutcome::Invalid;
+  }
+
+  if cosign.block_number > (latest_finalized + MAX_PENDING_COSIGN_DISTANCE) {
+    log::debug!(
+      target: logging::COSIGN,
+      network:? = cosign.network, block = cosign.block_number;
+      "received cosign for a block too far ahead"
+    );
+    return CosignOutcome::TooFarAhead;
+  }
+
+  if !pending.contains(&cosign) {
+    pending.push(cosign);
+    pending.sort_by_key(|cosign| cosign.block_number);
+    pending.truncate(MAX_PENDING_COSIGNS_PER_NETWORK);
+  }
+  CosignOutcome::Buffered
+}
+
+/// A network's progress in cosigning.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NetworkCosignProgress {
+  /// The network.
+  pub network: ExternalNetworkId,
+  /// The latest block this network has cosigned, if it has cosigned any.
+  pub latest_cosigned_block: Option<u64>,
+  /// This network's stake, if it has set keys.
+  pub stake: Option<u64>,
+}
+
+/// The progress in cosigning across all networks.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CosignProgress {
+  /// The progress of each network.
+  pub networks: Vec<NetworkCosignProgress>,
+  /// The latest block which was sufficiently cosigned.
+  pub latest_cosigned_block: u64,
+  /// The next block cosigned by any network which isn't yet sufficiently cosigned.
+  ///
+  /// The networks whose latest cosigned block is less than this are the ones holding it up.
+  pub pending_block: Option<u64>,
+  /// The stake which has cosigned the pending block.
+  pub pending_block_stake: u64,
+  /// The total stake across all networks which have set keys.
+  pub total_stake: u64,
+  /// The stake which must be exceeded for a block to be considered cosigned.
+  pub needed_stake: u64,
+  /// The report from the latest check for a distinct chain, if a distinct chain was ever cosigned.
+  pub distinct_chain: Option<DistinctChainReport>,
+  /// The current composition of the validator sets participating in cosigning.
+  pub composition: Option<CosigningComposition>,
+}
+
+/// The status of a block's cosigning.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BlockCosignStatus {
+  /// The block.
+  pub block_number: u64,
+  /// The networks which have cosigned this block (or a later block).
+  pub cosigned_by: Vec<ExternalNetworkId>,
+  /// The stake of the networks which have cosigned this block.
+  pub cosigned_stake: u64,
+  /// The total stake across all networks which have set keys.
+  pub total_stake: u64,
+  /// The additional stake which must cosign this block for it to be sufficiently cosigned.
+  ///
+  /// This is zero if the block has been sufficiently cosigned.
+  pub remaining_stake: u64,
+}
+
+pub(crate) fn block_cosign_status(
+  block_number: u64,
+  latest_cosigns: &HashMap<ExternalNetworkId, CosignedBlock>,
+  stakes: &HashMap<ExternalNetworkId, u64>,
+) -> BlockCosignStatus {
+  // A network's cosign of a block implicitly cosigns all prior blocks
+  let cosigned_by = EXTERNAL_NETWORKS
+    .into_iter()
+    .filter(|network| {
+      latest_cosigns.get(network).is_some_and(|cosign| cosign.block_number >= block_number)
+    })
+    .collect::<Vec<_>>();
+  let cosigned_stake =
+    cosigned_by.iter().map(|network| stakes.get(network).copied().unwrap_or(0)).sum::<u64>();
+  let total_stake = stakes.values().copied().sum::<u64>();
+  let remaining_stake = if sufficient_stake(total_stake, cosigned_stake) {
+    0
+  } else {
+    (needed_stake(total_stake) + 1) - cosigned_stake
+  };
+  BlockCosignStatus { block_number, cosigned_by, cosigned_stake, total_stake, remaining_stake }
+}
+
+/// Why cosigning has stalled.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CosignStallReason {
+  /// A block intended to be cosigned has yet to be sufficiently cosigned.
+  AwaitingCosigns {
+    /// The number of the block intended to be cosigned.
+    intended_block: u64,
+  },
+  /// The scan for blocks to cosign has yet to reach the latest finalized block.
+  ScanBehind {
+    /// The number of the next block to scan.
+    scan_from: u64,
+  },
+}
+
+/// An alert that cosigning has stalled.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CosignStall {
+  /// The number of the latest block which was sufficiently cosigned.
+  pub latest_cosigned_block: u64,
+  /// The number of the latest finalized block.
+  pub latest_finalized_block: u64,
+  /// How long the latest cosigned block has been stuck for.
+  pub stalled_for: Duration,
+  /// Why cosigning has stalled.
+  pub reason: CosignStallReason,
+}
+
+/// Tracks if cosigning has stalled, deciding when to alert.
+///
+/// An alert is due once the latest cosigned block hasn't advanced for `stall_after`, despite
+/// being behind the latest finalized block, and again for every further `stall_after` it remains
+/// stuck.
+pub(crate) struct StallTracker {
+  stall_after: Duration,
+  // The latest cosigned block, when we first saw it, and when we last alerted on it
+  stuck: Option<(u64, Instant, Option<Instant>)>,
+}
+
+impl StallTracker {
+  pub(crate) fn new(stall_after: Duration) -> Self {
+    Self { stall_after, stuck: None }
+  }
+
+  /// Observe the latest cosigned and finalized blocks as of `now`.
+  ///
+  /// Returns how long cosigning has been stalled for if an alert is due.
+  pub(crate) fn observe(
+    &mut self,
+    latest_cosigned_block: u64,
+    latest_finalized_block: u64,
+    now: Instant,
+  ) -> Option<Duration> {
+    if latest_cosigned_block >= latest_finalized_block {
+      self.stuck = None;
+      return None;
+    }
+
+    let (since, last_alert) = match self.stuck {
+      Some((block, since, last_alert)) if block == latest_cosigned_block => (since, last_alert),
+      _ => (now, None),
+    };
+    self.stuck = Some((latest_cosigned_block, since, last_alert));
+
+    if (now.duration_since(since) < self.stall_after) ||
+      last_alert.is_some_and(|last_alert| now.duration_since(last_alert) < self.stall_after)
+    {
+      return None;
+    }
+    self.stuck = Some((latest_cosigned_block, since, Some(now)));
+    Some(now.duration_since(since))
+  }
+}
+
+/// Spawn a task watching for cosigning to stall, returning a channel of alerts.
+///
+/// See `StallTracker` for when alerts are sent.
+pub fn cosign_watchdog<D: Db, C: Clock>(
+  db: D,
+  serai: Arc<Serai>,
+  stall_after: Duration,
+  clock: C,
+) -> mpsc::UnboundedReceiver<CosignStall> {
+  let (send, recv) = mpsc::unbounded_channel();
+  tokio::spawn(async move {
+    let mut tracker = StallTracker::new(stall_after);
+    loop {
+      clock.sleep(WATCHDOG_INTERVAL).await;
+
+      let Ok(latest_finalized_block) = serai.latest_finalized_block().await else {
+        log::warn!(
+          target: logging::COSIGN,
+          "couldn't get the latest finalized block to check if cosigning has stalled"
+        );
+        continue;
+      };
+      let latest_finalized_block = latest_finalized_block.number();
+      let latest_cosigned_block = LatestCosignedBlock::latest_cosigned_block(&db);
+      metrics::set_cosign_progress(latest_finalized_block, latest_cosigned_block);
+      let Some(stalled_for) =
+        tracker.observe(latest_cosigned_block, latest_finalized_block, clock.now())
+      else {
+        continue;
+      };
+
+      let reason = match IntendedCosign::get(&db) {
+        Some((intended_block, _)) if intended_block > latest_cosigned_block => {
+          CosignStallReason::AwaitingCosigns { intended_block }
+        }
+        _ => CosignStallReason::ScanBehind { scan_from: ScanCosignFrom::get(&db).unwrap_or(1) },
+      };
+      let stall =
+        CosignStall { latest_cosigned_block, latest_finalized_block, stalled_for, reason };
+      if send.send(stall).is_err() {
+        break;
+      }
+    }
+  });
+  recv
+}
+
+// How often cosigns should be rebroadcast, or None if they don't need to be
+//
+// Cosigns are rebroadcast aggressively while a block we intend to cosign has yet to be cosigned,
+// as our peers may have missed cosigns for it. Once every block we intended to cosign has been
+// cosigned, we stop rebroadcasting entirely, solely rebroadcasting upon receiving a cosign older
+// than our own (which means a peer is behind).
+pub(crate) fn rebroadcast_interval(getter: &impl Get) -> Option<Duration> {
+  match IntendedCosign::get(getter) {
+    Some((intended, _)) if intended > LatestCosignedBlock::latest_cosigned_block(getter) => {
+      Some(PENDING_REBROADCAST_INTERVAL)
+    }
+    Some(_) => None,
+    None => Some(REBROADCAST_INTERVAL),
+  }
+}
+
+/// A cloneable, read-only handle to the cosigning state.
+///
+/// This doesn't contend with the `CosignEvaluator` handling new cosigns, allowing other tasks to
+/// read the state concurrently.
+#[derive(Clone)]
+pub struct CosignReader<D: Db> {
+  db: D,
+  latest_cosigns: Arc<RwLock<HashMap<ExternalNetworkId, CosignedBlock>>>,
+  // If we've received a cosign older than our own since we last rebroadcasted
+  peer_behind: Arc<AtomicBool>,
+  // The stake of each network, once fetched by the evaluator
+  stakes: Arc<RwLock<Option<HashMap<ExternalNetworkId, u64>>>>,
+}
+
+impl<D: Db> CosignReader<D> {
+  pub(crate) fn new(db: D) -> Self {
+    let mut latest_cosigns = HashMap::new();
+    for network in EXTERNAL_NETWORKS {
+      if let Some(cosign) = LatestCosign::get(&db, network) {
+        latest_cosigns.insert(network, cosign);
+      }
+    }
+
+    CosignReader {
+      db,
+      latest_cosigns: Arc::new(RwLock::new(latest_cosigns)),
+      peer_behind: Arc::new(AtomicBool::new(false)),
+      stakes: Arc::new(RwLock::new(None)),
+    }
+  }
+
+  /// The number of the latest block which was sufficiently cosigned.
+  pub fn latest_cosigned_block_number(&self) -> u64 {
+    LatestCosignedBlock::latest_cosigned_block(&self.db)
+  }
+
+  /// The latest cosign from each network, which should be rebroadcast to our peers.
+  pub async fn cosigns_to_rebroadcast(&self) -> Vec<CosignedBlock> {
+    self.latest_cosigns.read().await.values().copied().collect()
+  }
+
+  /// The current progress in cosigning, or None if the stakes have yet to be fetched.
+  pub async fn progress(&self) -> Option<CosignProgress> {
+    let stakes = self.stakes.read().await.clone()?;
+    let latest_cosigns = self.latest_cosigns.read().await.clone();
+    let latest_cosigned_block = self.latest_cosigned_block_number();
+
+    let total_stake = stakes.values().copied().sum::<u64>();
+    let pending_block = latest_cosigns
+      .values()
+      .map(|cosign| cosign.block_number)
+      .filter(|block_number| *block_number > latest_cosigned_block)
+      .min();
+    let pending_block_stake = pending_block.map_or(0, |pending_block| {
+      latest_cosigns
+        .iter()
+        .filter(|(_, cosign)| cosign.block_number >= pending_block)
+        .map(|(network, _)| stakes.get(network).copied().unwrap_or(0))
+        .sum::<u64>()
+    });
+
+    Some(CosignProgress {
+      networks: EXTERNAL_NETWORKS
+        .into_iter()
+        .map(|network| NetworkCosignProgress {
+          network,
+          latest_cosigned_block: latest_cosigns.get(&network).map(|cosign| cosign.block_number),
+          stake: stakes.get(&network).copied(),
+        })
+        .collect(),
+      latest_cosigned_block,
+      pending_block,
+      pending_block_stake,
+      total_stake,
+      needed_stake: needed_stake(total_stake),
+      distinct_chain: self.distinct_chain_report(),
+      composition: self.cosigning_composition(),
+    })
+  }
+
+  /// The status of a block's cosigning, or None if the stakes have yet to be fetched.
+  pub async fn block_cosign_status(&self, block_number: u64) -> Option<BlockCosignStatus> {
+    let stakes = self.stakes.read().await.clone()?;
+    let latest_cosigns = self.latest_cosigns.read().await;
+    Some(block_cosign_status(block_number, &latest_cosigns, &stakes))
+  }
+
+  /// An archive of the cosigns present in the DB.
+  pub fn archive(&self) -> CosignArchive {
+    CosignArchive::export(&self.db)
+  }
+
+  /// The report from the latest check for a distinct chain, if a distinct chain was ever cosigned.
+  pub fn distinct_chain_report(&self) -> Option<DistinctChainReport> {
+    LatestDistinctChainReport::get(&self.db)
+  }
+
+  /// The current composition of the validator sets participating in cosigning, if one has been
+  /// observed.
+  pub fn cosigning_composition(&self) -> Option<CosigningComposition> {
+    self.cosigning_composition_by_id(LatestCosigningComposition::get(&self.db)?)
+  }
+
+  /// A composition of the validator sets participating in cosigning, by its ID.
+  pub fn cosigning_composition_by_id(&self, id: u32) -> Option<CosigningComposition> {
+    CosigningCompositions::get(&self.db, id)
   }
 }
 
 pub struct CosignEvaluator<D: Db> {
   db: Mutex<D>,
   serai: Arc<Serai>,
-  stakes: RwLock<Option<HashMap<ExternalNetworkId, u64>>>,
-  latest_cosigns: RwLock<HashMap<ExternalNetworkId, CosignedBlock>>,
+  reader: CosignReader<D>,
+  // If legacy cosigns, which aren't bound to a session, are accepted
+  accept_unbound: bool,
 }
 
 impl<D: Db> CosignEvaluator<D> {
   async fn update_latest_cosign(&self) {
-    let stakes_lock = self.stakes.read().await;
+    let stakes_lock = self.reader.stakes.read().await;
     // If we haven't gotten the stake data yet, return
     let Some(stakes) = stakes_lock.as_ref() else { return };
 
     let total_stake = stakes.values().copied().sum::<u64>();
 
-    let latest_cosigns = self.latest_cosigns.read().await;
+    let latest_cosigns = self.reader.latest_cosigns.read().await;
     let mut highest_block = 0;
     for cosign in latest_cosigns.values() {
       let mut networks = HashSet::new();
@@ -60,160 +1021,183 @@ impl<D: Db> CosignEvaluator<D> {
       }
       let sum_stake =
         networks.into_iter().map(|network| stakes.get(network).unwrap_or(&0)).sum::<u64>();
-      let needed_stake = ((total_stake * 2) / 3) + 1;
-      if (total_stake == 0) || (sum_stake > needed_stake) {
+      if sufficient_stake(total_stake, sum_stake) {
         highest_block = highest_block.max(cosign.block_number);
       }
     }
 
     let mut db_lock = self.db.lock().await;
     let mut txn = db_lock.txn();
-    if highest_block > LatestCosignedBlock::latest_cosigned_block(&txn) {
-      log::info!("setting latest cosigned block to {}", highest_block);
+    let advanced = highest_block > LatestCosignedBlock::latest_cosigned_block(&txn);
+    if advanced {
+      log::info!(target: logging::COSIGN, "setting latest cosigned block to {}", highest_block);
       LatestCosignedBlock::set(&mut txn, &highest_block);
     }
     txn.commit();
+    if advanced {
+      crate::event_feed::emit(crate::event_feed::Event::BlockCosigned {
+        block_number: highest_block,
+      });
+    }
   }
 
   async fn update_stakes(&self) -> Result<(), SeraiError> {
-    let serai = self.serai.as_of_latest_finalized_block().await?;
+    let latest_block = self.serai.latest_finalized_block_hash().await?;
+    let (stakes, sets) = cosigning_sets(&self.serai, &self.serai.as_of(latest_block)).await?;
 
-    let mut stakes = HashMap::new();
-    for network in EXTERNAL_NETWORKS {
-      // Use if this network has published a Batch for a short-circuit of if they've ever set a key
-      let set_key = serai.in_instructions().last_batch_for_network(network).await?.is_some();
-      if set_key {
-        stakes.insert(
-          network,
-          serai
-            .validator_sets()
-            .total_allocated_stake(network.into())
-            .await?
-            .expect("network which published a batch didn't have a stake set")
-            .0,
-        );
+    // Since we've successfully built stakes, set it
+    *self.reader.stakes.write().await = Some(stakes);
+
+    // Record the composition if it changed, retiring the prior composition within the same
+    // transaction
+    {
+      // Acquired before the DB, as the latest cosigns are elsewhere locked before the DB is
+      let mut latest_cosigns = self.reader.latest_cosigns.write().await;
+      let mut db = self.db.lock().await;
+      let latest = LatestCosigningComposition::get(&*db);
+      let current = latest.and_then(|id| CosigningCompositions::get(&*db, id));
+      if current.as_ref().map(|current| &current.sets) != Some(&sets) {
+        let id = latest.map_or(0, |id| id + 1);
+        let total_stake = sets.iter().map(|set| set.stake).sum();
+        let composition = CosigningComposition { id, block: latest_block, sets, total_stake };
+        log::info!(target: logging::COSIGN, "cosigning composition changed: {composition:?}");
+        let mut txn = db.txn();
+        let retired = current
+          .map(|current| retire_composition(&mut txn, &current, &composition))
+          .unwrap_or(vec![]);
+        CosigningCompositions::set(&mut txn, id, &composition);
+        LatestCosigningComposition::set(&mut txn, &id);
+        txn.commit();
+
+        for network in retired {
+          log::info!(target: logging::COSIGN, "{network:?} retired from cosigning");
+          latest_cosigns.remove(&network);
+        }
       }
     }
 
-    // Since we've successfully built stakes, set it
-    *self.stakes.write().await = Some(stakes);
-
     self.update_latest_cosign().await;
 
     Ok(())
   }
 
-  // Uses Err to signify a message should be retried
-  async fn handle_new_cosign(&self, cosign: CosignedBlock) -> Result<(), SeraiError> {
+  // Evaluate a cosign, verifying it without writing it to the DB
+  //
+  // `accepted` is the highest block number accepted per network within the batch this cosign is
+  // being evaluated as part of.
+  async fn evaluate_cosign(
+    &self,
+    accepted: &HashMap<ExternalNetworkId, u64>,
+    cosign: CosignedBlock,
+  ) -> Result<Evaluation, SeraiError> {
     // If we already have this cosign or a newer cosign, return
-    if let Some(latest) = self.latest_cosigns.read().await.get(&cosign.network) {
+    if let Some(latest) = self.reader.latest_cosigns.read().await.get(&cosign.network) {
       if latest.block_number >= cosign.block_number {
-        return Ok(());
+        // If this cosign is older than ours, the peer who sent it is behind
+        if latest.block_number > cosign.block_number {
+          self.reader.peer_behind.store(true, Ordering::Relaxed);
+        }
+        return Ok(Evaluation::Outcome(CosignOutcome::Stale));
       }
     }
+    if accepted.get(&cosign.network).is_some_and(|accepted| *accepted >= cosign.block_number) {
+      return Ok(Evaluation::Outcome(CosignOutcome::Stale));
+    }
 
     // If this an old cosign (older than a day), drop it
     let latest_block = self.serai.latest_finalized_block().await?;
     if (cosign.block_number + (24 * 60 * 60 / 6)) < latest_block.number() {
-      log::debug!("received old cosign supposedly signed by {:?}", cosign.network);
-      return Ok(());
+      log::debug!(
+        target: logging::COSIGN,
+        network:? = cosign.network, block = cosign.block_number;
+        "received stale cosign"
+      );
+      return Ok(Evaluation::Outcome(CosignOutcome::Stale));
     }
 
     let Some(block) = self.serai.finalized_block_by_number(cosign.block_number).await? else {
-      log::warn!("received cosign with a block number which doesn't map to a block");
-      return Ok(());
+      // If this is a cosign for a block we haven't finalized yet, buffer it until we have
+      if cosign.block_number > latest_block.number() {
+        let outcome = self.buffer_pending_cosign(latest_block.number(), cosign).await;
+        return Ok(Evaluation::Outcome(outcome));
+      }
+      log::warn!(
+        target: logging::COSIGN,
+        "received cosign with a block number which doesn't map to a block"
+      );
+      return Ok(Evaluation::Outcome(CosignOutcome::Invalid));
     };
 
-    async fn set_with_keys_fn(
-      serai: &TemporalSerai<'_>,
-      network: ExternalNetworkId,
-    ) -> Result<Option<ExternalValidatorSet>, SeraiError> {
-      let Some(latest_session) = serai.validator_sets().session(network.into()).await? else {
-        log::warn!("received cosign from {:?}, which doesn't yet have a session", network);
-        return Ok(None);
-      };
-      let prior_session = Session(latest_session.0.saturating_sub(1));
-      Ok(Some(
-        if serai
-          .validator_sets()
-          .keys(ExternalValidatorSet { network, session: prior_session })
-          .await?
-          .is_some()
-        {
-          ExternalValidatorSet { network, session: prior_session }
-        } else {
-          ExternalValidatorSet { network, session: latest_session }
-        },
-      ))
-    }
-
-    // Get the key for this network as of the prior block
-    // If we have two chains, this value may be different across chains depending on if one chain
-    // included the set_keys and one didn't
-    // Because set_keys will force a cosign, it will force detection of distinct blocks
-    // re: set_keys using keys prior to set_keys (assumed amenable to all)
-    let serai = self.serai.as_of(block.header.parent_hash.into());
-
-    let Some(set_with_keys) = set_with_keys_fn(&serai, cosign.network).await? else {
-      return Ok(());
-    };
-    let Some(keys) = serai.validator_sets().keys(set_with_keys).await? else {
-      log::warn!("received cosign for a block we didn't have keys for");
-      return Ok(());
+    let Some(set_with_keys) =
+      verify_cosign(&self.serai, &block, &cosign, self.accept_unbound).await?
+    else {
+      return Ok(Evaluation::Outcome(CosignOutcome::Invalid));
     };
 
-    if !keys
-      .0
-      .verify(&cosign_block_msg(cosign.block_number, cosign.block), &Signature(cosign.signature))
-    {
-      log::warn!("received cosigned block with an invalid signature");
-      return Ok(());
-    }
-
     log::info!(
+      target: logging::COSIGN,
       "received cosign for block {} ({}) by {:?}",
       block.number(),
       hex::encode(cosign.block),
       cosign.network
     );
 
-    // Save this cosign to the DB
-    {
-      let mut db = self.db.lock().await;
-      let mut txn = db.txn();
-      ReceivedCosign::set(&mut txn, set_with_keys, cosign.block, &cosign);
-      LatestCosign::set(&mut txn, set_with_keys.network, &(cosign));
-      txn.commit();
-    }
-
     if cosign.block != block.hash() {
       log::error!(
+        target: logging::COSIGN,
         "received cosign for a distinct block at {}. we have {}. cosign had {}",
         cosign.block_number,
         hex::encode(block.hash()),
         hex::encode(cosign.block)
       );
+    }
 
-      let serai = self.serai.as_of(latest_block.hash());
+    Ok(Evaluation::Valid {
+      set_with_keys,
+      distinct: cosign.block != block.hash(),
+      our_block: block.hash(),
+      latest_block: latest_block.hash(),
+    })
+  }
 
-      let mut db = self.db.lock().await;
-      // Save this set as being on a different chain
-      let mut txn = db.txn();
-      DistinctChain::set(&mut txn, set_with_keys, &());
-      txn.commit();
+  // Check if enough stake has cosigned a distinct chain for us to halt, saving a report on it
+  async fn check_distinct_chain(&self, latest_block: [u8; 32]) {
+    let serai = self.serai.as_of(latest_block);
+    let mut db = self.db.lock().await;
+
+    let mut sets = vec![];
+    let mut total_stake = 0;
+    let mut total_on_distinct_chain = 0;
+    for network in EXTERNAL_NETWORKS {
+      // Get the current set for this network
+      let set_with_keys = {
+        let mut res;
+        while {
+          res = set_with_keys_fn(&serai, network).await;
+          res.is_err()
+        } {
+          log::error!(
+            target: logging::COSIGN,
+            "couldn't get the set with keys when checking for a distinct chain: {:?}",
+            res
+          );
+          tokio::time::sleep(core::time::Duration::from_secs(3)).await;
+        }
+        res.unwrap()
+      };
 
-      let mut total_stake = 0;
-      let mut total_on_distinct_chain = 0;
-      for network in EXTERNAL_NETWORKS {
-        // Get the current set for this network
-        let set_with_keys = {
+      // Get its stake
+      // Doesn't use the stakes inside self to prevent deadlocks re: multi-lock acquisition
+      if let Some(set_with_keys) = set_with_keys {
+        let stake = {
           let mut res;
           while {
-            res = set_with_keys_fn(&serai, network).await;
+            res = serai.validator_sets().total_allocated_stake(set_with_keys.network.into()).await;
             res.is_err()
           } {
             log::error!(
-              "couldn't get the set with keys when checking for a distinct chain: {:?}",
+              target: logging::COSIGN,
+              "couldn't get total allocated stake when checking for a distinct chain: {:?}",
               res
             );
             tokio::time::sleep(core::time::Duration::from_secs(3)).await;
@@ -221,113 +1205,317 @@ impl<D: Db> CosignEvaluator<D> {
           res.unwrap()
         };
 
-        // Get its stake
-        // Doesn't use the stakes inside self to prevent deadlocks re: multi-lock acquisition
-        if let Some(set_with_keys) = set_with_keys {
-          let stake = {
-            let mut res;
-            while {
-              res =
-                serai.validator_sets().total_allocated_stake(set_with_keys.network.into()).await;
-              res.is_err()
-            } {
-              log::error!(
-                "couldn't get total allocated stake when checking for a distinct chain: {:?}",
-                res
-              );
-              tokio::time::sleep(core::time::Duration::from_secs(3)).await;
-            }
-            res.unwrap()
-          };
+        if let Some(stake) = stake {
+          total_stake += stake.0;
 
-          if let Some(stake) = stake {
-            total_stake += stake.0;
+          let distinct_cosign = DistinctChainCosign::get(&*db, set_with_keys);
+          let distinct =
+            distinct_cosign.is_some() || DistinctChain::get(&*db, set_with_keys).is_some();
+          if distinct {
+            total_on_distinct_chain += stake.0;
+          }
 
-            if DistinctChain::get(&*db, set_with_keys).is_some() {
-              total_on_distinct_chain += stake.0;
-            }
+          sets.push(DistinctChainSet {
+            set: set_with_keys,
+            stake: stake.0,
+            distinct,
+            distinct_cosign,
+          });
+        }
+      }
+    }
+
+    // See https://github.com/serai-dex/serai/issues/339 for the reasoning on 17%
+    let report = DistinctChainReport {
+      latest_block,
+      sets,
+      total_stake,
+      distinct_chain_stake: total_on_distinct_chain,
+      halted: (total_stake * 17 / 100) <= total_on_distinct_chain,
+    };
+    log::warn!(target: logging::COSIGN, "distinct chain report: {report:?}");
+    let mut txn = db.txn();
+    LatestDistinctChainReport::set(&mut txn, &report);
+    txn.commit();
+
+    if report.halted {
+      panic!("17% of validator sets (by stake) have co-signed a distinct chain");
+    }
+  }
+
+  // Handle a batch of cosigns, writing all valid cosigns to the DB under a single transaction
+  //
+  // Returns the outcome for each cosign, in the order the cosigns were provided.
+  // Uses Err to signify the batch should be retried
+  async fn handle_new_cosigns(
+    &self,
+    cosigns: &[CosignedBlock],
+  ) -> Result<Vec<CosignOutcome>, SeraiError> {
+    // Evaluate the cosigns in order of block number, so a newer cosign in the batch doesn't cause
+    // an older one to be treated as stale before the older one is evaluated
+    let mut order = (0 .. cosigns.len()).collect::<Vec<_>>();
+    order.sort_by_key(|i| cosigns[*i].block_number);
+
+    let mut outcomes = vec![CosignOutcome::Stale; cosigns.len()];
+    let mut accepted = HashMap::new();
+    let mut valid = vec![];
+    for i in order {
+      let cosign = cosigns[i];
+      match self.evaluate_cosign(&accepted, cosign).await? {
+        Evaluation::Outcome(outcome) => outcomes[i] = outcome,
+        Evaluation::Valid { set_with_keys, distinct, our_block, latest_block } => {
+          outcomes[i] =
+            if distinct { CosignOutcome::DistinctChain } else { CosignOutcome::Accepted };
+          if !distinct {
+            accepted.insert(cosign.network, cosign.block_number);
           }
+          valid.push((i, cosign, set_with_keys, distinct.then_some(our_block), latest_block));
         }
       }
+    }
 
-      // See https://github.com/serai-dex/serai/issues/339 for the reasoning on 17%
-      if (total_stake * 17 / 100) <= total_on_distinct_chain {
-        panic!("17% of validator sets (by stake) have co-signed a distinct chain");
+    if valid.is_empty() {
+      return Ok(outcomes);
+    }
+
+    // Save these cosigns to the DB
+    //
+    // Buffered cosigns are handled by a different task from cosigns received live, so a newer
+    // cosign may have been saved since these were evaluated. We check again here, under the DB's
+    // lock, so an older cosign never overwrites a newer one.
+    {
+      let mut db = self.db.lock().await;
+      let mut txn = db.txn();
+      for (i, cosign, set_with_keys, distinct, _) in &valid {
+        if LatestCosign::get(&txn, set_with_keys.network)
+          .is_some_and(|latest| latest.block_number >= cosign.block_number)
+        {
+          if distinct.is_none() {
+            outcomes[*i] = CosignOutcome::Stale;
+          }
+        } else {
+          LatestCosign::set(&mut txn, set_with_keys.network, cosign);
+        }
+        // Save this set as being on a different chain
+        // Only the first such cosign is saved, as any one is sufficient evidence
+        if let Some(our_block) = distinct {
+          if DistinctChainCosign::get(&txn, *set_with_keys).is_none() {
+            DistinctChainCosign::set(
+              &mut txn,
+              *set_with_keys,
+              &DistinctCosign { cosign: *cosign, our_block: *our_block },
+            );
+          }
+        }
       }
-    } else {
-      {
-        let mut latest_cosigns = self.latest_cosigns.write().await;
-        latest_cosigns.insert(cosign.network, cosign);
+      txn.commit();
+    }
+
+    let mut latest_block_with_distinct = None;
+    {
+      let mut latest_cosigns = self.reader.latest_cosigns.write().await;
+      for (_, cosign, _, distinct, latest_block) in valid {
+        if distinct.is_some() {
+          latest_block_with_distinct = Some(latest_block);
+        } else if latest_cosigns
+          .get(&cosign.network)
+          .map_or(true, |latest| latest.block_number < cosign.block_number)
+        {
+          latest_cosigns.insert(cosign.network, cosign);
+        }
       }
-      self.update_latest_cosign().await;
     }
 
-    Ok(())
+    if let Some(latest_block) = latest_block_with_distinct {
+      self.check_distinct_chain(latest_block).await;
+    }
+    self.update_latest_cosign().await;
+
+    Ok(outcomes)
   }
 
-  #[allow(clippy::new_ret_no_self)]
-  pub fn new<P: P2p>(db: D, p2p: P, serai: Arc<Serai>) -> mpsc::UnboundedSender<CosignedBlock> {
-    let mut latest_cosigns = HashMap::new();
+  // Buffer a cosign for a block we have yet to finalize, if it's valid under the current
+  // cosigning composition
+  async fn buffer_pending_cosign(
+    &self,
+    latest_finalized: u64,
+    cosign: CosignedBlock,
+  ) -> CosignOutcome {
+    let mut db = self.db.lock().await;
+    let composition =
+      LatestCosigningComposition::get(&*db).and_then(|id| CosigningCompositions::get(&*db, id));
+    let network = cosign.network;
+    let mut pending = PendingCosigns::get(&*db, network).unwrap_or(vec![]);
+    let outcome = buffer_cosign(
+      &mut pending,
+      composition.as_ref(),
+      latest_finalized,
+      self.accept_unbound,
+      cosign,
+    );
+    match outcome {
+      CosignOutcome::Buffered => {
+        let mut txn = db.txn();
+        PendingCosigns::set(&mut txn, network, &pending);
+        txn.commit();
+      }
+      CosignOutcome::Invalid => {
+        log::warn!(target: logging::COSIGN, "received invalid cosign for an unfinalized block");
+      }
+      _ => {}
+    }
+    outcome
+  }
+
+  // Handle all buffered cosigns whose blocks have since been finalized
+  async fn drain_pending_cosigns(&self) -> Result<(), SeraiError> {
+    let latest_finalized = self.serai.latest_finalized_block().await?.number();
     for network in EXTERNAL_NETWORKS {
-      if let Some(cosign) = LatestCosign::get(&db, network) {
-        latest_cosigns.insert(network, cosign);
+      let ready = {
+        let db = self.db.lock().await;
+        PendingCosigns::get(&*db, network)
+          .unwrap_or(vec![])
+          .into_iter()
+          .filter(|cosign| cosign.block_number <= latest_finalized)
+          .collect::<Vec<_>>()
+      };
+      if ready.is_empty() {
+        continue;
+      }
+
+      self.handle_new_cosigns(&ready).await?;
+
+      // Only now remove them, so they aren't lost if handling them errors
+      let mut db = self.db.lock().await;
+      let mut txn = db.txn();
+      let mut pending = PendingCosigns::get(&txn, network).unwrap_or(vec![]);
+      pending.retain(|cosign| cosign.block_number > latest_finalized);
+      if pending.is_empty() {
+        PendingCosigns::del(&mut txn, network);
+      } else {
+        PendingCosigns::set(&mut txn, network, &pending);
       }
+      txn.commit();
     }
+    Ok(())
+  }
 
-    let evaluator = Arc::new(Self {
-      db: Mutex::new(db),
-      serai,
-      stakes: RwLock::new(None),
-      latest_cosigns: RwLock::new(latest_cosigns),
-    });
+  #[allow(clippy::new_ret_no_self)]
+  pub fn new<P: P2p, C: Clock>(
+    db: D,
+    p2p: P,
+    serai: Arc<Serai>,
+    clock: C,
+    accept_unbound: bool,
+  ) -> (
+    mpsc::UnboundedSender<CosignedBlock>,
+    mpsc::UnboundedReceiver<(CosignedBlock, CosignOutcome)>,
+    CosignReader<D>,
+  ) {
+    let reader = CosignReader::new(db.clone());
+    let evaluator =
+      Arc::new(Self { db: Mutex::new(db), serai, reader: reader.clone(), accept_unbound });
 
     // Spawn a task to update stakes regularly
     tokio::spawn({
       let evaluator = evaluator.clone();
+      let clock = clock.clone();
       async move {
         loop {
           // Run this until it passes
           while evaluator.update_stakes().await.is_err() {
-            log::warn!("couldn't update stakes in the cosign evaluator");
+            log::warn!(target: logging::COSIGN, "couldn't update stakes in the cosign evaluator");
             // Try again in 10 seconds
-            sleep(Duration::from_secs(10)).await;
+            clock.sleep(Duration::from_secs(10)).await;
+          }
+          if let Some(progress) = evaluator.reader.progress().await {
+            log::debug!(target: logging::COSIGN, progress:? = progress; "cosign progress");
           }
           // Run it every 10 minutes as we don't need the exact stake data for this to be valid
-          sleep(Duration::from_secs(10 * 60)).await;
+          clock.sleep(Duration::from_secs(10 * 60)).await;
         }
       }
     });
 
-    // Spawn a task to receive cosigns and handle them
+    // Spawn a task to receive cosigns and handle them, reporting their outcomes
     let (send, mut recv) = mpsc::unbounded_channel();
+    let (outcomes_send, outcomes_recv) = mpsc::unbounded_channel();
     tokio::spawn({
       let evaluator = evaluator.clone();
+      let clock = clock.clone();
       async move {
-        while let Some(msg) = recv.recv().await {
-          while evaluator.handle_new_cosign(msg).await.is_err() {
-            // Try again in 10 seconds
-            sleep(Duration::from_secs(10)).await;
+        let mut batch = vec![];
+        // Handle every cosign which is queued as one batch
+        while recv.recv_many(&mut batch, MAX_COSIGN_BATCH_SIZE).await != 0 {
+          let outcomes = loop {
+            match evaluator.handle_new_cosigns(&batch).await {
+              Ok(outcomes) => break outcomes,
+              // Try again in 10 seconds
+              Err(_) => clock.sleep(Duration::from_secs(10)).await,
+            }
+          };
+          for (cosign, outcome) in batch.drain(..).zip(outcomes) {
+            metrics::note_cosign_outcome(cosign.network, outcome);
+            // The outcomes not being listened for isn't an error
+            let _ = outcomes_send.send((cosign, outcome));
           }
         }
       }
     });
 
-    // Spawn a task to rebroadcast the most recent cosigns
+    // Spawn a task to handle buffered cosigns once their blocks are finalized
     tokio::spawn({
+      let evaluator = evaluator.clone();
+      let clock = clock.clone();
       async move {
         loop {
-          let cosigns = evaluator.latest_cosigns.read().await.values().copied().collect::<Vec<_>>();
-          for cosign in cosigns {
-            let mut buf = vec![];
-            cosign.serialize(&mut buf).unwrap();
-            P2p::broadcast(&p2p, GossipMessageKind::CosignedBlock, buf).await;
+          if evaluator.drain_pending_cosigns().await.is_err() {
+            log::warn!(
+              target: logging::COSIGN,
+              "couldn't drain pending cosigns in the cosign evaluator"
+            );
           }
-          sleep(Duration::from_secs(60)).await;
+          // Check once per block
+          clock.sleep(Duration::from_secs(6)).await;
         }
       }
     });
 
-    // Return the channel to send cosigns
-    send
+    // Spawn a task to rebroadcast the most recent cosigns
+    tokio::spawn(rebroadcast_cosigns(reader.clone(), p2p, clock));
+
+    // Return the channel to send cosigns, the channel of their outcomes, and the reader
+    (send, outcomes_recv, reader)
+  }
+}
+
+/// Rebroadcast the most recent cosigns, as decided by `rebroadcast_interval`, forever.
+pub(crate) async fn rebroadcast_cosigns<D: Db, P: P2p, C: Clock>(
+  reader: CosignReader<D>,
+  p2p: P,
+  clock: C,
+) {
+  let mut last_rebroadcast: Option<Instant> = None;
+  loop {
+    let since_last = last_rebroadcast.map(|last| clock.now().duration_since(last));
+    let interval_elapsed = match rebroadcast_interval(&reader.db) {
+      Some(interval) => since_last.map_or(true, |since_last| since_last >= interval),
+      None => false,
+    };
+    // Rebroadcast for peers who are behind, without doing so more often than we would if
+    // cosigning was pending
+    let peer_behind = reader.peer_behind.load(Ordering::Relaxed) &&
+      since_last.map_or(true, |since_last| since_last >= PENDING_REBROADCAST_INTERVAL);
+
+    if interval_elapsed || peer_behind {
+      reader.peer_behind.store(false, Ordering::Relaxed);
+      for cosign in reader.cosigns_to_rebroadcast().await {
+        let mut buf = vec![];
+        cosign.serialize(&mut buf).unwrap();
+        P2p::broadcast(&p2p, GossipMessageKind::CosignedBlock, buf).await;
+      }
+      last_rebroadcast = Some(clock.now());
+    }
+    clock.sleep(REBROADCAST_POLL_INTERVAL).await;
   }
 }
@@ -0,0 +1,81 @@
+use sp_application_crypto::RuntimePublic;
+use serai_client::{
+  primitives::{ExternalNetworkId, Signature},
+  validator_sets::primitives::ExternalValidatorSet,
+  Public,
+};
+
+use serai_db::{Get, DbTxn};
+
+use crate::{p2p::CosignedBlock, substrate::CosignTransactions};
+
+/// Verify a cosign was signed by the specified key.
+///
+/// The message verified against is the cosign's `CosignPayload`, as signed by the processors.
+pub fn verify_cosign_signature(key: &Public, cosign: &CosignedBlock) -> bool {
+  key.verify(&cosign.payload().message(), &Signature(cosign.signature))
+}
+
+/// An error when assembling a cosign.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CosignProductionError {
+  /// The signature was of an invalid length.
+  InvalidSignatureLength,
+  /// The signature wasn't valid for the validator set's key.
+  InvalidSignature,
+}
+
+/// Assemble a cosign from the signature produced for it.
+///
+/// If the key of the validator set which produced the signature is provided, the signature is
+/// verified.
+pub fn assemble_cosign(
+  network: ExternalNetworkId,
+  session_start: [u8; 32],
+  block_number: u64,
+  block: [u8; 32],
+  signature: &[u8],
+  key: Option<&Public>,
+) -> Result<CosignedBlock, CosignProductionError> {
+  let signature =
+    <[u8; 64]>::try_from(signature).map_err(|_| CosignProductionError::InvalidSignatureLength)?;
+  let cosign =
+    CosignedBlock { network, session_start: Some(session_start), block_number, block, signature };
+  if let Some(key) = key {
+    if !verify_cosign_signature(key, &cosign) {
+      Err(CosignProductionError::InvalidSignature)?;
+    }
+  }
+  Ok(cosign)
+}
+
+/// A cosign a validator set intends to produce.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CosignIntent {
+  /// The validator set which should produce this cosign.
+  pub set: ExternalValidatorSet,
+  /// The number of the block to cosign.
+  pub block_number: u64,
+  /// The hash of the block to cosign.
+  pub block: [u8; 32],
+}
+
+impl CosignIntent {
+  /// Take the next cosign intended to be produced by a network's validator set, if there is one.
+  pub fn take(txn: &mut impl DbTxn, network: ExternalNetworkId) -> Option<CosignIntent> {
+    let (session, block_number, block) = CosignTransactions::try_recv(txn, network)?;
+    Some(CosignIntent { set: ExternalValidatorSet { network, session }, block_number, block })
+  }
+
+  /// The cosigns intended to be produced by a network's validator set, without taking them.
+  pub fn pending(getter: &impl Get, network: ExternalNetworkId) -> Vec<CosignIntent> {
+    CosignTransactions::pending(getter, network)
+      .into_iter()
+      .map(|(session, block_number, block)| CosignIntent {
+        set: ExternalValidatorSet { network, session },
+        block_number,
+        block,
+      })
+      .collect()
+  }
+}
@@ -0,0 +1,92 @@
+use serai_client::primitives::EXTERNAL_NETWORKS;
+
+use serai_db::Db;
+
+use crate::{
+  p2p::CosignedBlock,
+  cosign_evaluator::{DistinctChainReport, CosignReader},
+};
+
+/*
+  A public, read-only HTTP service relaying our view of cosigning as JSON, so explorers and bridge
+  watchers may follow cosigning without speaking libp2p.
+
+  Cosigns are served with their signatures, letting consumers verify them instead of trusting this
+  node. Nothing served is private, as all of it is already gossiped over the P2P network.
+*/
+
+fn cosign_json(cosign: &CosignedBlock) -> serde_json::Value {
+  serde_json::json!({
+    "network": format!("{:?}", cosign.network),
+    "session_start": cosign.session_start.map(hex::encode),
+    "block_number": cosign.block_number,
+    "block": hex::encode(cosign.block),
+    "signature": hex::encode(cosign.signature),
+  })
+}
+
+/// The latest cosign from each network, along with the latest block sufficiently cosigned.
+pub(crate) async fn cosigns_json<D: Db>(cosign_reader: &CosignReader<D>) -> serde_json::Value {
+  let mut cosigns = cosign_reader.cosigns_to_rebroadcast().await;
+  cosigns.sort_by_key(|cosign| EXTERNAL_NETWORKS.iter().position(|n| *n == cosign.network));
+  serde_json::json!({
+    "latest_cosigned_block": cosign_reader.latest_cosigned_block_number(),
+    "cosigns": cosigns.iter().map(cosign_json).collect::<Vec<_>>(),
+  })
+}
+
+/// The current composition of the validator sets participating in cosigning, or null if one has
+/// yet to be observed.
+pub(crate) fn session_json<D: Db>(cosign_reader: &CosignReader<D>) -> serde_json::Value {
+  cosign_reader
+    .cosigning_composition()
+    .map_or(serde_json::Value::Null, |composition| composition.to_json())
+}
+
+/// The evidence of validator sets cosigning a chain distinct from ours, or null if no distinct
+/// chain was ever cosigned.
+pub(crate) fn faults_json<D: Db>(cosign_reader: &CosignReader<D>) -> serde_json::Value {
+  let Some(DistinctChainReport { latest_block, sets, total_stake, distinct_chain_stake, halted }) =
+    cosign_reader.distinct_chain_report()
+  else {
+    return serde_json::Value::Null;
+  };
+  serde_json::json!({
+    "latest_block": hex::encode(latest_block),
+    "sets": sets.iter().map(|set| serde_json::json!({
+      "network": format!("{:?}", set.set.network),
+      "session": set.set.session.0,
+      "stake": set.stake,
+      "distinct": set.distinct,
+      "distinct_cosign": set.distinct_cosign.map(|distinct| serde_json::json!({
+        "cosign": cosign_json(&distinct.cosign),
+        "our_block": hex::encode(distinct.our_block),
+      })),
+    })).collect::<Vec<_>>(),
+    "total_stake": total_stake,
+    "distinct_chain_stake": distinct_chain_stake,
+    "halted": halted,
+  })
+}
+
+/// Serve the cosign relay on the specified port.
+///
+/// Unlike the admin API, this is bound to all interfaces, as it's intended for third parties.
+pub(crate) async fn serve<D: Db>(port: u16, cosign_reader: CosignReader<D>) {
+  log::info!("serving the cosign relay on port {port}");
+  serai_telemetry::http::serve(([0, 0, 0, 0], port).into(), move |path: String| {
+    let cosign_reader = cosign_reader.clone();
+    async move {
+      // Query parameters aren't used, yet shouldn't cause the path to not be found
+      let path = path.split_once('?').map_or(path.as_str(), |(path, _)| path);
+      let body = match path {
+        "/cosigns" => cosigns_json(&cosign_reader).await,
+        "/session" => session_json(&cosign_reader),
+        "/faults" => faults_json(&cosign_reader),
+        _ => None?,
+      };
+      Some(("application/json", body.to_string()))
+    }
+  })
+  .await
+}
@@ -1,3 +1,5 @@
+use std::time::SystemTime;
+
 use blake2::{
   digest::{consts::U32, Digest},
   Blake2b,
@@ -21,6 +23,8 @@ create_db!(
     HandledMessageDb: (network: ExternalNetworkId) -> u64,
     ActiveTributaryDb: () -> Vec<u8>,
     RetiredTributaryDb: (set: ExternalValidatorSet) -> (),
+    // The genesis of each retired Tributary yet to be pruned, with when it was retired
+    UnprunedTributaryDb: () -> Vec<([u8; 32], u64)>,
     FirstPreprocessDb: (
       network: ExternalNetworkId,
       id_type: RecognizedIdType,
@@ -65,7 +69,11 @@ impl ActiveTributaryDb {
     let mut active = Self::active_tributaries(txn).1;
     for i in 0 .. active.len() {
       if active[i].set() == set {
-        active.remove(i);
+        let retired = active.remove(i);
+        let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+        let mut unpruned = UnprunedTributaryDb::get(txn).unwrap_or_default();
+        unpruned.push((retired.genesis(), now));
+        UnprunedTributaryDb::set(txn, &unpruned);
         break;
       }
     }
@@ -79,6 +87,25 @@ impl ActiveTributaryDb {
   }
 }
 
+impl UnprunedTributaryDb {
+  /// The retired Tributaries which were retired at least `safety_window` seconds before `now`.
+  pub fn prunable<G: Get>(getter: &G, now: u64, safety_window: u64) -> Vec<[u8; 32]> {
+    Self::get(getter)
+      .unwrap_or_default()
+      .into_iter()
+      .filter_map(|(genesis, retired)| {
+        (retired.saturating_add(safety_window) <= now).then_some(genesis)
+      })
+      .collect()
+  }
+
+  pub fn pruned(txn: &mut impl DbTxn, genesis: [u8; 32]) {
+    let mut unpruned = Self::get(txn).unwrap_or_default();
+    unpruned.retain(|(unpruned, _)| *unpruned != genesis);
+    Self::set(txn, &unpruned);
+  }
+}
+
 impl FirstPreprocessDb {
   pub fn save_first_preprocess(
     txn: &mut impl DbTxn,
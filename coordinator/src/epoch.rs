@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+
+use borsh::{BorshSerialize, BorshDeserialize};
+
+use serai_client::{
+  primitives::ExternalNetworkId,
+  validator_sets::primitives::{ExternalValidatorSet, Session},
+};
+
+use serai_db::{Get, DbTxn, create_db};
+
+use crate::tributary::SlashReport;
+
+/*
+  Reports reconciling what occurred during each epoch, an epoch being a session of Serai's own
+  validator set.
+
+  The epoch's tally is updated by the Substrate scanner within the same transactions it handles
+  the relevant events in, so every event is tallied exactly once, even across reboots. Events
+  which occurred before the first epoch boundary we observed aren't tallied, as we don't know
+  which epoch they were in.
+*/
+
+/// A report of what occurred during an epoch.
+#[derive(Clone, PartialEq, Eq, Debug, BorshSerialize, BorshDeserialize)]
+pub(crate) struct EpochReport {
+  /// The session of Serai's validator set this epoch is for.
+  pub(crate) session: Session,
+  /// The Serai block this epoch started with.
+  pub(crate) start_block: u64,
+  /// The Serai block the next epoch started with, if this epoch has ended.
+  pub(crate) end_block: Option<u64>,
+  /// The latest cosigned block when this epoch started.
+  pub(crate) cosigned_at_start: u64,
+  /// The latest cosigned block when this epoch ended, if it has ended.
+  pub(crate) cosigned_at_end: Option<u64>,
+  /// The IDs of the Batches published, per network.
+  pub(crate) batches: BTreeMap<ExternalNetworkId, Vec<u32>>,
+  /// The amount of burns processed, per network.
+  pub(crate) burns: BTreeMap<ExternalNetworkId, u64>,
+  /// The validator sets whose slash reports became due.
+  pub(crate) slash_reports: Vec<ExternalValidatorSet>,
+}
+
+create_db!(
+  EpochDb {
+    CurrentEpoch: () -> EpochReport,
+    EpochReports: (session: Session) -> EpochReport,
+  }
+);
+
+fn update_current_epoch(txn: &mut impl DbTxn, update: impl FnOnce(&mut EpochReport)) {
+  if let Some(mut epoch) = CurrentEpoch::get(txn) {
+    update(&mut epoch);
+    CurrentEpoch::set(txn, &epoch);
+  }
+}
+
+/// Note a Batch was published.
+pub(crate) fn note_batch(txn: &mut impl DbTxn, network: ExternalNetworkId, id: u32) {
+  update_current_epoch(txn, |epoch| epoch.batches.entry(network).or_default().push(id));
+}
+
+/// Note a burn was processed.
+pub(crate) fn note_burn(txn: &mut impl DbTxn, network: ExternalNetworkId) {
+  update_current_epoch(txn, |epoch| *epoch.burns.entry(network).or_default() += 1);
+}
+
+/// Note a validator set's slash report became due.
+pub(crate) fn note_slash_report(txn: &mut impl DbTxn, set: ExternalValidatorSet) {
+  update_current_epoch(txn, |epoch| epoch.slash_reports.push(set));
+}
+
+/// Start a new epoch, ending the current one.
+///
+/// Returns the report for the epoch ended, if there was one.
+pub(crate) fn start_epoch(
+  txn: &mut impl DbTxn,
+  session: Session,
+  block: u64,
+  latest_cosigned_block: u64,
+) -> Option<EpochReport> {
+  let ended = CurrentEpoch::get(txn).map(|mut epoch| {
+    epoch.end_block = Some(block);
+    epoch.cosigned_at_end = Some(latest_cosigned_block);
+    EpochReports::set(txn, epoch.session, &epoch);
+    epoch
+  });
+  CurrentEpoch::set(
+    txn,
+    &EpochReport {
+      session,
+      start_block: block,
+      end_block: None,
+      cosigned_at_start: latest_cosigned_block,
+      cosigned_at_end: None,
+      batches: BTreeMap::new(),
+      burns: BTreeMap::new(),
+      slash_reports: vec![],
+    },
+  );
+  ended
+}
+
+/// The report for the specified epoch, or for the current epoch if no session is specified.
+pub(crate) fn epoch_report(getter: &impl Get, session: Option<Session>) -> Option<EpochReport> {
+  let current = CurrentEpoch::get(getter);
+  match session {
+    Some(session) if current.as_ref().map(|epoch| epoch.session) != Some(session) => {
+      EpochReports::get(getter, session)
+    }
+    _ => current,
+  }
+}
+
+impl EpochReport {
+  /// This report as JSON.
+  ///
+  /// The slashes within each slash report are included if the slash report is locally known.
+  pub(crate) fn to_json(&self, getter: &impl Get) -> serde_json::Value {
+    let networks = self
+      .batches
+      .keys()
+      .chain(self.burns.keys())
+      .collect::<std::collections::BTreeSet<_>>()
+      .into_iter()
+      .map(|network| {
+        serde_json::json!({
+          "network": format!("{network:?}"),
+          "batches": self.batches.get(network).cloned().unwrap_or_default(),
+          "burns": self.burns.get(network).copied().unwrap_or(0),
+        })
+      })
+      .collect::<Vec<_>>();
+
+    let slash_reports = self
+      .slash_reports
+      .iter()
+      .map(|set| {
+        let slashes = SlashReport::get(getter, *set).map(|slashes| {
+          slashes
+            .into_iter()
+            .map(|(validator, points)| {
+              serde_json::json!({ "validator": hex::encode(validator), "points": points })
+            })
+            .collect::<Vec<_>>()
+        });
+        serde_json::json!({
+          "network": format!("{:?}", set.network),
+          "session": set.session.0,
+          "slashes": slashes,
+        })
+      })
+      .collect::<Vec<_>>();
+
+    serde_json::json!({
+      "session": self.session.0,
+      "start_block": self.start_block,
+      "end_block": self.end_block,
+      "cosigned_blocks": self.cosigned_at_end.map(|end| end.saturating_sub(self.cosigned_at_start)),
+      "latest_cosigned_block": self.cosigned_at_end,
+      "networks": networks,
+      "slash_reports": slash_reports,
+    })
+  }
+}
+
+/// Write the report for an epoch to the directory specified by `EPOCH_REPORT_EXPORT`, if one was.
+pub(crate) fn export_epoch_report(getter: &impl Get, report: &EpochReport) {
+  let Some(dir) = serai_env::var("EPOCH_REPORT_EXPORT") else { return };
+  let path = std::path::Path::new(&dir).join(format!("epoch-{}.json", report.session.0));
+  if let Err(e) = std::fs::write(&path, report.to_json(getter).to_string()) {
+    log::error!("couldn't export the report for epoch {}: {e:?}", report.session.0);
+  }
+}
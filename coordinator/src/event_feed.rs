@@ -0,0 +1,183 @@
+use std::sync::OnceLock;
+
+use serai_client::{
+  primitives::{ExternalNetworkId, BlockHash},
+  validator_sets::primitives::ExternalValidatorSet,
+};
+
+use tokio::{
+  io::AsyncWriteExt,
+  net::{TcpStream, TcpListener},
+  sync::{mpsc, broadcast},
+};
+
+use serai_telemetry::websocket::{self, TEXT, CLOSE, PING, PONG, Frame, encode_frame, read_frame};
+
+/*
+  An optional, public, read-only WebSocket service streaming coordinator-level events as JSON, so
+  explorers and alerting systems may consume them without linking our crates or polling the Serai
+  node.
+
+  Events are streamed as they're handled, without any history or replay. Events may be repeated
+  if the coordinator reboots while handling them, so consumers should be idempotent. If a consumer
+  falls too far behind, it's sent a `lagged` event with the amount of events it missed.
+*/
+
+// How many events to buffer for consumers before they're considered lagging
+const BUFFERED_EVENTS: usize = 1024;
+
+// The maximum size of a frame we'll accept from a consumer
+const MAX_FRAME_SIZE: usize = 1024;
+
+/// An event streamed over the event feed.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(crate) enum Event {
+  /// A block became the latest block cosigned.
+  BlockCosigned { block_number: u64 },
+  /// A Batch was published to Serai.
+  BatchPublished {
+    network: ExternalNetworkId,
+    id: u32,
+    network_block: BlockHash,
+    instructions_hash: [u8; 32],
+    serai_block: u64,
+  },
+  /// A validator set was declared, starting its session.
+  SessionStarted { set: ExternalValidatorSet, serai_block: u64 },
+  /// A validator set's key pair was confirmed.
+  KeyPairConfirmed { set: ExternalValidatorSet, serai_block: u64 },
+  /// A validator set accepted the handover from its predecessor.
+  HandoverAccepted { set: ExternalValidatorSet, serai_block: u64 },
+  /// A validator set was retired, ending its session.
+  SessionRetired { set: ExternalValidatorSet, serai_block: u64 },
+}
+
+fn set_json(set: ExternalValidatorSet) -> serde_json::Value {
+  serde_json::json!({ "network": format!("{:?}", set.network), "session": set.session.0 })
+}
+
+impl Event {
+  pub(crate) fn to_json(&self) -> serde_json::Value {
+    match self {
+      Event::BlockCosigned { block_number } => {
+        serde_json::json!({ "type": "block_cosigned", "block_number": block_number })
+      }
+      Event::BatchPublished { network, id, network_block, instructions_hash, serai_block } => {
+        serde_json::json!({
+          "type": "batch_published",
+          "network": format!("{network:?}"),
+          "id": id,
+          "network_block": hex::encode(network_block.0),
+          "instructions_hash": hex::encode(instructions_hash),
+          "serai_block": serai_block,
+        })
+      }
+      Event::SessionStarted { set, serai_block } => serde_json::json!({
+        "type": "session_started", "set": set_json(*set), "serai_block": serai_block,
+      }),
+      Event::KeyPairConfirmed { set, serai_block } => serde_json::json!({
+        "type": "key_pair_confirmed", "set": set_json(*set), "serai_block": serai_block,
+      }),
+      Event::HandoverAccepted { set, serai_block } => serde_json::json!({
+        "type": "handover_accepted", "set": set_json(*set), "serai_block": serai_block,
+      }),
+      Event::SessionRetired { set, serai_block } => serde_json::json!({
+        "type": "session_retired", "set": set_json(*set), "serai_block": serai_block,
+      }),
+    }
+  }
+}
+
+fn events() -> &'static broadcast::Sender<Event> {
+  static EVENTS: OnceLock<broadcast::Sender<Event>> = OnceLock::new();
+  EVENTS.get_or_init(|| broadcast::channel(BUFFERED_EVENTS).0)
+}
+
+/// Emit an event to every consumer of the event feed.
+///
+/// This should only be called once the event has been committed to the DB.
+pub(crate) fn emit(event: Event) {
+  // This solely errors if there are no consumers, which is fine
+  let _ = events().send(event);
+}
+
+async fn handle_consumer(mut socket: TcpStream) {
+  let Some(request) = serai_telemetry::http::read_head(&mut socket).await else { return };
+  let request = String::from_utf8_lossy(&request);
+  let mut lines = request.split("\r\n");
+  let mut request_line = lines.next().unwrap_or("").split(' ');
+  let key = lines.find_map(|line| {
+    let (name, value) = line.split_once(':')?;
+    name.trim().eq_ignore_ascii_case("sec-websocket-key").then(|| value.trim().to_string())
+  });
+  let (Some("GET"), Some("/events"), Some(key)) = (request_line.next(), request_line.next(), key)
+  else {
+    let _ = socket
+      .write_all(b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+      .await;
+    return;
+  };
+
+  // Subscribe before completing the handshake so no events are missed after it
+  let mut events = events().subscribe();
+  let handshake = format!(
+    "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+     Sec-WebSocket-Accept: {}\r\n\r\n",
+    websocket::accept(&key)
+  );
+  if socket.write_all(handshake.as_bytes()).await.is_err() {
+    return;
+  }
+
+  // Read frames from the consumer in a distinct task, as reading a frame isn't cancel-safe
+  let (mut read, mut write) = socket.into_split();
+  let (frames_send, mut frames) = mpsc::channel(1);
+  tokio::spawn(async move {
+    while let Some(frame) = read_frame(&mut read, true, MAX_FRAME_SIZE).await {
+      if frames_send.send(frame).await.is_err() {
+        break;
+      }
+    }
+  });
+
+  loop {
+    let frame = tokio::select! {
+      event = events.recv() => {
+        let event = match event {
+          Ok(event) => event.to_json(),
+          Err(broadcast::error::RecvError::Lagged(missed)) => {
+            serde_json::json!({ "type": "lagged", "missed": missed })
+          }
+          Err(broadcast::error::RecvError::Closed) => return,
+        };
+        encode_frame(TEXT, event.to_string().as_bytes(), None)
+      }
+      frame = frames.recv() => match frame {
+        // Close, which we echo before closing the connection
+        Some(Frame { opcode: CLOSE, payload, .. }) => {
+          let _ = write.write_all(&encode_frame(CLOSE, &payload, None)).await;
+          return;
+        }
+        Some(Frame { opcode: PING, payload, .. }) => encode_frame(PONG, &payload, None),
+        // This is a read-only feed, so anything else sent to us is ignored
+        Some(_) => continue,
+        None => return,
+      },
+    };
+    if write.write_all(&frame).await.is_err() {
+      return;
+    }
+  }
+}
+
+/// Serve the event feed on the specified port.
+///
+/// Like the cosign relay, this is bound to all interfaces, as it's intended for third parties.
+pub(crate) async fn serve(port: u16) {
+  log::info!("serving the event feed on port {port}");
+  let server = TcpListener::bind(("0.0.0.0", port)).await.unwrap();
+  loop {
+    let Ok((socket, _)) = server.accept().await else { continue };
+    tokio::spawn(handle_consumer(socket));
+  }
+}
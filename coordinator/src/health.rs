@@ -0,0 +1,71 @@
+use serai_db::Db;
+
+use serai_client::Serai;
+
+use message_queue::client::MessageQueue;
+
+use tokio::time::{sleep, timeout};
+
+use serai_telemetry::health::{POLL_INTERVAL, CHECK_TIMEOUT, set_checks};
+
+use crate::substrate::NextBlock;
+
+/*
+  The checks for the coordinator's readiness.
+
+  The coordinator is ready if it's scanned the Serai node's latest finalized block (within a
+  tolerance), the message-queue accepts its key, and it's connected to enough P2P peers.
+*/
+
+// How many finalized blocks we may have yet to scan while still considered synced
+const SYNC_TOLERANCE: u64 = 10;
+
+async fn check_serai<D: Db>(db: &D, serai: &Serai) -> Result<String, String> {
+  let latest_finalized = timeout(CHECK_TIMEOUT, serai.latest_finalized_block())
+    .await
+    .map_err(|_| "timed out fetching the latest finalized block".to_string())?
+    .map_err(|e| format!("couldn't fetch the latest finalized block: {e}"))?
+    .number();
+  let next_block = NextBlock::get(db).unwrap_or(0);
+  let unscanned = (latest_finalized + 1).saturating_sub(next_block);
+  let detail =
+    format!("{unscanned} finalized blocks (through {latest_finalized}) yet to be scanned");
+  if unscanned > SYNC_TOLERANCE {
+    Err(detail)?;
+  }
+  Ok(detail)
+}
+
+async fn check_message_queue(message_queue: &MessageQueue) -> Result<String, String> {
+  match timeout(CHECK_TIMEOUT, message_queue.check_key()).await {
+    Ok(Some(true)) => Ok("accepted our key".to_string()),
+    Ok(Some(false)) => Err("didn't accept our key".to_string()),
+    Ok(None) | Err(_) => Err("couldn't be reached".to_string()),
+  }
+}
+
+fn check_p2p(min_peers: usize) -> Result<String, String> {
+  let peers = crate::metrics::p2p_peers().len();
+  let detail = format!("connected to {peers} peers, requiring {min_peers}");
+  if peers < min_peers {
+    Err(detail)?;
+  }
+  Ok(detail)
+}
+
+/// Poll the conditions for readiness.
+pub(crate) async fn poll<D: Db>(
+  db: D,
+  serai: &Serai,
+  message_queue: &MessageQueue,
+  min_peers: usize,
+) {
+  loop {
+    set_checks(vec![
+      ("serai", check_serai(&db, serai).await),
+      ("message_queue", check_message_queue(message_queue).await),
+      ("p2p", check_p2p(min_peers)),
+    ]);
+    sleep(POLL_INTERVAL).await;
+  }
+}
@@ -0,0 +1,45 @@
+use serai_client::primitives::ExternalNetworkId;
+
+use serai_db::{Get, DbTxn};
+
+use crate::db::HandledMessageDb;
+
+// Messages from processors may be redelivered by the message-queue, such as when we restart after
+// handling a message yet before acknowledging it. This suppresses such redeliveries, so each
+// message is handled exactly once.
+//
+// Messages from peers are intentionally not suppressed here. Tendermint rebroadcasts its messages
+// so peers who restarted (and lost the round's state) can recover, and peers rebroadcast stale
+// cosigns to signal they're behind. Suppressing repeated messages would break both, and the
+// handlers for peer messages are already idempotent.
+
+/// If a message from a processor is new, and accordingly should be handled.
+///
+/// Processor messages have sequential IDs, so every message with an ID at or below the last
+/// handled ID is a redelivery. Skipping an ID would mean a message was lost, which is a fatal
+/// error.
+pub(crate) fn new_processor_message(
+  getter: &impl Get,
+  network: ExternalNetworkId,
+  id: u64,
+) -> bool {
+  let Some(handled) = HandledMessageDb::get(getter, network) else {
+    assert_eq!(id, 0, "first message from the {network:?} processor had a non-zero ID");
+    return true;
+  };
+  if id <= handled {
+    log::debug!(
+      target: crate::logging::PROCESSOR,
+      network:? = network, id = id, handled = handled;
+      "ignoring redelivered processor message"
+    );
+    return false;
+  }
+  assert_eq!(id, handled + 1, "skipped messages from the {network:?} processor");
+  true
+}
+
+/// Note a message from a processor as handled.
+pub(crate) fn handled_processor_message(txn: &mut impl DbTxn, network: ExternalNetworkId, id: u64) {
+  HandledMessageDb::set(txn, network, &id);
+}
@@ -0,0 +1,16 @@
+// Stable targets for each subsystem
+//
+// These are prefixed with the crate's name so filters specified against the crate's module paths
+// continue to apply.
+/// The target for the coordinator's top-level handling.
+pub(crate) const COORDINATOR: &str = "serai_coordinator";
+/// The target for handling Substrate (Serai) blocks.
+pub(crate) const SUBSTRATE: &str = "serai_coordinator::substrate";
+/// The target for cosigning, both producing and evaluating cosigns.
+pub(crate) const COSIGN: &str = "serai_coordinator::cosign";
+/// The target for handling Tributaries.
+pub(crate) const TRIBUTARY: &str = "serai_coordinator::tributary";
+/// The target for the P2P network.
+pub(crate) const P2P: &str = "serai_coordinator::p2p";
+/// The target for handling messages from processors.
+pub(crate) const PROCESSOR: &str = "serai_coordinator::processor";
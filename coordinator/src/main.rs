@@ -5,14 +5,10 @@ use std::{
   collections::{VecDeque, HashSet, HashMap},
 };
 
-use zeroize::{Zeroize, Zeroizing};
-use rand_core::OsRng;
+use zeroize::Zeroizing;
 
 use ciphersuite::{
-  group::{
-    ff::{Field, PrimeField},
-    GroupEncoding,
-  },
+  group::{ff::Field, GroupEncoding},
   Ciphersuite, Ristretto,
 };
 use schnorr::SchnorrSignature;
@@ -45,6 +41,14 @@ use crate::tributary::{
 mod db;
 use db::*;
 
+mod intake;
+
+mod audit;
+
+mod networks;
+
+mod epoch;
+
 mod p2p;
 pub use p2p::*;
 
@@ -58,10 +62,28 @@ pub mod processors;
 use processors::Processors;
 
 mod substrate;
-use substrate::CosignTransactions;
+
+mod cosign_producer;
+use cosign_producer::{CosignIntent, assemble_cosign};
+
+mod clock;
+use clock::TokioClock;
 
 mod cosign_evaluator;
-use cosign_evaluator::CosignEvaluator;
+use cosign_evaluator::{CosignArchive, CosignStallReason, CosignEvaluator, cosign_watchdog};
+
+mod signer;
+pub use signer::CoordinatorSigner;
+
+mod preflight;
+
+mod logging;
+mod metrics;
+mod admin;
+mod cosign_relay;
+mod event_feed;
+#[cfg(feature = "telemetry")]
+mod health;
 
 #[cfg(test)]
 pub mod tests;
@@ -84,7 +106,7 @@ pub enum TributaryEvent<D: Db, P: P2p> {
 
 // Creates a new tributary and sends it to all listeners.
 async fn add_tributary<D: Db, Pro: Processors, P: P2p>(
-  db: D,
+  mut db: D,
   key: Zeroizing<<Ristretto as Ciphersuite>::F>,
   processors: &Pro,
   p2p: P,
@@ -93,6 +115,7 @@ async fn add_tributary<D: Db, Pro: Processors, P: P2p>(
 ) {
   if RetiredTributaryDb::get(&db, spec.set()).is_some() {
     log::info!("not adding tributary {:?} since it's been retired", spec.set());
+    return;
   }
 
   log::info!("adding tributary {:?}", spec.set());
@@ -100,7 +123,7 @@ async fn add_tributary<D: Db, Pro: Processors, P: P2p>(
   let tributary = Tributary::<_, Transaction, _>::new(
     // TODO2: Use a db on a distinct volume to protect against DoS attacks
     // TODO2: Delete said db once the Tributary is dropped
-    db,
+    db.clone(),
     spec.genesis(),
     spec.start_time(),
     key.clone(),
@@ -110,16 +133,21 @@ async fn add_tributary<D: Db, Pro: Processors, P: P2p>(
   .await
   .unwrap();
 
-  // Trigger a DKG for the newly added Tributary
-  // If we're rebooting, we'll re-fire this message
-  // This is safe due to the message-queue deduplicating based off the intent system
-  let set = spec.set();
-  let our_i = spec
-    .i(&[], Ristretto::generator() * key.deref())
-    .expect("adding a tributary for a set we aren't in set for");
-  processors
-    .send(
-      set.network,
+  // If we're rebooting during a DKG, resume it from where we left off
+  if !tributary::resend_dkg_messages(&db, processors, &spec).await {
+    // Trigger a DKG for the newly added Tributary
+    // If we're rebooting before our processor received this, we'll re-fire this message
+    // This is safe due to the message-queue deduplicating based off the intent system
+    let set = spec.set();
+    let our_i = spec
+      .i(&[], Ristretto::generator() * key.deref())
+      .expect("adding a tributary for a set we aren't in set for");
+    let mut txn = db.txn();
+    tributary::send_dkg_message(
+      &mut txn,
+      processors,
+      &spec,
+      0,
       processor_messages::key_gen::CoordinatorMessage::GenerateKey {
         id: processor_messages::key_gen::KeyGenId { session: set.session, attempt: 0 },
         params: frost::ThresholdParams::new(spec.t(), spec.n(&[]), our_i.start).unwrap(),
@@ -127,6 +155,8 @@ async fn add_tributary<D: Db, Pro: Processors, P: P2p>(
       },
     )
     .await;
+    txn.commit();
+  }
 
   tributaries
     .send(TributaryEvent::NewTributary(ActiveTributary { spec, tributary: Arc::new(tributary) }))
@@ -148,15 +178,8 @@ async fn handle_processor_message<D: Db, P: P2p>(
   network: ExternalNetworkId,
   msg: &processors::Message,
 ) -> bool {
-  #[allow(clippy::nonminimal_bool)]
-  if let Some(already_handled) = HandledMessageDb::get(db, msg.network) {
-    assert!(!(already_handled > msg.id));
-    assert!((already_handled == msg.id) || (already_handled == msg.id - 1));
-    if already_handled == msg.id {
-      return true;
-    }
-  } else {
-    assert_eq!(msg.id, 0);
+  if !intake::new_processor_message(db, msg.network, msg.id) {
+    return true;
   }
 
   let _hvq_lock = HANDOVER_VERIFY_QUEUE_LOCK.get_or_init(|| Mutex::new(())).lock().await;
@@ -182,6 +205,8 @@ async fn handle_processor_message<D: Db, P: P2p>(
       // Signer and only becomes a ProcessorMessage::Completed if the Signer is present and
       // confirms it
       sign::ProcessorMessage::Completed { session, .. } => Some(*session),
+      // This is also routed through the Signer, which will only emit it if actively signing
+      sign::ProcessorMessage::Invalidated { session, .. } => Some(*session),
     },
     ProcessorMessage::Coordinator(inner_msg) => match inner_msg {
       // This is a special case as it's relevant to *all* Tributaries for this network we're
@@ -205,6 +230,18 @@ async fn handle_processor_message<D: Db, P: P2p>(
           }
         }
 
+        audit::record(
+          "substrate_block_ack",
+          serde_json::json!({
+            "network": format!("{network:?}"),
+            "block": block,
+            "plans": plans.iter().map(|plan| serde_json::json!({
+              "session": plan.session.0,
+              "id": hex::encode(plan.id),
+            })).collect::<Vec<_>>(),
+          }),
+        );
+
         for session in sessions {
           let tributary = &tributaries[&session];
           let plans = plans
@@ -215,11 +252,15 @@ async fn handle_processor_message<D: Db, P: P2p>(
 
           let tx = Transaction::SubstrateBlock(*block);
           log::trace!(
-            "processor message effected transaction {} {:?}",
-            hex::encode(tx.hash()),
-            &tx
+            target: logging::PROCESSOR,
+            tx:% = hex::encode(tx.hash()), body:? = tx;
+            "processor message effected transaction"
+          );
+          log::trace!(
+            target: logging::TRIBUTARY,
+            tx:% = hex::encode(tx.hash());
+            "providing transaction"
           );
-          log::trace!("providing transaction {}", hex::encode(tx.hash()));
           let res = tributary.tributary.provide_transaction(tx).await;
           if !(res.is_ok() || (res == Err(ProvidedError::AlreadyProvided))) {
             if res == Err(ProvidedError::LocalMismatchesOnChain) {
@@ -247,17 +288,26 @@ async fn handle_processor_message<D: Db, P: P2p>(
       coordinator::ProcessorMessage::SlashReportPreprocess { id, .. } |
       coordinator::ProcessorMessage::SubstrateShare { id, .. } => Some(id.session),
       // This causes an action on our P2P net yet not on any Tributary
-      coordinator::ProcessorMessage::CosignedBlock { block_number, block, signature } => {
-        let cosigned_block = CosignedBlock {
-          network,
-          block_number: *block_number,
-          block: *block,
-          signature: {
-            let mut arr = [0; 64];
-            arr.copy_from_slice(signature);
-            arr
-          },
-        };
+      coordinator::ProcessorMessage::CosignedBlock {
+        session_start,
+        block_number,
+        block,
+        signature,
+      } => {
+        // This is verified by the cosign evaluator, which has the keys as of the block's parent
+        let cosigned_block =
+          assemble_cosign(network, *session_start, *block_number, *block, signature, None)
+            .expect("processor produced a cosign with an invalid signature");
+        audit::record(
+          "cosign",
+          serde_json::json!({
+            "network": format!("{network:?}"),
+            "session_start": hex::encode(session_start),
+            "block_number": block_number,
+            "block": hex::encode(block),
+            "signature": hex::encode(signature),
+          }),
+        );
         cosign_channel.send(cosigned_block).unwrap();
         let mut buf = vec![];
         cosigned_block.serialize(&mut buf).unwrap();
@@ -272,6 +322,18 @@ async fn handle_processor_message<D: Db, P: P2p>(
 
         let slashes = crate::tributary::SlashReport::get(&txn, set)
           .expect("signed slash report despite not having slash report locally");
+        audit::record(
+          "slash_report",
+          serde_json::json!({
+            "network": format!("{network:?}"),
+            "session": session.0,
+            "slashes": slashes.iter().map(|(validator, points)| serde_json::json!({
+              "validator": hex::encode(validator),
+              "points": points,
+            })).collect::<Vec<_>>(),
+            "signature": hex::encode(signature.0),
+          }),
+        );
         let slashes_pubs =
           slashes.iter().map(|(address, points)| (Public(*address), *points)).collect::<Vec<_>>();
 
@@ -331,7 +393,26 @@ async fn handle_processor_message<D: Db, P: P2p>(
           "processor sent us a signed batch for a different network than it was for",
         );
 
-        log::debug!("received batch {:?} {}", batch.batch.network, batch.batch.id);
+        log::debug!(
+          target: logging::PROCESSOR,
+          network:? = batch.batch.network, batch = batch.batch.id;
+          "received batch"
+        );
+
+        audit::record(
+          "batch",
+          serde_json::json!({
+            "network": format!("{:?}", batch.batch.network),
+            "id": batch.batch.id,
+            "block": hex::encode(batch.batch.block.0),
+            "instructions_hash": hex::encode(
+              <blake2::Blake2b<blake2::digest::consts::U32> as blake2::Digest>::digest(
+                batch.batch.instructions.encode()
+              )
+            ),
+            "signature": hex::encode(batch.signature.0),
+          }),
+        );
 
         // Save this batch to the disk
         BatchDb::set(&mut txn, batch.batch.network, batch.batch.id, &batch.clone());
@@ -359,7 +440,11 @@ async fn handle_processor_message<D: Db, P: P2p>(
           }
 
           let tx = SeraiInInstructions::execute_batch(batch.clone());
-          log::debug!("attempting to publish batch {:?} {}", batch.batch.network, batch.batch.id,);
+          log::debug!(
+            target: logging::SUBSTRATE,
+            network:? = batch.batch.network, batch = batch.batch.id;
+            "attempting to publish batch"
+          );
           // This publish may fail if this transactions already exists in the mempool, which is
           // possible, or if this batch was already executed on-chain
           // Either case will have eventual resolution and be handled by the above check on if
@@ -373,10 +458,9 @@ async fn handle_processor_message<D: Db, P: P2p>(
             );
           } else {
             log::debug!(
-              "couldn't publish batch {:?} {}: {:?}",
-              batch.batch.network,
-              batch.batch.id,
-              res,
+              target: logging::SUBSTRATE,
+              network:? = batch.batch.network, batch = batch.batch.id, error:? = res;
+              "couldn't publish batch"
             );
             // If we failed to publish it, restore it
             batches.push_front(batch);
@@ -418,7 +502,7 @@ async fn handle_processor_message<D: Db, P: P2p>(
     };
 
     let genesis = spec.genesis();
-    let pub_key = Ristretto::generator() * key.deref();
+    let pub_key = key.public_key();
 
     let txs = match msg.msg.clone() {
       ProcessorMessage::KeyGen(inner_msg) => match inner_msg {
@@ -543,9 +627,10 @@ async fn handle_processor_message<D: Db, P: P2p>(
         }
       },
       ProcessorMessage::Sign(msg) => match msg {
-        sign::ProcessorMessage::InvalidParticipant { .. } => {
-          // TODO: Locally increase slash points to maximum (distinct from an explicitly fatal
-          // slash) and censor transactions (yet don't explicitly ban)
+        sign::ProcessorMessage::InvalidParticipant { participant, .. } => {
+          // Locally increase slash points to maximum (distinct from an explicitly fatal slash)
+          // TODO: Censor transactions (yet don't explicitly ban)
+          crate::tributary::slash_invalid_participant(&mut txn, spec, participant);
           vec![]
         }
         sign::ProcessorMessage::Preprocess { id, preprocesses } => {
@@ -579,30 +664,36 @@ async fn handle_processor_message<D: Db, P: P2p>(
           })]
         }
         sign::ProcessorMessage::Completed { session: _, id, tx } => {
-          let r = Zeroizing::new(<Ristretto as Ciphersuite>::F::random(&mut OsRng));
-          #[allow(non_snake_case)]
-          let R = <Ristretto as Ciphersuite>::generator() * r.deref();
           let mut tx = Transaction::SignCompleted {
             plan: id,
             tx_hash: tx,
             first_signer: pub_key,
-            signature: SchnorrSignature { R, s: <Ristretto as Ciphersuite>::F::ZERO },
+            signature: SchnorrSignature {
+              R: <Ristretto as Ciphersuite>::generator(),
+              s: <Ristretto as Ciphersuite>::F::ZERO,
+            },
           };
-          let signed = SchnorrSignature::sign(key, r, tx.sign_completed_challenge());
-          match &mut tx {
-            Transaction::SignCompleted { signature, .. } => {
-              *signature = signed;
-            }
-            _ => unreachable!(),
-          }
+          key.sign_completion(&mut tx);
           vec![tx]
         }
+        sign::ProcessorMessage::Invalidated { session: _, id } => {
+          // The processor will schedule the plan's payments anew, which will be signed as new
+          // plans once acknowledged in a SubstrateBlock. Re-attempts for this plan will be
+          // ignored by the processor, so there's nothing to do here other than note it
+          log::warn!(
+            target: logging::PROCESSOR,
+            "plan {} was invalidated by an update to the key it was for",
+            hex::encode(id)
+          );
+          vec![]
+        }
       },
       ProcessorMessage::Coordinator(inner_msg) => match inner_msg {
         coordinator::ProcessorMessage::SubstrateBlockAck { .. } => unreachable!(),
-        coordinator::ProcessorMessage::InvalidParticipant { .. } => {
-          // TODO: Locally increase slash points to maximum (distinct from an explicitly fatal
-          // slash) and censor transactions (yet don't explicitly ban)
+        coordinator::ProcessorMessage::InvalidParticipant { participant, .. } => {
+          // Locally increase slash points to maximum (distinct from an explicitly fatal slash)
+          // TODO: Censor transactions (yet don't explicitly ban)
+          crate::tributary::slash_invalid_participant(&mut txn, spec, participant);
           vec![]
         }
         coordinator::ProcessorMessage::CosignPreprocess { id, preprocesses } |
@@ -732,11 +823,19 @@ async fn handle_processor_message<D: Db, P: P2p>(
 
     // If this created transactions, publish them
     for mut tx in txs {
-      log::trace!("processor message effected transaction {} {:?}", hex::encode(tx.hash()), &tx);
+      log::trace!(
+        target: logging::PROCESSOR,
+        tx:% = hex::encode(tx.hash()), body:? = tx;
+        "processor message effected transaction"
+      );
 
       match tx.kind() {
         TransactionKind::Provided(_) => {
-          log::trace!("providing transaction {}", hex::encode(tx.hash()));
+          log::trace!(
+            target: logging::TRIBUTARY,
+            tx:% = hex::encode(tx.hash());
+            "providing transaction"
+          );
           let res = tributary.provide_transaction(tx.clone()).await;
           if !(res.is_ok() || (res == Err(ProvidedError::AlreadyProvided))) {
             if res == Err(ProvidedError::LocalMismatchesOnChain) {
@@ -755,21 +854,25 @@ async fn handle_processor_message<D: Db, P: P2p>(
           }
         }
         TransactionKind::Unsigned => {
-          log::trace!("publishing unsigned transaction {}", hex::encode(tx.hash()));
+          log::trace!(
+            target: logging::TRIBUTARY,
+            tx:% = hex::encode(tx.hash());
+            "publishing unsigned transaction"
+          );
           match tributary.add_transaction(tx.clone()).await {
             Ok(_) => {}
             Err(e) => panic!("created an invalid unsigned transaction: {e:?}"),
           }
         }
         TransactionKind::Signed(_, _) => {
-          tx.sign(&mut OsRng, genesis, key);
+          key.sign_transaction(genesis, &mut tx);
           tributary::publish_signed_transaction(&mut txn, tributary, tx).await;
         }
       }
     }
   }
 
-  HandledMessageDb::set(&mut txn, msg.network, &msg.id);
+  intake::handled_processor_message(&mut txn, msg.network, msg.id);
   txn.commit();
 
   true
@@ -805,12 +908,12 @@ async fn handle_processor_messages<D: Db, Pro: Processors, P: P2p>(
       }
     }
 
-    // TODO: Check this ID is sane (last handled ID or expected next ID)
     let Ok(msg) = tokio::time::timeout(Duration::from_secs(1), processors.recv(network)).await
     else {
       continue;
     };
-    log::trace!("entering handle_processor_message for {:?}", network);
+    metrics::observe_processor_message(network, &msg.msg);
+    log::trace!(target: logging::PROCESSOR, network:? = network; "handling processor message");
     if handle_processor_message(
       &mut db,
       &key,
@@ -825,7 +928,7 @@ async fn handle_processor_messages<D: Db, Pro: Processors, P: P2p>(
     {
       processors.ack(msg).await;
     }
-    log::trace!("exited handle_processor_message for {:?}", network);
+    log::trace!(target: logging::PROCESSOR, network:? = network; "handled processor message");
   }
 }
 
@@ -860,16 +963,29 @@ async fn handle_cosigns_and_batch_publication<D: Db, P: P2p>(
     // Handle pending cosigns
     {
       let mut txn = db.txn();
-      while let Some((session, block, hash)) = CosignTransactions::try_recv(&mut txn, network) {
-        let Some(ActiveTributary { spec, tributary }) = tributaries.get(&session) else {
+      while let Some(intent) = CosignIntent::take(&mut txn, network) {
+        let Some(ActiveTributary { spec, tributary }) = tributaries.get(&intent.set.session) else {
+          // If the set retired (before its pending cosigns were dropped upon retirement), drop this
+          // cosign, as it'll never have a tributary to cosign with
+          if RetiredTributaryDb::get(&txn, intent.set).is_some() {
+            log::info!(
+              "dropping {:?} {:?} cosign of block #{} as the set retired",
+              network,
+              intent.set.session,
+              intent.block_number,
+            );
+            continue;
+          }
           log::warn!("didn't yet have tributary we're supposed to cosign with");
           break;
         };
         log::info!(
-          "{network:?} {session:?} cosigning block #{block} (hash {}...)",
-          hex::encode(&hash[.. 8])
+          "{network:?} {:?} cosigning block #{} (hash {}...)",
+          intent.set.session,
+          intent.block_number,
+          hex::encode(&intent.block[.. 8])
         );
-        let tx = Transaction::CosignSubstrateBlock(hash);
+        let tx = Transaction::CosignSubstrateBlock(intent.block);
         let res = tributary.provide_transaction(tx.clone()).await;
         if !(res.is_ok() || (res == Err(ProvidedError::AlreadyProvided))) {
           if res == Err(ProvidedError::LocalMismatchesOnChain) {
@@ -938,7 +1054,7 @@ async fn handle_cosigns_and_batch_publication<D: Db, P: P2p>(
           // Safe since this will drop the txn updating the most recently queued batch
           continue 'outer;
         };
-        log::debug!("providing Batch transaction {:?}", &tx);
+        log::debug!(target: logging::TRIBUTARY, tx:? = tx; "providing Batch transaction");
         let res = tributary.provide_transaction(tx.clone()).await;
         if !(res.is_ok() || (res == Err(ProvidedError::AlreadyProvided))) {
           if res == Err(ProvidedError::LocalMismatchesOnChain) {
@@ -972,7 +1088,7 @@ pub async fn handle_processors<D: Db, Pro: Processors, P: P2p>(
   mut tributary_event: broadcast::Receiver<TributaryEvent<D, P>>,
 ) {
   let mut channels = HashMap::new();
-  for network in serai_client::primitives::EXTERNAL_NETWORKS {
+  for network in networks::enabled_networks().iter().copied() {
     let (processor_send, processor_recv) = mpsc::unbounded_channel();
     tokio::spawn(handle_processor_messages(
       db.clone(),
@@ -993,12 +1109,13 @@ pub async fn handle_processors<D: Db, Pro: Processors, P: P2p>(
   loop {
     match tributary_event.recv().await.unwrap() {
       TributaryEvent::NewTributary(tributary) => {
-        let (c1, c2) = &channels[&tributary.spec.set().network];
+        let Some((c1, c2)) = channels.get(&tributary.spec.set().network) else { continue };
         c1.send(TributaryEvent::NewTributary(tributary.clone())).unwrap();
         c2.send(TributaryEvent::NewTributary(tributary)).unwrap();
       }
       TributaryEvent::TributaryRetired(set) => {
-        let (c1, c2) = &channels[&set.network];
+        // Sets are retired for every network, including the networks we don't serve
+        let Some((c1, c2)) = channels.get(&set.network) else { continue };
         c1.send(TributaryEvent::TributaryRetired(set)).unwrap();
         c2.send(TributaryEvent::TributaryRetired(set)).unwrap();
       }
@@ -1016,6 +1133,10 @@ pub async fn run<D: Db, Pro: Processors, P: P2p>(
   let (new_tributary_spec_send, mut new_tributary_spec_recv) = mpsc::unbounded_channel();
   // Reload active tributaries from the database
   for spec in ActiveTributaryDb::active_tributaries(&raw_db).1 {
+    if !networks::network_enabled(spec.set().network) {
+      log::warn!("not reloading tributary {:?} as its network isn't served", spec.set());
+      continue;
+    }
     new_tributary_spec_send.send(spec).unwrap();
   }
 
@@ -1084,6 +1205,7 @@ pub async fn run<D: Db, Pro: Processors, P: P2p>(
   let recognized_id = {
     let raw_db = raw_db.clone();
     let key = key.clone();
+    let processors = processors.clone();
 
     let specs = Arc::new(RwLock::new(HashMap::new()));
     let tributaries = Arc::new(RwLock::new(HashMap::new()));
@@ -1125,7 +1247,7 @@ pub async fn run<D: Db, Pro: Processors, P: P2p>(
         'task_loop: loop {
           match perform_slash_report_recv.recv().await {
             Some(set) => {
-              let (genesis, validators) = loop {
+              let spec = loop {
                 let specs = specs.read().await;
                 let Some(spec) = specs.get(&set) else {
                   // If we don't have this Tributary because it's retired, break and move on
@@ -1137,28 +1259,13 @@ pub async fn run<D: Db, Pro: Processors, P: P2p>(
                   log::warn!("tributary we don't have yet is supposed to perform a slash report");
                   continue;
                 };
-                break (spec.genesis(), spec.validators());
+                break spec.clone();
               };
+              let genesis = spec.genesis();
 
-              let mut slashes = vec![];
-              for (validator, _) in validators {
-                if validator == (<Ristretto as Ciphersuite>::generator() * key.deref()) {
-                  continue;
-                }
-                let validator = validator.to_bytes();
-
-                let fatally = tributary::FatallySlashed::get(&raw_db, genesis, validator).is_some();
-                // TODO: Properly type this
-                let points = if fatally {
-                  u32::MAX
-                } else {
-                  tributary::SlashPoints::get(&raw_db, genesis, validator).unwrap_or(0)
-                };
-                slashes.push(points);
-              }
-
+              let slashes = tributary::slash_report(&raw_db, &spec, &key);
               let mut tx = Transaction::SlashReport(slashes, Transaction::empty_signed());
-              tx.sign(&mut OsRng, genesis, &key);
+              key.sign_transaction(genesis, &mut tx);
 
               let mut first = true;
               loop {
@@ -1195,11 +1302,21 @@ pub async fn run<D: Db, Pro: Processors, P: P2p>(
     });
 
     move |set: ExternalValidatorSet, genesis, id_type, id: Vec<u8>| {
-      log::debug!("recognized ID {:?} {}", id_type, hex::encode(&id));
+      log::debug!(
+        target: logging::TRIBUTARY,
+        network:? = set.network, session = set.session.0, kind:? = id_type, id:% = hex::encode(&id);
+        "recognized ID"
+      );
       let mut raw_db = raw_db.clone();
       let key = key.clone();
+      let processors = processors.clone();
       let tributaries = tributaries.clone();
       async move {
+        // Don't start signing further Batches while this network's processor is behind
+        if id_type == RecognizedIdType::Batch {
+          processors.wait_for_capacity(set.network).await;
+        }
+
         // The transactions for these are fired before the preprocesses are actually
         // received/saved, creating a race between Tributary ack and the availability of all
         // Preprocesses
@@ -1233,7 +1350,7 @@ pub async fn run<D: Db, Pro: Processors, P: P2p>(
           }),
         };
 
-        tx.sign(&mut OsRng, genesis, &key);
+        key.sign_transaction(genesis, &mut tx);
 
         let mut first = true;
         loop {
@@ -1269,6 +1386,10 @@ pub async fn run<D: Db, Pro: Processors, P: P2p>(
   // Handle new blocks for each Tributary
   {
     let raw_db = raw_db.clone();
+    // How many Tributaries may replay their existing blocks at once, unbounded by default
+    let replay_parallelism = serai_env::var("TRIBUTARY_REPLAY_PARALLELISM").map(|parallelism| {
+      parallelism.parse().expect("TRIBUTARY_REPLAY_PARALLELISM wasn't a positive integer")
+    });
     tokio::spawn(tributary::scanner::scan_tributaries_task(
       raw_db,
       key.clone(),
@@ -1276,6 +1397,20 @@ pub async fn run<D: Db, Pro: Processors, P: P2p>(
       processors.clone(),
       serai.clone(),
       tributary_event_listener_2,
+      replay_parallelism,
+    ));
+  }
+
+  // Prune retired Tributaries once they're no longer needed
+  {
+    // How long to keep a retired Tributary before pruning it, 30 days by default
+    let safety_window =
+      serai_env::var("RETIRED_TRIBUTARY_PRUNE_AFTER_SECONDS").map_or(30 * 24 * 60 * 60, |secs| {
+        secs.parse().expect("RETIRED_TRIBUTARY_PRUNE_AFTER_SECONDS wasn't a non-negative integer")
+      });
+    tokio::spawn(tributary::prune_retired_tributaries_task::<_, P>(
+      raw_db.clone(),
+      Duration::from_secs(safety_window),
     ));
   }
 
@@ -1283,13 +1418,116 @@ pub async fn run<D: Db, Pro: Processors, P: P2p>(
   // in a while (presumably because we're behind)
   tokio::spawn(p2p::heartbeat_tributaries_task(p2p.clone(), tributary_event_listener_3));
 
+  // If legacy cosigns, which aren't bound to a session, are accepted
+  // These may be replayed across distinct chains which share session IDs, so they're rejected
+  // unless explicitly accepted, which is solely intended for while nodes are upgrading
+  let accept_unbound_cosigns = serai_env::var("COSIGN_ACCEPT_UNBOUND")
+    .is_some_and(|accept| accept.parse().expect("COSIGN_ACCEPT_UNBOUND wasn't a boolean"));
+
+  // Import an archive of cosigns, if one was specified
+  // This is done before creating the Cosign evaluator, which loads the imported cosigns
+  if let Some(path) = serai_env::var("COSIGN_ARCHIVE_IMPORT") {
+    let archive =
+      CosignArchive::read_from_file(&path).expect("couldn't read the specified cosign archive");
+    let mut db = raw_db.clone();
+    let imported = loop {
+      match archive.import(&mut db, &serai, accept_unbound_cosigns).await {
+        Ok(imported) => break imported,
+        Err(e) => {
+          log::error!("couldn't import the cosign archive: {e:?}");
+          tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+      }
+    };
+    log::info!("imported {imported} cosigns from the cosign archive");
+  }
+
   // Create the Cosign evaluator
-  let cosign_channel = CosignEvaluator::new(raw_db.clone(), p2p.clone(), serai.clone());
+  let (cosign_channel, cosign_outcomes, cosign_reader) = CosignEvaluator::new(
+    raw_db.clone(),
+    p2p.clone(),
+    serai.clone(),
+    TokioClock,
+    accept_unbound_cosigns,
+  );
+
+  // Alert if cosigning stalls
+  tokio::spawn({
+    let stall_after = serai_env::var("COSIGN_STALL_ALERT_SECONDS").map_or(30 * 60, |secs| {
+      secs.parse().expect("COSIGN_STALL_ALERT_SECONDS wasn't a non-negative integer")
+    });
+    let raw_db = raw_db.clone();
+    let mut stalls =
+      cosign_watchdog(raw_db.clone(), serai.clone(), Duration::from_secs(stall_after), TokioClock);
+    async move {
+      while let Some(stall) = stalls.recv().await {
+        let reason = match stall.reason {
+          CosignStallReason::AwaitingCosigns { intended_block } => {
+            format!("awaiting cosigns for block {intended_block}")
+          }
+          CosignStallReason::ScanBehind { scan_from } => {
+            format!("scanning for blocks to cosign is behind, at block {scan_from}")
+          }
+        };
+        log::error!(
+          "cosigning has stalled at block {} (latest finalized block: {}) for {}s: {reason}",
+          stall.latest_cosigned_block,
+          stall.latest_finalized_block,
+          stall.stalled_for.as_secs(),
+        );
+        // Report the cosigns which have yet to be provided to their Tributaries
+        for network in serai_client::primitives::EXTERNAL_NETWORKS {
+          for intent in CosignIntent::pending(&raw_db, network) {
+            log::error!(
+              "{:?} {:?} has yet to start cosigning block {}",
+              network,
+              intent.set.session,
+              intent.block_number,
+            );
+          }
+        }
+      }
+    }
+  });
+
+  // Regularly export an archive of cosigns, if requested
+  if let Some(path) = serai_env::var("COSIGN_ARCHIVE_EXPORT") {
+    tokio::spawn({
+      let cosign_reader = cosign_reader.clone();
+      async move {
+        loop {
+          if let Err(e) = cosign_reader.archive().write_to_file(&path) {
+            log::error!("couldn't export the cosign archive: {e:?}");
+          }
+          sleep(Duration::from_secs(10 * 60)).await;
+        }
+      }
+    });
+  }
+
+  // Serve the admin API, if a port to do so on was specified
+  if let Some(port) = serai_env::var("ADMIN_PORT") {
+    let port = port.parse().expect("ADMIN_PORT wasn't a valid port");
+    tokio::spawn(admin::serve(port, raw_db.clone(), cosign_reader.clone(), key.clone()));
+  }
+
+  // Serve the cosign relay, if a port to do so on was specified
+  if let Some(port) = serai_env::var("COSIGN_RELAY_PORT") {
+    let port = port.parse().expect("COSIGN_RELAY_PORT wasn't a valid port");
+    tokio::spawn(cosign_relay::serve(port, cosign_reader.clone()));
+  }
+
+  // Serve the event feed, if a port to do so on was specified
+  if let Some(port) = serai_env::var("EVENT_FEED_PORT") {
+    let port = port.parse().expect("EVENT_FEED_PORT wasn't a valid port");
+    tokio::spawn(event_feed::serve(port));
+  }
 
   // Handle P2P messages
   tokio::spawn(p2p::handle_p2p_task(
     p2p.clone(),
     cosign_channel.clone(),
+    cosign_outcomes,
     tributary_event_listener_4,
   ));
 
@@ -1308,24 +1546,32 @@ pub async fn run<D: Db, Pro: Processors, P: P2p>(
 
 #[tokio::main]
 async fn main() {
-  // Override the panic handler with one which will panic if any tokio task panics
-  {
-    let existing = std::panic::take_hook();
-    std::panic::set_hook(Box::new(move |panic| {
-      existing(panic);
-      const MSG: &str = "exiting the process due to a task panicking";
-      println!("{MSG}");
-      log::error!("{MSG}");
-      std::process::exit(1);
-    }));
-  }
-
-  if std::env::var("RUST_LOG").is_err() {
-    std::env::set_var("RUST_LOG", serai_env::var("RUST_LOG").unwrap_or_else(|| "info".to_string()));
+  serai_telemetry::exit_on_panic();
+
+  serai_telemetry::logging::init(
+    &std::env::var("RUST_LOG")
+      .ok()
+      .or_else(|| serai_env::var("RUST_LOG"))
+      .unwrap_or_else(|| "info".to_string()),
+  );
+
+  // If we were asked to verify an audit log, do so instead of running the coordinator
+  if let Some(path) = std::env::args().skip_while(|arg| arg != "--verify-audit-log").nth(1) {
+    let file = std::fs::File::open(&path).unwrap_or_else(|e| panic!("couldn't open {path}: {e:?}"));
+    match audit::verify(file) {
+      Ok((entries, last)) => {
+        println!("audit log was valid with {entries} entries, ending with {}", hex::encode(last));
+        std::process::exit(0);
+      }
+      Err(e) => {
+        println!("audit log was invalid: {e}");
+        std::process::exit(1);
+      }
+    }
   }
-  env_logger::init();
 
-  log::info!("starting coordinator service...");
+  log::info!(target: logging::COORDINATOR, "starting coordinator service...");
+  log::info!(target: logging::COORDINATOR, "serving networks {:?}", networks::enabled_networks());
 
   #[allow(unused_variables, unreachable_code)]
   let db = {
@@ -1340,24 +1586,39 @@ async fn main() {
     db
   };
 
-  let key = {
-    let mut key_hex = serai_env::var("SERAI_KEY").expect("Serai key wasn't provided");
-    let mut key_vec = hex::decode(&key_hex).map_err(|_| ()).expect("Serai key wasn't hex-encoded");
-    key_hex.zeroize();
-    if key_vec.len() != 32 {
-      key_vec.zeroize();
-      panic!("Serai key had an invalid length");
-    }
-    let mut key_bytes = [0; 32];
-    key_bytes.copy_from_slice(&key_vec);
-    key_vec.zeroize();
-    let key = Zeroizing::new(<Ristretto as Ciphersuite>::F::from_repr(key_bytes).unwrap());
-    key_bytes.zeroize();
-    key
-  };
+  // If we were asked to run the preflight checks, run them instead of the coordinator
+  if std::env::args().any(|arg| arg == "--preflight") {
+    preflight::preflight(db).await;
+  }
+
+  // Set the Tributaries' block time, if a non-default one was specified
+  if let Some(block_time) = serai_env::var("TRIBUTARY_BLOCK_TIME") {
+    let block_time = block_time.parse().expect("TRIBUTARY_BLOCK_TIME wasn't a positive integer");
+    ::tributary::tendermint::set_block_time(block_time)
+      .unwrap_or_else(|e| panic!("TRIBUTARY_BLOCK_TIME wasn't usable: {e}"));
+  }
+  log::info!(
+    target: logging::COORDINATOR,
+    "using a tributary block time of {}ms",
+    ::tributary::tendermint::target_block_time()
+  );
+
+  let key = signer::key_from_env().unwrap_or_else(|e| panic!("{e}"));
 
   let processors = Arc::new(MessageQueue::from_env(Service::Coordinator));
 
+  // Serve metrics, if a port to do so on was specified
+  #[cfg(feature = "telemetry")]
+  if let Some(port) = serai_env::var("METRICS_PORT") {
+    let port = port.parse().expect("METRICS_PORT wasn't a valid port");
+    serai_telemetry::metrics::register_collector(metrics::render);
+    tokio::spawn(serai_telemetry::metrics::serve(port));
+    tokio::spawn({
+      let processors = processors.clone();
+      async move { metrics::poll_message_queue_depths(&processors).await }
+    });
+  }
+
   let serai = (async {
     loop {
       let Ok(serai) = Serai::new(format!(
@@ -1366,15 +1627,42 @@ async fn main() {
       ))
       .await
       else {
-        log::error!("couldn't connect to the Serai node");
+        log::error!(target: logging::COORDINATOR, "couldn't connect to the Serai node");
         sleep(Duration::from_secs(5)).await;
         continue;
       };
-      log::info!("made initial connection to Serai node");
+      log::info!(target: logging::COORDINATOR, "made initial connection to Serai node");
       return Arc::new(serai);
     }
   })
   .await;
-  let p2p = LibP2p::new(serai.clone());
+
+  // Serve the health probes, if a port to do so on was specified
+  #[cfg(feature = "telemetry")]
+  if let Some(port) = serai_env::var("HEALTH_PORT") {
+    let port = port.parse().expect("HEALTH_PORT wasn't a valid port");
+    let min_peers = serai_env::var("HEALTH_MIN_P2P_PEERS").map_or(1, |peers| {
+      peers.parse().expect("HEALTH_MIN_P2P_PEERS wasn't a non-negative integer")
+    });
+    tokio::spawn(serai_telemetry::health::serve(port));
+    tokio::spawn({
+      let db = db.clone();
+      let serai = serai.clone();
+      let processors = processors.clone();
+      async move { health::poll(db, &serai, &processors, min_peers).await }
+    });
+  }
+
+  #[cfg(not(feature = "telemetry"))]
+  for var in ["METRICS_PORT", "HEALTH_PORT"] {
+    if serai_env::var(var).is_some() {
+      log::warn!(
+        target: logging::COORDINATOR,
+        "{var} was set yet the coordinator was built without the telemetry feature"
+      );
+    }
+  }
+
+  let p2p = LibP2p::new(db.clone(), serai.clone());
   run(db, key, p2p, processors, serai).await
 }
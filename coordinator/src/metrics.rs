@@ -0,0 +1,397 @@
+use core::{fmt::Write, time::Duration};
+use std::{
+  sync::{Mutex, OnceLock},
+  time::Instant,
+  collections::{HashSet, VecDeque, HashMap},
+};
+
+use scale::Encode;
+use serai_client::{
+  primitives::{ExternalNetworkId, EXTERNAL_NETWORKS},
+  validator_sets::primitives::ExternalValidatorSet,
+};
+
+use processor_messages::{
+  sign,
+  coordinator::{self, SubstrateSignableId},
+  ProcessorMessage,
+};
+use message_queue::{Service, client::MessageQueue};
+
+use tokio::time::sleep;
+
+use libp2p::Multiaddr;
+
+use serai_telemetry::metrics::{Histogram, increment_counter};
+
+use crate::cosign_evaluator::CosignOutcome;
+
+// How often to poll the message-queue for the depths of its queues
+const MESSAGE_QUEUE_POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+// How long to track a signing round before assuming it won't complete
+const SIGNING_ROUND_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Default, Debug)]
+struct Metrics {
+  tributary_heights: HashMap<ExternalValidatorSet, u64>,
+  cosign_progress: Option<(u64, u64)>,
+  // The depths of the queues to and from each processor
+  message_queue_depths: HashMap<(ExternalNetworkId, bool), u64>,
+  // When each message we queued to a processor, yet to be observed as acknowledged, was queued
+  queued_to_processors: HashMap<ExternalNetworkId, VecDeque<Instant>>,
+  processor_ack_latencies: HashMap<ExternalNetworkId, Histogram>,
+  p2p_peers: Option<HashMap<Multiaddr, HashSet<ExternalNetworkId>>>,
+  // The signing rounds in progress, with when they started and a description of them
+  signing_rounds_in_progress:
+    HashMap<(ExternalNetworkId, &'static str, Vec<u8>), (Instant, String)>,
+  signing_rounds: HashMap<(ExternalNetworkId, &'static str), Histogram>,
+}
+
+fn metrics() -> &'static Mutex<Metrics> {
+  static METRICS: OnceLock<Mutex<Metrics>> = OnceLock::new();
+  METRICS.get_or_init(|| Mutex::new(Metrics::default()))
+}
+
+fn network_label(network: ExternalNetworkId) -> &'static str {
+  match network {
+    ExternalNetworkId::Bitcoin => "bitcoin",
+    ExternalNetworkId::Ethereum => "ethereum",
+    ExternalNetworkId::Monero => "monero",
+  }
+}
+
+/// Set the height of the latest block handled for a Tributary.
+pub(crate) fn set_tributary_height(set: ExternalValidatorSet, height: u64) {
+  metrics().lock().unwrap().tributary_heights.insert(set, height);
+}
+
+/// Stop reporting the height of a retired Tributary.
+pub(crate) fn remove_tributary(set: ExternalValidatorSet) {
+  metrics().lock().unwrap().tributary_heights.remove(&set);
+}
+
+/// Set the latest finalized block and latest cosigned block.
+pub(crate) fn set_cosign_progress(latest_finalized_block: u64, latest_cosigned_block: u64) {
+  metrics().lock().unwrap().cosign_progress = Some((latest_finalized_block, latest_cosigned_block));
+}
+
+/// Set the peers we're connected to, by the networks they're associated with.
+pub(crate) fn set_p2p_peers(connected_peers: &HashMap<Multiaddr, HashSet<ExternalNetworkId>>) {
+  metrics().lock().unwrap().p2p_peers = Some(connected_peers.clone());
+}
+
+/// The peers we're connected to, by the networks they're associated with.
+pub(crate) fn p2p_peers() -> HashMap<Multiaddr, HashSet<ExternalNetworkId>> {
+  metrics().lock().unwrap().p2p_peers.clone().unwrap_or_default()
+}
+
+/// Note a message was queued to a processor.
+pub(crate) fn note_message_queued(network: ExternalNetworkId) {
+  metrics()
+    .lock()
+    .unwrap()
+    .queued_to_processors
+    .entry(network)
+    .or_default()
+    .push_back(Instant::now());
+}
+
+/// Set the depth of the queue to or from a processor, or None if it couldn't be fetched.
+///
+/// As processors acknowledge messages in the order they were queued, the depth of the queue to a
+/// processor tells us which of the messages we queued have since been acknowledged. Those are
+/// observed as acknowledged now, so the latency recorded is as precise as the depths are fresh.
+pub(crate) fn set_message_queue_depth(
+  network: ExternalNetworkId,
+  to_processor: bool,
+  depth: Option<u64>,
+) {
+  let mut metrics = metrics().lock().unwrap();
+  let Some(depth) = depth else {
+    metrics.message_queue_depths.remove(&(network, to_processor));
+    return;
+  };
+  metrics.message_queue_depths.insert((network, to_processor), depth);
+  if !to_processor {
+    return;
+  }
+
+  let Some(queued) = metrics.queued_to_processors.get_mut(&network) else { return };
+  let mut latencies = vec![];
+  while u64::try_from(queued.len()).unwrap() > depth {
+    latencies.push(queued.pop_front().unwrap().elapsed().as_secs_f64());
+  }
+  let histogram = metrics.processor_ack_latencies.entry(network).or_default();
+  for latency in latencies {
+    histogram.observe(latency);
+  }
+}
+
+/// Note a signing round, as identified by its kind and ID, was started by our processor.
+fn start_signing_round(
+  network: ExternalNetworkId,
+  kind: &'static str,
+  id: Vec<u8>,
+  description: String,
+) {
+  let mut metrics = metrics().lock().unwrap();
+  // Prune rounds which never completed, such as due to a participant not showing up
+  metrics
+    .signing_rounds_in_progress
+    .retain(|_, (start, _)| start.elapsed() < SIGNING_ROUND_TIMEOUT);
+  metrics
+    .signing_rounds_in_progress
+    .entry((network, kind, id))
+    .or_insert_with(|| (Instant::now(), description));
+}
+
+/// Note a signing round, as identified by its kind and ID, was completed by our processor.
+fn complete_signing_round(network: ExternalNetworkId, kind: &'static str, id: Vec<u8>) {
+  let mut metrics = metrics().lock().unwrap();
+  let Some((start, _)) = metrics.signing_rounds_in_progress.remove(&(network, kind, id)) else {
+    return;
+  };
+  metrics.signing_rounds.entry((network, kind)).or_default().observe(start.elapsed().as_secs_f64());
+}
+
+fn substrate_signing_kind(id: SubstrateSignableId) -> &'static str {
+  match id {
+    SubstrateSignableId::CosigningSubstrateBlock(_) => "cosign",
+    SubstrateSignableId::Batch(_) => "batch",
+    SubstrateSignableId::SlashReport => "slash_report",
+  }
+}
+
+/// Track the signing rounds performed by a processor, as observed via its messages.
+///
+/// A round is timed from when our processor sends its preprocess to when it sends its share,
+/// which is when it received the preprocesses from the other participants.
+pub(crate) fn observe_processor_message(network: ExternalNetworkId, msg: &ProcessorMessage) {
+  match msg {
+    ProcessorMessage::Sign(sign::ProcessorMessage::Preprocess { id, .. }) => {
+      start_signing_round(network, "sign", id.encode(), format!("{id:?}"))
+    }
+    ProcessorMessage::Sign(sign::ProcessorMessage::Share { id, .. }) => {
+      complete_signing_round(network, "sign", id.encode())
+    }
+    ProcessorMessage::Coordinator(
+      coordinator::ProcessorMessage::CosignPreprocess { id, .. } |
+      coordinator::ProcessorMessage::BatchPreprocess { id, .. } |
+      coordinator::ProcessorMessage::SlashReportPreprocess { id, .. },
+    ) => {
+      start_signing_round(network, substrate_signing_kind(id.id), id.encode(), format!("{id:?}"))
+    }
+    ProcessorMessage::Coordinator(coordinator::ProcessorMessage::SubstrateShare { id, .. }) => {
+      complete_signing_round(network, substrate_signing_kind(id.id), id.encode())
+    }
+    _ => {}
+  }
+}
+
+/// A signing round which has yet to complete.
+#[derive(Clone, Debug)]
+pub(crate) struct SigningRoundInProgress {
+  pub(crate) network: ExternalNetworkId,
+  pub(crate) kind: &'static str,
+  pub(crate) description: String,
+  pub(crate) elapsed: Duration,
+}
+
+/// The signing rounds which have yet to complete, oldest first.
+pub(crate) fn signing_rounds_in_progress() -> Vec<SigningRoundInProgress> {
+  let metrics = metrics().lock().unwrap();
+  let mut rounds = metrics
+    .signing_rounds_in_progress
+    .iter()
+    .map(|((network, kind, _), (start, description))| SigningRoundInProgress {
+      network: *network,
+      kind,
+      description: description.clone(),
+      elapsed: start.elapsed(),
+    })
+    .collect::<Vec<_>>();
+  rounds.sort_by_key(|round| core::cmp::Reverse(round.elapsed));
+  rounds
+}
+
+/// Render the metrics in the Prometheus text exposition format.
+///
+/// This is registered as a collector with the telemetry registry, as these metrics need state
+/// beyond what the registry tracks.
+#[cfg_attr(not(feature = "telemetry"), allow(dead_code))]
+pub(crate) fn render() -> String {
+  let metrics = metrics().lock().unwrap();
+  let mut res = String::new();
+
+  writeln!(res, "# HELP serai_coordinator_tributary_height The latest Tributary block handled.")
+    .unwrap();
+  writeln!(res, "# TYPE serai_coordinator_tributary_height gauge").unwrap();
+  let mut heights = metrics.tributary_heights.iter().collect::<Vec<_>>();
+  heights.sort_by_key(|(set, _)| (set.network.encode(), set.session.0));
+  for (set, height) in heights {
+    writeln!(
+      res,
+      "serai_coordinator_tributary_height{{network=\"{}\",session=\"{}\"}} {height}",
+      network_label(set.network),
+      set.session.0,
+    )
+    .unwrap();
+  }
+
+  if let Some((latest_finalized_block, latest_cosigned_block)) = metrics.cosign_progress {
+    writeln!(res, "# HELP serai_coordinator_latest_finalized_block The latest finalized block.")
+      .unwrap();
+    writeln!(res, "# TYPE serai_coordinator_latest_finalized_block gauge").unwrap();
+    writeln!(res, "serai_coordinator_latest_finalized_block {latest_finalized_block}").unwrap();
+    writeln!(res, "# HELP serai_coordinator_latest_cosigned_block The latest cosigned block.")
+      .unwrap();
+    writeln!(res, "# TYPE serai_coordinator_latest_cosigned_block gauge").unwrap();
+    writeln!(res, "serai_coordinator_latest_cosigned_block {latest_cosigned_block}").unwrap();
+    writeln!(
+      res,
+      "# HELP serai_coordinator_cosign_lag The amount of finalized blocks yet to be cosigned."
+    )
+    .unwrap();
+    writeln!(res, "# TYPE serai_coordinator_cosign_lag gauge").unwrap();
+    writeln!(
+      res,
+      "serai_coordinator_cosign_lag {}",
+      latest_finalized_block.saturating_sub(latest_cosigned_block)
+    )
+    .unwrap();
+  }
+
+  writeln!(
+    res,
+    "# HELP serai_coordinator_message_queue_depth The amount of unacknowledged messages queued."
+  )
+  .unwrap();
+  writeln!(res, "# TYPE serai_coordinator_message_queue_depth gauge").unwrap();
+  for network in EXTERNAL_NETWORKS {
+    for (to_processor, direction) in [(true, "to_processor"), (false, "from_processor")] {
+      let Some(depth) = metrics.message_queue_depths.get(&(network, to_processor)) else {
+        continue;
+      };
+      writeln!(
+        res,
+        "serai_coordinator_message_queue_depth{{network=\"{}\",direction=\"{direction}\"}} {depth}",
+        network_label(network),
+      )
+      .unwrap();
+    }
+  }
+
+  writeln!(
+    res,
+    "# HELP serai_coordinator_processor_oldest_unacknowledged_seconds How long the oldest message \
+     queued to a processor has gone unacknowledged."
+  )
+  .unwrap();
+  writeln!(res, "# TYPE serai_coordinator_processor_oldest_unacknowledged_seconds gauge").unwrap();
+  for network in EXTERNAL_NETWORKS {
+    let Some(queued) = metrics.queued_to_processors.get(&network) else { continue };
+    writeln!(
+      res,
+      "serai_coordinator_processor_oldest_unacknowledged_seconds{{network=\"{}\"}} {}",
+      network_label(network),
+      queued.front().map_or(0.0, |queued| queued.elapsed().as_secs_f64()),
+    )
+    .unwrap();
+  }
+
+  writeln!(
+    res,
+    "# HELP serai_coordinator_processor_ack_seconds The duration from queueing a message to a \
+     processor to it being acknowledged."
+  )
+  .unwrap();
+  writeln!(res, "# TYPE serai_coordinator_processor_ack_seconds histogram").unwrap();
+  for network in EXTERNAL_NETWORKS {
+    let Some(histogram) = metrics.processor_ack_latencies.get(&network) else { continue };
+    histogram.write(
+      &mut res,
+      "serai_coordinator_processor_ack_seconds",
+      &format!("network=\"{}\"", network_label(network)),
+    );
+  }
+
+  if let Some(connected_peers) = &metrics.p2p_peers {
+    let total = connected_peers.len();
+    let mut by_network = HashMap::new();
+    for nets in connected_peers.values() {
+      for net in nets {
+        *by_network.entry(*net).or_insert(0usize) += 1;
+      }
+    }
+    writeln!(res, "# HELP serai_coordinator_p2p_peers The amount of connected P2P peers.").unwrap();
+    writeln!(res, "# TYPE serai_coordinator_p2p_peers gauge").unwrap();
+    writeln!(res, "serai_coordinator_p2p_peers {total}").unwrap();
+    writeln!(
+      res,
+      "# HELP serai_coordinator_p2p_network_peers The amount of connected P2P peers per network."
+    )
+    .unwrap();
+    writeln!(res, "# TYPE serai_coordinator_p2p_network_peers gauge").unwrap();
+    for network in EXTERNAL_NETWORKS {
+      writeln!(
+        res,
+        "serai_coordinator_p2p_network_peers{{network=\"{}\"}} {}",
+        network_label(network),
+        by_network.get(&network).copied().unwrap_or(0),
+      )
+      .unwrap();
+    }
+  }
+
+  writeln!(
+    res,
+    "# HELP serai_coordinator_signing_round_seconds The duration of signing rounds, from our \
+     preprocess to our share."
+  )
+  .unwrap();
+  writeln!(res, "# TYPE serai_coordinator_signing_round_seconds histogram").unwrap();
+  let mut rounds = metrics.signing_rounds.iter().collect::<Vec<_>>();
+  rounds.sort_by_key(|((network, kind), _)| (network.encode(), *kind));
+  for ((network, kind), histogram) in rounds {
+    let labels = format!("network=\"{}\",kind=\"{kind}\"", network_label(*network));
+    histogram.write(&mut res, "serai_coordinator_signing_round_seconds", &labels);
+  }
+
+  res
+}
+
+/// Note the outcome of handling a cosign.
+pub(crate) fn note_cosign_outcome(network: ExternalNetworkId, outcome: CosignOutcome) {
+  let outcome = match outcome {
+    CosignOutcome::Stale => "stale",
+    CosignOutcome::Buffered => "buffered",
+    CosignOutcome::TooFarAhead => "too_far_ahead",
+    CosignOutcome::Invalid => "invalid",
+    CosignOutcome::Accepted => "accepted",
+    CosignOutcome::DistinctChain => "distinct_chain",
+  };
+  increment_counter(
+    "serai_coordinator_cosigns_total",
+    "The amount of cosigns received, by their outcome.",
+    &[("network", network_label(network)), ("outcome", outcome)],
+  );
+}
+
+/// Regularly poll the message-queue for the depths of the queues to and from each processor.
+///
+/// This is solely spawned when metrics are served, as these depths are solely used for metrics.
+#[cfg_attr(not(feature = "telemetry"), allow(dead_code))]
+pub(crate) async fn poll_message_queue_depths(message_queue: &MessageQueue) {
+  loop {
+    for network in crate::networks::enabled_networks().iter().copied() {
+      for (to_processor, from, to) in [
+        (true, Service::Coordinator, Service::Processor(network)),
+        (false, Service::Processor(network), Service::Coordinator),
+      ] {
+        set_message_queue_depth(network, to_processor, message_queue.depth(from, to).await);
+      }
+    }
+    sleep(MESSAGE_QUEUE_POLL_INTERVAL).await;
+  }
+}
@@ -0,0 +1,42 @@
+use std::sync::OnceLock;
+
+use serai_client::primitives::{ExternalNetworkId, EXTERNAL_NETWORKS};
+
+/// Parse a comma-separated list of networks, as `NETWORKS` is specified with.
+pub(crate) fn parse_networks(networks: &str) -> Result<Vec<ExternalNetworkId>, String> {
+  let mut res = vec![];
+  for network in networks.split(',').map(str::trim).filter(|network| !network.is_empty()) {
+    let network = match network.to_lowercase().as_str() {
+      "bitcoin" => ExternalNetworkId::Bitcoin,
+      "ethereum" => ExternalNetworkId::Ethereum,
+      "monero" => ExternalNetworkId::Monero,
+      _ => Err(format!("{network} isn't a known external network"))?,
+    };
+    if !res.contains(&network) {
+      res.push(network);
+    }
+  }
+  if res.is_empty() {
+    Err("no networks were specified".to_string())?;
+  }
+  Ok(res)
+}
+
+/// The external networks this coordinator serves.
+///
+/// This is configured via `NETWORKS`, a comma-separated list (such as `bitcoin,monero`), and
+/// defaults to all external networks. Processors, Tributaries, and peers aren't handled for the
+/// networks not served. Cosigns are still evaluated for every network, as they're necessary to
+/// follow Serai.
+pub(crate) fn enabled_networks() -> &'static [ExternalNetworkId] {
+  static NETWORKS: OnceLock<Vec<ExternalNetworkId>> = OnceLock::new();
+  NETWORKS.get_or_init(|| match serai_env::var("NETWORKS") {
+    Some(networks) => parse_networks(&networks).unwrap_or_else(|e| panic!("NETWORKS: {e}")),
+    None => EXTERNAL_NETWORKS.to_vec(),
+  })
+}
+
+/// If this coordinator serves the specified network.
+pub(crate) fn network_enabled(network: ExternalNetworkId) -> bool {
+  enabled_networks().contains(&network)
+}
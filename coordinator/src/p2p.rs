@@ -1,4 +1,4 @@
-use core::{time::Duration, fmt};
+use core::{time::Duration, hash::Hash, fmt};
 use std::{
   sync::Arc,
   io::{self, Read},
@@ -12,10 +12,14 @@ use rand_core::{RngCore, OsRng};
 use scale::{Decode, Encode};
 use borsh::{BorshSerialize, BorshDeserialize};
 use serai_client::{
-  primitives::ExternalNetworkId, validator_sets::primitives::ExternalValidatorSet, Serai,
+  primitives::{ExternalNetworkId, EXTERNAL_NETWORKS},
+  validator_sets::primitives::ExternalValidatorSet,
+  Serai,
 };
 
-use serai_db::Db;
+use serai_db::{Get, DbTxn, Db, create_db};
+
+use processor_messages::coordinator::CosignPayload;
 
 use futures_util::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, StreamExt};
 use tokio::{
@@ -34,17 +38,37 @@ use libp2p::{
     Behaviour as RrBehavior, ProtocolSupport,
   },
   gossipsub::{
-    IdentTopic, FastMessageId, MessageId, MessageAuthenticity, ValidationMode, ConfigBuilder,
-    IdentityTransform, AllowAllSubscriptionFilter, Event as GsEvent, PublishError,
-    Behaviour as GsBehavior,
+    IdentTopic, TopicHash, FastMessageId, MessageId, MessageAuthenticity, ValidationMode,
+    ConfigBuilder, DataTransform, RawMessage, Message as GsMessage, AllowAllSubscriptionFilter,
+    Event as GsEvent, PublishError, Behaviour as GsBehavior,
   },
+  relay, dcutr, identify,
   swarm::{NetworkBehaviour, SwarmEvent},
   SwarmBuilder,
 };
 
 pub(crate) use tributary::{ReadWrite, P2p as TributaryP2p};
 
-use crate::{Transaction, Block, Tributary, ActiveTributary, TributaryEvent};
+use crate::{
+  Transaction, Block, Tributary, ActiveTributary, TributaryEvent, cosign_evaluator::CosignOutcome,
+  logging,
+};
+
+// The amount of peers to maintain per network, unless overridden
+const DEFAULT_TARGET_PEERS: usize = 5;
+// The maximum amount of peers to remember per network
+const MAX_KNOWN_PEERS_PER_NETWORK: usize = 64;
+// How long after we last saw a peer we'll stop trying to reconnect to it
+const KNOWN_PEER_EXPIRY: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+// The maximum score for a known peer, bounding how many failed dials it takes to forget a peer
+const MAX_KNOWN_PEER_SCORE: u32 = 8;
+// The maximum amount of peers to share per network when exchanging peers
+const MAX_EXCHANGED_PEERS_PER_NETWORK: usize = 16;
+// The delay before redialing an address which failed to connect, doubled with each failure
+const DIAL_BACKOFF: Duration = Duration::from_secs(60);
+const MAX_DIAL_BACKOFF: Duration = Duration::from_secs(60 * 60);
+// The port we listen on, for both TCP and QUIC
+const PORT: u16 = 30563; // 5132 ^ (('c' << 8) | 'o')
 
 // Block size limit + 1 KB of space for signatures/metadata
 const MAX_LIBP2P_GOSSIP_MESSAGE_SIZE: usize = tributary::BLOCK_SIZE_LIMIT + 1024;
@@ -61,27 +85,211 @@ const MAX_LIBP2P_MESSAGE_SIZE: usize = {
   }
 };
 
-const LIBP2P_TOPIC: &str = "serai-coordinator";
+// The request-response protocols, in order of preference
+//
+// The compressed protocol is negotiated with peers which support it, with the legacy protocol
+// used with peers yet to upgrade
+const LIBP2P_COMPRESSED_PROTOCOL: &str = "/coordinator/zstd/1";
+const LIBP2P_PROTOCOL: &str = "/coordinator";
+
+// The protocol announced via identify, which DCUtR uses to learn our observed addresses
+const LIBP2P_IDENTIFY_PROTOCOL: &str = "/coordinator/identify/1";
+
+// The suffix for the versions of the gossip topics whose messages are compressed
+//
+// Upgraded peers subscribe to both the legacy and compressed topics, relaying the legacy topics
+// for peers yet to upgrade.
+const COMPRESSED_TOPIC_SUFFIX: &str = "/zstd/1";
+
+// The zstd compression level used for messages
+//
+// Messages are small and latency-sensitive, so this favors speed over the compression ratio
+const ZSTD_LEVEL: i32 = 3;
+
+fn compress(msg: &[u8]) -> io::Result<Vec<u8>> {
+  zstd::bulk::compress(msg, ZSTD_LEVEL)
+}
+
+// Decompress a message, erroring if it decompresses to more than `max_size` bytes
+fn decompress(msg: &[u8], max_size: usize) -> io::Result<Vec<u8>> {
+  zstd::bulk::decompress(msg, max_size)
+}
+
+pub(crate) fn compressed_topic(topic: &IdentTopic) -> IdentTopic {
+  IdentTopic::new(format!("{}{COMPRESSED_TOPIC_SUFFIX}", topic.hash().as_str()))
+}
 
-// Amount of blocks in a minute
-const BLOCKS_PER_MINUTE: usize = (60 / (tributary::tendermint::TARGET_BLOCK_TIME / 1000)) as usize;
+// Transparently compress messages published to, and decompress messages received on, the
+// compressed topics
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct GossipCompression;
+impl DataTransform for GossipCompression {
+  fn inbound_transform(&self, raw_message: RawMessage) -> io::Result<GsMessage> {
+    let data = if raw_message.topic.as_str().ends_with(COMPRESSED_TOPIC_SUFFIX) {
+      decompress(&raw_message.data, MAX_LIBP2P_GOSSIP_MESSAGE_SIZE)?
+    } else {
+      raw_message.data
+    };
+    Ok(GsMessage {
+      source: raw_message.source,
+      data,
+      sequence_number: raw_message.sequence_number,
+      topic: raw_message.topic,
+    })
+  }
+
+  fn outbound_transform(&self, topic: &TopicHash, data: Vec<u8>) -> io::Result<Vec<u8>> {
+    if topic.as_str().ends_with(COMPRESSED_TOPIC_SUFFIX) {
+      compress(&data)
+    } else {
+      Ok(data)
+    }
+  }
+}
+
+pub(crate) const LIBP2P_TOPIC: &str = "serai-coordinator";
+
+// Amount of blocks in a minute, with the default block time
+// This isn't updated for the configured block time as it bounds the size of messages on the wire
+const BLOCKS_PER_MINUTE: usize = (60 / tributary::tendermint::DEFAULT_BLOCK_TIME) as usize;
 
 // Maximum amount of blocks to send in a batch
 const BLOCKS_PER_BATCH: usize = BLOCKS_PER_MINUTE + 1;
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, BorshSerialize, BorshDeserialize)]
+// Serialize byte arrays as hex strings
+// serde doesn't support arrays of more than 32 elements, and hex is more legible for consumers of
+// the JSON
+#[cfg(feature = "serde")]
+pub(crate) mod serde_hex {
+  use serde::{Serializer, Deserializer, Deserialize, de::Error};
+
+  pub(crate) fn serialize<S: Serializer, const N: usize>(
+    bytes: &[u8; N],
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&hex::encode(bytes))
+  }
+
+  pub(crate) fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+    deserializer: D,
+  ) -> Result<[u8; N], D::Error> {
+    hex::decode(String::deserialize(deserializer)?)
+      .map_err(D::Error::custom)?
+      .try_into()
+      .map_err(|_| D::Error::custom("hex was of the wrong length"))
+  }
+
+  pub(crate) mod option {
+    use serde::{Serializer, Deserializer, Deserialize, de::Error};
+
+    pub(crate) fn serialize<S: Serializer, const N: usize>(
+      bytes: &Option<[u8; N]>,
+      serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+      match bytes {
+        Some(bytes) => serializer.serialize_some(&hex::encode(bytes)),
+        None => serializer.serialize_none(),
+      }
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+      deserializer: D,
+    ) -> Result<Option<[u8; N]>, D::Error> {
+      let Some(hex) = Option::<String>::deserialize(deserializer)? else { return Ok(None) };
+      Ok(Some(
+        hex::decode(hex)
+          .map_err(D::Error::custom)?
+          .try_into()
+          .map_err(|_| D::Error::custom("hex was of the wrong length"))?,
+      ))
+    }
+  }
+}
+
+// The byte prefixing a versioned cosign
+//
+// Legacy cosigns are prefixed by their network's byte, which is never this value
+const VERSIONED_COSIGN: u8 = u8::MAX;
+// The version of cosigns bound to the session which produced them
+const SESSION_BOUND_COSIGN_VERSION: u8 = 1;
+
+/// A cosign for a block.
+///
+/// Cosigns bound to the session which produced them are serialized with a version prefix, while
+/// legacy cosigns continue to be serialized as they historically were. Nodes which don't
+/// recognize the versioned serialization will fail to deserialize such cosigns, not misinterpret
+/// them.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CosignedBlock {
   pub network: ExternalNetworkId,
+  /// The hash of the block the cosigning validator set's session began with, or None if this is a
+  /// legacy cosign not bound to a session.
+  #[cfg_attr(feature = "serde", serde(with = "serde_hex::option"))]
+  pub session_start: Option<[u8; 32]>,
   pub block_number: u64,
+  #[cfg_attr(feature = "serde", serde(with = "serde_hex"))]
   pub block: [u8; 32],
+  #[cfg_attr(feature = "serde", serde(with = "serde_hex"))]
   pub signature: [u8; 64],
 }
 
+impl CosignedBlock {
+  /// The payload signed for this cosign.
+  pub fn payload(&self) -> CosignPayload {
+    CosignPayload {
+      session_start: self.session_start,
+      block_number: self.block_number,
+      block: self.block,
+    }
+  }
+}
+
+impl BorshSerialize for CosignedBlock {
+  fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+    if let Some(session_start) = self.session_start {
+      writer.write_all(&[VERSIONED_COSIGN, SESSION_BOUND_COSIGN_VERSION])?;
+      BorshSerialize::serialize(&self.network, writer)?;
+      writer.write_all(&session_start)?;
+    } else {
+      BorshSerialize::serialize(&self.network, writer)?;
+    }
+    BorshSerialize::serialize(&self.block_number, writer)?;
+    writer.write_all(&self.block)?;
+    writer.write_all(&self.signature)
+  }
+}
+
+impl BorshDeserialize for CosignedBlock {
+  fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+    let mut prefix = [0; 1];
+    reader.read_exact(&mut prefix)?;
+    let (network, session_start) = if prefix[0] == VERSIONED_COSIGN {
+      let version: u8 = BorshDeserialize::deserialize_reader(reader)?;
+      if version != SESSION_BOUND_COSIGN_VERSION {
+        Err(io::Error::other("unrecognized cosign version"))?;
+      }
+      let network = BorshDeserialize::deserialize_reader(reader)?;
+      (network, Some(BorshDeserialize::deserialize_reader(reader)?))
+    } else {
+      (BorshDeserialize::deserialize_reader(&mut prefix.as_slice())?, None)
+    };
+    Ok(CosignedBlock {
+      network,
+      session_start,
+      block_number: BorshDeserialize::deserialize_reader(reader)?,
+      block: BorshDeserialize::deserialize_reader(reader)?,
+      signature: BorshDeserialize::deserialize_reader(reader)?,
+    })
+  }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum ReqResMessageKind {
   KeepAlive,
   Heartbeat([u8; 32]),
   Block([u8; 32]),
+  Peers,
 }
 
 impl ReqResMessageKind {
@@ -100,6 +308,7 @@ impl ReqResMessageKind {
         reader.read_exact(&mut genesis).ok()?;
         ReqResMessageKind::Block(genesis)
       }),
+      3 => Some(ReqResMessageKind::Peers),
       _ => None,
     }
   }
@@ -117,6 +326,7 @@ impl ReqResMessageKind {
         res.extend(genesis);
         res
       }
+      ReqResMessageKind::Peers => vec![3],
     }
   }
 }
@@ -165,7 +375,7 @@ pub enum P2pMessageKind {
 impl P2pMessageKind {
   fn genesis(&self) -> Option<[u8; 32]> {
     match self {
-      P2pMessageKind::ReqRes(ReqResMessageKind::KeepAlive) |
+      P2pMessageKind::ReqRes(ReqResMessageKind::KeepAlive | ReqResMessageKind::Peers) |
       P2pMessageKind::Gossip(GossipMessageKind::CosignedBlock) => None,
       P2pMessageKind::ReqRes(
         ReqResMessageKind::Heartbeat(genesis) | ReqResMessageKind::Block(genesis),
@@ -206,9 +416,255 @@ pub struct HeartbeatBatch {
   pub timestamp: u64,
 }
 
+// The addresses of peers we're connected to, by the network whose validator set they're within
+#[derive(Clone, Debug, Encode, Decode)]
+struct PeerExchange(Vec<(ExternalNetworkId, Vec<Vec<u8>>)>);
+
+#[derive(Clone, PartialEq, Eq, Debug, BorshSerialize, BorshDeserialize)]
+pub(crate) struct KnownPeer {
+  addr: Vec<u8>,
+  // The time we were last connected to this peer, in seconds since the epoch
+  last_seen: u64,
+  // How reliably we can connect to this peer, incremented when we connect and decremented when we
+  // fail to
+  score: u32,
+}
+
+create_db! {
+  P2pDb {
+    // The peers we've connected to, by the network whose validator set they're within
+    // This is ordered from least to most recently seen
+    KnownPeers: (network: ExternalNetworkId) -> Vec<KnownPeer>,
+  }
+}
+
+impl KnownPeers {
+  fn now() -> u64 {
+    SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs()
+  }
+
+  pub(crate) fn seen(txn: &mut impl DbTxn, network: ExternalNetworkId, addr: &Multiaddr) {
+    let addr = addr.to_vec();
+    let mut peers = Self::get(txn, network).unwrap_or(vec![]);
+    let mut score = 0;
+    peers.retain(|peer| {
+      if peer.addr == addr {
+        score = peer.score;
+        return false;
+      }
+      true
+    });
+    let score = (score + 1).min(MAX_KNOWN_PEER_SCORE);
+    peers.push(KnownPeer { addr, last_seen: Self::now(), score });
+    if peers.len() > MAX_KNOWN_PEERS_PER_NETWORK {
+      peers.remove(0);
+    }
+    Self::set(txn, network, &peers);
+  }
+
+  // Note we failed to connect to a peer, forgetting it if it's repeatedly failed
+  pub(crate) fn failed(txn: &mut impl DbTxn, network: ExternalNetworkId, addr: &Multiaddr) {
+    let addr = addr.to_vec();
+    let mut peers = Self::get(txn, network).unwrap_or(vec![]);
+    let Some(i) = peers.iter().position(|peer| peer.addr == addr) else { return };
+    peers[i].score -= 1;
+    if peers[i].score == 0 {
+      peers.remove(i);
+    }
+    Self::set(txn, network, &peers);
+  }
+
+  fn live(getter: &impl Get, network: ExternalNetworkId) -> Vec<KnownPeer> {
+    let cutoff = Self::now().saturating_sub(KNOWN_PEER_EXPIRY.as_secs());
+    Self::get(getter, network)
+      .unwrap_or(vec![])
+      .into_iter()
+      .filter(|peer| peer.last_seen >= cutoff)
+      .collect()
+  }
+
+  // The addrs of the peers we've connected to, excluding those we haven't seen in a while (which
+  // are presumed to no longer be validators)
+  fn addrs(getter: &impl Get, network: ExternalNetworkId) -> Vec<Multiaddr> {
+    Self::live(getter, network)
+      .into_iter()
+      .filter_map(|peer| Multiaddr::try_from(peer.addr).ok())
+      .collect()
+  }
+
+  // The addrs of the best peers we've connected to, by score and then by how recently we saw them
+  pub(crate) fn best_addrs(
+    getter: &impl Get,
+    network: ExternalNetworkId,
+    amount: usize,
+  ) -> Vec<Multiaddr> {
+    let mut peers = Self::live(getter, network);
+    // As peers are ordered from least to most recently seen, reverse them so a stable sort by
+    // score will leave the most recently seen first
+    peers.reverse();
+    peers.sort_by_key(|peer| core::cmp::Reverse(peer.score));
+    peers.into_iter().filter_map(|peer| Multiaddr::try_from(peer.addr).ok()).take(amount).collect()
+  }
+}
+
+// If we should use QUIC, in addition to TCP
+//
+// QUIC offers faster connection establishment and better NAT traversal. When enabled, we listen
+// on both transports and prefer dialing peers over QUIC, falling back to TCP for peers which fail
+// to connect over QUIC.
+fn quic_enabled() -> bool {
+  serai_env::var("P2P_QUIC").is_some_and(|quic| quic.parse().expect("P2P_QUIC wasn't a boolean"))
+}
+
+// The relays to reserve slots with, so peers may reach us if we're behind a NAT
+//
+// These are configured via `P2P_RELAYS` as a comma-separated list of addresses, each ending with
+// the PeerId of the relay.
+pub(crate) fn relays() -> Result<Vec<Multiaddr>, String> {
+  let Some(relays) = serai_env::var("P2P_RELAYS") else { return Ok(vec![]) };
+  relays
+    .split(',')
+    .map(str::trim)
+    .filter(|relay| !relay.is_empty())
+    .map(|relay| {
+      let relay = relay
+        .parse::<Multiaddr>()
+        .map_err(|_| format!("P2P_RELAYS had an invalid address: {relay}"))?;
+      if !matches!(relay.iter().last(), Some(Protocol::P2p(_))) {
+        Err(format!("relay in P2P_RELAYS didn't end with its PeerId: {relay}"))?;
+      }
+      Ok(relay)
+    })
+    .collect()
+}
+
+/// The PeerId of the peer a relayed (circuit) address is for, if this is a relayed address.
+pub(crate) fn relayed_peer(addr: &Multiaddr) -> Option<PeerId> {
+  let mut protocols = addr.iter().skip_while(|protocol| *protocol != Protocol::P2pCircuit);
+  protocols.next()?;
+  match protocols.next() {
+    Some(Protocol::P2p(peer_id)) => Some(peer_id),
+    _ => None,
+  }
+}
+
+/// The address to dial a peer at, over either QUIC or TCP, from an address found for it.
+///
+/// This drops any PeerId and replaces the transport with our own, on our port. Relayed addresses
+/// are returned as-is, as they're dialed via the relay and require the PeerIds within them.
+pub(crate) fn dial_addr(addr: &Multiaddr, quic: bool) -> Multiaddr {
+  if relayed_peer(addr).is_some() {
+    return addr.clone();
+  }
+
+  let mut res = Multiaddr::empty();
+  for protocol in addr.iter() {
+    match protocol {
+      // Drop PeerIds from the Substrate P2p network
+      Protocol::P2p(_) => {}
+      // Use our own transport and port
+      Protocol::Tcp(_) | Protocol::Udp(_) => {
+        if quic {
+          res.push(Protocol::Udp(PORT));
+          res.push(Protocol::QuicV1);
+        } else {
+          res.push(Protocol::Tcp(PORT));
+        }
+      }
+      // Already pushed alongside the UDP port, if we're using QUIC
+      Protocol::Quic | Protocol::QuicV1 => {}
+      other => res.push(other),
+    }
+  }
+  res
+}
+
+// The amount of peers to maintain for a network, overridable via `{NETWORK}_P2P_TARGET_PEERS`
+fn target_peers(network: ExternalNetworkId) -> usize {
+  let network_str = match network {
+    ExternalNetworkId::Bitcoin => "BITCOIN",
+    ExternalNetworkId::Ethereum => "ETHEREUM",
+    ExternalNetworkId::Monero => "MONERO",
+  };
+  serai_env::var(&format!("{network_str}_P2P_TARGET_PEERS"))
+    .map_or(DEFAULT_TARGET_PEERS, |target| {
+      target.parse().expect("P2P target peers wasn't a non-negative integer")
+    })
+}
+
+// The score, net of decay, at which a peer is banned for sending invalid cosigns
+const INVALID_COSIGN_BAN_SCORE: u32 = 16;
+// How often one invalid cosign is forgiven
+const INVALID_COSIGN_DECAY: Duration = Duration::from_secs(60);
+// How long a peer is banned for
+const INVALID_COSIGN_BAN: Duration = Duration::from_secs(30 * 60);
+
+/// Scores of peers by the invalid cosigns they've sent us.
+///
+/// Cosigns are attributed to the peers which sent them until the cosign evaluator reports their
+/// outcome. Peers which send too many invalid cosigns are temporarily banned, with cosigns from
+/// them dropped without being evaluated.
+#[derive(Debug)]
+pub(crate) struct CosignPeerScores<Id: Copy + Eq + Hash> {
+  senders: HashMap<CosignedBlock, HashSet<Id>>,
+  // The score of each peer, with when it was last decayed
+  scores: HashMap<Id, (u32, Instant)>,
+  banned: HashMap<Id, Instant>,
+}
+
+impl<Id: Copy + Eq + Hash> CosignPeerScores<Id> {
+  pub(crate) fn new() -> Self {
+    CosignPeerScores { senders: HashMap::new(), scores: HashMap::new(), banned: HashMap::new() }
+  }
+
+  /// If a peer is banned.
+  pub(crate) fn banned(&mut self, peer: Id, now: Instant) -> bool {
+    let Some(until) = self.banned.get(&peer) else { return false };
+    if *until <= now {
+      self.banned.remove(&peer);
+      return false;
+    }
+    true
+  }
+
+  /// Attribute a cosign to the peer which sent it.
+  pub(crate) fn received(&mut self, peer: Id, cosign: CosignedBlock) {
+    self.senders.entry(cosign).or_default().insert(peer);
+  }
+
+  /// Handle the outcome of a cosign, returning the peers newly banned due to it.
+  pub(crate) fn outcome(
+    &mut self,
+    cosign: &CosignedBlock,
+    outcome: CosignOutcome,
+    now: Instant,
+  ) -> Vec<Id> {
+    let Some(senders) = self.senders.remove(cosign) else { return vec![] };
+    if outcome != CosignOutcome::Invalid {
+      return vec![];
+    }
+
+    let mut banned = vec![];
+    for peer in senders {
+      let (score, decayed_at) = self.scores.entry(peer).or_insert((0, now));
+      let periods =
+        now.saturating_duration_since(*decayed_at).as_secs() / INVALID_COSIGN_DECAY.as_secs();
+      *score = score.saturating_sub(u32::try_from(periods).unwrap_or(u32::MAX)) + 1;
+      *decayed_at = now;
+
+      if *score >= INVALID_COSIGN_BAN_SCORE {
+        self.scores.remove(&peer);
+        self.banned.insert(peer, now + INVALID_COSIGN_BAN);
+        banned.push(peer);
+      }
+    }
+    banned
+  }
+}
+
 #[async_trait]
 pub trait P2p: Send + Sync + Clone + fmt::Debug + TributaryP2p {
-  type Id: Send + Sync + Clone + Copy + fmt::Debug;
+  type Id: Send + Sync + Clone + Copy + Eq + Hash + fmt::Debug;
 
   async fn subscribe(&self, set: ExternalValidatorSet, genesis: [u8; 32]);
   async fn unsubscribe(&self, set: ExternalValidatorSet, genesis: [u8; 32]);
@@ -255,7 +711,7 @@ impl RrCodecTrait for RrCodec {
 
   async fn read_request<R: Send + Unpin + AsyncRead>(
     &mut self,
-    _: &Self::Protocol,
+    proto: &Self::Protocol,
     io: &mut R,
   ) -> io::Result<Vec<u8>> {
     let mut len = [0; 4];
@@ -269,6 +725,9 @@ impl RrCodecTrait for RrCodec {
     // the max message size should be sufficiently sane
     let mut buf = vec![0; len];
     io.read_exact(&mut buf).await?;
+    if *proto == LIBP2P_COMPRESSED_PROTOCOL {
+      buf = decompress(&buf, MAX_LIBP2P_REQRES_MESSAGE_SIZE)?;
+    }
     Ok(buf)
   }
   async fn read_response<R: Send + Unpin + AsyncRead>(
@@ -280,10 +739,13 @@ impl RrCodecTrait for RrCodec {
   }
   async fn write_request<W: Send + Unpin + AsyncWrite>(
     &mut self,
-    _: &Self::Protocol,
+    proto: &Self::Protocol,
     io: &mut W,
-    req: Vec<u8>,
+    mut req: Vec<u8>,
   ) -> io::Result<()> {
+    if *proto == LIBP2P_COMPRESSED_PROTOCOL {
+      req = compress(&req)?;
+    }
     io.write_all(
       &u32::try_from(req.len())
         .map_err(|_| io::Error::other("request length exceeded 2**32"))?
@@ -305,7 +767,12 @@ impl RrCodecTrait for RrCodec {
 #[derive(NetworkBehaviour)]
 struct Behavior {
   reqres: RrBehavior<RrCodec>,
-  gossipsub: GsBehavior,
+  gossipsub: GsBehavior<GossipCompression>,
+  // Used to listen via relays and to dial peers who are only reachable via relays
+  relay_client: relay::client::Behaviour,
+  // Upgrades relayed connections to direct connections via hole punching
+  dcutr: dcutr::Behaviour,
+  identify: identify::Behaviour,
 }
 
 #[allow(clippy::type_complexity)]
@@ -324,75 +791,111 @@ impl fmt::Debug for LibP2p {
 
 impl LibP2p {
   #[allow(clippy::new_without_default)]
-  pub fn new(serai: Arc<Serai>) -> Self {
+  pub fn new<D: Db>(mut db: D, serai: Arc<Serai>) -> Self {
     log::info!("creating a libp2p instance");
 
     let throwaway_key_pair = Keypair::generate_ed25519();
 
-    let behavior = Behavior {
-      reqres: { RrBehavior::new([("/coordinator", ProtocolSupport::Full)], RrConfig::default()) },
-      gossipsub: {
-        let heartbeat_interval = tributary::tendermint::LATENCY_TIME / 2;
-        let heartbeats_per_block =
-          usize::try_from(tributary::tendermint::TARGET_BLOCK_TIME / heartbeat_interval).unwrap();
-
-        use blake2::{Digest, Blake2s256};
-        let config = ConfigBuilder::default()
-          .heartbeat_interval(Duration::from_millis(heartbeat_interval.into()))
-          .history_length(heartbeats_per_block * 2)
-          .history_gossip(heartbeats_per_block)
-          .max_transmit_size(MAX_LIBP2P_GOSSIP_MESSAGE_SIZE)
-          // We send KeepAlive after 80s
-          .idle_timeout(Duration::from_secs(85))
-          .validation_mode(ValidationMode::Strict)
-          // Uses a content based message ID to avoid duplicates as much as possible
-          .message_id_fn(|msg| {
-            MessageId::new(&Blake2s256::digest([msg.topic.as_str().as_bytes(), &msg.data].concat()))
-          })
-          // Re-defines for fast ID to prevent needing to convert into a Message to run
-          // message_id_fn
-          // This function is valid for both
-          .fast_message_id_fn(|msg| {
-            FastMessageId::new(&Blake2s256::digest(
-              [msg.topic.as_str().as_bytes(), &msg.data].concat(),
-            ))
-          })
-          .build();
-        let mut gossipsub = GsBehavior::<IdentityTransform, AllowAllSubscriptionFilter>::new(
+    let reqres = {
+      RrBehavior::new(
+        [
+          (LIBP2P_COMPRESSED_PROTOCOL, ProtocolSupport::Full),
+          (LIBP2P_PROTOCOL, ProtocolSupport::Full),
+        ],
+        RrConfig::default(),
+      )
+    };
+    let gossipsub = {
+      let heartbeat_interval = tributary::tendermint::latency_time() / 2;
+      let heartbeats_per_block =
+        usize::try_from(tributary::tendermint::target_block_time() / heartbeat_interval).unwrap();
+
+      use blake2::{Digest, Blake2s256};
+      let config = ConfigBuilder::default()
+        .heartbeat_interval(Duration::from_millis(heartbeat_interval.into()))
+        .history_length(heartbeats_per_block * 2)
+        .history_gossip(heartbeats_per_block)
+        .max_transmit_size(MAX_LIBP2P_GOSSIP_MESSAGE_SIZE)
+        // We send KeepAlive after 80s
+        .idle_timeout(Duration::from_secs(85))
+        .validation_mode(ValidationMode::Strict)
+        // Uses a content based message ID to avoid duplicates as much as possible
+        .message_id_fn(|msg| {
+          MessageId::new(&Blake2s256::digest([msg.topic.as_str().as_bytes(), &msg.data].concat()))
+        })
+        // Re-defines for fast ID to prevent needing to convert into a Message to run
+        // message_id_fn
+        // This function is valid for both
+        .fast_message_id_fn(|msg| {
+          FastMessageId::new(&Blake2s256::digest(
+            [msg.topic.as_str().as_bytes(), &msg.data].concat(),
+          ))
+        })
+        .build();
+      let mut gossipsub =
+        GsBehavior::<GossipCompression, AllowAllSubscriptionFilter>::new_with_transform(
           MessageAuthenticity::Signed(throwaway_key_pair.clone()),
           config.unwrap(),
+          None,
+          GossipCompression,
         )
         .unwrap();
 
-        // Subscribe to the base topic
-        let topic = IdentTopic::new(LIBP2P_TOPIC);
-        gossipsub.subscribe(&topic).unwrap();
+      // Subscribe to the base topic
+      let topic = IdentTopic::new(LIBP2P_TOPIC);
+      gossipsub.subscribe(&compressed_topic(&topic)).unwrap();
+      gossipsub.subscribe(&topic).unwrap();
 
-        gossipsub
-      },
+      gossipsub
     };
 
     // Uses noise for authentication, yamux for multiplexing
+    // QUIC provides its own authentication (TLS) and multiplexing
     // TODO: Do we want to add a custom authentication protocol to only accept connections from
     // fellow validators? Doing so would reduce the potential for spam
-    // TODO: Relay client?
+    fn yamux_config() -> yamux::Config {
+      let mut config = yamux::Config::default();
+      // 1 MiB default + max message size
+      config.set_max_buffer_size((1024 * 1024) + MAX_LIBP2P_MESSAGE_SIZE);
+      // 256 KiB default + max message size
+      config.set_receive_window_size(((256 * 1024) + MAX_LIBP2P_MESSAGE_SIZE).try_into().unwrap());
+      config
+    }
     let mut swarm = SwarmBuilder::with_existing_identity(throwaway_key_pair)
       .with_tokio()
-      .with_tcp(TcpConfig::default().nodelay(true), noise::Config::new, || {
-        let mut config = yamux::Config::default();
-        // 1 MiB default + max message size
-        config.set_max_buffer_size((1024 * 1024) + MAX_LIBP2P_MESSAGE_SIZE);
-        // 256 KiB default + max message size
-        config
-          .set_receive_window_size(((256 * 1024) + MAX_LIBP2P_MESSAGE_SIZE).try_into().unwrap());
-        config
-      })
+      .with_tcp(TcpConfig::default().nodelay(true), noise::Config::new, yamux_config)
       .unwrap()
-      .with_behaviour(|_| behavior)
+      .with_quic()
+      // Relayed connections are authenticated and multiplexed the same as TCP connections
+      .with_relay_client(noise::Config::new, yamux_config)
+      .unwrap()
+      .with_behaviour(|key, relay_client| Behavior {
+        reqres,
+        gossipsub,
+        relay_client,
+        dcutr: dcutr::Behaviour::new(key.public().to_peer_id()),
+        identify: identify::Behaviour::new(identify::Config::new(
+          LIBP2P_IDENTIFY_PROTOCOL.to_string(),
+          key.public(),
+        )),
+      })
       .unwrap()
       .build();
-    const PORT: u16 = 30563; // 5132 ^ (('c' << 8) | 'o')
+    // Always listen over TCP so peers who don't use QUIC can still connect to us
     swarm.listen_on(format!("/ip4/0.0.0.0/tcp/{PORT}").parse().unwrap()).unwrap();
+    let quic = quic_enabled();
+    if quic {
+      log::info!(target: logging::P2P, port = PORT; "listening over QUIC");
+      swarm.listen_on(format!("/ip4/0.0.0.0/udp/{PORT}/quic-v1").parse().unwrap()).unwrap();
+    }
+    // Reserve a slot with each relay, so peers who can't dial us directly can reach us through it
+    // Once connected via a relay, we'll attempt to upgrade to a direct connection via DCUtR
+    for relay in relays().unwrap_or_else(|e| panic!("{e}")) {
+      log::info!(target: logging::P2P, relay:% = relay; "listening via relay");
+      if let Err(e) = swarm.listen_on(relay.clone().with(Protocol::P2pCircuit)) {
+        log::warn!(target: logging::P2P, relay:% = relay, err:? = e; "couldn't listen via relay");
+      }
+    }
 
     let (send_send, mut send_recv) = mpsc::unbounded_channel();
     let (broadcast_send, mut broadcast_recv) = mpsc::unbounded_channel();
@@ -403,104 +906,167 @@ impl LibP2p {
       IdentTopic::new(format!("{LIBP2P_TOPIC}-{}", hex::encode(set.encode())))
     }
 
-    // TODO: If a network has less than TARGET_PEERS, this will cause retries ad infinitum
-    const TARGET_PEERS: usize = 5;
+    // TODO: If a network has less than its target peers, this will cause retries ad infinitum
+    let target_peers = EXTERNAL_NETWORKS
+      .into_iter()
+      .map(|network| (network, target_peers(network)))
+      .collect::<HashMap<_, _>>();
 
     // The addrs we're currently dialing, and the networks associated with them
     let dialing_peers = Arc::new(RwLock::new(HashMap::new()));
     // The peers we're currently connected to, and the networks associated with them
     let connected_peers =
       Arc::new(RwLock::new(HashMap::<Multiaddr, HashSet<ExternalNetworkId>>::new()));
+    // The addrs which failed to connect, with how many times they've failed and when we may next
+    // dial them
+    let dial_backoff = Arc::new(RwLock::new(HashMap::<Multiaddr, (u32, Instant)>::new()));
+    // The addrs of peers shared with us by our peers, by network
+    let exchanged_peers =
+      Arc::new(RwLock::new(HashMap::<ExternalNetworkId, Vec<Multiaddr>>::new()));
 
     // Find and connect to peers
     let (connect_to_network_send, mut connect_to_network_recv) =
       tokio::sync::mpsc::unbounded_channel();
     let (to_dial_send, mut to_dial_recv) = tokio::sync::mpsc::unbounded_channel();
     tokio::spawn({
+      let db = db.clone();
+      let target_peers = target_peers.clone();
       let dialing_peers = dialing_peers.clone();
       let connected_peers = connected_peers.clone();
+      let dial_backoff = dial_backoff.clone();
+      let exchanged_peers = exchanged_peers.clone();
 
       let connect_to_network_send = connect_to_network_send.clone();
       async move {
-        loop {
-          let connect = |network: ExternalNetworkId, addr: Multiaddr| {
-            let dialing_peers = dialing_peers.clone();
-            let connected_peers = connected_peers.clone();
-            let to_dial_send = to_dial_send.clone();
-            let connect_to_network_send = connect_to_network_send.clone();
-            async move {
-              log::info!("found peer from substrate: {addr}");
-
-              let protocols = addr.iter().filter_map(|piece| match piece {
-                // Drop PeerIds from the Substrate P2p network
-                Protocol::P2p(_) => None,
-                // Use our own TCP port
-                Protocol::Tcp(_) => Some(Protocol::Tcp(PORT)),
-                other => Some(other),
-              });
-
-              let mut new_addr = Multiaddr::empty();
-              for protocol in protocols {
-                new_addr.push(protocol);
+        let connect = |network: ExternalNetworkId, addr: Multiaddr| {
+          let db = db.clone();
+          let target_peers = target_peers.clone();
+          let dialing_peers = dialing_peers.clone();
+          let connected_peers = connected_peers.clone();
+          let dial_backoff = dial_backoff.clone();
+          let to_dial_send = to_dial_send.clone();
+          let connect_to_network_send = connect_to_network_send.clone();
+          async move {
+            log::info!("found peer: {addr}");
+
+            let mut addr = dial_addr(&addr, quic);
+            // If this peer recently failed to connect over QUIC, which it may not support, fall
+            // back to TCP
+            if quic &&
+              dial_backoff
+                .read()
+                .await
+                .get(&addr)
+                .is_some_and(|(_, next_dial)| Instant::now() < *next_dial)
+            {
+              addr = dial_addr(&addr, false);
+            }
+            log::debug!(target: logging::P2P, addr:% = addr; "transformed found peer");
+
+            // If this addr recently failed to connect, don't dial it again yet
+            if dial_backoff
+              .read()
+              .await
+              .get(&addr)
+              .is_some_and(|(_, next_dial)| Instant::now() < *next_dial)
+            {
+              log::debug!(
+                target: logging::P2P,
+                addr:% = addr;
+                "not dialing peer which recently failed to connect"
+              );
+              return;
+            }
+
+            let (is_fresh_dial, nets) = {
+              let mut dialing_peers = dialing_peers.write().await;
+              let is_fresh_dial = !dialing_peers.contains_key(&addr);
+              if is_fresh_dial {
+                dialing_peers.insert(addr.clone(), HashSet::new());
               }
-              let addr = new_addr;
-              log::debug!("transformed found peer: {addr}");
+              // Associate this network with this peer
+              dialing_peers.get_mut(&addr).unwrap().insert(network);
+
+              let nets = dialing_peers.get(&addr).unwrap().clone();
+              (is_fresh_dial, nets)
+            };
 
-              let (is_fresh_dial, nets) = {
+            // Spawn a task to remove this peer from 'dialing' in sixty seconds, in case dialing
+            // fails
+            // This performs cleanup and bounds the size of the map to whatever growth occurs
+            // within a temporal window
+            tokio::spawn({
+              let mut db = db.clone();
+              let dialing_peers = dialing_peers.clone();
+              let connected_peers = connected_peers.clone();
+              let dial_backoff = dial_backoff.clone();
+              let connect_to_network_send = connect_to_network_send.clone();
+              let addr = addr.clone();
+              async move {
+                tokio::time::sleep(core::time::Duration::from_secs(60)).await;
                 let mut dialing_peers = dialing_peers.write().await;
-                let is_fresh_dial = !dialing_peers.contains_key(&addr);
-                if is_fresh_dial {
-                  dialing_peers.insert(addr.clone(), HashSet::new());
-                }
-                // Associate this network with this peer
-                dialing_peers.get_mut(&addr).unwrap().insert(network);
-
-                let nets = dialing_peers.get(&addr).unwrap().clone();
-                (is_fresh_dial, nets)
-              };
-
-              // Spawn a task to remove this peer from 'dialing' in sixty seconds, in case dialing
-              // fails
-              // This performs cleanup and bounds the size of the map to whatever growth occurs
-              // within a temporal window
-              tokio::spawn({
-                let dialing_peers = dialing_peers.clone();
-                let connected_peers = connected_peers.clone();
-                let connect_to_network_send = connect_to_network_send.clone();
-                let addr = addr.clone();
-                async move {
-                  tokio::time::sleep(core::time::Duration::from_secs(60)).await;
-                  let mut dialing_peers = dialing_peers.write().await;
-                  if let Some(expected_nets) = dialing_peers.remove(&addr) {
-                    log::debug!("removed addr from dialing upon timeout: {addr}");
-
-                    // TODO: De-duplicate this below instance
-                    // If we failed to dial and haven't gotten enough actual connections, retry
-                    let connected_peers = connected_peers.read().await;
-                    for net in expected_nets {
-                      let mut remaining_peers = 0;
-                      for nets in connected_peers.values() {
-                        if nets.contains(&net) {
-                          remaining_peers += 1;
-                        }
-                      }
-                      // If we do not, start connecting to this network again
-                      if remaining_peers < TARGET_PEERS {
-                        connect_to_network_send.send(net).expect(
-                          "couldn't send net to connect to due to disconnects (receiver dropped?)",
-                        );
+                if let Some(expected_nets) = dialing_peers.remove(&addr) {
+                  log::debug!(
+                    target: logging::P2P,
+                    addr:% = addr;
+                    "removed addr from dialing upon timeout"
+                  );
+
+                  // Lower this peer's score, if it's a peer we know
+                  {
+                    let mut txn = db.txn();
+                    for net in &expected_nets {
+                      KnownPeers::failed(&mut txn, *net, &addr);
+                    }
+                    txn.commit();
+                  }
+
+                  // Back off from dialing this addr again
+                  {
+                    let mut dial_backoff = dial_backoff.write().await;
+                    let failures = dial_backoff.get(&addr).map_or(0, |(failures, _)| *failures);
+                    let delay = DIAL_BACKOFF
+                      .saturating_mul(2u32.saturating_pow(failures))
+                      .min(MAX_DIAL_BACKOFF);
+                    dial_backoff.insert(addr.clone(), (failures + 1, Instant::now() + delay));
+                  }
+
+                  // TODO: De-duplicate this below instance
+                  // If we failed to dial and haven't gotten enough actual connections, retry
+                  let connected_peers = connected_peers.read().await;
+                  for net in expected_nets {
+                    let mut remaining_peers = 0;
+                    for nets in connected_peers.values() {
+                      if nets.contains(&net) {
+                        remaining_peers += 1;
                       }
                     }
+                    // If we do not, start connecting to this network again
+                    if remaining_peers < target_peers[&net] {
+                      connect_to_network_send.send(net).expect(
+                        "couldn't send net to connect to due to disconnects (receiver dropped?)",
+                      );
+                    }
                   }
                 }
-              });
-
-              if is_fresh_dial {
-                to_dial_send.send((addr, nets)).unwrap();
               }
+            });
+
+            if is_fresh_dial {
+              to_dial_send.send((addr, nets)).unwrap();
             }
-          };
+          }
+        };
 
+        // Eagerly dial the best peers we've previously connected to, as they're likely to still
+        // be online, instead of waiting to discover peers via the Serai node
+        for network in crate::networks::enabled_networks().iter().copied() {
+          for addr in KnownPeers::best_addrs(&db, network, (3 * target_peers[&network]) / 2) {
+            connect(network, addr).await;
+          }
+        }
+
+        loop {
           // TODO: We should also connect to random peers from random nets as needed for
           // cosigning
 
@@ -511,9 +1077,21 @@ impl LibP2p {
           }
           for network in connect_to_network_networks {
             if let Ok(mut nodes) = serai.p2p_validators(network.into()).await {
+              // Also connect to the peers we've previously connected to and the peers shared with
+              // us, so we don't solely rely on those the Serai node is aware of
+              for node in KnownPeers::addrs(&db, network)
+                .into_iter()
+                .chain(exchanged_peers.read().await.get(&network).cloned().unwrap_or(vec![]))
+              {
+                if !nodes.contains(&node) {
+                  nodes.push(node);
+                }
+              }
+
+              let target_peers = target_peers[&network];
               // If there's an insufficient amount of nodes known, connect to all yet add it
               // back and break
-              if nodes.len() < TARGET_PEERS {
+              if nodes.len() < target_peers {
                 log::warn!(
                   "insufficient amount of P2P nodes known for {:?}: {}",
                   network,
@@ -527,8 +1105,8 @@ impl LibP2p {
                 continue;
               }
 
-              // Randomly select up to 150% of the TARGET_PEERS
-              for _ in 0 .. ((3 * TARGET_PEERS) / 2) {
+              // Randomly select up to 150% of the target peers
+              for _ in 0 .. ((3 * target_peers) / 2) {
                 if !nodes.is_empty() {
                   let to_connect = nodes.swap_remove(
                     usize::try_from(OsRng.next_u64() % u64::try_from(nodes.len()).unwrap())
@@ -552,6 +1130,33 @@ impl LibP2p {
       async move {
         let connected_peers = connected_peers.clone();
 
+        // Our addresses via relays, which we share alongside our peers so peers may reach us
+        let mut relayed_addrs = HashSet::<Multiaddr>::new();
+
+        // Build the message sharing our peers, as sent to each peer we connect to
+        let peer_exchange = |connected_peers: &HashMap<Multiaddr, HashSet<ExternalNetworkId>>,
+                             relayed_addrs: &HashSet<Multiaddr>,
+                             our_nets: HashSet<ExternalNetworkId>| {
+          let mut peers = HashMap::<_, Vec<_>>::new();
+          for net in our_nets {
+            let peers = peers.entry(net).or_default();
+            for addr in relayed_addrs.iter().take(MAX_EXCHANGED_PEERS_PER_NETWORK) {
+              peers.push(addr.to_vec());
+            }
+          }
+          for (addr, nets) in connected_peers {
+            for net in nets {
+              let peers = peers.entry(*net).or_default();
+              if peers.len() < MAX_EXCHANGED_PEERS_PER_NETWORK {
+                peers.push(addr.to_vec());
+              }
+            }
+          }
+          let mut msg = ReqResMessageKind::Peers.serialize();
+          msg.extend(PeerExchange(peers.into_iter().collect()).encode());
+          msg
+        };
+
         let mut set_for_genesis = HashMap::new();
         loop {
           let time_since_last = Instant::now().duration_since(time_of_last_p2p_message);
@@ -567,11 +1172,15 @@ impl LibP2p {
                 log::info!("subscribing to p2p messages for {set:?}");
                 connect_to_network_send.send(set.network).unwrap();
                 set_for_genesis.insert(genesis, set);
-                swarm.behaviour_mut().gossipsub.subscribe(&topic).unwrap();
+                let gossipsub = &mut swarm.behaviour_mut().gossipsub;
+                gossipsub.subscribe(&compressed_topic(&topic)).unwrap();
+                gossipsub.subscribe(&topic).unwrap();
               } else {
                 log::info!("unsubscribing to p2p messages for {set:?}");
                 set_for_genesis.remove(&genesis);
-                swarm.behaviour_mut().gossipsub.unsubscribe(&topic).unwrap();
+                let gossipsub = &mut swarm.behaviour_mut().gossipsub;
+                gossipsub.unsubscribe(&compressed_topic(&topic)).unwrap();
+                gossipsub.unsubscribe(&topic).unwrap();
               }
             }
 
@@ -605,18 +1214,34 @@ impl LibP2p {
                   IdentTopic::new(LIBP2P_TOPIC)
                 };
 
-                match swarm.behaviour_mut().gossipsub.publish(topic, msg.clone()) {
-                  Err(PublishError::SigningError(e)) => {
-                    panic!("signing error when broadcasting: {e}")
-                  },
-                  Err(PublishError::InsufficientPeers) => {
-                    log::warn!("failed to send p2p message due to insufficient peers")
-                  }
-                  Err(PublishError::MessageTooLarge) => {
-                    panic!("tried to send a too large message: {}", hex::encode(msg))
+                // Publish the message compressed to peers which have upgraded, only publishing it
+                // uncompressed if there are peers who have yet to
+                let gossipsub = &mut swarm.behaviour_mut().gossipsub;
+                let legacy_peers = {
+                  let (topic, compressed) = (topic.hash(), compressed_topic(&topic).hash());
+                  gossipsub
+                    .all_peers()
+                    .any(|(_, topics)| topics.contains(&&topic) && !topics.contains(&&compressed))
+                };
+                let mut published = false;
+                for topic in [Some(compressed_topic(&topic)), legacy_peers.then_some(topic)] {
+                  let Some(topic) = topic else { continue };
+                  match gossipsub.publish(topic, msg.clone()) {
+                    Err(PublishError::SigningError(e)) => {
+                      panic!("signing error when broadcasting: {e}")
+                    },
+                    Err(PublishError::InsufficientPeers) => {}
+                    Err(PublishError::MessageTooLarge) => {
+                      panic!("tried to send a too large message: {}", hex::encode(&msg))
+                    }
+                    Err(PublishError::TransformFailed(e)) => {
+                      panic!("compressing a message failed: {e}")
+                    }
+                    Err(PublishError::Duplicate) | Ok(_) => published = true,
                   }
-                  Err(PublishError::TransformFailed(e)) => panic!("IdentityTransform failed: {e}"),
-                  Err(PublishError::Duplicate) | Ok(_) => {}
+                }
+                if !published {
+                  log::warn!("failed to send p2p message due to insufficient peers")
                 }
               }
             }
@@ -625,7 +1250,11 @@ impl LibP2p {
             event = swarm.next() => {
               match event {
                 Some(SwarmEvent::Dialing { connection_id, .. }) => {
-                  log::debug!("dialing to peer in connection ID {}", &connection_id);
+                  log::debug!(
+                    target: logging::P2P,
+                    connection:% = connection_id;
+                    "dialing to peer"
+                  );
                 }
                 Some(SwarmEvent::ConnectionEstablished {
                   peer_id,
@@ -642,33 +1271,74 @@ impl LibP2p {
                   let addr = endpoint.get_remote_address();
                   let nets = {
                     let mut dialing_peers = dialing_peers.write().await;
+                    // If this is a direct connection upgraded from a relayed connection, it
+                    // inherits the networks of the relayed connection
+                    let upgraded = || async {
+                      let connected_peers = connected_peers.read().await;
+                      connected_peers
+                        .iter()
+                        .find(|(addr, _)| relayed_peer(addr) == Some(peer_id))
+                        .map(|(_, nets)| nets.clone())
+                    };
                     if let Some(nets) = dialing_peers.remove(addr) {
                       nets
+                    } else if let Some(nets) = upgraded().await {
+                      nets
                     } else {
-                      log::debug!("connected to a peer who we didn't have within dialing");
+                      log::debug!(
+                        target: logging::P2P,
+                        addr:% = addr;
+                        "connected to a peer who we didn't have within dialing"
+                      );
                       HashSet::new()
                     }
                   };
-                  {
+                  dial_backoff.write().await.remove(addr);
+
+                  // Remember this peer so we can reconnect to it after rebooting
+                  if !nets.is_empty() {
+                    let mut txn = db.txn();
+                    for net in &nets {
+                      KnownPeers::seen(&mut txn, *net, addr);
+                    }
+                    txn.commit();
+                  }
+
+                  let exchange = {
                     let mut connected_peers = connected_peers.write().await;
                     connected_peers.insert(addr.clone(), nets);
+                    crate::metrics::set_p2p_peers(&connected_peers);
 
                     log::debug!(
-                      "connection established to peer {} in connection ID {}, connected peers: {}",
-                      &peer_id,
-                      &connection_id,
-                      connected_peers.len(),
+                      target: logging::P2P,
+                      peer:% = peer_id,
+                      connection:% = connection_id,
+                      connected_peers = connected_peers.len();
+                      "connection established"
                     );
-                  }
+
+                    let our_nets = set_for_genesis
+                      .values()
+                      .map(|set: &ExternalValidatorSet| set.network)
+                      .collect();
+                    peer_exchange(&connected_peers, &relayed_addrs, our_nets)
+                  };
+                  // Share our peers with them
+                  swarm.behaviour_mut().reqres.send_request(&peer_id, exchange);
                 }
                 Some(SwarmEvent::ConnectionClosed { peer_id, endpoint, .. }) => {
                   let mut connected_peers = connected_peers.write().await;
                   let Some(nets) = connected_peers.remove(endpoint.get_remote_address()) else {
-                    log::debug!("closed connection to peer which wasn't in connected_peers");
+                    log::debug!(
+                      target: logging::P2P,
+                      peer:% = peer_id;
+                      "closed connection to peer which wasn't in connected_peers"
+                    );
                     continue;
                   };
                   // Downgrade to a read lock
                   let connected_peers = connected_peers.downgrade();
+                  crate::metrics::set_p2p_peers(&connected_peers);
 
                   // For each net we lost a peer for, check if we still have sufficient peers
                   // overall
@@ -680,7 +1350,7 @@ impl LibP2p {
                       }
                     }
                     // If we do not, start connecting to this network again
-                    if remaining_peers < TARGET_PEERS {
+                    if remaining_peers < target_peers[&net] {
                       connect_to_network_send
                         .send(net)
                         .expect(
@@ -690,8 +1360,9 @@ impl LibP2p {
                   }
 
                   log::debug!(
-                    "connection with peer {peer_id} closed, connected peers: {}",
-                    connected_peers.len(),
+                    target: logging::P2P,
+                    peer:% = peer_id, connected_peers = connected_peers.len();
+                    "connection closed"
                   );
                 }
                 Some(SwarmEvent::Behaviour(BehaviorEvent::Reqres(
@@ -704,6 +1375,35 @@ impl LibP2p {
 
                   let mut msg_ref = message.as_slice();
                   let Some(kind) = ReqResMessageKind::read(&mut msg_ref) else { continue };
+
+                  // Handle peers shared with us here, as this doesn't need to be received by the
+                  // rest of the coordinator
+                  if kind == ReqResMessageKind::Peers {
+                    let Ok(PeerExchange(peers)) = PeerExchange::decode(&mut msg_ref) else {
+                      log::debug!(
+                        target: logging::P2P,
+                        peer:% = peer;
+                        "peer sent an invalidly serialized peer exchange"
+                      );
+                      continue;
+                    };
+                    let mut exchanged_peers = exchanged_peers.write().await;
+                    for (network, addrs) in peers {
+                      let exchanged_peers = exchanged_peers.entry(network).or_default();
+                      for addr in addrs.into_iter().take(MAX_EXCHANGED_PEERS_PER_NETWORK) {
+                        let Ok(addr) = Multiaddr::try_from(addr) else { continue };
+                        if !exchanged_peers.contains(&addr) {
+                          exchanged_peers.push(addr);
+                        }
+                      }
+                      // Bound the amount of exchanged peers, preferring the most recent
+                      let excess =
+                        exchanged_peers.len().saturating_sub(MAX_KNOWN_PEERS_PER_NETWORK);
+                      exchanged_peers.drain(.. excess);
+                    }
+                    continue;
+                  }
+
                   let message = Message {
                     sender: peer,
                     kind: P2pMessageKind::ReqRes(kind),
@@ -711,6 +1411,56 @@ impl LibP2p {
                   };
                   receive_send.send(message).expect("receive_send closed. are we shutting down?");
                 }
+                Some(SwarmEvent::NewListenAddr { address, .. }) => {
+                  if address.iter().any(|protocol| protocol == Protocol::P2pCircuit) {
+                    // Peers need our PeerId to dial us via the relay
+                    let address = if relayed_peer(&address).is_some() {
+                      address
+                    } else {
+                      address.with(Protocol::P2p(*swarm.local_peer_id()))
+                    };
+                    log::info!(target: logging::P2P, addr:% = address; "listening via relay");
+                    relayed_addrs.insert(address);
+                  }
+                }
+                Some(SwarmEvent::ExpiredListenAddr { address, .. }) => {
+                  if address.iter().any(|protocol| protocol == Protocol::P2pCircuit) {
+                    log::info!(
+                      target: logging::P2P,
+                      addr:% = address;
+                      "no longer listening via relay"
+                    );
+                    let address = address.to_vec();
+                    relayed_addrs.retain(|relayed| !relayed.to_vec().starts_with(&address));
+                  }
+                }
+                Some(SwarmEvent::Behaviour(BehaviorEvent::RelayClient(
+                  relay::client::Event::ReservationReqAccepted { relay_peer_id, renewal, .. },
+                ))) => {
+                  log::debug!(
+                    target: logging::P2P,
+                    relay:% = relay_peer_id, renewal = renewal;
+                    "relay accepted our reservation"
+                  );
+                }
+                Some(SwarmEvent::Behaviour(BehaviorEvent::Dcutr(
+                  dcutr::Event::DirectConnectionUpgradeSucceeded { remote_peer_id, connection_id },
+                ))) => {
+                  log::debug!(
+                    target: logging::P2P,
+                    peer:% = remote_peer_id, connection:% = connection_id;
+                    "upgraded relayed connection to a direct connection"
+                  );
+                }
+                Some(SwarmEvent::Behaviour(BehaviorEvent::Dcutr(
+                  dcutr::Event::DirectConnectionUpgradeFailed { remote_peer_id, error },
+                ))) => {
+                  log::debug!(
+                    target: logging::P2P,
+                    peer:% = remote_peer_id, err:% = error;
+                    "couldn't upgrade relayed connection to a direct connection"
+                  );
+                }
                 Some(SwarmEvent::Behaviour(BehaviorEvent::Gossipsub(
                   GsEvent::Message { propagation_source, message, .. },
                 ))) => {
@@ -733,11 +1483,15 @@ impl LibP2p {
                 addr_and_nets.expect("received address was None (sender dropped?)");
               // If we've already dialed and connected to this address, don't further dial them
               // Just associate these networks with them
-              if let Some(existing_nets) = connected_peers.write().await.get_mut(&addr) {
-                for net in nets {
-                  existing_nets.insert(net);
+              {
+                let mut connected_peers = connected_peers.write().await;
+                if let Some(existing_nets) = connected_peers.get_mut(&addr) {
+                  for net in nets {
+                    existing_nets.insert(net);
+                  }
+                  crate::metrics::set_p2p_peers(&connected_peers);
+                  continue;
                 }
-                continue;
               }
 
               if let Err(e) = swarm.dial(addr) {
@@ -875,6 +1629,7 @@ pub async fn heartbeat_tributaries_task<D: Db, P: P2p>(
 pub async fn handle_p2p_task<D: Db, P: P2p>(
   p2p: P,
   cosign_channel: mpsc::UnboundedSender<CosignedBlock>,
+  mut cosign_outcomes: mpsc::UnboundedReceiver<(CosignedBlock, CosignOutcome)>,
   mut tributary_event: broadcast::Receiver<TributaryEvent<D, P>>,
 ) {
   let channels = Arc::new(RwLock::new(HashMap::<_, mpsc::UnboundedSender<Message<P>>>::new()));
@@ -932,7 +1687,13 @@ pub async fn handle_p2p_task<D: Db, P: P2p>(
                         continue;
                       }
 
-                      log::debug!("received heartbeat with a recent timestamp");
+                      log::debug!(
+                        target: logging::P2P,
+                        network:? = spec_set.network,
+                        session = spec_set.session.0,
+                        peer:? = msg.sender;
+                        "received heartbeat with a recent timestamp"
+                      );
 
                       let reader = tributary.tributary.reader();
 
@@ -989,21 +1750,29 @@ pub async fn handle_p2p_task<D: Db, P: P2p>(
 
                         let res = tributary.tributary.sync_block(block, bc.commit).await;
                         log::debug!(
-                          "received block from {:?}, sync_block returned {}",
-                          msg.sender,
-                          res
+                          target: logging::P2P,
+                          network:? = spec_set.network,
+                          session = spec_set.session.0,
+                          peer:? = msg.sender,
+                          synced = res;
+                          "received block"
                         );
                       }
                     }
 
                     P2pMessageKind::Gossip(GossipMessageKind::Tributary(msg_genesis)) => {
                       assert_eq!(msg_genesis, genesis);
-                      log::trace!("handling message for tributary {:?}", spec_set);
+                      log::trace!(
+                        target: logging::P2P,
+                        network:? = spec_set.network, session = spec_set.session.0;
+                        "handling message for tributary"
+                      );
                       if tributary.tributary.handle_message(&msg.msg).await {
                         P2p::broadcast(&p2p, msg.kind, msg.msg).await;
                       }
                     }
 
+                    P2pMessageKind::ReqRes(ReqResMessageKind::Peers) |
                     P2pMessageKind::Gossip(GossipMessageKind::CosignedBlock) => unreachable!(),
                   }
                 }
@@ -1021,10 +1790,25 @@ pub async fn handle_p2p_task<D: Db, P: P2p>(
     }
   });
 
+  let mut cosign_scores = CosignPeerScores::new();
+  let mut cosign_outcomes_open = true;
   loop {
-    let msg = p2p.receive().await;
+    let msg = tokio::select! {
+      msg = p2p.receive() => msg,
+      outcome = cosign_outcomes.recv(), if cosign_outcomes_open => {
+        let Some((cosign, outcome)) = outcome else {
+          cosign_outcomes_open = false;
+          continue;
+        };
+        for peer in cosign_scores.outcome(&cosign, outcome, Instant::now()) {
+          log::warn!("banning {peer:?} from sending us cosigns due to invalid cosigns");
+        }
+        continue;
+      }
+    };
     match msg.kind {
-      P2pMessageKind::ReqRes(ReqResMessageKind::KeepAlive) => {}
+      // Peer exchanges are handled by the P2p implementation itself
+      P2pMessageKind::ReqRes(ReqResMessageKind::KeepAlive | ReqResMessageKind::Peers) => {}
       P2pMessageKind::Gossip(GossipMessageKind::Tributary(genesis)) |
       P2pMessageKind::ReqRes(
         ReqResMessageKind::Heartbeat(genesis) | ReqResMessageKind::Block(genesis),
@@ -1034,10 +1818,18 @@ pub async fn handle_p2p_task<D: Db, P: P2p>(
         }
       }
       P2pMessageKind::Gossip(GossipMessageKind::CosignedBlock) => {
+        let sender = msg.sender;
         let Ok(msg) = CosignedBlock::deserialize_reader(&mut msg.msg.as_slice()) else {
           log::error!("received CosignedBlock message with invalidly serialized contents");
           continue;
         };
+        if cosign_scores.banned(sender, Instant::now()) {
+          continue;
+        }
+        // Only attribute cosigns while the evaluator is reporting their outcomes
+        if cosign_outcomes_open {
+          cosign_scores.received(sender, msg);
+        }
         cosign_channel.send(msg).unwrap();
       }
     }
@@ -0,0 +1,254 @@
+use core::{str::FromStr, num::NonZeroUsize, time::Duration};
+
+use ciphersuite::group::GroupEncoding;
+
+use borsh::BorshDeserialize;
+
+use serai_db::{DbTxn, Db};
+
+use serai_client::{primitives::EXTERNAL_NETWORKS, Serai};
+
+use message_queue::{Service, client::MessageQueue};
+
+use libp2p::core::multiaddr::{Protocol, Multiaddr};
+
+use tokio::net::TcpStream;
+
+use crate::{
+  db::ActiveTributaryDb, tributary::TributarySpec, signer::key_from_env, p2p::relays,
+  networks::parse_networks, CoordinatorSigner,
+};
+
+// How long to wait for any individual check before considering it failed
+const CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/*
+  Preflight checks validating the coordinator's configuration, intended to be run by deployment
+  automation before starting the coordinator.
+
+  This checks the configuration is well-formed, the DB is writable and its contents are readable,
+  the validator key is valid, and the message-queue, Serai node, and P2P relays are reachable. It
+  doesn't join any Tributary, connect to the P2P network, or sign anything.
+*/
+
+struct Check {
+  name: &'static str,
+  result: Result<String, String>,
+}
+
+#[derive(Default)]
+struct Report(Vec<Check>);
+
+impl Report {
+  fn push(&mut self, name: &'static str, result: Result<String, String>) {
+    match &result {
+      Ok(detail) => log::info!("preflight check {name} passed: {detail}"),
+      Err(detail) => log::error!("preflight check {name} failed: {detail}"),
+    }
+    self.0.push(Check { name, result });
+  }
+
+  fn passed(&self) -> bool {
+    self.0.iter().all(|check| check.result.is_ok())
+  }
+
+  fn to_json(&self) -> serde_json::Value {
+    let checks = self
+      .0
+      .iter()
+      .map(|check| {
+        let (passed, detail) = match &check.result {
+          Ok(detail) => (true, detail),
+          Err(detail) => (false, detail),
+        };
+        serde_json::json!({ "name": check.name, "passed": passed, "detail": detail })
+      })
+      .collect::<Vec<_>>();
+    serde_json::json!({ "passed": self.passed(), "checks": checks })
+  }
+}
+
+fn check_config() -> Result<String, String> {
+  fn parse<T: FromStr>(var: &str, expected: &str) -> Result<(), String> {
+    match serai_env::var(var) {
+      Some(value) if value.parse::<T>().is_err() => Err(format!("{var} wasn't {expected}")),
+      _ => Ok(()),
+    }
+  }
+
+  if serai_env::var("SERAI_HOSTNAME").is_none() {
+    Err("SERAI_HOSTNAME wasn't provided".to_string())?;
+  }
+  parse::<u16>("METRICS_PORT", "a valid port")?;
+  parse::<u16>("ADMIN_PORT", "a valid port")?;
+  parse::<u16>("COSIGN_RELAY_PORT", "a valid port")?;
+  parse::<u16>("EVENT_FEED_PORT", "a valid port")?;
+  parse::<u16>("HEALTH_PORT", "a valid port")?;
+  parse::<usize>("HEALTH_MIN_P2P_PEERS", "a non-negative integer")?;
+  parse::<u64>("PROCESSOR_QUEUE_LIMIT", "a non-negative integer")?;
+  parse::<u64>("COSIGN_STALL_ALERT_SECONDS", "a non-negative integer")?;
+  parse::<NonZeroUsize>("TRIBUTARY_REPLAY_PARALLELISM", "a positive integer")?;
+  parse::<u64>("RETIRED_TRIBUTARY_PRUNE_AFTER_SECONDS", "a non-negative integer")?;
+  if let Some(block_time) = serai_env::var("TRIBUTARY_BLOCK_TIME") {
+    use ::tributary::tendermint::{MIN_BLOCK_TIME, MAX_BLOCK_TIME};
+    if !block_time.parse::<u32>().is_ok_and(|t| (MIN_BLOCK_TIME ..= MAX_BLOCK_TIME).contains(&t)) {
+      Err(format!(
+        "TRIBUTARY_BLOCK_TIME wasn't an integer within {MIN_BLOCK_TIME} ..= {MAX_BLOCK_TIME}"
+      ))?;
+    }
+  }
+  parse::<bool>("P2P_QUIC", "a boolean")?;
+  parse::<usize>("BITCOIN_P2P_TARGET_PEERS", "a non-negative integer")?;
+  parse::<usize>("ETHEREUM_P2P_TARGET_PEERS", "a non-negative integer")?;
+  parse::<usize>("MONERO_P2P_TARGET_PEERS", "a non-negative integer")?;
+  if let Some(networks) = serai_env::var("NETWORKS") {
+    parse_networks(&networks).map_err(|e| format!("NETWORKS wasn't valid: {e}"))?;
+  }
+  Ok("configuration is well-formed".to_string())
+}
+
+fn check_db<D: Db>(db: &mut D) -> Result<String, String> {
+  const KEY: &[u8] = b"coordinator_preflight";
+  const VALUE: &[u8] = b"writable";
+
+  let mut txn = db.txn();
+  txn.put(KEY, VALUE);
+  txn.commit();
+  if db.get(KEY).as_deref() != Some(VALUE) {
+    Err("value written to the DB wasn't read back".to_string())?;
+  }
+
+  let mut txn = db.txn();
+  txn.del(KEY);
+  txn.commit();
+  if db.get(KEY).is_some() {
+    Err("value deleted from the DB was still present".to_string())?;
+  }
+
+  Ok("DB is writable".to_string())
+}
+
+fn check_db_contents<D: Db>(db: &D) -> Result<String, String> {
+  // Read the active Tributaries without `ActiveTributaryDb::active_tributaries`, which panics on
+  // an invalid serialization
+  let bytes = ActiveTributaryDb::get(db).unwrap_or_default();
+  let mut bytes = bytes.as_slice();
+  let mut tributaries = 0;
+  while !bytes.is_empty() {
+    TributarySpec::deserialize_reader(&mut bytes)
+      .map_err(|e| format!("active Tributary #{tributaries} couldn't be read: {e}"))?;
+    tributaries += 1;
+  }
+  Ok(format!("{tributaries} active Tributaries were read"))
+}
+
+fn check_key() -> Result<String, String> {
+  let key = key_from_env()?;
+  Ok(format!("validator key is {}", hex::encode(key.public_key().to_bytes())))
+}
+
+async fn check_message_queue(processors: &MessageQueue) -> Result<String, String> {
+  match tokio::time::timeout(CHECK_TIMEOUT, processors.check_key()).await {
+    Ok(Some(true)) => Ok("message-queue accepted our key".to_string()),
+    Ok(Some(false)) => Err("message-queue rejected our key".to_string()),
+    Ok(None) => Err("couldn't connect to the message-queue".to_string()),
+    Err(_) => Err("timed out connecting to the message-queue".to_string()),
+  }
+}
+
+async fn connect_to_serai(report: &mut Report) -> Option<Serai> {
+  let hostname = serai_env::var("SERAI_HOSTNAME")?;
+  let serai = match tokio::time::timeout(
+    CHECK_TIMEOUT,
+    Serai::new(format!("http://{hostname}:9944")),
+  )
+  .await
+  {
+    Ok(Ok(serai)) => serai,
+    Ok(Err(e)) => {
+      report.push("serai_connection", Err(format!("couldn't connect to the Serai node: {e:?}")));
+      return None;
+    }
+    Err(_) => {
+      report.push("serai_connection", Err("timed out connecting to the Serai node".to_string()));
+      return None;
+    }
+  };
+  report.push("serai_connection", Ok("connected to the Serai node".to_string()));
+  Some(serai)
+}
+
+async fn check_serai(serai: &Serai) -> Result<String, String> {
+  match tokio::time::timeout(CHECK_TIMEOUT, serai.latest_finalized_block()).await {
+    Ok(Ok(block)) => Ok(format!("latest finalized block is #{}", block.number())),
+    Ok(Err(e)) => Err(format!("couldn't get the latest finalized block: {e:?}")),
+    Err(_) => Err("timed out getting the latest finalized block".to_string()),
+  }
+}
+
+async fn check_peers(serai: &Serai) -> Result<String, String> {
+  let mut found = vec![];
+  for network in EXTERNAL_NETWORKS {
+    match tokio::time::timeout(CHECK_TIMEOUT, serai.p2p_validators(network.into())).await {
+      Ok(Ok(peers)) => found.push(format!("{network:?}: {}", peers.len())),
+      Ok(Err(e)) => Err(format!("couldn't get the {network:?} validators' addresses: {e:?}"))?,
+      Err(_) => Err(format!("timed out getting the {network:?} validators' addresses"))?,
+    }
+  }
+  Ok(format!("validators' addresses known to the Serai node ({})", found.join(", ")))
+}
+
+async fn check_relay(relay: &Multiaddr) -> Result<(), String> {
+  let mut host = None;
+  let mut port = None;
+  for protocol in relay.iter() {
+    match protocol {
+      Protocol::Ip4(ip) => host = Some(ip.to_string()),
+      Protocol::Ip6(ip) => host = Some(ip.to_string()),
+      Protocol::Dns(name) | Protocol::Dns4(name) | Protocol::Dns6(name) => {
+        host = Some(name.to_string())
+      }
+      Protocol::Tcp(tcp_port) => port = Some(tcp_port),
+      _ => {}
+    }
+  }
+  let (Some(host), Some(port)) = (host, port) else {
+    Err(format!("relay {relay} isn't reachable over TCP"))?
+  };
+  match tokio::time::timeout(CHECK_TIMEOUT, TcpStream::connect((host.as_str(), port))).await {
+    Ok(Ok(_)) => Ok(()),
+    Ok(Err(e)) => Err(format!("couldn't connect to relay {relay}: {e}")),
+    Err(_) => Err(format!("timed out connecting to relay {relay}")),
+  }
+}
+
+async fn check_relays() -> Result<String, String> {
+  let relays = relays()?;
+  for relay in &relays {
+    check_relay(relay).await?;
+  }
+  Ok(format!("{} relays were reachable", relays.len()))
+}
+
+/// Run the preflight checks, printing a JSON report to stdout and exiting with a non-zero status
+/// if any check failed.
+pub async fn preflight<D: Db>(mut db: D) -> ! {
+  let mut report = Report::default();
+
+  report.push("config", check_config());
+  report.push("db", check_db(&mut db));
+  report.push("db_contents", check_db_contents(&db));
+  report.push("key", check_key());
+  report.push(
+    "message_queue",
+    check_message_queue(&MessageQueue::from_env(Service::Coordinator)).await,
+  );
+  if let Some(serai) = connect_to_serai(&mut report).await {
+    report.push("serai", check_serai(&serai).await);
+    report.push("p2p_peers", check_peers(&serai).await);
+  }
+  report.push("p2p_relays", check_relays().await);
+
+  println!("{}", report.to_json());
+  std::process::exit(if report.passed() { 0 } else { 1 });
+}
@@ -1,10 +1,27 @@
+use core::time::Duration;
 use std::sync::Arc;
 
+use tokio::time::sleep;
+
 use serai_client::primitives::ExternalNetworkId;
 use processor_messages::{ProcessorMessage, CoordinatorMessage};
 
 use message_queue::{Service, Metadata, client::MessageQueue};
 
+use crate::{logging, metrics};
+
+// The amount of unacknowledged messages which may be queued to a processor before we wait for it
+// to catch up, overridable via `PROCESSOR_QUEUE_LIMIT`
+const DEFAULT_QUEUE_LIMIT: u64 = 1000;
+// How long to wait before checking if a processor which fell behind has caught up
+const BACKPRESSURE_INTERVAL: Duration = Duration::from_secs(5);
+
+fn queue_limit() -> u64 {
+  serai_env::var("PROCESSOR_QUEUE_LIMIT").map_or(DEFAULT_QUEUE_LIMIT, |limit| {
+    limit.parse().expect("PROCESSOR_QUEUE_LIMIT wasn't a non-negative integer")
+  })
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Message {
   pub id: u64,
@@ -17,6 +34,11 @@ pub trait Processors: 'static + Send + Sync + Clone {
   async fn send(&self, network: ExternalNetworkId, msg: impl Send + Into<CoordinatorMessage>);
   async fn recv(&self, network: ExternalNetworkId) -> Message;
   async fn ack(&self, msg: Message);
+
+  /// Wait until the processor for this network has capacity for further messages.
+  ///
+  /// This applies backpressure, so we don't queue messages faster than the processor handles them.
+  async fn wait_for_capacity(&self, _network: ExternalNetworkId) {}
 }
 
 #[async_trait::async_trait]
@@ -27,6 +49,7 @@ impl Processors for Arc<MessageQueue> {
       Metadata { from: self.service, to: Service::Processor(network), intent: msg.intent() };
     let msg = borsh::to_vec(&msg).unwrap();
     self.queue(metadata, msg).await;
+    metrics::note_message_queued(network);
   }
   async fn recv(&self, network: ExternalNetworkId) -> Message {
     let msg = self.next(Service::Processor(network)).await;
@@ -43,4 +66,33 @@ impl Processors for Arc<MessageQueue> {
   async fn ack(&self, msg: Message) {
     MessageQueue::ack(self, Service::Processor(msg.network), msg.id).await
   }
+  async fn wait_for_capacity(&self, network: ExternalNetworkId) {
+    let limit = queue_limit();
+    let mut warned = false;
+    loop {
+      // If the message-queue couldn't be reached, don't block on it here
+      // Queueing the message will retry until the message-queue is reachable
+      let depth = self.depth(self.service, Service::Processor(network)).await;
+      metrics::set_message_queue_depth(network, true, depth);
+      let Some(depth) = depth else {
+        break;
+      };
+      if depth < limit {
+        break;
+      }
+
+      if !warned {
+        log::warn!(
+          target: logging::PROCESSOR,
+          network:? = network, depth = depth, limit = limit;
+          "processor fell behind, waiting for it to catch up"
+        );
+        warned = true;
+      }
+      sleep(BACKPRESSURE_INTERVAL).await;
+    }
+    if warned {
+      log::info!(target: logging::PROCESSOR, network:? = network; "processor caught up");
+    }
+  }
 }
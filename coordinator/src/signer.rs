@@ -0,0 +1,115 @@
+use core::ops::Deref;
+
+use zeroize::{Zeroize, Zeroizing};
+use rand_core::OsRng;
+
+use transcript::{Transcript, RecommendedTranscript};
+
+use ciphersuite::{
+  group::{
+    ff::{Field, PrimeField},
+    GroupEncoding,
+  },
+  Ciphersuite, Ristretto,
+};
+use schnorr::SchnorrSignature;
+
+use crate::tributary::Transaction;
+
+/// Load the coordinator's validator key from `SERAI_KEY`.
+pub(crate) fn key_from_env() -> Result<Zeroizing<<Ristretto as Ciphersuite>::F>, &'static str> {
+  let mut key_hex = serai_env::var("SERAI_KEY").ok_or("Serai key wasn't provided")?;
+  let key_vec = hex::decode(&key_hex).map_err(|_| "Serai key wasn't hex-encoded");
+  key_hex.zeroize();
+  let mut key_vec = key_vec?;
+  if key_vec.len() != 32 {
+    key_vec.zeroize();
+    Err("Serai key had an invalid length")?;
+  }
+  let mut key_bytes = [0; 32];
+  key_bytes.copy_from_slice(&key_vec);
+  key_vec.zeroize();
+  let key = Option::from(<Ristretto as Ciphersuite>::F::from_repr(key_bytes)).map(Zeroizing::new);
+  key_bytes.zeroize();
+  key.ok_or("Serai key wasn't a valid scalar")
+}
+
+/// The challenge for a signature over an attestation.
+///
+/// This is domain-separated from the signatures for Tributary transactions, so an attestation
+/// can't be reused as one.
+pub(crate) fn attestation_challenge(
+  signer: <Ristretto as Ciphersuite>::G,
+  nonce: <Ristretto as Ciphersuite>::G,
+  message: &[u8],
+) -> <Ristretto as Ciphersuite>::F {
+  let mut transcript = RecommendedTranscript::new(b"Coordinator Attestation");
+  transcript.append_message(b"signer", signer.to_bytes());
+  transcript.append_message(b"nonce", nonce.to_bytes());
+  transcript.append_message(b"message", message);
+  Ristretto::hash_to_F(b"Coordinator Attestation signature", &transcript.challenge(b"challenge"))
+}
+
+/// Verify a signature over an attestation.
+pub(crate) fn verify_attestation(
+  signer: <Ristretto as Ciphersuite>::G,
+  message: &[u8],
+  signature: &SchnorrSignature<Ristretto>,
+) -> bool {
+  signature.verify(signer, attestation_challenge(signer, signature.R, message))
+}
+
+/// A signer for the coordinator's validator key.
+///
+/// This is implemented for the in-memory key, yet may be implemented over an HSM or a remote
+/// signing service so the key never has to be present within the coordinator.
+pub trait CoordinatorSigner: Send + Sync {
+  /// The public key of this signer.
+  fn public_key(&self) -> <Ristretto as Ciphersuite>::G;
+
+  /// Sign a transaction for the Tributary with the specified genesis.
+  ///
+  /// This sets the transaction's signer, nonce, and signature.
+  fn sign_transaction(&self, genesis: [u8; 32], tx: &mut Transaction);
+
+  /// Sign a `SignCompleted` transaction, claiming the completion of a plan.
+  ///
+  /// This sets the transaction's first signer and signature.
+  fn sign_completion(&self, tx: &mut Transaction);
+
+  /// Sign an attestation to our local state, such as the composition of the cosigning sets.
+  fn sign_attestation(&self, message: &[u8]) -> SchnorrSignature<Ristretto>;
+}
+
+impl CoordinatorSigner for Zeroizing<<Ristretto as Ciphersuite>::F> {
+  fn public_key(&self) -> <Ristretto as Ciphersuite>::G {
+    Ristretto::generator() * self.deref().deref()
+  }
+
+  fn sign_transaction(&self, genesis: [u8; 32], tx: &mut Transaction) {
+    tx.sign(&mut OsRng, genesis, self);
+  }
+
+  fn sign_completion(&self, tx: &mut Transaction) {
+    let public_key = self.public_key();
+    let Transaction::SignCompleted { first_signer, signature, .. } = tx else {
+      panic!("sign_completion called on transaction which wasn't SignCompleted")
+    };
+    let r = Zeroizing::new(<Ristretto as Ciphersuite>::F::random(&mut OsRng));
+    *first_signer = public_key;
+    *signature = SchnorrSignature {
+      R: Ristretto::generator() * r.deref(),
+      s: <Ristretto as Ciphersuite>::F::ZERO,
+    };
+    let challenge = tx.sign_completed_challenge();
+    let Transaction::SignCompleted { signature, .. } = tx else { unreachable!() };
+    *signature = SchnorrSignature::sign(self, r, challenge);
+  }
+
+  fn sign_attestation(&self, message: &[u8]) -> SchnorrSignature<Ristretto> {
+    let r = Zeroizing::new(<Ristretto as Ciphersuite>::F::random(&mut OsRng));
+    let challenge =
+      attestation_challenge(self.public_key(), Ristretto::generator() * r.deref(), message);
+    SchnorrSignature::sign(self, r, challenge)
+  }
+}
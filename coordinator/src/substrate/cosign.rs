@@ -21,12 +21,12 @@ use borsh::{BorshSerialize, BorshDeserialize};
 use serai_client::{
   primitives::ExternalNetworkId,
   validator_sets::primitives::{ExternalValidatorSet, Session},
-  Serai, SeraiError,
+  Serai, SeraiError, TemporalSerai, SPEC_VERSION,
 };
 
 use serai_db::*;
 
-use crate::{Db, substrate::in_set, tributary::SeraiBlockNumber};
+use crate::{Db, substrate::in_set, tributary::SeraiBlockNumber, logging};
 
 // 5 minutes, expressed in blocks
 // TODO: Pull a constant for block time
@@ -84,6 +84,41 @@ impl CosignTransactions {
   ) {
     CosignTransactions::send(txn, set.network, &(set.session, number, hash))
   }
+
+  // Drop the pending cosigns for a retired set (and any sets prior), returning how many were
+  // dropped.
+  //
+  // Cosigns are appended in order of the blocks cosigned, and a set is only retired after its
+  // successor started cosigning, so the cosigns for retired sets will be at the front of the
+  // channel.
+  pub fn retire_set(txn: &mut impl DbTxn, set: ExternalValidatorSet) -> usize {
+    let mut dropped = 0;
+    loop {
+      let mut cursor = CosignTransactions::cursor(txn, set.network);
+      match CosignTransactions::peek(txn, set.network, &mut cursor) {
+        Some((session, _, _)) if session <= set.session => {
+          CosignTransactions::try_recv(txn, set.network).unwrap();
+          dropped += 1;
+        }
+        _ => break,
+      }
+    }
+    dropped
+  }
+}
+
+// Decode if a block has events, per the events of the runtime version this library is for
+async fn decode_block_events(serai: &TemporalSerai<'_>) -> Result<HasEvents, SeraiError> {
+  if !serai.validator_sets().key_gen_events().await?.is_empty() {
+    return Ok(HasEvents::KeyGen);
+  }
+
+  let has_no_events = serai.coins().burn_with_instruction_events().await?.is_empty() &&
+    serai.in_instructions().batch_events().await?.is_empty() &&
+    serai.validator_sets().new_set_events().await?.is_empty() &&
+    serai.validator_sets().set_retired_events().await?.is_empty();
+
+  Ok(if has_no_events { HasEvents::No } else { HasEvents::Yes })
 }
 
 async fn block_has_events(
@@ -102,16 +137,26 @@ async fn block_has_events(
           .hash(),
       );
 
-      if !serai.validator_sets().key_gen_events().await?.is_empty() {
-        return Ok(HasEvents::KeyGen);
-      }
-
-      let has_no_events = serai.coins().burn_with_instruction_events().await?.is_empty() &&
-        serai.in_instructions().batch_events().await?.is_empty() &&
-        serai.validator_sets().new_set_events().await?.is_empty() &&
-        serai.validator_sets().set_retired_events().await?.is_empty();
-
-      let has_events = if has_no_events { HasEvents::No } else { HasEvents::Yes };
+      let has_events = match decode_block_events(&serai).await {
+        Ok(has_events) => has_events,
+        // If we couldn't decode this block's events, check if it was produced by a distinct
+        // runtime version
+        Err(SeraiError::InvalidRuntime(e)) => {
+          let spec_version = serai.spec_version().await?;
+          if spec_version == SPEC_VERSION {
+            Err(SeraiError::InvalidRuntime(e))?;
+          }
+          // If so, we can't tell if this block set keys. Conservatively treat it as if it did, so
+          // it's cosigned regardless, instead of being unable to advance past it
+          log::warn!(
+            target: logging::COSIGN,
+            "couldn't decode events of block {block} (runtime version {spec_version}), {}",
+            "treating it as setting keys",
+          );
+          HasEvents::KeyGen
+        }
+        Err(e) => Err(e)?,
+      };
 
       BlockHasEventsCache::set(txn, block, &has_events);
       Ok(has_events)
@@ -141,7 +186,11 @@ async fn potentially_cosign_block(
   if (block_has_events == HasEvents::No) &&
     (LatestCosignedBlock::latest_cosigned_block(txn) == (block - 1))
   {
-    log::debug!("automatically co-signing next block ({block}) since it has no events");
+    log::debug!(
+      target: logging::COSIGN,
+      block = block;
+      "automatically cosigning block without events"
+    );
     LatestCosignedBlock::set(txn, &block);
   }
 
@@ -229,7 +278,11 @@ async fn advance_cosign_protocol_inner(
     for b in scan_start_block ..= window_end_inclusive.min(latest_number) {
       if block_has_events(&mut txn, serai, b).await? == HasEvents::Yes {
         skipped_block = Some(b);
-        log::debug!("skipping cosigning {b} due to proximity to prior cosign");
+        log::debug!(
+          target: logging::COSIGN,
+          block = b;
+          "skipping cosigning block due to proximity to prior cosign"
+        );
         IntendedCosign::set_skipped_cosign(&mut txn, b);
         break;
       }
@@ -284,8 +337,15 @@ async fn advance_cosign_protocol_inner(
           }
         };
 
-        log::debug!("{:?} will be cosigning {block}", set_with_keys.network);
-        cosigning.push((set_with_keys, in_set(key, &serai, set_with_keys.into()).await?.unwrap()));
+        log::debug!(
+          target: logging::COSIGN,
+          block = block, network:? = set_with_keys.network;
+          "network will be cosigning block"
+        );
+        // We only cosign for the networks we serve, as we don't run the Tributaries for the others
+        let in_set = in_set(key, &serai, set_with_keys.into()).await?.unwrap() &&
+          crate::networks::network_enabled(network);
+        cosigning.push((set_with_keys, in_set));
       }
 
       break;
@@ -301,12 +361,20 @@ async fn advance_cosign_protocol_inner(
     // If this block doesn't have cosigners, yet does have events, automatically mark it as
     // cosigned
     if cosigning.is_empty() {
-      log::debug!("{} had no cosigners available, marking as cosigned", number);
+      log::debug!(
+        target: logging::COSIGN,
+        block = number;
+        "block had no cosigners available, marking as cosigned"
+      );
       LatestCosignedBlock::set(&mut txn, &number);
     } else {
       for (set, in_set) in cosigning {
         if in_set {
-          log::debug!("cosigning {number} with {:?} {:?}", set.network, set.session);
+          log::debug!(
+            target: logging::COSIGN,
+            block = number, network:? = set.network, session = set.session.0;
+            "cosigning block"
+          );
           CosignTransactions::append_cosign(&mut txn, set, number, hash);
         }
       }
@@ -11,7 +11,7 @@ use ciphersuite::{group::GroupEncoding, Ciphersuite, Ristretto};
 use serai_client::{
   coins::CoinsEvent,
   in_instructions::InInstructionsEvent,
-  primitives::{BlockHash, ExternalNetworkId},
+  primitives::{BlockHash, NetworkId, ExternalNetworkId},
   validator_sets::{
     primitives::{ExternalValidatorSet, ValidatorSet},
     ValidatorSetsEvent,
@@ -29,6 +29,8 @@ use crate::{
   Db,
   processors::Processors,
   tributary::{TributarySpec, SeraiDkgCompleted},
+  event_feed::{self, Event},
+  logging,
 };
 
 mod db;
@@ -57,11 +59,21 @@ async fn handle_new_set<D: Db>(
   block: &Block,
   set: ExternalValidatorSet,
 ) -> Result<(), SeraiError> {
-  if in_set(key, &serai.as_of(block.hash()), set.into())
+  let in_set = in_set(key, &serai.as_of(block.hash()), set.into())
     .await?
-    .expect("NewSet for set which doesn't exist")
-  {
-    log::info!("present in set {:?}", set);
+    .expect("NewSet for set which doesn't exist");
+  if in_set && !crate::networks::network_enabled(set.network) {
+    log::warn!(
+      target: logging::SUBSTRATE,
+      network:? = set.network, session = set.session.0;
+      "present in new set for a network which isn't served"
+    );
+  } else if in_set {
+    log::info!(
+      target: logging::SUBSTRATE,
+      network:? = set.network, session = set.session.0;
+      "present in new set"
+    );
 
     let set_data = {
       let serai = serai.as_of(block.hash());
@@ -95,7 +107,11 @@ async fn handle_new_set<D: Db>(
 
     let spec = TributarySpec::new(block.hash(), time, set, set_data);
 
-    log::info!("creating new tributary for {:?}", spec.set());
+    log::info!(
+      target: logging::SUBSTRATE,
+      network:? = set.network, session = set.session.0, genesis:% = hex::encode(spec.genesis());
+      "creating new tributary"
+    );
 
     // Save it to the database now, not on the channel receiver's side, so this is safe against
     // reboots
@@ -107,7 +123,11 @@ async fn handle_new_set<D: Db>(
 
     new_tributary_spec.send(spec).unwrap();
   } else {
-    log::info!("not present in new set {:?}", set);
+    log::info!(
+      target: logging::SUBSTRATE,
+      network:? = set.network, session = set.session.0;
+      "not present in new set"
+    );
   }
 
   Ok(())
@@ -118,7 +138,7 @@ async fn handle_batch_and_burns<Pro: Processors>(
   processors: &Pro,
   serai: &Serai,
   block: &Block,
-) -> Result<(), SeraiError> {
+) -> Result<Vec<Event>, SeraiError> {
   // Track which networks had events with a Vec in ordr to preserve the insertion order
   // While that shouldn't be needed, ensuring order never hurts, and may enable design choices
   // with regards to Processor <-> Coordinator message passing
@@ -136,6 +156,7 @@ async fn handle_batch_and_burns<Pro: Processors>(
   let mut batch_block = HashMap::new();
   let mut batches = HashMap::<ExternalNetworkId, Vec<u32>>::new();
   let mut burns = HashMap::new();
+  let mut published = vec![];
 
   let serai = serai.as_of(block.hash());
   for batch in serai.in_instructions().batch_events().await? {
@@ -151,6 +172,14 @@ async fn handle_batch_and_burns<Pro: Processors>(
 
       // Add the batch included by this block
       batches.get_mut(&network).unwrap().push(id);
+      crate::epoch::note_batch(txn, network, id);
+      published.push(Event::BatchPublished {
+        network,
+        id,
+        network_block,
+        instructions_hash,
+        serai_block: block.number(),
+      });
     } else {
       panic!("Batch event wasn't Batch: {batch:?}");
     }
@@ -163,6 +192,7 @@ async fn handle_batch_and_burns<Pro: Processors>(
 
       // network_had_event should register an entry in burns
       burns.get_mut(&network).unwrap().push(instruction);
+      crate::epoch::note_burn(txn, network);
     } else {
       panic!("Burn event wasn't Burn: {burn:?}");
     }
@@ -171,6 +201,9 @@ async fn handle_batch_and_burns<Pro: Processors>(
   assert_eq!(HashSet::<&_>::from_iter(networks_with_event.iter()).len(), networks_with_event.len());
 
   for network in networks_with_event {
+    if !crate::networks::network_enabled(network) {
+      continue;
+    }
     let network_latest_finalized_block = if let Some(block) = batch_block.remove(&network) {
       block
     } else {
@@ -182,6 +215,7 @@ async fn handle_batch_and_burns<Pro: Processors>(
         .expect("network had a batch/burn yet never set a latest block")
     };
 
+    processors.wait_for_capacity(network).await;
     processors
       .send(
         network,
@@ -198,7 +232,7 @@ async fn handle_batch_and_burns<Pro: Processors>(
       .await;
   }
 
-  Ok(())
+  Ok(published)
 }
 
 // Handle a specific Substrate block, returning an error when it fails to get data
@@ -219,6 +253,9 @@ async fn handle_block<D: Db, Pro: Processors>(
   // Define an indexed event ID.
   let mut event_id = 0;
 
+  // The session of Serai's validator set which started with this block, if one did
+  let mut new_epoch = None;
+
   // If a new validator set was activated, create tributary/inform processor to do a DKG
   for new_set in serai.as_of(hash).validator_sets().new_set_events().await? {
     // Individually mark each event as handled so on reboot, we minimize duplicates
@@ -229,14 +266,24 @@ async fn handle_block<D: Db, Pro: Processors>(
       panic!("NewSet event wasn't NewSet: {new_set:?}");
     };
 
+    // A new set for Serai itself starts a new epoch, which is handled with the final event
+    if set.network == NetworkId::Serai {
+      new_epoch = Some(set.session);
+    }
+
     // We only coordinate/process external networks
     let Ok(set) = ExternalValidatorSet::try_from(set) else { continue };
     if HandledEvent::is_unhandled(db, hash, event_id) {
-      log::info!("found fresh new set event {:?}", new_set);
+      log::info!(
+        target: logging::SUBSTRATE,
+        block = block.number(), event:? = new_set;
+        "found fresh event"
+      );
       let mut txn = db.txn();
       handle_new_set::<D>(&mut txn, key, new_tributary_spec, serai, &block, set).await?;
       HandledEvent::handle_event(&mut txn, hash, event_id);
       txn.commit();
+      event_feed::emit(Event::SessionStarted { set, serai_block: block.number() });
     }
     event_id += 1;
   }
@@ -244,31 +291,38 @@ async fn handle_block<D: Db, Pro: Processors>(
   // If a key pair was confirmed, inform the processor
   for key_gen in serai.as_of(hash).validator_sets().key_gen_events().await? {
     if HandledEvent::is_unhandled(db, hash, event_id) {
-      log::info!("found fresh key gen event {:?}", key_gen);
+      log::info!(
+        target: logging::SUBSTRATE,
+        block = block.number(), event:? = key_gen;
+        "found fresh event"
+      );
       let ValidatorSetsEvent::KeyGen { set, key_pair } = key_gen else {
         panic!("KeyGen event wasn't KeyGen: {key_gen:?}");
       };
       let substrate_key = key_pair.0 .0;
-      processors
-        .send(
-          set.network,
-          processor_messages::substrate::CoordinatorMessage::ConfirmKeyPair {
-            context: SubstrateContext {
-              serai_time: block.time().unwrap() / 1000,
-              network_latest_finalized_block: serai
-                .as_of(block.hash())
-                .in_instructions()
-                .latest_block_for_network(set.network)
-                .await?
-                // The processor treats this as a magic value which will cause it to find a network
-                // block which has a time greater than or equal to the Serai time
-                .unwrap_or(BlockHash([0; 32])),
+      if crate::networks::network_enabled(set.network) {
+        processors.wait_for_capacity(set.network).await;
+        processors
+          .send(
+            set.network,
+            processor_messages::substrate::CoordinatorMessage::ConfirmKeyPair {
+              context: SubstrateContext {
+                serai_time: block.time().unwrap() / 1000,
+                network_latest_finalized_block: serai
+                  .as_of(block.hash())
+                  .in_instructions()
+                  .latest_block_for_network(set.network)
+                  .await?
+                  // The processor treats this as a magic value which will cause it to find a
+                  // network block which has a time greater than or equal to the Serai time
+                  .unwrap_or(BlockHash([0; 32])),
+              },
+              session: set.session,
+              key_pair,
             },
-            session: set.session,
-            key_pair,
-          },
-        )
-        .await;
+          )
+          .await;
+      }
 
       // TODO: If we were in the set, yet were removed, drop the tributary
 
@@ -276,6 +330,7 @@ async fn handle_block<D: Db, Pro: Processors>(
       SeraiDkgCompleted::set(&mut txn, set, &substrate_key);
       HandledEvent::handle_event(&mut txn, hash, event_id);
       txn.commit();
+      event_feed::emit(Event::KeyPairConfirmed { set, serai_block: block.number() });
     }
     event_id += 1;
   }
@@ -287,13 +342,19 @@ async fn handle_block<D: Db, Pro: Processors>(
 
     let Ok(set) = ExternalValidatorSet::try_from(set) else { continue };
     if HandledEvent::is_unhandled(db, hash, event_id) {
-      log::info!("found fresh accepted handover event {:?}", accepted_handover);
+      log::info!(
+        target: logging::SUBSTRATE,
+        block = block.number(), event:? = accepted_handover;
+        "found fresh event"
+      );
       // TODO: This isn't atomic with the event handling
       // Send a oneshot receiver so we can await the response?
       perform_slash_report.send(set).unwrap();
       let mut txn = db.txn();
+      crate::epoch::note_slash_report(&mut txn, set);
       HandledEvent::handle_event(&mut txn, hash, event_id);
       txn.commit();
+      event_feed::emit(Event::HandoverAccepted { set, serai_block: block.number() });
     }
     event_id += 1;
   }
@@ -305,12 +366,55 @@ async fn handle_block<D: Db, Pro: Processors>(
 
     let Ok(set) = ExternalValidatorSet::try_from(set) else { continue };
     if HandledEvent::is_unhandled(db, hash, event_id) {
-      log::info!("found fresh set retired event {:?}", retired_set);
+      log::info!(
+        target: logging::SUBSTRATE,
+        block = block.number(), event:? = retired_set;
+        "found fresh event"
+      );
       let mut txn = db.txn();
       crate::ActiveTributaryDb::retire_tributary(&mut txn, set);
+      let dropped = CosignTransactions::retire_set(&mut txn, set);
+      if dropped != 0 {
+        log::info!(
+          target: logging::SUBSTRATE,
+          network:? = set.network, session = set.session.0;
+          "dropped {dropped} pending cosigns for retired set"
+        );
+      }
       tributary_retired.send(set).unwrap();
       HandledEvent::handle_event(&mut txn, hash, event_id);
       txn.commit();
+      event_feed::emit(Event::SessionRetired { set, serai_block: block.number() });
+    }
+    event_id += 1;
+  }
+
+  // If a network was halted/resumed, inform its processor
+  for event in serai.as_of(hash).in_instructions().halt_and_resume_events().await? {
+    if HandledEvent::is_unhandled(db, hash, event_id) {
+      log::info!(
+        target: logging::SUBSTRATE,
+        block = block.number(), event:? = event;
+        "found fresh event"
+      );
+      let (network, msg) = match event {
+        InInstructionsEvent::Halt { network } => (
+          network,
+          processor_messages::substrate::CoordinatorMessage::Halt { block: block.number() },
+        ),
+        InInstructionsEvent::Resume { network } => (
+          network,
+          processor_messages::substrate::CoordinatorMessage::Resume { block: block.number() },
+        ),
+        _ => panic!("Halt/Resume event wasn't Halt/Resume: {event:?}"),
+      };
+      if crate::networks::network_enabled(network) {
+        processors.wait_for_capacity(network).await;
+        processors.send(network, msg).await;
+      }
+      let mut txn = db.txn();
+      HandledEvent::handle_event(&mut txn, hash, event_id);
+      txn.commit();
     }
     event_id += 1;
   }
@@ -320,9 +424,27 @@ async fn handle_block<D: Db, Pro: Processors>(
   // following events share data collection
   if HandledEvent::is_unhandled(db, hash, event_id) {
     let mut txn = db.txn();
-    handle_batch_and_burns(&mut txn, processors, serai, &block).await?;
+    let published = handle_batch_and_burns(&mut txn, processors, serai, &block).await?;
+    // This block's events are considered part of the epoch it ends, if it ends one
+    let ended_epoch = new_epoch.and_then(|session| {
+      let latest_cosigned_block = LatestCosignedBlock::latest_cosigned_block(&txn);
+      crate::epoch::start_epoch(&mut txn, session, block.number(), latest_cosigned_block)
+    });
     HandledEvent::handle_event(&mut txn, hash, event_id);
     txn.commit();
+
+    for event in published {
+      event_feed::emit(event);
+    }
+
+    if let Some(ended_epoch) = ended_epoch {
+      log::info!(
+        target: logging::SUBSTRATE,
+        session = ended_epoch.session.0;
+        "epoch ended"
+      );
+      crate::epoch::export_epoch_report(&*db, &ended_epoch);
+    }
   }
 
   Ok(())
@@ -358,7 +480,7 @@ async fn handle_new_blocks<D: Db, Pro: Processors>(
       .await?
       .expect("couldn't get block before the latest finalized block");
 
-    log::info!("handling substrate block {b}");
+    log::info!(target: logging::SUBSTRATE, block = b; "handling substrate block");
     handle_block(
       db,
       key,
@@ -376,7 +498,7 @@ async fn handle_new_blocks<D: Db, Pro: Processors>(
     NextBlock::set(&mut txn, next_block);
     txn.commit();
 
-    log::info!("handled substrate block {b}");
+    log::info!(target: logging::SUBSTRATE, block = b; "handled substrate block");
   }
 
   Ok(())
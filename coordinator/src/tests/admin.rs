@@ -0,0 +1,118 @@
+use rand_core::OsRng;
+
+use serai_client::primitives::ExternalNetworkId;
+
+use serai_db::{DbTxn, Db, MemDb};
+
+use crate::{
+  db::ActiveTributaryDb,
+  tributary::{Topic, AttemptDb, SeraiDkgCompleted, DkgLocallyCompleted},
+  p2p::CosignedBlock,
+  cosign_evaluator::{LatestCosign, CosignReader},
+  admin::{DkgStatus, dkg_status, sessions_report, cosign_report, log_report},
+  logging,
+  tests::tributary::{new_keys, new_spec},
+};
+
+#[test]
+fn dkg_status_test() {
+  let keys = new_keys(&mut OsRng);
+  let spec = new_spec(&mut OsRng, &keys);
+  let genesis = spec.genesis();
+
+  let mut db = MemDb::new();
+  assert_eq!(dkg_status(&db, &spec), DkgStatus::InProgress { attempt: 0 });
+
+  let mut txn = db.txn();
+  AttemptDb::start_next_attempt(&mut txn, genesis, Topic::Dkg);
+  txn.commit();
+  assert_eq!(dkg_status(&db, &spec), DkgStatus::InProgress { attempt: 1 });
+
+  let mut txn = db.txn();
+  DkgLocallyCompleted::set(&mut txn, genesis, &());
+  txn.commit();
+  assert_eq!(dkg_status(&db, &spec), DkgStatus::LocallyCompleted);
+
+  let mut txn = db.txn();
+  SeraiDkgCompleted::set(&mut txn, spec.set(), &[0xff; 32]);
+  txn.commit();
+  assert_eq!(dkg_status(&db, &spec), DkgStatus::Completed);
+}
+
+#[test]
+fn sessions_report_test() {
+  let keys = new_keys(&mut OsRng);
+  let spec = new_spec(&mut OsRng, &keys);
+
+  let mut db = MemDb::new();
+  assert_eq!(sessions_report(&db), "sessions:\n");
+
+  let mut txn = db.txn();
+  ActiveTributaryDb::add_participating_in_tributary(&mut txn, &spec);
+  txn.commit();
+
+  let report = sessions_report(&db);
+  let mut lines = report.lines().skip(1);
+  let line = lines.next().unwrap();
+  assert!(line.contains(&hex::encode(spec.genesis())));
+  assert!(line.contains(&format!("{} validators", keys.len())));
+  assert!(line.ends_with("DKG in progress (attempt 0)"));
+  assert!(lines.next().is_none());
+
+  // Retired Tributaries shouldn't be reported
+  let mut txn = db.txn();
+  ActiveTributaryDb::retire_tributary(&mut txn, spec.set());
+  txn.commit();
+  assert_eq!(sessions_report(&db), "sessions:\n");
+}
+
+#[tokio::test]
+async fn cosign_report_test() {
+  let mut db = MemDb::new();
+  let cosign = CosignedBlock {
+    network: ExternalNetworkId::Bitcoin,
+    session_start: Some([0; 32]),
+    block_number: 5,
+    block: [0xaa; 32],
+    signature: [0; 64],
+  };
+  let mut txn = db.txn();
+  LatestCosign::set(&mut txn, cosign.network, &cosign);
+  txn.commit();
+
+  let report = cosign_report(&db, &CosignReader::new(db.clone()), None).await;
+  assert!(
+    report.contains(&format!("latest cosign by Bitcoin: block 5 ({})", hex::encode([0xaa; 32])))
+  );
+  // The progress isn't known until the evaluator fetches the stakes
+  assert!(report.contains("cosign progress unknown as stakes have yet to be fetched"));
+}
+
+#[test]
+fn log_report_test() {
+  let enabled = |target, level| {
+    serai_telemetry::logging::enabled(&log::Metadata::builder().target(target).level(level).build())
+  };
+
+  assert_eq!(log_report(Some("filter=info")), "log filter: info\n");
+  assert!(enabled(logging::P2P, log::Level::Info));
+  assert!(!enabled(logging::P2P, log::Level::Debug));
+
+  // Only the filter parameter should be used
+  let filter = format!("{}=debug,{}=trace", logging::P2P, logging::COSIGN);
+  assert_eq!(
+    log_report(Some(&format!("other=1&filter={filter}"))),
+    format!("log filter: {filter}\n")
+  );
+  assert!(enabled(logging::P2P, log::Level::Debug));
+  assert!(!enabled(logging::P2P, log::Level::Trace));
+  assert!(enabled(logging::COSIGN, log::Level::Trace));
+  assert!(!enabled(logging::TRIBUTARY, log::Level::Error));
+  assert_eq!(serai_telemetry::logging::filter().unwrap(), filter);
+
+  // Reporting the filter shouldn't change it
+  assert_eq!(log_report(None), format!("log filter: {filter}\n"));
+  assert_eq!(log_report(Some("filter=")), format!("log filter: {filter}\n"));
+
+  serai_telemetry::logging::set_filter("info");
+}
@@ -0,0 +1,32 @@
+use crate::audit::{GENESIS, entry, verify};
+
+#[test]
+fn audit_log() {
+  let mut log = vec![];
+  let mut prev = GENESIS;
+  for i in 0 .. 3 {
+    let (line, hash) = entry(i, prev, "batch", serde_json::json!({ "id": i }));
+    log.extend(line);
+    prev = hash;
+  }
+  assert_eq!(verify(log.as_slice()).unwrap(), (3, prev));
+  assert_eq!(verify([].as_slice()).unwrap(), (0, GENESIS));
+
+  let lines = log.split_inclusive(|b| *b == b'\n').collect::<Vec<_>>();
+
+  // Removing an entry should be detected
+  let removed = [lines[0], lines[2]].concat();
+  assert!(verify(removed.as_slice()).is_err());
+
+  // Reordering entries should be detected
+  let reordered = [lines[1], lines[0], lines[2]].concat();
+  assert!(verify(reordered.as_slice()).is_err());
+
+  // Modifying an entry should be detected
+  let modified = String::from_utf8(log.clone()).unwrap().replacen("\"id\":1", "\"id\":4", 1);
+  assert!(verify(modified.as_bytes()).is_err());
+
+  // Truncating the log from the end can't be detected, which is why the last hash is returned
+  let truncated = [lines[0], lines[1]].concat();
+  assert_eq!(verify(truncated.as_slice()).unwrap().0, 2);
+}
@@ -0,0 +1,464 @@
+use core::time::Duration;
+use std::collections::HashMap;
+
+use zeroize::Zeroizing;
+use rand_core::{RngCore, OsRng};
+
+use ciphersuite::{
+  group::{ff::Field, GroupEncoding},
+  Ciphersuite, Ristretto,
+};
+
+use sp_application_crypto::{sr25519, Pair as PairTrait};
+
+use serai_client::{
+  primitives::ExternalNetworkId,
+  validator_sets::primitives::{ExternalValidatorSet, Session},
+};
+
+use serai_db::{DbTxn, Db, MemDb};
+
+use processor_messages::coordinator::CosignPayload;
+
+use crate::{
+  p2p::CosignedBlock,
+  substrate::{IntendedCosign, LatestCosignedBlock},
+  clock::{Instant, TokioClock},
+  cosign_evaluator::{
+    LatestCosign, PendingCosigns, CosigningCompositions, LatestCosigningComposition,
+    CompositionCosigns, CosignArchive, CosigningSet, CosigningComposition, CosignVerificationError,
+    StallTracker, CosignReader, verify_cosigned_block, retire_composition, rebroadcast_interval,
+    rebroadcast_cosigns, block_cosign_status, CompositionAttestation, CosignOutcome, buffer_cosign,
+  },
+  tests::LocalP2p,
+  CoordinatorSigner,
+};
+
+fn session_start(network: ExternalNetworkId) -> [u8; 32] {
+  [u8::try_from(network as usize).unwrap(); 32]
+}
+
+fn cosign_with_session_start(
+  pair: &sr25519::Pair,
+  network: ExternalNetworkId,
+  session_start: Option<[u8; 32]>,
+  number: u64,
+  block: [u8; 32],
+) -> CosignedBlock {
+  let payload = CosignPayload { session_start, block_number: number, block };
+  CosignedBlock {
+    network,
+    session_start,
+    block_number: number,
+    block,
+    signature: pair.sign(&payload.message()).0,
+  }
+}
+
+fn cosign(
+  pair: &sr25519::Pair,
+  network: ExternalNetworkId,
+  number: u64,
+  block: [u8; 32],
+) -> CosignedBlock {
+  cosign_with_session_start(pair, network, Some(session_start(network)), number, block)
+}
+
+#[test]
+fn verify_cosigned_block_test() {
+  let networks =
+    [ExternalNetworkId::Bitcoin, ExternalNetworkId::Ethereum, ExternalNetworkId::Monero];
+  let pairs = networks.map(|_| sr25519::Pair::generate().0);
+  let stakes = [50, 30, 20];
+  let composition = CosigningComposition {
+    id: 0,
+    block: [0; 32],
+    sets: (0 .. networks.len())
+      .map(|i| CosigningSet {
+        set: ExternalValidatorSet { network: networks[i], session: Session(0) },
+        key: pairs[i].public().0,
+        session_start: session_start(networks[i]),
+        stake: stakes[i],
+      })
+      .collect(),
+    total_stake: stakes.iter().sum(),
+  };
+
+  let number = OsRng.next_u64() >> 1;
+  let mut block = [0; 32];
+  OsRng.fill_bytes(&mut block);
+  let cosigns = (0 .. networks.len())
+    .map(|i| cosign(&pairs[i], networks[i], number, block))
+    .collect::<Vec<_>>();
+
+  // All sets cosigning is sufficient
+  assert_eq!(verify_cosigned_block(&composition, number, block, &cosigns, false), Ok(100));
+  // As is more than two thirds of the stake
+  assert_eq!(
+    verify_cosigned_block(&composition, number, block, &[cosigns[0], cosigns[2]], false),
+    Ok(70)
+  );
+  // Half of the stake isn't
+  assert_eq!(
+    verify_cosigned_block(&composition, number, block, &cosigns[1 ..], false),
+    Err(CosignVerificationError::InsufficientStake { cosigned_stake: 50, needed_stake: 67 })
+  );
+
+  // A set can't have its stake counted twice
+  assert_eq!(
+    verify_cosigned_block(&composition, number, block, &[cosigns[0], cosigns[0]], false),
+    Err(CosignVerificationError::DuplicateNetwork(ExternalNetworkId::Bitcoin))
+  );
+
+  // Cosigns must be for the block being verified
+  assert_eq!(
+    verify_cosigned_block(&composition, number + 1, block, &cosigns, false),
+    Err(CosignVerificationError::DistinctBlock(ExternalNetworkId::Bitcoin))
+  );
+  let distinct = cosign(&pairs[0], networks[0], number, [0xff; 32]);
+  assert_eq!(
+    verify_cosigned_block(&composition, number, block, &[distinct, cosigns[1]], false),
+    Err(CosignVerificationError::DistinctBlock(ExternalNetworkId::Bitcoin))
+  );
+
+  // Cosigns must be signed by the set's key
+  let forged = cosign(&pairs[1], networks[0], number, block);
+  assert_eq!(
+    verify_cosigned_block(&composition, number, block, &[forged, cosigns[1]], false),
+    Err(CosignVerificationError::InvalidSignature(ExternalNetworkId::Bitcoin))
+  );
+
+  // Cosigns must be bound to the set's session
+  let replayed = cosign_with_session_start(&pairs[0], networks[0], Some([0xff; 32]), number, block);
+  assert_eq!(
+    verify_cosigned_block(&composition, number, block, &[replayed, cosigns[1]], false),
+    Err(CosignVerificationError::DistinctSession(ExternalNetworkId::Bitcoin))
+  );
+  // And the session they're bound to is signed for
+  let mut rebound = cosigns[0];
+  rebound.session_start = None;
+  assert_eq!(
+    verify_cosigned_block(&composition, number, block, &[rebound, cosigns[1]], false),
+    Err(CosignVerificationError::InvalidSignature(ExternalNetworkId::Bitcoin))
+  );
+  // Legacy cosigns, which aren't bound to a session, are refused, as they may be replayed from a
+  // distinct chain
+  let legacy = cosign_with_session_start(&pairs[0], networks[0], None, number, block);
+  assert_eq!(
+    verify_cosigned_block(&composition, number, block, &[legacy, cosigns[1]], false),
+    Err(CosignVerificationError::UnboundSession(ExternalNetworkId::Bitcoin))
+  );
+  // Unless explicitly accepted
+  assert_eq!(
+    verify_cosigned_block(&composition, number, block, &[legacy, cosigns[1]], true),
+    Ok(80)
+  );
+
+  // Cosigns must be by a set within the composition
+  let mut partial = composition.clone();
+  partial.sets.pop();
+  assert_eq!(
+    verify_cosigned_block(&partial, number, block, &cosigns, false),
+    Err(CosignVerificationError::UnknownNetwork(ExternalNetworkId::Monero))
+  );
+}
+
+#[test]
+fn retire_composition_test() {
+  let pairs = [sr25519::Pair::generate().0, sr25519::Pair::generate().0];
+  let composition = |session, networks: &[ExternalNetworkId]| CosigningComposition {
+    id: session,
+    block: [0; 32],
+    sets: networks
+      .iter()
+      .zip(&pairs)
+      .map(|(network, pair)| CosigningSet {
+        set: ExternalValidatorSet { network: *network, session: Session(session) },
+        key: pair.public().0,
+        session_start: session_start(*network),
+        stake: 1,
+      })
+      .collect(),
+    total_stake: u64::try_from(networks.len()).unwrap(),
+  };
+  let prior = composition(0, &[ExternalNetworkId::Bitcoin, ExternalNetworkId::Ethereum]);
+
+  let mut db = MemDb::new();
+  let mut txn = db.txn();
+  for (network, pair) in
+    [ExternalNetworkId::Bitcoin, ExternalNetworkId::Ethereum].iter().zip(&pairs)
+  {
+    let cosign = cosign(pair, *network, 1, [0; 32]);
+    LatestCosign::set(&mut txn, *network, &cosign);
+    PendingCosigns::set(&mut txn, *network, &vec![cosign]);
+  }
+  txn.commit();
+
+  // A set rotating within a network doesn't retire the network's working state
+  let mut txn = db.txn();
+  let rotated = composition(1, &[ExternalNetworkId::Bitcoin, ExternalNetworkId::Ethereum]);
+  assert!(retire_composition(&mut txn, &prior, &rotated).is_empty());
+  txn.commit();
+  assert!(LatestCosign::get(&db, ExternalNetworkId::Ethereum).is_some());
+
+  // A network leaving the composition does
+  let mut txn = db.txn();
+  let current = composition(2, &[ExternalNetworkId::Bitcoin]);
+  assert_eq!(retire_composition(&mut txn, &rotated, &current), vec![ExternalNetworkId::Ethereum]);
+  txn.commit();
+  assert!(LatestCosign::get(&db, ExternalNetworkId::Bitcoin).is_some());
+  assert!(PendingCosigns::get(&db, ExternalNetworkId::Bitcoin).is_some());
+  assert!(LatestCosign::get(&db, ExternalNetworkId::Ethereum).is_none());
+  assert!(PendingCosigns::get(&db, ExternalNetworkId::Ethereum).is_none());
+
+  // The latest cosigns within each retired composition are retained
+  assert_eq!(CompositionCosigns::get(&db, 0).unwrap().len(), 2);
+  assert_eq!(CompositionCosigns::get(&db, 1).unwrap().len(), 2);
+
+  // And exported alongside the compositions
+  let mut txn = db.txn();
+  for composition in [&prior, &rotated, &current] {
+    CosigningCompositions::set(&mut txn, composition.id, composition);
+  }
+  LatestCosigningComposition::set(&mut txn, &current.id);
+  txn.commit();
+  let archive = CosignArchive::export(&db);
+  assert_eq!(archive.compositions.len(), 3);
+  assert_eq!(archive.compositions[0].composition, prior);
+  assert_eq!(archive.compositions[1].cosigns.len(), 2);
+  assert_eq!(archive.compositions[2].composition, current);
+  assert_eq!(
+    archive.compositions[2].cosigns,
+    vec![LatestCosign::get(&db, ExternalNetworkId::Bitcoin).unwrap()]
+  );
+}
+
+#[test]
+fn buffer_cosign_test() {
+  let network = ExternalNetworkId::Bitcoin;
+  let pair = sr25519::Pair::generate().0;
+  let composition = CosigningComposition {
+    id: 0,
+    block: [0; 32],
+    sets: vec![CosigningSet {
+      set: ExternalValidatorSet { network, session: Session(0) },
+      key: pair.public().0,
+      session_start: session_start(network),
+      stake: 1,
+    }],
+    total_stake: 1,
+  };
+  let buffer =
+    |pending: &mut Vec<_>, cosign| buffer_cosign(pending, Some(&composition), 100, false, cosign);
+
+  // Cosigns which aren't valid under the current composition aren't buffered
+  let mut pending = vec![];
+  let imposter = sr25519::Pair::generate().0;
+  assert_eq!(
+    buffer(&mut pending, cosign(&imposter, network, 101, [1; 32])),
+    CosignOutcome::Invalid
+  );
+  assert_eq!(
+    buffer(&mut pending, cosign(&pair, ExternalNetworkId::Ethereum, 101, [1; 32])),
+    CosignOutcome::Invalid
+  );
+  assert_eq!(
+    buffer(&mut pending, cosign_with_session_start(&pair, network, Some([0xff; 32]), 101, [1; 32])),
+    CosignOutcome::Invalid
+  );
+  assert_eq!(
+    buffer(&mut pending, cosign_with_session_start(&pair, network, None, 101, [1; 32])),
+    CosignOutcome::Invalid
+  );
+  assert_eq!(
+    buffer_cosign(&mut pending, None, 100, false, cosign(&pair, network, 101, [1; 32])),
+    CosignOutcome::Invalid
+  );
+  assert!(pending.is_empty());
+
+  // Valid cosigns are buffered once
+  let valid = cosign(&pair, network, 102, [2; 32]);
+  assert_eq!(buffer(&mut pending, valid), CosignOutcome::Buffered);
+  assert_eq!(buffer(&mut pending, valid), CosignOutcome::Buffered);
+  assert_eq!(pending, vec![valid]);
+
+  // Unless they're too far ahead
+  assert_eq!(
+    buffer(&mut pending, cosign(&pair, network, 1_000, [3; 32])),
+    CosignOutcome::TooFarAhead
+  );
+  assert_eq!(pending, vec![valid]);
+
+  // When full, the cosigns for the lowest blocks are kept
+  for i in 0 .. 16 {
+    let number = 120 - i;
+    buffer(&mut pending, cosign(&pair, network, number, [u8::try_from(i).unwrap(); 32]));
+  }
+  assert_eq!(pending.len(), 8);
+  assert_eq!(pending.iter().map(|cosign| cosign.block_number).min(), Some(102));
+  assert!(pending.windows(2).all(|cosigns| cosigns[0].block_number <= cosigns[1].block_number));
+}
+
+#[test]
+fn rebroadcast_interval_test() {
+  let mut db = MemDb::new();
+  // Until we've determined a block to cosign, cosigns are rebroadcast at the default interval
+  let default = rebroadcast_interval(&db).unwrap();
+
+  // Cosigns are rebroadcast more frequently while the block we intend to cosign isn't cosigned
+  let mut txn = db.txn();
+  IntendedCosign::set_intended_cosign(&mut txn, 5);
+  LatestCosignedBlock::set(&mut txn, &4);
+  txn.commit();
+  assert!(rebroadcast_interval(&db).unwrap() < default);
+
+  // Once it's cosigned, cosigns aren't rebroadcast
+  let mut txn = db.txn();
+  LatestCosignedBlock::set(&mut txn, &5);
+  txn.commit();
+  assert_eq!(rebroadcast_interval(&db), None);
+
+  // Until we intend to cosign another block
+  let mut txn = db.txn();
+  IntendedCosign::set_intended_cosign(&mut txn, 10);
+  txn.commit();
+  assert!(rebroadcast_interval(&db).unwrap() < default);
+}
+
+// Take the rebroadcasts received by the peer, clearing the deduplication so further rebroadcasts
+// of the same cosigns are received
+async fn take_rebroadcasts(peer: &LocalP2p) -> usize {
+  let mut lock = peer.1.write().await;
+  lock.0.clear();
+  lock.1[1].drain(..).count()
+}
+
+#[tokio::test(start_paused = true)]
+async fn rebroadcast_cosigns_test() {
+  let pair = sr25519::Pair::generate().0;
+  let mut db = MemDb::new();
+  let mut txn = db.txn();
+  let latest = cosign(&pair, ExternalNetworkId::Bitcoin, 1, [0; 32]);
+  LatestCosign::set(&mut txn, ExternalNetworkId::Bitcoin, &latest);
+  txn.commit();
+  let interval = rebroadcast_interval(&db).unwrap();
+
+  let mut p2ps = LocalP2p::new(2);
+  let peer = p2ps.pop().unwrap();
+  tokio::spawn(rebroadcast_cosigns(CosignReader::new(db.clone()), p2ps.pop().unwrap(), TokioClock));
+
+  // The cosigns are immediately broadcast
+  tokio::time::sleep(Duration::from_secs(1)).await;
+  assert_eq!(take_rebroadcasts(&peer).await, 1);
+
+  // They aren't rebroadcast until the interval has passed
+  tokio::time::sleep(interval - Duration::from_secs(2)).await;
+  assert_eq!(take_rebroadcasts(&peer).await, 0);
+  tokio::time::sleep(Duration::from_secs(10)).await;
+  assert_eq!(take_rebroadcasts(&peer).await, 1);
+
+  // Once every block we intended to cosign has been cosigned, they're no longer rebroadcast
+  let mut txn = db.txn();
+  IntendedCosign::set_intended_cosign(&mut txn, 5);
+  LatestCosignedBlock::set(&mut txn, &5);
+  txn.commit();
+  tokio::time::sleep(interval * 10).await;
+  assert_eq!(take_rebroadcasts(&peer).await, 0);
+}
+
+#[test]
+fn stall_tracker_test() {
+  let stall_after = Duration::from_secs(60);
+  let start = Instant::now();
+  let at = |secs| start + Duration::from_secs(secs);
+
+  let mut tracker = StallTracker::new(stall_after);
+  // Cosigning having caught up isn't a stall
+  assert_eq!(tracker.observe(5, 5, at(0)), None);
+
+  // Being behind for less than `stall_after` isn't a stall
+  assert_eq!(tracker.observe(5, 6, at(0)), None);
+  assert_eq!(tracker.observe(5, 7, at(59)), None);
+  assert_eq!(tracker.observe(5, 8, at(60)), Some(stall_after));
+
+  // We don't alert again until another `stall_after` has passed
+  assert_eq!(tracker.observe(5, 8, at(119)), None);
+  assert_eq!(tracker.observe(5, 8, at(120)), Some(2 * stall_after));
+
+  // Cosigning advancing resets when it's considered stuck since
+  assert_eq!(tracker.observe(6, 8, at(121)), None);
+  assert_eq!(tracker.observe(6, 8, at(180)), None);
+  assert_eq!(tracker.observe(6, 8, at(181)), Some(stall_after));
+}
+
+#[test]
+fn block_cosign_status_test() {
+  let pair = sr25519::Pair::generate().0;
+  let stakes = HashMap::from([
+    (ExternalNetworkId::Bitcoin, 50),
+    (ExternalNetworkId::Ethereum, 30),
+    (ExternalNetworkId::Monero, 20),
+  ]);
+  let latest_cosigns = HashMap::from([
+    (ExternalNetworkId::Bitcoin, cosign(&pair, ExternalNetworkId::Bitcoin, 10, [10; 32])),
+    (ExternalNetworkId::Ethereum, cosign(&pair, ExternalNetworkId::Ethereum, 5, [5; 32])),
+  ]);
+
+  // Cosigns of later blocks count towards prior blocks
+  let status = block_cosign_status(5, &latest_cosigns, &stakes);
+  assert_eq!(status.cosigned_by, vec![ExternalNetworkId::Bitcoin, ExternalNetworkId::Ethereum]);
+  assert_eq!(status.cosigned_stake, 80);
+  assert_eq!(status.total_stake, 100);
+  assert_eq!(status.remaining_stake, 0);
+
+  // More than two thirds of the stake must cosign a block
+  let status = block_cosign_status(6, &latest_cosigns, &stakes);
+  assert_eq!(status.cosigned_by, vec![ExternalNetworkId::Bitcoin]);
+  assert_eq!(status.cosigned_stake, 50);
+  assert_eq!(status.remaining_stake, 18);
+
+  let status = block_cosign_status(11, &latest_cosigns, &stakes);
+  assert!(status.cosigned_by.is_empty());
+  assert_eq!(status.cosigned_stake, 0);
+  assert_eq!(status.remaining_stake, 68);
+}
+
+#[test]
+fn composition_attestation() {
+  let key = Zeroizing::new(<Ristretto as Ciphersuite>::F::random(&mut OsRng));
+  let composition = CosigningComposition {
+    id: 3,
+    block: [0xff; 32],
+    sets: vec![CosigningSet {
+      set: ExternalValidatorSet { network: ExternalNetworkId::Monero, session: Session(1) },
+      key: [1; 32],
+      session_start: session_start(ExternalNetworkId::Monero),
+      stake: 5,
+    }],
+    total_stake: 5,
+  };
+
+  let attestation = CompositionAttestation::attest(&key, composition.clone());
+  assert_eq!(attestation.composition, composition);
+  assert_eq!(attestation.signer, key.public_key());
+  assert!(attestation.verify());
+
+  let json = attestation.to_json();
+  assert_eq!(json["composition"]["id"], 3);
+  assert_eq!(json["composition"]["sets"][0]["stake"], 5);
+  assert_eq!(json["signer"], hex::encode(key.public_key().to_bytes()));
+
+  // A divergent composition shouldn't verify under the signature
+  let mut divergent = attestation.clone();
+  divergent.composition.sets[0].stake = 6;
+  assert!(!divergent.verify());
+  let mut divergent = attestation.clone();
+  divergent.composition.sets[0].session_start = [0; 32];
+  assert!(!divergent.verify());
+
+  // Nor should the attestation verify for another signer
+  let mut other_signer = attestation;
+  other_signer.signer =
+    Zeroizing::new(<Ristretto as Ciphersuite>::F::random(&mut OsRng)).public_key();
+  assert!(!other_signer.verify());
+}
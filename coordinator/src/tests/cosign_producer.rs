@@ -0,0 +1,142 @@
+use sp_application_crypto::{sr25519, Pair as PairTrait};
+
+use serai_client::{
+  primitives::ExternalNetworkId,
+  validator_sets::primitives::{ExternalValidatorSet, Session},
+  Public,
+};
+
+use serai_db::{DbTxn, Db, MemDb};
+
+use processor_messages::coordinator::{CosignPayload, cosign_block_msg};
+
+use crate::{
+  substrate::CosignTransactions,
+  cosign_producer::{
+    CosignIntent, CosignProductionError, verify_cosign_signature, assemble_cosign,
+  },
+};
+
+#[test]
+fn cosign_intent_test() {
+  let mut db = MemDb::new();
+  let network = ExternalNetworkId::Bitcoin;
+  let set = ExternalValidatorSet { network, session: Session(1) };
+
+  let mut txn = db.txn();
+  assert_eq!(CosignIntent::take(&mut txn, network), None);
+  CosignTransactions::append_cosign(&mut txn, set, 5, [0xaa; 32]);
+  CosignTransactions::append_cosign(&mut txn, set, 10, [0xbb; 32]);
+  txn.commit();
+
+  // Pending intents can be observed without taking them
+  let pending = vec![
+    CosignIntent { set, block_number: 5, block: [0xaa; 32] },
+    CosignIntent { set, block_number: 10, block: [0xbb; 32] },
+  ];
+  assert_eq!(CosignIntent::pending(&db, network), pending);
+  assert_eq!(CosignIntent::pending(&db, network), pending);
+
+  // Intents are taken in order, per network
+  let mut txn = db.txn();
+  assert_eq!(CosignIntent::take(&mut txn, ExternalNetworkId::Ethereum), None);
+  assert_eq!(
+    CosignIntent::take(&mut txn, network),
+    Some(CosignIntent { set, block_number: 5, block: [0xaa; 32] })
+  );
+  // Once taken, an intent is no longer pending
+  assert_eq!(CosignIntent::pending(&txn, network), pending[1 ..]);
+  assert_eq!(
+    CosignIntent::take(&mut txn, network),
+    Some(CosignIntent { set, block_number: 10, block: [0xbb; 32] })
+  );
+  assert_eq!(CosignIntent::take(&mut txn, network), None);
+}
+
+#[test]
+fn retired_set_cosign_intents_test() {
+  let mut db = MemDb::new();
+  let network = ExternalNetworkId::Bitcoin;
+  let prior = ExternalValidatorSet { network, session: Session(1) };
+  let next = ExternalValidatorSet { network, session: Session(2) };
+
+  // The prior set cosigns until the handover, after which the next set cosigns
+  let mut txn = db.txn();
+  CosignTransactions::append_cosign(&mut txn, prior, 5, [0xaa; 32]);
+  CosignTransactions::append_cosign(&mut txn, prior, 10, [0xbb; 32]);
+  CosignTransactions::append_cosign(&mut txn, next, 15, [0xcc; 32]);
+  CosignTransactions::append_cosign(
+    &mut txn,
+    ExternalValidatorSet { network: ExternalNetworkId::Ethereum, session: Session(1) },
+    5,
+    [0xaa; 32],
+  );
+  txn.commit();
+
+  // Retiring the prior set should drop its pending cosigns, and only its pending cosigns
+  let mut txn = db.txn();
+  assert_eq!(CosignTransactions::retire_set(&mut txn, prior), 2);
+  txn.commit();
+  let remaining = vec![CosignIntent { set: next, block_number: 15, block: [0xcc; 32] }];
+  assert_eq!(CosignIntent::pending(&db, network), remaining);
+  assert_eq!(CosignIntent::pending(&db, ExternalNetworkId::Ethereum).len(), 1);
+
+  // Retiring it again should be a no-op
+  let mut txn = db.txn();
+  assert_eq!(CosignTransactions::retire_set(&mut txn, prior), 0);
+  txn.commit();
+  assert_eq!(CosignIntent::pending(&db, network), remaining);
+
+  // Once the next set also retires, nothing should remain
+  let mut txn = db.txn();
+  assert_eq!(CosignTransactions::retire_set(&mut txn, next), 1);
+  assert_eq!(CosignIntent::take(&mut txn, network), None);
+  txn.commit();
+}
+
+#[test]
+fn assemble_cosign_test() {
+  let pair = sr25519::Pair::generate().0;
+  let key = Public(pair.public().0);
+  let network = ExternalNetworkId::Monero;
+
+  let session_start = [0xdd; 32];
+
+  // The message signed must be the message the processors sign
+  let message = cosign_block_msg(session_start, 7, [0xcc; 32]);
+  let signature = pair.sign(&message).0;
+
+  let cosign =
+    assemble_cosign(network, session_start, 7, [0xcc; 32], &signature, Some(&key)).unwrap();
+  assert_eq!(cosign.session_start, Some(session_start));
+  assert_eq!(cosign.payload().message(), message);
+  assert_eq!(cosign.signature, signature);
+  assert!(verify_cosign_signature(&key, &cosign));
+
+  // The signature must be for this block
+  assert_eq!(
+    assemble_cosign(network, session_start, 8, [0xcc; 32], &signature, Some(&key)),
+    Err(CosignProductionError::InvalidSignature)
+  );
+  // And this session
+  assert_eq!(
+    assemble_cosign(network, [0xee; 32], 7, [0xcc; 32], &signature, Some(&key)),
+    Err(CosignProductionError::InvalidSignature)
+  );
+  // Though it's only verified if a key is provided
+  assert!(assemble_cosign(network, session_start, 8, [0xcc; 32], &signature, None).is_ok());
+
+  assert_eq!(
+    assemble_cosign(network, session_start, 7, [0xcc; 32], &signature[.. 63], None),
+    Err(CosignProductionError::InvalidSignatureLength)
+  );
+
+  // Legacy cosigns, which aren't bound to a session, are signed over a distinct message
+  let legacy = CosignPayload { session_start: None, block_number: 7, block: [0xcc; 32] };
+  assert!(legacy.message() != message);
+  let mut cosign = cosign;
+  cosign.session_start = None;
+  assert!(!verify_cosign_signature(&key, &cosign));
+  cosign.signature = pair.sign(&legacy.message()).0;
+  assert!(verify_cosign_signature(&key, &cosign));
+}
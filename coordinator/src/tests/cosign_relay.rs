@@ -0,0 +1,104 @@
+use serai_client::{
+  primitives::ExternalNetworkId,
+  validator_sets::primitives::{ExternalValidatorSet, Session},
+};
+
+use serai_db::{DbTxn, Db, MemDb};
+
+use crate::{
+  p2p::CosignedBlock,
+  substrate::LatestCosignedBlock,
+  cosign_evaluator::{
+    LatestCosign, LatestDistinctChainReport, CosigningCompositions, LatestCosigningComposition,
+    DistinctCosign, DistinctChainSet, DistinctChainReport, CosigningSet, CosigningComposition,
+    CosignReader,
+  },
+  cosign_relay::{cosigns_json, session_json, faults_json},
+};
+
+fn cosign(network: ExternalNetworkId, block_number: u64) -> CosignedBlock {
+  CosignedBlock {
+    network,
+    session_start: Some([1; 32]),
+    block_number,
+    block: [u8::try_from(block_number).unwrap(); 32],
+    signature: [2; 64],
+  }
+}
+
+#[tokio::test]
+async fn cosign_relay_test() {
+  let mut db = MemDb::new();
+
+  // Nothing should be served before anything was cosigned
+  {
+    let reader = CosignReader::new(db.clone());
+    let cosigns = cosigns_json(&reader).await;
+    assert_eq!(cosigns["latest_cosigned_block"], 0);
+    assert!(cosigns["cosigns"].as_array().unwrap().is_empty());
+    assert!(session_json(&reader).is_null());
+    assert!(faults_json(&reader).is_null());
+  }
+
+  let monero = ExternalValidatorSet { network: ExternalNetworkId::Monero, session: Session(1) };
+  let mut txn = db.txn();
+  LatestCosign::set(&mut txn, ExternalNetworkId::Monero, &cosign(ExternalNetworkId::Monero, 5));
+  LatestCosign::set(&mut txn, ExternalNetworkId::Bitcoin, &cosign(ExternalNetworkId::Bitcoin, 4));
+  LatestCosignedBlock::set(&mut txn, &4);
+  CosigningCompositions::set(
+    &mut txn,
+    2,
+    &CosigningComposition {
+      id: 2,
+      block: [3; 32],
+      sets: vec![CosigningSet { set: monero, key: [4; 32], session_start: [1; 32], stake: 7 }],
+      total_stake: 7,
+    },
+  );
+  LatestCosigningComposition::set(&mut txn, &2);
+  LatestDistinctChainReport::set(
+    &mut txn,
+    &DistinctChainReport {
+      latest_block: [5; 32],
+      sets: vec![DistinctChainSet {
+        set: monero,
+        stake: 7,
+        distinct: true,
+        distinct_cosign: Some(DistinctCosign {
+          cosign: cosign(ExternalNetworkId::Monero, 6),
+          our_block: [6; 32],
+        }),
+      }],
+      total_stake: 7,
+      distinct_chain_stake: 7,
+      halted: true,
+    },
+  );
+  txn.commit();
+
+  let reader = CosignReader::new(db);
+
+  // The cosigns should be served in the order of their networks, with their signatures
+  let cosigns = cosigns_json(&reader).await;
+  assert_eq!(cosigns["latest_cosigned_block"], 4);
+  let cosigns = cosigns["cosigns"].as_array().unwrap();
+  assert_eq!(cosigns.len(), 2);
+  assert_eq!(cosigns[0]["network"], "Bitcoin");
+  assert_eq!(cosigns[0]["block_number"], 4);
+  assert_eq!(cosigns[1]["network"], "Monero");
+  assert_eq!(cosigns[1]["block"], hex::encode([5; 32]));
+  assert_eq!(cosigns[1]["session_start"], hex::encode([1; 32]));
+  assert_eq!(cosigns[1]["signature"], hex::encode([2; 64]));
+
+  let session = session_json(&reader);
+  assert_eq!(session["id"], 2);
+  assert_eq!(session["sets"][0]["network"], "Monero");
+  assert_eq!(session["sets"][0]["stake"], 7);
+
+  let faults = faults_json(&reader);
+  assert_eq!(faults["halted"], true);
+  assert_eq!(faults["distinct_chain_stake"], 7);
+  assert_eq!(faults["sets"][0]["distinct"], true);
+  assert_eq!(faults["sets"][0]["distinct_cosign"]["cosign"]["block_number"], 6);
+  assert_eq!(faults["sets"][0]["distinct_cosign"]["our_block"], hex::encode([6; 32]));
+}
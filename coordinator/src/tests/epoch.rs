@@ -0,0 +1,53 @@
+use serai_client::{
+  primitives::ExternalNetworkId,
+  validator_sets::primitives::{ExternalValidatorSet, Session},
+};
+
+use serai_db::{DbTxn, Db, MemDb};
+
+use crate::epoch::{note_batch, note_burn, note_slash_report, start_epoch, epoch_report};
+
+#[test]
+fn epoch_reports() {
+  let mut db = MemDb::new();
+  let set = ExternalValidatorSet { network: ExternalNetworkId::Bitcoin, session: Session(1) };
+
+  // Events before the first epoch boundary aren't tallied
+  let mut txn = db.txn();
+  note_batch(&mut txn, ExternalNetworkId::Bitcoin, 0);
+  assert!(start_epoch(&mut txn, Session(1), 10, 5).is_none());
+  txn.commit();
+  let current = epoch_report(&db, None).unwrap();
+  assert_eq!(current.session, Session(1));
+  assert!(current.batches.is_empty());
+
+  let mut txn = db.txn();
+  note_batch(&mut txn, ExternalNetworkId::Bitcoin, 1);
+  note_batch(&mut txn, ExternalNetworkId::Bitcoin, 2);
+  note_burn(&mut txn, ExternalNetworkId::Monero);
+  note_slash_report(&mut txn, set);
+  let ended = start_epoch(&mut txn, Session(2), 20, 15).unwrap();
+  txn.commit();
+
+  assert_eq!(ended.session, Session(1));
+  assert_eq!((ended.start_block, ended.end_block), (10, Some(20)));
+  assert_eq!((ended.cosigned_at_start, ended.cosigned_at_end), (5, Some(15)));
+  assert_eq!(ended.batches[&ExternalNetworkId::Bitcoin], vec![1, 2]);
+  assert_eq!(ended.burns[&ExternalNetworkId::Monero], 1);
+  assert_eq!(ended.slash_reports, vec![set]);
+  assert_eq!(epoch_report(&db, Some(Session(1))), Some(ended.clone()));
+
+  let json = ended.to_json(&db);
+  assert_eq!(json["cosigned_blocks"], 10);
+  assert_eq!(json["networks"].as_array().unwrap().len(), 2);
+  // The slash report isn't locally known
+  assert!(json["slash_reports"][0]["slashes"].is_null());
+
+  // The new epoch should start empty
+  let current = epoch_report(&db, None).unwrap();
+  assert_eq!(current, epoch_report(&db, Some(Session(2))).unwrap());
+  assert!(
+    current.batches.is_empty() && current.burns.is_empty() && current.slash_reports.is_empty()
+  );
+  assert!(epoch_report(&db, Some(Session(3))).is_none());
+}
@@ -0,0 +1,48 @@
+use serai_client::{
+  primitives::{ExternalNetworkId, BlockHash},
+  validator_sets::primitives::{ExternalValidatorSet, Session},
+};
+
+use serai_telemetry::websocket::{accept, encode_frame};
+
+use crate::event_feed::Event;
+
+#[test]
+fn websocket_handshake_and_frames() {
+  // The example from RFC 6455
+  assert_eq!(accept("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+
+  assert_eq!(encode_frame(0x1, b"Hi", None), vec![0x81, 2, b'H', b'i']);
+  let medium = encode_frame(0x1, &[0; 200], None);
+  assert_eq!(&medium[.. 4], &[0x81, 126, 0, 200]);
+  assert_eq!(medium.len(), 4 + 200);
+  let large = encode_frame(0x2, &vec![0; 70_000], None);
+  assert_eq!(&large[.. 10], &[0x82, 127, 0, 0, 0, 0, 0, 1, 0x11, 0x70]);
+  assert_eq!(large.len(), 10 + 70_000);
+}
+
+#[test]
+fn event_json() {
+  let event = Event::BatchPublished {
+    network: ExternalNetworkId::Monero,
+    id: 3,
+    network_block: BlockHash([1; 32]),
+    instructions_hash: [2; 32],
+    serai_block: 10,
+  };
+  let json = event.to_json();
+  assert_eq!(json["type"], "batch_published");
+  assert_eq!(json["network"], "Monero");
+  assert_eq!(json["id"], 3);
+  assert_eq!(json["network_block"], hex::encode([1; 32]));
+  assert_eq!(json["instructions_hash"], hex::encode([2; 32]));
+  assert_eq!(json["serai_block"], 10);
+
+  let set = ExternalValidatorSet { network: ExternalNetworkId::Bitcoin, session: Session(2) };
+  let json = Event::SessionRetired { set, serai_block: 20 }.to_json();
+  assert_eq!(json["type"], "session_retired");
+  assert_eq!(json["set"]["network"], "Bitcoin");
+  assert_eq!(json["set"]["session"], 2);
+
+  assert_eq!(Event::BlockCosigned { block_number: 5 }.to_json()["block_number"], 5);
+}
@@ -0,0 +1,41 @@
+use serai_client::primitives::ExternalNetworkId;
+
+use serai_db::{DbTxn, Db, MemDb};
+
+use crate::intake::{new_processor_message, handled_processor_message};
+
+#[test]
+fn processor_intake() {
+  let mut db = MemDb::new();
+  let network = ExternalNetworkId::Bitcoin;
+
+  assert!(new_processor_message(&db, network, 0));
+  let mut txn = db.txn();
+  handled_processor_message(&mut txn, network, 0);
+  txn.commit();
+
+  // Redeliveries of handled messages should be suppressed
+  assert!(!new_processor_message(&db, network, 0));
+  assert!(new_processor_message(&db, network, 1));
+
+  let mut txn = db.txn();
+  handled_processor_message(&mut txn, network, 1);
+  txn.commit();
+  assert!(!new_processor_message(&db, network, 0));
+  assert!(!new_processor_message(&db, network, 1));
+  assert!(new_processor_message(&db, network, 2));
+
+  // Other networks should be unaffected
+  assert!(new_processor_message(&db, ExternalNetworkId::Monero, 0));
+}
+
+#[test]
+#[should_panic]
+fn processor_intake_gap() {
+  let mut db = MemDb::new();
+  let network = ExternalNetworkId::Bitcoin;
+  let mut txn = db.txn();
+  handled_processor_message(&mut txn, network, 0);
+  txn.commit();
+  new_processor_message(&db, network, 2);
+}
@@ -0,0 +1,99 @@
+use serai_client::{
+  primitives::ExternalNetworkId,
+  validator_sets::primitives::{ExternalValidatorSet, Session},
+};
+
+use processor_messages::{sign, ProcessorMessage};
+
+use crate::metrics::{
+  set_tributary_height, remove_tributary, note_message_queued, set_message_queue_depth,
+  observe_processor_message, render,
+};
+
+#[test]
+fn tributary_height_metrics_test() {
+  // Use a session no other test will, as the metrics are global
+  let set = ExternalValidatorSet { network: ExternalNetworkId::Monero, session: Session(u32::MAX) };
+  let line =
+    format!("serai_coordinator_tributary_height{{network=\"monero\",session=\"{}\"}} 5", u32::MAX);
+
+  set_tributary_height(set, 5);
+  assert!(render().lines().any(|rendered| rendered == line));
+
+  remove_tributary(set);
+  assert!(!render().lines().any(|rendered| rendered.starts_with(&line[.. line.len() - 2])));
+}
+
+#[test]
+fn signing_round_metrics_test() {
+  let id = sign::SignId { session: Session(u32::MAX), id: [0xff; 32], attempt: 0 };
+  let count = || {
+    render()
+      .lines()
+      .find_map(|line| {
+        line.strip_prefix(
+          "serai_coordinator_signing_round_seconds_count{network=\"ethereum\",kind=\"sign\"} ",
+        )
+      })
+      .map(|count| count.parse::<u64>().unwrap())
+  };
+  let before = count().unwrap_or(0);
+
+  // A share without a preceding preprocess shouldn't be observed
+  observe_processor_message(
+    ExternalNetworkId::Ethereum,
+    &ProcessorMessage::Sign(sign::ProcessorMessage::Share { id: id.clone(), shares: vec![] }),
+  );
+  assert_eq!(count().unwrap_or(0), before);
+
+  observe_processor_message(
+    ExternalNetworkId::Ethereum,
+    &ProcessorMessage::Sign(sign::ProcessorMessage::Preprocess {
+      id: id.clone(),
+      preprocesses: vec![],
+    }),
+  );
+  observe_processor_message(
+    ExternalNetworkId::Ethereum,
+    &ProcessorMessage::Sign(sign::ProcessorMessage::Share { id, shares: vec![] }),
+  );
+  assert!(count().unwrap() > before);
+}
+
+#[test]
+fn processor_ack_latency_metrics_test() {
+  // Bitcoin's queue isn't used by any other test, as the metrics are global
+  let count = || {
+    render()
+      .lines()
+      .find_map(|line| {
+        line.strip_prefix("serai_coordinator_processor_ack_seconds_count{network=\"bitcoin\"} ")
+      })
+      .map_or(0, |count| count.parse::<u64>().unwrap())
+  };
+
+  note_message_queued(ExternalNetworkId::Bitcoin);
+  note_message_queued(ExternalNetworkId::Bitcoin);
+  note_message_queued(ExternalNetworkId::Bitcoin);
+  assert!(render().lines().any(|line| line.starts_with(
+    "serai_coordinator_processor_oldest_unacknowledged_seconds{network=\"bitcoin\"} "
+  )));
+
+  // While every message remains queued, none have been acknowledged
+  set_message_queue_depth(ExternalNetworkId::Bitcoin, true, Some(3));
+  assert_eq!(count(), 0);
+
+  // The depth of the queue from the processor shouldn't be considered
+  set_message_queue_depth(ExternalNetworkId::Bitcoin, false, Some(0));
+  assert_eq!(count(), 0);
+
+  set_message_queue_depth(ExternalNetworkId::Bitcoin, true, Some(1));
+  assert_eq!(count(), 2);
+
+  // Failing to fetch the depth shouldn't observe anything
+  set_message_queue_depth(ExternalNetworkId::Bitcoin, true, None);
+  assert_eq!(count(), 2);
+
+  set_message_queue_depth(ExternalNetworkId::Bitcoin, true, Some(0));
+  assert_eq!(count(), 3);
+}
@@ -19,6 +19,18 @@ use crate::{
 
 pub mod tributary;
 
+mod cosign_producer;
+mod cosign_evaluator;
+mod p2p;
+mod metrics;
+mod admin;
+mod cosign_relay;
+mod event_feed;
+mod intake;
+mod epoch;
+mod networks;
+mod audit;
+
 #[derive(Clone)]
 pub struct MemProcessors(pub Arc<RwLock<HashMap<ExternalNetworkId, VecDeque<CoordinatorMessage>>>>);
 impl MemProcessors {
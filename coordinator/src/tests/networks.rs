@@ -0,0 +1,14 @@
+use serai_client::primitives::ExternalNetworkId;
+
+use crate::networks::parse_networks;
+
+#[test]
+fn networks_parsing() {
+  assert_eq!(parse_networks("monero"), Ok(vec![ExternalNetworkId::Monero]));
+  assert_eq!(
+    parse_networks("Bitcoin, monero,bitcoin,"),
+    Ok(vec![ExternalNetworkId::Bitcoin, ExternalNetworkId::Monero])
+  );
+  assert!(parse_networks("").is_err());
+  assert!(parse_networks("bitcoin,serai").is_err());
+}
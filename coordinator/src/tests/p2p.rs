@@ -0,0 +1,220 @@
+use core::time::Duration;
+use std::time::Instant;
+
+use borsh::{BorshSerialize, BorshDeserialize};
+
+use libp2p::{
+  core::multiaddr::Protocol,
+  Multiaddr, PeerId,
+  gossipsub::{IdentTopic, TopicHash, DataTransform, RawMessage},
+};
+
+use serai_db::{DbTxn, Db, MemDb};
+
+use serai_client::primitives::ExternalNetworkId;
+
+use crate::{
+  p2p::{
+    LIBP2P_TOPIC, CosignedBlock, CosignPeerScores, KnownPeers, GossipCompression, compressed_topic,
+    dial_addr, relayed_peer,
+  },
+  cosign_evaluator::CosignOutcome,
+};
+
+#[test]
+fn cosign_peer_scores_test() {
+  let cosign = |block_number| CosignedBlock {
+    network: ExternalNetworkId::Bitcoin,
+    session_start: Some([0; 32]),
+    block_number,
+    block: [0; 32],
+    signature: [0; 64],
+  };
+
+  let now = Instant::now();
+  let mut scores = CosignPeerScores::<usize>::new();
+
+  // Valid cosigns don't affect a peer's score, nor do cosigns from unknown senders
+  scores.received(0, cosign(0));
+  assert!(scores.outcome(&cosign(0), CosignOutcome::Accepted, now).is_empty());
+  assert!(scores.outcome(&cosign(1), CosignOutcome::Invalid, now).is_empty());
+
+  // Sending too many invalid cosigns bans a peer, with every sender of a cosign scored
+  for i in 0 .. 15 {
+    scores.received(0, cosign(i));
+    scores.received(1, cosign(i));
+    assert!(scores.outcome(&cosign(i), CosignOutcome::Invalid, now).is_empty());
+  }
+  scores.received(0, cosign(15));
+  assert_eq!(scores.outcome(&cosign(15), CosignOutcome::Invalid, now), vec![0]);
+  assert!(scores.banned(0, now));
+  assert!(!scores.banned(1, now));
+
+  // Invalid cosigns are forgiven over time
+  let later = now + Duration::from_secs(60);
+  scores.received(1, cosign(16));
+  assert!(scores.outcome(&cosign(16), CosignOutcome::Invalid, later).is_empty());
+
+  // Bans are temporary
+  assert!(scores.banned(0, now + Duration::from_secs(30 * 60) - Duration::from_secs(1)));
+  assert!(!scores.banned(0, now + Duration::from_secs(30 * 60)));
+}
+
+#[test]
+fn cosigned_block_serialization_test() {
+  let legacy = CosignedBlock {
+    network: ExternalNetworkId::Monero,
+    session_start: None,
+    block_number: 5,
+    block: [0xaa; 32],
+    signature: [0xbb; 64],
+  };
+  let session_bound = CosignedBlock { session_start: Some([0xcc; 32]), ..legacy };
+
+  // Legacy cosigns continue to be serialized as they historically were
+  let mut legacy_buf = vec![];
+  legacy.serialize(&mut legacy_buf).unwrap();
+  let mut expected = vec![3];
+  expected.extend(5u64.to_le_bytes());
+  expected.extend([0xaa; 32]);
+  expected.extend([0xbb; 64]);
+  assert_eq!(legacy_buf, expected);
+
+  // While session-bound cosigns are versioned
+  let mut session_bound_buf = vec![];
+  session_bound.serialize(&mut session_bound_buf).unwrap();
+  assert_eq!(&session_bound_buf[.. 3], &[u8::MAX, 1, 3]);
+  assert_eq!(session_bound_buf.len(), legacy_buf.len() + 2 + 32);
+
+  for (cosign, buf) in [(legacy, legacy_buf), (session_bound, session_bound_buf.clone())] {
+    assert_eq!(CosignedBlock::deserialize_reader(&mut buf.as_slice()).unwrap(), cosign);
+  }
+  // Lists of cosigns, as saved to the DB, remain decodable
+  assert_eq!(
+    borsh::from_slice::<Vec<CosignedBlock>>(&borsh::to_vec(&vec![legacy, session_bound]).unwrap())
+      .unwrap(),
+    vec![legacy, session_bound]
+  );
+
+  // Unrecognized versions are rejected
+  session_bound_buf[1] = 2;
+  assert!(CosignedBlock::deserialize_reader(&mut session_bound_buf.as_slice()).is_err());
+}
+
+#[test]
+fn gossip_compression_test() {
+  let transform = GossipCompression;
+  let received = |topic: TopicHash, data| {
+    transform.inbound_transform(RawMessage {
+      source: None,
+      data,
+      sequence_number: None,
+      topic,
+      signature: None,
+      key: None,
+      validated: false,
+    })
+  };
+
+  let msg = [[1; 256], [2; 256]].concat();
+
+  // Messages on the legacy topics are untouched
+  let legacy = IdentTopic::new(LIBP2P_TOPIC).hash();
+  assert_eq!(transform.outbound_transform(&legacy, msg.clone()).unwrap(), msg);
+  assert_eq!(received(legacy, msg.clone()).unwrap().data, msg);
+
+  // Messages on the compressed topics are compressed, and decompressed upon receipt
+  let compressed = compressed_topic(&IdentTopic::new(LIBP2P_TOPIC)).hash();
+  let sent = transform.outbound_transform(&compressed, msg.clone()).unwrap();
+  assert!(sent.len() < msg.len());
+  assert_eq!(received(compressed.clone(), sent).unwrap().data, msg);
+
+  // Messages which aren't validly compressed are rejected
+  assert!(received(compressed.clone(), msg).is_err());
+  // As are messages which decompress to larger than the maximum message size
+  let bomb = zstd::bulk::compress(&vec![0; 16 * 1024 * 1024], 3).unwrap();
+  assert!(received(compressed, bomb).is_err());
+}
+
+#[test]
+fn known_peers_test() {
+  let network = ExternalNetworkId::Bitcoin;
+  let a: Multiaddr = "/ip4/127.0.0.1/tcp/30563".parse().unwrap();
+  let b: Multiaddr = "/ip4/127.0.0.2/tcp/30563".parse().unwrap();
+  let c: Multiaddr = "/ip4/127.0.0.3/tcp/30563".parse().unwrap();
+
+  let mut db = MemDb::new();
+  assert!(KnownPeers::best_addrs(&db, network, 3).is_empty());
+
+  let mut txn = db.txn();
+  KnownPeers::seen(&mut txn, network, &a);
+  KnownPeers::seen(&mut txn, network, &a);
+  KnownPeers::seen(&mut txn, network, &b);
+  KnownPeers::seen(&mut txn, network, &c);
+  txn.commit();
+
+  // Peers are ordered by score, then by how recently they were seen
+  assert_eq!(KnownPeers::best_addrs(&db, network, 3), vec![a.clone(), c.clone(), b.clone()]);
+  assert_eq!(KnownPeers::best_addrs(&db, network, 1), vec![a.clone()]);
+  // Peers are tracked per network
+  assert!(KnownPeers::best_addrs(&db, ExternalNetworkId::Monero, 3).is_empty());
+
+  // Failing to connect lowers a peer's score, forgetting it once its score is exhausted
+  let mut txn = db.txn();
+  KnownPeers::failed(&mut txn, network, &a);
+  KnownPeers::failed(&mut txn, network, &c);
+  txn.commit();
+  assert_eq!(KnownPeers::best_addrs(&db, network, 3), vec![b.clone(), a.clone()]);
+
+  let mut txn = db.txn();
+  KnownPeers::failed(&mut txn, network, &a);
+  // Failing to connect to an unknown peer is a no-op
+  KnownPeers::failed(&mut txn, network, &c);
+  txn.commit();
+  assert_eq!(KnownPeers::best_addrs(&db, network, 3), vec![b]);
+}
+
+#[test]
+fn dial_addr_test() {
+  let addr = |addr: &str| addr.parse::<Multiaddr>().unwrap();
+
+  // Addresses from the Serai node should have their PeerId dropped and use our port
+  let substrate =
+    addr("/ip4/1.2.3.4/tcp/30333/p2p/12D3KooWDpJ7As7BWAwRMfu1VU2WCqNjvq387JEYKDBj4kx6nXTN");
+  assert_eq!(dial_addr(&substrate, false), addr("/ip4/1.2.3.4/tcp/30563"));
+  assert_eq!(dial_addr(&substrate, true), addr("/ip4/1.2.3.4/udp/30563/quic-v1"));
+
+  // Addresses we've connected to over QUIC should be convertible back to TCP
+  let quic = addr("/ip6/::1/udp/1234/quic-v1");
+  assert_eq!(dial_addr(&quic, true), addr("/ip6/::1/udp/30563/quic-v1"));
+  assert_eq!(dial_addr(&quic, false), addr("/ip6/::1/tcp/30563"));
+  assert_eq!(
+    dial_addr(&addr("/dns4/example.com/udp/1/quic"), false),
+    addr("/dns4/example.com/tcp/30563")
+  );
+
+  // Converting should be idempotent
+  for quic in [false, true] {
+    let dial = dial_addr(&substrate, quic);
+    assert_eq!(dial_addr(&dial, quic), dial);
+  }
+}
+
+#[test]
+fn relayed_addr_test() {
+  let relay =
+    "/ip4/1.2.3.4/tcp/30563".parse::<Multiaddr>().unwrap().with(Protocol::P2p(PeerId::random()));
+  let circuit = relay.clone().with(Protocol::P2pCircuit);
+  let peer = PeerId::random();
+
+  let relayed = circuit.clone().with(Protocol::P2p(peer));
+  assert_eq!(relayed_peer(&relayed), Some(peer));
+  // Relayed addresses should be dialed as-is, as the PeerIds within them are required
+  for quic in [false, true] {
+    assert_eq!(dial_addr(&relayed, quic), relayed);
+  }
+
+  // Addresses which aren't relayed, or which don't specify the peer, aren't for a relayed peer
+  assert_eq!(relayed_peer(&relay), None);
+  assert_eq!(relayed_peer(&circuit), None);
+}
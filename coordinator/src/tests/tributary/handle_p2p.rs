@@ -40,7 +40,8 @@ async fn handle_p2p_test() {
     tributary_arcs.push(tributary.clone());
     let (new_tributary_send, new_tributary_recv) = broadcast::channel(5);
     let (cosign_send, _) = mpsc::unbounded_channel();
-    tokio::spawn(handle_p2p_task(p2p, cosign_send, new_tributary_recv));
+    let (_, cosign_outcomes) = mpsc::unbounded_channel();
+    tokio::spawn(handle_p2p_task(p2p, cosign_send, cosign_outcomes, new_tributary_recv));
     new_tributary_send
       .send(TributaryEvent::NewTributary(ActiveTributary { spec: spec.clone(), tributary }))
       .map_err(|_| "failed to send ActiveTributary")
@@ -0,0 +1,46 @@
+use rand_core::OsRng;
+
+use ciphersuite::{group::GroupEncoding, Ciphersuite, Ristretto};
+
+use serai_db::{DbTxn, Db, MemDb};
+
+use crate::{
+  tributary::{FatallySlashed, observe_commit, missed_attempt, slash_points, slash_report},
+  tests::tributary::{new_keys, new_spec},
+};
+
+#[test]
+fn slash_report_from_liveness() {
+  let keys = new_keys(&mut OsRng);
+  let spec = new_spec(&mut OsRng, &keys);
+  let genesis = spec.genesis();
+  let validators =
+    spec.validators().into_iter().map(|(validator, _)| validator.to_bytes()).collect::<Vec<_>>();
+
+  let mut db = MemDb::new();
+  let mut txn = db.txn();
+  // The second validator is absent from the commits of twenty blocks
+  let signers = validators.iter().copied().filter(|v| *v != validators[1]).collect::<Vec<_>>();
+  for _ in 0 .. 20 {
+    observe_commit(&mut txn, &spec, &signers);
+  }
+  // Everyone signs the commit for this block
+  observe_commit(&mut txn, &spec, &validators);
+  // The third validator misses an attempt of a signing protocol
+  missed_attempt(&mut txn, genesis, validators[2]);
+  // The fourth validator is fatally slashed
+  FatallySlashed::set_fatally_slashed(&mut txn, genesis, validators[3]);
+  txn.commit();
+
+  assert_eq!(slash_points(&db, genesis, validators[0]), 0);
+  assert_eq!(slash_points(&db, genesis, validators[1]), 2);
+  assert_eq!(slash_points(&db, genesis, validators[2]), 1);
+  assert_eq!(slash_points(&db, genesis, validators[3]), u32::MAX);
+
+  // The report omits whoever is producing it
+  let us = keys
+    .iter()
+    .find(|key| (<Ristretto as Ciphersuite>::generator() * ***key).to_bytes() == validators[4])
+    .unwrap();
+  assert_eq!(slash_report(&db, &spec, us), vec![0, 2, 1, u32::MAX]);
+}
@@ -26,6 +26,11 @@ mod dkg;
 mod handle_p2p;
 mod sync;
 
+mod liveness;
+
+mod prune;
+mod resume_dkg;
+
 #[async_trait::async_trait]
 impl PublishSeraiTransaction for () {
   async fn publish_set_keys(
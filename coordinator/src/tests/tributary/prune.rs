@@ -0,0 +1,58 @@
+use core::time::Duration;
+use std::time::SystemTime;
+
+use rand_core::OsRng;
+
+use serai_db::{DbTxn, Db, MemDb};
+
+use crate::{
+  db::{ActiveTributaryDb, UnprunedTributaryDb},
+  tributary::{LastHandledBlock, prune_retired_tributaries},
+  tests::{
+    LocalP2p,
+    tributary::{new_keys, new_spec},
+  },
+};
+
+#[test]
+fn prune_retired_tributaries_test() {
+  let keys = new_keys(&mut OsRng);
+  let spec = new_spec(&mut OsRng, &keys);
+  let genesis = spec.genesis();
+  let window = Duration::from_secs(60 * 60);
+
+  let mut db = MemDb::new();
+  let mut txn = db.txn();
+  ActiveTributaryDb::add_participating_in_tributary(&mut txn, &spec);
+  LastHandledBlock::set(&mut txn, genesis, &[0xff; 32]);
+  txn.commit();
+
+  let now = || SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+
+  // Active Tributaries shouldn't be pruned
+  assert!(prune_retired_tributaries::<_, LocalP2p>(
+    &mut db,
+    now() + (2 * window.as_secs()),
+    window
+  )
+  .is_empty());
+
+  let mut txn = db.txn();
+  ActiveTributaryDb::retire_tributary(&mut txn, spec.set());
+  txn.commit();
+
+  // Nor should Tributaries retired within the safety window
+  assert!(prune_retired_tributaries::<_, LocalP2p>(&mut db, now(), window).is_empty());
+  assert_eq!(LastHandledBlock::get(&db, genesis), Some([0xff; 32]));
+
+  let after_window = now() + window.as_secs();
+  assert_eq!(UnprunedTributaryDb::prunable(&db, after_window, window.as_secs()), vec![genesis]);
+  assert_eq!(
+    prune_retired_tributaries::<_, LocalP2p>(&mut db, after_window, window),
+    vec![genesis]
+  );
+  assert_eq!(LastHandledBlock::get(&db, genesis), None);
+
+  // It should only be pruned once
+  assert!(prune_retired_tributaries::<_, LocalP2p>(&mut db, after_window, window).is_empty());
+}
@@ -0,0 +1,59 @@
+use rand_core::OsRng;
+
+use frost::{Participant, ThresholdParams};
+
+use serai_db::{DbTxn, Db, MemDb};
+
+use processor_messages::{
+  key_gen::{self, KeyGenId},
+  CoordinatorMessage,
+};
+
+use crate::{
+  tributary::{DkgLocallyCompleted, send_dkg_message, resend_dkg_messages},
+  tests::{
+    MemProcessors,
+    tributary::{new_keys, new_spec},
+  },
+};
+
+#[tokio::test]
+async fn resume_dkg_test() {
+  let keys = new_keys(&mut OsRng);
+  let spec = new_spec(&mut OsRng, &keys);
+  let genesis = spec.genesis();
+  let network = spec.set().network;
+
+  let mut db = MemDb::new();
+  let processors = MemProcessors::new();
+
+  // Nothing should be re-sent if the DKG hasn't started
+  assert!(!resend_dkg_messages(&db, &processors, &spec).await);
+  assert!(processors.0.read().await.get(&network).is_none());
+
+  let msg = key_gen::CoordinatorMessage::GenerateKey {
+    id: KeyGenId { session: spec.set().session, attempt: 0 },
+    params: ThresholdParams::new(spec.t(), spec.n(&[]), Participant::new(1).unwrap()).unwrap(),
+    shares: 1,
+  };
+  let mut txn = db.txn();
+  send_dkg_message(&mut txn, &processors, &spec, 0, msg.clone()).await;
+  // Sending the same message again, as done when re-handling a block, shouldn't re-record it
+  send_dkg_message(&mut txn, &processors, &spec, 0, msg.clone()).await;
+  txn.commit();
+  processors.0.write().await.get_mut(&network).unwrap().clear();
+
+  // After rebooting, the recorded messages should be re-sent
+  assert!(resend_dkg_messages(&db, &processors, &spec).await);
+  assert_eq!(
+    processors.0.write().await.get_mut(&network).unwrap().drain(..).collect::<Vec<_>>(),
+    vec![CoordinatorMessage::KeyGen(msg)]
+  );
+
+  // Once the DKG locally completes, they shouldn't be
+  let mut txn = db.txn();
+  DkgLocallyCompleted::set(&mut txn, genesis, &());
+  txn.commit();
+  assert!(resend_dkg_messages(&db, &processors, &spec).await);
+  assert!(processors.0.read().await.get(&network).unwrap().is_empty());
+}
@@ -50,7 +50,9 @@ async fn sync_test() {
     tributary_arcs.push(tributary.clone());
     let (new_tributary_send, new_tributary_recv) = broadcast::channel(5);
     let (cosign_send, _) = mpsc::unbounded_channel();
-    let thread = tokio::spawn(handle_p2p_task(p2p, cosign_send, new_tributary_recv));
+    let (_, cosign_outcomes) = mpsc::unbounded_channel();
+    let thread =
+      tokio::spawn(handle_p2p_task(p2p, cosign_send, cosign_outcomes, new_tributary_recv));
     new_tributary_send
       .send(TributaryEvent::NewTributary(ActiveTributary { spec: spec.clone(), tributary }))
       .map_err(|_| "failed to send ActiveTributary")
@@ -86,7 +88,13 @@ async fn sync_test() {
   let syncer_tributary = Arc::new(syncer_tributary);
   let (syncer_tributary_send, syncer_tributary_recv) = broadcast::channel(5);
   let (cosign_send, _) = mpsc::unbounded_channel();
-  tokio::spawn(handle_p2p_task(syncer_p2p.clone(), cosign_send, syncer_tributary_recv));
+  let (_, cosign_outcomes) = mpsc::unbounded_channel();
+  tokio::spawn(handle_p2p_task(
+    syncer_p2p.clone(),
+    cosign_send,
+    cosign_outcomes,
+    syncer_tributary_recv,
+  ));
   syncer_tributary_send
     .send(TributaryEvent::NewTributary(ActiveTributary {
       spec: spec.clone(),
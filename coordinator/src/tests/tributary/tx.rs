@@ -1,17 +1,22 @@
 use core::time::Duration;
 
+use zeroize::Zeroizing;
 use rand_core::{RngCore, OsRng};
 
+use ciphersuite::{group::ff::Field, Ciphersuite, Ristretto};
+
 use tokio::time::sleep;
 
 use serai_db::MemDb;
 
 use tributary::{
-  transaction::Transaction as TransactionTrait, Transaction as TributaryTransaction, Tributary,
+  transaction::{Transaction as TransactionTrait, TransactionKind},
+  Transaction as TributaryTransaction, Tributary,
 };
 
 use crate::{
   tributary::Transaction,
+  CoordinatorSigner,
   tests::{
     LocalP2p,
     tributary::{new_keys, new_spec, new_tributaries, run_tributaries, wait_for_tx_inclusion},
@@ -61,3 +66,33 @@ async fn tx_test() {
     assert_eq!(block.transactions, vec![TributaryTransaction::Application(tx.clone())]);
   }
 }
+
+#[test]
+fn signer_test() {
+  let key = Zeroizing::new(<Ristretto as Ciphersuite>::F::random(&mut OsRng));
+  let mut genesis = [0; 32];
+  OsRng.fill_bytes(&mut genesis);
+
+  let mut tx = Transaction::DkgCommitments {
+    attempt: 0,
+    commitments: vec![vec![0; 32]],
+    signed: Transaction::empty_signed(),
+  };
+  key.sign_transaction(genesis, &mut tx);
+  let TransactionKind::Signed(_, signed) = tx.kind() else {
+    panic!("DkgCommitments wasn't signed")
+  };
+  assert_eq!(signed.signer, key.public_key());
+  assert!(signed.signature.verify(signed.signer, tx.sig_hash(genesis)));
+
+  let mut tx = Transaction::SignCompleted {
+    plan: [0; 32],
+    tx_hash: vec![1; 32],
+    first_signer: Transaction::empty_signed().signer,
+    signature: Transaction::empty_signed().signature,
+  };
+  key.sign_completion(&mut tx);
+  let Transaction::SignCompleted { first_signer, .. } = &tx else { unreachable!() };
+  assert_eq!(*first_signer, key.public_key());
+  tx.verify().unwrap();
+}
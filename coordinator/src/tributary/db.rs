@@ -8,7 +8,7 @@ use frost::Participant;
 
 use serai_client::validator_sets::primitives::{KeyPair, ExternalValidatorSet};
 
-use processor_messages::coordinator::SubstrateSignableId;
+use processor_messages::{key_gen, coordinator::SubstrateSignableId};
 
 pub use serai_db::*;
 
@@ -66,12 +66,16 @@ create_db!(
     ReattemptDb: (genesis: [u8; 32], block: u32) -> Vec<Topic>,
     DataReceived: (genesis: [u8; 32], data_spec: &DataSpecification) -> u16,
     DataDb: (genesis: [u8; 32], data_spec: &DataSpecification, signer_bytes: &[u8; 32]) -> Vec<u8>,
+    // The validators whose data was used once an accumulation became ready
+    DataParticipants: (genesis: [u8; 32], data_spec: &DataSpecification) -> Vec<[u8; 32]>,
 
     DkgShare: (genesis: [u8; 32], from: u16, to: u16) -> Vec<u8>,
     ConfirmationNonces: (genesis: [u8; 32], attempt: u32) -> HashMap<Participant, Vec<u8>>,
     DkgKeyPair: (genesis: [u8; 32], attempt: u32) -> KeyPair,
     KeyToDkgAttempt: (key: [u8; 32]) -> u32,
     DkgLocallyCompleted: (genesis: [u8; 32]) -> (),
+    // The messages sent to our processor for each DKG attempt, so they may be re-sent on reboot
+    DkgMessages: (genesis: [u8; 32], attempt: u32) -> Vec<key_gen::CoordinatorMessage>,
 
     PlanIds: (genesis: &[u8], block: u64) -> Vec<[u8; 32]>,
 
@@ -94,6 +98,13 @@ impl FatalSlashes {
   }
 }
 
+impl SlashPoints {
+  pub fn slash(txn: &mut impl DbTxn, genesis: [u8; 32], account: [u8; 32], points: u32) {
+    let existing = Self::get(txn, genesis, account).unwrap_or(0);
+    Self::set(txn, genesis, account, &existing.saturating_add(points));
+  }
+}
+
 impl FatallySlashed {
   pub fn set_fatally_slashed(txn: &mut impl DbTxn, genesis: [u8; 32], account: [u8; 32]) {
     Self::set(txn, genesis, account, &());
@@ -146,16 +157,16 @@ impl ReattemptDb {
   ) {
     // 5 minutes
     #[cfg(not(feature = "longer-reattempts"))]
-    const BASE_REATTEMPT_DELAY: u32 = (5 * 60 * 1000) / tributary::tendermint::TARGET_BLOCK_TIME;
+    let base_reattempt_delay = (5 * 60 * 1000) / tributary::tendermint::target_block_time();
 
     // 10 minutes, intended for latent environments like the GitHub CI
     #[cfg(feature = "longer-reattempts")]
-    const BASE_REATTEMPT_DELAY: u32 = (10 * 60 * 1000) / tributary::tendermint::TARGET_BLOCK_TIME;
+    let base_reattempt_delay = (10 * 60 * 1000) / tributary::tendermint::target_block_time();
 
     // 5 minutes for attempts 0 ..= 2, 10 minutes for attempts 3 ..= 5, 15 minutes for attempts > 5
     // Assumes no event will take longer than 15 minutes, yet grows the time in case there are
     // network bandwidth issues
-    let mut reattempt_delay = BASE_REATTEMPT_DELAY *
+    let mut reattempt_delay = base_reattempt_delay *
       ((AttemptDb::attempt(txn, genesis, topic)
         .expect("scheduling re-attempt for unknown topic") /
         3) +
@@ -2,7 +2,6 @@ use core::ops::Deref;
 use std::collections::HashMap;
 
 use zeroize::Zeroizing;
-use rand_core::OsRng;
 
 use ciphersuite::{group::GroupEncoding, Ciphersuite, Ristretto};
 use frost::dkg::Participant;
@@ -29,7 +28,7 @@ use crate::{
       RecognizedIdType, RIDTrait, PublishSeraiTransaction, PTTTrait, TributaryBlockHandler,
     },
   },
-  P2p,
+  P2p, CoordinatorSigner, logging,
 };
 
 pub fn dkg_confirmation_nonces(
@@ -93,7 +92,14 @@ impl<
     signer: <Ristretto as Ciphersuite>::G,
     data: &Vec<u8>,
   ) -> Accumulation {
-    log::debug!("accumulating entry for {:?} attempt #{}", &data_spec.topic, &data_spec.attempt);
+    log::debug!(
+      target: logging::TRIBUTARY,
+      network:? = self.spec.set().network,
+      session = self.spec.set().session.0,
+      topic:? = data_spec.topic,
+      attempt = data_spec.attempt;
+      "accumulating entry"
+    );
     let genesis = self.spec.genesis();
     if DataDb::get(self.txn, genesis, data_spec, &signer.to_bytes()).is_some() {
       panic!("accumulating data for a participant multiple times");
@@ -134,12 +140,16 @@ impl<
     let needed = if needs_everyone { self.spec.n(removed) } else { self.spec.t() };
     if received_range.contains(&needed) {
       log::debug!(
-        "accumulation for entry {:?} attempt #{} is ready",
-        &data_spec.topic,
-        &data_spec.attempt
+        target: logging::TRIBUTARY,
+        network:? = self.spec.set().network,
+        session = self.spec.set().session.0,
+        topic:? = data_spec.topic,
+        attempt = data_spec.attempt;
+        "accumulation is ready"
       );
 
       let mut data = HashMap::new();
+      let mut participants = vec![];
       for validator in self.spec.validators().iter().map(|validator| validator.0) {
         let Some(i) = self.spec.i(removed, validator) else { continue };
         data.insert(
@@ -150,9 +160,12 @@ impl<
             continue;
           },
         );
+        participants.push(validator.to_bytes());
       }
 
       assert_eq!(data.len(), usize::from(needed));
+      // Save who participated so those who fail to continue participating can be slashed
+      DataParticipants::set(self.txn, genesis, data_spec, &participants);
 
       // Remove our own piece of data, if we were involved
       if let Some(i) = self.spec.i(removed, Ristretto::generator() * self.our_key.deref()) {
@@ -191,10 +204,13 @@ impl<
     // If the attempt is lesser than the blockchain's, return
     if data_spec.attempt < curr_attempt {
       log::debug!(
-        "dated attempt published onto tributary for topic {:?} (used attempt {}, current {})",
-        data_spec.topic,
-        data_spec.attempt,
-        curr_attempt
+        target: logging::TRIBUTARY,
+        network:? = self.spec.set().network,
+        session = self.spec.set().session.0,
+        topic:? = data_spec.topic,
+        attempt = data_spec.attempt,
+        current_attempt = curr_attempt;
+        "dated attempt published onto tributary"
       );
       return Accumulation::NotReady;
     }
@@ -295,16 +311,17 @@ impl<
           Accumulation::Ready(DataSet::Participating(mut commitments)) => {
             log::info!("got all DkgCommitments for {}", hex::encode(genesis));
             unflatten(self.spec, &removed, &mut commitments);
-            self
-              .processors
-              .send(
-                self.spec.set().network,
-                key_gen::CoordinatorMessage::Commitments {
-                  id: KeyGenId { session: self.spec.set().session, attempt },
-                  commitments,
-                },
-              )
-              .await;
+            send_dkg_message(
+              self.txn,
+              self.processors,
+              self.spec,
+              attempt,
+              key_gen::CoordinatorMessage::Commitments {
+                id: KeyGenId { session: self.spec.set().session, attempt },
+                commitments,
+              },
+            )
+            .await;
           }
           Accumulation::Ready(DataSet::NotParticipating) => {
             assert!(
@@ -439,16 +456,17 @@ impl<
               }
             }
 
-            self
-              .processors
-              .send(
-                self.spec.set().network,
-                key_gen::CoordinatorMessage::Shares {
-                  id: KeyGenId { session: self.spec.set().session, attempt },
-                  shares: expanded_shares,
-                },
-              )
-              .await;
+            send_dkg_message(
+              self.txn,
+              self.processors,
+              self.spec,
+              attempt,
+              key_gen::CoordinatorMessage::Shares {
+                id: KeyGenId { session: self.spec.set().session, attempt },
+                shares: expanded_shares,
+              },
+            )
+            .await;
           }
           Accumulation::Ready(DataSet::NotParticipating) => {
             assert!(not_participating, "NotParticipating in a DkgShares we weren't removed for");
@@ -489,19 +507,20 @@ impl<
           );
           return;
         };
-        self
-          .processors
-          .send(
-            self.spec.set().network,
-            key_gen::CoordinatorMessage::VerifyBlame {
-              id: KeyGenId { session: self.spec.set().session, attempt },
-              accuser,
-              accused: faulty,
-              share,
-              blame,
-            },
-          )
-          .await;
+        send_dkg_message(
+          self.txn,
+          self.processors,
+          self.spec,
+          attempt,
+          key_gen::CoordinatorMessage::VerifyBlame {
+            id: KeyGenId { session: self.spec.set().session, attempt },
+            accuser,
+            accused: faulty,
+            share,
+            blame,
+          },
+        )
+        .await;
       }
 
       Transaction::DkgConfirmed { attempt, confirmation_share, signed } => {
@@ -538,7 +557,7 @@ impl<
                   participant: self.spec.reverse_lookup_i(&removed, p).unwrap(),
                   signed: Transaction::empty_signed(),
                 };
-                tx.sign(&mut OsRng, genesis, self.our_key);
+                self.our_key.sign_transaction(genesis, &mut tx);
                 self.publish_tributary_tx.publish_tributary_tx(tx).await;
                 return;
               }
@@ -579,6 +598,8 @@ impl<
             id: SubstrateSignableId::CosigningSubstrateBlock(hash),
             attempt: 0,
           },
+          // The Serai block this Tributary was created with is the block its session began with
+          session_start: self.spec.serai_block(),
           block_number,
         };
         self.processors.send(self.spec.set().network, msg).await;
@@ -767,7 +788,7 @@ impl<
             genesis,
             // 30 minutes into the future
             &(u64::from(self.block_number) +
-              ((30 * 60 * 1000) / u64::from(tributary::tendermint::TARGET_BLOCK_TIME))),
+              ((30 * 60 * 1000) / u64::from(tributary::tendermint::target_block_time()))),
           );
         }
       }
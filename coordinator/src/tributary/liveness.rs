@@ -0,0 +1,78 @@
+use core::ops::Deref;
+
+use zeroize::Zeroizing;
+
+use ciphersuite::{group::GroupEncoding, Ciphersuite, Ristretto};
+
+use serai_db::{Get, DbTxn, create_db};
+
+use crate::tributary::{TributarySpec, SlashPoints, FatallySlashed};
+
+// The amount of blocks a validator may be absent from the commits of before they accrue a slash
+// point
+//
+// Commits only require the signatures of a supermajority, so an honest yet slow validator will
+// occasionally be absent from them. Since the report is reduced by the points of the
+// worst-performing member of the supermajority, such absences don't need to be tolerated here.
+const MISSED_BLOCKS_PER_SLASH_POINT: u32 = 10;
+
+create_db!(
+  Liveness {
+    // The amount of blocks a validator's signature was absent from the commit of
+    MissedBlocks: (genesis: [u8; 32], account: [u8; 32]) -> u32,
+    // The amount of attempts of signing protocols a validator didn't participate in
+    MissedAttempts: (genesis: [u8; 32], account: [u8; 32]) -> u32,
+  }
+);
+
+/// Observe the validators who signed the commit for a block.
+pub fn observe_commit(txn: &mut impl DbTxn, spec: &TributarySpec, signers: &[[u8; 32]]) {
+  let genesis = spec.genesis();
+  for (validator, _) in spec.validators() {
+    let validator = validator.to_bytes();
+    if !signers.contains(&validator) {
+      let missed = MissedBlocks::get(txn, genesis, validator).unwrap_or(0);
+      MissedBlocks::set(txn, genesis, validator, &missed.saturating_add(1));
+    }
+  }
+}
+
+/// Note a validator didn't participate in an attempt of a signing protocol.
+pub fn missed_attempt(txn: &mut impl DbTxn, genesis: [u8; 32], account: [u8; 32]) {
+  let missed = MissedAttempts::get(txn, genesis, account).unwrap_or(0);
+  MissedAttempts::set(txn, genesis, account, &missed.saturating_add(1));
+  SlashPoints::slash(txn, genesis, account, 1);
+}
+
+/// The slash points a validator has accrued within a Tributary.
+///
+/// Validators who were fatally slashed are assigned `u32::MAX` points.
+pub fn slash_points(getter: &impl Get, genesis: [u8; 32], account: [u8; 32]) -> u32 {
+  // TODO: Properly type this
+  if FatallySlashed::get(getter, genesis, account).is_some() {
+    return u32::MAX;
+  }
+  let missed_blocks = MissedBlocks::get(getter, genesis, account).unwrap_or(0);
+  SlashPoints::get(getter, genesis, account)
+    .unwrap_or(0)
+    .saturating_add(missed_blocks / MISSED_BLOCKS_PER_SLASH_POINT)
+}
+
+/// The slash report for us to submit at the end of a Tributary.
+///
+/// This has the slash points for every validator other than ourselves, in the order of the
+/// Tributary's validators.
+pub fn slash_report(
+  getter: &impl Get,
+  spec: &TributarySpec,
+  key: &Zeroizing<<Ristretto as Ciphersuite>::F>,
+) -> Vec<u32> {
+  let genesis = spec.genesis();
+  let us = <Ristretto as Ciphersuite>::generator() * key.deref();
+  spec
+    .validators()
+    .into_iter()
+    .filter(|(validator, _)| *validator != us)
+    .map(|(validator, _)| slash_points(getter, genesis, validator.to_bytes()))
+    .collect()
+}
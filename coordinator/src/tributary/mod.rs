@@ -1,7 +1,10 @@
 use ciphersuite::{group::GroupEncoding, Ciphersuite, Ristretto};
+use frost::Participant;
 
 use serai_client::validator_sets::primitives::ExternalValidatorSet;
 
+use processor_messages::key_gen;
+
 use tributary::{
   ReadWrite,
   transaction::{TransactionError, TransactionKind, Transaction as TransactionTrait},
@@ -19,11 +22,17 @@ pub use transaction::{Label, SignData, Transaction};
 
 mod signing_protocol;
 
+mod liveness;
+pub use liveness::*;
+
 mod handle;
 pub use handle::*;
 
 pub mod scanner;
 
+mod prune;
+pub use prune::*;
+
 pub fn removed_as_of_dkg_attempt(
   getter: &impl Get,
   genesis: [u8; 32],
@@ -57,12 +66,89 @@ pub fn removed_as_of_set_keys(
   removed_as_of_dkg_attempt(getter, genesis, attempt)
 }
 
+/// Increase the slash points of a participant who sent an invalid message during a signing
+/// protocol to the maximum.
+///
+/// This is distinct from a fatal slash, as the fault is only observed locally.
+pub fn slash_invalid_participant(
+  txn: &mut impl DbTxn,
+  spec: &TributarySpec,
+  participant: Participant,
+) {
+  let genesis = spec.genesis();
+  let removed = removed_as_of_set_keys(txn, spec.set(), genesis)
+    .expect("signing with a participant yet have yet to set keys");
+  let Some(validator) = spec.reverse_lookup_i(&removed, participant) else {
+    log::warn!("processor reported an invalid participant {participant:?} which doesn't exist");
+    return;
+  };
+  log::warn!("slashing {} for being an invalid participant", hex::encode(validator.to_bytes()));
+  SlashPoints::slash(txn, genesis, validator.to_bytes(), u32::MAX);
+}
+
+/// Send a message for a DKG attempt to our processor, recording it so it may be re-sent if we
+/// reboot during the DKG.
+pub async fn send_dkg_message<Pro: crate::processors::Processors>(
+  txn: &mut impl DbTxn,
+  processors: &Pro,
+  spec: &TributarySpec,
+  attempt: u32,
+  msg: key_gen::CoordinatorMessage,
+) {
+  let genesis = spec.genesis();
+  let mut msgs = DkgMessages::get(txn, genesis, attempt).unwrap_or_default();
+  // If we're re-handling a block, we'll re-send the messages we sent while handling it
+  if !msgs.contains(&msg) {
+    msgs.push(msg.clone());
+    DkgMessages::set(txn, genesis, attempt, &msgs);
+  }
+  processors.send(spec.set().network, msg).await;
+}
+
+/// Re-send the messages for the current DKG attempt to our processor, if the DKG is incomplete.
+///
+/// The message-queue deduplicates messages by their intents, so messages the processor already
+/// received won't be received again. This ensures any which weren't queued before we rebooted
+/// are, letting the processor resume the DKG from where it left off.
+///
+/// Returns false if no messages were recorded for the current attempt, meaning the DKG has yet to
+/// start (or started before messages were recorded).
+pub async fn resend_dkg_messages<Pro: crate::processors::Processors>(
+  getter: &impl Get,
+  processors: &Pro,
+  spec: &TributarySpec,
+) -> bool {
+  let genesis = spec.genesis();
+  let attempt = AttemptDb::attempt(getter, genesis, Topic::Dkg).unwrap();
+  let Some(msgs) = DkgMessages::get(getter, genesis, attempt) else { return false };
+  if (DkgLocallyCompleted::get(getter, genesis).is_some()) ||
+    (SeraiDkgCompleted::get(getter, spec.set()).is_some())
+  {
+    return true;
+  }
+
+  log::info!(
+    target: crate::logging::TRIBUTARY,
+    network:? = spec.set().network, session = spec.set().session.0, attempt = attempt,
+    messages = msgs.len();
+    "resuming DKG"
+  );
+  for msg in msgs {
+    processors.send(spec.set().network, msg).await;
+  }
+  true
+}
+
 pub async fn publish_signed_transaction<D: Db, P: crate::P2p>(
   txn: &mut D::Transaction<'_>,
   tributary: &Tributary<D, Transaction, P>,
   tx: Transaction,
 ) {
-  log::debug!("publishing transaction {}", hex::encode(tx.hash()));
+  log::debug!(
+    target: crate::logging::TRIBUTARY,
+    genesis:% = hex::encode(tributary.genesis()), tx:% = hex::encode(tx.hash());
+    "publishing transaction"
+  );
 
   let (order, signer) = if let TransactionKind::Signed(order, signed) = tx.kind() {
     let signer = signed.signer;
@@ -0,0 +1,68 @@
+use core::time::Duration;
+use std::time::SystemTime;
+
+use tokio::time::sleep;
+
+use serai_db::{DbTxn, Db};
+
+use tributary::Tributary;
+
+use crate::{
+  P2p, logging,
+  db::UnprunedTributaryDb,
+  tributary::{
+    Transaction, Topic, AttemptDb, LastHandledBlock, FatalSlashes, OfflineDuringDkg,
+    DkgLocallyCompleted, DkgMessages,
+  },
+};
+
+// How often to check for retired Tributaries to prune
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Prune the retired Tributaries which were retired at least `safety_window` before `now`.
+///
+/// This deletes the Tributary's blocks and commits, along with our own state for it which is
+/// solely indexed by its genesis (or by its genesis and DKG attempt). Other state indexed by
+/// attempts, signers, or data specifications is left as it can't be enumerated without iterating
+/// the database.
+///
+/// Returns the genesis of each Tributary pruned.
+pub fn prune_retired_tributaries<D: Db, P: P2p>(
+  db: &mut D,
+  now: u64,
+  safety_window: Duration,
+) -> Vec<[u8; 32]> {
+  let prunable = UnprunedTributaryDb::prunable(db, now, safety_window.as_secs());
+  for genesis in &prunable {
+    let genesis = *genesis;
+    Tributary::<D, Transaction, P>::prune(db.clone(), genesis);
+
+    // Only mark this as pruned after the Tributary was, so an interruption causes a re-attempt
+    let mut txn = db.txn();
+    LastHandledBlock::del(&mut txn, genesis);
+    FatalSlashes::del(&mut txn, genesis);
+    OfflineDuringDkg::del(&mut txn, genesis);
+    DkgLocallyCompleted::del(&mut txn, genesis);
+    for attempt in 0 ..= AttemptDb::attempt(&txn, genesis, Topic::Dkg).unwrap_or(0) {
+      DkgMessages::del(&mut txn, genesis, attempt);
+    }
+    UnprunedTributaryDb::pruned(&mut txn, genesis);
+    txn.commit();
+
+    log::info!(
+      target: logging::TRIBUTARY,
+      genesis:% = hex::encode(genesis);
+      "pruned retired tributary"
+    );
+  }
+  prunable
+}
+
+/// Regularly prune the Tributaries retired at least `safety_window` ago.
+pub async fn prune_retired_tributaries_task<D: Db, P: P2p>(mut db: D, safety_window: Duration) {
+  loop {
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+    prune_retired_tributaries::<D, P>(&mut db, now, safety_window);
+    sleep(PRUNE_INTERVAL).await;
+  }
+}
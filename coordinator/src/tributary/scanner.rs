@@ -1,11 +1,11 @@
-use core::{marker::PhantomData, ops::Deref, future::Future, time::Duration};
+use core::{marker::PhantomData, ops::Deref, future::Future, num::NonZeroUsize, time::Duration};
 use std::{sync::Arc, collections::HashSet};
 
 use zeroize::Zeroizing;
 
 use ciphersuite::{group::GroupEncoding, Ciphersuite, Ristretto};
 
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, Semaphore};
 
 use scale::{Encode, Decode};
 use serai_client::{
@@ -313,8 +313,17 @@ impl<
             let removed =
               crate::tributary::removed_as_of_set_keys(self.txn, self.spec.set(), genesis)
                 .expect("SubstrateSign/Sign yet have yet to set keys");
-            // TODO: If 67% sent preprocesses, this should be them. Else, this should be vec![]
-            let expected_participants = vec![];
+            // If 67% sent preprocesses, this is those whose preprocesses were used. Else, this is
+            // empty
+            let expected_participants = DataParticipants::get(
+              self.txn,
+              genesis,
+              &DataSpecification { topic, label: Label::Preprocess, attempt: prior_attempt },
+            )
+            .unwrap_or(vec![])
+            .into_iter()
+            .map(|validator| <Ristretto as Ciphersuite>::G::from_bytes(&validator).unwrap())
+            .collect();
             (removed, expected_participants)
           }
         };
@@ -371,13 +380,23 @@ impl<
         // If a supermajority didn't participate as expected, the protocol was likely aborted due
         // to detection of a completion or some larger networking error
         // Accordingly, clear did_not_participate
-        // TODO
+        {
+          let mut did_not_participate_shares = 0;
+          for validator in &did_not_participate {
+            if let Some(i) = self.spec.i(&removed, *validator) {
+              did_not_participate_shares += u16::from(i.end) - u16::from(i.start);
+            }
+          }
+          if did_not_participate_shares >= self.spec.t() {
+            did_not_participate.clear();
+          }
+        }
 
         // If during the DKG, explicitly mark these people as having been offline
         // TODO: If they were offline sufficiently long ago, don't strike them off
         if topic == Topic::Dkg {
           let mut existing = OfflineDuringDkg::get(self.txn, genesis).unwrap_or(vec![]);
-          for did_not_participate in did_not_participate {
+          for did_not_participate in &did_not_participate {
             existing.push(did_not_participate.to_bytes());
           }
           OfflineDuringDkg::set(self.txn, genesis, &existing);
@@ -390,7 +409,13 @@ impl<
         // At the end of the protocol, the accumulated slashes are reduced by the amount obtained
         // by the worst-performing member of the supermajority, and this is expected to
         // sufficiently compensate for slashes which occur under normal operation
-        // TODO
+        for did_not_participate in did_not_participate {
+          log::info!(
+            "slashing {} for not participating in {topic:?} attempt {prior_attempt}",
+            hex::encode(did_not_participate.to_bytes()),
+          );
+          missed_attempt(self.txn, genesis, did_not_participate.to_bytes());
+        }
       }
 
       /*
@@ -470,13 +495,14 @@ impl<
               frost::ThresholdParams::new(t, self.spec.n(&removed), our_i.start).unwrap();
             let shares = u16::from(our_i.end) - u16::from(our_i.start);
 
-            self
-              .processors
-              .send(
-                self.spec.set().network,
-                processor_messages::key_gen::CoordinatorMessage::GenerateKey { id, params, shares },
-              )
-              .await;
+            send_dkg_message(
+              self.txn,
+              self.processors,
+              self.spec,
+              attempt,
+              processor_messages::key_gen::CoordinatorMessage::GenerateKey { id, params, shares },
+            )
+            .await;
           }
         }
         Topic::DkgConfirmation => unreachable!(),
@@ -697,6 +723,8 @@ pub(crate) async fn handle_new_blocks<
     let mut db_clone = db.clone();
     let mut txn = db_clone.txn();
     TributaryBlockNumber::set(&mut txn, next, &block_number);
+    // Track who participated in consensus for this block
+    observe_commit(&mut txn, spec, &tributary.parsed_commit(&next).unwrap().validators);
     (TributaryBlockHandler {
       db,
       txn: &mut txn,
@@ -715,6 +743,7 @@ pub(crate) async fn handle_new_blocks<
     last_block = next;
     LastHandledBlock::set(&mut txn, genesis, &next);
     txn.commit();
+    crate::metrics::set_tributary_height(spec.set(), u64::from(block_number));
   }
 }
 
@@ -730,9 +759,16 @@ pub(crate) async fn scan_tributaries_task<
   processors: Pro,
   serai: Arc<Serai>,
   mut tributary_event: broadcast::Receiver<crate::TributaryEvent<D, P>>,
+  replay_parallelism: Option<NonZeroUsize>,
 ) {
   log::info!("scanning tributaries");
 
+  // The Tributaries are replayed concurrently, as they're independent of each other. This bounds
+  // how many may replay at once, so a validator active in many sets doesn't exhaust its resources
+  // replaying all of them when it restarts.
+  let replay_permits =
+    Arc::new(Semaphore::new(replay_parallelism.map_or(Semaphore::MAX_PERMITS, NonZeroUsize::get)));
+
   loop {
     match tributary_event.recv().await {
       Ok(crate::TributaryEvent::NewTributary(crate::ActiveTributary { spec, tributary })) => {
@@ -743,13 +779,17 @@ pub(crate) async fn scan_tributaries_task<
           let recognized_id = recognized_id.clone();
           let processors = processors.clone();
           let serai = serai.clone();
+          let replay_permits = replay_permits.clone();
           async move {
             let spec = &spec;
             let reader = tributary.reader();
             let mut tributary_db = raw_db.clone();
+            // Held until the blocks which existed when this task started have been handled
+            let mut replay_permit = Some(replay_permits.acquire_owned().await.unwrap());
             loop {
               // Check if the set was retired, and if so, don't further operate
               if crate::db::RetiredTributaryDb::get(&raw_db, spec.set()).is_some() {
+                crate::metrics::remove_tributary(spec.set());
                 break;
               }
 
@@ -782,6 +822,8 @@ pub(crate) async fn scan_tributaries_task<
                 &reader,
               )
               .await;
+              // Release our permit to replay, as we're now handling blocks as they occur
+              replay_permit.take();
 
               // Run either when the notification fires, or every interval of block_time
               let _ = tokio::time::timeout(
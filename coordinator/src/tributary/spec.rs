@@ -68,6 +68,10 @@ impl TributarySpec {
     Self { serai_block, start_time, set, validators }
   }
 
+  pub fn serai_block(&self) -> [u8; 32] {
+    self.serai_block
+  }
+
   pub fn set(&self) -> ExternalValidatorSet {
     self.set
   }
@@ -1,16 +1,17 @@
-use std::collections::{VecDeque, HashSet};
+use std::collections::{VecDeque, HashSet, BTreeMap};
 
 use ciphersuite::{group::GroupEncoding, Ciphersuite, Ristretto};
 
 use serai_db::{Get, DbTxn, Db};
 
-use scale::Decode;
+use scale::{Encode, Decode};
 
 use tendermint::ext::{Network, Commit};
 
 use crate::{
   ReadWrite, ProvidedError, ProvidedTransactions, BlockError, Block, Mempool, Transaction,
   transaction::{Signed, TransactionKind, TransactionError, Transaction as TransactionTrait},
+  snapshot::{TributarySnapshot, SnapshotError},
 };
 
 #[derive(Debug)]
@@ -32,8 +33,8 @@ impl<D: Db, T: TransactionTrait> Blockchain<D, T> {
   fn tip_key(genesis: [u8; 32]) -> Vec<u8> {
     D::key(b"tributary_blockchain", b"tip", genesis)
   }
-  fn block_number_key(&self) -> Vec<u8> {
-    D::key(b"tributary_blockchain", b"block_number", self.genesis)
+  fn block_number_key(genesis: [u8; 32]) -> Vec<u8> {
+    D::key(b"tributary_blockchain", b"block_number", genesis)
   }
   fn block_key(genesis: &[u8], hash: &[u8; 32]) -> Vec<u8> {
     D::key(b"tributary_blockchain", b"block", [genesis, hash].concat())
@@ -53,6 +54,9 @@ impl<D: Db, T: TransactionTrait> Blockchain<D, T> {
   fn provided_included_key(genesis: &[u8], hash: &[u8; 32]) -> Vec<u8> {
     D::key(b"tributary_blockchain", b"provided_included", [genesis, hash].concat())
   }
+  fn snapshot_key(genesis: &[u8]) -> Vec<u8> {
+    D::key(b"tributary_blockchain", b"snapshot", genesis)
+  }
   fn next_nonce_key(
     genesis: &[u8; 32],
     signer: &<Ristretto as Ciphersuite>::G,
@@ -86,7 +90,8 @@ impl<D: Db, T: TransactionTrait> Blockchain<D, T> {
 
     if let Some((block_number, tip)) = {
       let db = res.db.as_ref().unwrap();
-      db.get(res.block_number_key()).map(|number| (number, db.get(Self::tip_key(genesis)).unwrap()))
+      db.get(Self::block_number_key(genesis))
+        .map(|number| (number, db.get(Self::tip_key(genesis)).unwrap()))
     } {
       res.block_number = u64::from_le_bytes(block_number.try_into().unwrap());
       res.tip.copy_from_slice(&tip);
@@ -151,6 +156,193 @@ impl<D: Db, T: TransactionTrait> Blockchain<D, T> {
     db.get(Self::tip_key(genesis)).map_or(genesis, |bytes| bytes.try_into().unwrap())
   }
 
+  /// Delete a Tributary's blocks, commits, and the indexes and state derived from them.
+  ///
+  /// This MUST only be called for a Tributary which will never again be created with
+  /// `Blockchain::new`.
+  pub(crate) fn prune(db: &mut D, genesis: [u8; 32]) {
+    let Some(block_number) = db
+      .get(Self::block_number_key(genesis))
+      .map(|number| u64::from_le_bytes(number.try_into().unwrap()))
+    else {
+      return;
+    };
+
+    // Prune block-by-block, so the size of each transaction is bounded
+    for number in 1 ..= block_number {
+      // Blocks prior to an imported snapshot won't be present
+      let Some(hash) = Self::block_hash_from_db(db, genesis, number) else { continue };
+      let block = Self::block_from_db(db, genesis, &hash);
+
+      let mut txn = db.txn();
+      if let Some(block) = block {
+        txn.del(Self::block_after_key(&genesis, &block.parent()));
+        for tx in &block.transactions {
+          match tx.kind() {
+            TransactionKind::Provided(order) => {
+              txn.del(Self::provided_included_key(&genesis, &tx.hash()));
+              txn.del(ProvidedTransactions::<D, T>::block_provided_quantity_key(
+                &genesis, &hash, order,
+              ));
+            }
+            TransactionKind::Unsigned => {
+              txn.del(Self::unsigned_included_key(&genesis, &tx.hash()));
+            }
+            TransactionKind::Signed(order, Signed { signer, .. }) => {
+              txn.del(Self::next_nonce_key(&genesis, signer, &order));
+            }
+          }
+        }
+      }
+      txn.del(Self::block_key(&genesis, &hash));
+      txn.del(Self::commit_key(&genesis, &hash));
+      txn.del(Self::block_hash_key(&genesis, number));
+      txn.commit();
+    }
+
+    // Delete the tip last, so an interrupted prune will be redone in full
+    let mut txn = db.txn();
+    txn.del(Self::tip_key(genesis));
+    txn.del(Self::block_number_key(genesis));
+    txn.del(Self::snapshot_key(&genesis));
+    txn.commit();
+  }
+
+  /// Build a snapshot as of the specified block.
+  ///
+  /// This builds off the latest snapshot built or imported, if it's prior to the specified block,
+  /// and saves the built snapshot for future snapshots to build off of.
+  pub(crate) fn snapshot(
+    db: &D,
+    genesis: [u8; 32],
+    block_number: u64,
+  ) -> Option<TributarySnapshot> {
+    let base = db
+      .get(Self::snapshot_key(&genesis))
+      .map(|bytes| TributarySnapshot::decode(&mut bytes.as_slice()).unwrap())
+      .filter(|base| base.block_number <= block_number);
+    if let Some(base) = base.as_ref().filter(|base| base.block_number == block_number) {
+      return Some(base.clone());
+    }
+
+    let (mut next_nonces, mut unsigned_included, mut provided_included, start) = match base {
+      Some(base) => (
+        base
+          .next_nonces
+          .into_iter()
+          .map(|(signer, order, nonce)| ((signer, order), nonce))
+          .collect(),
+        base.unsigned_included,
+        base.provided_included.into_iter().collect(),
+        base.block_number + 1,
+      ),
+      None => (BTreeMap::new(), vec![], BTreeMap::new(), 1),
+    };
+
+    let mut hash = None;
+    for number in start ..= block_number {
+      hash = Some(Self::block_hash_from_db(db, genesis, number)?);
+      let block = Self::block_from_db(db, genesis, hash.as_ref().unwrap())?;
+      for tx in &block.transactions {
+        match tx.kind() {
+          TransactionKind::Provided(order) => {
+            provided_included.entry(order.as_bytes().to_vec()).or_insert(vec![]).push(tx.hash());
+          }
+          TransactionKind::Unsigned => unsigned_included.push(tx.hash()),
+          TransactionKind::Signed(order, Signed { signer, nonce, .. }) => {
+            next_nonces.insert((signer.to_bytes(), order), nonce + 1);
+          }
+        }
+      }
+    }
+    let hash = hash?;
+
+    let snapshot = TributarySnapshot {
+      block_number,
+      block: db.get(Self::block_key(&genesis, &hash))?,
+      commit: Self::commit_from_db(db, genesis, &hash)?,
+      next_nonces: next_nonces
+        .into_iter()
+        .map(|((signer, order), nonce)| (signer, order, nonce))
+        .collect(),
+      unsigned_included,
+      provided_included: provided_included.into_iter().collect(),
+    };
+
+    let mut db = db.clone();
+    let mut txn = db.txn();
+    txn.put(Self::snapshot_key(&genesis), snapshot.encode());
+    txn.commit();
+
+    Some(snapshot)
+  }
+
+  /// Import a snapshot which was already verified, with the hash of its block.
+  pub(crate) fn import_snapshot(
+    db: &mut D,
+    genesis: [u8; 32],
+    tip: [u8; 32],
+    snapshot: &TributarySnapshot,
+  ) -> Result<(), SnapshotError> {
+    let block_number = db
+      .get(Self::block_number_key(genesis))
+      .map_or(0, |number| u64::from_le_bytes(number.try_into().unwrap()));
+    if block_number >= snapshot.block_number {
+      Err(SnapshotError::NotBehind)?;
+    }
+    let block = Block::<T>::read::<&[u8]>(&mut snapshot.block.as_ref()).unwrap();
+
+    let mut txn = db.txn();
+
+    txn.put(Self::tip_key(genesis), tip);
+    txn.put(Self::block_number_key(genesis), snapshot.block_number.to_le_bytes());
+    txn.put(Self::block_hash_key(&genesis, snapshot.block_number), tip);
+    txn.put(Self::block_key(&genesis, &tip), &snapshot.block);
+    txn.put(Self::commit_key(&genesis, &tip), &snapshot.commit);
+
+    for (signer, order, nonce) in &snapshot.next_nonces {
+      let signer = Ristretto::read_G::<&[u8]>(&mut signer.as_ref())
+        .map_err(|_| SnapshotError::InvalidState)?;
+      txn.put(Self::next_nonce_key(&genesis, &signer, order), nonce.to_le_bytes());
+    }
+    for hash in &snapshot.unsigned_included {
+      txn.put(Self::unsigned_included_key(&genesis, hash), []);
+    }
+    for (order, hashes) in &snapshot.provided_included {
+      let order = core::str::from_utf8(order).map_err(|_| SnapshotError::InvalidState)?;
+      for (id, hash) in hashes.iter().enumerate() {
+        txn.put(Self::provided_included_key(&genesis, hash), []);
+        txn.put(
+          ProvidedTransactions::<D, T>::on_chain_provided_key(
+            &genesis,
+            order,
+            u32::try_from(id).unwrap(),
+          ),
+          hash,
+        );
+      }
+      let quantity = u32::try_from(hashes.len()).unwrap().to_le_bytes();
+      txn.put(
+        ProvidedTransactions::<D, T>::on_chain_provided_quantity_key(&genesis, order),
+        quantity,
+      );
+      if block
+        .transactions
+        .iter()
+        .any(|tx| matches!(tx.kind(), TransactionKind::Provided(tx_order) if tx_order == order))
+      {
+        txn.put(
+          ProvidedTransactions::<D, T>::block_provided_quantity_key(&genesis, &tip, order),
+          quantity,
+        );
+      }
+    }
+
+    txn.put(Self::snapshot_key(&genesis), snapshot.encode());
+    txn.commit();
+    Ok(())
+  }
+
   pub(crate) fn add_transaction<N: Network>(
     &mut self,
     internal: bool,
@@ -298,7 +490,7 @@ impl<D: Db, T: TransactionTrait> Blockchain<D, T> {
     txn.put(Self::tip_key(self.genesis), self.tip);
 
     self.block_number += 1;
-    txn.put(self.block_number_key(), self.block_number.to_le_bytes());
+    txn.put(Self::block_number_key(self.genesis), self.block_number.to_le_bytes());
 
     txn.put(Self::block_hash_key(&self.genesis, self.block_number), self.tip);
 
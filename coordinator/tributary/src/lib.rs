@@ -11,7 +11,7 @@ use scale::Decode;
 use futures_channel::mpsc::UnboundedReceiver;
 use futures_util::{StreamExt, SinkExt};
 use ::tendermint::{
-  ext::{BlockNumber, Commit, Block as BlockTrait, Network},
+  ext::{BlockNumber, Commit, Signer as SignerTrait, Block as BlockTrait, Network},
   SignedMessageFor, SyncedBlock, SyncedBlockSender, SyncedBlockResultReceiver, MessageSender,
   TendermintMachine, TendermintHandle,
 };
@@ -43,6 +43,11 @@ pub(crate) use blockchain::*;
 mod mempool;
 pub(crate) use mempool::*;
 
+pub mod snapshot;
+use snapshot::{
+  SNAPSHOT_INTERVAL, TributarySnapshot, SnapshotAttestation, SignedSnapshot, SnapshotError,
+};
+
 pub mod tendermint;
 pub(crate) use crate::tendermint::*;
 
@@ -303,6 +308,60 @@ impl<D: Db, T: TransactionTrait, P: P2p> Tributary<D, T, P> {
     self.sync_block_internal(block, commit, &mut result).await
   }
 
+  /// A snapshot of this Tributary as of the latest multiple of the snapshot interval, attested to
+  /// by us.
+  ///
+  /// Returns None if we haven't reached the first snapshot interval.
+  pub async fn snapshot(&self) -> Option<SignedSnapshot> {
+    let block_number = (self.block_number().await / SNAPSHOT_INTERVAL) * SNAPSHOT_INTERVAL;
+    if block_number == 0 {
+      return None;
+    }
+    let snapshot = Blockchain::<D, T>::snapshot(&self.db, self.genesis, block_number)?;
+    let signer = self.network.signer();
+    let attestation = SnapshotAttestation {
+      validator: signer.validator_id().await?,
+      signature: signer.sign(&snapshot.attestation_message(self.genesis)).await,
+    };
+    Some(SignedSnapshot { snapshot, attestation })
+  }
+
+  /// Import a snapshot, allowing a Tributary which is behind to continue from it instead of
+  /// syncing every block prior.
+  ///
+  /// The snapshot's block must have a valid commit and the snapshot must be attested to by enough
+  /// validators that at least one honest validator attested to it.
+  ///
+  /// This MUST be called before the Tributary is created with `Tributary::new`. Blocks prior to
+  /// the snapshot's block will not be present.
+  pub fn import_snapshot(
+    mut db: D,
+    genesis: [u8; 32],
+    validators: Vec<(<Ristretto as Ciphersuite>::G, u64)>,
+    snapshot: &TributarySnapshot,
+    attestations: &[SnapshotAttestation],
+  ) -> Result<(), SnapshotError> {
+    let validators =
+      Validators::new(genesis, validators).ok_or(SnapshotError::InvalidValidators)?;
+    let tip = snapshot::verify_snapshot::<T>(genesis, &validators, snapshot, attestations)?;
+    Blockchain::<D, T>::import_snapshot(&mut db, genesis, tip, snapshot)?;
+    log::info!(
+      "imported snapshot of tributary {} as of block {}",
+      hex::encode(genesis),
+      snapshot.block_number,
+    );
+    Ok(())
+  }
+
+  /// Delete a Tributary's blocks and commits from the database.
+  ///
+  /// This MUST only be called for a Tributary which has been dropped and will never again be
+  /// created with `Tributary::new`.
+  pub fn prune(mut db: D, genesis: [u8; 32]) {
+    Blockchain::<D, T>::prune(&mut db, genesis);
+    log::info!("pruned tributary {}", hex::encode(genesis));
+  }
+
   // Return true if the message should be rebroadcasted.
   pub async fn handle_message(&self, msg: &[u8]) -> bool {
     match msg.first() {
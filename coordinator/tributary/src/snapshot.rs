@@ -0,0 +1,156 @@
+use std::collections::HashSet;
+
+use thiserror::Error;
+
+use blake2::{Digest, Blake2s256};
+
+use scale::{Encode, Decode};
+use ::tendermint::{
+  commit_msg,
+  ext::{SignatureScheme, Weights, Commit},
+};
+
+use crate::{ReadWrite, Block, transaction::Transaction as TransactionTrait, Validators};
+
+/// The interval, in blocks, snapshots are taken at.
+///
+/// Validators only attest to snapshots taken at a multiple of this interval, so validators at
+/// distinct (yet similar) tips will attest to the same snapshot.
+pub const SNAPSHOT_INTERVAL: u64 = 600;
+
+const SNAPSHOT_SIGNATURE_DST: &[u8] = b"Tributary Snapshot Attestation";
+
+/// The state of a Tributary as of a block, sufficient to continue the Tributary from said block.
+#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode)]
+pub struct TributarySnapshot {
+  /// The number of the block this is a snapshot as of.
+  pub block_number: u64,
+  /// The block this is a snapshot as of, serialized.
+  pub block: Vec<u8>,
+  /// The commit for the block this is a snapshot as of.
+  pub commit: Vec<u8>,
+  /// The next nonce for each signer, by order, sorted by signer and order.
+  pub next_nonces: Vec<([u8; 32], Vec<u8>, u32)>,
+  /// The hashes of the unsigned transactions included on-chain, in the order they were included.
+  pub unsigned_included: Vec<[u8; 32]>,
+  /// The hashes of the provided transactions included on-chain, by order, in the order they were
+  /// included.
+  pub provided_included: Vec<(Vec<u8>, Vec<[u8; 32]>)>,
+}
+
+impl TributarySnapshot {
+  /// The hash of this snapshot, as attested to.
+  pub fn hash(&self, genesis: [u8; 32]) -> [u8; 32] {
+    Blake2s256::digest([b"tributary_snapshot".as_ref(), &genesis, &self.encode()].concat()).into()
+  }
+
+  pub(crate) fn attestation_message(&self, genesis: [u8; 32]) -> Vec<u8> {
+    [SNAPSHOT_SIGNATURE_DST, &self.hash(genesis)].concat()
+  }
+}
+
+/// A validator's attestation to a snapshot.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Encode, Decode)]
+pub struct SnapshotAttestation {
+  /// The validator attesting to the snapshot.
+  pub validator: [u8; 32],
+  /// The validator's signature for the snapshot.
+  pub signature: [u8; 64],
+}
+
+/// A snapshot, as served by a validator, with the validator's attestation to it.
+#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode)]
+pub struct SignedSnapshot {
+  pub snapshot: TributarySnapshot,
+  pub attestation: SnapshotAttestation,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Error)]
+pub enum SnapshotError {
+  /// The validators specified for the Tributary were invalid.
+  #[error("invalid validators")]
+  InvalidValidators,
+  /// The snapshot's block was invalid.
+  #[error("snapshot's block was invalid")]
+  InvalidBlock,
+  /// The snapshot's commit was invalid for its block.
+  #[error("snapshot's commit was invalid")]
+  InvalidCommit,
+  /// The snapshot's state was invalid.
+  #[error("snapshot's state was invalid")]
+  InvalidState,
+  /// An attestation was invalid.
+  #[error("attestation by {} was invalid", hex::encode(.0))]
+  InvalidAttestation([u8; 32]),
+  /// The snapshot wasn't attested to by enough validators.
+  #[error("snapshot was attested to by {attested} weight, needed {needed}")]
+  InsufficientAttestations { attested: u64, needed: u64 },
+  /// The local Tributary wasn't behind the snapshot.
+  #[error("the local Tributary wasn't behind the snapshot")]
+  NotBehind,
+}
+
+/// Verify a snapshot, returning its block's hash.
+///
+/// The commit must be valid for the snapshot's block, and the snapshot must be attested to by
+/// validators whose weight exceeds the fault threshold, ensuring at least one honest validator
+/// attested to it.
+pub(crate) fn verify_snapshot<T: TransactionTrait>(
+  genesis: [u8; 32],
+  validators: &Validators,
+  snapshot: &TributarySnapshot,
+  attestations: &[SnapshotAttestation],
+) -> Result<[u8; 32], SnapshotError> {
+  if snapshot.block_number == 0 {
+    Err(SnapshotError::InvalidBlock)?;
+  }
+  let block = Block::<T>::read::<&[u8]>(&mut snapshot.block.as_ref())
+    .map_err(|_| SnapshotError::InvalidBlock)?;
+  if block.serialize() != snapshot.block {
+    Err(SnapshotError::InvalidBlock)?;
+  }
+  let hash = block.hash();
+
+  let mut commit_ref = snapshot.commit.as_ref();
+  let commit =
+    Commit::<Validators>::decode(&mut commit_ref).map_err(|_| SnapshotError::InvalidCommit)?;
+  if !commit_ref.is_empty() ||
+    (commit.validators.iter().collect::<HashSet<_>>().len() != commit.validators.len()) ||
+    (!commit.validators.iter().all(|validator| validators.is_validator(validator))) ||
+    (!validators.verify_aggregate(
+      &commit.validators,
+      &commit_msg(commit.end_time, hash.as_ref()),
+      &commit.signature,
+    )) ||
+    (commit.validators.iter().map(|validator| validators.weight(*validator)).sum::<u64>() <
+      validators.threshold())
+  {
+    Err(SnapshotError::InvalidCommit)?;
+  }
+
+  for (order, _) in &snapshot.provided_included {
+    if core::str::from_utf8(order).is_err() {
+      Err(SnapshotError::InvalidState)?;
+    }
+  }
+
+  let msg = snapshot.attestation_message(genesis);
+  let mut attested = HashSet::new();
+  let mut attested_weight = 0;
+  for attestation in attestations {
+    if !validators.verify(attestation.validator, &msg, &attestation.signature) {
+      Err(SnapshotError::InvalidAttestation(attestation.validator))?;
+    }
+    if attested.insert(attestation.validator) {
+      attested_weight += validators.weight(attestation.validator);
+    }
+  }
+  if attested_weight < validators.fault_threshold() {
+    Err(SnapshotError::InsufficientAttestations {
+      attested: attested_weight,
+      needed: validators.fault_threshold(),
+    })?;
+  }
+
+  Ok(hash)
+}
@@ -1,5 +1,10 @@
 use core::ops::Deref;
-use std::{sync::Arc, collections::HashMap};
+use std::{
+  sync::{Arc, OnceLock},
+  collections::HashMap,
+};
+
+use thiserror::Error;
 
 use async_trait::async_trait;
 
@@ -155,6 +160,10 @@ impl Validators {
 
     Some(Validators { genesis, total_weight, weights, robin })
   }
+
+  pub(crate) fn is_validator(&self, validator: &[u8; 32]) -> bool {
+    self.weights.contains_key(validator)
+  }
 }
 
 impl SignatureScheme for Validators {
@@ -270,9 +279,58 @@ pub struct TendermintNetwork<D: Db, T: TransactionTrait, P: P2p> {
   pub(crate) p2p: P,
 }
 
-pub const BLOCK_PROCESSING_TIME: u32 = 999;
-pub const LATENCY_TIME: u32 = 1667;
-pub const TARGET_BLOCK_TIME: u32 = BLOCK_PROCESSING_TIME + (3 * LATENCY_TIME);
+/// The default block time, in seconds.
+pub const DEFAULT_BLOCK_TIME: u32 = 6;
+/// The minimum configurable block time, in seconds.
+pub const MIN_BLOCK_TIME: u32 = 1;
+/// The maximum configurable block time, in seconds.
+pub const MAX_BLOCK_TIME: u32 = 60;
+
+static BLOCK_TIME: OnceLock<u32> = OnceLock::new();
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Error)]
+pub enum BlockTimeError {
+  #[error("block time was outside of the allowed bounds")]
+  OutOfBounds,
+  #[error("block time was already set or used")]
+  AlreadySet,
+}
+
+/// Set the block time, in seconds, used by every Tributary in this process.
+///
+/// All validators on a Tributary must use the same block time. This must be called before any
+/// Tributary is created (or the block time is otherwise read), as it can't be changed afterwards.
+pub fn set_block_time(block_time: u32) -> Result<(), BlockTimeError> {
+  if !(MIN_BLOCK_TIME ..= MAX_BLOCK_TIME).contains(&block_time) {
+    Err(BlockTimeError::OutOfBounds)?;
+  }
+  BLOCK_TIME.set(block_time).map_err(|_| BlockTimeError::AlreadySet)
+}
+
+/// Split a block time, in seconds, into the block processing time and latency time, in
+/// milliseconds.
+///
+/// The latency time is five-sixths of the block time, divided by three (rounding up), with the
+/// block processing time being the remainder. A six-second block time has a processing time of
+/// 999ms and a latency time of 1667ms.
+pub fn split_block_time(block_time: u32) -> (u32, u32) {
+  let block_time = block_time * 1000;
+  let latency_time = ((block_time * 5) / 6).div_ceil(3);
+  (block_time - (3 * latency_time), latency_time)
+}
+
+/// The block processing time, in milliseconds.
+pub fn block_processing_time() -> u32 {
+  split_block_time(*BLOCK_TIME.get_or_init(|| DEFAULT_BLOCK_TIME)).0
+}
+/// The latency time, in milliseconds.
+pub fn latency_time() -> u32 {
+  split_block_time(*BLOCK_TIME.get_or_init(|| DEFAULT_BLOCK_TIME)).1
+}
+/// The target block time, in milliseconds.
+pub fn target_block_time() -> u32 {
+  block_processing_time() + (3 * latency_time())
+}
 
 #[async_trait]
 impl<D: Db, T: TransactionTrait, P: P2p> Network for TendermintNetwork<D, T, P> {
@@ -283,12 +341,16 @@ impl<D: Db, T: TransactionTrait, P: P2p> Network for TendermintNetwork<D, T, P>
   type Weights = Arc<Validators>;
   type Block = TendermintBlock;
 
-  // These are in milliseconds and create a six-second block time.
+  // These are in milliseconds and create a six-second block time by default.
   // The block time is the latency on message delivery (where a message is some piece of data
   // embedded in a transaction) times three plus the block processing time, hence why it should be
   // kept low.
-  const BLOCK_PROCESSING_TIME: u32 = BLOCK_PROCESSING_TIME;
-  const LATENCY_TIME: u32 = LATENCY_TIME;
+  fn block_processing_time() -> u32 {
+    block_processing_time()
+  }
+  fn latency_time() -> u32 {
+    latency_time()
+  }
 
   fn signer(&self) -> Arc<Signer> {
     self.signer.clone()
@@ -58,6 +58,41 @@ fn block_addition() {
   );
 }
 
+#[test]
+fn prune() {
+  let genesis = new_genesis();
+  let validators = Arc::new(Validators::new(genesis, vec![]).unwrap());
+  let (mut db, mut blockchain) = new_blockchain::<SignedTransaction>(genesis, &[]);
+
+  let mut hashes = vec![];
+  for _ in 0 .. 3 {
+    let block = blockchain.build_block::<N>(&validators);
+    blockchain.add_block::<N>(&block, vec![], &validators).unwrap();
+    hashes.push(block.hash());
+  }
+
+  // Pruning another Tributary shouldn't affect this one
+  Blockchain::<MemDb, SignedTransaction>::prune(&mut db, new_genesis());
+  assert_eq!(Blockchain::<MemDb, SignedTransaction>::tip_from_db(&db, genesis), hashes[2]);
+
+  Blockchain::<MemDb, SignedTransaction>::prune(&mut db, genesis);
+  assert_eq!(Blockchain::<MemDb, SignedTransaction>::tip_from_db(&db, genesis), genesis);
+  assert!(Blockchain::<MemDb, SignedTransaction>::block_after(&db, genesis, &genesis).is_none());
+  for (i, hash) in hashes.iter().enumerate() {
+    let number = u64::try_from(i + 1).unwrap();
+    assert!(
+      Blockchain::<MemDb, SignedTransaction>::block_hash_from_db(&db, genesis, number).is_none()
+    );
+    assert!(Blockchain::<MemDb, SignedTransaction>::block_from_db(&db, genesis, hash).is_none());
+    assert!(Blockchain::<MemDb, SignedTransaction>::commit_from_db(&db, genesis, hash).is_none());
+  }
+
+  // A fresh Blockchain shouldn't pick up any of the pruned state
+  let blockchain = Blockchain::<MemDb, SignedTransaction>::new(db, genesis, &[]);
+  assert_eq!(blockchain.tip(), genesis);
+  assert_eq!(blockchain.block_number(), 0);
+}
+
 #[test]
 fn invalid_block() {
   let genesis = new_genesis();
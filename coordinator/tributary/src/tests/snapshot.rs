@@ -0,0 +1,160 @@
+use core::ops::Deref;
+use std::sync::Arc;
+
+use zeroize::Zeroizing;
+use rand::rngs::OsRng;
+
+use ciphersuite::{
+  group::{ff::Field, GroupEncoding},
+  Ciphersuite, Ristretto,
+};
+
+use scale::Encode;
+use ::tendermint::{
+  commit_msg,
+  ext::{Signer as SignerTrait, SignatureScheme, Commit},
+};
+
+use serai_db::MemDb;
+
+use crate::{
+  Tributary, Transaction, Block, Blockchain,
+  snapshot::{TributarySnapshot, SnapshotAttestation, SnapshotError},
+  tendermint::{TendermintNetwork, Validators, Signer},
+  tests::{SignedTransaction, p2p::DummyP2p, new_genesis, signed_transaction},
+};
+
+type N = TendermintNetwork<MemDb, SignedTransaction, DummyP2p>;
+type T = Tributary<MemDb, SignedTransaction, DummyP2p>;
+
+async fn sign_commit(
+  validators: &Validators,
+  signers: &[Signer],
+  end_time: u64,
+  block: &Block<SignedTransaction>,
+) -> Vec<u8> {
+  let msg = commit_msg(end_time, block.hash().as_ref());
+  let mut ids = vec![];
+  let mut sigs = vec![];
+  for signer in signers {
+    ids.push(signer.validator_id().await.unwrap());
+    sigs.push(signer.sign(&msg).await);
+  }
+  let signature = validators.aggregate(&ids, &msg, &sigs);
+  Commit::<Arc<Validators>> { end_time, validators: ids, signature }.encode()
+}
+
+async fn attest(
+  genesis: [u8; 32],
+  signer: &Signer,
+  snapshot: &TributarySnapshot,
+) -> SnapshotAttestation {
+  SnapshotAttestation {
+    validator: signer.validator_id().await.unwrap(),
+    signature: signer.sign(&snapshot.attestation_message(genesis)).await,
+  }
+}
+
+#[tokio::test]
+async fn snapshot() {
+  let genesis = new_genesis();
+
+  let keys = (0 .. 4)
+    .map(|_| Zeroizing::new(<Ristretto as Ciphersuite>::F::random(&mut OsRng)))
+    .collect::<Vec<_>>();
+  let validators_vec =
+    keys.iter().map(|key| (Ristretto::generator() * key.deref(), 1)).collect::<Vec<_>>();
+  let validators = Arc::new(Validators::new(genesis, validators_vec.clone()).unwrap());
+  let signers = keys.iter().map(|key| Signer::new(genesis, key.clone())).collect::<Vec<_>>();
+
+  let tx_key = Zeroizing::new(<Ristretto as Ciphersuite>::F::random(&mut OsRng));
+  let tx_signer = Ristretto::generator() * tx_key.deref();
+
+  // Build a chain of three blocks, each with a signed transaction
+  let db = MemDb::new();
+  let mut blockchain =
+    Blockchain::<MemDb, SignedTransaction>::new(db.clone(), genesis, &[tx_signer]);
+  for nonce in 0 .. 3 {
+    let tx = signed_transaction(&mut OsRng, genesis, &tx_key, nonce);
+    blockchain.add_transaction::<N>(true, Transaction::Application(tx), &validators).unwrap();
+    let block = blockchain.build_block::<N>(&validators);
+    let commit = sign_commit(&validators, &signers[.. 3], nonce.into(), &block).await;
+    blockchain.add_block::<N>(&block, commit, &validators).unwrap();
+  }
+
+  let snapshot = Blockchain::<MemDb, SignedTransaction>::snapshot(&db, genesis, 3).unwrap();
+  assert_eq!(snapshot.block_number, 3);
+  assert_eq!(snapshot.next_nonces, vec![(tx_signer.to_bytes(), vec![], 3)]);
+  // Building off a prior snapshot produces the same snapshot as building from scratch
+  assert_eq!(
+    Blockchain::<MemDb, SignedTransaction>::snapshot(&db, genesis, 2).unwrap().block_number,
+    2
+  );
+  assert_eq!(Blockchain::<MemDb, SignedTransaction>::snapshot(&db, genesis, 3).unwrap(), snapshot);
+
+  let mut attestations = vec![];
+  for signer in &signers {
+    attestations.push(attest(genesis, signer, &snapshot).await);
+  }
+
+  let import = |db: &MemDb, snapshot: &TributarySnapshot, attestations: &[SnapshotAttestation]| {
+    T::import_snapshot(db.clone(), genesis, validators_vec.clone(), snapshot, attestations)
+  };
+
+  // A snapshot requires attestations from more than a third of the validators
+  let new_db = MemDb::new();
+  assert_eq!(
+    import(&new_db, &snapshot, &attestations[.. 1]),
+    Err(SnapshotError::InsufficientAttestations { attested: 1, needed: 2 })
+  );
+  // Which can't be achieved by repeating an attestation
+  assert_eq!(
+    import(&new_db, &snapshot, &[attestations[0], attestations[0]]),
+    Err(SnapshotError::InsufficientAttestations { attested: 1, needed: 2 })
+  );
+  // Attestations must be valid
+  let mut forged = attestations[1];
+  forged.validator = attestations[2].validator;
+  assert_eq!(
+    import(&new_db, &snapshot, &[attestations[0], forged]),
+    Err(SnapshotError::InvalidAttestation(forged.validator))
+  );
+  // And for the snapshot in question
+  let mut tampered = snapshot.clone();
+  tampered.next_nonces[0].2 += 1;
+  assert_eq!(
+    import(&new_db, &tampered, &attestations[.. 2]),
+    Err(SnapshotError::InvalidAttestation(attestations[0].validator))
+  );
+  // The commit must be valid, even if the snapshot was attested to
+  let mut tampered = snapshot.clone();
+  tampered.commit =
+    sign_commit(&validators, &signers[.. 2], 2, &blockchain.build_block::<N>(&validators)).await;
+  let tampered_attestations = vec![
+    attest(genesis, &signers[0], &tampered).await,
+    attest(genesis, &signers[1], &tampered).await,
+  ];
+  assert_eq!(import(&new_db, &tampered, &tampered_attestations), Err(SnapshotError::InvalidCommit));
+
+  // Import the snapshot
+  import(&new_db, &snapshot, &attestations[2 ..]).unwrap();
+  let mut imported =
+    Blockchain::<MemDb, SignedTransaction>::new(new_db.clone(), genesis, &[tx_signer]);
+  assert_eq!(imported.tip(), blockchain.tip());
+  assert_eq!(imported.block_number(), 3);
+  assert_eq!(imported.next_nonce(&tx_signer, &[]), Some(3));
+  assert_eq!(
+    Blockchain::<MemDb, SignedTransaction>::snapshot(&new_db, genesis, 3),
+    Some(snapshot.clone())
+  );
+  assert_eq!(import(&new_db, &snapshot, &attestations), Err(SnapshotError::NotBehind));
+
+  // The imported chain can continue, with future snapshots building off the imported one
+  let tx = signed_transaction(&mut OsRng, genesis, &tx_key, 3);
+  imported.add_transaction::<N>(true, Transaction::Application(tx), &validators).unwrap();
+  let block = imported.build_block::<N>(&validators);
+  let commit = sign_commit(&validators, &signers[.. 3], 3, &block).await;
+  imported.add_block::<N>(&block, commit, &validators).unwrap();
+  let next = Blockchain::<MemDb, SignedTransaction>::snapshot(&new_db, genesis, 4).unwrap();
+  assert_eq!(next.next_nonces, vec![(tx_signer.to_bytes(), vec![], 4)]);
+}
@@ -1,7 +1,10 @@
 use tendermint::ext::Network;
 use crate::{
   P2p, TendermintTx,
-  tendermint::{TARGET_BLOCK_TIME, TendermintNetwork},
+  tendermint::{
+    DEFAULT_BLOCK_TIME, MIN_BLOCK_TIME, MAX_BLOCK_TIME, BlockTimeError, target_block_time,
+    set_block_time, split_block_time, TendermintNetwork,
+  },
 };
 
 #[test]
@@ -19,10 +22,27 @@ fn assert_target_block_time() {
   }
 
   // Type paremeters don't matter here since we only need to call the block_time()
-  // and it only relies on the constants of the trait implementation. block_time() is in seconds,
-  // TARGET_BLOCK_TIME is in milliseconds.
+  // and it only relies on the functions of the trait implementation. block_time() is in seconds,
+  // target_block_time() is in milliseconds.
   assert_eq!(
     <TendermintNetwork<MemDb, TendermintTx, DummyP2p> as Network>::block_time(),
-    TARGET_BLOCK_TIME / 1000
-  )
+    target_block_time() / 1000
+  );
+  assert_eq!(target_block_time(), DEFAULT_BLOCK_TIME * 1000);
+  // The block time can't be changed once used
+  assert_eq!(set_block_time(DEFAULT_BLOCK_TIME), Err(BlockTimeError::AlreadySet));
+}
+
+#[test]
+fn block_time_bounds() {
+  assert_eq!(split_block_time(DEFAULT_BLOCK_TIME), (999, 1667));
+  for block_time in MIN_BLOCK_TIME ..= MAX_BLOCK_TIME {
+    let (processing, latency) = split_block_time(block_time);
+    assert!(processing > 0);
+    assert!(latency > processing);
+    assert_eq!(processing + (3 * latency), block_time * 1000);
+  }
+
+  assert_eq!(set_block_time(MIN_BLOCK_TIME - 1), Err(BlockTimeError::OutOfBounds));
+  assert_eq!(set_block_time(MAX_BLOCK_TIME + 1), Err(BlockTimeError::OutOfBounds));
 }
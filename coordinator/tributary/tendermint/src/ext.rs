@@ -228,16 +228,20 @@ pub trait Network: Sized + Send + Sync {
   ///
   /// This should include both the time to download the block and the actual processing time.
   ///
-  /// BLOCK_PROCESSING_TIME + (3 * LATENCY_TIME) must be divisible by 1000.
-  const BLOCK_PROCESSING_TIME: u32;
+  /// block_processing_time() + (3 * latency_time()) must be divisible by 1000.
+  ///
+  /// This must return the same value for the lifetime of the machine.
+  fn block_processing_time() -> u32;
   /// Network latency time in milliseconds.
   ///
-  /// BLOCK_PROCESSING_TIME + (3 * LATENCY_TIME) must be divisible by 1000.
-  const LATENCY_TIME: u32;
+  /// block_processing_time() + (3 * latency_time()) must be divisible by 1000.
+  ///
+  /// This must return the same value for the lifetime of the machine.
+  fn latency_time() -> u32;
 
   /// The block time, in seconds. Defined as the processing time plus three times the latency.
   fn block_time() -> u32 {
-    let raw = Self::BLOCK_PROCESSING_TIME + (3 * Self::LATENCY_TIME);
+    let raw = Self::block_processing_time() + (3 * Self::latency_time());
     let res = raw / 1000;
     assert_eq!(res * 1000, raw);
     res
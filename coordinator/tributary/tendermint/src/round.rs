@@ -33,8 +33,8 @@ impl<N: Network> RoundData<N> {
   }
 
   fn timeout(&self, step: Step) -> CanonicalInstant {
-    let adjusted_block = N::BLOCK_PROCESSING_TIME * (self.number.0 + 1);
-    let adjusted_latency = N::LATENCY_TIME * (self.number.0 + 1);
+    let adjusted_block = N::block_processing_time() * (self.number.0 + 1);
+    let adjusted_latency = N::latency_time() * (self.number.0 + 1);
     let offset = Duration::from_millis(
       (match step {
         Step::Propose => adjusted_block + adjusted_latency,
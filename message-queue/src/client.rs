@@ -16,8 +16,10 @@ use tokio::{
 
 use serai_env as env;
 
-#[rustfmt::skip]
-use crate::{Service, Metadata, QueuedMessage, MessageQueueRequest, message_challenge, ack_challenge};
+use crate::{
+  Service, Metadata, QueuedMessage, MessageQueueRequest, message_challenge, ack_challenge,
+  check_key_challenge,
+};
 
 pub struct MessageQueue {
   pub service: Service,
@@ -233,4 +235,42 @@ impl MessageQueue {
       break;
     }
   }
+
+  /// Check if our key is the one the message-queue has registered for our service.
+  ///
+  /// Unlike the other methods, this doesn't retry, returning None if the message-queue couldn't
+  /// be reached.
+  pub async fn check_key(&self) -> Option<bool> {
+    let nonce = Zeroizing::new(<Ristretto as Ciphersuite>::F::random(&mut OsRng));
+    let nonce_pub = Ristretto::generator() * nonce.deref();
+    let sig = SchnorrSignature::<Ristretto>::sign(
+      &self.priv_key,
+      nonce,
+      check_key_challenge(self.service, self.pub_key, nonce_pub),
+    )
+    .serialize();
+
+    let msg = MessageQueueRequest::CheckKey { service: self.service, sig };
+    let mut socket = TcpStream::connect(&self.url).await.ok()?;
+    if !Self::send(&mut socket, msg).await {
+      None?;
+    }
+    Some(socket.read_u8().await.ok()? == 1)
+  }
+
+  /// Get the amount of messages queued from one service to another yet to be acknowledged.
+  ///
+  /// Like `check_key`, this doesn't retry, returning None if the message-queue couldn't be reached
+  /// or doesn't have such a queue.
+  pub async fn depth(&self, from: Service, to: Service) -> Option<u64> {
+    let msg = MessageQueueRequest::Depth { from, to };
+    let mut socket = TcpStream::connect(&self.url).await.ok()?;
+    if !Self::send(&mut socket, msg).await {
+      None?;
+    }
+    if socket.read_u8().await.ok()? != 1 {
+      None?;
+    }
+    socket.read_u64_le().await.ok()
+  }
 }
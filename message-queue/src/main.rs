@@ -146,6 +146,29 @@ pub(crate) fn ack_message(from: Service, to: Service, id: u64, sig: SchnorrSigna
   QUEUES.read().unwrap()[&(from, to)].write().unwrap().ack_message(id)
 }
 
+// check_key RPC method
+/*
+  Checks if a service's key is the one registered with this server.
+
+  Unlike the other authenticated methods, this returns false on failure instead of panicking, as
+  it's intended to be used to validate configurations.
+*/
+pub(crate) fn check_key(service: Service, sig: &[u8]) -> bool {
+  let Some(key) = KEYS.read().unwrap().get(&service).copied() else { return false };
+  let Ok(sig) = SchnorrSignature::<Ristretto>::read(&mut &*sig) else { return false };
+  sig.verify(key, check_key_challenge(service, key, sig.R))
+}
+
+// depth RPC method
+/*
+  Gets the amount of messages queued for the named services yet to be acknowledged.
+
+  This is not authenticated for the same reasons as next. Returns None if there's no such queue.
+*/
+pub(crate) fn get_depth(from: Service, to: Service) -> Option<u64> {
+  Some(QUEUES.read().unwrap().get(&(from, to))?.read().unwrap().depth())
+}
+
 #[tokio::main(flavor = "current_thread")]
 async fn main() {
   // Override the panic handler with one which will panic if any tokio task panics
@@ -270,6 +293,19 @@ async fn main() {
             );
             let Ok(()) = socket.write_all(&[1]).await else { break };
           }
+          MessageQueueRequest::CheckKey { service, sig } => {
+            let valid = check_key(service, &sig);
+            let Ok(()) = socket.write_all(&[u8::from(valid)]).await else { break };
+          }
+          MessageQueueRequest::Depth { from, to } => match get_depth(from, to) {
+            Some(depth) => {
+              let Ok(()) = socket.write_all(&[1]).await else { break };
+              let Ok(()) = socket.write_all(&depth.to_le_bytes()).await else { break };
+            }
+            None => {
+              let Ok(()) = socket.write_all(&[0]).await else { break };
+            }
+          },
         }
       }
     });
@@ -31,6 +31,8 @@ pub enum MessageQueueRequest {
   Queue { meta: Metadata, msg: Vec<u8>, sig: Vec<u8> },
   Next { from: Service, to: Service },
   Ack { from: Service, to: Service, id: u64, sig: Vec<u8> },
+  CheckKey { service: Service, sig: Vec<u8> },
+  Depth { from: Service, to: Service },
 }
 
 pub fn message_challenge(
@@ -72,3 +74,17 @@ pub fn ack_challenge(
   transcript.append_message(b"nonce", nonce.to_bytes());
   <Ristretto as Ciphersuite>::hash_to_F(b"ack_challenge", &transcript.challenge(b"challenge"))
 }
+
+pub fn check_key_challenge(
+  service: Service,
+  key: <Ristretto as Ciphersuite>::G,
+  nonce: <Ristretto as Ciphersuite>::G,
+) -> <Ristretto as Ciphersuite>::F {
+  let mut transcript = RecommendedTranscript::new(b"Serai Message Queue v0.1 Key Check");
+  transcript.domain_separate(b"metadata");
+  transcript.append_message(b"service", borsh::to_vec(&service).unwrap());
+  transcript.append_message(b"key", key.to_bytes());
+  transcript.domain_separate(b"signature");
+  transcript.append_message(b"nonce", nonce.to_bytes());
+  <Ristretto as Ciphersuite>::hash_to_F(b"check_key_challenge", &transcript.challenge(b"challenge"))
+}
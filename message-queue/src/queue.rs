@@ -29,6 +29,11 @@ impl<D: Db> Queue<D> {
       .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
   }
 
+  /// The amount of messages queued yet to be acknowledged.
+  pub(crate) fn depth(&self) -> u64 {
+    self.message_count() - self.last_acknowledged().map_or(0, |i| i + 1)
+  }
+
   fn message_key(&self, id: u64) -> Vec<u8> {
     Self::key(b"message", borsh::to_vec(&(self.1, self.2, id)).unwrap())
   }
@@ -30,6 +30,8 @@ fn main() {
 
     "./src/tests/contracts/Schnorr.sol",
     "./src/tests/contracts/ERC20.sol",
+    "./src/tests/contracts/WETH.sol",
+    "./src/tests/contracts/ERC677.sol",
 
     "--no-color",
   ];
@@ -18,3 +18,38 @@ interface IERC20 {
   function approve(address spender, uint256 value) external returns (bool);
   function allowance(address owner, address spender) external view returns (uint256);
 }
+
+// https://eips.ethereum.org/EIPS/eip-2612
+interface IERC20Permit {
+  function permit(
+    address owner,
+    address spender,
+    uint256 value,
+    uint256 deadline,
+    uint8 v,
+    bytes32 r,
+    bytes32 s
+  ) external;
+  function nonces(address owner) external view returns (uint256);
+  // solhint-disable-next-line func-name-mixedcase
+  function DOMAIN_SEPARATOR() external view returns (bytes32);
+}
+
+// https://github.com/ethereum/EIPs/issues/677
+interface IERC677 is IERC20 {
+  function transferAndCall(address to, uint256 value, bytes calldata data) external returns (bool);
+}
+
+// The interface called by an ERC-677 token on the recipient of a transferAndCall
+interface IERC677Receiver {
+  function onTokenTransfer(address from, uint256 value, bytes calldata data) external;
+}
+
+// The interface for WETH9, the canonical wrapped Ether contract
+interface IWETH is IERC20 {
+  event Deposit(address indexed dst, uint256 wad);
+  event Withdrawal(address indexed src, uint256 wad);
+
+  function deposit() external payable;
+  function withdraw(uint256 wad) external;
+}
@@ -7,13 +7,16 @@ import "./Schnorr.sol";
 import "./Sandbox.sol";
 
 contract Router {
-  // Nonce is incremented for each batch of transactions executed/key update
+  // Nonce is incremented for each batch of transactions executed/key update/pause update
   uint256 public nonce;
 
   // Current public key's x-coordinate
   // This key must always have the parity defined within the Schnorr contract
   bytes32 public seraiKey;
 
+  // If the acceptance of InInstructions is paused, as done for emergency response
+  bool public paused;
+
   struct OutInstruction {
     address to;
     Call[] calls;
@@ -31,6 +34,11 @@ contract Router {
     bytes32 indexed key,
     Signature signature
   );
+  event PausedUpdated(
+    uint256 indexed nonce,
+    bool indexed paused,
+    Signature signature
+  );
   event InInstruction(
     address indexed from,
     address indexed coin,
@@ -51,6 +59,7 @@ contract Router {
   error InvalidAmount();
   error FailedTransfer();
   error TooManyTransactions();
+  error Paused();
 
   modifier _updateSeraiKeyAtEndOfFn(
     uint256 _nonce,
@@ -78,6 +87,12 @@ contract Router {
     nonce = 1;
   }
 
+  // The version of the domain separation this contract verifies signatures
+  // with, letting signers detect a Router expecting a distinct challenge
+  function schnorrDomainVersion() external pure returns (uint8) {
+    return Schnorr.DOMAIN_VERSION;
+  }
+
   // updateSeraiKey validates the given Schnorr signature against the current
   // public key, and if successful, updates the contract's public key to the
   // given one.
@@ -85,13 +100,31 @@ contract Router {
     bytes32 _seraiKey,
     Signature calldata sig
   ) external _updateSeraiKeyAtEndOfFn(nonce, _seraiKey, sig) {
-    bytes memory message =
-      abi.encodePacked("updateSeraiKey", block.chainid, nonce, _seraiKey);
+    bytes memory message = abi.encodePacked(nonce, _seraiKey);
+    nonce++;
+
+    if (
+      !Schnorr.verifyWithDomain(seraiKey, "updateSeraiKey", message, sig.c, sig.s)
+    ) {
+      revert InvalidSignature();
+    }
+  }
+
+  // setPaused validates the given Schnorr signature against the current public
+  // key, and if successful, pauses/unpauses the acceptance of InInstructions.
+  function setPaused(bool _paused, Signature calldata sig) external {
+    bytes memory message = abi.encodePacked(nonce, _paused);
+    uint256 paused_with_nonce = nonce;
     nonce++;
 
-    if (!Schnorr.verify(seraiKey, message, sig.c, sig.s)) {
+    if (
+      !Schnorr.verifyWithDomain(seraiKey, "setPaused", message, sig.c, sig.s)
+    ) {
       revert InvalidSignature();
     }
+
+    paused = _paused;
+    emit PausedUpdated(paused_with_nonce, _paused, sig);
   }
 
   function inInstruction(
@@ -99,6 +132,101 @@ contract Router {
     uint256 amount,
     bytes memory instruction
   ) external payable {
+    _inInstruction(coin, amount, instruction);
+  }
+
+  // inInstructionWithPermit uses an EIP-2612 permit, signed by the sender, to
+  // approve the Router, and then performs an inInstruction, all within a single
+  // transaction
+  //
+  // This is bound to the sender, as the permit doesn't commit to the
+  // instruction. If anyone could submit a permit, anyone who saw it could
+  // submit it with their own instruction
+  function inInstructionWithPermit(
+    address coin,
+    uint256 amount,
+    uint256 deadline,
+    uint8 v,
+    bytes32 r,
+    bytes32 s,
+    bytes memory instruction
+  ) external {
+    if (coin == address(0)) {
+      revert InvalidAmount();
+    }
+
+    // If the permit fails, continue with whatever allowance the sender has
+    // This prevents griefing by front-running the permit, which would cause the
+    // permit here to fail due to its nonce having already been used, yet would
+    // have still set the allowance
+    try IERC20Permit(coin).permit(
+      msg.sender,
+      address(this),
+      amount,
+      deadline,
+      v,
+      r,
+      s
+    ) {} catch {}
+
+    _inInstruction(coin, amount, instruction);
+  }
+
+  // inInstructionWrappingEther wraps the ETH sent into WETH, via the specified
+  // WETH contract, and then records an InInstruction for the WETH
+  //
+  // This lets ETH be deposited yet treated as an ERC20, for uniformity with
+  // all other coins. The WETH contract is specified by the caller, yet Serai
+  // will only credit the InInstruction if it's a WETH contract Serai accepts
+  function inInstructionWrappingEther(
+    address weth,
+    bytes memory instruction
+  ) external payable {
+    if (paused) {
+      revert Paused();
+    }
+
+    if ((weth == address(0)) || (msg.value == 0)) {
+      revert InvalidAmount();
+    }
+
+    uint256 balance = IERC20(weth).balanceOf(address(this));
+    IWETH(weth).deposit{ value: msg.value }();
+    if (IERC20(weth).balanceOf(address(this)) != (balance + msg.value)) {
+      revert FailedTransfer();
+    }
+
+    emit InInstruction(msg.sender, weth, msg.value, instruction);
+  }
+
+  // onTokenTransfer is called by ERC-677 tokens within transferAndCall, letting
+  // a deposit be made with its InInstruction in a single transaction
+  //
+  // Anyone may call this, yet the coin is always the caller. Serai will only
+  // credit the InInstruction if the caller is a token Serai accepts as an
+  // ERC-677 token, and the transfer to the Router is within the same
+  // transaction
+  function onTokenTransfer(
+    address from,
+    uint256 amount,
+    bytes calldata instruction
+  ) external {
+    if (paused) {
+      revert Paused();
+    }
+
+    emit InInstruction(from, msg.sender, amount, instruction);
+  }
+
+  function _inInstruction(
+    address coin,
+    uint256 amount,
+    bytes memory instruction
+  ) internal {
+    if (paused) {
+      revert Paused();
+    }
+
     if (coin == address(0)) {
       if (amount != msg.value) {
         revert InvalidAmount();
@@ -161,14 +289,15 @@ contract Router {
       revert TooManyTransactions();
     }
 
-    bytes memory message =
-      abi.encode("execute", block.chainid, nonce, transactions);
+    bytes memory message = abi.encode(nonce, transactions);
     uint256 executed_with_nonce = nonce;
     // This prevents re-entrancy from causing double spends yet does allow
     // out-of-order execution via re-entrancy
     nonce++;
 
-    if (!Schnorr.verify(seraiKey, message, sig.c, sig.s)) {
+    if (
+      !Schnorr.verifyWithDomain(seraiKey, "execute", message, sig.c, sig.s)
+    ) {
       revert InvalidSignature();
     }
 
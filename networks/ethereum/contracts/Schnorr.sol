@@ -12,11 +12,57 @@ library Schnorr {
   // Bitcoin's Taproot
   uint8 constant public KEY_PARITY = 27;
 
+  // The version of the domain separation applied by verifyWithDomain
+  // Incrementing this invalidates all signatures produced under prior versions,
+  // letting the challenge be migrated without ambiguity as to what was signed
+  uint8 constant public DOMAIN_VERSION = 1;
+
   error InvalidSOrA();
   error MalformedSignature();
 
+  // The domain separator for signatures verified by this contract, on this
+  // chain, for the specified purpose
+  function domainSeparator(
+    bytes memory purpose
+  ) internal view returns (bytes32) {
+    return keccak256(
+      abi.encodePacked(
+        "Serai Schnorr",
+        DOMAIN_VERSION,
+        block.chainid,
+        address(this),
+        keccak256(purpose)
+      )
+    );
+  }
+
+  // Verify a signature bound to this contract, on this chain, for the specified
+  // purpose
+  //
+  // This prevents a signature produced for one contract from being replayed
+  // against another contract using the same key and this library
+  function verifyWithDomain(
+    bytes32 px,
+    bytes memory purpose,
+    bytes memory message,
+    bytes32 c,
+    bytes32 s
+  ) internal view returns (bool) {
+    return verify(
+      px,
+      abi.encodePacked(domainSeparator(purpose), message),
+      c,
+      s
+    );
+  }
+
+  // Verify a signature over an arbitrary message, without domain separation
+  //
+  // Contracts should use verifyWithDomain unless the message is already bound
+  // to its verifier
+  //
   // px := public key x-coord, where the public key has a parity of KEY_PARITY
-  // message := 32-byte hash of the message
+  // message := the message
   // c := schnorr signature challenge
   // s := schnorr signature
   function verify(
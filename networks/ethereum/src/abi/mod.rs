@@ -11,6 +11,9 @@ mod erc20_container {
   sol!("contracts/IERC20.sol");
 }
 pub use erc20_container::IERC20 as erc20;
+pub use erc20_container::IERC20Permit as erc20_permit;
+pub use erc20_container::IERC677 as erc677;
+pub use erc20_container::IWETH as weth;
 
 #[rustfmt::skip]
 #[allow(warnings)]
@@ -11,7 +11,7 @@ use frost::{
   curve::{Ciphersuite, Secp256k1},
 };
 
-use alloy_core::primitives::{Parity, Signature as AlloySignature};
+use alloy_core::primitives::{Parity, Signature as AlloySignature, U256};
 use alloy_consensus::{SignableTransaction, Signed, TxLegacy};
 
 use crate::abi::router::{Signature as AbiSignature};
@@ -103,7 +103,6 @@ impl PublicKey {
     self.px.to_repr().into()
   }
 
-  #[cfg(test)]
   pub(crate) fn from_eth_repr(repr: [u8; 32]) -> Option<Self> {
     #[allow(non_snake_case)]
     let A = Option::<AffinePoint>::from(AffinePoint::decompress(&repr.into(), 0.into()))?.into();
@@ -127,6 +126,42 @@ impl Hram<Secp256k1> for EthereumHram {
   }
 }
 
+/// The version of the domain separation applied to Schnorr challenges.
+///
+/// This must equal the Schnorr contract's `DOMAIN_VERSION`. It's incremented whenever the domain
+/// separation changes, so a signer and a verifier disagreeing on the challenge is detectable (via
+/// `Router::schnorr_domain_version`) instead of solely causing signatures to fail to verify.
+pub const DOMAIN_VERSION: u8 = 1;
+
+/// The domain a Schnorr signature is bound to.
+///
+/// The challenge commits to the chain, the contract verifying the signature, and the purpose of
+/// the signature, so a signature produced for one contract can't be replayed against another
+/// contract using the same key.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SignatureDomain {
+  pub chain_id: U256,
+  pub verifier: [u8; 20],
+  pub purpose: &'static [u8],
+}
+
+impl SignatureDomain {
+  /// The domain separator, as calculated by the Schnorr contract's `domainSeparator`.
+  pub fn separator(&self) -> [u8; 32] {
+    let mut preimage = b"Serai Schnorr".to_vec();
+    preimage.push(DOMAIN_VERSION);
+    preimage.extend(&self.chain_id.to_be_bytes::<32>());
+    preimage.extend(&self.verifier);
+    preimage.extend(&keccak256(self.purpose));
+    keccak256(&preimage)
+  }
+
+  /// The message to pass to `EthereumHram` in order to sign `message` within this domain.
+  pub fn bind(&self, message: &[u8]) -> Vec<u8> {
+    [self.separator().as_slice(), message].concat()
+  }
+}
+
 /// A signature for the Schnorr contract.
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct Signature {
@@ -134,27 +169,46 @@ pub struct Signature {
   pub(crate) s: Scalar,
 }
 impl Signature {
-  pub fn verify(&self, public_key: &PublicKey, message: &[u8]) -> bool {
+  /// The challenge for a signature with the specified nonce, within the specified domain.
+  #[allow(non_snake_case)]
+  pub fn challenge(
+    R: &ProjectivePoint,
+    public_key: &PublicKey,
+    domain: &SignatureDomain,
+    message: &[u8],
+  ) -> Scalar {
+    EthereumHram::hram(R, &public_key.A, &domain.bind(message))
+  }
+
+  // Verify this signature over a message without domain separation, as the Schnorr contract's
+  // `verify` does
+  pub(crate) fn verify_without_domain(&self, public_key: &PublicKey, message: &[u8]) -> bool {
     #[allow(non_snake_case)]
     let R = (Secp256k1::generator() * self.s) - (public_key.A * self.c);
     EthereumHram::hram(&R, &public_key.A, message) == self.c
   }
 
+  pub fn verify(&self, public_key: &PublicKey, domain: &SignatureDomain, message: &[u8]) -> bool {
+    self.verify_without_domain(public_key, &domain.bind(message))
+  }
+
   /// Construct a new `Signature`.
   ///
-  /// This will return None if the signature is invalid.
+  /// The signature must have been produced over `domain.bind(message)`. This will return None if
+  /// the signature is invalid.
   pub fn new(
     public_key: &PublicKey,
+    domain: &SignatureDomain,
     message: &[u8],
     signature: SchnorrSignature<Secp256k1>,
   ) -> Option<Signature> {
-    let c = EthereumHram::hram(&signature.R, &public_key.A, message);
+    let c = Self::challenge(&signature.R, public_key, domain, message);
     if !signature.verify(public_key.A, c) {
       None?;
     }
 
     let res = Signature { c, s: signature.s };
-    assert!(res.verify(public_key, message));
+    assert!(res.verify(public_key, domain, message));
     Some(res)
   }
 
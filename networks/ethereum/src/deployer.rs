@@ -38,7 +38,8 @@ impl Deployer {
       chain_id: None,
       nonce: 0,
       gas_price: 100_000_000_000u128,
-      // TODO: Use a more accurate gas limit
+      // This can't be estimated, as the gas limit is bound to the deterministic signer, and
+      // accordingly to the Deployer's address
       gas_limit: 1_000_000,
       to: TxKind::Create,
       value: U256::ZERO,
@@ -84,6 +85,16 @@ impl Deployer {
     provider: Arc<RootProvider<SimpleRequest>>,
     key: &PublicKey,
   ) -> Result<Option<Router>, Error> {
+    Ok(self.find_router_deployment(provider, key).await?.map(|(router, _)| router))
+  }
+
+  /// Find the first Router deployed with the specified key as its first key, along with the block
+  /// it was deployed in.
+  pub async fn find_router_deployment(
+    &self,
+    provider: Arc<RootProvider<SimpleRequest>>,
+    key: &PublicKey,
+  ) -> Result<Option<(Router, u64)>, Error> {
     let init_code = Router::init_code(key);
     let init_code_hash = keccak256(&init_code);
 
@@ -101,6 +112,7 @@ impl Deployer {
     let logs = provider.get_logs(&filter).await.map_err(|_| Error::ConnectionError)?;
 
     let Some(first_log) = logs.first() else { return Ok(None) };
+    let block = first_log.block_number.ok_or(Error::ConnectionError)?;
     let router = first_log
       .log_decode::<abi::Deployment>()
       .map_err(|_| Error::ConnectionError)?
@@ -108,6 +120,6 @@ impl Deployer {
       .data
       .created;
 
-    Ok(Some(Router::new(provider, router)))
+    Ok(Some((Router::new(provider, router), block)))
   }
 }
@@ -1,16 +1,17 @@
 use std::{sync::Arc, collections::HashSet};
 
-use alloy_core::primitives::{Address, B256, U256};
+use alloy_core::primitives::{Address, B256, U256, TxKind};
+use alloy_consensus::TxLegacy;
 
-use alloy_sol_types::{SolInterface, SolEvent};
+use alloy_sol_types::{SolValue, SolInterface, SolCall, SolEvent};
 
-use alloy_rpc_types_eth::Filter;
+use alloy_rpc_types_eth::{Filter, TransactionInput, TransactionRequest};
 use alloy_simple_request_transport::SimpleRequest;
 use alloy_provider::{Provider, RootProvider};
 
-use crate::Error;
-pub use crate::abi::erc20 as abi;
-use abi::{IERC20Calls, Transfer, transferCall, transferFromCall};
+use crate::{Error, crypto::keccak256};
+pub use crate::abi::{erc20 as abi, erc20_permit as permit_abi, erc677 as erc677_abi};
+use abi::{IERC20Calls, Transfer, transferCall, transferFromCall, approveCall, allowanceCall};
 
 #[derive(Clone, Debug)]
 pub struct TopLevelErc20Transfer {
@@ -20,6 +21,60 @@ pub struct TopLevelErc20Transfer {
   pub data: Vec<u8>,
 }
 
+/// An EIP-2612 permit, authorizing a spender to transfer an owner's tokens.
+///
+/// <https://eips.ethereum.org/EIPS/eip-2612>
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Permit {
+  pub owner: [u8; 20],
+  pub spender: [u8; 20],
+  pub value: U256,
+  pub nonce: U256,
+  pub deadline: U256,
+}
+
+impl Permit {
+  /// The EIP-712 digest to sign for this permit, under the token's domain separator.
+  pub fn digest(&self, domain_separator: [u8; 32]) -> [u8; 32] {
+    let type_hash = keccak256(
+      b"Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)",
+    );
+    let struct_hash = keccak256(
+      &(
+        B256::from(type_hash),
+        Address::from(self.owner),
+        Address::from(self.spender),
+        self.value,
+        self.nonce,
+        self.deadline,
+      )
+        .abi_encode_params(),
+    );
+
+    let mut preimage = Vec::with_capacity(2 + 32 + 32);
+    preimage.extend(b"\x19\x01");
+    preimage.extend(&domain_separator);
+    preimage.extend(&struct_hash);
+    keccak256(&preimage)
+  }
+}
+
+/// An ECDSA signature for an EIP-2612 permit, in the form `permit` expects.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PermitSignature {
+  pub v: u8,
+  pub r: [u8; 32],
+  pub s: [u8; 32],
+}
+
+impl PermitSignature {
+  /// Create a `PermitSignature` from a signature produced by `sign_prehash_recoverable`.
+  pub fn new(signature: &k256::ecdsa::Signature, recovery_id: k256::ecdsa::RecoveryId) -> Self {
+    let (r, s) = signature.split_bytes();
+    PermitSignature { v: 27 + recovery_id.to_byte(), r: r.into(), s: s.into() }
+  }
+}
+
 /// A view for an ERC20 contract.
 #[derive(Clone, Debug)]
 pub struct Erc20(Arc<RootProvider<SimpleRequest>>, Address);
@@ -29,6 +84,93 @@ impl Erc20 {
     Self(provider, Address::from(&address))
   }
 
+  /// The EIP-712 domain separator for this token's permits.
+  ///
+  /// This will error if the token doesn't support EIP-2612.
+  pub async fn domain_separator(&self) -> Result<[u8; 32], Error> {
+    let call = TransactionRequest::default()
+      .to(self.1)
+      .input(TransactionInput::new(permit_abi::DOMAIN_SEPARATORCall::new(()).abi_encode().into()));
+    let bytes = self.0.call(&call).await.map_err(|_| Error::ConnectionError)?;
+    let res = permit_abi::DOMAIN_SEPARATORCall::abi_decode_returns(&bytes, true)
+      .map_err(|_| Error::ConnectionError)?;
+    Ok(res._0.0)
+  }
+
+  /// The nonce the next permit from the specified owner must use.
+  ///
+  /// This is as of the latest block and may accordingly be reorganized.
+  pub async fn permit_nonce(&self, owner: [u8; 20]) -> Result<U256, Error> {
+    let call = TransactionRequest::default().to(self.1).input(TransactionInput::new(
+      permit_abi::noncesCall::new((owner.into(),)).abi_encode().into(),
+    ));
+    let bytes = self.0.call(&call).await.map_err(|_| Error::ConnectionError)?;
+    let res = permit_abi::noncesCall::abi_decode_returns(&bytes, true)
+      .map_err(|_| Error::ConnectionError)?;
+    Ok(res._0)
+  }
+
+  /// The amount the specified spender is allowed to transfer from the specified owner.
+  ///
+  /// This is as of the latest block and may accordingly be reorganized.
+  pub async fn allowance(&self, owner: [u8; 20], spender: [u8; 20]) -> Result<U256, Error> {
+    let call = TransactionRequest::default().to(self.1).input(TransactionInput::new(
+      allowanceCall::new((owner.into(), spender.into())).abi_encode().into(),
+    ));
+    let bytes = self.0.call(&call).await.map_err(|_| Error::ConnectionError)?;
+    let res =
+      allowanceCall::abi_decode_returns(&bytes, true).map_err(|_| Error::ConnectionError)?;
+    Ok(res._0)
+  }
+
+  /// Approve the specified spender to transfer `amount` from the sender of this transaction.
+  ///
+  /// This is the first half of the approve-then-`inInstruction` flow, where the spender is the
+  /// Router.
+  pub fn approve(&self, spender: [u8; 20], amount: U256) -> TxLegacy {
+    // TODO: Set a more accurate gas
+    TxLegacy {
+      to: TxKind::Call(self.1),
+      input: approveCall::new((spender.into(), amount)).abi_encode().into(),
+      gas_limit: 100_000,
+      ..Default::default()
+    }
+  }
+
+  /// Transfer `amount` from the sender of this transaction, with `data` appended to the call.
+  ///
+  /// Transferring to the Router, with an `InInstruction` as the data, deposits without any
+  /// approval. Such transfers are only recognized when made as top-level calls to the ERC20 (see
+  /// `top_level_transfers`).
+  pub fn transfer_with_data(&self, to: [u8; 20], amount: U256, data: &[u8]) -> TxLegacy {
+    let mut input = transferCall::new((to.into(), amount)).abi_encode();
+    input.extend(data);
+    // TODO: Set a more accurate gas
+    TxLegacy {
+      to: TxKind::Call(self.1),
+      input: input.into(),
+      gas_limit: 100_000,
+      ..Default::default()
+    }
+  }
+
+  /// Transfer `amount` from the sender of this transaction via ERC-677's `transferAndCall`.
+  ///
+  /// Transferring to the Router, with an `InInstruction` as the data, has the token call the
+  /// Router, which records the `InInstruction`. This will fail if the token doesn't support
+  /// ERC-677, and is only credited if the token is accepted as an ERC-677 token.
+  pub fn transfer_and_call(&self, to: [u8; 20], amount: U256, data: &[u8]) -> TxLegacy {
+    // TODO: Set a more accurate gas
+    TxLegacy {
+      to: TxKind::Call(self.1),
+      input: erc677_abi::transferAndCallCall::new((to.into(), amount, data.to_vec().into()))
+        .abi_encode()
+        .into(),
+      gas_limit: 200_000,
+      ..Default::default()
+    }
+  }
+
   pub async fn top_level_transfers(
     &self,
     block: u64,
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use alloy_core::primitives::Address;
+use alloy_consensus::TxLegacy;
+
+use alloy_sol_types::SolCall;
+
+use alloy_rpc_types_eth::{TransactionRequest, TransactionInput};
+use alloy_simple_request_transport::SimpleRequest;
+use alloy_provider::{Provider, RootProvider};
+
+use crate::{Error, abi::router as router_abi};
+
+/// The multiplier applied to gas estimates by default, as a percentage of the estimate.
+pub const DEFAULT_GAS_MULTIPLIER_PERCENT: u64 = 120;
+
+/// The gas limit to use for a transaction, given its estimated gas, the multiplier to apply to the
+/// estimate (as a percentage), and the cap on its gas limit.
+///
+/// The gas limit is capped, yet if the estimate itself exceeds the cap, `None` is returned. A
+/// multiplier below 100% is treated as 100%.
+pub fn gas_limit(estimate: u64, multiplier_percent: u64, cap: u64) -> Option<u64> {
+  if estimate > cap {
+    return None;
+  }
+  let limit = (u128::from(estimate) * u128::from(multiplier_percent.max(100))).div_ceil(100);
+  Some(u64::try_from(limit.min(u128::from(cap))).unwrap())
+}
+
+/// An estimator of the gas limits for transactions, via `eth_estimateGas`.
+///
+/// The gas limit a transaction was constructed with is used as the cap on its gas limit. The gas
+/// limit is the estimate with a safety margin applied, so the transaction doesn't fail if it uses
+/// slightly more gas when executed, without reserving the fee for the entire cap.
+///
+/// Calls to the Router's `execute` are never estimated, keeping the gas limit from
+/// `Router::execute_gas`. `execute` forwards a fixed amount of gas to each `OutInstruction` and
+/// doesn't revert if one fails, so `eth_estimateGas` (which finds the least gas the call doesn't
+/// revert with) may leave the `OutInstruction`s without enough gas to succeed.
+#[derive(Clone, Debug)]
+pub struct GasEstimator {
+  provider: Arc<RootProvider<SimpleRequest>>,
+  multiplier_percent: u64,
+}
+
+impl GasEstimator {
+  /// Create a new estimator, which multiplies the estimates by the specified percentage.
+  pub fn new(provider: Arc<RootProvider<SimpleRequest>>, multiplier_percent: u64) -> Self {
+    GasEstimator { provider, multiplier_percent }
+  }
+
+  /// Estimate the gas limit for a transaction, as sent from the specified address.
+  ///
+  /// The address only needs to be specified if the transaction's execution depends on its sender,
+  /// such as if it transfers value. Errors with `Error::GasLimitExceeded` if the estimate exceeds
+  /// the transaction's current gas limit. Calls to the Router's `execute` keep their current gas
+  /// limit.
+  pub async fn estimate(&self, from: Option<Address>, tx: &TxLegacy) -> Result<u64, Error> {
+    if tx.input.starts_with(&router_abi::executeCall::SELECTOR) {
+      return Ok(tx.gas_limit);
+    }
+
+    let mut call = TransactionRequest { to: Some(tx.to), ..Default::default() }
+      .input(TransactionInput::new(tx.input.clone()))
+      .value(tx.value);
+    if let Some(from) = from {
+      call = call.from(from);
+    }
+    let estimate = self.provider.estimate_gas(&call).await.map_err(|_| Error::ConnectionError)?;
+    let estimate = u64::try_from(estimate).map_err(|_| Error::GasLimitExceeded)?;
+    gas_limit(estimate, self.multiplier_percent, tx.gas_limit).ok_or(Error::GasLimitExceeded)
+  }
+
+  /// Set the gas limit for a transaction to its estimate, as sent from the specified address.
+  pub async fn set_gas_limit(&self, from: Option<Address>, tx: &mut TxLegacy) -> Result<(), Error> {
+    tx.gas_limit = self.estimate(from, tx).await?;
+    Ok(())
+  }
+}
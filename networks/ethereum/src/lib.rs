@@ -20,6 +20,8 @@ pub(crate) mod abi;
 pub mod erc20;
 pub mod deployer;
 pub mod router;
+pub mod gas_limit;
+pub mod publisher;
 
 pub mod machine;
 
@@ -32,4 +34,6 @@ pub enum Error {
   InvalidSignature,
   #[error("couldn't make call/send TX")]
   ConnectionError,
+  #[error("estimated gas exceeded the gas limit")]
+  GasLimitExceeded,
 }
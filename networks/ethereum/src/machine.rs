@@ -18,7 +18,7 @@ use frost::{
 use alloy_core::primitives::U256;
 
 use crate::{
-  crypto::{PublicKey, EthereumHram, Signature},
+  crypto::{PublicKey, EthereumHram, SignatureDomain, Signature},
   router::{
     abi::{Call as AbiCall, OutInstruction as AbiOutInstruction},
     Router,
@@ -166,23 +166,39 @@ impl From<OutInstruction> for AbiOutInstruction {
   }
 }
 
+// The kinds of RouterCommands, as serialized.
+//
+// Commands serialized before they were bound to a Router used the kinds 0, 1, and 2, without the
+// Router's address. As they were signed without domain separation, they'll never be accepted by a
+// Router, so they're explicitly rejected when read (instead of read as a distinct command).
+const LEGACY_KINDS: [u8; 3] = [0, 1, 2];
+const UPDATE_SERAI_KEY_KIND: u8 = 3;
+const EXECUTE_KIND: u8 = 4;
+const SET_PAUSED_KIND: u8 = 5;
+
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum RouterCommand {
-  UpdateSeraiKey { chain_id: U256, nonce: U256, key: PublicKey },
-  Execute { chain_id: U256, nonce: U256, outs: Vec<OutInstruction> },
+  UpdateSeraiKey { chain_id: U256, router: [u8; 20], nonce: U256, key: PublicKey },
+  Execute { chain_id: U256, router: [u8; 20], nonce: U256, outs: Vec<OutInstruction> },
+  SetPaused { chain_id: U256, router: [u8; 20], nonce: U256, paused: bool },
 }
 
 impl RouterCommand {
-  pub fn msg(&self) -> Vec<u8> {
+  /// The domain this command is signed within, and the message signed.
+  pub fn msg(&self) -> (SignatureDomain, Vec<u8>) {
     match self {
-      RouterCommand::UpdateSeraiKey { chain_id, nonce, key } => {
-        Router::update_serai_key_message(*chain_id, *nonce, key)
+      RouterCommand::UpdateSeraiKey { chain_id, router, nonce, key } => {
+        Router::update_serai_key_message(*chain_id, *router, *nonce, key)
       }
-      RouterCommand::Execute { chain_id, nonce, outs } => Router::execute_message(
+      RouterCommand::Execute { chain_id, router, nonce, outs } => Router::execute_message(
         *chain_id,
+        *router,
         *nonce,
         outs.iter().map(|out| out.clone().into()).collect(),
       ),
+      RouterCommand::SetPaused { chain_id, router, nonce, paused } => {
+        Router::set_paused_message(*chain_id, *router, *nonce, *paused)
+      }
     }
   }
 
@@ -191,10 +207,13 @@ impl RouterCommand {
     reader.read_exact(&mut kind)?;
 
     match kind[0] {
-      0 => {
+      UPDATE_SERAI_KEY_KIND => {
         let mut chain_id = [0; 32];
         reader.read_exact(&mut chain_id)?;
 
+        let mut router = [0; 20];
+        reader.read_exact(&mut router)?;
+
         let mut nonce = [0; 32];
         reader.read_exact(&mut nonce)?;
 
@@ -202,15 +221,19 @@ impl RouterCommand {
           .ok_or(io::Error::other("key for RouterCommand doesn't have an eth representation"))?;
         Ok(RouterCommand::UpdateSeraiKey {
           chain_id: U256::from_le_slice(&chain_id),
+          router,
           nonce: U256::from_le_slice(&nonce),
           key,
         })
       }
-      1 => {
+      EXECUTE_KIND => {
         let mut chain_id = [0; 32];
         reader.read_exact(&mut chain_id)?;
         let chain_id = U256::from_le_slice(&chain_id);
 
+        let mut router = [0; 20];
+        reader.read_exact(&mut router)?;
+
         let mut nonce = [0; 32];
         reader.read_exact(&mut nonce)?;
         let nonce = U256::from_le_slice(&nonce);
@@ -224,23 +247,53 @@ impl RouterCommand {
           outs.push(OutInstruction::read(reader)?);
         }
 
-        Ok(RouterCommand::Execute { chain_id, nonce, outs })
+        Ok(RouterCommand::Execute { chain_id, router, nonce, outs })
       }
+      SET_PAUSED_KIND => {
+        let mut chain_id = [0; 32];
+        reader.read_exact(&mut chain_id)?;
+
+        let mut router = [0; 20];
+        reader.read_exact(&mut router)?;
+
+        let mut nonce = [0; 32];
+        reader.read_exact(&mut nonce)?;
+
+        let mut paused = [0xff];
+        reader.read_exact(&mut paused)?;
+        let paused = match paused[0] {
+          0 => false,
+          1 => true,
+          _ => Err(io::Error::other("invalid bool for RouterCommand::SetPaused"))?,
+        };
+
+        Ok(RouterCommand::SetPaused {
+          chain_id: U256::from_le_slice(&chain_id),
+          router,
+          nonce: U256::from_le_slice(&nonce),
+          paused,
+        })
+      }
+      kind if LEGACY_KINDS.contains(&kind) => Err(io::Error::other(
+        "reading RouterCommand serialized before commands were bound to a Router",
+      ))?,
       _ => Err(io::Error::other("reading unknown type of RouterCommand"))?,
     }
   }
 
   pub fn write<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
     match self {
-      RouterCommand::UpdateSeraiKey { chain_id, nonce, key } => {
-        writer.write_all(&[0])?;
+      RouterCommand::UpdateSeraiKey { chain_id, router, nonce, key } => {
+        writer.write_all(&[UPDATE_SERAI_KEY_KIND])?;
         writer.write_all(&chain_id.as_le_bytes())?;
+        writer.write_all(router)?;
         writer.write_all(&nonce.as_le_bytes())?;
         writer.write_all(&key.A.to_bytes())
       }
-      RouterCommand::Execute { chain_id, nonce, outs } => {
-        writer.write_all(&[1])?;
+      RouterCommand::Execute { chain_id, router, nonce, outs } => {
+        writer.write_all(&[EXECUTE_KIND])?;
         writer.write_all(&chain_id.as_le_bytes())?;
+        writer.write_all(router)?;
         writer.write_all(&nonce.as_le_bytes())?;
         writer.write_all(&u32::try_from(outs.len()).unwrap().to_le_bytes())?;
         for out in outs {
@@ -248,6 +301,13 @@ impl RouterCommand {
         }
         Ok(())
       }
+      RouterCommand::SetPaused { chain_id, router, nonce, paused } => {
+        writer.write_all(&[SET_PAUSED_KIND])?;
+        writer.write_all(&chain_id.as_le_bytes())?;
+        writer.write_all(router)?;
+        writer.write_all(&nonce.as_le_bytes())?;
+        writer.write_all(&[u8::from(*paused)])
+      }
     }
   }
 
@@ -270,7 +330,8 @@ impl SignedRouterCommand {
     let s = Secp256k1::read_F(&mut &signature[32 ..]).ok()?;
     let signature = Signature { c, s };
 
-    if !signature.verify(key, &command.msg()) {
+    let (domain, msg) = command.msg();
+    if !signature.verify(key, &domain, &msg) {
       None?
     }
     Some(SignedRouterCommand { command, signature })
@@ -382,7 +443,8 @@ impl SignMachine<SignedRouterCommand> for RouterCommandSignMachine {
       panic!("message was passed to a RouterCommand machine when it generates its own");
     }
 
-    let (machine, share) = self.machine.sign(commitments, &self.command.msg())?;
+    let (domain, msg) = self.command.msg();
+    let (machine, share) = self.machine.sign(commitments, &domain.bind(&msg))?;
 
     Ok((RouterCommandSignatureMachine { key: self.key, command: self.command, machine }, share))
   }
@@ -407,8 +469,9 @@ impl SignatureMachine<SignedRouterCommand> for RouterCommandSignatureMachine {
     shares: HashMap<Participant, Self::SignatureShare>,
   ) -> Result<SignedRouterCommand, FrostError> {
     let sig = self.machine.complete(shares)?;
-    let signature = Signature::new(&self.key, &self.command.msg(), sig)
-      .expect("machine produced an invalid signature");
+    let (domain, msg) = self.command.msg();
+    let signature =
+      Signature::new(&self.key, &domain, &msg, sig).expect("machine produced an invalid signature");
     Ok(SignedRouterCommand { command: self.command, signature })
   }
 }
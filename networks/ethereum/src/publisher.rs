@@ -0,0 +1,263 @@
+use std::{sync::Arc, collections::HashMap};
+
+use k256::ProjectivePoint;
+
+use alloy_core::primitives::{Address, U256, Signature};
+use alloy_consensus::{SignableTransaction, Signed, TxLegacy};
+
+use alloy_rpc_types_eth::BlockId;
+use alloy_simple_request_transport::SimpleRequest;
+use alloy_provider::{Provider, RootProvider};
+
+use crate::{
+  Error,
+  crypto::{address, deterministically_sign},
+  router::Router,
+  gas_limit::GasEstimator,
+};
+
+/// The minimum percentage a transaction's gas price is increased by when its fee is bumped.
+///
+/// Nodes commonly reject replacement transactions which don't increase the gas price by at least
+/// 10%.
+pub const MIN_GAS_PRICE_BUMP_PERCENT: u128 = 10;
+
+/// The gas price to resubmit a transaction with, given its prior gas price and the current gas
+/// price.
+pub fn bumped_gas_price(prior: u128, current: u128) -> u128 {
+  let bump = prior.saturating_mul(MIN_GAS_PRICE_BUMP_PERCENT).div_ceil(100).max(1);
+  prior.saturating_add(bump).max(current)
+}
+
+/// How the transactions published are signed.
+#[derive(Clone, Debug)]
+pub enum TransactionSigner {
+  /// Sign with the key for an account.
+  ///
+  /// A transaction with a bumped fee is signed with the same nonce, replacing the transaction
+  /// whose fee was bumped.
+  Account(k256::ecdsa::SigningKey),
+  /// Sign deterministically, as done by `deterministically_sign`.
+  ///
+  /// As the signer is derived from the transaction, a transaction with a bumped fee has a distinct
+  /// signer and doesn't replace the transaction whose fee was bumped. Instead, whichever executes
+  /// first will cause the other to revert. Each signer must be funded before its transaction is
+  /// published.
+  Deterministic,
+}
+
+impl TransactionSigner {
+  fn sign(&self, tx: TxLegacy) -> Signed<TxLegacy> {
+    match self {
+      TransactionSigner::Account(key) => {
+        let sig = key.sign_prehash_recoverable(tx.signature_hash().as_ref()).unwrap();
+        tx.into_signed(Signature::from(sig))
+      }
+      TransactionSigner::Deterministic => deterministically_sign(&tx),
+    }
+  }
+}
+
+/// The result of publishing a transaction.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Publication {
+  /// The transaction was published, with the specified hash.
+  Published([u8; 32]),
+  /// The transaction wasn't published as its signer must first be funded with the specified amount.
+  Unfunded {
+    /// The signer of the transaction.
+    signer: Address,
+    /// The amount the signer must be funded with.
+    amount: U256,
+  },
+}
+
+// A transaction published, yet to be mined
+#[derive(Clone, Debug)]
+struct Pending {
+  // The transaction, as most recently published
+  tx: TxLegacy,
+  // The hashes of every version of this transaction published
+  hashes: Vec<[u8; 32]>,
+  // The block number as of when this transaction was most recently published
+  published_at: u64,
+}
+
+/// A publisher of transactions calling the Router, which bumps the fee of transactions left
+/// unmined.
+///
+/// Transactions are tracked by the nonce of the Router command they perform. Once the Router
+/// executes the command, or any version of its transaction is mined, the transaction is no longer
+/// tracked. If a transaction isn't mined within the configured amount of blocks, it's resubmitted
+/// with a bumped fee.
+#[derive(Clone, Debug)]
+pub struct Publisher {
+  provider: Arc<RootProvider<SimpleRequest>>,
+  signer: TransactionSigner,
+  bump_after: u64,
+  estimator: Option<GasEstimator>,
+  // The nonce of the next transaction from the account signing, if signing with an account
+  next_account_nonce: Option<u64>,
+  pending: HashMap<u64, Pending>,
+}
+
+impl Publisher {
+  /// Create a new publisher, which bumps the fee of transactions unmined after `bump_after`
+  /// blocks.
+  pub fn new(
+    provider: Arc<RootProvider<SimpleRequest>>,
+    signer: TransactionSigner,
+    bump_after: u64,
+  ) -> Publisher {
+    Publisher {
+      provider,
+      signer,
+      bump_after,
+      estimator: None,
+      next_account_nonce: None,
+      pending: HashMap::new(),
+    }
+  }
+
+  /// Estimate the gas limit of transactions before publishing them.
+  ///
+  /// The gas limit each transaction was constructed with is used as the cap on its gas limit.
+  pub fn with_gas_estimator(mut self, estimator: GasEstimator) -> Publisher {
+    self.estimator = Some(estimator);
+    self
+  }
+
+  // Sign and send a transaction, if its signer is funded
+  async fn send(
+    provider: &RootProvider<SimpleRequest>,
+    signer: &TransactionSigner,
+    tx: TxLegacy,
+  ) -> Result<Publication, Error> {
+    let tx = signer.sign(tx);
+    let from = tx.recover_signer().map_err(|_| Error::ConnectionError)?;
+
+    let amount = (U256::from(tx.tx().gas_limit) * U256::from(tx.tx().gas_price)) + tx.tx().value;
+    if provider.get_balance(from).await.map_err(|_| Error::ConnectionError)? < amount {
+      return Ok(Publication::Unfunded { signer: from, amount });
+    }
+
+    let hash = (*tx.hash()).into();
+    let (tx, sig, _) = tx.into_parts();
+    let mut bytes = vec![];
+    tx.encode_with_signature_fields(&sig, &mut bytes);
+    provider.send_raw_transaction(&bytes).await.map_err(|_| Error::ConnectionError)?;
+    Ok(Publication::Published(hash))
+  }
+
+  /// Publish a transaction performing the Router command with the specified nonce.
+  ///
+  /// If the transaction doesn't have a gas price set, it's published with the current gas price.
+  /// If it's signed by an account, it's published with the account's next nonce, accounting for
+  /// the account's transactions which have yet to be mined. If a gas estimator was specified, it's
+  /// published with its estimated gas limit.
+  pub async fn publish(&mut self, nonce: u64, mut tx: TxLegacy) -> Result<Publication, Error> {
+    tx.chain_id = None;
+    if tx.gas_price == 0 {
+      tx.gas_price = self.provider.get_gas_price().await.map_err(|_| Error::ConnectionError)?;
+    }
+    let from = match &self.signer {
+      TransactionSigner::Account(key) => {
+        Some(Address::from(address(&ProjectivePoint::from(*key.verifying_key().as_affine()))))
+      }
+      TransactionSigner::Deterministic => None,
+    };
+    if let Some(from) = from {
+      // Use the account's pending transaction count, so transactions yet to be mined (including
+      // those published by another instance) are accounted for, yet never go below the nonces
+      // we've already used
+      let pending_count = self
+        .provider
+        .get_transaction_count(from)
+        .block_id(BlockId::pending())
+        .await
+        .map_err(|_| Error::ConnectionError)?;
+      tx.nonce = self.next_account_nonce.map_or(pending_count, |next| next.max(pending_count));
+    }
+    if let Some(estimator) = &self.estimator {
+      estimator.set_gas_limit(from, &mut tx).await?;
+    }
+
+    let published_at =
+      self.provider.get_block_number().await.map_err(|_| Error::ConnectionError)?;
+    let publication = Self::send(&self.provider, &self.signer, tx.clone()).await?;
+    if let Publication::Published(hash) = publication {
+      if from.is_some() {
+        self.next_account_nonce = Some(tx.nonce + 1);
+      }
+      self.pending.insert(nonce, Pending { tx, hashes: vec![hash], published_at });
+    }
+    Ok(publication)
+  }
+
+  /// The nonces of the Router commands whose transactions are still pending.
+  pub fn pending(&self) -> Vec<u64> {
+    let mut pending = self.pending.keys().copied().collect::<Vec<_>>();
+    pending.sort();
+    pending
+  }
+
+  /// Check on the pending transactions, resubmitting any unmined after the configured amount of
+  /// blocks with a bumped fee.
+  ///
+  /// Returns the nonces of the commands whose transactions were resubmitted, with the result of
+  /// each resubmission.
+  pub async fn poll(&mut self, router: &Router) -> Result<Vec<(u64, Publication)>, Error> {
+    let router_nonce =
+      u64::try_from(router.latest_nonce().await?).map_err(|_| Error::ConnectionError)?;
+    // Stop tracking the transactions whose commands were already executed
+    self.pending.retain(|nonce, _| *nonce >= router_nonce);
+
+    let block = self.provider.get_block_number().await.map_err(|_| Error::ConnectionError)?;
+    let mut mined = vec![];
+    let mut resubmissions = vec![];
+    for nonce in self.pending() {
+      let pending = self.pending.get_mut(&nonce).unwrap();
+
+      // If any version of this transaction was mined, stop tracking it, even if it reverted
+      let mut was_mined = false;
+      for hash in &pending.hashes {
+        if self
+          .provider
+          .get_transaction_receipt((*hash).into())
+          .await
+          .map_err(|_| Error::ConnectionError)?
+          .is_some()
+        {
+          was_mined = true;
+          break;
+        }
+      }
+      if was_mined {
+        mined.push(nonce);
+        continue;
+      }
+
+      if block < pending.published_at.saturating_add(self.bump_after) {
+        continue;
+      }
+
+      let mut tx = pending.tx.clone();
+      tx.gas_price = bumped_gas_price(
+        tx.gas_price,
+        self.provider.get_gas_price().await.map_err(|_| Error::ConnectionError)?,
+      );
+      let publication = Self::send(&self.provider, &self.signer, tx.clone()).await?;
+      if let Publication::Published(hash) = publication {
+        pending.tx = tx;
+        pending.hashes.push(hash);
+        pending.published_at = block;
+      }
+      resubmissions.push((nonce, publication));
+    }
+    for nonce in mined {
+      self.pending.remove(&nonce);
+    }
+
+    Ok(resubmissions)
+  }
+}
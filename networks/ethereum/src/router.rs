@@ -1,29 +1,33 @@
-use std::{sync::Arc, io, collections::HashSet};
+use std::{
+  sync::{Arc, Mutex},
+  io,
+  ops::RangeInclusive,
+  collections::{HashSet, BTreeSet},
+};
 
 use k256::{
   elliptic_curve::{group::GroupEncoding, sec1},
   ProjectivePoint,
 };
 
-use alloy_core::primitives::{hex::FromHex, Address, U256, Bytes, TxKind};
-#[cfg(test)]
-use alloy_core::primitives::B256;
+use alloy_core::primitives::{hex::FromHex, Address, B256, U256, Bytes, TxKind};
 use alloy_consensus::TxLegacy;
 
 use alloy_sol_types::{SolValue, SolConstructor, SolCall, SolEvent};
 
-use alloy_rpc_types_eth::Filter;
-#[cfg(test)]
-use alloy_rpc_types_eth::{BlockId, TransactionRequest, TransactionInput};
+use alloy_rpc_types_eth::{Filter, BlockId, TransactionRequest, TransactionInput};
 use alloy_simple_request_transport::SimpleRequest;
 use alloy_provider::{Provider, RootProvider};
 
 pub use crate::{
   Error,
-  crypto::{PublicKey, Signature},
-  abi::{erc20::Transfer, router as abi},
+  crypto::{PublicKey, SignatureDomain, Signature},
+  abi::{erc20::Transfer, weth::Deposit, router as abi},
+  erc20::PermitSignature,
+};
+use abi::{
+  SeraiKeyUpdated, PausedUpdated, InInstruction as InInstructionEvent, Executed as ExecutedEvent,
 };
-use abi::{SeraiKeyUpdated, InInstruction as InInstructionEvent, Executed as ExecutedEvent};
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum Coin {
@@ -130,9 +134,43 @@ pub struct Executed {
   pub signature: [u8; 64],
 }
 
+/// An update of the key for Serai.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct KeyUpdate {
+  /// The block the key was updated in.
+  pub block: u64,
+  /// The hash of the transaction which updated the key.
+  pub tx_id: [u8; 32],
+  /// The nonce of the command which updated the key.
+  ///
+  /// The key set by the Router's constructor has a nonce of 0. A key authorizes every command
+  /// with a nonce greater than the nonce it was set with, until the key's next update.
+  pub nonce: u64,
+  /// The new key.
+  pub key: PublicKey,
+}
+
+// The Router's history, as scanned from its events
+#[derive(Default, Debug)]
+struct RouterHistory {
+  // The block the history has been scanned through
+  scanned_through: Option<u64>,
+  key_updates: Vec<KeyUpdate>,
+  // The nonce of each command executed, with the block it was executed in, ordered by nonce
+  nonces: Vec<(u64, u64)>,
+  // Each update of if the Router is paused, with the block it was updated in, ordered by nonce
+  paused_updates: Vec<(u64, bool)>,
+}
+
+/// The gas budgeted for an `execute` call, independent of how many `OutInstruction`s it has.
+pub const EXECUTE_BASE_GAS: u64 = 100_000;
+/// The gas budgeted for each `OutInstruction` within an `execute` call.
+// TODO: Calculate this per `OutInstruction`, as the Router allots up to 350_000 gas to calls
+pub const EXECUTE_GAS_PER_OUT_INSTRUCTION: u64 = 200_000 + 10_000;
+
 /// The contract Serai uses to manage its state.
 #[derive(Clone, Debug)]
-pub struct Router(Arc<RootProvider<SimpleRequest>>, Address);
+pub struct Router(Arc<RootProvider<SimpleRequest>>, Address, Arc<Mutex<RouterHistory>>);
 impl Router {
   pub(crate) fn code() -> Vec<u8> {
     let bytecode = include_str!("../artifacts/Router.bin");
@@ -148,7 +186,7 @@ impl Router {
 
   // This isn't pub in order to force users to use `Deployer::find_router`.
   pub(crate) fn new(provider: Arc<RootProvider<SimpleRequest>>, address: Address) -> Self {
-    Self(provider, address)
+    Self(provider, address, Arc::new(Mutex::new(RouterHistory::default())))
   }
 
   pub fn address(&self) -> [u8; 20] {
@@ -172,13 +210,30 @@ impl Router {
     PublicKey::from_eth_repr(res._0.0).ok_or(Error::ConnectionError)
   }
 
-  /// Get the message to be signed in order to update the key for Serai.
-  pub(crate) fn update_serai_key_message(chain_id: U256, nonce: U256, key: &PublicKey) -> Vec<u8> {
-    let mut buffer = b"updateSeraiKey".to_vec();
-    buffer.extend(&chain_id.to_be_bytes::<32>());
-    buffer.extend(&nonce.to_be_bytes::<32>());
+  /// The version of the Schnorr domain separation this Router verifies signatures with.
+  ///
+  /// Signatures should only be produced for this Router if this equals `crypto::DOMAIN_VERSION`.
+  pub async fn schnorr_domain_version(&self) -> Result<u8, Error> {
+    let call = TransactionRequest::default()
+      .to(self.1)
+      .input(TransactionInput::new(abi::schnorrDomainVersionCall::new(()).abi_encode().into()));
+    let bytes = self.0.call(&call).await.map_err(|_| Error::ConnectionError)?;
+    let res = abi::schnorrDomainVersionCall::abi_decode_returns(&bytes, true)
+      .map_err(|_| Error::ConnectionError)?;
+    Ok(res._0)
+  }
+
+  /// Get the domain and message to be signed in order to update the key for Serai.
+  pub(crate) fn update_serai_key_message(
+    chain_id: U256,
+    router: [u8; 20],
+    nonce: U256,
+    key: &PublicKey,
+  ) -> (SignatureDomain, Vec<u8>) {
+    let domain = SignatureDomain { chain_id, verifier: router, purpose: b"updateSeraiKey" };
+    let mut buffer = nonce.to_be_bytes::<32>().to_vec();
     buffer.extend(&key.eth_repr());
-    buffer
+    (domain, buffer)
   }
 
   /// Update the key representing Serai.
@@ -194,30 +249,141 @@ impl Router {
     }
   }
 
-  /// Get the current nonce for the published batches.
-  #[cfg(test)]
-  pub async fn nonce(&self, at: [u8; 32]) -> Result<U256, Error> {
+  /// Get the domain and message to be signed in order to pause/unpause the acceptance of
+  /// `InInstruction`s.
+  pub(crate) fn set_paused_message(
+    chain_id: U256,
+    router: [u8; 20],
+    nonce: U256,
+    paused: bool,
+  ) -> (SignatureDomain, Vec<u8>) {
+    let domain = SignatureDomain { chain_id, verifier: router, purpose: b"setPaused" };
+    let mut buffer = nonce.to_be_bytes::<32>().to_vec();
+    buffer.push(u8::from(paused));
+    (domain, buffer)
+  }
+
+  /// Pause/unpause the acceptance of `InInstruction`s.
+  pub fn set_paused(&self, paused: bool, sig: &Signature) -> TxLegacy {
+    // TODO: Set a more accurate gas
+    TxLegacy {
+      to: TxKind::Call(self.1),
+      input: abi::setPausedCall::new((paused, sig.into())).abi_encode().into(),
+      gas_limit: 100_000,
+      ..Default::default()
+    }
+  }
+
+  /// Deposit a coin and record an `InInstruction` for it.
+  ///
+  /// If the coin is ETH, the amount is sent as this transaction's value. If the coin is an ERC20,
+  /// the Router will `transferFrom` the sender, requiring the sender to have already approved the
+  /// Router for at least `amount`.
+  pub fn in_instruction(&self, coin: &Coin, amount: U256, instruction: Vec<u8>) -> TxLegacy {
+    let (coin, value) = match coin {
+      Coin::Ether => (Address::ZERO, amount),
+      Coin::Erc20(token) => (Address::from(token), U256::ZERO),
+    };
+    // TODO: Set a more accurate gas
+    TxLegacy {
+      to: TxKind::Call(self.1),
+      value,
+      input: abi::inInstructionCall::new((coin, amount, instruction.into())).abi_encode().into(),
+      gas_limit: 200_000,
+      ..Default::default()
+    }
+  }
+
+  /// Deposit an ERC20 and record an `InInstruction`, in a single transaction, via an EIP-2612
+  /// permit.
+  ///
+  /// The permit must be signed by the sender of this transaction, for the Router, for `amount`.
+  /// If the permit fails (such as due to being front-run), the deposit will be attempted with
+  /// whatever allowance the sender has already granted.
+  pub fn in_instruction_with_permit(
+    &self,
+    coin: [u8; 20],
+    amount: U256,
+    deadline: U256,
+    sig: &PermitSignature,
+    instruction: Vec<u8>,
+  ) -> TxLegacy {
+    // TODO: Set a more accurate gas
+    TxLegacy {
+      to: TxKind::Call(self.1),
+      input: abi::inInstructionWithPermitCall::new((
+        coin.into(),
+        amount,
+        deadline,
+        sig.v,
+        B256::from(sig.r),
+        B256::from(sig.s),
+        instruction.into(),
+      ))
+      .abi_encode()
+      .into(),
+      gas_limit: 200_000,
+      ..Default::default()
+    }
+  }
+
+  /// Deposit ETH and record an `InInstruction` for it as WETH, in a single transaction.
+  ///
+  /// The ETH is wrapped via the specified WETH contract. The `InInstruction` will only be credited
+  /// if this WETH contract is one of the tokens Serai accepts, and is the WETH contract scanned
+  /// for, letting ETH be treated the same as any other ERC20. Deposits of ETH which should be
+  /// credited as ETH should be made with the `inInstruction` function.
+  pub fn in_instruction_wrapping_ether(
+    &self,
+    weth: [u8; 20],
+    amount: U256,
+    instruction: Vec<u8>,
+  ) -> TxLegacy {
+    // TODO: Set a more accurate gas
+    TxLegacy {
+      to: TxKind::Call(self.1),
+      value: amount,
+      input: abi::inInstructionWrappingEtherCall::new((weth.into(), instruction.into()))
+        .abi_encode()
+        .into(),
+      gas_limit: 200_000,
+      ..Default::default()
+    }
+  }
+
+  async fn nonce_as_of(&self, block: BlockId) -> Result<U256, Error> {
     let call = TransactionRequest::default()
       .to(self.1)
       .input(TransactionInput::new(abi::nonceCall::new(()).abi_encode().into()));
-    let bytes = self
-      .0
-      .call(&call)
-      .block(BlockId::Hash(B256::from(at).into()))
-      .await
-      .map_err(|_| Error::ConnectionError)?;
+    let bytes = self.0.call(&call).block(block).await.map_err(|_| Error::ConnectionError)?;
     let res =
       abi::nonceCall::abi_decode_returns(&bytes, true).map_err(|_| Error::ConnectionError)?;
     Ok(res._0)
   }
 
-  /// Get the message to be signed in order to update the key for Serai.
+  /// Get the current nonce for the published batches.
+  #[cfg(test)]
+  pub async fn nonce(&self, at: [u8; 32]) -> Result<U256, Error> {
+    self.nonce_as_of(BlockId::Hash(B256::from(at).into())).await
+  }
+
+  /// Get the nonce for the next command, as of the latest block.
+  ///
+  /// This is not as of a finalized block and accordingly may be reorganized. It should solely be
+  /// used to decide if publishing a command is worthwhile.
+  pub async fn latest_nonce(&self) -> Result<U256, Error> {
+    self.nonce_as_of(BlockId::latest()).await
+  }
+
+  /// Get the domain and message to be signed in order to execute a batch of `OutInstruction`s.
   pub(crate) fn execute_message(
     chain_id: U256,
+    router: [u8; 20],
     nonce: U256,
     outs: Vec<abi::OutInstruction>,
-  ) -> Vec<u8> {
-    ("execute".to_string(), chain_id, nonce, outs).abi_encode_params()
+  ) -> (SignatureDomain, Vec<u8>) {
+    let domain = SignatureDomain { chain_id, verifier: router, purpose: b"execute" };
+    (domain, (nonce, outs).abi_encode_params())
   }
 
   /// Execute a batch of `OutInstruction`s.
@@ -225,12 +391,16 @@ impl Router {
     TxLegacy {
       to: TxKind::Call(self.1),
       input: abi::executeCall::new((outs.to_vec(), sig.into())).abi_encode().into(),
-      // TODO
-      gas_limit: 100_000 + ((200_000 + 10_000) * u64::try_from(outs.len()).unwrap()),
+      gas_limit: Self::execute_gas(outs.len()),
       ..Default::default()
     }
   }
 
+  /// The gas limit used for an `execute` call with the specified amount of `OutInstruction`s.
+  pub fn execute_gas(outs: usize) -> u64 {
+    EXECUTE_BASE_GAS + (EXECUTE_GAS_PER_OUT_INSTRUCTION * u64::try_from(outs).unwrap())
+  }
+
   pub async fn key_at_end_of_block(&self, block: u64) -> Result<Option<ProjectivePoint>, Error> {
     let filter = Filter::new().from_block(0).to_block(block).address(self.1);
     let filter = filter.event_signature(SeraiKeyUpdated::SIGNATURE_HASH);
@@ -256,10 +426,199 @@ impl Router {
     Ok(Some(key))
   }
 
+  /// If the acceptance of `InInstruction`s was paused as of the end of the specified block.
+  ///
+  /// This is built by scanning the Router's events, and the results are cached. Accordingly, only
+  /// finalized blocks should be queried.
+  pub async fn paused_at_end_of_block(&self, block: u64) -> Result<bool, Error> {
+    self.scan_history(block).await?;
+    let history = self.2.lock().unwrap();
+    Ok(
+      history
+        .paused_updates
+        .iter()
+        .rev()
+        .find(|(updated_in, _)| *updated_in <= block)
+        .is_some_and(|(_, paused)| *paused),
+    )
+  }
+
+  /// If the acceptance of `InInstruction`s was paused at any point within each of the specified
+  /// blocks.
+  ///
+  /// A block is considered paused if the Router was paused at its start, or was paused by a
+  /// command executed within it. This is built by scanning the Router's events, and the results
+  /// are cached. Accordingly, only finalized blocks should be queried.
+  pub async fn paused_in_blocks(&self, blocks: RangeInclusive<u64>) -> Result<Vec<bool>, Error> {
+    self.scan_history(*blocks.end()).await?;
+    let history = self.2.lock().unwrap();
+
+    let mut res = vec![];
+    // If the Router was paused as of the start of the next block
+    let mut paused = history
+      .paused_updates
+      .iter()
+      .rev()
+      .find(|(updated_in, _)| *updated_in < *blocks.start())
+      .is_some_and(|(_, paused)| *paused);
+    for block in blocks {
+      let mut paused_within = paused;
+      for (_, update) in
+        history.paused_updates.iter().filter(|(updated_in, _)| *updated_in == block)
+      {
+        paused_within |= *update;
+        paused = *update;
+      }
+      res.push(paused_within);
+    }
+    Ok(res)
+  }
+
+  async fn history_logs<E: SolEvent>(
+    &self,
+    from: u64,
+    to: u64,
+  ) -> Result<Vec<(u64, [u8; 32], E)>, Error> {
+    let filter = Filter::new().from_block(from).to_block(to).address(self.1);
+    let filter = filter.event_signature(E::SIGNATURE_HASH);
+    let logs = self.0.get_logs(&filter).await.map_err(|_| Error::ConnectionError)?;
+
+    let mut res = vec![];
+    for log in logs {
+      // Double check the address which emitted this log
+      if log.address() != self.1 {
+        Err(Error::ConnectionError)?;
+      }
+
+      let block = log.block_number.ok_or(Error::ConnectionError)?;
+      let tx_id = log.transaction_hash.ok_or(Error::ConnectionError)?.into();
+      let log = log.log_decode::<E>().map_err(|_| Error::ConnectionError)?.inner.data;
+      res.push((block, tx_id, log));
+    }
+    Ok(res)
+  }
+
+  // Scan the Router's events through the specified block, caching the resulting history
+  async fn scan_history(&self, through: u64) -> Result<(), Error> {
+    let from = match self.2.lock().unwrap().scanned_through {
+      Some(scanned_through) if scanned_through >= through => return Ok(()),
+      Some(scanned_through) => scanned_through + 1,
+      None => 0,
+    };
+
+    let mut key_updates = vec![];
+    let mut nonces = vec![];
+    let mut paused_updates = vec![];
+    for (block, tx_id, update) in self.history_logs::<SeraiKeyUpdated>(from, through).await? {
+      let nonce = update.nonce.try_into().map_err(|_| Error::ConnectionError)?;
+      let key = PublicKey::from_eth_repr(update.key.0).ok_or(Error::ConnectionError)?;
+      key_updates.push(KeyUpdate { block, tx_id, nonce, key });
+      nonces.push((block, nonce));
+    }
+    for (block, _, update) in self.history_logs::<PausedUpdated>(from, through).await? {
+      let nonce = update.nonce.try_into().map_err(|_| Error::ConnectionError)?;
+      paused_updates.push((nonce, block, update.paused));
+      nonces.push((block, nonce));
+    }
+    for (block, _, executed) in self.history_logs::<ExecutedEvent>(from, through).await? {
+      nonces.push((block, executed.nonce.try_into().map_err(|_| Error::ConnectionError)?));
+    }
+    key_updates.sort_by_key(|update| update.nonce);
+    nonces.sort_by_key(|(_, nonce)| *nonce);
+    paused_updates.sort_by_key(|(nonce, _, _)| *nonce);
+
+    let mut history = self.2.lock().unwrap();
+    // If the history was updated while we were scanning, defer to that update
+    if history.scanned_through.map_or(0, |scanned_through| scanned_through + 1) != from {
+      return Ok(());
+    }
+    history.key_updates.extend(key_updates);
+    history.nonces.extend(nonces);
+    history
+      .paused_updates
+      .extend(paused_updates.into_iter().map(|(_, block, paused)| (block, paused)));
+    history.scanned_through = Some(through);
+    Ok(())
+  }
+
+  /// The updates to the key for Serai which occurred within the specified range of blocks.
+  ///
+  /// This is built by scanning the Router's events, and the results are cached. Accordingly, only
+  /// finalized blocks should be queried.
+  pub async fn key_history(&self, blocks: RangeInclusive<u64>) -> Result<Vec<KeyUpdate>, Error> {
+    self.scan_history(*blocks.end()).await?;
+    let history = self.2.lock().unwrap();
+    Ok(
+      history.key_updates.iter().filter(|update| blocks.contains(&update.block)).copied().collect(),
+    )
+  }
+
+  /// The nonce of the next command, as of the end of the specified block.
+  ///
+  /// Returns `None` if the Router wasn't deployed as of the specified block.
+  ///
+  /// This is built by scanning the Router's events, and the results are cached. Accordingly, only
+  /// finalized blocks should be queried.
+  pub async fn nonce_at(&self, block: u64) -> Result<Option<u64>, Error> {
+    self.scan_history(block).await?;
+    let history = self.2.lock().unwrap();
+    let executed_by_block =
+      history.nonces.partition_point(|(executed_in, _)| *executed_in <= block);
+    Ok(executed_by_block.checked_sub(1).map(|i| history.nonces[i].1 + 1))
+  }
+
+  /// The blocks within the specified range in which the Router emitted an event, or in which one
+  /// of the specified tokens emitted a `Transfer` to the Router.
+  ///
+  /// This is intended to index long ranges of blocks at once, letting scanners skip the blocks
+  /// without anything relevant to them. Providers may limit how many blocks or logs a single query
+  /// may span, in which case this will error and a smaller range should be queried.
+  pub async fn blocks_with_events(
+    &self,
+    blocks: RangeInclusive<u64>,
+    tokens: &HashSet<[u8; 20]>,
+  ) -> Result<BTreeSet<u64>, Error> {
+    let mut res = BTreeSet::new();
+
+    let filter = Filter::new().from_block(*blocks.start()).to_block(*blocks.end()).address(self.1);
+    for log in self.0.get_logs(&filter).await.map_err(|_| Error::ConnectionError)? {
+      // Double check the address which emitted this log
+      if log.address() != self.1 {
+        Err(Error::ConnectionError)?;
+      }
+      res.insert(log.block_number.ok_or(Error::ConnectionError)?);
+    }
+
+    let mut to_topic = [0; 32];
+    to_topic[12 ..].copy_from_slice(self.1.as_slice());
+    for token in tokens {
+      let token = Address::from(*token);
+      let filter = Filter::new().from_block(*blocks.start()).to_block(*blocks.end()).address(token);
+      let filter = filter.event_signature(Transfer::SIGNATURE_HASH).topic2(B256::from(to_topic));
+      for log in self.0.get_logs(&filter).await.map_err(|_| Error::ConnectionError)? {
+        if log.address() != token {
+          Err(Error::ConnectionError)?;
+        }
+        res.insert(log.block_number.ok_or(Error::ConnectionError)?);
+      }
+    }
+
+    Ok(res)
+  }
+
+  /// The `InInstruction`s within a block.
+  ///
+  /// Only `InInstruction`s for ETH, or for the allowed tokens, are returned. Deposits made via
+  /// ERC-677's `transferAndCall` are only returned for the tokens specified as ERC-677 tokens, as
+  /// the semantics of a call to the Router from within a token differ by token. A WETH `Deposit`
+  /// is only accepted as the transfer for an `InInstruction` if it was emitted by the specified
+  /// WETH contract, as other tokens may emit a distinct event with the same signature.
   pub async fn in_instructions(
     &self,
     block: u64,
     allowed_tokens: &HashSet<[u8; 20]>,
+    erc677_tokens: &HashSet<[u8; 20]>,
+    weth: Option<[u8; 20]>,
   ) -> Result<Vec<InInstruction>, Error> {
     let Some(key_at_end_of_block) = self.key_at_end_of_block(block).await? else {
       return Ok(vec![]);
@@ -310,7 +669,10 @@ impl Router {
         //
         // This will either let it be handled by the top-level transfer hook or will drop it
         // entirely on the side of caution
-        if tx.to == Some(token.into()) {
+        //
+        // ERC-677 tokens call the Router from within `transferAndCall`, which the top-level
+        // transfer hook doesn't recognize, so their top-level calls aren't dropped
+        if (tx.to == Some(token.into())) && !erc677_tokens.contains(&token) {
           continue;
         }
 
@@ -336,14 +698,20 @@ impl Router {
           if tx_log.address().0 != token {
             continue;
           }
-          // Check if this is a transfer log
+          // Check if this is a transfer log, or a deposit log if this was ETH wrapped into WETH
           // https://github.com/alloy-rs/core/issues/589
-          if tx_log.topics()[0] != Transfer::SIGNATURE_HASH {
+          let Some(topic) = tx_log.topics().first() else { continue };
+          let (to, value) = if *topic == Transfer::SIGNATURE_HASH {
+            let Ok(transfer) = Transfer::decode_log(&tx_log.inner.clone(), true) else { continue };
+            (transfer.to, transfer.value)
+          } else if (*topic == Deposit::SIGNATURE_HASH) && (weth == Some(token)) {
+            let Ok(deposit) = Deposit::decode_log(&tx_log.inner.clone(), true) else { continue };
+            (deposit.dst, deposit.wad)
+          } else {
             continue;
-          }
-          let Ok(transfer) = Transfer::decode_log(&tx_log.inner.clone(), true) else { continue };
+          };
           // Check if this is a transfer to us for the expected amount
-          if (transfer.to == self.1) && (transfer.value == log.amount) {
+          if (to == self.1) && (value == log.amount) {
             transfer_check.insert(log_index);
             found_transfer = true;
             break;
@@ -403,6 +771,32 @@ impl Router {
       }
     }
 
+    {
+      let filter = Filter::new().from_block(block).to_block(block).address(self.1);
+      let filter = filter.event_signature(PausedUpdated::SIGNATURE_HASH);
+      let logs = self.0.get_logs(&filter).await.map_err(|_| Error::ConnectionError)?;
+
+      for log in logs {
+        // Double check the address which emitted this log
+        if log.address() != self.1 {
+          Err(Error::ConnectionError)?;
+        }
+
+        let tx_id = log.transaction_hash.ok_or(Error::ConnectionError)?.into();
+
+        let log = log.log_decode::<PausedUpdated>().map_err(|_| Error::ConnectionError)?.inner.data;
+
+        let mut signature = [0; 64];
+        signature[.. 32].copy_from_slice(log.signature.c.as_ref());
+        signature[32 ..].copy_from_slice(log.signature.s.as_ref());
+        res.push(Executed {
+          tx_id,
+          nonce: log.nonce.try_into().map_err(|_| Error::ConnectionError)?,
+          signature,
+        });
+      }
+    }
+
     {
       let filter = Filter::new().from_block(block).to_block(block).address(self.1);
       let filter = filter.event_signature(ExecutedEvent::SIGNATURE_HASH);
@@ -437,6 +831,10 @@ impl Router {
     Filter::new().address(self.1).event_signature(SeraiKeyUpdated::SIGNATURE_HASH)
   }
   #[cfg(feature = "tests")]
+  pub fn paused_updated_filter(&self) -> Filter {
+    Filter::new().address(self.1).event_signature(PausedUpdated::SIGNATURE_HASH)
+  }
+  #[cfg(feature = "tests")]
   pub fn executed_filter(&self) -> Filter {
     Filter::new().address(self.1).event_signature(ExecutedEvent::SIGNATURE_HASH)
   }
@@ -32,20 +32,80 @@ contract TestERC20 {
   function transfer(address to, uint256 value) public returns (bool) {
     balances[msg.sender] -= value;
     balances[to] += value;
+    emit Transfer(msg.sender, to, value);
     return true;
   }
   function transferFrom(address from, address to, uint256 value) public returns (bool) {
     allowances[from][msg.sender] -= value;
     balances[from] -= value;
     balances[to] += value;
+    emit Transfer(from, to, value);
     return true;
   }
 
   function approve(address spender, uint256 value) public returns (bool) {
     allowances[msg.sender][spender] = value;
+    emit Approval(msg.sender, spender, value);
     return true;
   }
   function allowance(address owner, address spender) public view returns (uint256) {
     return allowances[owner][spender];
   }
+
+  mapping(address => uint256) public nonces;
+
+  // solhint-disable-next-line func-name-mixedcase
+  function DOMAIN_SEPARATOR() public view returns (bytes32) {
+    return keccak256(
+      abi.encode(
+        keccak256(
+          "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"
+        ),
+        keccak256(bytes(name())),
+        keccak256(bytes("1")),
+        block.chainid,
+        address(this)
+      )
+    );
+  }
+
+  function permit(
+    address owner,
+    address spender,
+    uint256 value,
+    uint256 deadline,
+    uint8 v,
+    bytes32 r,
+    bytes32 s
+  ) public {
+    require(block.timestamp <= deadline);
+    bytes32 digest = keccak256(
+      abi.encodePacked(
+        "\x19\x01",
+        DOMAIN_SEPARATOR(),
+        keccak256(
+          abi.encode(
+            keccak256(
+              "Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)"
+            ),
+            owner,
+            spender,
+            value,
+            nonces[owner]++,
+            deadline
+          )
+        )
+      )
+    );
+    address signer = ecrecover(digest, v, r, s);
+    require((signer != address(0)) && (signer == owner));
+    allowances[owner][spender] = value;
+    emit Approval(owner, spender, value);
+  }
+
+  // Allow anyone to mint, solely for testing
+  function mint(address to, uint256 value) public {
+    balances[to] += value;
+    emit Transfer(address(0), to, value);
+  }
 }
@@ -0,0 +1,17 @@
+// SPDX-License-Identifier: AGPLv3
+pragma solidity ^0.8.0;
+
+import "../../../contracts/IERC20.sol";
+import "./ERC20.sol";
+
+contract TestERC677 is TestERC20 {
+  function transferAndCall(
+    address to,
+    uint256 value,
+    bytes calldata data
+  ) public returns (bool) {
+    transfer(to, value);
+    IERC677Receiver(to).onTokenTransfer(msg.sender, value, data);
+    return true;
+  }
+}
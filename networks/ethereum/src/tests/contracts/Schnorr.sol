@@ -12,4 +12,14 @@ contract TestSchnorr {
   ) external pure returns (bool) {
     return Schnorr.verify(px, message, c, s);
   }
+
+  function verifyWithDomain(
+    bytes32 px,
+    bytes calldata purpose,
+    bytes calldata message,
+    bytes32 c,
+    bytes32 s
+  ) external view returns (bool) {
+    return Schnorr.verifyWithDomain(px, purpose, message, c, s);
+  }
 }
@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: AGPLv3
+pragma solidity ^0.8.0;
+
+// A minimal implementation of WETH9
+contract TestWETH {
+  event Transfer(address indexed from, address indexed to, uint256 value);
+  event Approval(address indexed owner, address indexed spender, uint256 value);
+  event Deposit(address indexed dst, uint256 wad);
+  event Withdrawal(address indexed src, uint256 wad);
+
+  mapping(address => uint256) public balanceOf;
+  mapping(address => mapping(address => uint256)) public allowance;
+
+  function decimals() public pure returns (uint8) {
+    return 18;
+  }
+
+  function totalSupply() public view returns (uint256) {
+    return address(this).balance;
+  }
+
+  function deposit() public payable {
+    balanceOf[msg.sender] += msg.value;
+    emit Deposit(msg.sender, msg.value);
+  }
+
+  function withdraw(uint256 wad) public {
+    require(balanceOf[msg.sender] >= wad);
+    balanceOf[msg.sender] -= wad;
+    payable(msg.sender).transfer(wad);
+    emit Withdrawal(msg.sender, wad);
+  }
+
+  function approve(address spender, uint256 value) public returns (bool) {
+    allowance[msg.sender][spender] = value;
+    emit Approval(msg.sender, spender, value);
+    return true;
+  }
+
+  function transfer(address to, uint256 value) public returns (bool) {
+    return transferFrom(msg.sender, to, value);
+  }
+
+  function transferFrom(address from, address to, uint256 value) public returns (bool) {
+    require(balanceOf[from] >= value);
+    if ((from != msg.sender) && (allowance[from][msg.sender] != type(uint256).max)) {
+      require(allowance[from][msg.sender] >= value);
+      allowance[from][msg.sender] -= value;
+    }
+    balanceOf[from] -= value;
+    balanceOf[to] += value;
+    emit Transfer(from, to, value);
+    return true;
+  }
+}
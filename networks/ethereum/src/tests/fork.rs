@@ -0,0 +1,165 @@
+use std::sync::Arc;
+
+use alloy_core::primitives::{address, Address, U256, B256};
+use alloy_sol_types::SolCall;
+
+use alloy_rpc_types_eth::{TransactionInput, TransactionRequest};
+use alloy_simple_request_transport::SimpleRequest;
+use alloy_rpc_client::ClientBuilder;
+use alloy_provider::{Provider, RootProvider};
+
+use alloy_node_bindings::{Anvil, AnvilInstance};
+
+use crate::{crypto::keccak256, erc20::abi as erc20};
+
+/// The environment variable specifying the RPC URL to fork from.
+pub const FORK_RPC_URL_ENV: &str = "ETHEREUM_FORK_RPC_URL";
+/// The environment variable specifying the block number to fork at.
+pub const FORK_BLOCK_NUMBER_ENV: &str = "ETHEREUM_FORK_BLOCK_NUMBER";
+
+/// The configuration for a mainnet fork.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ForkConfig {
+  /// The RPC URL of the node to fork from.
+  pub rpc_url: String,
+  /// The block number to fork at, or the latest block if None.
+  pub block_number: Option<u64>,
+}
+
+impl ForkConfig {
+  /// Read the fork configuration from the environment.
+  ///
+  /// Returns None if no RPC URL was specified, signifying fork tests should be skipped.
+  pub fn from_env() -> Option<Self> {
+    let rpc_url = std::env::var(FORK_RPC_URL_ENV).ok()?;
+    let block_number = std::env::var(FORK_BLOCK_NUMBER_ENV)
+      .ok()
+      .map(|block| block.parse().expect("fork block number wasn't a u64"));
+    Some(ForkConfig { rpc_url, block_number })
+  }
+}
+
+/// A real ERC20 present on mainnet.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MainnetErc20 {
+  /// Circle's USD Coin, which has a blacklist.
+  Usdc,
+  /// MakerDAO's Dai.
+  Dai,
+}
+
+impl MainnetErc20 {
+  /// The address of this token on mainnet.
+  pub fn address(self) -> Address {
+    match self {
+      MainnetErc20::Usdc => address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
+      MainnetErc20::Dai => address!("6B175474E89094C44Da98b954EedeAC495271d0F"),
+    }
+  }
+
+  // The storage slot of the `mapping(address => uint256)` used for balances
+  fn balances_slot(self) -> u64 {
+    match self {
+      // FiatTokenV2_2 packs the blacklist flag into the highest bit of this mapping's values
+      MainnetErc20::Usdc => 9,
+      MainnetErc20::Dai => 2,
+    }
+  }
+
+  /// The storage key for the balance of the specified account.
+  pub fn balance_storage_key(self, account: Address) -> B256 {
+    let mut preimage = [0; 64];
+    preimage[12 .. 32].copy_from_slice(account.as_slice());
+    preimage[56 ..].copy_from_slice(&self.balances_slot().to_be_bytes());
+    B256::from(keccak256(&preimage))
+  }
+}
+
+/// Spawn an Anvil instance forking the configured chain.
+pub async fn spawn_fork(config: &ForkConfig) -> (AnvilInstance, Arc<RootProvider<SimpleRequest>>) {
+  let mut anvil = Anvil::new().fork(config.rpc_url.clone());
+  if let Some(block_number) = config.block_number {
+    anvil = anvil.fork_block_number(block_number);
+  }
+  let anvil = anvil.spawn();
+
+  let provider = Arc::new(RootProvider::new(
+    ClientBuilder::default().transport(SimpleRequest::new(anvil.endpoint()), true),
+  ));
+  (anvil, provider)
+}
+
+/// Set the ETH balance of an account via Anvil's cheat codes.
+pub async fn set_balance(
+  provider: &RootProvider<SimpleRequest>,
+  account: Address,
+  balance: U256,
+) -> Option<()> {
+  provider.raw_request::<_, ()>("anvil_setBalance".into(), (account, balance)).await.ok()
+}
+
+/// Impersonate an account via Anvil's cheat codes, allowing sending transactions from it without
+/// its private key.
+pub async fn impersonate(provider: &RootProvider<SimpleRequest>, account: Address) -> Option<()> {
+  provider.raw_request::<_, ()>("anvil_impersonateAccount".into(), (account,)).await.ok()
+}
+
+/// Stop impersonating an account.
+pub async fn stop_impersonating(
+  provider: &RootProvider<SimpleRequest>,
+  account: Address,
+) -> Option<()> {
+  provider.raw_request::<_, ()>("anvil_stopImpersonatingAccount".into(), (account,)).await.ok()
+}
+
+/// Query the balance an account has of an ERC20.
+pub async fn erc20_balance(
+  provider: &RootProvider<SimpleRequest>,
+  token: Address,
+  account: Address,
+) -> Option<U256> {
+  let call = TransactionRequest::default()
+    .to(token)
+    .input(TransactionInput::new(erc20::balanceOfCall::new((account,)).abi_encode().into()));
+  let bytes = provider.call(&call).await.ok()?;
+  let res = erc20::balanceOfCall::abi_decode_returns(&bytes, true).ok()?;
+  Some(res._0)
+}
+
+/// Acquire a real ERC20 by directly writing the account's balance to the token's storage.
+///
+/// This does not update the token's total supply.
+pub async fn acquire_erc20(
+  provider: &RootProvider<SimpleRequest>,
+  token: MainnetErc20,
+  account: Address,
+  amount: U256,
+) -> Option<()> {
+  provider
+    .raw_request::<_, ()>(
+      "anvil_setStorageAt".into(),
+      (token.address(), token.balance_storage_key(account), B256::from(amount)),
+    )
+    .await
+    .ok()?;
+
+  // Verify the write had the intended effect, as the storage layout is assumed
+  if erc20_balance(provider, token.address(), account).await? != amount {
+    None?;
+  }
+  Some(())
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_acquire_erc20() {
+  let Some(config) = ForkConfig::from_env() else { return };
+  let (_anvil, provider) = spawn_fork(&config).await;
+
+  let account = Address::from([0xff; 20]);
+  for token in [MainnetErc20::Usdc, MainnetErc20::Dai] {
+    let amount = U256::from(1_000_000_000u64);
+    acquire_erc20(&provider, token, account, amount).await.unwrap();
+    assert_eq!(erc20_balance(&provider, token.address(), account).await.unwrap(), amount);
+  }
+}
@@ -0,0 +1,184 @@
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Deserialize};
+
+use alloy_rpc_types_eth::TransactionReceipt;
+
+/// The environment variable specifying the path to a JSON report of baselines to compare against.
+pub const GAS_BASELINES_ENV: &str = "ETHEREUM_GAS_BASELINES";
+/// The environment variable specifying the maximum regression, as a percentage of the baseline.
+pub const GAS_MAX_REGRESSION_PERCENT_ENV: &str = "ETHEREUM_GAS_MAX_REGRESSION_PERCENT";
+/// The maximum regression tolerated by default, as a percentage of the baseline.
+pub const DEFAULT_MAX_REGRESSION_PERCENT: u64 = 0;
+
+/// The gas used by an operation, as present within a report.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct OperationReport {
+  /// The gas used by this operation.
+  pub gas_used: u64,
+  /// The baseline this operation was compared against, if there was one.
+  #[serde(default)]
+  pub baseline: Option<u64>,
+  /// If this operation regressed past the tolerated amount.
+  #[serde(default)]
+  pub regressed: bool,
+}
+
+/// A report on the gas used by a set of operations.
+///
+/// A report may be used as the baselines for a future benchmark, allowing CI to compare against
+/// the report produced by a prior run.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct GasReport {
+  /// The maximum regression tolerated, as a percentage of the baseline.
+  #[serde(default)]
+  pub max_regression_percent: u64,
+  /// The operations benchmarked, by name.
+  pub operations: BTreeMap<String, OperationReport>,
+}
+
+impl GasReport {
+  /// Serialize this report to JSON.
+  pub fn to_json(&self) -> String {
+    serde_json::to_string_pretty(self).unwrap()
+  }
+
+  /// Deserialize a report from JSON.
+  pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+    serde_json::from_str(json)
+  }
+}
+
+/// An operation which used more gas than tolerated.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GasRegression {
+  /// The name of the operation.
+  pub operation: String,
+  /// The baseline for this operation.
+  pub baseline: u64,
+  /// The gas used by this operation.
+  pub gas_used: u64,
+}
+
+/// A benchmark of the gas used by operations, compared against per-operation baselines.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GasBenchmark {
+  baselines: BTreeMap<String, u64>,
+  max_regression_percent: u64,
+  gas_used: BTreeMap<String, u64>,
+}
+
+impl GasBenchmark {
+  /// Create a new benchmark with the specified baselines.
+  pub fn new(baselines: BTreeMap<String, u64>, max_regression_percent: u64) -> Self {
+    GasBenchmark { baselines, max_regression_percent, gas_used: BTreeMap::new() }
+  }
+
+  /// Create a new benchmark configured by the environment.
+  ///
+  /// If no baselines were specified, operations are recorded yet never considered regressions.
+  pub fn from_env() -> Self {
+    let baselines = std::env::var(GAS_BASELINES_ENV)
+      .ok()
+      .map(|path| {
+        let json = std::fs::read_to_string(path).expect("couldn't read the gas baselines");
+        GasReport::from_json(&json)
+          .expect("gas baselines weren't a valid report")
+          .operations
+          .into_iter()
+          .map(|(operation, report)| (operation, report.gas_used))
+          .collect()
+      })
+      .unwrap_or_default();
+    let max_regression_percent = std::env::var(GAS_MAX_REGRESSION_PERCENT_ENV)
+      .ok()
+      .map(|percent| percent.parse().expect("max gas regression percent wasn't a u64"))
+      .unwrap_or(DEFAULT_MAX_REGRESSION_PERCENT);
+    Self::new(baselines, max_regression_percent)
+  }
+
+  /// Set the baseline for an operation.
+  pub fn with_baseline(mut self, operation: &str, gas: u64) -> Self {
+    self.baselines.insert(operation.to_string(), gas);
+    self
+  }
+
+  /// Record the gas used by an operation.
+  pub fn record_gas(&mut self, operation: &str, gas_used: u64) {
+    self.gas_used.insert(operation.to_string(), gas_used);
+  }
+
+  /// Record the gas used by the transaction an operation was performed with.
+  pub fn record(&mut self, operation: &str, receipt: &TransactionReceipt) {
+    self.record_gas(operation, u64::try_from(receipt.gas_used).unwrap());
+  }
+
+  fn regressed(&self, baseline: u64, gas_used: u64) -> bool {
+    u128::from(gas_used) * 100 >
+      u128::from(baseline) * u128::from(100 + self.max_regression_percent)
+  }
+
+  /// The operations which used more gas than tolerated.
+  pub fn regressions(&self) -> Vec<GasRegression> {
+    self
+      .gas_used
+      .iter()
+      .filter_map(|(operation, gas_used)| {
+        let baseline = *self.baselines.get(operation)?;
+        self.regressed(baseline, *gas_used).then(|| GasRegression {
+          operation: operation.clone(),
+          baseline,
+          gas_used: *gas_used,
+        })
+      })
+      .collect()
+  }
+
+  /// Produce a report on the operations recorded.
+  pub fn report(&self) -> GasReport {
+    GasReport {
+      max_regression_percent: self.max_regression_percent,
+      operations: self
+        .gas_used
+        .iter()
+        .map(|(operation, gas_used)| {
+          let baseline = self.baselines.get(operation).copied();
+          let regressed = baseline.is_some_and(|baseline| self.regressed(baseline, *gas_used));
+          (operation.clone(), OperationReport { gas_used: *gas_used, baseline, regressed })
+        })
+        .collect(),
+    }
+  }
+
+  /// Print the report and panic if any operation regressed.
+  pub fn assert_no_regressions(&self) {
+    println!("{}", self.report().to_json());
+    let regressions = self.regressions();
+    assert!(regressions.is_empty(), "gas usage regressed: {regressions:?}");
+  }
+}
+
+#[test]
+fn test_gas_benchmark() {
+  let mut benchmark = GasBenchmark::new(BTreeMap::new(), 10).with_baseline("op", 1000);
+  benchmark.record_gas("op", 1100);
+  benchmark.record_gas("unknown", 5000);
+  assert!(benchmark.regressions().is_empty());
+
+  benchmark.record_gas("op", 1101);
+  assert_eq!(
+    benchmark.regressions(),
+    vec![GasRegression { operation: "op".to_string(), baseline: 1000, gas_used: 1101 }]
+  );
+
+  let report = benchmark.report();
+  assert!(report.operations["op"].regressed);
+  assert_eq!(report.operations["unknown"].baseline, None);
+  assert_eq!(GasReport::from_json(&report.to_json()).unwrap(), report);
+
+  // A report can be used as the baselines for a future benchmark
+  let baselines = report.operations.into_iter().map(|(op, report)| (op, report.gas_used)).collect();
+  let mut benchmark = GasBenchmark::new(baselines, 0);
+  benchmark.record_gas("op", 1101);
+  assert!(benchmark.regressions().is_empty());
+}
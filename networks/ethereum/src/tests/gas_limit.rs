@@ -0,0 +1,24 @@
+use crate::gas_limit::{DEFAULT_GAS_MULTIPLIER_PERCENT, gas_limit};
+
+#[test]
+fn test_gas_limit() {
+  // The multiplier should be applied to the estimate
+  assert_eq!(gas_limit(100_000, DEFAULT_GAS_MULTIPLIER_PERCENT, 1_000_000), Some(120_000));
+  assert_eq!(gas_limit(100_000, 150, 1_000_000), Some(150_000));
+
+  // Rounding up
+  assert_eq!(gas_limit(1, DEFAULT_GAS_MULTIPLIER_PERCENT, 1_000_000), Some(2));
+
+  // A multiplier below 100% shouldn't reduce the estimate
+  assert_eq!(gas_limit(100_000, 50, 1_000_000), Some(100_000));
+
+  // The gas limit should be capped
+  assert_eq!(gas_limit(100_000, DEFAULT_GAS_MULTIPLIER_PERCENT, 110_000), Some(110_000));
+  assert_eq!(gas_limit(100_000, DEFAULT_GAS_MULTIPLIER_PERCENT, 100_000), Some(100_000));
+
+  // Unless the estimate itself exceeds the cap
+  assert_eq!(gas_limit(100_001, DEFAULT_GAS_MULTIPLIER_PERCENT, 100_000), None);
+
+  // And shouldn't overflow
+  assert_eq!(gas_limit(u64::MAX, u64::MAX, u64::MAX), Some(u64::MAX));
+}
@@ -0,0 +1,44 @@
+use alloy_core::primitives::U256;
+
+use crate::{
+  machine::{OutInstructionTarget, OutInstruction, RouterCommand},
+  tests::key_gen,
+};
+
+#[test]
+fn test_router_command_serialization() {
+  let (_, public_key) = key_gen();
+  let commands = [
+    RouterCommand::UpdateSeraiKey {
+      chain_id: U256::from(1),
+      router: [0xaa; 20],
+      nonce: U256::from(2),
+      key: public_key,
+    },
+    RouterCommand::Execute {
+      chain_id: U256::from(1),
+      router: [0xaa; 20],
+      nonce: U256::from(3),
+      outs: vec![OutInstruction {
+        target: OutInstructionTarget::Direct([0xbb; 20]),
+        value: U256::from(4),
+      }],
+    },
+    RouterCommand::SetPaused {
+      chain_id: U256::from(1),
+      router: [0xaa; 20],
+      nonce: U256::from(5),
+      paused: true,
+    },
+  ];
+
+  for command in commands {
+    let serialized = command.serialize();
+    assert_eq!(RouterCommand::read(&mut serialized.as_slice()).unwrap(), command);
+
+    // Commands serialized before they were bound to a Router shouldn't be read
+    let mut legacy = serialized.clone();
+    legacy[0] -= 3;
+    assert!(RouterCommand::read(&mut legacy.as_slice()).is_err());
+  }
+}
@@ -25,7 +25,19 @@ mod abi;
 #[cfg(test)]
 mod schnorr;
 #[cfg(test)]
+mod schnorr_differential;
+#[cfg(test)]
 mod router;
+#[cfg(test)]
+mod machine;
+#[cfg(test)]
+mod gas_limit;
+#[cfg(test)]
+mod publisher;
+
+pub mod fork;
+pub mod gas;
+pub mod rehearsal;
 
 pub fn key_gen() -> (HashMap<Participant, ThresholdKeys<Secp256k1>>, PublicKey) {
   let mut keys = frost_key_gen::<_, Secp256k1>(&mut OsRng);
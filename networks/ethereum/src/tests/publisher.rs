@@ -0,0 +1,19 @@
+use crate::publisher::{MIN_GAS_PRICE_BUMP_PERCENT, bumped_gas_price};
+
+#[test]
+fn test_bumped_gas_price() {
+  // The gas price should be bumped by the minimum percentage
+  let prior = 100_000_000_000u128;
+  assert_eq!(bumped_gas_price(prior, 0), prior + (prior * MIN_GAS_PRICE_BUMP_PERCENT / 100));
+
+  // Unless the current gas price is higher
+  assert_eq!(bumped_gas_price(prior, 3 * prior), 3 * prior);
+
+  // The bump should round up, so it always increases the gas price
+  assert_eq!(bumped_gas_price(1, 0), 2);
+  assert_eq!(bumped_gas_price(0, 0), 1);
+  assert_eq!(bumped_gas_price(15, 0), 17);
+
+  // And shouldn't overflow
+  assert_eq!(bumped_gas_price(u128::MAX, 0), u128::MAX);
+}
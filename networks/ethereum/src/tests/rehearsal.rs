@@ -0,0 +1,292 @@
+use std::{
+  time::Instant,
+  collections::{HashSet, HashMap},
+};
+
+use rand_core::{RngCore, OsRng};
+
+use frost::{
+  curve::Secp256k1,
+  Participant, ThresholdKeys,
+  algorithm::IetfSchnorr,
+  tests::{algorithm_machines, sign},
+};
+
+use alloy_core::primitives::{Address, U256, TxKind};
+use alloy_sol_types::SolCall;
+use alloy_consensus::TxLegacy;
+
+use alloy_rpc_types_eth::TransactionReceipt;
+use alloy_simple_request_transport::SimpleRequest;
+use alloy_provider::{Provider, RootProvider};
+
+use serde::{Serialize, Deserialize};
+
+use crate::{
+  crypto::{address, EthereumHram, PublicKey, SignatureDomain, Signature},
+  deployer::Deployer,
+  router::{Router, Coin, abi as router},
+  tests::{
+    key_gen, send,
+    fork::{ForkConfig, spawn_fork, set_balance},
+    gas::GasBenchmark,
+  },
+};
+
+/// The environment variable specifying the path to write a rehearsal's report to.
+pub const REHEARSAL_REPORT_ENV: &str = "ETHEREUM_REHEARSAL_REPORT";
+
+/// A step of a rehearsed key rotation.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct RehearsalStep {
+  /// The name of this step.
+  pub name: String,
+  /// The gas used by this step's transaction.
+  pub gas_used: u64,
+  /// How long this step's transaction took to be included, in milliseconds.
+  pub duration_ms: u64,
+  /// If this step's transaction succeeded.
+  pub succeeded: bool,
+}
+
+/// A report on a rehearsed key rotation.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct RehearsalReport {
+  /// The number of the block the rehearsal forked from.
+  pub fork_block: u64,
+  /// The steps performed, in order.
+  pub steps: Vec<RehearsalStep>,
+  /// How long the entire rehearsal took, in milliseconds.
+  pub duration_ms: u64,
+}
+
+impl RehearsalReport {
+  /// Serialize this report to JSON.
+  pub fn to_json(&self) -> String {
+    serde_json::to_string_pretty(self).unwrap()
+  }
+
+  /// Deserialize a report from JSON.
+  pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+    serde_json::from_str(json)
+  }
+
+  /// The total gas used by the rehearsal.
+  pub fn total_gas_used(&self) -> u64 {
+    self.steps.iter().map(|step| step.gas_used).sum()
+  }
+
+  /// Record the gas used by each step into a benchmark, so it may be compared against baselines.
+  pub fn record_gas(&self, benchmark: &mut GasBenchmark) {
+    for step in &self.steps {
+      benchmark.record_gas(&format!("rehearsal_{}", step.name), step.gas_used);
+    }
+  }
+}
+
+struct Rehearsal {
+  provider: std::sync::Arc<RootProvider<SimpleRequest>>,
+  wallet: k256::ecdsa::SigningKey,
+  steps: Vec<RehearsalStep>,
+}
+
+impl Rehearsal {
+  async fn step(&mut self, name: &str, tx: TxLegacy) -> TransactionReceipt {
+    let start = Instant::now();
+    let receipt = send(&self.provider, &self.wallet, tx)
+      .await
+      .unwrap_or_else(|| panic!("couldn't publish the transaction for {name}"));
+    self.steps.push(RehearsalStep {
+      name: name.to_string(),
+      gas_used: u64::try_from(receipt.gas_used).unwrap(),
+      duration_ms: u64::try_from(start.elapsed().as_millis()).unwrap(),
+      succeeded: receipt.status(),
+    });
+    receipt
+  }
+}
+
+fn sign_message(
+  keys: &HashMap<Participant, ThresholdKeys<Secp256k1>>,
+  public_key: &PublicKey,
+  (domain, message): &(SignatureDomain, Vec<u8>),
+) -> Signature {
+  let algo = IetfSchnorr::<Secp256k1, EthereumHram>::ietf();
+  let bound = domain.bind(message);
+  let sig =
+    sign(&mut OsRng, &algo, keys.clone(), algorithm_machines(&mut OsRng, &algo, keys), &bound);
+  Signature::new(public_key, domain, message, sig).unwrap()
+}
+
+fn deposit(router: &Router, amount: U256, instruction: Vec<u8>) -> TxLegacy {
+  TxLegacy {
+    to: TxKind::Call(router.address().into()),
+    value: amount,
+    input: router::inInstructionCall::new((Address::ZERO, amount, instruction.into()))
+      .abi_encode()
+      .into(),
+    gas_limit: 200_000,
+    ..Default::default()
+  }
+}
+
+/// Rehearse a key rotation on a fork of the configured chain.
+///
+/// This deploys a Router (and the Deployer, if it isn't already present) and accepts deposits
+/// under the initial key. It drains the Router under that key and rotates to a new key. It then
+/// checks the old key is no longer accepted, and accepts and drains deposits under the new key.
+/// The gas used and time taken by each step is reported.
+///
+/// This panics if the rotation doesn't behave as expected.
+pub async fn rehearse_key_rotation(config: &ForkConfig, deposits: usize) -> RehearsalReport {
+  let start = Instant::now();
+  let (_anvil, provider) = spawn_fork(config).await;
+  let fork_block = provider.get_block_number().await.unwrap();
+  let chain_id = U256::from(provider.get_chain_id().await.unwrap());
+
+  // Use a fresh, funded account to publish every transaction
+  let wallet = k256::ecdsa::SigningKey::random(&mut OsRng);
+  let wallet_address = Address::from(address(&(*wallet.verifying_key().as_affine()).into()));
+  set_balance(
+    &provider,
+    wallet_address,
+    U256::from(1_000u64) * U256::from(10u64).pow(U256::from(18)),
+  )
+  .await
+  .unwrap();
+
+  let mut rehearsal = Rehearsal { provider: provider.clone(), wallet, steps: vec![] };
+
+  // Deploy the Deployer, if it isn't already on this chain
+  let deployer = match Deployer::new(provider.clone()).await.unwrap() {
+    Some(deployer) => deployer,
+    None => {
+      let tx = Deployer::deployment_tx();
+      let deployer_deployer = tx.recover_signer().unwrap();
+      set_balance(
+        &provider,
+        deployer_deployer,
+        U256::from(tx.tx().gas_limit) * U256::from(tx.tx().gas_price),
+      )
+      .await
+      .unwrap();
+      let (tx, sig, _) = tx.into_parts();
+      let mut bytes = vec![];
+      tx.encode_with_signature_fields(&sig, &mut bytes);
+      let step_start = Instant::now();
+      let receipt =
+        provider.send_raw_transaction(&bytes).await.unwrap().get_receipt().await.unwrap();
+      assert!(receipt.status());
+      rehearsal.steps.push(RehearsalStep {
+        name: "deploy_deployer".to_string(),
+        gas_used: u64::try_from(receipt.gas_used).unwrap(),
+        duration_ms: u64::try_from(step_start.elapsed().as_millis()).unwrap(),
+        succeeded: true,
+      });
+      Deployer::new(provider.clone()).await.unwrap().expect("deployer wasn't deployed")
+    }
+  };
+
+  // Deploy the Router with the initial key
+  let (old_keys, old_key) = key_gen();
+  assert!(rehearsal.step("deploy_router", deployer.deploy_router(&old_key)).await.status());
+  let router = deployer.find_router(provider.clone(), &old_key).await.unwrap().unwrap();
+
+  let amount = U256::from(1_000_000_000u64);
+  // Use a fresh recipient so its balance is solely what's sent to it during the rehearsal
+  let recipient = {
+    let mut recipient = [0; 20];
+    OsRng.fill_bytes(&mut recipient);
+    Address::from(recipient)
+  };
+  let execute = |keys: &HashMap<_, _>, key: &PublicKey, nonce: u64, value: U256| {
+    let outs = vec![router::OutInstruction { to: recipient, value, calls: vec![] }];
+    let message =
+      Router::execute_message(chain_id, router.address(), U256::from(nonce), outs.clone());
+    router.execute(&outs, &sign_message(keys, key, &message))
+  };
+
+  // Accept deposits under the initial key
+  for i in 0 .. deposits {
+    let receipt =
+      rehearsal.step(&format!("old_key_deposit_{i}"), deposit(&router, amount, vec![1])).await;
+    assert!(receipt.status());
+    let in_instructions = router
+      .in_instructions(receipt.block_number.unwrap(), &HashSet::new(), &HashSet::new(), None)
+      .await
+      .unwrap();
+    assert_eq!(in_instructions.len(), 1);
+    assert_eq!(in_instructions[0].coin, Coin::Ether);
+    assert_eq!(in_instructions[0].key_at_end_of_block, old_key.point());
+  }
+
+  // Drain the Router under the initial key, as done before a rotation
+  let mut nonce = 1;
+  let old_key_funds = amount * U256::from(deposits);
+  let receipt =
+    rehearsal.step("old_key_drain", execute(&old_keys, &old_key, nonce, old_key_funds)).await;
+  assert!(receipt.status());
+  nonce += 1;
+
+  // Rotate to the new key
+  let (new_keys, new_key) = key_gen();
+  let message =
+    Router::update_serai_key_message(chain_id, router.address(), U256::from(nonce), &new_key);
+  let tx = router.update_serai_key(&new_key, &sign_message(&old_keys, &old_key, &message));
+  let receipt = rehearsal.step("update_serai_key", tx).await;
+  assert!(receipt.status());
+  nonce += 1;
+  let block = receipt.block_number.unwrap();
+  assert_eq!(router.key_at_end_of_block(block).await.unwrap(), Some(new_key.point()));
+
+  // The initial key should no longer be able to execute
+  let receipt =
+    rehearsal.step("old_key_rejected", execute(&old_keys, &old_key, nonce, U256::ZERO)).await;
+  assert!(!receipt.status());
+
+  // Accept deposits under the new key
+  for i in 0 .. deposits {
+    let receipt =
+      rehearsal.step(&format!("new_key_deposit_{i}"), deposit(&router, amount, vec![2])).await;
+    assert!(receipt.status());
+    let in_instructions = router
+      .in_instructions(receipt.block_number.unwrap(), &HashSet::new(), &HashSet::new(), None)
+      .await
+      .unwrap();
+    assert_eq!(in_instructions.len(), 1);
+    assert_eq!(in_instructions[0].key_at_end_of_block, new_key.point());
+  }
+
+  // Drain the Router under the new key
+  let nonce = u64::try_from(router.latest_nonce().await.unwrap()).unwrap();
+  let new_key_funds = amount * U256::from(deposits);
+  let receipt =
+    rehearsal.step("new_key_drain", execute(&new_keys, &new_key, nonce, new_key_funds)).await;
+  assert!(receipt.status());
+  assert_eq!(provider.get_balance(router.address().into()).await.unwrap(), U256::ZERO);
+  assert_eq!(provider.get_balance(recipient).await.unwrap(), old_key_funds + new_key_funds);
+
+  RehearsalReport {
+    fork_block,
+    steps: rehearsal.steps,
+    duration_ms: u64::try_from(start.elapsed().as_millis()).unwrap(),
+  }
+}
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_rehearse_key_rotation() {
+  let Some(config) = ForkConfig::from_env() else { return };
+  let report = rehearse_key_rotation(&config, 2).await;
+  assert!(report.steps.iter().any(|step| step.name == "update_serai_key"));
+  assert_eq!(RehearsalReport::from_json(&report.to_json()).unwrap(), report);
+
+  println!("{}", report.to_json());
+  if let Ok(path) = std::env::var(REHEARSAL_REPORT_ENV) {
+    std::fs::write(path, report.to_json()).expect("couldn't write the rehearsal report");
+  }
+
+  let mut gas = GasBenchmark::from_env();
+  report.record_gas(&mut gas);
+  gas.assert_no_regressions();
+}
@@ -1,4 +1,8 @@
-use std::{convert::TryFrom, sync::Arc, collections::HashMap};
+use std::{
+  convert::TryFrom,
+  sync::Arc,
+  collections::{HashSet, HashMap, BTreeSet},
+};
 
 use rand_core::OsRng;
 
@@ -11,7 +15,9 @@ use frost::{
   tests::{algorithm_machines, sign},
 };
 
-use alloy_core::primitives::{Address, U256};
+use alloy_core::primitives::{Address, U256, TxKind};
+use alloy_sol_types::{SolCall, sol};
+use alloy_consensus::TxLegacy;
 
 use alloy_simple_request_transport::SimpleRequest;
 use alloy_rpc_types_eth::BlockTransactionsKind;
@@ -21,12 +27,20 @@ use alloy_provider::{Provider, RootProvider};
 use alloy_node_bindings::{Anvil, AnvilInstance};
 
 use crate::{
+  Error,
   crypto::*,
   deployer::Deployer,
-  router::{Router, abi as router},
-  tests::{key_gen, send, fund_account},
+  gas_limit::{DEFAULT_GAS_MULTIPLIER_PERCENT, GasEstimator},
+  erc20::{Erc20, Permit, PermitSignature},
+  router::{Router, Coin, abi as router},
+  publisher::{TransactionSigner, Publication, Publisher},
+  tests::{key_gen, send, fund_account, deploy_contract, gas::GasBenchmark},
 };
 
+sol! {
+  function mint(address to, uint256 value);
+}
+
 async fn setup_test() -> (
   AnvilInstance,
   Arc<RootProvider<SimpleRequest>>,
@@ -101,19 +115,21 @@ async fn test_deploy_contract() {
   let block_hash = latest_block_hash(&client).await;
   assert_eq!(router.serai_key(block_hash).await.unwrap(), public_key);
   assert_eq!(router.nonce(block_hash).await.unwrap(), U256::try_from(1u64).unwrap());
+  assert_eq!(router.schnorr_domain_version().await.unwrap(), DOMAIN_VERSION);
   // TODO: Check it emitted SeraiKeyUpdated(public_key) at its genesis
 }
 
 pub fn hash_and_sign(
   keys: &HashMap<Participant, ThresholdKeys<Secp256k1>>,
   public_key: &PublicKey,
-  message: &[u8],
+  (domain, message): &(SignatureDomain, Vec<u8>),
 ) -> Signature {
   let algo = IetfSchnorr::<Secp256k1, EthereumHram>::ietf();
+  let bound = domain.bind(message);
   let sig =
-    sign(&mut OsRng, &algo, keys.clone(), algorithm_machines(&mut OsRng, &algo, keys), message);
+    sign(&mut OsRng, &algo, keys.clone(), algorithm_machines(&mut OsRng, &algo, keys), &bound);
 
-  Signature::new(public_key, message, sig).unwrap()
+  Signature::new(public_key, domain, message, sig).unwrap()
 }
 
 #[tokio::test]
@@ -128,11 +144,24 @@ async fn test_router_update_serai_key() {
 
   let message = Router::update_serai_key_message(
     U256::try_from(chain_id).unwrap(),
+    contract.address(),
     U256::try_from(1u64).unwrap(),
     &next_key,
   );
   let sig = hash_and_sign(&keys, &public_key, &message);
 
+  // A signature for another contract, despite using the same key, shouldn't be accepted
+  let other_domain = SignatureDomain { verifier: [0xff; 20], ..message.0 };
+  let other_sig = hash_and_sign(&keys, &public_key, &(other_domain, message.1.clone()));
+  let receipt = send(
+    &client,
+    &anvil.keys()[0].clone().into(),
+    contract.update_serai_key(&next_key, &other_sig),
+  )
+  .await
+  .unwrap();
+  assert!(!receipt.status());
+
   let first_block_hash = latest_block_hash(&client).await;
   assert_eq!(contract.serai_key(first_block_hash).await.unwrap(), public_key);
 
@@ -146,10 +175,22 @@ async fn test_router_update_serai_key() {
   assert_eq!(contract.serai_key(second_block_hash).await.unwrap(), next_key);
   // Check this does still offer the historical state
   assert_eq!(contract.serai_key(first_block_hash).await.unwrap(), public_key);
-  // TODO: Check logs
 
-  println!("gas used: {:?}", receipt.gas_used);
-  // println!("logs: {:?}", receipt.logs);
+  // Check the history reconstructed from the logs
+  let block = client.get_block_number().await.unwrap();
+  let history = contract.key_history(0 ..= block).await.unwrap();
+  assert_eq!(history.len(), 2);
+  assert_eq!((history[0].nonce, history[0].key), (0, public_key));
+  assert_eq!((history[1].nonce, history[1].key), (1, next_key));
+  assert_eq!(history[1].tx_id, receipt.transaction_hash.0);
+  assert_eq!(contract.key_history(block ..= block).await.unwrap(), vec![history[1]]);
+  assert_eq!(contract.nonce_at(0).await.unwrap(), None);
+  assert_eq!(contract.nonce_at(history[0].block).await.unwrap(), Some(1));
+  assert_eq!(contract.nonce_at(block).await.unwrap(), Some(2));
+
+  let mut gas = GasBenchmark::from_env();
+  gas.record("router_update_serai_key", &receipt);
+  gas.assert_no_regressions();
 }
 
 #[tokio::test]
@@ -165,7 +206,12 @@ async fn test_router_execute() {
   let nonce = contract.nonce(first_block_hash).await.unwrap();
   assert_eq!(nonce, U256::try_from(1u64).unwrap());
 
-  let message = Router::execute_message(U256::try_from(chain_id).unwrap(), nonce, txs.clone());
+  let message = Router::execute_message(
+    U256::try_from(chain_id).unwrap(),
+    contract.address(),
+    nonce,
+    txs.clone(),
+  );
   let sig = hash_and_sign(&keys, &public_key, &message);
 
   let receipt =
@@ -178,6 +224,477 @@ async fn test_router_execute() {
   assert_eq!(contract.nonce(first_block_hash).await.unwrap(), U256::try_from(1u64).unwrap());
   // TODO: Check logs
 
-  println!("gas used: {:?}", receipt.gas_used);
-  // println!("logs: {:?}", receipt.logs);
+  // The only blocks with events should be the Router's deployment and this execution
+  let block = receipt.block_number.unwrap();
+  let deployed = contract.key_history(0 ..= block).await.unwrap()[0].block;
+  assert_eq!(
+    contract.blocks_with_events(0 ..= block, &HashSet::new()).await.unwrap(),
+    BTreeSet::from([deployed, block])
+  );
+  assert!(contract
+    .blocks_with_events(0 ..= (deployed - 1), &HashSet::new())
+    .await
+    .unwrap()
+    .is_empty());
+
+  let mut gas = GasBenchmark::from_env();
+  gas.record("router_execute", &receipt);
+  gas.assert_no_regressions();
+}
+
+#[tokio::test]
+async fn test_router_gas_estimation() {
+  let (anvil, client, chain_id, contract, keys, public_key) = setup_test().await;
+  let estimator = GasEstimator::new(client.clone(), DEFAULT_GAS_MULTIPLIER_PERCENT);
+
+  // execute should never be estimated, as its OutInstructions are forwarded a fixed amount of gas
+  let txs =
+    vec![router::OutInstruction { to: Address::from([0; 20]), value: U256::ZERO, calls: vec![] }];
+  let message = Router::execute_message(
+    U256::try_from(chain_id).unwrap(),
+    contract.address(),
+    contract.latest_nonce().await.unwrap(),
+    txs.clone(),
+  );
+  let sig = hash_and_sign(&keys, &public_key, &message);
+  assert_eq!(
+    estimator.estimate(None, &contract.execute(&txs, &sig)).await,
+    Ok(Router::execute_gas(txs.len()))
+  );
+
+  let message = Router::set_paused_message(
+    U256::try_from(chain_id).unwrap(),
+    contract.address(),
+    contract.latest_nonce().await.unwrap(),
+    true,
+  );
+  let sig = hash_and_sign(&keys, &public_key, &message);
+  let mut tx = contract.set_paused(true, &sig);
+  let fixed = tx.gas_limit;
+
+  // An estimate exceeding the gas limit the transaction was constructed with should be refused
+  let mut capped = tx.clone();
+  capped.gas_limit = 21_000;
+  assert_eq!(estimator.estimate(None, &capped).await, Err(Error::GasLimitExceeded));
+
+  // The estimated gas limit should be less than the fixed gas limit, yet still suffice
+  estimator.set_gas_limit(None, &mut tx).await.unwrap();
+  assert!(tx.gas_limit < fixed);
+  let receipt = send(&client, &anvil.keys()[0].clone().into(), tx).await.unwrap();
+  assert!(receipt.status());
+  assert!(contract.paused_at_end_of_block(receipt.block_number.unwrap()).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_publisher_poll() {
+  let (anvil, client, chain_id, contract, keys, public_key) = setup_test().await;
+
+  // Stop mining blocks automatically, so the published transactions remain pending
+  client.raw_request::<_, ()>("evm_setAutomine".into(), (false,)).await.unwrap();
+  let mine = || async {
+    client.raw_request::<_, ()>("anvil_mine".into(), (U256::from(1u8),)).await.unwrap();
+  };
+
+  // Resubmit any transaction left unmined as of the next poll
+  let mut publisher =
+    Publisher::new(client.clone(), TransactionSigner::Account(anvil.keys()[1].clone().into()), 0);
+
+  // Publish the command with nonce 1
+  let txs =
+    vec![router::OutInstruction { to: Address::from([0; 20]), value: U256::ZERO, calls: vec![] }];
+  let message = Router::execute_message(
+    U256::try_from(chain_id).unwrap(),
+    contract.address(),
+    contract.latest_nonce().await.unwrap(),
+    txs.clone(),
+  );
+  let sig = hash_and_sign(&keys, &public_key, &message);
+  let Publication::Published(execute_hash) =
+    publisher.publish(1, contract.execute(&txs, &sig)).await.unwrap()
+  else {
+    panic!("funded account was considered unfunded")
+  };
+
+  // Publish a transaction for a future command, which shouldn't collide with the pending
+  // transaction's nonce
+  let transfer =
+    TxLegacy { to: TxKind::Call(Address::from([1; 20])), gas_limit: 21_000, ..Default::default() };
+  let Publication::Published(transfer_hash) = publisher.publish(3, transfer.clone()).await.unwrap()
+  else {
+    panic!("funded account was considered unfunded")
+  };
+  assert_eq!(publisher.pending(), [1, 3]);
+
+  // Both should be resubmitted with a bumped fee, replacing the prior transactions
+  let resubmissions = publisher.poll(&contract).await.unwrap();
+  assert_eq!(resubmissions.len(), 2);
+  assert_eq!(resubmissions[0].0, 1);
+  assert_eq!(resubmissions[1].0, 3);
+  assert_ne!(resubmissions[0].1, Publication::Published(execute_hash));
+  assert_ne!(resubmissions[1].1, Publication::Published(transfer_hash));
+
+  // Once mined, neither should be tracked
+  // The command with nonce 1 is retired by the Router's nonce, while the transaction for the
+  // command with nonce 3 is found to have been mined
+  mine().await;
+  assert_eq!(contract.latest_nonce().await.unwrap(), U256::from(2u8));
+  assert!(publisher.poll(&contract).await.unwrap().is_empty());
+  assert!(publisher.pending().is_empty());
+
+  // A transaction for a command the Router already executed should no longer be tracked, even
+  // though it was never mined
+  let Publication::Published(_) = publisher.publish(1, transfer).await.unwrap() else {
+    panic!("funded account was considered unfunded")
+  };
+  assert_eq!(publisher.pending(), [1]);
+  assert!(publisher.poll(&contract).await.unwrap().is_empty());
+  assert!(publisher.pending().is_empty());
+}
+
+#[tokio::test]
+async fn test_router_set_paused() {
+  let (anvil, client, chain_id, contract, keys, public_key) = setup_test().await;
+
+  let start = client.get_block_number().await.unwrap();
+  assert!(!contract.paused_at_end_of_block(start).await.unwrap());
+
+  for (nonce, paused) in [(1u64, true), (2, false)] {
+    let nonce = U256::try_from(nonce).unwrap();
+    let message = Router::set_paused_message(
+      U256::try_from(chain_id).unwrap(),
+      contract.address(),
+      nonce,
+      paused,
+    );
+    let sig = hash_and_sign(&keys, &public_key, &message);
+
+    let receipt = send(&client, &anvil.keys()[0].clone().into(), contract.set_paused(paused, &sig))
+      .await
+      .unwrap();
+    assert!(receipt.status());
+
+    let block_hash = latest_block_hash(&client).await;
+    assert_eq!(contract.nonce(block_hash).await.unwrap(), nonce + U256::from(1u8));
+
+    let block = client.get_block_number().await.unwrap();
+    assert_eq!(contract.paused_at_end_of_block(block).await.unwrap(), paused);
+    // The pause should be recognized as an executed command
+    let executed = contract.executed_commands(block).await.unwrap();
+    assert_eq!(executed.len(), 1);
+    assert_eq!(U256::from(executed[0].nonce), nonce);
+  }
+
+  // Check this does still offer the historical state
+  assert!(!contract.paused_at_end_of_block(start).await.unwrap());
+  assert!(contract.paused_at_end_of_block(start + 1).await.unwrap());
+
+  // The block unpausing the Router was still paused at its start
+  assert_eq!(contract.paused_in_blocks(start ..= (start + 2)).await.unwrap(), [false, true, true]);
+  assert_eq!(contract.paused_in_blocks((start + 2) ..= (start + 2)).await.unwrap(), [true]);
+}
+
+#[tokio::test]
+async fn test_router_in_instruction_with_permit() {
+  let (anvil, client, _, contract, _, public_key) = setup_test().await;
+
+  let funder: k256::ecdsa::SigningKey = anvil.keys()[0].clone().into();
+  let token = deploy_contract(client.clone(), &funder, "TestERC20").await.unwrap();
+  let erc20 = Erc20::new(client.clone(), **token);
+
+  let wallet: k256::ecdsa::SigningKey = anvil.keys()[1].clone().into();
+  let user = address(&(*wallet.verifying_key().as_affine()).into());
+
+  let amount = U256::from(1_000_000u64);
+  let mint = TxLegacy {
+    to: TxKind::Call(token),
+    input: mintCall::new((user.into(), amount)).abi_encode().into(),
+    gas_limit: 100_000,
+    ..Default::default()
+  };
+  assert!(send(&client, &funder, mint).await.unwrap().status());
+
+  let deadline = U256::MAX;
+  let permit = Permit {
+    owner: user,
+    spender: contract.address(),
+    value: amount,
+    nonce: erc20.permit_nonce(user).await.unwrap(),
+    deadline,
+  };
+  let digest = permit.digest(erc20.domain_separator().await.unwrap());
+  let (sig, recovery_id) = wallet.sign_prehash_recoverable(&digest).unwrap();
+  let sig = PermitSignature::new(&sig, recovery_id);
+
+  let instruction = vec![0xff; 32];
+  let receipt = send(
+    &client,
+    &wallet,
+    contract.in_instruction_with_permit(**token, amount, deadline, &sig, instruction.clone()),
+  )
+  .await
+  .unwrap();
+  assert!(receipt.status());
+  // The permit should have been consumed
+  assert_eq!(erc20.permit_nonce(user).await.unwrap(), U256::from(1u8));
+
+  let block = receipt.block_number.unwrap();
+  let in_instructions = contract
+    .in_instructions(block, &HashSet::from([**token]), &HashSet::new(), None)
+    .await
+    .unwrap();
+  assert_eq!(in_instructions.len(), 1);
+  assert_eq!(in_instructions[0].from, user);
+  assert_eq!(in_instructions[0].coin, Coin::Erc20(**token));
+  assert_eq!(in_instructions[0].amount, amount);
+  assert_eq!(in_instructions[0].data, instruction);
+  assert_eq!(in_instructions[0].key_at_end_of_block, public_key.point());
+
+  // Replaying the deposit should fail, as the permit was consumed and no allowance remains
+  let receipt = send(
+    &client,
+    &wallet,
+    contract.in_instruction_with_permit(**token, amount, deadline, &sig, instruction),
+  )
+  .await
+  .unwrap();
+  assert!(!receipt.status());
+}
+
+#[tokio::test]
+async fn test_erc20_in_instruction() {
+  let (anvil, client, _, contract, _, public_key) = setup_test().await;
+
+  let funder: k256::ecdsa::SigningKey = anvil.keys()[0].clone().into();
+  let token = deploy_contract(client.clone(), &funder, "TestERC20").await.unwrap();
+  let erc20 = Erc20::new(client.clone(), **token);
+  let coin = Coin::Erc20(**token);
+
+  let wallet: k256::ecdsa::SigningKey = anvil.keys()[1].clone().into();
+  let user = address(&(*wallet.verifying_key().as_affine()).into());
+
+  let amount = U256::from(1_000_000u64);
+  let mint = TxLegacy {
+    to: TxKind::Call(token),
+    input: mintCall::new((user.into(), amount * U256::from(2u8))).abi_encode().into(),
+    gas_limit: 100_000,
+    ..Default::default()
+  };
+  assert!(send(&client, &funder, mint).await.unwrap().status());
+
+  let instruction = vec![0xff; 32];
+
+  // Without an approval, the deposit should fail
+  let receipt =
+    send(&client, &wallet, contract.in_instruction(&coin, amount, instruction.clone())).await;
+  assert!(!receipt.unwrap().status());
+
+  // Approve, then deposit
+  let receipt = send(&client, &wallet, erc20.approve(contract.address(), amount)).await.unwrap();
+  assert!(receipt.status());
+  assert_eq!(erc20.allowance(user, contract.address()).await.unwrap(), amount);
+
+  let receipt =
+    send(&client, &wallet, contract.in_instruction(&coin, amount, instruction.clone())).await;
+  let receipt = receipt.unwrap();
+  assert!(receipt.status());
+  // The allowance should have been consumed
+  assert_eq!(erc20.allowance(user, contract.address()).await.unwrap(), U256::ZERO);
+
+  let block = receipt.block_number.unwrap();
+  let in_instructions = contract
+    .in_instructions(block, &HashSet::from([**token]), &HashSet::new(), None)
+    .await
+    .unwrap();
+  assert_eq!(in_instructions.len(), 1);
+  assert_eq!(in_instructions[0].from, user);
+  assert_eq!(in_instructions[0].coin, coin);
+  assert_eq!(in_instructions[0].amount, amount);
+  assert_eq!(in_instructions[0].data, instruction);
+  assert_eq!(in_instructions[0].key_at_end_of_block, public_key.point());
+
+  // If the token isn't allowed, the InInstruction should be ignored
+  assert!(contract
+    .in_instructions(block, &HashSet::new(), &HashSet::new(), None)
+    .await
+    .unwrap()
+    .is_empty());
+
+  // Transfer-then-call, where the InInstruction is appended to a top-level transfer to the Router
+  let receipt =
+    send(&client, &wallet, erc20.transfer_with_data(contract.address(), amount, &instruction))
+      .await
+      .unwrap();
+  assert!(receipt.status());
+
+  let block = receipt.block_number.unwrap();
+  // This isn't an InInstruction event from the Router
+  assert!(contract
+    .in_instructions(block, &HashSet::from([**token]), &HashSet::new(), None)
+    .await
+    .unwrap()
+    .is_empty());
+  let transfers = erc20.top_level_transfers(block, contract.address()).await.unwrap();
+  assert_eq!(transfers.len(), 1);
+  assert_eq!(transfers[0].id, *receipt.transaction_hash);
+  assert_eq!(transfers[0].from, user);
+  assert_eq!(transfers[0].amount, amount);
+  assert_eq!(transfers[0].data, instruction);
+
+  // A transfer to anyone other than the Router shouldn't be recognized
+  assert!(erc20.top_level_transfers(block, user).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_eth_in_instruction() {
+  let (anvil, client, _, contract, _, public_key) = setup_test().await;
+
+  let wallet: k256::ecdsa::SigningKey = anvil.keys()[1].clone().into();
+  let user = address(&(*wallet.verifying_key().as_affine()).into());
+
+  let amount = U256::from(1_000_000u64);
+  let instruction = vec![0xff; 32];
+  let receipt =
+    send(&client, &wallet, contract.in_instruction(&Coin::Ether, amount, instruction.clone()))
+      .await
+      .unwrap();
+  assert!(receipt.status());
+
+  let block = receipt.block_number.unwrap();
+  let in_instructions =
+    contract.in_instructions(block, &HashSet::new(), &HashSet::new(), None).await.unwrap();
+  assert_eq!(in_instructions.len(), 1);
+  assert_eq!(in_instructions[0].from, user);
+  assert_eq!(in_instructions[0].coin, Coin::Ether);
+  assert_eq!(in_instructions[0].amount, amount);
+  assert_eq!(in_instructions[0].data, instruction);
+  assert_eq!(in_instructions[0].key_at_end_of_block, public_key.point());
+}
+
+#[tokio::test]
+async fn test_router_in_instruction_wrapping_ether() {
+  let (anvil, client, _, contract, _, public_key) = setup_test().await;
+
+  let funder: k256::ecdsa::SigningKey = anvil.keys()[0].clone().into();
+  let weth = deploy_contract(client.clone(), &funder, "TestWETH").await.unwrap();
+
+  let wallet: k256::ecdsa::SigningKey = anvil.keys()[1].clone().into();
+  let user = address(&(*wallet.verifying_key().as_affine()).into());
+
+  let amount = U256::from(1_000_000u64);
+  let instruction = vec![0xff; 32];
+  let receipt = send(
+    &client,
+    &wallet,
+    contract.in_instruction_wrapping_ether(**weth, amount, instruction.clone()),
+  )
+  .await
+  .unwrap();
+  assert!(receipt.status());
+  // The ETH should have been wrapped, with the Router not holding any ETH itself
+  assert_eq!(client.get_balance(weth).await.unwrap(), amount);
+  assert_eq!(client.get_balance(contract.address().into()).await.unwrap(), U256::ZERO);
+
+  let block = receipt.block_number.unwrap();
+  let in_instructions = contract
+    .in_instructions(block, &HashSet::from([**weth]), &HashSet::new(), Some(**weth))
+    .await
+    .unwrap();
+  assert_eq!(in_instructions.len(), 1);
+  assert_eq!(in_instructions[0].from, user);
+  assert_eq!(in_instructions[0].coin, Coin::Erc20(**weth));
+  assert_eq!(in_instructions[0].amount, amount);
+  assert_eq!(in_instructions[0].data, instruction);
+  assert_eq!(in_instructions[0].key_at_end_of_block, public_key.point());
+
+  // If WETH isn't accepted, the InInstruction shouldn't be credited
+  assert!(contract
+    .in_instructions(block, &HashSet::new(), &HashSet::new(), Some(**weth))
+    .await
+    .unwrap()
+    .is_empty());
+  // If the token isn't the WETH contract scanned for, its Deposit shouldn't count as a transfer
+  assert!(contract
+    .in_instructions(block, &HashSet::from([**weth]), &HashSet::new(), None)
+    .await
+    .is_err());
+
+  // Wrapping nothing, or via no WETH contract, should fail
+  for (weth, amount) in [(**weth, U256::ZERO), ([0; 20], amount)] {
+    let receipt = send(
+      &client,
+      &wallet,
+      contract.in_instruction_wrapping_ether(weth, amount, instruction.clone()),
+    )
+    .await
+    .unwrap();
+    assert!(!receipt.status());
+  }
+}
+
+#[tokio::test]
+async fn test_erc677_in_instruction() {
+  let (anvil, client, _, contract, _, public_key) = setup_test().await;
+
+  let funder: k256::ecdsa::SigningKey = anvil.keys()[0].clone().into();
+  let token = deploy_contract(client.clone(), &funder, "TestERC677").await.unwrap();
+  let erc20 = Erc20::new(client.clone(), **token);
+
+  let wallet: k256::ecdsa::SigningKey = anvil.keys()[1].clone().into();
+  let user = address(&(*wallet.verifying_key().as_affine()).into());
+
+  let amount = U256::from(1_000_000u64);
+  let mint = TxLegacy {
+    to: TxKind::Call(token),
+    input: mintCall::new((user.into(), amount)).abi_encode().into(),
+    gas_limit: 100_000,
+    ..Default::default()
+  };
+  assert!(send(&client, &funder, mint).await.unwrap().status());
+
+  // Deposit without any approval, by having the token call the Router
+  let instruction = vec![0xff; 32];
+  let receipt =
+    send(&client, &wallet, erc20.transfer_and_call(contract.address(), amount, &instruction))
+      .await
+      .unwrap();
+  assert!(receipt.status());
+  assert_eq!(erc20.allowance(user, contract.address()).await.unwrap(), U256::ZERO);
+
+  let block = receipt.block_number.unwrap();
+  let tokens = HashSet::from([**token]);
+  let in_instructions = contract.in_instructions(block, &tokens, &tokens, None).await.unwrap();
+  assert_eq!(in_instructions.len(), 1);
+  assert_eq!(in_instructions[0].from, user);
+  assert_eq!(in_instructions[0].coin, Coin::Erc20(**token));
+  assert_eq!(in_instructions[0].amount, amount);
+  assert_eq!(in_instructions[0].data, instruction);
+  assert_eq!(in_instructions[0].key_at_end_of_block, public_key.point());
+
+  // If the token isn't accepted as an ERC-677 token, the InInstruction shouldn't be credited
+  assert!(contract
+    .in_instructions(block, &tokens, &HashSet::new(), None)
+    .await
+    .unwrap()
+    .is_empty());
+  // Nor should it be credited as a top-level transfer
+  assert!(erc20.top_level_transfers(block, contract.address()).await.unwrap().is_empty());
+
+  // Calling the Router as if we were the token shouldn't be credited as the token
+  let receipt = send(
+    &client,
+    &wallet,
+    TxLegacy {
+      to: TxKind::Call(contract.address().into()),
+      input: router::onTokenTransferCall::new((user.into(), amount, instruction.into()))
+        .abi_encode()
+        .into(),
+      gas_limit: 100_000,
+      ..Default::default()
+    },
+  )
+  .await
+  .unwrap();
+  assert!(receipt.status());
+  let block = receipt.block_number.unwrap();
+  assert!(contract.in_instructions(block, &tokens, &tokens, None).await.unwrap().is_empty());
 }
@@ -7,11 +7,11 @@ use k256::Scalar;
 
 use frost::{
   curve::Secp256k1,
-  algorithm::IetfSchnorr,
+  algorithm::{Hram, IetfSchnorr},
   tests::{algorithm_machines, sign},
 };
 
-use alloy_core::primitives::Address;
+use alloy_core::primitives::{Address, U256};
 
 use alloy_sol_types::SolCall;
 
@@ -25,10 +25,10 @@ use alloy_node_bindings::{Anvil, AnvilInstance};
 use crate::{
   Error,
   crypto::*,
-  tests::{key_gen, deploy_contract, abi::schnorr as abi},
+  tests::{key_gen, deploy_contract, abi::schnorr as abi, gas::GasBenchmark},
 };
 
-async fn setup_test() -> (AnvilInstance, Arc<RootProvider<SimpleRequest>>, Address) {
+pub(crate) async fn setup_test() -> (AnvilInstance, Arc<RootProvider<SimpleRequest>>, Address) {
   let anvil = Anvil::new().spawn();
 
   let provider = RootProvider::new(
@@ -46,21 +46,30 @@ async fn test_deploy_contract() {
   setup_test().await;
 }
 
-pub async fn call_verify(
-  provider: &RootProvider<SimpleRequest>,
+fn verify_call(
   contract: Address,
   public_key: &PublicKey,
   message: &[u8],
   signature: &Signature,
-) -> Result<(), Error> {
+) -> TransactionRequest {
   let px: [u8; 32] = public_key.px.to_repr().into();
   let c_bytes: [u8; 32] = signature.c.to_repr().into();
   let s_bytes: [u8; 32] = signature.s.to_repr().into();
-  let call = TransactionRequest::default().to(contract).input(TransactionInput::new(
+  TransactionRequest::default().to(contract).input(TransactionInput::new(
     abi::verifyCall::new((px.into(), message.to_vec().into(), c_bytes.into(), s_bytes.into()))
       .abi_encode()
       .into(),
-  ));
+  ))
+}
+
+pub async fn call_verify(
+  provider: &RootProvider<SimpleRequest>,
+  contract: Address,
+  public_key: &PublicKey,
+  message: &[u8],
+  signature: &Signature,
+) -> Result<(), Error> {
+  let call = verify_call(contract, public_key, message, signature);
   let bytes = provider.call(&call).await.map_err(|_| Error::ConnectionError)?;
   let res =
     abi::verifyCall::abi_decode_returns(&bytes, true).map_err(|_| Error::ConnectionError)?;
@@ -83,11 +92,98 @@ async fn test_ecrecover_hack() {
   let algo = IetfSchnorr::<Secp256k1, EthereumHram>::ietf();
   let sig =
     sign(&mut OsRng, &algo, keys.clone(), algorithm_machines(&mut OsRng, &algo, &keys), MESSAGE);
-  let sig = Signature::new(&public_key, MESSAGE, sig).unwrap();
+  // This contract function doesn't apply domain separation, so calculate the challenge directly
+  let sig = Signature { c: EthereumHram::hram(&sig.R, &public_key.point(), MESSAGE), s: sig.s };
 
   call_verify(&client, contract, &public_key, MESSAGE, &sig).await.unwrap();
+  let mut gas = GasBenchmark::from_env();
+  gas.record_gas(
+    "schnorr_verify",
+    u64::try_from(
+      client.estimate_gas(&verify_call(contract, &public_key, MESSAGE, &sig)).await.unwrap(),
+    )
+    .unwrap(),
+  );
+  gas.assert_no_regressions();
+
   // Test an invalid signature fails
   let mut sig = sig;
   sig.s += Scalar::ONE;
   assert!(call_verify(&client, contract, &public_key, MESSAGE, &sig).await.is_err());
 }
+
+async fn call_verify_with_domain(
+  provider: &RootProvider<SimpleRequest>,
+  contract: Address,
+  public_key: &PublicKey,
+  purpose: &[u8],
+  message: &[u8],
+  signature: &Signature,
+) -> Result<(), Error> {
+  let px: [u8; 32] = public_key.px.to_repr().into();
+  let c_bytes: [u8; 32] = signature.c.to_repr().into();
+  let s_bytes: [u8; 32] = signature.s.to_repr().into();
+  let call = TransactionRequest::default().to(contract).input(TransactionInput::new(
+    abi::verifyWithDomainCall::new((
+      px.into(),
+      purpose.to_vec().into(),
+      message.to_vec().into(),
+      c_bytes.into(),
+      s_bytes.into(),
+    ))
+    .abi_encode()
+    .into(),
+  ));
+  let bytes = provider.call(&call).await.map_err(|_| Error::ConnectionError)?;
+  let res = abi::verifyWithDomainCall::abi_decode_returns(&bytes, true)
+    .map_err(|_| Error::ConnectionError)?;
+
+  if res._0 {
+    Ok(())
+  } else {
+    Err(Error::InvalidSignature)
+  }
+}
+
+#[tokio::test]
+async fn test_domain_separation() {
+  let (anvil, client, contract) = setup_test().await;
+  let other =
+    deploy_contract(client.clone(), &anvil.keys()[0].clone().into(), "TestSchnorr").await.unwrap();
+  let chain_id = U256::from(client.get_chain_id().await.unwrap());
+
+  let (keys, public_key) = key_gen();
+
+  const PURPOSE: &[u8] = b"test";
+  const MESSAGE: &[u8] = b"Hello, World!";
+  let domain = SignatureDomain { chain_id, verifier: **contract, purpose: PURPOSE };
+
+  let algo = IetfSchnorr::<Secp256k1, EthereumHram>::ietf();
+  let sig = sign(
+    &mut OsRng,
+    &algo,
+    keys.clone(),
+    algorithm_machines(&mut OsRng, &algo, &keys),
+    &domain.bind(MESSAGE),
+  );
+  let sig = Signature::new(&public_key, &domain, MESSAGE, sig).unwrap();
+
+  call_verify_with_domain(&client, contract, &public_key, PURPOSE, MESSAGE, &sig).await.unwrap();
+
+  // The signature shouldn't be valid for another contract, another purpose, or another chain
+  assert!(call_verify_with_domain(&client, other, &public_key, PURPOSE, MESSAGE, &sig)
+    .await
+    .is_err());
+  assert!(call_verify_with_domain(&client, contract, &public_key, b"other", MESSAGE, &sig)
+    .await
+    .is_err());
+  assert!(!sig.verify(&public_key, &SignatureDomain { verifier: **other, ..domain }, MESSAGE));
+  assert!(!sig.verify(&public_key, &SignatureDomain { purpose: b"other", ..domain }, MESSAGE));
+  assert!(!sig.verify(
+    &public_key,
+    &SignatureDomain { chain_id: chain_id + U256::from(1u8), ..domain },
+    MESSAGE
+  ));
+  // Nor should it be valid without domain separation
+  assert!(call_verify(&client, contract, &public_key, MESSAGE, &sig).await.is_err());
+}
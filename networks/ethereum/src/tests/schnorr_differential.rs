@@ -0,0 +1,256 @@
+// Differential testing of the Rust Schnorr verifier against the Solidity Schnorr verifier
+//
+// Any input one accepts and the other rejects would allow a signature to be considered valid
+// off-chain yet not on-chain (or vice versa), which would be a critical fault
+
+use rand_core::{RngCore, OsRng};
+
+use group::ff::{Field, PrimeField};
+use k256::{Scalar, ProjectivePoint};
+
+use frost::algorithm::Hram;
+
+use alloy_core::primitives::{Address, U256};
+
+use alloy_sol_types::SolCall;
+
+use alloy_rpc_types_eth::{TransactionInput, TransactionRequest};
+use alloy_simple_request_transport::SimpleRequest;
+use alloy_provider::{Provider, RootProvider};
+
+use crate::{
+  crypto::*,
+  tests::{abi::schnorr as abi, schnorr::setup_test},
+};
+
+// The amount of keys to generate, with each key being used to sign a variety of messages
+const KEYS: usize = 8;
+
+// An input to the verifiers, without any guarantee it's well-formed
+#[derive(Clone, Debug)]
+struct Input {
+  px: [u8; 32],
+  message: Vec<u8>,
+  c: [u8; 32],
+  s: [u8; 32],
+}
+
+fn rust_verify(input: &Input) -> bool {
+  let Some(public_key) = PublicKey::from_eth_repr(input.px) else { return false };
+  let mut signature = [0; 64];
+  signature[.. 32].copy_from_slice(&input.c);
+  signature[32 ..].copy_from_slice(&input.s);
+  let Ok(signature) = Signature::from_bytes(signature) else { return false };
+  signature.verify_without_domain(&public_key, &input.message)
+}
+
+async fn contract_verify(
+  provider: &RootProvider<SimpleRequest>,
+  contract: Address,
+  input: &Input,
+) -> bool {
+  let call = TransactionRequest::default().to(contract).input(TransactionInput::new(
+    abi::verifyCall::new((
+      input.px.into(),
+      input.message.clone().into(),
+      input.c.into(),
+      input.s.into(),
+    ))
+    .abi_encode()
+    .into(),
+  ));
+  match provider.call(&call).await {
+    Ok(bytes) => abi::verifyCall::abi_decode_returns(&bytes, true).unwrap()._0,
+    // The contract reverting is the contract rejecting the signature
+    Err(e) => {
+      assert!(e.as_error_resp().is_some(), "couldn't call the verifier: {e:?}");
+      false
+    }
+  }
+}
+
+fn scalar_bytes(scalar: Scalar) -> [u8; 32] {
+  scalar.to_repr().into()
+}
+
+fn random_key() -> (Scalar, PublicKey) {
+  loop {
+    let private_key = Scalar::random(&mut OsRng);
+    if let Some(public_key) = PublicKey::new(ProjectivePoint::GENERATOR * private_key) {
+      return (private_key, public_key);
+    }
+  }
+}
+
+fn random_message() -> Vec<u8> {
+  let len = match OsRng.next_u64() % 4 {
+    0 => 0,
+    1 => 32,
+    2 => usize::try_from(OsRng.next_u64() % 1024).unwrap(),
+    3 => 64 * 1024,
+    _ => unreachable!(),
+  };
+  let mut message = vec![0; len];
+  OsRng.fill_bytes(&mut message);
+  message
+}
+
+fn random_bytes() -> [u8; 32] {
+  let mut bytes = [0; 32];
+  OsRng.fill_bytes(&mut bytes);
+  bytes
+}
+
+#[allow(non_snake_case)]
+fn sign(private_key: Scalar, public_key: &PublicKey, message: &[u8]) -> Input {
+  let r = Scalar::random(&mut OsRng);
+  let R = ProjectivePoint::GENERATOR * r;
+  let c = EthereumHram::hram(&R, &public_key.point(), message);
+  let s = r + (c * private_key);
+  Input {
+    px: public_key.eth_repr(),
+    message: message.to_vec(),
+    c: scalar_bytes(c),
+    s: scalar_bytes(s),
+  }
+}
+
+// Values at the boundaries of the scalar field, the coordinate field, and 256-bit integers
+fn boundaries() -> Vec<[u8; 32]> {
+  let q = U256::from_be_bytes(scalar_bytes(-Scalar::ONE)) + U256::from(1);
+  let p = U256::MAX - U256::from(0x1000003d0u64);
+  [
+    U256::ZERO,
+    U256::from(1),
+    q - U256::from(1),
+    q,
+    q + U256::from(1),
+    p - U256::from(1),
+    p,
+    U256::MAX,
+  ]
+  .into_iter()
+  .map(|value| value.to_be_bytes::<32>())
+  .collect()
+}
+
+// Mutations of a valid signature, each of which should presumably be invalid
+fn mutations(valid: &Input, other_px: [u8; 32]) -> Vec<Input> {
+  let c = Scalar::from_repr(valid.c.into()).unwrap();
+  let s = Scalar::from_repr(valid.s.into()).unwrap();
+
+  let mut res = vec![];
+  let mut mutate = |f: &dyn Fn(&mut Input)| {
+    let mut input = valid.clone();
+    f(&mut input);
+    res.push(input);
+  };
+
+  // Malleate the scalars
+  mutate(&|input| input.c = scalar_bytes(c + Scalar::ONE));
+  mutate(&|input| input.c = scalar_bytes(c - Scalar::ONE));
+  mutate(&|input| input.c = scalar_bytes(-c));
+  mutate(&|input| input.s = scalar_bytes(s + Scalar::ONE));
+  mutate(&|input| input.s = scalar_bytes(s - Scalar::ONE));
+  mutate(&|input| input.s = scalar_bytes(-s));
+  mutate(&|input| (input.c, input.s) = (input.s, input.c));
+  // Add the order to the scalars, which only fits within 256 bits for small scalars
+  mutate(&|input| {
+    let q = U256::from_be_bytes(scalar_bytes(-Scalar::ONE)) + U256::from(1);
+    if let Some(s) = U256::from_be_bytes(input.s).checked_add(q) {
+      input.s = s.to_be_bytes::<32>();
+    }
+  });
+
+  // Flip a bit in each field
+  let bit = usize::try_from(OsRng.next_u64() % 256).unwrap();
+  mutate(&|input| input.px[bit / 8] ^= 1 << (bit % 8));
+  mutate(&|input| input.c[bit / 8] ^= 1 << (bit % 8));
+  mutate(&|input| input.s[bit / 8] ^= 1 << (bit % 8));
+
+  // Mutate the message
+  mutate(&|input| input.message.push(0));
+  mutate(&|input| {
+    if let Some(byte) = input.message.first_mut() {
+      *byte ^= 1;
+    }
+  });
+  mutate(&|input| {
+    input.message.pop();
+  });
+
+  // Use another key
+  mutate(&|input| input.px = other_px);
+
+  // Replace each field with boundary values
+  for boundary in boundaries() {
+    mutate(&|input| input.px = boundary);
+    mutate(&|input| input.c = boundary);
+    mutate(&|input| input.s = boundary);
+  }
+
+  // Replace each field with random values
+  mutate(&|input| input.px = random_bytes());
+  mutate(&|input| input.c = random_bytes());
+  mutate(&|input| input.s = random_bytes());
+  mutate(&|input| {
+    input.c = random_bytes();
+    input.s = random_bytes();
+  });
+
+  res
+}
+
+#[tokio::test]
+async fn test_schnorr_differential() {
+  let (_anvil, client, contract) = setup_test().await;
+
+  let mut disagreements = vec![];
+  let mut check = |input: Input, rust_res: bool, contract_res: bool| {
+    if rust_res != contract_res {
+      disagreements.push((input, rust_res, contract_res));
+    }
+  };
+
+  let keys = (0 .. KEYS).map(|_| random_key()).collect::<Vec<_>>();
+  for (i, (private_key, public_key)) in keys.iter().enumerate() {
+    let other_px = keys[(i + 1) % KEYS].1.eth_repr();
+    for message in [vec![], vec![0; 64 * 1024], random_message()] {
+      let valid = sign(*private_key, public_key, &message);
+      // Sanity check our signing
+      assert!(rust_verify(&valid));
+
+      let contract_res = contract_verify(&client, contract, &valid).await;
+      check(valid.clone(), true, contract_res);
+
+      for input in mutations(&valid, other_px) {
+        let rust_res = rust_verify(&input);
+        let contract_res = contract_verify(&client, contract, &input).await;
+        check(input, rust_res, contract_res);
+      }
+    }
+  }
+
+  // Entirely random inputs
+  for _ in 0 .. 32 {
+    let input =
+      Input { px: random_bytes(), message: random_message(), c: random_bytes(), s: random_bytes() };
+    let rust_res = rust_verify(&input);
+    let contract_res = contract_verify(&client, contract, &input).await;
+    check(input, rust_res, contract_res);
+  }
+
+  for (input, rust_res, contract_res) in &disagreements {
+    println!(
+      "disagreement (Rust: {rust_res}, contract: {contract_res}): {}, message ({} bytes) {}",
+      format_args!("px {}, c {}, s {}", hex(&input.px), hex(&input.c), hex(&input.s)),
+      input.message.len(),
+      hex(&input.message[.. input.message.len().min(64)]),
+    );
+  }
+  assert!(disagreements.is_empty());
+}
+
+fn hex(bytes: &[u8]) -> String {
+  alloy_core::hex::encode(bytes)
+}
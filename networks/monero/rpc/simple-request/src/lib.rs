@@ -5,7 +5,7 @@
 use core::future::Future;
 use std::{sync::Arc, io::Read, time::Duration};
 
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, Semaphore};
 
 use digest_auth::{WwwAuthenticateHeader, AuthContext};
 use simple_request::{
@@ -13,33 +13,45 @@ use simple_request::{
   Response, Client,
 };
 
-use monero_rpc::{RpcError, Rpc};
+use monero_rpc::{RpcError, RpcVersion, RpcCapabilities, Rpc};
 
 const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 8;
+
+// An authenticated connection, with the digest-auth challenge for it and the cnonce used with it
+type AuthenticatedConnection = Mutex<(Option<(WwwAuthenticateHeader, u64)>, Client)>;
 
 #[derive(Clone, Debug)]
 enum Authentication {
-  // If unauthenticated, use a single client
+  // If unauthenticated, use a single client, whose connection pool keeps connections alive for
+  // reuse
   Unauthenticated(Client),
-  // If authenticated, use a single client which supports being locked and tracks its nonce
+  // If authenticated, use a client for each concurrent request, each of which supports being
+  // locked and tracks its nonce
   // This ensures that if a nonce is requested, another caller doesn't make a request invalidating
   // it
+  // The semaphore has a permit per client, ensuring a client is available to whoever holds one
   Authenticated {
     username: String,
     password: String,
-    #[allow(clippy::type_complexity)]
-    connection: Arc<Mutex<(Option<(WwwAuthenticateHeader, u64)>, Client)>>,
+    permits: Arc<Semaphore>,
+    connections: Arc<Vec<AuthenticatedConnection>>,
   },
 }
 
 /// An HTTP(S) transport for the RPC.
 ///
+/// Connections are kept alive and reused across requests. Requests may be made concurrently. For
+/// daemons requiring authentication, this is up to a limit, with each concurrent request made over
+/// its own connection.
+///
 /// Requires tokio.
 #[derive(Clone, Debug)]
 pub struct SimpleRequestRpc {
   authentication: Authentication,
   url: String,
   request_timeout: Duration,
+  capabilities: Option<RpcCapabilities>,
 }
 
 impl SimpleRequestRpc {
@@ -63,6 +75,10 @@ impl SimpleRequestRpc {
   ///
   /// A daemon requiring authentication can be used via including the username and password in the
   /// URL.
+  ///
+  /// The node's capabilities are queried upon connecting, allowing use of restricted RPCs. If they
+  /// can't be queried, the node is assumed to be restricted. Nodes whose RPC version is older than
+  /// `RpcVersion::MINIMUM` are refused.
   pub async fn new(url: String) -> Result<SimpleRequestRpc, RpcError> {
     Self::with_custom_timeout(url, DEFAULT_TIMEOUT).await
   }
@@ -72,9 +88,56 @@ impl SimpleRequestRpc {
   /// A daemon requiring authentication can be used via including the username and password in the
   /// URL.
   pub async fn with_custom_timeout(
+    url: String,
+    request_timeout: Duration,
+  ) -> Result<SimpleRequestRpc, RpcError> {
+    Self::with_minimum_version(url, request_timeout, RpcVersion::MINIMUM).await
+  }
+
+  /// Create a new HTTP(S) RPC connection with a custom timeout and minimum RPC version.
+  ///
+  /// A daemon requiring authentication can be used via including the username and password in the
+  /// URL.
+  ///
+  /// Nodes whose RPC version is older than the minimum are refused. If the node's version can't be
+  /// queried upon connecting, the connection is still returned and `Rpc::check_version` may be
+  /// used to check it later.
+  pub async fn with_minimum_version(
+    url: String,
+    request_timeout: Duration,
+    minimum_version: RpcVersion,
+  ) -> Result<SimpleRequestRpc, RpcError> {
+    Self::with_max_concurrent_requests(
+      url,
+      request_timeout,
+      minimum_version,
+      DEFAULT_MAX_CONCURRENT_REQUESTS,
+    )
+    .await
+  }
+
+  /// Create a new HTTP(S) RPC connection with a custom timeout, minimum RPC version, and limit on
+  /// the amount of requests made concurrently.
+  ///
+  /// A daemon requiring authentication can be used via including the username and password in the
+  /// URL.
+  ///
+  /// The limit solely applies to daemons requiring authentication, which are connected to with a
+  /// distinct connection for each concurrent request. Requests made while the limit is reached
+  /// wait for a prior request to complete, with the time spent waiting counting towards the
+  /// request's timeout. Unauthenticated daemons are connected to via a connection pool, without a
+  /// limit.
+  pub async fn with_max_concurrent_requests(
     mut url: String,
     request_timeout: Duration,
+    minimum_version: RpcVersion,
+    max_concurrent_requests: usize,
   ) -> Result<SimpleRequestRpc, RpcError> {
+    if max_concurrent_requests == 0 {
+      Err(RpcError::ConnectionError("max concurrent requests was zero".to_string()))?;
+    }
+    let max_concurrent_requests = max_concurrent_requests.min(Semaphore::MAX_PERMITS);
+
     let authentication = if url.contains('@') {
       // Parse out the username and password
       let url_clone = url;
@@ -113,16 +176,33 @@ impl SimpleRequestRpc {
           .await
           .map_err(|e| RpcError::ConnectionError(format!("{e:?}")))?,
       )?;
+      // The challenge is per-connection, so the other connections obtain their own when first used
+      let mut connections = vec![Mutex::new((challenge, client))];
+      for _ in 1 .. max_concurrent_requests {
+        connections.push(Mutex::new((
+          None,
+          Client::without_connection_pool(&url)
+            .map_err(|_| RpcError::ConnectionError("invalid URL".to_string()))?,
+        )));
+      }
       Authentication::Authenticated {
         username: split_userpass[0].to_string(),
         password: (*split_userpass.get(1).unwrap_or(&"")).to_string(),
-        connection: Arc::new(Mutex::new((challenge, client))),
+        permits: Arc::new(Semaphore::new(max_concurrent_requests)),
+        connections: Arc::new(connections),
       }
     } else {
       Authentication::Unauthenticated(Client::with_connection_pool())
     };
 
-    Ok(SimpleRequestRpc { authentication, url, request_timeout })
+    let mut rpc = SimpleRequestRpc { authentication, url, request_timeout, capabilities: None };
+    rpc.capabilities = rpc.get_capabilities().await.ok();
+    if let Some(version) = rpc.capabilities.and_then(|capabilities| capabilities.version) {
+      if version < minimum_version {
+        Err(RpcError::UnsupportedVersion(version, minimum_version))?;
+      }
+    }
+    Ok(rpc)
   }
 }
 
@@ -178,8 +258,18 @@ impl SimpleRequestRpc {
           )
           .await?
         }
-        Authentication::Authenticated { username, password, connection } => {
-          let mut connection_lock = connection.lock().await;
+        Authentication::Authenticated { username, password, permits, connections } => {
+          // Wait until a connection is available
+          // This is within the request's timeout, so this won't wait forever
+          let _permit = permits
+            .acquire()
+            .await
+            .map_err(|_| RpcError::InternalError("request limiter was closed".to_string()))?;
+          // Use whichever connection isn't in use, which the permit ensures exists
+          let mut connection_lock = connections
+            .iter()
+            .find_map(|connection| connection.try_lock().ok())
+            .ok_or_else(|| RpcError::InternalError("no connection was available".to_string()))?;
 
           let mut request = request_fn("/".to_string() + route)?;
 
@@ -291,4 +381,8 @@ impl Rpc for SimpleRequestRpc {
         .map_err(|e| RpcError::ConnectionError(format!("{e:?}")))?
     }
   }
+
+  fn capabilities(&self) -> Option<RpcCapabilities> {
+    self.capabilities
+  }
 }
@@ -39,7 +39,9 @@ const GRACE_BLOCKS_FOR_FEE_ESTIMATE: u64 = 10;
 // Monero errors if more than 100 is requested unless using a non-restricted RPC
 // https://github.com/monero-project/monero/blob/cc73fe71162d564ffda8e549b79a350bca53c454
 //   /src/rpc/core_rpc_server.cpp#L75
-const TXS_PER_REQUEST: usize = 100;
+const RESTRICTED_TXS_PER_REQUEST: usize = 100;
+// A non-restricted RPC has no limit, yet we still batch to bound the size of the response
+const UNRESTRICTED_TXS_PER_REQUEST: usize = 1000;
 
 /// An error from the RPC.
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -71,6 +73,9 @@ pub enum RpcError {
   /// The priority intended for use wasn't usable.
   #[cfg_attr(feature = "std", error("invalid priority"))]
   InvalidPriority,
+  /// The node's RPC version is older than the minimum version supported.
+  #[cfg_attr(feature = "std", error("node's RPC version ({0}) is older than the minimum ({1})"))]
+  UnsupportedVersion(RpcVersion, RpcVersion),
 }
 
 /// A block which is able to be scanned.
@@ -186,6 +191,95 @@ impl FeePriority {
   }
 }
 
+/// The version of a node's RPC.
+///
+/// This is the version of the RPC interface, not of the node's software, as returned by
+/// `get_version`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct RpcVersion {
+  /// The major version, incremented upon incompatible changes.
+  pub major: u16,
+  /// The minor version, incremented upon additions.
+  pub minor: u16,
+}
+
+impl RpcVersion {
+  /// The minimum RPC version supported by this library.
+  ///
+  /// This is the RPC version of monerod v0.18.0.0, the first release to support the current
+  /// hard fork.
+  pub const MINIMUM: RpcVersion = RpcVersion { major: 3, minor: 11 };
+
+  /// The RPC version from its encoding as a `u32`, as used by `get_version`.
+  pub fn from_u32(version: u32) -> RpcVersion {
+    #[allow(clippy::cast_possible_truncation)]
+    RpcVersion { major: (version >> 16) as u16, minor: (version & u32::from(u16::MAX)) as u16 }
+  }
+}
+
+impl core::fmt::Display for RpcVersion {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    write!(f, "{}.{}", self.major, self.minor)
+  }
+}
+
+/// A feature which may be supported by a node's RPC.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RpcFeature {
+  /// Outputs with view tags, as introduced with hard fork 15 (monerod v0.18.0.0).
+  ViewTags,
+  /// Information on the transaction pool within `get_blocks.bin` (monerod v0.18.3.0).
+  PoolInfo,
+  /// The binary (epee-encoded) endpoints, such as `get_o_indexes.bin`.
+  BinaryEndpoints,
+}
+
+impl RpcFeature {
+  /// The minimum RPC version which supports this feature.
+  pub fn minimum_version(&self) -> RpcVersion {
+    match self {
+      RpcFeature::ViewTags => RpcVersion { major: 3, minor: 11 },
+      RpcFeature::PoolInfo => RpcVersion { major: 3, minor: 13 },
+      RpcFeature::BinaryEndpoints => RpcVersion { major: 3, minor: 0 },
+    }
+  }
+}
+
+/// The capabilities of a node's RPC.
+///
+/// Public nodes commonly run a restricted RPC, which rejects some routes and limits how much may
+/// be requested at once.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RpcCapabilities {
+  /// If the RPC is restricted.
+  pub restricted: bool,
+  /// The version of the RPC, if known.
+  pub version: Option<RpcVersion>,
+}
+
+impl RpcCapabilities {
+  /// The capabilities assumed for a node whose capabilities are unknown.
+  ///
+  /// This assumes the node is restricted, as that's compatible with all nodes.
+  pub const UNKNOWN: RpcCapabilities = RpcCapabilities { restricted: true, version: None };
+
+  /// If the RPC supports a feature.
+  ///
+  /// If the version of the RPC is unknown, no features are assumed to be supported.
+  pub fn supports(&self, feature: RpcFeature) -> bool {
+    self.version.is_some_and(|version| version >= feature.minimum_version())
+  }
+
+  /// The amount of transactions to request at once.
+  pub fn transactions_per_request(&self) -> usize {
+    if self.restricted {
+      RESTRICTED_TXS_PER_REQUEST
+    } else {
+      UNRESTRICTED_TXS_PER_REQUEST
+    }
+  }
+}
+
 #[derive(Debug, Deserialize)]
 struct JsonRpcResponse<T> {
   result: T,
@@ -310,6 +404,72 @@ pub trait Rpc: Sync + Clone {
     async move { self.post(route, params).await }
   }
 
+  /// The capabilities of the node, if known.
+  ///
+  /// The provided methods use this to adapt to the node, assuming `RpcCapabilities::UNKNOWN` if
+  /// `None` is returned. Implementors are recommended to call `get_capabilities` upon connecting
+  /// and return the result here.
+  fn capabilities(&self) -> Option<RpcCapabilities> {
+    None
+  }
+
+  /// Query the version of the node's RPC.
+  fn get_version(&self) -> impl Send + Future<Output = Result<RpcVersion, RpcError>> {
+    async move {
+      #[derive(Debug, Deserialize)]
+      struct VersionResponse {
+        status: String,
+        version: u32,
+      }
+
+      let res = self.json_rpc_call::<VersionResponse>("get_version", None).await?;
+      if res.status != "OK" {
+        Err(RpcError::InvalidNode(
+          "node couldn't service the request for its version".to_string(),
+        ))?;
+      }
+      Ok(RpcVersion::from_u32(res.version))
+    }
+  }
+
+  /// Query the capabilities of the node.
+  fn get_capabilities(&self) -> impl Send + Future<Output = Result<RpcCapabilities, RpcError>> {
+    async move {
+      #[derive(Debug, Deserialize)]
+      struct InfoResponse {
+        status: String,
+        restricted: Option<bool>,
+      }
+
+      let res = self.json_rpc_call::<InfoResponse>("get_info", None).await?;
+      if res.status != "OK" {
+        Err(RpcError::InvalidNode("node couldn't service the request for its info".to_string()))?;
+      }
+      let version = self.get_version().await?;
+      // If the node didn't specify, assume the worst
+      Ok(RpcCapabilities { restricted: res.restricted.unwrap_or(true), version: Some(version) })
+    }
+  }
+
+  /// Check the node's RPC is at least the specified version.
+  ///
+  /// This uses the capabilities of the node if known, querying its version otherwise.
+  fn check_version(
+    &self,
+    minimum: RpcVersion,
+  ) -> impl Send + Future<Output = Result<(), RpcError>> {
+    async move {
+      let version = match self.capabilities().and_then(|capabilities| capabilities.version) {
+        Some(version) => version,
+        None => self.get_version().await?,
+      };
+      if version < minimum {
+        Err(RpcError::UnsupportedVersion(version, minimum))?;
+      }
+      Ok(())
+    }
+  }
+
   /// Get the active blockchain protocol version.
   ///
   /// This is specifically the major version within the most recent block header.
@@ -366,10 +526,12 @@ pub trait Rpc: Sync + Clone {
         return Ok(vec![]);
       }
 
+      let txs_per_request =
+        self.capabilities().unwrap_or(RpcCapabilities::UNKNOWN).transactions_per_request();
       let mut hashes_hex = hashes.iter().map(hex::encode).collect::<Vec<_>>();
       let mut all_txs = Vec::with_capacity(hashes.len());
       while !hashes_hex.is_empty() {
-        let this_count = TXS_PER_REQUEST.min(hashes_hex.len());
+        let this_count = txs_per_request.min(hashes_hex.len());
 
         let txs: TransactionsResponse = self
           .rpc_call(
@@ -439,10 +601,12 @@ pub trait Rpc: Sync + Clone {
         return Ok(vec![]);
       }
 
+      let txs_per_request =
+        self.capabilities().unwrap_or(RpcCapabilities::UNKNOWN).transactions_per_request();
       let mut hashes_hex = hashes.iter().map(hex::encode).collect::<Vec<_>>();
       let mut all_txs = Vec::with_capacity(hashes.len());
       while !hashes_hex.is_empty() {
-        let this_count = TXS_PER_REQUEST.min(hashes_hex.len());
+        let this_count = txs_per_request.min(hashes_hex.len());
 
         let txs: TransactionsResponse = self
           .rpc_call(
@@ -785,6 +949,12 @@ pub trait Rpc: Sync + Clone {
     block_count: usize,
   ) -> impl Send + Future<Output = Result<(Vec<[u8; 32]>, usize), RpcError>> {
     async move {
+      if self.capabilities().is_some_and(|capabilities| capabilities.restricted) {
+        Err(RpcError::ConnectionError(
+          "generateblocks is unavailable on a restricted RPC".to_string(),
+        ))?;
+      }
+
       #[derive(Debug, Deserialize)]
       struct BlocksResponse {
         blocks: Vec<String>,
@@ -0,0 +1,418 @@
+use core::ops::Deref;
+use std_shims::{
+  vec,
+  vec::Vec,
+  io::{self, Read, Write},
+  collections::HashMap,
+};
+
+use zeroize::{Zeroize, Zeroizing};
+use rand_core::{RngCore, CryptoRng};
+
+use curve25519_dalek::{constants::ED25519_BASEPOINT_TABLE, traits::Identity, Scalar, EdwardsPoint};
+
+use monero_serai::{
+  io::*,
+  primitives::keccak256_to_scalar,
+  generators::hash_to_point,
+  transaction::{Input, TransactionPrefix},
+};
+use monero_rpc::ScannableBlock;
+
+use crate::WalletOutput;
+
+// The amount of bits in the bloom filter per key image
+//
+// With four probes, this has a false positive rate of ~0.24%
+const BITS_PER_KEY_IMAGE: usize = 16;
+// The amount of probes into the bloom filter per key image
+const PROBES: usize = 4;
+// The minimum capacity of the bloom filter, in key images
+const MIN_CAPACITY: usize = 64;
+
+/// The key image for an output.
+///
+/// This requires the private spend key of the wallet which received the output.
+pub fn key_image(spend_key: &Zeroizing<Scalar>, output: &WalletOutput) -> EdwardsPoint {
+  let input_key = Zeroizing::new(spend_key.deref() + output.key_offset());
+  input_key.deref() * hash_to_point(output.key().compress().to_bytes())
+}
+
+/// If a point is a well-formed key image.
+///
+/// Key images must be torsion-free and not the identity, else a single output could be spent
+/// multiple times under distinct key images.
+pub fn is_valid_key_image(key_image: &EdwardsPoint) -> bool {
+  key_image.is_torsion_free() && (*key_image != EdwardsPoint::identity())
+}
+
+/// Key material with which the key images for a wallet's outputs may be calculated.
+///
+/// This abstracts over where the private spend key is held, allowing it to be held by a device
+/// which doesn't export it.
+pub trait SpendKeyMaterial {
+  /// The key image for an output received by the wallet.
+  fn key_image(&self, output: &WalletOutput) -> EdwardsPoint;
+
+  /// Prove the key image for an output received by the wallet is the output's key image.
+  fn prove_key_image(
+    &self,
+    rng: &mut (impl RngCore + CryptoRng),
+    output: &WalletOutput,
+  ) -> KeyImageProof;
+}
+
+impl SpendKeyMaterial for Zeroizing<Scalar> {
+  fn key_image(&self, output: &WalletOutput) -> EdwardsPoint {
+    key_image(self, output)
+  }
+
+  fn prove_key_image(
+    &self,
+    rng: &mut (impl RngCore + CryptoRng),
+    output: &WalletOutput,
+  ) -> KeyImageProof {
+    let input_key = Zeroizing::new(self.deref() + output.key_offset());
+    let generator = hash_to_point(output.key().compress().to_bytes());
+    let key_image = input_key.deref() * generator;
+
+    // Prove the discrete logarithm of the output key over G equals the discrete logarithm of the
+    // key image over the hash of the output key
+    let nonce = Zeroizing::new(Scalar::random(rng));
+    let c = key_image_challenge(
+      output.key(),
+      key_image,
+      [nonce.deref() * ED25519_BASEPOINT_TABLE, generator * nonce.deref()],
+    );
+    let s = nonce.deref() - (c * input_key.deref());
+    KeyImageProof { key_image, c, s }
+  }
+}
+
+/// An error when verifying a key image proof.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+pub enum KeyImageProofError {
+  /// The key image wasn't a well-formed key image.
+  #[cfg_attr(feature = "std", error("key image wasn't well-formed"))]
+  InvalidKeyImage,
+  /// The proof was invalid.
+  #[cfg_attr(feature = "std", error("invalid key image proof"))]
+  InvalidProof,
+}
+
+fn key_image_challenge(
+  output_key: EdwardsPoint,
+  key_image: EdwardsPoint,
+  nonces: [EdwardsPoint; 2],
+) -> Scalar {
+  let mut transcript = b"monero_wallet_key_image_proof".to_vec();
+  for point in [output_key, key_image, nonces[0], nonces[1]] {
+    transcript.extend(point.compress().to_bytes());
+  }
+  keccak256_to_scalar(transcript)
+}
+
+/// A proof a key image is the key image for an output.
+///
+/// This allows a third party (such as an auditor) to learn if an output has been spent, and to
+/// detect multiple outputs sharing a key image (as with the burning bug), without the private
+/// spend key. As the proof reveals the key image, whoever has the proof can identify the
+/// transaction which spends the output.
+///
+/// This is not compatible with the key image exports produced by the reference wallet.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct KeyImageProof {
+  key_image: EdwardsPoint,
+  c: Scalar,
+  s: Scalar,
+}
+
+impl KeyImageProof {
+  /// The key image this proof is for.
+  pub fn key_image(&self) -> EdwardsPoint {
+    self.key_image
+  }
+
+  /// Verify this is a proof for the key image of the output with the specified key, returning the
+  /// key image if so.
+  pub fn verify(&self, output_key: EdwardsPoint) -> Result<EdwardsPoint, KeyImageProofError> {
+    if !is_valid_key_image(&self.key_image) {
+      Err(KeyImageProofError::InvalidKeyImage)?;
+    }
+
+    let generator = hash_to_point(output_key.compress().to_bytes());
+    let c = key_image_challenge(
+      output_key,
+      self.key_image,
+      [
+        (&self.s * ED25519_BASEPOINT_TABLE) + (output_key * self.c),
+        (generator * self.s) + (self.key_image * self.c),
+      ],
+    );
+    if c != self.c {
+      Err(KeyImageProofError::InvalidProof)?;
+    }
+    Ok(self.key_image)
+  }
+
+  /// Write the KeyImageProof.
+  ///
+  /// This is not a Monero protocol defined struct, and this is accordingly not a Monero protocol
+  /// defined serialization.
+  pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+    write_point(&self.key_image, w)?;
+    write_scalar(&self.c, w)?;
+    write_scalar(&self.s, w)
+  }
+
+  /// Serialize the KeyImageProof to a `Vec<u8>`.
+  ///
+  /// This is not a Monero protocol defined struct, and this is accordingly not a Monero protocol
+  /// defined serialization.
+  pub fn serialize(&self) -> Vec<u8> {
+    let mut res = Vec::with_capacity(3 * 32);
+    self.write(&mut res).unwrap();
+    res
+  }
+
+  /// Read a KeyImageProof.
+  ///
+  /// This is not a Monero protocol defined struct, and this is accordingly not a Monero protocol
+  /// defined serialization.
+  pub fn read<R: Read>(r: &mut R) -> io::Result<KeyImageProof> {
+    Ok(KeyImageProof { key_image: read_point(r)?, c: read_scalar(r)?, s: read_scalar(r)? })
+  }
+}
+
+/// An index of the key images for a wallet's outputs, used to detect when they're spent.
+///
+/// This is composed of a bloom filter, which is checked first, and an exact map from each key
+/// image to the output it's for. As the vast majority of inputs on the blockchain won't be
+/// spending the wallet's outputs, the vast majority of lookups will be resolved by the bloom
+/// filter alone. Both lookups are constant-time with regards to the amount of outputs indexed.
+///
+/// Only the exact map is serialized. The bloom filter is rebuilt when deserializing.
+#[derive(Clone, Debug, Default)]
+pub struct KeyImageIndex {
+  bloom: Vec<u64>,
+  // The amount of bits set in the bloom filter which are for removed key images
+  //
+  // Bloom filters don't support removal, so we track this and rebuild the bloom filter once this
+  // exceeds the amount of key images indexed.
+  removed: usize,
+  key_images: HashMap<[u8; 32], ([u8; 32], u32)>,
+}
+
+impl PartialEq for KeyImageIndex {
+  fn eq(&self, other: &Self) -> bool {
+    self.key_images == other.key_images
+  }
+}
+impl Eq for KeyImageIndex {}
+
+impl Zeroize for KeyImageIndex {
+  fn zeroize(&mut self) {
+    self.bloom.zeroize();
+    self.removed.zeroize();
+    for (mut key_image, (mut transaction, mut index)) in self.key_images.drain() {
+      key_image.zeroize();
+      transaction.zeroize();
+      index.zeroize();
+    }
+  }
+}
+
+impl KeyImageIndex {
+  /// Create a new, empty index.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// The amount of key images indexed.
+  pub fn len(&self) -> usize {
+    self.key_images.len()
+  }
+
+  /// If this index is empty.
+  pub fn is_empty(&self) -> bool {
+    self.key_images.is_empty()
+  }
+
+  // The positions of the bits in the bloom filter for a key image
+  //
+  // Key images are the encodings of points which aren't controllable without solving the discrete
+  // logarithm problem, so their bytes are used directly as the probes. The worst a malicious key
+  // image can do is cause a false positive, which only costs a lookup into the exact map.
+  fn probes(bloom_bits: usize, key_image: &[u8; 32]) -> impl Iterator<Item = usize> + '_ {
+    debug_assert!(bloom_bits.is_power_of_two());
+    (0 .. PROBES).map(move |i| {
+      let probe = u64::from_le_bytes(key_image[(i * 8) .. ((i + 1) * 8)].try_into().unwrap());
+      // Truncating the probe is fine as we only use its lower bits
+      #[allow(clippy::cast_possible_truncation)]
+      let probe = probe as usize;
+      probe & (bloom_bits - 1)
+    })
+  }
+
+  fn set_bloom_bits(&mut self, key_image: &[u8; 32]) {
+    for bit in Self::probes(self.bloom.len() * 64, key_image) {
+      self.bloom[bit / 64] |= 1 << (bit % 64);
+    }
+  }
+
+  // Rebuild the bloom filter, sizing it for the specified amount of key images
+  fn rebuild_bloom(&mut self, capacity: usize) {
+    let bits = (capacity.max(MIN_CAPACITY) * BITS_PER_KEY_IMAGE).next_power_of_two();
+    self.bloom = vec![0; bits / 64];
+    self.removed = 0;
+    let key_images = self.key_images.keys().copied().collect::<Vec<_>>();
+    for key_image in &key_images {
+      self.set_bloom_bits(key_image);
+    }
+  }
+
+  fn key_image_bytes(key_image: &EdwardsPoint) -> [u8; 32] {
+    key_image.compress().to_bytes()
+  }
+
+  /// Index the key image for an output.
+  ///
+  /// View-only wallets are unable to calculate the key images for their outputs, and must be
+  /// provided them by a wallet with the private spend key.
+  pub fn insert(&mut self, key_image: &EdwardsPoint, output: &WalletOutput) {
+    let key_image = Self::key_image_bytes(key_image);
+    let location = (output.transaction(), output.index_in_transaction());
+    if self.key_images.insert(key_image, location).is_some() {
+      return;
+    }
+
+    // Grow the bloom filter if it's now over capacity, doubling it to amortize the rebuilds
+    if (self.key_images.len() * BITS_PER_KEY_IMAGE) > (self.bloom.len() * 64) {
+      self.rebuild_bloom(self.key_images.len() * 2);
+    } else {
+      self.set_bloom_bits(&key_image);
+    }
+  }
+
+  /// Calculate and index the key image for an output.
+  pub fn insert_output(&mut self, spend_key: &Zeroizing<Scalar>, output: &WalletOutput) {
+    self.insert(&key_image(spend_key, output), output);
+  }
+
+  fn get_bytes(&self, key_image: &[u8; 32]) -> Option<([u8; 32], u32)> {
+    if self.bloom.is_empty() {
+      return None;
+    }
+    for bit in Self::probes(self.bloom.len() * 64, key_image) {
+      if (self.bloom[bit / 64] & (1 << (bit % 64))) == 0 {
+        return None;
+      }
+    }
+    self.key_images.get(key_image).copied()
+  }
+
+  /// The output a key image is for, if it's indexed.
+  ///
+  /// This returns the hash of the transaction which created the output and the output's index
+  /// within that transaction.
+  pub fn get(&self, key_image: &EdwardsPoint) -> Option<([u8; 32], u32)> {
+    self.get_bytes(&Self::key_image_bytes(key_image))
+  }
+
+  /// If a key image is indexed.
+  pub fn contains(&self, key_image: &EdwardsPoint) -> bool {
+    self.get(key_image).is_some()
+  }
+
+  /// Remove a key image from the index, returning the output it was for.
+  pub fn remove(&mut self, key_image: &EdwardsPoint) -> Option<([u8; 32], u32)> {
+    let res = self.key_images.remove(&Self::key_image_bytes(key_image))?;
+    self.removed += 1;
+    if self.removed > self.key_images.len() {
+      self.rebuild_bloom(self.key_images.len());
+    }
+    Some(res)
+  }
+
+  /// The outputs indexed which are spent by a transaction.
+  ///
+  /// This returns the hash of the transaction which created each output and the output's index
+  /// within that transaction. The spent outputs are not removed from the index, as the
+  /// transaction's block may be reorganized off the blockchain.
+  pub fn spent_by(&self, prefix: &TransactionPrefix) -> Vec<([u8; 32], u32)> {
+    let mut res = vec![];
+    if self.is_empty() {
+      return res;
+    }
+    for input in &prefix.inputs {
+      match input {
+        Input::Gen(_) => {}
+        Input::ToKey { key_image, .. } => {
+          if let Some(output) = self.get(key_image) {
+            res.push(output);
+          }
+        }
+      }
+    }
+    res
+  }
+
+  /// The outputs indexed which are spent within a block.
+  ///
+  /// This has the same semantics as `spent_by`, and is intended to be called alongside scanning
+  /// the block for received outputs.
+  pub fn spent_in(&self, block: &ScannableBlock) -> Vec<([u8; 32], u32)> {
+    // The miner transaction only has a Gen input, so it can't spend any outputs
+    block.transactions.iter().flat_map(|tx| self.spent_by(tx.prefix())).collect()
+  }
+
+  /// Write the KeyImageIndex.
+  ///
+  /// This is not a Monero protocol defined struct, and this is accordingly not a Monero protocol
+  /// defined serialization.
+  pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+    // Sort the key images so the serialization is deterministic
+    let mut key_images = self.key_images.iter().collect::<Vec<_>>();
+    key_images.sort_unstable_by_key(|(key_image, _)| **key_image);
+    write_vec(
+      |(key_image, (transaction, index)): &(&[u8; 32], &([u8; 32], u32)), w: &mut W| {
+        w.write_all(*key_image)?;
+        w.write_all(transaction)?;
+        w.write_all(&index.to_le_bytes())
+      },
+      &key_images,
+      w,
+    )
+  }
+
+  /// Serialize the KeyImageIndex to a `Vec<u8>`.
+  ///
+  /// This is not a Monero protocol defined struct, and this is accordingly not a Monero protocol
+  /// defined serialization.
+  pub fn serialize(&self) -> Vec<u8> {
+    let mut serialized = Vec::with_capacity(1 + (self.len() * (32 + 32 + 4)));
+    self.write(&mut serialized).unwrap();
+    serialized
+  }
+
+  /// Read a KeyImageIndex.
+  ///
+  /// This is not a Monero protocol defined struct, and this is accordingly not a Monero protocol
+  /// defined serialization.
+  pub fn read<R: Read>(r: &mut R) -> io::Result<KeyImageIndex> {
+    let key_images = read_vec(|r| Ok((read_bytes(r)?, (read_bytes(r)?, read_u32(r)?))), r)?;
+    let mut res = KeyImageIndex {
+      bloom: vec![],
+      removed: 0,
+      key_images: HashMap::with_capacity(key_images.len()),
+    };
+    for (key_image, location) in key_images {
+      if res.key_images.insert(key_image, location).is_some() {
+        Err(io::Error::other("duplicate key image"))?;
+      }
+    }
+    res.rebuild_bloom(res.key_images.len() * 2);
+    Ok(res)
+  }
+}
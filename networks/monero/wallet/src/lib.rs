@@ -38,9 +38,28 @@ pub use scan::{Timelocked, ScanError, Scanner, GuaranteedScanner};
 mod decoys;
 pub use decoys::OutputWithDecoys;
 
+mod output_index;
+pub use output_index::{OutputIndexError, verify_output_index};
+
+mod key_images;
+pub use key_images::{
+  key_image, is_valid_key_image, SpendKeyMaterial, KeyImageProofError, KeyImageProof, KeyImageIndex,
+};
+
+mod origin_proof;
+pub use origin_proof::{OriginProofError, OriginProof};
+
+mod reserve_proof;
+pub use reserve_proof::{ReserveProofError, ReserveProofEntry, ReserveProof};
+
 /// Structs and functionality for sending transactions.
 pub mod send;
 
+#[cfg(feature = "wallet-file")]
+mod wallet_file;
+#[cfg(feature = "wallet-file")]
+pub use wallet_file::{WalletFileError, KdfParameters, HistoryEntry, WalletData, WalletFile};
+
 #[cfg(test)]
 mod tests;
 
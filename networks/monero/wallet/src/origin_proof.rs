@@ -0,0 +1,315 @@
+use core::ops::Deref;
+use std_shims::{
+  vec,
+  vec::Vec,
+  io::{self, Read, Write},
+};
+
+use zeroize::Zeroizing;
+use rand_core::{RngCore, CryptoRng};
+
+use curve25519_dalek::{
+  constants::{ED25519_BASEPOINT_POINT, ED25519_BASEPOINT_TABLE},
+  Scalar, EdwardsPoint,
+};
+
+use monero_serai::{
+  io::*,
+  primitives::keccak256_to_scalar,
+  transaction::{Pruned, Transaction},
+};
+use crate::{
+  address::MoneroAddress, ViewPair, GuaranteedViewPair, WalletOutput, Extra, SharedKeyDerivations,
+};
+
+/// An error when proving or verifying the origin of an output.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+pub enum OriginProofError {
+  /// The transaction was not the transaction the output/proof is for.
+  #[cfg_attr(feature = "std", error("transaction wasn't the transaction the output is within"))]
+  WrongTransaction,
+  /// The transaction didn't have the output, or had a malformed output.
+  #[cfg_attr(feature = "std", error("transaction didn't have the output"))]
+  MissingOutput,
+  /// The transaction's extra didn't include the key the output was derived with.
+  #[cfg_attr(
+    feature = "std",
+    error("transaction didn't have the key the output was derived with")
+  )]
+  MissingTransactionKey,
+  /// The output wasn't received by the address.
+  #[cfg_attr(feature = "std", error("output wasn't received by the address"))]
+  NotReceived,
+  /// The proof of the shared secret was invalid.
+  #[cfg_attr(feature = "std", error("invalid proof of the shared secret"))]
+  InvalidProof,
+}
+
+/// A proof an output was received by an address.
+///
+/// This is intended to satisfy source-of-funds requests (such as those made for travel-rule
+/// compliance) without revealing the private view key. The proof reveals the ECDH shared secret
+/// for the transaction key the output was derived with, and proves it was correctly derived with
+/// the private view key of the recipient's address. With it, the verifier can confirm the output
+/// was sent to the address and decrypt its amount.
+///
+/// The shared secret is shared by every output derived with the same transaction key. Accordingly,
+/// this proof also allows the verifier to identify (and decrypt the amounts of) any other outputs
+/// within the same transaction, to the same address, derived with the same transaction key (such
+/// as change outputs, if this was a transaction sent by the address).
+///
+/// This is not compatible with the proofs produced by the reference wallet (`get_tx_proof`).
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct OriginProof {
+  transaction: [u8; 32],
+  index_in_transaction: u32,
+  transaction_key: EdwardsPoint,
+  // aR, where a is the private view key and R is the transaction key
+  ecdh: EdwardsPoint,
+  c: Scalar,
+  s: Scalar,
+}
+
+// The generator the view key is defined over, and the public view key
+// For a standard address, this is G, aG
+// For a subaddress, this is D, aD where D is the subaddress's spend key
+fn view_key_generator(spend: EdwardsPoint, subaddress: bool) -> EdwardsPoint {
+  if subaddress {
+    spend
+  } else {
+    ED25519_BASEPOINT_POINT
+  }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn challenge(
+  transaction: [u8; 32],
+  index_in_transaction: u32,
+  spend: EdwardsPoint,
+  generator: EdwardsPoint,
+  view: EdwardsPoint,
+  transaction_key: EdwardsPoint,
+  ecdh: EdwardsPoint,
+  nonces: [EdwardsPoint; 2],
+) -> Scalar {
+  let mut transcript = b"monero_wallet_origin_proof".to_vec();
+  transcript.extend(transaction);
+  transcript.extend(index_in_transaction.to_le_bytes());
+  for point in [spend, generator, view, transaction_key, ecdh, nonces[0], nonces[1]] {
+    transcript.extend(point.compress().to_bytes());
+  }
+  keccak256_to_scalar(transcript)
+}
+
+// The keys within the transaction the output at index `o` may have been derived with
+fn transaction_keys(tx: &Transaction<Pruned>, o: usize) -> Vec<EdwardsPoint> {
+  let Ok(extra) = Extra::read::<&[u8]>(&mut tx.prefix().extra.as_ref()) else { return vec![] };
+  let Some((mut keys, additional)) = extra.keys() else { return vec![] };
+  if let Some(additional) = additional.and_then(|additional| additional.get(o).copied()) {
+    keys.push(additional);
+  }
+  keys
+}
+
+// Check the output at index `o` was derived with `ecdh` for `spend`, returning its amount if so
+fn received_amount(
+  tx: &Transaction<Pruned>,
+  o: usize,
+  guaranteed: bool,
+  spend: EdwardsPoint,
+  ecdh: EdwardsPoint,
+) -> Result<u64, OriginProofError> {
+  let output = tx.prefix().outputs.get(o).ok_or(OriginProofError::MissingOutput)?;
+  let output_key =
+    decompress_point(output.key.to_bytes()).ok_or(OriginProofError::MissingOutput)?;
+
+  let output_derivations = SharedKeyDerivations::output_derivations(
+    guaranteed.then(|| SharedKeyDerivations::uniqueness(&tx.prefix().inputs)),
+    Zeroizing::new(ecdh),
+    o,
+  );
+  if let Some(view_tag) = output.view_tag {
+    if view_tag != output_derivations.view_tag {
+      Err(OriginProofError::NotReceived)?;
+    }
+  }
+  if output_key != ((&output_derivations.shared_key * ED25519_BASEPOINT_TABLE) + spend) {
+    Err(OriginProofError::NotReceived)?;
+  }
+
+  // Miner transaction
+  if let Some(amount) = output.amount {
+    return Ok(amount);
+  }
+  let Transaction::V2 { proofs: Some(ref proofs), .. } = tx else {
+    Err(OriginProofError::MissingOutput)?
+  };
+  let commitment = output_derivations
+    .decrypt(proofs.base.encrypted_amounts.get(o).ok_or(OriginProofError::MissingOutput)?);
+  if Some(&commitment.calculate()) != proofs.base.commitments.get(o) {
+    Err(OriginProofError::NotReceived)?;
+  }
+  Ok(commitment.amount)
+}
+
+impl OriginProof {
+  fn prove_internal(
+    rng: &mut (impl RngCore + CryptoRng),
+    pair: &ViewPair,
+    guaranteed: bool,
+    tx: &Transaction<Pruned>,
+    output: &WalletOutput,
+  ) -> Result<OriginProof, OriginProofError> {
+    let o = usize::try_from(output.index_in_transaction()).unwrap();
+    if tx.prefix().outputs.get(o).map(|tx_output| tx_output.key) != Some(output.key().compress()) {
+      Err(OriginProofError::WrongTransaction)?;
+    }
+
+    let (spend, view) = match output.subaddress() {
+      Some(subaddress) => pair.subaddress_keys(subaddress),
+      None => (pair.spend(), pair.view()),
+    };
+    let generator = view_key_generator(spend, output.subaddress().is_some());
+
+    // Find the transaction key this output was derived with
+    let mut found = None;
+    for transaction_key in transaction_keys(tx, o) {
+      let ecdh = pair.view.deref() * transaction_key;
+      if received_amount(tx, o, guaranteed, spend, ecdh).is_ok() {
+        found = Some((transaction_key, ecdh));
+        break;
+      }
+    }
+    let (transaction_key, ecdh) = found.ok_or(OriginProofError::MissingTransactionKey)?;
+
+    // Prove the discrete logarithm of the view key over its generator equals the discrete
+    // logarithm of the ECDH over the transaction key
+    let nonce = Zeroizing::new(Scalar::random(rng));
+    let c = challenge(
+      output.transaction(),
+      output.index_in_transaction(),
+      spend,
+      generator,
+      view,
+      transaction_key,
+      ecdh,
+      [generator * nonce.deref(), transaction_key * nonce.deref()],
+    );
+    let s = nonce.deref() - (c * pair.view.deref());
+
+    Ok(OriginProof {
+      transaction: output.transaction(),
+      index_in_transaction: output.index_in_transaction(),
+      transaction_key,
+      ecdh,
+      c,
+      s,
+    })
+  }
+
+  /// Prove the origin of an output received by this ViewPair.
+  ///
+  /// `tx` MUST be the transaction the output is within.
+  pub fn prove(
+    rng: &mut (impl RngCore + CryptoRng),
+    pair: &ViewPair,
+    tx: &Transaction<Pruned>,
+    output: &WalletOutput,
+  ) -> Result<OriginProof, OriginProofError> {
+    Self::prove_internal(rng, pair, false, tx, output)
+  }
+
+  /// Prove the origin of an output received by this GuaranteedViewPair.
+  ///
+  /// `tx` MUST be the transaction the output is within.
+  pub fn prove_guaranteed(
+    rng: &mut (impl RngCore + CryptoRng),
+    pair: &GuaranteedViewPair,
+    tx: &Transaction<Pruned>,
+    output: &WalletOutput,
+  ) -> Result<OriginProof, OriginProofError> {
+    Self::prove_internal(rng, &pair.0, true, tx, output)
+  }
+
+  /// The hash of the transaction the output is within.
+  pub fn transaction(&self) -> [u8; 32] {
+    self.transaction
+  }
+
+  /// The index of the output within the transaction.
+  pub fn index_in_transaction(&self) -> u32 {
+    self.index_in_transaction
+  }
+
+  pub(crate) fn verify_pruned(
+    &self,
+    address: &MoneroAddress,
+    tx: &Transaction<Pruned>,
+  ) -> Result<u64, OriginProofError> {
+    let o = usize::try_from(self.index_in_transaction).unwrap();
+    if !transaction_keys(tx, o).contains(&self.transaction_key) {
+      Err(OriginProofError::MissingTransactionKey)?;
+    }
+
+    let generator = view_key_generator(address.spend(), address.is_subaddress());
+    let c = challenge(
+      self.transaction,
+      self.index_in_transaction,
+      address.spend(),
+      generator,
+      address.view(),
+      self.transaction_key,
+      self.ecdh,
+      [
+        (generator * self.s) + (address.view() * self.c),
+        (self.transaction_key * self.s) + (self.ecdh * self.c),
+      ],
+    );
+    if c != self.c {
+      Err(OriginProofError::InvalidProof)?;
+    }
+
+    received_amount(tx, o, address.is_guaranteed(), address.spend(), self.ecdh)
+  }
+
+  /// Verify this proof, returning the amount received by the address.
+  ///
+  /// `tx` is the transaction claimed to contain the output. It will be checked to have the hash
+  /// this proof is for. It SHOULD be fetched from a trusted node to ensure it's actually on-chain.
+  pub fn verify(&self, address: &MoneroAddress, tx: &Transaction) -> Result<u64, OriginProofError> {
+    if tx.hash() != self.transaction {
+      Err(OriginProofError::WrongTransaction)?;
+    }
+    self.verify_pruned(address, &tx.clone().into())
+  }
+
+  /// Write the OriginProof.
+  pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+    w.write_all(&self.transaction)?;
+    w.write_all(&self.index_in_transaction.to_le_bytes())?;
+    write_point(&self.transaction_key, w)?;
+    write_point(&self.ecdh, w)?;
+    write_scalar(&self.c, w)?;
+    write_scalar(&self.s, w)
+  }
+
+  /// Serialize the OriginProof to a `Vec<u8>`.
+  pub fn serialize(&self) -> Vec<u8> {
+    let mut res = Vec::with_capacity(32 + 4 + (4 * 32));
+    self.write(&mut res).unwrap();
+    res
+  }
+
+  /// Read an OriginProof.
+  pub fn read<R: Read>(r: &mut R) -> io::Result<OriginProof> {
+    Ok(OriginProof {
+      transaction: read_bytes(r)?,
+      index_in_transaction: read_u32(r)?,
+      transaction_key: read_point(r)?,
+      ecdh: read_point(r)?,
+      c: read_scalar(r)?,
+      s: read_scalar(r)?,
+    })
+  }
+}
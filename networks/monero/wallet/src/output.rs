@@ -10,6 +10,7 @@ use curve25519_dalek::{Scalar, edwards::EdwardsPoint};
 
 use crate::{
   io::*, primitives::Commitment, transaction::Timelock, address::SubaddressIndex, extra::PaymentId,
+  SpendKeyMaterial,
 };
 
 /// An absolute output ID, defined as its transaction hash and output index.
@@ -280,6 +281,17 @@ impl WalletOutput {
     self.data.key_offset()
   }
 
+  /// The key image for this output, calculated with the key material of the wallet which received
+  /// it.
+  pub fn key_image(&self, key: &impl SpendKeyMaterial) -> EdwardsPoint {
+    key.key_image(self)
+  }
+
+  /// If this output's key image is the specified key image.
+  pub fn has_key_image(&self, key: &impl SpendKeyMaterial, key_image: &EdwardsPoint) -> bool {
+    self.key_image(key) == *key_image
+  }
+
   /// The commitment this output created.
   pub fn commitment(&self) -> &Commitment {
     self.data.commitment()
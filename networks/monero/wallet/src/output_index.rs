@@ -0,0 +1,144 @@
+use std_shims::string::ToString;
+
+use crate::{
+  transaction::Transaction,
+  rpc::{RpcError, ScannableBlock, DecoyRpc},
+};
+
+/// An error from verifying the output index within a `ScannableBlock`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+pub enum OutputIndexError {
+  /// The RPC used to verify the output index errored.
+  #[cfg_attr(feature = "std", error("RPC error ({0})"))]
+  Rpc(RpcError),
+  /// The block didn't have a block number.
+  #[cfg_attr(feature = "std", error("block didn't have a block number"))]
+  InvalidBlock,
+  /// The amount of RingCT outputs within the block didn't match the amount the output distribution
+  /// had for it.
+  #[cfg_attr(
+    feature = "std",
+    error(
+      "block {block} has {in_block} RingCT outputs, yet the output distribution has \
+      {in_distribution} (cumulative {prior_cumulative}, {through_cumulative})"
+    )
+  )]
+  OutputCountMismatch {
+    /// The number of the block.
+    block: usize,
+    /// The amount of RingCT outputs within the block.
+    in_block: u64,
+    /// The amount of RingCT outputs the output distribution had for the block.
+    in_distribution: u64,
+    /// The cumulative amount of RingCT outputs prior to the block.
+    prior_cumulative: u64,
+    /// The cumulative amount of RingCT outputs through the block.
+    through_cumulative: u64,
+  },
+  /// The output index for the first RingCT output within the block was incorrect.
+  #[cfg_attr(
+    feature = "std",
+    error(
+      "block {block} claimed {claimed:?} as the output index for its first RingCT output, yet \
+      it was {expected:?} ({ringct_outputs} RingCT outputs)"
+    )
+  )]
+  IndexMismatch {
+    /// The number of the block.
+    block: usize,
+    /// The output index claimed by the `ScannableBlock`.
+    claimed: Option<u64>,
+    /// The output index recomputed from the output distribution.
+    expected: Option<u64>,
+    /// The amount of RingCT outputs within the block.
+    ringct_outputs: u64,
+  },
+}
+
+// The amount of RingCT outputs within a block, as defined by the block itself
+fn ringct_outputs(block: &ScannableBlock) -> u64 {
+  // For why only v2 transactions are counted, please see the documentation in
+  // `Rpc::get_scannable_block`
+  let miner = match &block.block.miner_transaction {
+    Transaction::V1 { .. } => 0,
+    Transaction::V2 { prefix, .. } => prefix.outputs.len(),
+  };
+  let transactions = block
+    .transactions
+    .iter()
+    .filter(|tx| matches!(tx, Transaction::V2 { .. }))
+    .map(|tx| tx.prefix().outputs.len())
+    .sum::<usize>();
+  u64::try_from(miner + transactions).unwrap()
+}
+
+/// Check a `ScannableBlock`'s output index against the cumulative RingCT output distribution for
+/// its block and the block prior.
+///
+/// The distribution may omit the prior block (or both blocks) if RingCT outputs weren't yet
+/// created on-chain as of them, as the RPC does.
+pub(crate) fn check_output_index(
+  block: &ScannableBlock,
+  distribution: &[u64],
+) -> Result<(), OutputIndexError> {
+  let number = block.block.number().ok_or(OutputIndexError::InvalidBlock)?;
+  let (prior_cumulative, through_cumulative) = match distribution {
+    [] => (0, 0),
+    // The genesis block, or the block RingCT outputs were first created in
+    [through] => (0, *through),
+    [prior, through] => (*prior, *through),
+    _ => Err(OutputIndexError::Rpc(RpcError::InvalidNode(
+      "output distribution had more blocks than requested".to_string(),
+    )))?,
+  };
+  let in_distribution = through_cumulative.checked_sub(prior_cumulative).ok_or_else(|| {
+    OutputIndexError::Rpc(RpcError::InvalidNode(
+      "cumulative output distribution decreased".to_string(),
+    ))
+  })?;
+
+  let in_block = ringct_outputs(block);
+  if in_block != in_distribution {
+    Err(OutputIndexError::OutputCountMismatch {
+      block: number,
+      in_block,
+      in_distribution,
+      prior_cumulative,
+      through_cumulative,
+    })?;
+  }
+
+  let expected = (in_block != 0).then_some(prior_cumulative);
+  if block.output_index_for_first_ringct_output != expected {
+    Err(OutputIndexError::IndexMismatch {
+      block: number,
+      claimed: block.output_index_for_first_ringct_output,
+      expected,
+      ringct_outputs: in_block,
+    })?;
+  }
+  Ok(())
+}
+
+/// Verify the output index for the first RingCT output within a `ScannableBlock`.
+///
+/// Scanning trusts this index, as provided by the node the `ScannableBlock` was fetched from, and
+/// derives the output index of every output scanned from it. An incorrect index won't be noticed
+/// when scanning, yet will cause any transaction spending the scanned outputs to reference the
+/// wrong outputs, making it invalid.
+///
+/// This recomputes the index from the output distribution provided by `rpc`, additionally checking
+/// the amount of RingCT outputs within the block itself matches the distribution. `rpc` may be the
+/// node the `ScannableBlock` was fetched from or a distinct node to cross-check against.
+pub async fn verify_output_index(
+  rpc: &impl DecoyRpc,
+  block: &ScannableBlock,
+) -> Result<(), OutputIndexError> {
+  let number = block.block.number().ok_or(OutputIndexError::InvalidBlock)?;
+  let distribution = rpc
+    .get_output_distribution(number.saturating_sub(1) ..= number)
+    .await
+    .map_err(OutputIndexError::Rpc)?;
+  check_output_index(block, &distribution)
+}
@@ -0,0 +1,309 @@
+use core::ops::Deref;
+use std_shims::{
+  vec::Vec,
+  io::{self, Read, Write},
+  collections::{HashSet, HashMap},
+};
+
+use zeroize::Zeroizing;
+use rand_core::{RngCore, CryptoRng};
+
+use curve25519_dalek::{constants::ED25519_BASEPOINT_TABLE, Scalar, EdwardsPoint};
+
+use monero_serai::{
+  io::*,
+  primitives::{keccak256, keccak256_to_scalar},
+  transaction::{Pruned, Transaction},
+};
+use crate::{
+  address::MoneroAddress, WalletOutput, SpendKeyMaterial, KeyImageProofError, KeyImageProof,
+  OriginProofError, OriginProof,
+};
+
+/// An error when proving or verifying reserves.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+pub enum ReserveProofError {
+  /// The origin proof wasn't for the output.
+  #[cfg_attr(feature = "std", error("origin proof wasn't for the output"))]
+  WrongOriginProof,
+  /// The spend key wasn't the key the output was received with.
+  #[cfg_attr(feature = "std", error("spend key wasn't the key the output was received with"))]
+  WrongSpendKey,
+  /// The transaction an entry is for wasn't provided.
+  #[cfg_attr(feature = "std", error("transaction for an entry wasn't provided"))]
+  MissingTransaction,
+  /// The proof of an output's origin was invalid.
+  #[cfg_attr(feature = "std", error("invalid origin proof ({0})"))]
+  Origin(OriginProofError),
+  /// The proof of an output's key image was invalid.
+  #[cfg_attr(feature = "std", error("invalid key image proof ({0})"))]
+  KeyImage(KeyImageProofError),
+  /// The signature over the challenge was invalid.
+  #[cfg_attr(feature = "std", error("invalid signature over the challenge"))]
+  InvalidSignature,
+  /// Multiple entries were for the same key image.
+  #[cfg_attr(feature = "std", error("multiple entries were for the same key image"))]
+  DuplicateKeyImage,
+  /// The sum of the amounts exceeded a u64.
+  #[cfg_attr(feature = "std", error("sum of the amounts exceeded a u64"))]
+  AmountOverflow,
+}
+
+impl From<OriginProofError> for ReserveProofError {
+  fn from(err: OriginProofError) -> Self {
+    ReserveProofError::Origin(err)
+  }
+}
+
+impl From<KeyImageProofError> for ReserveProofError {
+  fn from(err: KeyImageProofError) -> Self {
+    ReserveProofError::KeyImage(err)
+  }
+}
+
+fn challenge(
+  challenge: &[u8],
+  transaction: [u8; 32],
+  index_in_transaction: u32,
+  output_key: EdwardsPoint,
+  key_image: EdwardsPoint,
+  nonce: EdwardsPoint,
+) -> Scalar {
+  let mut transcript = b"monero_wallet_reserve_proof".to_vec();
+  transcript.extend(keccak256(challenge));
+  transcript.extend(transaction);
+  transcript.extend(index_in_transaction.to_le_bytes());
+  for point in [output_key, key_image, nonce] {
+    transcript.extend(point.compress().to_bytes());
+  }
+  keccak256_to_scalar(transcript)
+}
+
+/// An entry within a reserve proof, proving ownership of a single unspent output.
+///
+/// This is composed of an `OriginProof`, proving the output was received by an address (and its
+/// amount), a `KeyImageProof`, revealing the output's key image so the verifier can check it's
+/// unspent, and a signature with the output's key over the verifier's challenge, proving the
+/// prover can currently spend the output.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ReserveProofEntry {
+  origin: OriginProof,
+  key_image: KeyImageProof,
+  c: Scalar,
+  s: Scalar,
+}
+
+impl ReserveProofEntry {
+  /// Prove ownership of an output, over the verifier's challenge.
+  ///
+  /// `origin` MUST be a proof of the output's origin, as produced by `OriginProof::prove` (or
+  /// `OriginProof::prove_guaranteed`).
+  pub fn prove(
+    rng: &mut (impl RngCore + CryptoRng),
+    spend_key: &Zeroizing<Scalar>,
+    challenge_bytes: &[u8],
+    output: &WalletOutput,
+    origin: OriginProof,
+  ) -> Result<ReserveProofEntry, ReserveProofError> {
+    if (origin.transaction() != output.transaction()) ||
+      (origin.index_in_transaction() != output.index_in_transaction())
+    {
+      Err(ReserveProofError::WrongOriginProof)?;
+    }
+
+    let input_key = Zeroizing::new(spend_key.deref() + output.key_offset());
+    if (input_key.deref() * ED25519_BASEPOINT_TABLE) != output.key() {
+      Err(ReserveProofError::WrongSpendKey)?;
+    }
+
+    let key_image = spend_key.prove_key_image(rng, output);
+
+    let nonce = Zeroizing::new(Scalar::random(rng));
+    let c = challenge(
+      challenge_bytes,
+      output.transaction(),
+      output.index_in_transaction(),
+      output.key(),
+      key_image.key_image(),
+      nonce.deref() * ED25519_BASEPOINT_TABLE,
+    );
+    let s = nonce.deref() - (c * input_key.deref());
+    Ok(ReserveProofEntry { origin, key_image, c, s })
+  }
+
+  /// The hash of the transaction the output is within.
+  pub fn transaction(&self) -> [u8; 32] {
+    self.origin.transaction()
+  }
+
+  /// The index of the output within the transaction.
+  pub fn index_in_transaction(&self) -> u32 {
+    self.origin.index_in_transaction()
+  }
+
+  /// The key image of the output.
+  pub fn key_image(&self) -> EdwardsPoint {
+    self.key_image.key_image()
+  }
+
+  pub(crate) fn verify_pruned(
+    &self,
+    address: &MoneroAddress,
+    challenge_bytes: &[u8],
+    tx: &Transaction<Pruned>,
+  ) -> Result<(EdwardsPoint, u64), ReserveProofError> {
+    let amount = self.origin.verify_pruned(address, tx)?;
+
+    // The origin proof having verified ensures this output exists and is well-formed
+    let o = usize::try_from(self.index_in_transaction()).unwrap();
+    let output_key = decompress_point(tx.prefix().outputs[o].key.to_bytes())
+      .ok_or(ReserveProofError::Origin(OriginProofError::MissingOutput))?;
+    let key_image = self.key_image.verify(output_key)?;
+
+    let c = challenge(
+      challenge_bytes,
+      self.transaction(),
+      self.index_in_transaction(),
+      output_key,
+      key_image,
+      (&self.s * ED25519_BASEPOINT_TABLE) + (output_key * self.c),
+    );
+    if c != self.c {
+      Err(ReserveProofError::InvalidSignature)?;
+    }
+    Ok((key_image, amount))
+  }
+
+  /// Verify this entry, returning the output's key image and amount.
+  ///
+  /// `tx` is the transaction claimed to contain the output. It will be checked to have the hash
+  /// this entry is for. It SHOULD be fetched from a trusted node to ensure it's actually on-chain.
+  ///
+  /// The returned key image MUST be checked to not have been spent, with a trusted node, for the
+  /// output to still be held.
+  pub fn verify(
+    &self,
+    address: &MoneroAddress,
+    challenge: &[u8],
+    tx: &Transaction,
+  ) -> Result<(EdwardsPoint, u64), ReserveProofError> {
+    if tx.hash() != self.transaction() {
+      Err(ReserveProofError::Origin(OriginProofError::WrongTransaction))?;
+    }
+    self.verify_pruned(address, challenge, &tx.clone().into())
+  }
+
+  /// Write the ReserveProofEntry.
+  pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+    self.origin.write(w)?;
+    self.key_image.write(w)?;
+    write_scalar(&self.c, w)?;
+    write_scalar(&self.s, w)
+  }
+
+  /// Read a ReserveProofEntry.
+  pub fn read<R: Read>(r: &mut R) -> io::Result<ReserveProofEntry> {
+    Ok(ReserveProofEntry {
+      origin: OriginProof::read(r)?,
+      key_image: KeyImageProof::read(r)?,
+      c: read_scalar(r)?,
+      s: read_scalar(r)?,
+    })
+  }
+}
+
+/// A proof of the reserves held by an address, over a challenge chosen by the verifier.
+///
+/// This allows a custodian to attest to its holdings without revealing its full wallet, solely
+/// revealing the outputs it chooses to include (and their key images, so the verifier can confirm
+/// they're unspent). It's similar in spirit to the reference wallet's `get_reserve_proof`, yet not
+/// compatible with it.
+///
+/// Each entry is proven independently, so a proof may be built incrementally (such as while
+/// scanning), and the entries for a large wallet needn't be held in memory all at once by the
+/// prover or the verifier (who may verify each entry with `ReserveProofEntry::verify`).
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ReserveProof {
+  entries: Vec<ReserveProofEntry>,
+}
+
+impl ReserveProof {
+  /// Create a new, empty reserve proof.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Add an entry to this reserve proof.
+  pub fn push(&mut self, entry: ReserveProofEntry) {
+    self.entries.push(entry);
+  }
+
+  /// The entries within this reserve proof.
+  pub fn entries(&self) -> &[ReserveProofEntry] {
+    &self.entries
+  }
+
+  /// The hashes of the transactions needed to verify this reserve proof.
+  pub fn transactions(&self) -> Vec<[u8; 32]> {
+    let mut seen = HashSet::new();
+    self.entries.iter().map(ReserveProofEntry::transaction).filter(|tx| seen.insert(*tx)).collect()
+  }
+
+  pub(crate) fn verify_pruned(
+    &self,
+    address: &MoneroAddress,
+    challenge: &[u8],
+    txs: &HashMap<[u8; 32], Transaction<Pruned>>,
+  ) -> Result<(u64, Vec<EdwardsPoint>), ReserveProofError> {
+    let mut amount = 0u64;
+    let mut key_images = Vec::with_capacity(self.entries.len());
+    let mut seen = HashSet::new();
+    for entry in &self.entries {
+      let tx = txs.get(&entry.transaction()).ok_or(ReserveProofError::MissingTransaction)?;
+      let (key_image, entry_amount) = entry.verify_pruned(address, challenge, tx)?;
+      // Outputs sharing a key image (as possible with the burning bug) may only be spent once
+      if !seen.insert(key_image.compress().to_bytes()) {
+        Err(ReserveProofError::DuplicateKeyImage)?;
+      }
+      amount = amount.checked_add(entry_amount).ok_or(ReserveProofError::AmountOverflow)?;
+      key_images.push(key_image);
+    }
+    Ok((amount, key_images))
+  }
+
+  /// Verify this reserve proof, returning the total amount proven and the key images of the
+  /// outputs.
+  ///
+  /// `txs` MUST include the transactions returned by `transactions`, and SHOULD be fetched from a
+  /// trusted node to ensure they're actually on-chain.
+  ///
+  /// The returned key images MUST be checked to not have been spent, with a trusted node, for the
+  /// amount to still be held.
+  pub fn verify(
+    &self,
+    address: &MoneroAddress,
+    challenge: &[u8],
+    txs: &[Transaction],
+  ) -> Result<(u64, Vec<EdwardsPoint>), ReserveProofError> {
+    let txs = txs.iter().map(|tx| (tx.hash(), tx.clone().into())).collect();
+    self.verify_pruned(address, challenge, &txs)
+  }
+
+  /// Write the ReserveProof.
+  pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+    write_vec(ReserveProofEntry::write, &self.entries, w)
+  }
+
+  /// Serialize the ReserveProof to a `Vec<u8>`.
+  pub fn serialize(&self) -> Vec<u8> {
+    let mut res = Vec::with_capacity(1 + (self.entries.len() * ((32 + 4 + (4 * 32)) + (5 * 32))));
+    self.write(&mut res).unwrap();
+    res
+  }
+
+  /// Read a ReserveProof.
+  pub fn read<R: Read>(r: &mut R) -> io::Result<ReserveProof> {
+    Ok(ReserveProof { entries: read_vec(ReserveProofEntry::read, r)? })
+  }
+}
@@ -0,0 +1,131 @@
+use std_shims::{vec, vec::Vec};
+
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{
+  ringct::RctType,
+  generators::MAX_COMMITMENTS,
+  address::MoneroAddress,
+  rpc::FeeRate,
+  OutputWithDecoys,
+  send::{Change, SendError, SignableTransaction},
+};
+
+/// A batch of payouts, to be fulfilled by as few transactions as possible.
+///
+/// Services making many payouts at once (such as an exchange processing withdrawals) may have
+/// more payouts than fit within a single transaction. This splits the payouts across
+/// transactions, respecting the limit on outputs per transaction, the limit of one payment ID per
+/// transaction, and the fee each transaction requires.
+///
+/// The produced transactions are deterministic to the inputs and payouts specified (independent
+/// of the order inputs were added in), so a batch may be rebuilt on retry to reproduce the same
+/// plan.
+#[derive(Clone, PartialEq, Eq, Debug, Zeroize)]
+pub struct PayoutBatch {
+  rct_type: RctType,
+  outgoing_view_key: Zeroizing<[u8; 32]>,
+  inputs: Vec<OutputWithDecoys>,
+  payouts: Vec<(MoneroAddress, u64)>,
+  change: Change,
+  fee_rate: FeeRate,
+}
+
+impl PayoutBatch {
+  /// Create a new, empty batch of payouts.
+  ///
+  /// The arguments are as for `SignableTransaction::new`. Every transaction produced sends its
+  /// change to the specified change address.
+  pub fn new(
+    rct_type: RctType,
+    outgoing_view_key: Zeroizing<[u8; 32]>,
+    change: Change,
+    fee_rate: FeeRate,
+  ) -> PayoutBatch {
+    PayoutBatch { rct_type, outgoing_view_key, inputs: vec![], payouts: vec![], change, fee_rate }
+  }
+
+  /// Add an input which may be spent to fulfill the payouts.
+  pub fn add_input(&mut self, input: OutputWithDecoys) -> &mut Self {
+    self.inputs.push(input);
+    self
+  }
+
+  /// Add a payout.
+  ///
+  /// Payouts are assigned to transactions in the order they're added.
+  pub fn add_payout(&mut self, address: MoneroAddress, amount: u64) -> &mut Self {
+    self.payouts.push((address, amount));
+    self
+  }
+
+  // Split the payouts into the groups each transaction will fulfill
+  fn groups(&self) -> Vec<Vec<(MoneroAddress, u64)>> {
+    // Reserve an output for the change, if there is change
+    let capacity = MAX_COMMITMENTS - usize::from(self.change.0.is_some());
+
+    let mut groups: Vec<Vec<(MoneroAddress, u64)>> = vec![];
+    for payout in &self.payouts {
+      // Only one payment ID is allowed per transaction
+      let has_payment_id = |address: &MoneroAddress| address.payment_id().is_some();
+      let fits = groups.last().is_some_and(|group| {
+        (group.len() < capacity) &&
+          !(has_payment_id(&payout.0) &&
+            group.iter().any(|(address, _)| has_payment_id(address)))
+      });
+      if !fits {
+        groups.push(vec![]);
+      }
+      groups.last_mut().unwrap().push(*payout);
+    }
+    groups
+  }
+
+  /// Build the transactions fulfilling this batch.
+  ///
+  /// Inputs are spent largest first, with each transaction spending as few inputs as it can
+  /// while still paying its payouts and fee. Inputs not needed by any transaction are left
+  /// unspent.
+  pub fn build(self) -> Result<Vec<SignableTransaction>, SendError> {
+    if self.payouts.is_empty() {
+      Err(SendError::NoOutputs)?;
+    }
+    let groups = self.groups();
+
+    // Order the inputs deterministically, largest first
+    let mut inputs = self.inputs;
+    inputs.sort_by(|a, b| {
+      b.commitment()
+        .amount
+        .cmp(&a.commitment().amount)
+        .then_with(|| a.key().compress().to_bytes().cmp(&b.key().compress().to_bytes()))
+    });
+    let mut inputs = inputs.into_iter();
+
+    let mut txs = vec![];
+    for payouts in groups {
+      let mut tx_inputs = vec![];
+      let mut last_error = SendError::NoInputs;
+      let tx = loop {
+        let Some(input) = inputs.next() else { Err(last_error)? };
+        tx_inputs.push(input);
+        match SignableTransaction::new(
+          self.rct_type,
+          self.outgoing_view_key.clone(),
+          tx_inputs.clone(),
+          payouts.clone(),
+          self.change.clone(),
+          vec![],
+          self.fee_rate,
+        ) {
+          Ok(tx) => break tx,
+          // Spend another input and try again
+          Err(e @ SendError::NotEnoughFunds { .. }) => last_error = e,
+          Err(e) => Err(e)?,
+        }
+      };
+      txs.push(tx);
+    }
+    Ok(txs)
+  }
+}
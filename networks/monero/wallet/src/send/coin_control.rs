@@ -0,0 +1,269 @@
+use std_shims::{vec, vec::Vec};
+
+use zeroize::{Zeroize, Zeroizing};
+
+use curve25519_dalek::EdwardsPoint;
+
+use crate::{
+  ringct::RctType,
+  address::MoneroAddress,
+  rpc::FeeRate,
+  OutputWithDecoys,
+  send::{Change, SendError, SignableTransaction},
+};
+
+/// An error when funding a transaction under coin control.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+pub enum CoinControlError {
+  /// An input was required to be spent, yet wasn't added as an input.
+  #[cfg_attr(feature = "std", error("a required input wasn't added as an input"))]
+  UnknownRequiredInput,
+  /// An input was required to be spent, yet was locked.
+  #[cfg_attr(feature = "std", error("a required input was locked"))]
+  RequiredInputLocked,
+  /// An input was required to be spent, yet didn't have the minimum amount of confirmations.
+  #[cfg_attr(
+    feature = "std",
+    error("a required input had {confirmations} confirmations, yet {minimum} were required")
+  )]
+  RequiredInputUnconfirmed {
+    /// The amount of confirmations the input had.
+    confirmations: usize,
+    /// The minimum amount of confirmations required.
+    minimum: usize,
+  },
+  /// The inputs which may be spent didn't suffice to fund the transaction.
+  #[cfg_attr(
+    feature = "std",
+    error(
+      "not enough funds (spendable {spendable}, excluded {excluded}, outputs {outputs}, \
+       necessary_fee {necessary_fee:?})"
+    )
+  )]
+  NotEnoughFunds {
+    /// The amount of funds the inputs which may be spent contributed.
+    spendable: u64,
+    /// The amount of funds within inputs excluded for being locked or insufficiently confirmed.
+    excluded: u64,
+    /// The amount of funds the outputs required.
+    outputs: u64,
+    /// The fee necessary to be paid on top.
+    ///
+    /// If this is None, it is because the fee was not calculated as the outputs alone caused this
+    /// error.
+    necessary_fee: Option<u64>,
+  },
+  /// The transaction couldn't be created.
+  #[cfg_attr(feature = "std", error("{0}"))]
+  SendError(SendError),
+}
+
+impl From<SendError> for CoinControlError {
+  fn from(e: SendError) -> CoinControlError {
+    CoinControlError::SendError(e)
+  }
+}
+
+/// A transaction, funded with explicit control over which inputs are spent.
+///
+/// Inputs may be required to be spent, locked so they're never spent, or excluded from being spent
+/// until they have a minimum amount of confirmations. Required inputs are always spent. Further
+/// inputs are spent as needed to fund the transaction, largest first, unless only the required
+/// inputs may be spent.
+///
+/// The transaction produced is deterministic to the inputs, constraints, and payments specified
+/// (independent of the order inputs were added in), so it may be rebuilt on retry to reproduce the
+/// same transaction.
+#[derive(Clone, PartialEq, Eq, Debug, Zeroize)]
+pub struct CoinControl {
+  rct_type: RctType,
+  outgoing_view_key: Zeroizing<[u8; 32]>,
+  // Each input, with the number of the block it was received in
+  inputs: Vec<(OutputWithDecoys, usize)>,
+  required: Vec<EdwardsPoint>,
+  locked: Vec<EdwardsPoint>,
+  // The number of the latest block, and the minimum amount of confirmations for an input
+  confirmations: Option<(usize, usize)>,
+  only_required: bool,
+  payments: Vec<(MoneroAddress, u64)>,
+  change: Change,
+  data: Vec<Vec<u8>>,
+  fee_rate: FeeRate,
+}
+
+impl CoinControl {
+  /// Create a new transaction, without any inputs or payments.
+  ///
+  /// The arguments are as for `SignableTransaction::new`.
+  pub fn new(
+    rct_type: RctType,
+    outgoing_view_key: Zeroizing<[u8; 32]>,
+    change: Change,
+    fee_rate: FeeRate,
+  ) -> CoinControl {
+    CoinControl {
+      rct_type,
+      outgoing_view_key,
+      inputs: vec![],
+      required: vec![],
+      locked: vec![],
+      confirmations: None,
+      only_required: false,
+      payments: vec![],
+      change,
+      data: vec![],
+      fee_rate,
+    }
+  }
+
+  /// Add an input which may be spent, received in the block with the specified number.
+  pub fn add_input(&mut self, input: OutputWithDecoys, block_number: usize) -> &mut Self {
+    self.inputs.push((input, block_number));
+    self
+  }
+
+  /// Require the input with the specified key be spent.
+  pub fn require_input(&mut self, key: EdwardsPoint) -> &mut Self {
+    if !self.required.contains(&key) {
+      self.required.push(key);
+    }
+    self
+  }
+
+  /// Lock the input with the specified key, preventing it from being spent.
+  pub fn lock_input(&mut self, key: EdwardsPoint) -> &mut Self {
+    if !self.locked.contains(&key) {
+      self.locked.push(key);
+    }
+    self
+  }
+
+  /// Unlock the input with the specified key, if it was locked.
+  pub fn unlock_input(&mut self, key: EdwardsPoint) -> &mut Self {
+    self.locked.retain(|locked| *locked != key);
+    self
+  }
+
+  /// Exclude inputs with less than the minimum amount of confirmations from being spent.
+  ///
+  /// An input received in the latest block has one confirmation.
+  pub fn exclude_unconfirmed(
+    &mut self,
+    latest_block_number: usize,
+    minimum_confirmations: usize,
+  ) -> &mut Self {
+    self.confirmations = Some((latest_block_number, minimum_confirmations));
+    self
+  }
+
+  /// Only spend the required inputs, never spending additional inputs to fund the transaction.
+  pub fn only_required_inputs(&mut self) -> &mut Self {
+    self.only_required = true;
+    self
+  }
+
+  /// Add a payment.
+  pub fn add_payment(&mut self, address: MoneroAddress, amount: u64) -> &mut Self {
+    self.payments.push((address, amount));
+    self
+  }
+
+  /// Add arbitrary data to the transaction.
+  pub fn add_data(&mut self, data: Vec<u8>) -> &mut Self {
+    self.data.push(data);
+    self
+  }
+
+  // The amount of confirmations an input has, if it's insufficiently confirmed
+  fn unconfirmed(&self, block_number: usize) -> Option<(usize, usize)> {
+    let (latest_block_number, minimum) = self.confirmations?;
+    let confirmations = (latest_block_number + 1).saturating_sub(block_number);
+    (confirmations < minimum).then_some((confirmations, minimum))
+  }
+
+  /// Build the transaction.
+  ///
+  /// The required inputs are spent, with additional inputs spent largest first until the
+  /// transaction pays its payments and fee.
+  pub fn build(self) -> Result<SignableTransaction, CoinControlError> {
+    if self.payments.is_empty() {
+      Err(SendError::NoOutputs)?;
+    }
+
+    let mut required = vec![];
+    for key in &self.required {
+      let Some((input, block_number)) = self.inputs.iter().find(|(input, _)| input.key() == *key)
+      else {
+        Err(CoinControlError::UnknownRequiredInput)?
+      };
+      if self.locked.contains(key) {
+        Err(CoinControlError::RequiredInputLocked)?;
+      }
+      if let Some((confirmations, minimum)) = self.unconfirmed(*block_number) {
+        Err(CoinControlError::RequiredInputUnconfirmed { confirmations, minimum })?;
+      }
+      required.push(input.clone());
+    }
+
+    let mut additional = vec![];
+    let mut excluded = 0u64;
+    for (input, block_number) in &self.inputs {
+      if self.required.contains(&input.key()) {
+        continue;
+      }
+      if self.locked.contains(&input.key()) || self.unconfirmed(*block_number).is_some() {
+        excluded = excluded.saturating_add(input.commitment().amount);
+        continue;
+      }
+      if !self.only_required {
+        additional.push(input.clone());
+      }
+    }
+
+    // Order the additional inputs deterministically, largest first
+    additional.sort_by(|a, b| {
+      b.commitment()
+        .amount
+        .cmp(&a.commitment().amount)
+        .then_with(|| a.key().compress().to_bytes().cmp(&b.key().compress().to_bytes()))
+    });
+    let mut additional = additional.into_iter();
+
+    let mut inputs = required;
+    if inputs.is_empty() {
+      inputs.extend(additional.next());
+    }
+    let mut necessary_fee = None;
+    loop {
+      if !inputs.is_empty() {
+        match SignableTransaction::new(
+          self.rct_type,
+          self.outgoing_view_key.clone(),
+          inputs.clone(),
+          self.payments.clone(),
+          self.change.clone(),
+          self.data.clone(),
+          self.fee_rate,
+        ) {
+          Ok(tx) => return Ok(tx),
+          Err(SendError::NotEnoughFunds { necessary_fee: fee, .. }) => necessary_fee = fee,
+          Err(e) => Err(e)?,
+        }
+      }
+
+      // Spend another input and try again
+      let Some(input) = additional.next() else {
+        Err(CoinControlError::NotEnoughFunds {
+          spendable: inputs
+            .iter()
+            .fold(0u64, |sum, input| sum.saturating_add(input.commitment().amount)),
+          excluded,
+          outputs: self.payments.iter().fold(0u64, |sum, (_, amount)| sum.saturating_add(*amount)),
+          necessary_fee,
+        })?
+      };
+      inputs.push(input);
+    }
+  }
+}
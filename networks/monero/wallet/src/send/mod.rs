@@ -33,6 +33,12 @@ pub use tx_keys::TransactionKeys;
 mod tx;
 mod eventuality;
 pub use eventuality::Eventuality;
+mod shape;
+pub use shape::TransactionShape;
+mod batch;
+pub use batch::PayoutBatch;
+mod coin_control;
+pub use coin_control::{CoinControlError, CoinControl};
 
 #[cfg(feature = "multisig")]
 mod multisig;
@@ -174,6 +180,12 @@ pub enum SendError {
   /// Too much arbitrary data was specified.
   #[cfg_attr(feature = "std", error("too much data"))]
   TooMuchArbitraryData,
+  /// The transaction could not be constructed with the specified shape.
+  ///
+  /// This occurs when the amount of inputs differs from the shape's, there are more outputs than
+  /// the shape allows, or no change address was specified to pad the outputs with.
+  #[cfg_attr(feature = "std", error("transaction didn't fit the specified shape"))]
+  InvalidShape,
   /// The created transaction was too large.
   #[cfg_attr(feature = "std", error("too large of a transaction"))]
   TooLargeTransaction,
@@ -374,6 +386,63 @@ impl SignableTransaction {
     Ok(res)
   }
 
+  /// Create a new SignableTransaction with the specified shape.
+  ///
+  /// The outputs are padded with dummy outputs to the change address until the transaction has
+  /// the shape's amount of outputs. The amount of inputs is not padded, and this will error if it
+  /// doesn't equal the shape's.
+  ///
+  /// Returns the transaction and the additional fee it requires due to being padded, enabling
+  /// callers to evaluate the cost of this policy. All other arguments are as for `new`.
+  #[allow(clippy::too_many_arguments)]
+  pub fn new_with_shape(
+    rct_type: RctType,
+    outgoing_view_key: Zeroizing<[u8; 32]>,
+    inputs: Vec<OutputWithDecoys>,
+    mut payments: Vec<(MoneroAddress, u64)>,
+    change: Change,
+    data: Vec<Vec<u8>>,
+    fee_rate: FeeRate,
+    shape: TransactionShape,
+  ) -> Result<(SignableTransaction, u64), SendError> {
+    if inputs.len() != shape.inputs() {
+      Err(SendError::InvalidShape)?;
+    }
+    let Some(change_enum) = change.0.clone() else { Err(SendError::InvalidShape)? };
+    // + 1 for the change output
+    if (payments.len() + 1) > shape.outputs() {
+      Err(SendError::InvalidShape)?;
+    }
+
+    // Calculate the fee necessary without padding
+    let unpadded_fee = {
+      let mut unpadded_payments = payments
+        .iter()
+        .map(|(addr, amount)| InternalPayment::Payment(*addr, *amount))
+        .collect::<Vec<_>>();
+      unpadded_payments.push(InternalPayment::Change(change_enum.clone()));
+      SignableTransaction {
+        rct_type,
+        outgoing_view_key: outgoing_view_key.clone(),
+        inputs: inputs.clone(),
+        payments: unpadded_payments,
+        data: data.clone(),
+        fee_rate,
+      }
+      .necessary_fee()
+    };
+
+    // Pad with dummy outputs to the change address
+    let dummy_address = InternalPayment::Change(change_enum).address();
+    while (payments.len() + 1) < shape.outputs() {
+      payments.push((dummy_address, 0));
+    }
+
+    let res = Self::new(rct_type, outgoing_view_key, inputs, payments, change, data, fee_rate)?;
+    let padding_fee = res.necessary_fee().saturating_sub(unpadded_fee);
+    Ok((res, padding_fee))
+  }
+
   /// The fee rate this transaction uses.
   pub fn fee_rate(&self) -> FeeRate {
     self.fee_rate
@@ -0,0 +1,41 @@
+use crate::generators::MAX_COMMITMENTS;
+
+/// An amount of inputs and outputs for transactions to have.
+///
+/// A service whose transactions have varying amounts of inputs and outputs produces transactions
+/// which may be identified by these amounts. Constructing every transaction with the same shape
+/// removes this distinction, though solely this distinction. Transactions may still be
+/// distinguished by their fees, timing, and any other properties.
+///
+/// Transactions are padded to their amount of outputs with dummy outputs, of zero value, to the
+/// change address. The amount of inputs is not padded, as that would require selecting additional
+/// outputs to spend. Constructing a transaction whose amount of inputs differs from its shape's
+/// errors, with callers expected to select exactly as many inputs as the shape specifies.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TransactionShape {
+  inputs: usize,
+  outputs: usize,
+}
+
+impl TransactionShape {
+  /// Create a new transaction shape.
+  ///
+  /// This returns None if the shape would not yield valid transactions. Transactions must have at
+  /// least one input and at least two outputs (including the change output).
+  pub fn new(inputs: usize, outputs: usize) -> Option<TransactionShape> {
+    if (inputs == 0) || (outputs < 2) || (outputs > MAX_COMMITMENTS) {
+      None?;
+    }
+    Some(TransactionShape { inputs, outputs })
+  }
+
+  /// The amount of inputs transactions of this shape have.
+  pub fn inputs(&self) -> usize {
+    self.inputs
+  }
+
+  /// The amount of outputs transactions of this shape have, including the change output.
+  pub fn outputs(&self) -> usize {
+    self.outputs
+  }
+}
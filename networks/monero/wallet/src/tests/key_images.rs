@@ -0,0 +1,165 @@
+use zeroize::Zeroizing;
+use rand_core::OsRng;
+
+use curve25519_dalek::{
+  traits::Identity,
+  constants::{ED25519_BASEPOINT_TABLE, EIGHT_TORSION},
+  Scalar, EdwardsPoint,
+};
+
+use crate::{
+  transaction::{Timelock, Input, TransactionPrefix},
+  key_image, is_valid_key_image, SpendKeyMaterial, KeyImageProofError, KeyImageProof,
+  KeyImageIndex,
+};
+
+use super::{
+  scalar,
+  scan::{SPEND_KEY, wallet_output0, wallet_output1},
+};
+
+fn spend_key() -> Zeroizing<Scalar> {
+  Zeroizing::new(scalar(SPEND_KEY))
+}
+
+fn spending(key_images: &[EdwardsPoint]) -> TransactionPrefix {
+  TransactionPrefix {
+    additional_timelock: Timelock::None,
+    inputs: key_images
+      .iter()
+      .map(|key_image| Input::ToKey { amount: None, key_offsets: vec![], key_image: *key_image })
+      .collect(),
+    outputs: vec![],
+    extra: vec![],
+  }
+}
+
+#[test]
+fn key_image_index() {
+  let spend_key = spend_key();
+  let output0 = wallet_output0();
+  let output1 = wallet_output1();
+  let location0 = (output0.transaction(), output0.index_in_transaction());
+  let location1 = (output1.transaction(), output1.index_in_transaction());
+  let key_image0 = key_image(&spend_key, &output0);
+  let key_image1 = key_image(&spend_key, &output1);
+  let unrelated = &Scalar::ONE * ED25519_BASEPOINT_TABLE;
+
+  let mut index = KeyImageIndex::new();
+  assert!(index.is_empty());
+  assert!(index.spent_by(&spending(&[key_image0])).is_empty());
+
+  index.insert_output(&spend_key, &output0);
+  index.insert(&key_image1, &output1);
+  // Inserting a key image again should be a no-op
+  index.insert_output(&spend_key, &output1);
+  assert_eq!(index.len(), 2);
+  assert_eq!(index.get(&key_image0), Some(location0));
+  assert_eq!(index.get(&key_image1), Some(location1));
+  assert!(!index.contains(&unrelated));
+
+  assert_eq!(index.spent_by(&spending(&[unrelated, key_image1])), vec![location1]);
+  assert_eq!(
+    index.spent_by(&spending(&[key_image0, unrelated, key_image1])),
+    vec![location0, location1]
+  );
+  // Detecting spends shouldn't remove the key images
+  assert_eq!(index.len(), 2);
+
+  // The index should survive serialization
+  let serialized = index.serialize();
+  let read = KeyImageIndex::read::<&[u8]>(&mut serialized.as_ref()).unwrap();
+  assert_eq!(read, index);
+  assert_eq!(read.serialize(), serialized);
+  assert_eq!(read.get(&key_image0), Some(location0));
+  assert_eq!(read.get(&key_image1), Some(location1));
+
+  // Duplicate key images should be rejected
+  {
+    let mut duplicated = serialized.clone();
+    duplicated[0] = 3;
+    duplicated.extend(&serialized[1 .. (1 + 32 + 32 + 4)]);
+    assert!(KeyImageIndex::read::<&[u8]>(&mut duplicated.as_ref()).is_err());
+  }
+
+  assert_eq!(index.remove(&key_image0), Some(location0));
+  assert_eq!(index.remove(&key_image0), None);
+  assert!(!index.contains(&key_image0));
+  assert_eq!(index.get(&key_image1), Some(location1));
+  assert_eq!(index.spent_by(&spending(&[key_image0, key_image1])), vec![location1]);
+}
+
+#[test]
+fn key_image_index_growth() {
+  let spend_key = spend_key();
+  let output = wallet_output0();
+  let location = (output.transaction(), output.index_in_transaction());
+
+  // Insert enough key images to grow the bloom filter multiple times
+  let mut index = KeyImageIndex::new();
+  let key_images =
+    (1 ..= 1000u64).map(|i| &Scalar::from(i) * ED25519_BASEPOINT_TABLE).collect::<Vec<_>>();
+  for key_image in &key_images {
+    index.insert(key_image, &output);
+  }
+  index.insert_output(&spend_key, &output);
+  assert_eq!(index.len(), 1001);
+  for key_image in &key_images {
+    assert_eq!(index.get(key_image), Some(location));
+  }
+
+  // Remove most of them, triggering the bloom filter to be rebuilt
+  for key_image in &key_images[.. 900] {
+    assert_eq!(index.remove(key_image), Some(location));
+  }
+  assert_eq!(index.len(), 101);
+  for key_image in &key_images[.. 900] {
+    assert!(!index.contains(key_image));
+  }
+  for key_image in &key_images[900 ..] {
+    assert!(index.contains(key_image));
+  }
+  assert!(index.contains(&key_image(&spend_key, &output)));
+}
+
+#[test]
+fn key_image_proof() {
+  let spend_key = spend_key();
+  let output0 = wallet_output0();
+  let output1 = wallet_output1();
+  let key_image0 = output0.key_image(&spend_key);
+  assert_eq!(key_image0, key_image(&spend_key, &output0));
+  assert!(is_valid_key_image(&key_image0));
+  assert!(output0.has_key_image(&spend_key, &key_image0));
+  assert!(!output1.has_key_image(&spend_key, &key_image0));
+
+  let proof = spend_key.prove_key_image(&mut OsRng, &output0);
+  assert_eq!(proof.key_image(), key_image0);
+  assert_eq!(proof.verify(output0.key()), Ok(key_image0));
+  // The proof shouldn't verify for another output
+  assert_eq!(proof.verify(output1.key()), Err(KeyImageProofError::InvalidProof));
+
+  // The proof should survive serialization
+  let serialized = proof.serialize();
+  assert_eq!(serialized.len(), 3 * 32);
+  let read = KeyImageProof::read::<&[u8]>(&mut serialized.as_ref()).unwrap();
+  assert_eq!(read, proof);
+  assert_eq!(read.verify(output0.key()), Ok(key_image0));
+
+  // Malleating the key image should be detected
+  {
+    let mut malleated = serialized.clone();
+    malleated[.. 32].copy_from_slice(&(key_image0 + EIGHT_TORSION[1]).compress().to_bytes());
+    let malleated = KeyImageProof::read::<&[u8]>(&mut malleated.as_ref()).unwrap();
+    assert_eq!(malleated.verify(output0.key()), Err(KeyImageProofError::InvalidKeyImage));
+  }
+  {
+    let mut malleated = serialized;
+    malleated[.. 32].copy_from_slice(&key_image(&spend_key, &output1).compress().to_bytes());
+    let malleated = KeyImageProof::read::<&[u8]>(&mut malleated.as_ref()).unwrap();
+    assert_eq!(malleated.verify(output0.key()), Err(KeyImageProofError::InvalidProof));
+  }
+
+  assert!(!is_valid_key_image(&EdwardsPoint::identity()));
+  assert!(!is_valid_key_image(&EIGHT_TORSION[1]));
+}
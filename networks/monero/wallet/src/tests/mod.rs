@@ -1,2 +1,53 @@
+use zeroize::Zeroizing;
+use curve25519_dalek::{Scalar, constants::ED25519_BASEPOINT_TABLE};
+
+use monero_rpc::ScannableBlock;
+use crate::{
+  transaction::{Pruned, Transaction},
+  block::Block,
+  ViewPair, Scanner, WalletOutput,
+};
+
 mod extra;
 mod scan;
+mod output_index;
+mod origin_proof;
+mod reserve_proof;
+mod key_images;
+#[cfg(feature = "wallet-file")]
+mod wallet_file;
+
+use scan::{
+  SPEND_KEY, VIEW_KEY, PRUNED_TX_WITH_LONG_ENCRYPTED_AMOUNT, BLOCK,
+  OUTPUT_INDEX_FOR_FIRST_RINGCT_OUTPUT,
+};
+
+fn scalar(hex_str: &str) -> Scalar {
+  Scalar::from_canonical_bytes(hex::decode(hex_str).unwrap().try_into().unwrap()).unwrap()
+}
+
+// The view pair the test transaction was sent to
+fn view_pair() -> ViewPair {
+  ViewPair::new(&scalar(SPEND_KEY) * ED25519_BASEPOINT_TABLE, Zeroizing::new(scalar(VIEW_KEY)))
+    .unwrap()
+}
+
+fn transaction() -> Transaction<Pruned> {
+  let tx_buf = hex::decode(PRUNED_TX_WITH_LONG_ENCRYPTED_AMOUNT).unwrap();
+  Transaction::<Pruned>::read::<&[u8]>(&mut tx_buf.as_ref()).unwrap()
+}
+
+// The test block has a v1 miner transaction and a single v2 transaction with two outputs
+fn scannable_block(output_index_for_first_ringct_output: Option<u64>) -> ScannableBlock {
+  let block_buf = hex::decode(BLOCK).unwrap();
+  let block = Block::read::<&[u8]>(&mut block_buf.as_ref()).unwrap();
+  ScannableBlock { block, transactions: vec![transaction()], output_index_for_first_ringct_output }
+}
+
+// Scan the test block, returning the outputs found
+fn scanned_outputs(scanner: &mut Scanner) -> Vec<WalletOutput> {
+  scanner
+    .scan(scannable_block(Some(OUTPUT_INDEX_FOR_FIRST_RINGCT_OUTPUT)))
+    .unwrap()
+    .not_additionally_locked()
+}
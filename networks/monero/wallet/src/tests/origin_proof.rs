@@ -0,0 +1,39 @@
+use rand_core::OsRng;
+
+use crate::{
+  address::{Network, SubaddressIndex},
+  Scanner, OriginProofError, OriginProof,
+};
+
+use super::{view_pair, transaction, scanned_outputs};
+
+#[test]
+fn origin_proof() {
+  let pair = view_pair();
+  let tx = transaction();
+  let outputs = scanned_outputs(&mut Scanner::new(pair.clone()));
+  assert_eq!(outputs.len(), 2);
+
+  let address = pair.legacy_address(Network::Mainnet);
+  for output in &outputs {
+    let proof = OriginProof::prove(&mut OsRng, &pair, &tx, output).unwrap();
+    assert_eq!(proof.transaction(), output.transaction());
+    assert_eq!(proof.index_in_transaction(), output.index_in_transaction());
+    assert_eq!(proof.verify_pruned(&address, &tx), Ok(output.commitment().amount));
+
+    // Check the proof serializes
+    let serialized = proof.serialize();
+    assert_eq!(OriginProof::read::<&[u8]>(&mut serialized.as_ref()).unwrap(), proof);
+
+    // The proof shouldn't verify for another address
+    let subaddress = pair.subaddress(Network::Mainnet, SubaddressIndex::new(0, 1).unwrap());
+    assert_eq!(proof.verify_pruned(&subaddress, &tx), Err(OriginProofError::InvalidProof));
+
+    // A malleated proof shouldn't verify
+    let mut malleated = serialized.clone();
+    *malleated.last_mut().unwrap() ^= 1;
+    if let Ok(malleated) = OriginProof::read::<&[u8]>(&mut malleated.as_ref()) {
+      assert_eq!(malleated.verify_pruned(&address, &tx), Err(OriginProofError::InvalidProof));
+    }
+  }
+}
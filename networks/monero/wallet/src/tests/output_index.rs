@@ -0,0 +1,49 @@
+use crate::output_index::{OutputIndexError, check_output_index};
+
+use super::scannable_block;
+
+#[test]
+fn output_index() {
+  let number = scannable_block(None).block.number().unwrap();
+
+  // The correct index should verify
+  check_output_index(&scannable_block(Some(100)), &[100, 102]).unwrap();
+  // Including if RingCT outputs were first created within this block
+  check_output_index(&scannable_block(Some(0)), &[2]).unwrap();
+
+  // An incorrect index should be a hard error
+  assert_eq!(
+    check_output_index(&scannable_block(Some(101)), &[100, 102]),
+    Err(OutputIndexError::IndexMismatch {
+      block: number,
+      claimed: Some(101),
+      expected: Some(100),
+      ringct_outputs: 2
+    })
+  );
+  assert_eq!(
+    check_output_index(&scannable_block(None), &[100, 102]),
+    Err(OutputIndexError::IndexMismatch {
+      block: number,
+      claimed: None,
+      expected: Some(100),
+      ringct_outputs: 2
+    })
+  );
+
+  // As should a distribution disagreeing with the block's contents
+  assert_eq!(
+    check_output_index(&scannable_block(Some(100)), &[100, 103]),
+    Err(OutputIndexError::OutputCountMismatch {
+      block: number,
+      in_block: 2,
+      in_distribution: 3,
+      prior_cumulative: 100,
+      through_cumulative: 103,
+    })
+  );
+  assert!(matches!(
+    check_output_index(&scannable_block(Some(100)), &[102, 100]),
+    Err(OutputIndexError::Rpc(_))
+  ));
+}
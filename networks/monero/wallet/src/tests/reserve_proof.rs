@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use rand_core::OsRng;
+
+use zeroize::Zeroizing;
+use curve25519_dalek::Scalar;
+
+use crate::{
+  address::{Network, SubaddressIndex},
+  Scanner, OriginProof, ReserveProofError, ReserveProofEntry, ReserveProof,
+};
+
+use super::{scalar, view_pair, transaction, scanned_outputs, scan::SPEND_KEY};
+
+#[test]
+fn reserve_proof() {
+  let spend_key = Zeroizing::new(scalar(SPEND_KEY));
+  let pair = view_pair();
+  let tx = transaction();
+  let outputs = scanned_outputs(&mut Scanner::new(pair.clone()));
+  assert_eq!(outputs.len(), 2);
+
+  const CHALLENGE: &[u8] = b"reserve proof challenge";
+  let address = pair.legacy_address(Network::Mainnet);
+  let txs = HashMap::from([(outputs[0].transaction(), tx.clone())]);
+
+  // Build the proof incrementally, checking each entry as it's added
+  let mut proof = ReserveProof::new();
+  let mut expected = 0;
+  for output in &outputs {
+    let origin = OriginProof::prove(&mut OsRng, &pair, &tx, output).unwrap();
+    let entry =
+      ReserveProofEntry::prove(&mut OsRng, &spend_key, CHALLENGE, output, origin).unwrap();
+    assert_eq!(entry.transaction(), output.transaction());
+    assert_eq!(entry.index_in_transaction(), output.index_in_transaction());
+    assert_eq!(entry.key_image(), output.key_image(&spend_key));
+    assert_eq!(
+      entry.verify_pruned(&address, CHALLENGE, &tx),
+      Ok((output.key_image(&spend_key), output.commitment().amount))
+    );
+
+    // The entry shouldn't verify for another challenge
+    assert_eq!(
+      entry.verify_pruned(&address, b"another challenge", &tx),
+      Err(ReserveProofError::InvalidSignature)
+    );
+
+    proof.push(entry);
+    expected += output.commitment().amount;
+  }
+  assert_eq!(proof.transactions(), vec![outputs[0].transaction()]);
+
+  let (amount, key_images) = proof.verify_pruned(&address, CHALLENGE, &txs).unwrap();
+  assert_eq!(amount, expected);
+  assert_eq!(
+    key_images,
+    outputs.iter().map(|output| output.key_image(&spend_key)).collect::<Vec<_>>()
+  );
+
+  // Check the proof serializes
+  let serialized = proof.serialize();
+  assert_eq!(ReserveProof::read::<&[u8]>(&mut serialized.as_ref()).unwrap(), proof);
+
+  // The proof shouldn't verify for another challenge, another address, or without the transaction
+  assert_eq!(
+    proof.verify_pruned(&address, b"another challenge", &txs),
+    Err(ReserveProofError::InvalidSignature)
+  );
+  let subaddress = pair.subaddress(Network::Mainnet, SubaddressIndex::new(0, 1).unwrap());
+  assert!(matches!(
+    proof.verify_pruned(&subaddress, CHALLENGE, &txs),
+    Err(ReserveProofError::Origin(_))
+  ));
+  assert_eq!(
+    proof.verify_pruned(&address, CHALLENGE, &HashMap::new()),
+    Err(ReserveProofError::MissingTransaction)
+  );
+
+  // An output may only be included once
+  let mut duplicated = proof.clone();
+  duplicated.push(proof.entries()[0].clone());
+  assert_eq!(
+    duplicated.verify_pruned(&address, CHALLENGE, &txs),
+    Err(ReserveProofError::DuplicateKeyImage)
+  );
+
+  // Proving with the wrong spend key, or the wrong origin proof, should error
+  let origin = OriginProof::prove(&mut OsRng, &pair, &tx, &outputs[0]).unwrap();
+  assert_eq!(
+    ReserveProofEntry::prove(
+      &mut OsRng,
+      &Zeroizing::new(Scalar::random(&mut OsRng)),
+      CHALLENGE,
+      &outputs[0],
+      origin.clone()
+    ),
+    Err(ReserveProofError::WrongSpendKey)
+  );
+  assert_eq!(
+    ReserveProofEntry::prove(&mut OsRng, &spend_key, CHALLENGE, &outputs[1], origin),
+    Err(ReserveProofError::WrongOriginProof)
+  );
+}
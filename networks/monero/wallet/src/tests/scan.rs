@@ -2,6 +2,7 @@ use monero_rpc::ScannableBlock;
 use crate::{
   transaction::{Pruned, Transaction},
   block::Block,
+  address::SubaddressIndex,
   ViewPair, Scanner, WalletOutput,
   output::{AbsoluteId, RelativeId, OutputData, Metadata},
   Commitment,
@@ -12,18 +13,20 @@ use crate::{
 use zeroize::Zeroizing;
 use curve25519_dalek::{Scalar, constants::ED25519_BASEPOINT_TABLE, edwards::CompressedEdwardsY};
 
-const SPEND_KEY: &str = "ccf0ea10e1ea64354f42fa710c2b318e581969cf49046d809d1f0aadb3fc7a02";
-const VIEW_KEY: &str = "a28b4b2085592881df94ee95da332c16b5bb773eb8bb74730208cbb236c73806";
+pub(super) const SPEND_KEY: &str =
+  "ccf0ea10e1ea64354f42fa710c2b318e581969cf49046d809d1f0aadb3fc7a02";
+pub(super) const VIEW_KEY: &str =
+  "a28b4b2085592881df94ee95da332c16b5bb773eb8bb74730208cbb236c73806";
 
 #[rustfmt::skip]
-const PRUNED_TX_WITH_LONG_ENCRYPTED_AMOUNT: &str = "020001020003060101cf60390bb71aa15eb24037772012d59dc68cb4b6211e1c93206db09a6c346261020002ee8ca293511571c0005e1c144e49d09b8ff03046dbafb3e064a34cb9fc1994b600029e2e5cd08c8681dbcf2ce66071467e835f7e86613fbfed3c4fb170127b94e1072c01d3ce2a622c6e06ed465f81017dd6188c3a6e3d8e65a846f9c98416da0e150a82020901c553d35e54111bd001e0bbcbf289d701ce90e309ead2b487ec1d4d8af5d649543eb99a7620f6b54e532898527be29704f050e6f06de61e5967b2ddd506b4d6d36546065d6aae156ac7bec18c99580c07867fb98cb29853edbafec91af2df605c12f9aaa81a9165625afb6649f5a652012c5ba6612351140e1fb4a8463cc765d0a9bb7d999ba35750f365c5285d77230b76c7a612784f4845812a2899f2ca6a304fee61362db59b263115c27d2ce78af6b1d9e939c1f4036c7707851f41abe6458cf1c748353e593469ebf43536a939f7";
+pub(super) const PRUNED_TX_WITH_LONG_ENCRYPTED_AMOUNT: &str = "020001020003060101cf60390bb71aa15eb24037772012d59dc68cb4b6211e1c93206db09a6c346261020002ee8ca293511571c0005e1c144e49d09b8ff03046dbafb3e064a34cb9fc1994b600029e2e5cd08c8681dbcf2ce66071467e835f7e86613fbfed3c4fb170127b94e1072c01d3ce2a622c6e06ed465f81017dd6188c3a6e3d8e65a846f9c98416da0e150a82020901c553d35e54111bd001e0bbcbf289d701ce90e309ead2b487ec1d4d8af5d649543eb99a7620f6b54e532898527be29704f050e6f06de61e5967b2ddd506b4d6d36546065d6aae156ac7bec18c99580c07867fb98cb29853edbafec91af2df605c12f9aaa81a9165625afb6649f5a652012c5ba6612351140e1fb4a8463cc765d0a9bb7d999ba35750f365c5285d77230b76c7a612784f4845812a2899f2ca6a304fee61362db59b263115c27d2ce78af6b1d9e939c1f4036c7707851f41abe6458cf1c748353e593469ebf43536a939f7";
 
 #[rustfmt::skip]
-const BLOCK: &str = "0202e8e28efe04db09e2fc4d57854786220bd33e0169ff692440d27ae3932b9219df9ab1d7260b00000000014101ff050580d0acf30e02704972eb1878e94686b62fa4c0202f3e7e3a263073bd6edd751990ea769494ee80c0fc82aa0202edac72ab7c5745d4acaa95f76a3b76e238a55743cd51efb586f968e09821788d80d0dbc3f40202f9b4cf3141aac4203a1aaed01f09326615544997d1b68964928d9aafd07e38e580a0e5b9c29101023405e3aa75b1b7adf04e8c7faa3c3d45616ae740a8b11fb7cc1555dd8b9e4c9180c0dfda8ee90602d2b78accfe1c2ae57bed4fe3385f7735a988f160ef3bbc1f9d7a0c911c26ffd92101d2d55b5066d247a97696be4a84bf70873e4f149687f57e606eb6682f11650e1701b74773bbea995079805398052da9b69244bda034b089b50e4d9151dedb59a12f";
+pub(super) const BLOCK: &str = "0202e8e28efe04db09e2fc4d57854786220bd33e0169ff692440d27ae3932b9219df9ab1d7260b00000000014101ff050580d0acf30e02704972eb1878e94686b62fa4c0202f3e7e3a263073bd6edd751990ea769494ee80c0fc82aa0202edac72ab7c5745d4acaa95f76a3b76e238a55743cd51efb586f968e09821788d80d0dbc3f40202f9b4cf3141aac4203a1aaed01f09326615544997d1b68964928d9aafd07e38e580a0e5b9c29101023405e3aa75b1b7adf04e8c7faa3c3d45616ae740a8b11fb7cc1555dd8b9e4c9180c0dfda8ee90602d2b78accfe1c2ae57bed4fe3385f7735a988f160ef3bbc1f9d7a0c911c26ffd92101d2d55b5066d247a97696be4a84bf70873e4f149687f57e606eb6682f11650e1701b74773bbea995079805398052da9b69244bda034b089b50e4d9151dedb59a12f";
 
-const OUTPUT_INDEX_FOR_FIRST_RINGCT_OUTPUT: u64 = 0; // note the miner tx is a v1 tx
+pub(super) const OUTPUT_INDEX_FOR_FIRST_RINGCT_OUTPUT: u64 = 0; // note the miner tx is a v1 tx
 
-fn wallet_output0() -> WalletOutput {
+pub(super) fn wallet_output0() -> WalletOutput {
   WalletOutput {
     absolute_id: AbsoluteId {
       transaction: hex::decode("b74773bbea995079805398052da9b69244bda034b089b50e4d9151dedb59a12f")
@@ -69,7 +72,7 @@ fn wallet_output0() -> WalletOutput {
   }
 }
 
-fn wallet_output1() -> WalletOutput {
+pub(super) fn wallet_output1() -> WalletOutput {
   WalletOutput {
     absolute_id: AbsoluteId {
       transaction: hex::decode("b74773bbea995079805398052da9b69244bda034b089b50e4d9151dedb59a12f")
@@ -166,3 +169,21 @@ fn scan_long_encrypted_amount() {
   assert_eq!(outputs[0], wallet_output0());
   assert_eq!(outputs[1], wallet_output1());
 }
+
+#[test]
+fn scan_with_subaddresses_registered_in_bulk() {
+  // Register enough subaddresses their keys are derived across threads, including duplicates and
+  // subaddresses already registered
+  let mut scanner = Scanner::new(super::view_pair());
+  scanner.register_subaddress(SubaddressIndex::new(0, 1).unwrap());
+  let subaddresses = (0 .. 5000)
+    .map(|i| SubaddressIndex::new(i % 4, (i / 4) + 1).unwrap())
+    .chain([SubaddressIndex::new(0, 1).unwrap(), SubaddressIndex::new(1, 1).unwrap()])
+    .collect::<Vec<_>>();
+  scanner.register_subaddresses(&subaddresses);
+  scanner.register_subaddresses(&subaddresses[.. 10]);
+
+  // The outputs to the primary address should still be found
+  let outputs = super::scanned_outputs(&mut scanner);
+  assert_eq!(outputs, vec![wallet_output0(), wallet_output1()]);
+}
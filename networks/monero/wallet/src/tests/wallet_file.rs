@@ -0,0 +1,146 @@
+use rand_core::OsRng;
+
+use zeroize::Zeroizing;
+
+use crate::{
+  address::SubaddressIndex, WalletFileError, KdfParameters, HistoryEntry, WalletData, WalletFile,
+};
+
+use super::{
+  scalar, scannable_block, scanned_outputs,
+  scan::{SPEND_KEY, VIEW_KEY},
+};
+
+// Minimal parameters, as the tests would otherwise be needlessly slow
+const PARAMS: KdfParameters = KdfParameters { memory_kib: 8, iterations: 1, parallelism: 1 };
+
+fn wallet_data() -> WalletData {
+  let mut data =
+    WalletData::new(Zeroizing::new(scalar(SPEND_KEY)), Zeroizing::new(scalar(VIEW_KEY)), false);
+  data.subaddresses.push(SubaddressIndex::new(0, 1).unwrap());
+
+  let block_number = u64::try_from(scannable_block(None).block.number().unwrap()).unwrap();
+  let outputs = scanned_outputs(&mut data.scanner().unwrap().unwrap());
+  assert!(!outputs.is_empty());
+
+  data.history.push(HistoryEntry {
+    transaction: outputs[0].transaction(),
+    block_number,
+    received: outputs.iter().map(|output| output.commitment().amount).sum(),
+    spent: 0,
+  });
+  data.add_outputs(outputs);
+  assert_eq!(data.key_images.len(), data.outputs.len());
+  data.next_block = block_number + 1;
+  data
+}
+
+#[test]
+fn wallet_file() {
+  let data = wallet_data();
+  let file = WalletFile::new(&mut OsRng, b"password", PARAMS, data.clone()).unwrap();
+  let serialized = file.serialize(&mut OsRng);
+  // A fresh nonce should be used every time the wallet file is serialized
+  assert!(serialized != file.serialize(&mut OsRng));
+
+  let opened = WalletFile::read(b"password", &serialized).unwrap();
+  assert_eq!(opened.data(), &data);
+  for output in &data.outputs {
+    assert!(opened
+      .data()
+      .key_images
+      .contains(&crate::key_image(data.spend_key.as_ref().unwrap(), output)));
+  }
+  assert_eq!(
+    WalletFile::read(b"wrong password", &serialized).unwrap_err(),
+    WalletFileError::IncorrectPassword
+  );
+
+  // The header should be authenticated
+  {
+    let mut tampered = serialized.clone();
+    // Flip a bit within the salt
+    tampered[30] ^= 1;
+    assert_eq!(
+      WalletFile::read(b"password", &tampered).unwrap_err(),
+      WalletFileError::IncorrectPassword
+    );
+  }
+  // As should the ciphertext
+  {
+    let mut tampered = serialized.clone();
+    *tampered.last_mut().unwrap() ^= 1;
+    assert_eq!(
+      WalletFile::read(b"password", &tampered).unwrap_err(),
+      WalletFileError::IncorrectPassword
+    );
+  }
+  // Truncated files should be rejected
+  assert_eq!(
+    WalletFile::read(b"password", &serialized[.. 60]).unwrap_err(),
+    WalletFileError::InvalidData
+  );
+
+  // Check the magic and version
+  {
+    let mut invalid = serialized.clone();
+    invalid[0] ^= 1;
+    assert_eq!(WalletFile::read(b"password", &invalid).unwrap_err(), WalletFileError::InvalidMagic);
+    let mut invalid = serialized.clone();
+    invalid[12] = 0xff;
+    assert_eq!(
+      WalletFile::read(b"password", &invalid).unwrap_err(),
+      WalletFileError::UnsupportedVersion(0xff)
+    );
+  }
+
+  // Excessive key derivation parameters should be rejected
+  {
+    let mut invalid = serialized.clone();
+    invalid[13 .. 17].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(
+      WalletFile::read(b"password", &invalid).unwrap_err(),
+      WalletFileError::InvalidKdfParameters
+    );
+  }
+}
+
+#[test]
+fn wallet_file_change_password() {
+  let data = wallet_data();
+  let mut file = WalletFile::new(&mut OsRng, b"password", PARAMS, data.clone()).unwrap();
+  file.change_password(&mut OsRng, b"new password", PARAMS).unwrap();
+  file.data_mut().next_block += 1;
+  let serialized = file.serialize(&mut OsRng);
+
+  assert_eq!(
+    WalletFile::read(b"password", &serialized).unwrap_err(),
+    WalletFileError::IncorrectPassword
+  );
+  let opened = WalletFile::read(b"new password", &serialized).unwrap();
+  assert_eq!(opened.data().next_block, data.next_block + 1);
+}
+
+#[test]
+fn wallet_file_view_only() {
+  let data = wallet_data();
+  let view_only = WalletData::new_view_only(data.spend, data.view.clone(), false);
+  let file = WalletFile::new(&mut OsRng, b"password", PARAMS, view_only.clone()).unwrap();
+  let opened = WalletFile::read(b"password", &file.serialize(&mut OsRng)).unwrap();
+  assert_eq!(opened.data(), &view_only);
+  assert!(opened.data().spend_key.is_none());
+}
+
+#[test]
+fn wallet_file_save_open() {
+  let data = wallet_data();
+  let file = WalletFile::new(&mut OsRng, b"password", PARAMS, data.clone()).unwrap();
+
+  let path = std::env::temp_dir().join(format!("monero-wallet-file-test-{}", std::process::id()));
+  file.save(&mut OsRng, &path).unwrap();
+  let opened = WalletFile::open(&path, b"password").unwrap();
+  std::fs::remove_file(&path).unwrap();
+  assert_eq!(opened.data(), &data);
+
+  assert!(matches!(WalletFile::open(&path, b"password").unwrap_err(), WalletFileError::Io(_)));
+}
@@ -64,9 +64,13 @@ impl ViewPair {
     ))
   }
 
-  pub(crate) fn subaddress_keys(&self, index: SubaddressIndex) -> (EdwardsPoint, EdwardsPoint) {
+  pub(crate) fn subaddress_spend(&self, index: SubaddressIndex) -> EdwardsPoint {
     let scalar = self.subaddress_derivation(index);
-    let spend = self.spend + (&scalar * ED25519_BASEPOINT_TABLE);
+    self.spend + (&scalar * ED25519_BASEPOINT_TABLE)
+  }
+
+  pub(crate) fn subaddress_keys(&self, index: SubaddressIndex) -> (EdwardsPoint, EdwardsPoint) {
+    let spend = self.subaddress_spend(index);
     let view = self.view.deref() * spend;
     (spend, view)
   }
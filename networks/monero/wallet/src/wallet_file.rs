@@ -0,0 +1,513 @@
+use core::ops::Deref;
+use std::{
+  path::Path,
+  io::{self, Read, Write},
+  fs,
+};
+
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+use rand_core::{RngCore, CryptoRng};
+
+use curve25519_dalek::{constants::ED25519_BASEPOINT_TABLE, Scalar, EdwardsPoint};
+
+use argon2::{Algorithm, Version, Params, Argon2};
+use chacha20poly1305::{
+  aead::{Aead, KeyInit, Payload},
+  XChaCha20Poly1305, XNonce,
+};
+
+use monero_serai::io::*;
+
+use crate::{
+  address::SubaddressIndex, ViewPairError, ViewPair, GuaranteedViewPair, WalletOutput, Scanner,
+  GuaranteedScanner, KeyImageIndex,
+};
+
+const MAGIC: &[u8; 12] = b"monero-serai";
+// Version 2 added the key image index
+const VERSION: u8 = 2;
+
+const SALT_LEN: usize = 32;
+const NONCE_LEN: usize = 24;
+const TAG_LEN: usize = 16;
+
+// The limits on the key derivation parameters we'll accept when opening a wallet file
+// These prevent a malicious wallet file from causing us to exhaust our memory or spin for hours
+const MAX_MEMORY_KIB: u32 = 4 * 1024 * 1024;
+const MAX_ITERATIONS: u32 = 64;
+const MAX_PARALLELISM: u32 = 16;
+
+/// An error when working with a wallet file.
+#[derive(Clone, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum WalletFileError {
+  /// The file wasn't a wallet file.
+  #[error("file wasn't a wallet file")]
+  InvalidMagic,
+  /// The wallet file was of a version this library doesn't support.
+  #[error("unsupported wallet file version ({0})")]
+  UnsupportedVersion(u8),
+  /// The key derivation parameters were invalid or exceeded the supported limits.
+  #[error("invalid key derivation parameters")]
+  InvalidKdfParameters,
+  /// The password was incorrect, or the wallet file was corrupted.
+  #[error("incorrect password or corrupted wallet file")]
+  IncorrectPassword,
+  /// The decrypted wallet data was malformed.
+  #[error("malformed wallet data")]
+  InvalidData,
+  /// The wallet's keys were invalid.
+  #[error("invalid keys ({0})")]
+  InvalidKeys(ViewPairError),
+  /// An I/O error occurred when reading or writing the wallet file.
+  #[error("I/O error ({0:?})")]
+  Io(io::ErrorKind),
+}
+
+impl From<io::Error> for WalletFileError {
+  fn from(e: io::Error) -> Self {
+    WalletFileError::Io(e.kind())
+  }
+}
+
+/// The parameters for deriving the key a wallet file is encrypted with from its password.
+///
+/// The key is derived with Argon2id.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct KdfParameters {
+  /// The memory to use, in KiB.
+  pub memory_kib: u32,
+  /// The amount of iterations to perform.
+  pub iterations: u32,
+  /// The degree of parallelism.
+  pub parallelism: u32,
+}
+
+impl Default for KdfParameters {
+  /// The recommended parameters, using 64 MiB of memory over three iterations.
+  fn default() -> Self {
+    KdfParameters { memory_kib: 64 * 1024, iterations: 3, parallelism: 1 }
+  }
+}
+
+impl KdfParameters {
+  fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+    w.write_all(&self.memory_kib.to_le_bytes())?;
+    w.write_all(&self.iterations.to_le_bytes())?;
+    w.write_all(&self.parallelism.to_le_bytes())
+  }
+
+  fn read<R: Read>(r: &mut R) -> io::Result<KdfParameters> {
+    Ok(KdfParameters {
+      memory_kib: read_u32(r)?,
+      iterations: read_u32(r)?,
+      parallelism: read_u32(r)?,
+    })
+  }
+
+  fn derive_key(
+    &self,
+    password: &[u8],
+    salt: &[u8; SALT_LEN],
+  ) -> Result<Zeroizing<[u8; 32]>, WalletFileError> {
+    if (self.memory_kib > MAX_MEMORY_KIB) ||
+      (self.iterations > MAX_ITERATIONS) ||
+      (self.parallelism > MAX_PARALLELISM)
+    {
+      Err(WalletFileError::InvalidKdfParameters)?;
+    }
+    let params = Params::new(self.memory_kib, self.iterations, self.parallelism, Some(32))
+      .map_err(|_| WalletFileError::InvalidKdfParameters)?;
+    let mut key = Zeroizing::new([0; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+      .hash_password_into(password, salt, key.as_mut())
+      .map_err(|_| WalletFileError::InvalidKdfParameters)?;
+    Ok(key)
+  }
+}
+
+/// An entry in a wallet's transaction history.
+#[derive(Clone, PartialEq, Eq, Debug, Zeroize)]
+pub struct HistoryEntry {
+  /// The hash of the transaction.
+  pub transaction: [u8; 32],
+  /// The number of the block the transaction was included in.
+  pub block_number: u64,
+  /// The amount received by the wallet within this transaction.
+  pub received: u64,
+  /// The amount spent by the wallet within this transaction.
+  pub spent: u64,
+}
+
+impl HistoryEntry {
+  fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+    w.write_all(&self.transaction)?;
+    w.write_all(&self.block_number.to_le_bytes())?;
+    w.write_all(&self.received.to_le_bytes())?;
+    w.write_all(&self.spent.to_le_bytes())
+  }
+
+  fn read<R: Read>(r: &mut R) -> io::Result<HistoryEntry> {
+    Ok(HistoryEntry {
+      transaction: read_bytes(r)?,
+      block_number: read_u64(r)?,
+      received: read_u64(r)?,
+      spent: read_u64(r)?,
+    })
+  }
+}
+
+/// The contents of a wallet file.
+#[derive(Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct WalletData {
+  /// The private spend key, if this wallet is able to spend.
+  ///
+  /// If this is None, this is a view-only wallet.
+  pub spend_key: Option<Zeroizing<Scalar>>,
+  /// The public spend key.
+  pub spend: EdwardsPoint,
+  /// The private view key.
+  pub view: Zeroizing<Scalar>,
+  /// If this wallet scans for guaranteed outputs.
+  pub guaranteed: bool,
+  /// The subaddresses registered with the scanner.
+  pub subaddresses: Vec<SubaddressIndex>,
+  /// The number of the next block to scan.
+  pub next_block: u64,
+  /// The outputs owned by this wallet.
+  pub outputs: Vec<WalletOutput>,
+  /// The history of transactions involving this wallet.
+  pub history: Vec<HistoryEntry>,
+  /// The index of the key images for this wallet's outputs.
+  ///
+  /// This is populated by `add_outputs` for wallets able to spend. View-only wallets must insert
+  /// key images provided by a wallet with the private spend key.
+  pub key_images: KeyImageIndex,
+}
+
+impl core::fmt::Debug for WalletData {
+  fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+    fmt
+      .debug_struct("WalletData")
+      .field("view_only", &self.spend_key.is_none())
+      .field("spend", &hex::encode(self.spend.compress().0))
+      .field("guaranteed", &self.guaranteed)
+      .field("subaddresses", &self.subaddresses)
+      .field("next_block", &self.next_block)
+      .field("outputs", &self.outputs)
+      .field("history", &self.history)
+      .field("key_images", &self.key_images.len())
+      .finish_non_exhaustive()
+  }
+}
+
+impl WalletData {
+  /// Create the data for a new wallet able to spend.
+  pub fn new(spend_key: Zeroizing<Scalar>, view: Zeroizing<Scalar>, guaranteed: bool) -> Self {
+    let spend = spend_key.deref() * ED25519_BASEPOINT_TABLE;
+    WalletData {
+      spend_key: Some(spend_key),
+      spend,
+      view,
+      guaranteed,
+      subaddresses: vec![],
+      next_block: 0,
+      outputs: vec![],
+      history: vec![],
+      key_images: KeyImageIndex::new(),
+    }
+  }
+
+  /// Create the data for a new view-only wallet.
+  pub fn new_view_only(spend: EdwardsPoint, view: Zeroizing<Scalar>, guaranteed: bool) -> Self {
+    WalletData {
+      spend_key: None,
+      spend,
+      view,
+      guaranteed,
+      subaddresses: vec![],
+      next_block: 0,
+      outputs: vec![],
+      history: vec![],
+      key_images: KeyImageIndex::new(),
+    }
+  }
+
+  /// The ViewPair for this wallet.
+  ///
+  /// This does not consider if this wallet scans for guaranteed outputs.
+  pub fn view_pair(&self) -> Result<ViewPair, ViewPairError> {
+    ViewPair::new(self.spend, self.view.clone())
+  }
+
+  /// A Scanner for this wallet, with its subaddresses registered.
+  ///
+  /// Returns None if this wallet scans for guaranteed outputs.
+  pub fn scanner(&self) -> Result<Option<Scanner>, ViewPairError> {
+    if self.guaranteed {
+      return Ok(None);
+    }
+    let mut scanner = Scanner::new(self.view_pair()?);
+    scanner.register_subaddresses(&self.subaddresses);
+    Ok(Some(scanner))
+  }
+
+  /// A GuaranteedScanner for this wallet, with its subaddresses registered.
+  ///
+  /// Returns None if this wallet doesn't scan for guaranteed outputs.
+  pub fn guaranteed_scanner(&self) -> Result<Option<GuaranteedScanner>, ViewPairError> {
+    if !self.guaranteed {
+      return Ok(None);
+    }
+    let mut scanner =
+      GuaranteedScanner::new(GuaranteedViewPair::new(self.spend, self.view.clone())?);
+    scanner.register_subaddresses(&self.subaddresses);
+    Ok(Some(scanner))
+  }
+
+  /// Add newly-received outputs to this wallet, indexing their key images if able to.
+  pub fn add_outputs(&mut self, outputs: Vec<WalletOutput>) {
+    if let Some(spend_key) = &self.spend_key {
+      for output in &outputs {
+        self.key_images.insert_output(spend_key, output);
+      }
+    }
+    self.outputs.extend(outputs);
+  }
+
+  fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+    match &self.spend_key {
+      Some(spend_key) => {
+        w.write_all(&[1])?;
+        write_scalar(spend_key, w)?;
+      }
+      None => w.write_all(&[0])?,
+    }
+    write_point(&self.spend, w)?;
+    write_scalar(&self.view, w)?;
+    w.write_all(&[u8::from(self.guaranteed)])?;
+    write_vec(
+      |subaddress: &SubaddressIndex, w: &mut W| {
+        w.write_all(&subaddress.account().to_le_bytes())?;
+        w.write_all(&subaddress.address().to_le_bytes())
+      },
+      &self.subaddresses,
+      w,
+    )?;
+    w.write_all(&self.next_block.to_le_bytes())?;
+    write_vec(WalletOutput::write, &self.outputs, w)?;
+    write_vec(HistoryEntry::write, &self.history, w)?;
+    self.key_images.write(w)
+  }
+
+  fn read<R: Read>(r: &mut R, version: u8) -> io::Result<WalletData> {
+    let spend_key = match read_byte(r)? {
+      0 => None,
+      1 => Some(Zeroizing::new(read_scalar(r)?)),
+      _ => Err(io::Error::other("invalid spend key flag"))?,
+    };
+    let spend = read_torsion_free_point(r)?;
+    let view = Zeroizing::new(read_scalar(r)?);
+    if let Some(spend_key) = &spend_key {
+      if (spend_key.deref() * ED25519_BASEPOINT_TABLE) != spend {
+        Err(io::Error::other("spend key didn't match public spend key"))?;
+      }
+    }
+    let guaranteed = match read_byte(r)? {
+      0 => false,
+      1 => true,
+      _ => Err(io::Error::other("invalid guaranteed flag"))?,
+    };
+    let subaddresses = read_vec(
+      |r| {
+        SubaddressIndex::new(read_u32(r)?, read_u32(r)?)
+          .ok_or_else(|| io::Error::other("invalid subaddress index"))
+      },
+      r,
+    )?;
+    let next_block = read_u64(r)?;
+    let outputs = read_vec(WalletOutput::read, r)?;
+    let history = read_vec(HistoryEntry::read, r)?;
+    let key_images = if version >= 2 {
+      KeyImageIndex::read(r)?
+    } else {
+      // Prior versions didn't persist the key image index, so rebuild it if we're able to
+      let mut key_images = KeyImageIndex::new();
+      if let Some(spend_key) = &spend_key {
+        for output in &outputs {
+          key_images.insert_output(spend_key, output);
+        }
+      }
+      key_images
+    };
+    Ok(WalletData {
+      spend_key,
+      spend,
+      view,
+      guaranteed,
+      subaddresses,
+      next_block,
+      outputs,
+      history,
+      key_images,
+    })
+  }
+}
+
+/// An encrypted, versioned wallet file.
+///
+/// The file is composed of a header (magic, version, key derivation parameters, salt, and nonce)
+/// followed by the wallet data, encrypted with XChaCha20-Poly1305 under a key derived from the
+/// password with Argon2id. The header is authenticated as associated data.
+///
+/// A fresh nonce is used whenever the wallet file is saved. The salt is only changed when the
+/// password is.
+pub struct WalletFile {
+  params: KdfParameters,
+  salt: [u8; SALT_LEN],
+  key: Zeroizing<[u8; 32]>,
+  data: WalletData,
+}
+
+impl core::fmt::Debug for WalletFile {
+  fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+    fmt
+      .debug_struct("WalletFile")
+      .field("params", &self.params)
+      .field("data", &self.data)
+      .finish_non_exhaustive()
+  }
+}
+
+impl WalletFile {
+  /// Create a new wallet file, encrypted under the specified password.
+  pub fn new<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    password: &[u8],
+    params: KdfParameters,
+    data: WalletData,
+  ) -> Result<WalletFile, WalletFileError> {
+    let mut salt = [0; SALT_LEN];
+    rng.fill_bytes(&mut salt);
+    let key = params.derive_key(password, &salt)?;
+    Ok(WalletFile { params, salt, key, data })
+  }
+
+  /// The wallet's data.
+  pub fn data(&self) -> &WalletData {
+    &self.data
+  }
+
+  /// A mutable reference to the wallet's data.
+  ///
+  /// Modifications are only persisted once the wallet file is saved.
+  pub fn data_mut(&mut self) -> &mut WalletData {
+    &mut self.data
+  }
+
+  /// Change the password the wallet file is encrypted under.
+  ///
+  /// This also changes the salt and key derivation parameters. The change is only persisted once
+  /// the wallet file is saved.
+  pub fn change_password<R: RngCore + CryptoRng>(
+    &mut self,
+    rng: &mut R,
+    password: &[u8],
+    params: KdfParameters,
+  ) -> Result<(), WalletFileError> {
+    let mut salt = [0; SALT_LEN];
+    rng.fill_bytes(&mut salt);
+    self.key = params.derive_key(password, &salt)?;
+    self.params = params;
+    self.salt = salt;
+    Ok(())
+  }
+
+  fn header(params: KdfParameters, salt: &[u8; SALT_LEN], nonce: &[u8; NONCE_LEN]) -> Vec<u8> {
+    let mut header = MAGIC.to_vec();
+    header.push(VERSION);
+    params.write(&mut header).unwrap();
+    header.extend(salt);
+    header.extend(nonce);
+    header
+  }
+
+  /// Serialize and encrypt the wallet file.
+  pub fn serialize<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Vec<u8> {
+    let mut nonce = [0; NONCE_LEN];
+    rng.fill_bytes(&mut nonce);
+    let mut res = Self::header(self.params, &self.salt, &nonce);
+
+    let mut plaintext = Zeroizing::new(vec![]);
+    self.data.write(&mut *plaintext).unwrap();
+    let ciphertext = XChaCha20Poly1305::new(self.key.deref().into())
+      .encrypt(XNonce::from_slice(&nonce), Payload { msg: &plaintext, aad: &res })
+      .expect("couldn't encrypt the wallet data");
+    res.extend(ciphertext);
+    res
+  }
+
+  /// Decrypt and deserialize a wallet file.
+  pub fn read(password: &[u8], file: &[u8]) -> Result<WalletFile, WalletFileError> {
+    let mut r = file;
+    let magic =
+      read_bytes::<_, { MAGIC.len() }>(&mut r).map_err(|_| WalletFileError::InvalidMagic)?;
+    if &magic != MAGIC {
+      Err(WalletFileError::InvalidMagic)?;
+    }
+    let version = read_byte(&mut r).map_err(|_| WalletFileError::InvalidData)?;
+    if !(1 ..= VERSION).contains(&version) {
+      Err(WalletFileError::UnsupportedVersion(version))?;
+    }
+    let params = KdfParameters::read(&mut r).map_err(|_| WalletFileError::InvalidData)?;
+    let salt = read_bytes::<_, SALT_LEN>(&mut r).map_err(|_| WalletFileError::InvalidData)?;
+    let nonce = read_bytes::<_, NONCE_LEN>(&mut r).map_err(|_| WalletFileError::InvalidData)?;
+    if r.len() < TAG_LEN {
+      Err(WalletFileError::InvalidData)?;
+    }
+    let header = &file[.. (file.len() - r.len())];
+
+    let key = params.derive_key(password, &salt)?;
+    let plaintext = Zeroizing::new(
+      XChaCha20Poly1305::new(key.deref().into())
+        .decrypt(XNonce::from_slice(&nonce), Payload { msg: r, aad: header })
+        .map_err(|_| WalletFileError::IncorrectPassword)?,
+    );
+
+    let mut plaintext_ref = plaintext.as_slice();
+    let data =
+      WalletData::read(&mut plaintext_ref, version).map_err(|_| WalletFileError::InvalidData)?;
+    if !plaintext_ref.is_empty() {
+      Err(WalletFileError::InvalidData)?;
+    }
+    data.view_pair().map_err(WalletFileError::InvalidKeys)?;
+
+    Ok(WalletFile { params, salt, key, data })
+  }
+
+  /// Open the wallet file at the specified path.
+  pub fn open(path: impl AsRef<Path>, password: &[u8]) -> Result<WalletFile, WalletFileError> {
+    Self::read(password, &fs::read(path)?)
+  }
+
+  /// Save the wallet file to the specified path.
+  ///
+  /// The wallet file is written to a temporary file which is then renamed, so an existing wallet
+  /// file is never left partially written.
+  pub fn save<R: RngCore + CryptoRng>(
+    &self,
+    rng: &mut R,
+    path: impl AsRef<Path>,
+  ) -> Result<(), WalletFileError> {
+    let path = path.as_ref();
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+
+    {
+      let mut file = fs::File::create(&tmp_path)?;
+      file.write_all(&self.serialize(rng))?;
+      file.sync_all()?;
+    }
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+  }
+}
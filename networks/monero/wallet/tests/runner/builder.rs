@@ -5,7 +5,7 @@ use monero_wallet::{
   rpc::FeeRate,
   address::MoneroAddress,
   OutputWithDecoys,
-  send::{Change, SendError, SignableTransaction},
+  send::{Change, SendError, SignableTransaction, TransactionShape, PayoutBatch, CoinControl},
   extra::MAX_ARBITRARY_DATA_SIZE,
 };
 
@@ -79,4 +79,51 @@ impl SignableTransactionBuilder {
       self.fee_rate,
     )
   }
+
+  #[allow(unused)]
+  pub fn build_with_shape(
+    self,
+    shape: TransactionShape,
+  ) -> Result<(SignableTransaction, u64), SendError> {
+    SignableTransaction::new_with_shape(
+      self.rct_type,
+      self.outgoing_view_key,
+      self.inputs,
+      self.payments,
+      self.change,
+      self.data,
+      self.fee_rate,
+      shape,
+    )
+  }
+
+  #[allow(unused)]
+  pub fn payout_batch(self) -> PayoutBatch {
+    let mut batch =
+      PayoutBatch::new(self.rct_type, self.outgoing_view_key, self.change, self.fee_rate);
+    for input in self.inputs {
+      batch.add_input(input);
+    }
+    for (address, amount) in self.payments {
+      batch.add_payout(address, amount);
+    }
+    batch
+  }
+
+  /// Create coin control for this transaction, with every input received in the specified block.
+  #[allow(unused)]
+  pub fn coin_control(self, block_number: usize) -> CoinControl {
+    let mut coin_control =
+      CoinControl::new(self.rct_type, self.outgoing_view_key, self.change, self.fee_rate);
+    for input in self.inputs {
+      coin_control.add_input(input, block_number);
+    }
+    for (address, amount) in self.payments {
+      coin_control.add_payment(address, amount);
+    }
+    for data in self.data {
+      coin_control.add_data(data);
+    }
+    coin_control
+  }
 }
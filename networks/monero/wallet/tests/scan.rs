@@ -158,3 +158,37 @@ test!(
     },
   ),
 );
+
+test!(
+  scan_guaranteed_integrated_subaddress_with_additional_keys,
+  (
+    |_, mut builder: Builder, _| async move {
+      let subaddress = SubaddressIndex::new(0, 4).unwrap();
+      let other_subaddress = SubaddressIndex::new(1, 1).unwrap();
+
+      let view = runner::random_guaranteed_address().1;
+      let mut scanner = GuaranteedScanner::new(view.clone());
+      scanner.register_subaddress(subaddress);
+      scanner.register_subaddress(other_subaddress);
+
+      let mut payment_id = [0u8; 8];
+      OsRng.fill_bytes(&mut payment_id);
+
+      // Paying multiple subaddresses causes the usage of additional keys, with the payment ID
+      // encrypted using the additional key for its output
+      builder.add_payment(view.address(Network::Mainnet, Some(subaddress), Some(payment_id)), 5);
+      builder.add_payment(view.address(Network::Mainnet, Some(other_subaddress), None), 6);
+      (builder.build().unwrap(), (scanner, payment_id, subaddress))
+    },
+    |_rpc, block, tx: Tx, _, mut state: (GuaranteedScanner, [u8; 8], SubaddressIndex)| async move {
+      let mut outputs = state.0.scan(block).unwrap().not_additionally_locked();
+      assert_eq!(outputs.len(), 2);
+      outputs.sort_by_key(|output| output.commitment().amount);
+      let output = &outputs[0];
+      assert_eq!(output.transaction(), tx.hash());
+      assert_eq!(output.commitment().amount, 5);
+      assert_eq!(output.payment_id(), Some(PaymentId::Encrypted(state.1)));
+      assert_eq!(output.subaddress(), Some(state.2));
+    },
+  ),
+);
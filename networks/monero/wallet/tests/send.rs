@@ -294,6 +294,75 @@ test!(
   ),
 );
 
+test!(
+  spend_max_distinct_subaddresses_with_additional_keys,
+  (
+    |_, mut builder: Builder, addr| async move {
+      builder.add_payment(addr, 1000000000000);
+      (builder.build().unwrap(), ())
+    },
+    |_rpc: SimpleRequestRpc, block, tx: Transaction, mut scanner: Scanner, ()| async move {
+      let outputs = scanner.scan(block).unwrap().not_additionally_locked();
+      assert_eq!(outputs.len(), 1);
+      assert_eq!(outputs[0].transaction(), tx.hash());
+      assert_eq!(outputs[0].commitment().amount, 1000000000000);
+      outputs
+    },
+  ),
+  (
+    |rct_type, rpc: SimpleRequestRpc, _, _, outputs: Vec<WalletOutput>| async move {
+      use monero_wallet::rpc::FeePriority;
+
+      let mut outgoing_view = Zeroizing::new([0; 32]);
+      OsRng.fill_bytes(outgoing_view.as_mut());
+      // Without change, every output may be a payment
+      let mut builder = SignableTransactionBuilder::new(
+        rct_type,
+        outgoing_view,
+        Change::fingerprintable(None),
+        rpc.get_fee_rate(FeePriority::Unimportant).await.unwrap(),
+      );
+      add_inputs(rct_type, &rpc, outputs, &mut builder).await;
+
+      let view = runner::random_address().1;
+      let mut scanner = Scanner::new(view.clone());
+
+      // Pay the maximum amount of distinct subaddresses, across multiple accounts
+      let mut subaddresses = vec![];
+      for i in 0 .. 16 {
+        let subaddress = SubaddressIndex::new(i % 2, (i / 2) + 1).unwrap();
+        scanner.register_subaddress(subaddress);
+        builder.add_payment(view.subaddress(Network::Mainnet, subaddress), u64::from(i + 1));
+        subaddresses.push(subaddress);
+      }
+
+      (builder.build().unwrap(), (scanner, subaddresses))
+    },
+    |_rpc: SimpleRequestRpc,
+     block,
+     tx: Transaction,
+     _,
+     mut state: (Scanner, Vec<SubaddressIndex>)| async move {
+      // Each output should have its own additional key
+      let extra = Extra::read::<&[u8]>(&mut tx.prefix().extra.as_ref()).unwrap();
+      let (_, additional_keys) = extra.keys().unwrap();
+      assert_eq!(additional_keys.unwrap().len(), 16);
+      assert_eq!(tx.prefix().outputs.len(), 16);
+
+      // The outputs are only scannable with their additional keys, as the subaddresses' view keys
+      // aren't used by the transaction key
+      let mut outputs = state.0.scan(block).unwrap().not_additionally_locked();
+      assert_eq!(outputs.len(), 16);
+      outputs.sort_by_key(|output| output.commitment().amount);
+      for (i, output) in outputs.iter().enumerate() {
+        assert_eq!(output.transaction(), tx.hash());
+        assert_eq!(output.commitment().amount, u64::try_from(i + 1).unwrap());
+        assert_eq!(output.subaddress(), Some(state.1[i]));
+      }
+    },
+  ),
+);
+
 test!(
   spend_one_input_to_two_outputs_no_change,
   (
@@ -399,3 +468,144 @@ test!(
     },
   ),
 );
+
+test!(
+  spend_with_uniform_shape,
+  (
+    |_, mut builder: Builder, addr| async move {
+      use monero_wallet::send::{SendError, TransactionShape};
+
+      builder.add_payment(addr, 5);
+      // The amount of inputs isn't padded
+      assert!(matches!(
+        builder.clone().build_with_shape(TransactionShape::new(2, 4).unwrap()),
+        Err(SendError::InvalidShape)
+      ));
+      let (tx, padding_fee) =
+        builder.build_with_shape(TransactionShape::new(1, 4).unwrap()).unwrap();
+      assert!(padding_fee > 0);
+      (tx, ())
+    },
+    |_rpc: SimpleRequestRpc, block, tx: Transaction, mut scanner: Scanner, ()| async move {
+      assert_eq!(tx.prefix().outputs.len(), 4);
+      let outputs = scanner.scan(block).unwrap().not_additionally_locked();
+      assert_eq!(outputs.len(), 1);
+      assert_eq!(outputs[0].transaction(), tx.hash());
+      assert_eq!(outputs[0].commitment().amount, 5);
+    },
+  ),
+);
+
+test!(
+  spend_payout_batch,
+  (
+    |_, mut builder: Builder, addr| async move {
+      for _ in 0 .. 3 {
+        builder.add_payment(addr, 1000000000000);
+      }
+      (builder.build().unwrap(), ())
+    },
+    |_rpc: SimpleRequestRpc, block, tx: Transaction, mut scanner: Scanner, ()| async move {
+      let outputs = scanner.scan(block).unwrap().not_additionally_locked();
+      assert_eq!(outputs.len(), 3);
+      assert!(outputs.iter().all(|output| output.transaction() == tx.hash()));
+      outputs
+    },
+  ),
+  (
+    |rct_type: RctType, rpc, mut builder: Builder, addr, outputs: Vec<WalletOutput>| async move {
+      add_inputs(rct_type, &rpc, outputs, &mut builder).await;
+      for i in 0 .. 20 {
+        builder.add_payment(addr, 10000000000 + i);
+      }
+      let batch = builder.payout_batch();
+
+      // The same batch should produce the same plan
+      let txs = batch.clone().build().unwrap();
+      assert_eq!(txs, batch.build().unwrap());
+
+      // 20 payouts require two transactions, each spending a single 1 XMR input
+      assert_eq!(txs.len(), 2);
+      (txs[0].clone(), ())
+    },
+    |_rpc: SimpleRequestRpc, block, tx: Transaction, mut scanner: Scanner, ()| async move {
+      // 15 payouts and the change
+      assert_eq!(tx.prefix().outputs.len(), 16);
+      let mut outputs = scanner.scan(block).unwrap().not_additionally_locked();
+      assert_eq!(outputs.len(), 15);
+      outputs.sort_by(|x, y| x.commitment().amount.cmp(&y.commitment().amount));
+      for (i, output) in outputs.iter().enumerate() {
+        assert_eq!(output.transaction(), tx.hash());
+        assert_eq!(output.commitment().amount, 10000000000 + u64::try_from(i).unwrap());
+      }
+    },
+  ),
+);
+
+test!(
+  spend_with_coin_control,
+  (
+    |_, mut builder: Builder, addr| async move {
+      builder.add_payment(addr, 1000000000000);
+      builder.add_payment(addr, 2000000000000);
+      builder.add_payment(addr, 3000000000000);
+      (builder.build().unwrap(), ())
+    },
+    |_rpc: SimpleRequestRpc, block, tx: Transaction, mut scanner: Scanner, ()| async move {
+      let outputs = scanner.scan(block).unwrap().not_additionally_locked();
+      assert_eq!(outputs.len(), 3);
+      assert!(outputs.iter().all(|output| output.transaction() == tx.hash()));
+      outputs
+    },
+  ),
+  (
+    |rct_type: RctType, rpc, mut builder: Builder, addr, outputs: Vec<WalletOutput>| async move {
+      use monero_wallet::send::CoinControlError;
+
+      let mut outputs = outputs;
+      outputs.sort_by(|x, y| x.commitment().amount.cmp(&y.commitment().amount));
+      let smallest = outputs[0].key();
+      let largest = outputs[2].key();
+      add_inputs(rct_type, &rpc, outputs, &mut builder).await;
+      builder.add_payment(addr, 2500000000000);
+
+      // Treat every input as received in the latest block
+      let latest = rpc.get_height().await.unwrap() - 1;
+      let coin_control = builder.coin_control(latest);
+
+      // With every input insufficiently confirmed, nothing is spendable
+      let mut unconfirmed = coin_control.clone();
+      unconfirmed.exclude_unconfirmed(latest, 2);
+      assert!(matches!(
+        unconfirmed.build(),
+        Err(CoinControlError::NotEnoughFunds { spendable: 0, excluded: 6000000000000, .. })
+      ));
+
+      // The smallest input alone doesn't suffice
+      let mut only_smallest = coin_control.clone();
+      only_smallest.require_input(smallest).only_required_inputs();
+      assert!(matches!(
+        only_smallest.build(),
+        Err(CoinControlError::NotEnoughFunds { spendable: 1000000000000, .. })
+      ));
+
+      // A required input can't also be locked
+      let mut contradictory = coin_control.clone();
+      contradictory.require_input(largest).lock_input(largest);
+      assert_eq!(contradictory.build(), Err(CoinControlError::RequiredInputLocked));
+
+      // Requiring the smallest input and locking the largest should spend the remaining input
+      let mut coin_control = coin_control;
+      coin_control.require_input(smallest).lock_input(largest);
+      let tx = coin_control.clone().build().unwrap();
+      // The same constraints should produce the same transaction
+      assert_eq!(tx, coin_control.build().unwrap());
+      (tx, ())
+    },
+    |_rpc: SimpleRequestRpc, block, tx: Transaction, mut scanner: Scanner, ()| async move {
+      assert_eq!(tx.prefix().inputs.len(), 2);
+      let outputs = scanner.scan(block).unwrap().not_additionally_locked();
+      assert!(outputs.iter().any(|output| output.commitment().amount == 2500000000000));
+    },
+  ),
+);
@@ -0,0 +1,202 @@
+// Differential tests of our scanning against wallet2's, as exposed by monero-wallet-rpc
+//
+// These create a view-only wallet within monero-wallet-rpc for the same keys we scan with, send
+// to it with transactions we construct, and check both wallets found the same outputs with the
+// same amounts, subaddresses, and payment IDs.
+
+use std::collections::BTreeMap;
+
+use zeroize::Zeroizing;
+use rand_core::{OsRng, RngCore};
+
+use curve25519_dalek::{constants::ED25519_BASEPOINT_TABLE, scalar::Scalar};
+
+use serde::Deserialize;
+use serde_json::json;
+
+use monero_simple_request_rpc::SimpleRequestRpc;
+use monero_wallet::{
+  transaction::Transaction,
+  rpc::{Rpc, ScannableBlock},
+  address::{Network, SubaddressIndex},
+  extra::PaymentId,
+  ViewPair, Scanner,
+};
+
+mod runner;
+
+// A received output, keyed by the transaction and subaddress which received it
+//
+// wallet2 aggregates the amount received by a transaction per-subaddress, so we do the same
+type Received = BTreeMap<(String, (u32, u32)), (u64, String)>;
+
+#[derive(Deserialize, Debug)]
+struct EmptyResponse {}
+
+#[derive(Debug, Deserialize)]
+struct Index {
+  major: u32,
+  minor: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct Transfer {
+  txid: String,
+  amount: u64,
+  payment_id: String,
+  subaddr_index: Index,
+}
+
+#[derive(Debug, Deserialize)]
+struct TransfersResponse {
+  #[serde(rename = "in", default)]
+  incoming: Vec<Transfer>,
+}
+
+struct DifferentialWallet {
+  wallet_rpc: SimpleRequestRpc,
+  view: ViewPair,
+  subaddresses: Vec<SubaddressIndex>,
+}
+
+impl DifferentialWallet {
+  // Create a fresh wallet, opening a view-only copy of it within monero-wallet-rpc
+  async fn new(rpc: &SimpleRequestRpc, subaddresses: Vec<SubaddressIndex>) -> Self {
+    let wallet_rpc = SimpleRequestRpc::new("http://127.0.0.1:18082".to_string()).await.unwrap();
+
+    let spend = &Scalar::random(&mut OsRng) * ED25519_BASEPOINT_TABLE;
+    let view_key = Zeroizing::new(Scalar::random(&mut OsRng));
+    let view = ViewPair::new(spend, view_key.clone()).unwrap();
+
+    let mut wallet_id = [0; 8];
+    OsRng.fill_bytes(&mut wallet_id);
+    let _: serde_json::Value = wallet_rpc
+      .json_rpc_call(
+        "generate_from_keys",
+        Some(json!({
+          "filename": hex::encode(wallet_id),
+          "address": view.legacy_address(Network::Mainnet).to_string(),
+          "viewkey": hex::encode(view_key.to_bytes()),
+          "password": "",
+          "restore_height": rpc.get_height().await.unwrap().saturating_sub(1),
+        })),
+      )
+      .await
+      .unwrap();
+
+    DifferentialWallet { wallet_rpc, view, subaddresses }
+  }
+
+  // The outputs we found within this block
+  fn scan(&self, block: ScannableBlock) -> Received {
+    let mut scanner = Scanner::new(self.view.clone());
+    for subaddress in &self.subaddresses {
+      scanner.register_subaddress(*subaddress);
+    }
+
+    let mut received = Received::new();
+    for output in scanner.scan(block).unwrap().ignore_additional_timelock() {
+      let subaddress =
+        output.subaddress().map_or((0, 0), |index| (index.account(), index.address()));
+      let payment_id = match output.payment_id() {
+        Some(PaymentId::Encrypted(id)) => hex::encode(id),
+        Some(PaymentId::Unencrypted(id)) => hex::encode(id),
+        None => hex::encode([0; 8]),
+      };
+      let entry = received
+        .entry((hex::encode(output.transaction()), subaddress))
+        .or_insert((0, payment_id.clone()));
+      entry.0 += output.commitment().amount;
+      assert_eq!(entry.1, payment_id, "outputs in the same TX had distinct payment IDs");
+    }
+    received
+  }
+
+  // The outputs monero-wallet-rpc found within this transaction
+  async fn wallet2_received(&self, tx: &Transaction) -> Received {
+    let _: EmptyResponse = self.wallet_rpc.json_rpc_call("refresh", None).await.unwrap();
+    let transfers: TransfersResponse = self
+      .wallet_rpc
+      .json_rpc_call("get_transfers", Some(json!({ "in": true, "all_accounts": true })))
+      .await
+      .unwrap();
+
+    let tx_hash = hex::encode(tx.hash());
+    let mut received = Received::new();
+    for transfer in transfers.incoming.into_iter().filter(|transfer| transfer.txid == tx_hash) {
+      let subaddress = (transfer.subaddr_index.major, transfer.subaddr_index.minor);
+      assert!(
+        received
+          .insert((transfer.txid, subaddress), (transfer.amount, transfer.payment_id))
+          .is_none(),
+        "wallet2 reported multiple transfers to the same subaddress in the same TX"
+      );
+    }
+    received
+  }
+
+  async fn check(&self, block: ScannableBlock, tx: &Transaction) {
+    let ours = self.scan(block);
+    assert!(!ours.is_empty());
+    assert_eq!(ours, self.wallet2_received(tx).await);
+  }
+}
+
+test!(
+  wallet2_differential_standard_and_subaddresses,
+  (
+    |rpc: SimpleRequestRpc, mut builder: Builder, _| async move {
+      let subaddresses =
+        vec![SubaddressIndex::new(0, 1).unwrap(), SubaddressIndex::new(1, 2).unwrap()];
+      let wallet = DifferentialWallet::new(&rpc, subaddresses.clone()).await;
+
+      builder.add_payment(wallet.view.legacy_address(Network::Mainnet), 1_000_000);
+      builder.add_payment(wallet.view.subaddress(Network::Mainnet, subaddresses[0]), 2_000_000);
+      builder.add_payment(wallet.view.subaddress(Network::Mainnet, subaddresses[1]), 3_000_000);
+      (builder.build().unwrap(), wallet)
+    },
+    |_, block, tx: Transaction, _, wallet: DifferentialWallet| async move {
+      wallet.check(block, &tx).await;
+    },
+  ),
+);
+
+test!(
+  wallet2_differential_multiple_outputs_to_subaddress,
+  (
+    |rpc: SimpleRequestRpc, mut builder: Builder, _| async move {
+      let subaddress = SubaddressIndex::new(0, 3).unwrap();
+      let wallet = DifferentialWallet::new(&rpc, vec![subaddress]).await;
+
+      let addr = wallet.view.subaddress(Network::Mainnet, subaddress);
+      builder.add_payments(&[(addr, 1_000_000), (addr, 2_000_000)]);
+      (builder.build().unwrap(), wallet)
+    },
+    |_, block, tx: Transaction, _, wallet: DifferentialWallet| async move {
+      wallet.check(block, &tx).await;
+    },
+  ),
+);
+
+test!(
+  wallet2_differential_integrated,
+  (
+    |rpc: SimpleRequestRpc, mut builder: Builder, _| async move {
+      let wallet = DifferentialWallet::new(&rpc, vec![]).await;
+
+      let mut payment_id = [0u8; 8];
+      OsRng.fill_bytes(&mut payment_id);
+      builder.add_payment(
+        wallet.view.legacy_integrated_address(Network::Mainnet, payment_id),
+        1_000_000,
+      );
+      (builder.build().unwrap(), (wallet, payment_id))
+    },
+    |_, block, tx: Transaction, _, data: (DifferentialWallet, [u8; 8])| async move {
+      let (wallet, payment_id) = data;
+      let ours = wallet.scan(block.clone());
+      assert!(ours.values().all(|(_, id)| *id == hex::encode(payment_id)));
+      wallet.check(block, &tx).await;
+    },
+  ),
+);
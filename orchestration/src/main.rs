@@ -516,8 +516,8 @@ fn start(network: Network, services: HashSet<String>) {
           if network == Network::Dev {
             command
           } else {
-            // Publish the port
-            command.arg("-p").arg("30563:30563")
+            // Publish the port, for both TCP and QUIC
+            command.arg("-p").arg("30563:30563").arg("-p").arg("30563:30563/udp")
           }
         }
         "serai" => {
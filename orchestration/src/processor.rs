@@ -27,7 +27,7 @@ RUN svm use 0.8.25
         ""
       },
       network.release(),
-      &format!("binaries {} {coin}", network.db()),
+      &format!("binaries telemetry {} {coin}", network.db()),
       "serai-processor",
     );
 
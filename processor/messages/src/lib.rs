@@ -148,19 +148,53 @@ pub mod sign {
     Share { id: SignId, shares: Vec<Vec<u8>> },
     // Completed a signing protocol already.
     Completed { session: Session, id: [u8; 32], tx: Vec<u8> },
+    // The signing protocol can no longer be completed, as the key it was for was superseded.
+    Invalidated { session: Session, id: [u8; 32] },
   }
 }
 
 pub mod coordinator {
   use super::*;
 
-  pub fn cosign_block_msg(block_number: u64, block: [u8; 32]) -> Vec<u8> {
-    const DST: &[u8] = b"Cosign";
-    let mut res = vec![u8::try_from(DST.len()).unwrap()];
-    res.extend(DST);
-    res.extend(block_number.to_le_bytes());
-    res.extend(block);
-    res
+  /// The payload signed to cosign a block.
+  ///
+  /// This is the canonical encoding of the message signed for a cosign, used by both the
+  /// processors signing cosigns and the coordinators verifying them.
+  #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+  pub struct CosignPayload {
+    /// The hash of the block the cosigning validator set's session began with.
+    ///
+    /// This binds the cosign to the session producing it, so it can't be replayed onto a distinct
+    /// chain which happens to have a session with the same ID. This is None for legacy cosigns,
+    /// which weren't bound to a session.
+    pub session_start: Option<[u8; 32]>,
+    /// The number of the block being cosigned.
+    pub block_number: u64,
+    /// The hash of the block being cosigned.
+    pub block: [u8; 32],
+  }
+
+  impl CosignPayload {
+    /// The message to sign.
+    pub fn message(&self) -> Vec<u8> {
+      // The distinct DSTs, which are length-prefixed, ensure the legacy message and the session-
+      // bound message never collide
+      const LEGACY_DST: &[u8] = b"Cosign";
+      const DST: &[u8] = b"Cosign-V1";
+      let dst = if self.session_start.is_some() { DST } else { LEGACY_DST };
+      let mut res = vec![u8::try_from(dst.len()).unwrap()];
+      res.extend(dst);
+      if let Some(session_start) = self.session_start {
+        res.extend(session_start);
+      }
+      res.extend(self.block_number.to_le_bytes());
+      res.extend(self.block);
+      res
+    }
+  }
+
+  pub fn cosign_block_msg(session_start: [u8; 32], block_number: u64, block: [u8; 32]) -> Vec<u8> {
+    CosignPayload { session_start: Some(session_start), block_number, block }.message()
   }
 
   #[derive(
@@ -181,7 +215,7 @@ pub mod coordinator {
 
   #[derive(Clone, PartialEq, Eq, Debug, BorshSerialize, BorshDeserialize)]
   pub enum CoordinatorMessage {
-    CosignSubstrateBlock { id: SubstrateSignId, block_number: u64 },
+    CosignSubstrateBlock { id: SubstrateSignId, session_start: [u8; 32], block_number: u64 },
     SignSlashReport { id: SubstrateSignId, report: Vec<([u8; 32], u32)> },
     SubstratePreprocesses { id: SubstrateSignId, preprocesses: HashMap<Participant, [u8; 64]> },
     SubstrateShares { id: SubstrateSignId, shares: HashMap<Participant, [u8; 32]> },
@@ -207,15 +241,42 @@ pub mod coordinator {
 
   #[derive(Clone, PartialEq, Eq, Debug, BorshSerialize, BorshDeserialize)]
   pub enum ProcessorMessage {
-    SubstrateBlockAck { block: u64, plans: Vec<PlanMeta> },
-    InvalidParticipant { id: SubstrateSignId, participant: Participant },
-    CosignPreprocess { id: SubstrateSignId, preprocesses: Vec<[u8; 64]> },
-    BatchPreprocess { id: SubstrateSignId, block: BlockHash, preprocesses: Vec<[u8; 64]> },
-    SlashReportPreprocess { id: SubstrateSignId, preprocesses: Vec<[u8; 64]> },
-    SubstrateShare { id: SubstrateSignId, shares: Vec<[u8; 32]> },
+    SubstrateBlockAck {
+      block: u64,
+      plans: Vec<PlanMeta>,
+    },
+    InvalidParticipant {
+      id: SubstrateSignId,
+      participant: Participant,
+    },
+    CosignPreprocess {
+      id: SubstrateSignId,
+      preprocesses: Vec<[u8; 64]>,
+    },
+    BatchPreprocess {
+      id: SubstrateSignId,
+      block: BlockHash,
+      preprocesses: Vec<[u8; 64]>,
+    },
+    SlashReportPreprocess {
+      id: SubstrateSignId,
+      preprocesses: Vec<[u8; 64]>,
+    },
+    SubstrateShare {
+      id: SubstrateSignId,
+      shares: Vec<[u8; 32]>,
+    },
     // TODO: Make these signatures [u8; 64]?
-    CosignedBlock { block_number: u64, block: [u8; 32], signature: Vec<u8> },
-    SignedSlashReport { session: Session, signature: Vec<u8> },
+    CosignedBlock {
+      session_start: [u8; 32],
+      block_number: u64,
+      block: [u8; 32],
+      signature: Vec<u8>,
+    },
+    SignedSlashReport {
+      session: Session,
+      signature: Vec<u8>,
+    },
   }
 }
 
@@ -235,6 +296,14 @@ pub mod substrate {
       burns: Vec<OutInstructionWithBalance>,
       batches: Vec<u32>,
     },
+    // Serai halted this network as of this block, and we should stop publishing transactions.
+    Halt {
+      block: u64,
+    },
+    // Serai resumed this network as of this block.
+    Resume {
+      block: u64,
+    },
   }
 
   impl CoordinatorMessage {
@@ -242,6 +311,7 @@ pub mod substrate {
       let context = match self {
         CoordinatorMessage::ConfirmKeyPair { context, .. } |
         CoordinatorMessage::SubstrateBlock { context, .. } => context,
+        CoordinatorMessage::Halt { .. } | CoordinatorMessage::Resume { .. } => return None,
       };
       Some(context.network_latest_finalized_block)
     }
@@ -379,6 +449,9 @@ impl CoordinatorMessage {
           // Unique since there's only one key pair for a session
           substrate::CoordinatorMessage::ConfirmKeyPair { session, .. } => (0, session.encode()),
           substrate::CoordinatorMessage::SubstrateBlock { block, .. } => (1, block.encode()),
+          // Unique since a network can only be halted/resumed once per block
+          substrate::CoordinatorMessage::Halt { block } => (2, block.encode()),
+          substrate::CoordinatorMessage::Resume { block } => (3, block.encode()),
         };
 
         let mut res = vec![COORDINATOR_UID, TYPE_SUBSTRATE_UID, sub];
@@ -421,6 +494,8 @@ impl ProcessorMessage {
           sign::ProcessorMessage::Share { id, .. } => (2, id.encode()),
           // Unique since a processor will only sign a TX once
           sign::ProcessorMessage::Completed { id, .. } => (3, id.to_vec()),
+          // Unique since a plan is only invalidated once
+          sign::ProcessorMessage::Invalidated { id, .. } => (4, id.to_vec()),
         };
 
         let mut res = vec![PROCESSOR_UID, TYPE_SIGN_UID, sub];
@@ -0,0 +1,190 @@
+use core::{
+  num::{NonZeroU32, NonZeroU64},
+  time::Duration,
+};
+use std::{sync::Arc, time::Instant, collections::HashSet};
+
+use ciphersuite::{Ciphersuite, Secp256k1};
+
+use ethereum_serai::{
+  alloy::{
+    rpc_types::{BlockTransactionsKind, BlockNumberOrTag},
+    simple_request_transport::SimpleRequest,
+    rpc_client::ClientBuilder,
+    provider::{Provider, RootProvider},
+  },
+  crypto::PublicKey,
+  deployer::Deployer,
+  router::Router,
+};
+
+use serai_client::validator_sets::primitives::Session;
+
+use tokio::time::sleep;
+
+use crate::{
+  Db, DbTxn,
+  key_gen::NetworkKeyDb,
+  networks::ethereum::{
+    DAI, ChainIdDb, BackfilledThroughEpochDb, BackfilledEpochDb, verify_chain_id,
+  },
+};
+
+/*
+  A backfill of the Router's history, intended to be run when standing up a processor from scratch
+  (such as one replacing a prior node) before starting it.
+
+  The scanner queries every block within an epoch for events, which makes scanning the Router's
+  entire history slow. This instead queries long ranges of blocks at once, indexing which blocks
+  have events relevant to us, letting the scanner skip the blocks without any.
+
+  Progress is checkpointed to the DB after every query, so the backfill may be interrupted and
+  resumed. Queries are rate limited and rotated between the configured providers, with the range
+  queried shrinking whenever a provider errors (as providers commonly bound the range of a query).
+*/
+
+fn new_provider(url: String) -> Arc<RootProvider<SimpleRequest>> {
+  Arc::new(RootProvider::new(ClientBuilder::default().transport(SimpleRequest::new(url), true)))
+}
+
+/// Backfill the index of which blocks have events relevant to us, from the Router's deployment
+/// through the latest finalized epoch, then exit.
+pub async fn backfill<D: Db>(mut db: D, url: String) -> ! {
+  // Additional providers to rotate between, alongside the one used by the processor
+  let mut urls = vec![url];
+  if let Some(additional) = serai_env::var("ETHEREUM_BACKFILL_RPC_URLS") {
+    urls.extend(
+      additional.split(',').map(str::trim).filter(|url| !url.is_empty()).map(str::to_string),
+    );
+  }
+  let requests_per_second =
+    serai_env::var("ETHEREUM_BACKFILL_REQUESTS_PER_SECOND").map_or(10, |requests| {
+      requests
+        .parse::<NonZeroU32>()
+        .expect("ETHEREUM_BACKFILL_REQUESTS_PER_SECOND wasn't a positive integer")
+        .get()
+    });
+  let request_interval = Duration::from_secs(1) / requests_per_second;
+  let max_epochs_per_query = serai_env::var("ETHEREUM_BACKFILL_EPOCHS_PER_QUERY").map_or(64, |e| {
+    e.parse::<NonZeroU64>()
+      .expect("ETHEREUM_BACKFILL_EPOCHS_PER_QUERY wasn't a positive integer")
+      .get()
+  });
+
+  let Some(first_key) = NetworkKeyDb::get(&db, Session(0)) else {
+    log::error!("no key has been confirmed, so there's no Router to backfill");
+    std::process::exit(1);
+  };
+  let key = Secp256k1::read_G(&mut first_key.as_slice()).unwrap();
+  let key = PublicKey::new(key).unwrap();
+
+  // The chain we operate on is either explicitly configured, the one we first operated on, or the
+  // one our first provider is for
+  let mut expected_chain_id = serai_env::var("ETHEREUM_CHAIN_ID")
+    .map(|chain_id| chain_id.parse().expect("ETHEREUM_CHAIN_ID wasn't a valid chain ID"))
+    .or_else(|| ChainIdDb::get(&db));
+
+  // Find the Router with every provider, ensuring they agree on it
+  let mut routers: Vec<(Arc<RootProvider<SimpleRequest>>, Router)> = vec![];
+  let mut deployment_block = None;
+  for url in urls {
+    let provider = new_provider(url.clone());
+    let chain_id = provider
+      .get_chain_id()
+      .await
+      .unwrap_or_else(|e| panic!("couldn't get the chain ID from {url}: {e:?}"));
+    verify_chain_id(*expected_chain_id.get_or_insert(chain_id), chain_id);
+
+    let deployer = Deployer::new(provider.clone())
+      .await
+      .unwrap_or_else(|e| panic!("couldn't check for the Deployer with {url}: {e:?}"))
+      .unwrap_or_else(|| panic!("{url} didn't have the Deployer deployed"));
+    let (router, block) = deployer
+      .find_router_deployment(provider.clone(), &key)
+      .await
+      .unwrap_or_else(|e| panic!("couldn't search for the Router with {url}: {e:?}"))
+      .unwrap_or_else(|| panic!("{url} didn't have the Router deployed"));
+    if let Some((_, existing)) = routers.first() {
+      assert_eq!(existing.address(), router.address(), "providers disagreed on the Router");
+      assert_eq!(deployment_block, Some(block), "providers disagreed on the Router's deployment");
+    }
+    deployment_block = Some(block);
+    routers.push((provider, router));
+  }
+  let deployment_block = deployment_block.unwrap();
+  if ChainIdDb::get(&db).is_none() {
+    let mut txn = db.txn();
+    ChainIdDb::set(&mut txn, &expected_chain_id.unwrap());
+    txn.commit();
+  }
+
+  // Backfill through the latest finalized epoch, as of when we started
+  let latest_finalized = loop {
+    match routers[0]
+      .0
+      .get_block(BlockNumberOrTag::Finalized.into(), BlockTransactionsKind::Hashes)
+      .await
+    {
+      Ok(Some(block)) => break block.header.number,
+      Ok(None) => log::error!("Ethereum node didn't have a finalized block"),
+      Err(e) => log::error!("couldn't get the latest finalized block: {e:?}"),
+    }
+    sleep(Duration::from_secs(5)).await;
+  };
+  // If this is 33, the division will return 1, yet 1 is the epoch in progress
+  let Some(latest_epoch) = (latest_finalized / 32).checked_sub(1) else {
+    log::info!("there hasn't been a full epoch to backfill yet");
+    std::process::exit(0);
+  };
+
+  let mut next_epoch = BackfilledThroughEpochDb::get(&db)
+    .map_or(deployment_block / 32, |backfilled_through| backfilled_through + 1);
+  let mut epochs_per_query = max_epochs_per_query;
+  let mut provider = 0;
+  let mut last_request: Option<Instant> = None;
+  let tokens = HashSet::from([DAI]);
+  while next_epoch <= latest_epoch {
+    if let Some(last_request) = last_request {
+      sleep(request_interval.saturating_sub(last_request.elapsed())).await;
+    }
+    last_request = Some(Instant::now());
+
+    let end_epoch = (next_epoch + (epochs_per_query - 1)).min(latest_epoch);
+    let blocks = (next_epoch * 32) ..= ((end_epoch * 32) + 31);
+    match routers[provider].1.blocks_with_events(blocks.clone(), &tokens).await {
+      Ok(blocks_with_events) => {
+        let mut txn = db.txn();
+        for epoch in next_epoch ..= end_epoch {
+          let blocks_with_events = blocks_with_events
+            .range((epoch * 32) ..= ((epoch * 32) + 31))
+            .copied()
+            .collect::<Vec<_>>();
+          if !blocks_with_events.is_empty() {
+            BackfilledEpochDb::set(&mut txn, epoch, &blocks_with_events);
+          }
+        }
+        BackfilledThroughEpochDb::set(&mut txn, &end_epoch);
+        txn.commit();
+
+        log::info!(
+          "backfilled through epoch {end_epoch} of {latest_epoch} ({} blocks with events)",
+          blocks_with_events.len(),
+        );
+        next_epoch = end_epoch + 1;
+        epochs_per_query = (epochs_per_query * 2).min(max_epochs_per_query);
+      }
+      Err(e) => {
+        log::warn!(
+          "provider #{provider} failed to return the events for blocks {}..={}: {e:?}",
+          blocks.start(),
+          blocks.end(),
+        );
+        provider = (provider + 1) % routers.len();
+        epochs_per_query = (epochs_per_query / 2).max(1);
+      }
+    }
+  }
+
+  log::info!("backfilled through epoch {latest_epoch}");
+  std::process::exit(0);
+}
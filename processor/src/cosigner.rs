@@ -37,6 +37,7 @@ pub struct Cosigner {
   session: Session,
   keys: Vec<ThresholdKeys<Ristretto>>,
 
+  session_start: [u8; 32],
   block_number: u64,
   id: [u8; 32],
   attempt: u32,
@@ -51,6 +52,7 @@ impl fmt::Debug for Cosigner {
     fmt
       .debug_struct("Cosigner")
       .field("session", &self.session)
+      .field("session_start", &self.session_start)
       .field("block_number", &self.block_number)
       .field("id", &self.id)
       .field("attempt", &self.attempt)
@@ -65,6 +67,7 @@ impl Cosigner {
     txn: &mut impl DbTxn,
     session: Session,
     keys: Vec<ThresholdKeys<Ristretto>>,
+    session_start: [u8; 32],
     block_number: u64,
     id: [u8; 32],
     attempt: u32,
@@ -105,7 +108,16 @@ impl Cosigner {
       SubstrateSignId { session, id: SubstrateSignableId::CosigningSubstrateBlock(id), attempt };
 
     Some((
-      Cosigner { session, keys, block_number, id, attempt, preprocessing, signing: None },
+      Cosigner {
+        session,
+        keys,
+        session_start,
+        block_number,
+        id,
+        attempt,
+        preprocessing,
+        signing: None,
+      },
       ProcessorMessage::CosignPreprocess {
         id: substrate_sign_id,
         preprocesses: serialized_preprocesses,
@@ -181,22 +193,23 @@ impl Cosigner {
             }
           }
 
-          let (machine, share) =
-            match machine.sign(preprocesses, &cosign_block_msg(self.block_number, self.id)) {
-              Ok(res) => res,
-              Err(e) => match e {
-                FrostError::InternalError(_) |
-                FrostError::InvalidParticipant(_, _) |
-                FrostError::InvalidSigningSet(_) |
-                FrostError::InvalidParticipantQuantity(_, _) |
-                FrostError::DuplicatedParticipant(_) |
-                FrostError::MissingParticipant(_) => unreachable!(),
-
-                FrostError::InvalidPreprocess(l) | FrostError::InvalidShare(l) => {
-                  return Some(ProcessorMessage::InvalidParticipant { id, participant: l })
-                }
-              },
-            };
+          let (machine, share) = match machine
+            .sign(preprocesses, &cosign_block_msg(self.session_start, self.block_number, self.id))
+          {
+            Ok(res) => res,
+            Err(e) => match e {
+              FrostError::InternalError(_) |
+              FrostError::InvalidParticipant(_, _) |
+              FrostError::InvalidSigningSet(_) |
+              FrostError::InvalidParticipantQuantity(_, _) |
+              FrostError::DuplicatedParticipant(_) |
+              FrostError::MissingParticipant(_) => unreachable!(),
+
+              FrostError::InvalidPreprocess(l) | FrostError::InvalidShare(l) => {
+                return Some(ProcessorMessage::InvalidParticipant { id, participant: l })
+              }
+            },
+          };
           if m == 0 {
             signature_machine = Some(machine);
           }
@@ -285,6 +298,7 @@ impl Cosigner {
         Completed::set(txn, block, &());
 
         Some(ProcessorMessage::CosignedBlock {
+          session_start: self.session_start,
           block_number: self.block_number,
           block,
           signature: sig.to_bytes().to_vec(),
@@ -10,10 +10,24 @@ use crate::networks::{Block, Network};
 create_db!(
   MainDb {
     HandledMessageDb: (id: u64) -> (),
-    PendingActivationsDb: () -> Vec<u8>
+    PendingActivationsDb: () -> Vec<u8>,
+    HaltedDb: () -> ()
   }
 );
 
+impl HaltedDb {
+  // If Serai halted this network, in which case we shouldn't publish any transactions
+  pub fn halted(getter: &impl Get) -> bool {
+    Self::get(getter).is_some()
+  }
+  pub fn halt(txn: &mut impl DbTxn) {
+    Self::set(txn, &());
+  }
+  pub fn resume(txn: &mut impl DbTxn) {
+    txn.del(Self::key());
+  }
+}
+
 impl PendingActivationsDb {
   pub fn pending_activation<N: Network>(
     getter: &impl Get,
@@ -0,0 +1,73 @@
+use core::time::Duration;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use borsh::{BorshSerialize, BorshDeserialize};
+
+/// A point in time, as observed on an external network (by its block number) and by the wall
+/// clock (as seconds since the Unix epoch).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct NetworkTime {
+  pub block: usize,
+  pub unix: u64,
+}
+
+impl NetworkTime {
+  /// The current time, as of the specified block.
+  pub fn at_block(block: usize) -> NetworkTime {
+    NetworkTime {
+      block,
+      unix: SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock is set to before the Unix epoch")
+        .as_secs(),
+    }
+  }
+}
+
+/// A deadline, expressed as an external network's block and/or a wall-clock time, reached once
+/// either is.
+///
+/// This lets anything scheduled by the processor (such as waiting for a Batch to be published, or
+/// alarming on a transaction not having been completed on-chain) be expressed uniformly across
+/// networks, regardless of their block times.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, BorshSerialize, BorshDeserialize)]
+pub struct Deadline {
+  block: Option<u64>,
+  unix: Option<u64>,
+}
+
+impl Deadline {
+  /// A deadline reached once the specified block is.
+  pub fn by_block(block: usize) -> Deadline {
+    Deadline { block: Some(block.try_into().unwrap()), unix: None }
+  }
+
+  /// A deadline reached after the specified amount of blocks, or the specified duration, has
+  /// passed since `now`.
+  pub fn after(now: NetworkTime, blocks: usize, duration: Duration) -> Deadline {
+    Deadline::by_block(now.block + blocks).or_by_time(now.unix + duration.as_secs())
+  }
+
+  /// Additionally consider this deadline reached once the specified wall-clock time is.
+  #[must_use]
+  pub fn or_by_time(self, unix: u64) -> Deadline {
+    Deadline { unix: Some(self.unix.map_or(unix, |existing| existing.min(unix))), ..self }
+  }
+
+  /// If this deadline has been reached as of `now`.
+  pub fn reached(&self, now: NetworkTime) -> bool {
+    self.block.is_some_and(|block| u64::try_from(now.block).unwrap() >= block) ||
+      self.unix.is_some_and(|unix| now.unix >= unix)
+  }
+
+  /// How far past this deadline `now` is, as an amount of blocks and an amount of seconds.
+  ///
+  /// Each is None if this deadline isn't expressed in such terms, and zero if `now` isn't past
+  /// this deadline in such terms.
+  pub fn overdue(&self, now: NetworkTime) -> (Option<u64>, Option<u64>) {
+    (
+      self.block.map(|block| u64::try_from(now.block).unwrap().saturating_sub(block)),
+      self.unix.map(|unix| now.unix.saturating_sub(unix)),
+    )
+  }
+}
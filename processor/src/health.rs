@@ -0,0 +1,41 @@
+use message_queue::client::MessageQueue;
+
+use tokio::time::{sleep, timeout};
+
+use serai_telemetry::health::{POLL_INTERVAL, CHECK_TIMEOUT, set_checks};
+
+use crate::networks::Network;
+
+/*
+  The checks for the processor's readiness.
+
+  The processor is ready if the external network's node is reachable and the message-queue accepts
+  its key.
+*/
+
+async fn check_network<N: Network>(network: &N) -> Result<String, String> {
+  match timeout(CHECK_TIMEOUT, network.get_latest_block_number()).await {
+    Ok(Ok(latest)) => Ok(format!("reachable with latest block {latest}")),
+    Ok(Err(e)) => Err(format!("couldn't fetch the latest block: {e:?}")),
+    Err(_) => Err("timed out fetching the latest block".to_string()),
+  }
+}
+
+async fn check_message_queue(message_queue: &MessageQueue) -> Result<String, String> {
+  match timeout(CHECK_TIMEOUT, message_queue.check_key()).await {
+    Ok(Some(true)) => Ok("accepted our key".to_string()),
+    Ok(Some(false)) => Err("didn't accept our key".to_string()),
+    Ok(None) | Err(_) => Err("couldn't be reached".to_string()),
+  }
+}
+
+/// Poll the conditions for readiness.
+pub(crate) async fn poll<N: Network>(network: N, message_queue: MessageQueue) {
+  loop {
+    set_checks(vec![
+      ("network", check_network(&network).await),
+      ("message_queue", check_message_queue(&message_queue).await),
+    ]);
+    sleep(POLL_INTERVAL).await;
+  }
+}
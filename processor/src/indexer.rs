@@ -0,0 +1,215 @@
+use core::time::Duration;
+use std::sync::Arc;
+
+use ethereum_serai::alloy::{
+  rpc_types::{BlockTransactionsKind, BlockNumberOrTag, Header},
+  simple_request_transport::SimpleRequest,
+  provider::{Provider, RootProvider},
+};
+
+use tokio::time::sleep;
+
+use crate::{Get, Db, DbTxn, create_db, networks::NetworkError};
+
+/*
+  An index of the hashes of recent blocks, used when the processor is configured to consider
+  blocks final once they're a certain depth into the chain (instead of relying on the node's
+  finalized block).
+
+  Blocks are indexed as soon as they're produced, with their hashes tracked. If a newly produced
+  block doesn't build on the block indexed before it, the indexed block was orphaned by a reorg and
+  is rolled back, repeating until we find the block the new chain builds on.
+
+  Every event source (the Router's InInstructions, top-level transfers, the Router's executed
+  commands, and the Router's key updates) is queried by block number, once the blocks reach the
+  finality depth. An epoch's hash must match the hash indexed for its last block. After the
+  events for an epoch are queried, the epoch's last block is checked to still have the epoch's
+  hash. As blocks form a chain, this confirms every block within the epoch (and prior) is as it
+  was when the epoch was fetched.
+
+  A reorg of a block which reached the finality depth, and may have been scanned, is
+  unrecoverable. We halt, erroring until an operator intervenes. Blocks are marked as scanned
+  once their events are, so the index also detects such reorgs once they're rolled back.
+
+  This is weaker than relying on the node's finalized block. A finality depth is a heuristic, and
+  a reorg deeper than it (which finality would prevent) will halt the processor.
+*/
+
+create_db!(
+  EthereumIndexer {
+    // The latest block indexed
+    IndexedTipDb: () -> u64,
+    // The hash of an indexed block, retained until it's scanned
+    IndexedBlockDb: (block: u64) -> [u8; 32],
+    // The latest block whose events were scanned
+    ScannedThroughDb: () -> u64,
+    // The block whose reorg exceeded the finality depth, if one did
+    TooDeepReorgDb: () -> u64,
+  }
+);
+
+// How often to check for new blocks, set to be well under Ethereum's block time
+const INDEX_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Index a block, with its hash.
+pub(crate) fn index_block(txn: &mut impl DbTxn, block: u64, hash: [u8; 32]) {
+  IndexedBlockDb::set(txn, block, &hash);
+  IndexedTipDb::set(txn, &block);
+}
+
+/// Roll back an indexed block which was orphaned by a reorg.
+///
+/// If the block was already scanned, the reorg exceeded the finality depth. The indexer is then
+/// halted and `None` is returned.
+pub(crate) fn roll_back_block(txn: &mut impl DbTxn, block: u64) -> Option<()> {
+  if ScannedThroughDb::get(txn).is_some_and(|scanned| block <= scanned) {
+    if TooDeepReorgDb::get(txn).is_none() {
+      TooDeepReorgDb::set(txn, &block);
+    }
+    return None;
+  }
+
+  log::warn!("rolling back block {block} due to a reorg");
+  IndexedBlockDb::del(txn, block);
+  match block.checked_sub(1) {
+    Some(tip) => IndexedTipDb::set(txn, &tip),
+    None => IndexedTipDb::del(txn),
+  }
+  Some(())
+}
+
+/// Mark the blocks through the specified block as scanned.
+pub(crate) fn mark_scanned(txn: &mut impl DbTxn, block: u64) {
+  let prior = ScannedThroughDb::get(txn);
+  if prior.is_some_and(|prior| block <= prior) {
+    return;
+  }
+  ScannedThroughDb::set(txn, &block);
+  // Only the hash of the latest scanned block needs to be retained, as a reorg of any prior block
+  // would also be a reorg of it
+  for prunable in prior.unwrap_or(block) .. block {
+    IndexedBlockDb::del(txn, prunable);
+  }
+}
+
+/// The block whose reorg exceeded the finality depth, if one did.
+pub(crate) fn too_deep_reorg(getter: &impl Get) -> Option<u64> {
+  TooDeepReorgDb::get(getter)
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Indexer {
+  provider: Arc<RootProvider<SimpleRequest>>,
+  depth: u64,
+}
+
+impl Indexer {
+  pub(crate) fn new(provider: Arc<RootProvider<SimpleRequest>>, depth: u64) -> Indexer {
+    Indexer { provider, depth }
+  }
+
+  async fn header(&self, block: BlockNumberOrTag) -> Result<Header, NetworkError> {
+    Ok(
+      self
+        .provider
+        .get_block(block.into(), BlockTransactionsKind::Hashes)
+        .await
+        .map_err(|_| NetworkError::ConnectionError)?
+        .ok_or(NetworkError::ConnectionError)?
+        .header,
+    )
+  }
+
+  // Error if we've halted due to a reorg exceeding the finality depth
+  fn check_halted(getter: &impl Get) -> Result<(), NetworkError> {
+    if let Some(block) = too_deep_reorg(getter) {
+      log::error!(
+        "halted as block {block} was reorged after reaching the finality depth, {}",
+        "which requires operator intervention"
+      );
+      Err(NetworkError::ConnectionError)?;
+    }
+    Ok(())
+  }
+
+  /// The latest block which has reached the finality depth.
+  pub(crate) async fn latest_final_block_number(
+    &self,
+    getter: &impl Get,
+  ) -> Result<u64, NetworkError> {
+    Self::check_halted(getter)?;
+    Ok(self.header(BlockNumberOrTag::Latest).await?.number.saturating_sub(self.depth))
+  }
+
+  /// The hash indexed for a block, if it's indexed.
+  pub(crate) fn indexed_block(getter: &impl Get, block: u64) -> Option<[u8; 32]> {
+    IndexedBlockDb::get(getter, block)
+  }
+
+  /// Check the specified block still has the expected hash, after the events within it (and the
+  /// blocks prior) were queried.
+  ///
+  /// The block must have reached the finality depth, so if it was reorged, we halt.
+  pub(crate) async fn check_block<D: Db>(
+    &self,
+    db: &mut D,
+    block: u64,
+    expected: [u8; 32],
+  ) -> Result<(), NetworkError> {
+    Self::check_halted(db)?;
+    let hash: [u8; 32] = self.header(block.into()).await?.hash.into();
+    if hash != expected {
+      let mut txn = db.txn();
+      TooDeepReorgDb::set(&mut txn, &block);
+      txn.commit();
+      Self::check_halted(db)?;
+    }
+    Ok(())
+  }
+
+  /// Index every block produced since the last sync, rolling back any orphaned by a reorg.
+  pub(crate) async fn sync<D: Db>(&self, db: &mut D) -> Result<(), NetworkError> {
+    Self::check_halted(db)?;
+
+    let head = self.header(BlockNumberOrTag::Latest).await?.number;
+    // Blocks which have already reached the finality depth don't need to be indexed
+    let mut next =
+      IndexedTipDb::get(db).map_or(0, |tip| tip + 1).max(head.saturating_sub(self.depth) + 1);
+    while next <= head {
+      let header = self.header(next.into()).await?;
+
+      // If this block doesn't build on the block we indexed prior, the prior block was orphaned
+      if let Some(parent) = next.checked_sub(1).and_then(|parent| IndexedBlockDb::get(db, parent)) {
+        if parent != <[u8; 32]>::from(header.parent_hash) {
+          let mut txn = db.txn();
+          let rolled_back = roll_back_block(&mut txn, next - 1);
+          txn.commit();
+          if rolled_back.is_none() {
+            Self::check_halted(db)?;
+          }
+          next -= 1;
+          continue;
+        }
+      }
+
+      let mut txn = db.txn();
+      index_block(&mut txn, next, header.hash.into());
+      txn.commit();
+      next += 1;
+    }
+    Ok(())
+  }
+
+  /// Spawn a task syncing the index as blocks are produced.
+  pub(crate) fn spawn<D: Db>(&self, mut db: D) {
+    let indexer = self.clone();
+    tokio::spawn(async move {
+      loop {
+        if let Err(e) = indexer.sync(&mut db).await {
+          log::error!("couldn't sync the index of recent blocks: {e:?}");
+        }
+        sleep(INDEX_INTERVAL).await;
+      }
+    });
+  }
+}
@@ -11,5 +11,10 @@ mod key_gen;
 pub mod networks;
 pub(crate) mod multisigs;
 
+#[cfg(feature = "ethereum")]
+mod indexer;
+
+mod deadline;
+
 mod additional_key;
 pub use additional_key::additional_key;
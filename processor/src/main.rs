@@ -1,4 +1,8 @@
-use std::{time::Duration, collections::HashMap};
+use std::{
+  sync::Arc,
+  time::{Duration, Instant},
+  collections::HashMap,
+};
 
 use zeroize::{Zeroize, Zeroizing};
 
@@ -36,6 +40,8 @@ use networks::Ethereum;
 #[cfg(feature = "monero")]
 use networks::Monero;
 
+mod deadline;
+
 mod additional_key;
 pub use additional_key::additional_key;
 
@@ -61,7 +67,21 @@ mod slash_report_signer;
 use slash_report_signer::SlashReportSigner;
 
 mod multisigs;
-use multisigs::{MultisigEvent, MultisigManager};
+use multisigs::{
+  MultisigEvent, MultisigManager,
+  attestation::{BlockAttestor, NodeAttestor},
+};
+
+#[cfg(feature = "telemetry")]
+mod health;
+mod metrics;
+
+mod self_test;
+
+#[cfg(feature = "ethereum")]
+mod backfill;
+#[cfg(feature = "ethereum")]
+mod indexer;
 
 #[cfg(test)]
 mod tests;
@@ -240,7 +260,7 @@ async fn handle_coordinator_msg<D: Db, N: Network, Co: Coordinator>(
     }
 
     CoordinatorMessage::Coordinator(msg) => match msg {
-      CoordinatorCoordinatorMessage::CosignSubstrateBlock { id, block_number } => {
+      CoordinatorCoordinatorMessage::CosignSubstrateBlock { id, session_start, block_number } => {
         let SubstrateSignableId::CosigningSubstrateBlock(block) = id.id else {
           panic!("CosignSubstrateBlock id didn't have a CosigningSubstrateBlock")
         };
@@ -248,7 +268,7 @@ async fn handle_coordinator_msg<D: Db, N: Network, Co: Coordinator>(
           panic!("didn't have key shares for the key we were told to cosign with");
         };
         if let Some((cosigner, msg)) =
-          Cosigner::new(txn, id.session, keys, block_number, block, id.attempt)
+          Cosigner::new(txn, id.session, keys, session_start, block_number, block, id.attempt)
         {
           tributary_mutable.cosigner = Some(cosigner);
           coordinator.send(msg).await;
@@ -475,6 +495,16 @@ async fn handle_coordinator_msg<D: Db, N: Network, Co: Coordinator>(
             substrate_mutable.release_scanner_lock().await;
           }
         }
+
+        // We continue to scan and sign while halted, solely not publishing transactions
+        messages::substrate::CoordinatorMessage::Halt { block } => {
+          warn!("Serai halted this network as of Serai block {block}, halting publication");
+          HaltedDb::halt(txn);
+        }
+        messages::substrate::CoordinatorMessage::Resume { block } => {
+          info!("Serai resumed this network as of Serai block {block}, resuming publication");
+          HaltedDb::resume(txn);
+        }
       }
     }
   }
@@ -483,6 +513,7 @@ async fn handle_coordinator_msg<D: Db, N: Network, Co: Coordinator>(
 async fn boot<N: Network, D: Db, Co: Coordinator>(
   raw_db: &mut D,
   network: &N,
+  attestor: Option<Arc<dyn BlockAttestor<N>>>,
   coordinator: &mut Co,
 ) -> (D, TributaryMutable<N, D>, SubstrateMutable<N, D>) {
   let mut entropy_transcript = {
@@ -522,7 +553,7 @@ async fn boot<N: Network, D: Db, Co: Coordinator>(
   let key_gen = KeyGen::<N, _>::new(raw_db.clone(), entropy(b"key-gen_entropy"));
 
   let (multisig_manager, current_keys, actively_signing) =
-    MultisigManager::new(raw_db, network).await;
+    MultisigManager::new(raw_db, network, attestor).await;
 
   let mut batch_signer = None;
   let mut signers = HashMap::new();
@@ -579,15 +610,50 @@ async fn boot<N: Network, D: Db, Co: Coordinator>(
 }
 
 #[allow(clippy::await_holding_lock)] // Needed for txn, unfortunately can't be down-scoped
-async fn run<N: Network, D: Db, Co: Coordinator>(mut raw_db: D, network: N, mut coordinator: Co) {
+async fn run<N: Network, D: Db, Co: Coordinator>(
+  mut raw_db: D,
+  network: N,
+  attestor: Option<N>,
+  mut coordinator: Co,
+) {
   // We currently expect a contextless bidirectional mapping between these two values
   // (which is that any value of A can be interpreted as B and vice versa)
   // While we can write a contextual mapping, we have yet to do so
   // This check ensures no network which doesn't have a bidirectional mapping is defined
   assert_eq!(<N::Block as Block<N>>::Id::default().as_ref().len(), BlockHash([0u8; 32]).0.len());
 
-  let (main_db, mut tributary_mutable, mut substrate_mutable) =
-    boot(&mut raw_db, &network, &mut coordinator).await;
+  // Serve metrics, if a port to do so on was specified
+  #[cfg(feature = "telemetry")]
+  if let Some(port) = env::var("METRICS_PORT") {
+    let port = port.parse().expect("METRICS_PORT wasn't a valid port");
+    tokio::spawn(serai_telemetry::metrics::serve(port));
+  }
+
+  // Serve the health probes, if a port to do so on was specified
+  #[cfg(feature = "telemetry")]
+  if let Some(port) = env::var("HEALTH_PORT") {
+    let port = port.parse().expect("HEALTH_PORT wasn't a valid port");
+    tokio::spawn(serai_telemetry::health::serve(port));
+    tokio::spawn(health::poll(
+      network.clone(),
+      MessageQueue::from_env(Service::Processor(N::NETWORK)),
+    ));
+  }
+
+  #[cfg(not(feature = "telemetry"))]
+  for var in ["METRICS_PORT", "HEALTH_PORT"] {
+    if env::var(var).is_some() {
+      warn!("{var} was set yet the processor was built without the telemetry feature");
+    }
+  }
+
+  let (main_db, mut tributary_mutable, mut substrate_mutable) = boot(
+    &mut raw_db,
+    &network,
+    attestor.map(|node| Arc::new(NodeAttestor(node)) as Arc<dyn BlockAttestor<N>>),
+    &mut coordinator,
+  )
+  .await;
 
   // We can't load this from the DB as we can't guarantee atomic increments with the ack function
   // TODO: Load with a slight tolerance
@@ -615,6 +681,7 @@ async fn run<N: Network, D: Db, Co: Coordinator>(mut raw_db: D, network: N, mut
         // Only handle this if we haven't already
         if HandledMessageDb::get(&main_db, msg.id).is_none() {
           HandledMessageDb::set(&mut txn, msg.id, &());
+          let start = Instant::now();
 
           // This is isolated to better think about how its ordered, or rather, about how the other
           // cases aren't ordered
@@ -634,6 +701,7 @@ async fn run<N: Network, D: Db, Co: Coordinator>(mut raw_db: D, network: N, mut
             &mut substrate_mutable,
             &msg,
           ).await;
+          metrics::note_coordinator_message(N::NETWORK, start.elapsed());
         }
 
         outer_msg = Some(msg);
@@ -651,6 +719,7 @@ async fn run<N: Network, D: Db, Co: Coordinator>(mut raw_db: D, network: N, mut
             // Start signing this batch
             for batch in batches {
               info!("created batch {} ({} instructions)", batch.id, batch.instructions.len());
+              metrics::note_batch(N::NETWORK);
 
               // The coordinator expects BatchPreprocess to immediately follow Batch
               coordinator.send(
@@ -678,6 +747,7 @@ async fn run<N: Network, D: Db, Co: Coordinator>(mut raw_db: D, network: N, mut
             }
           },
           MultisigEvent::Completed(key, id, tx) => {
+            metrics::note_completion(N::NETWORK);
             if let Some(session) = SessionDb::get(&txn, &key) {
               let signer = tributary_mutable.signers.get_mut(&session).unwrap();
               if let Some(msg) = signer.completed(&mut txn, id, &tx) {
@@ -685,6 +755,14 @@ async fn run<N: Network, D: Db, Co: Coordinator>(mut raw_db: D, network: N, mut
               }
             }
           }
+          MultisigEvent::Invalidated(key, id) => {
+            if let Some(session) = SessionDb::get(&txn, &key) {
+              let signer = tributary_mutable.signers.get_mut(&session).unwrap();
+              if let Some(msg) = signer.invalidated(&mut txn, id) {
+                coordinator.send(msg).await;
+              }
+            }
+          }
         }
       },
     }
@@ -698,22 +776,14 @@ async fn run<N: Network, D: Db, Co: Coordinator>(mut raw_db: D, network: N, mut
 
 #[tokio::main]
 async fn main() {
-  // Override the panic handler with one which will panic if any tokio task panics
-  {
-    let existing = std::panic::take_hook();
-    std::panic::set_hook(Box::new(move |panic| {
-      existing(panic);
-      const MSG: &str = "exiting the process due to a task panicking";
-      println!("{MSG}");
-      log::error!("{MSG}");
-      std::process::exit(1);
-    }));
-  }
+  serai_telemetry::exit_on_panic();
 
-  if std::env::var("RUST_LOG").is_err() {
-    std::env::set_var("RUST_LOG", serai_env::var("RUST_LOG").unwrap_or_else(|| "info".to_string()));
-  }
-  env_logger::init();
+  serai_telemetry::logging::init(
+    &std::env::var("RUST_LOG")
+      .ok()
+      .or_else(|| serai_env::var("RUST_LOG"))
+      .unwrap_or_else(|| "info".to_string()),
+  );
 
   #[allow(unused_variables, unreachable_code)]
   let db = {
@@ -735,6 +805,8 @@ async fn main() {
     let port = env::var("NETWORK_RPC_PORT").expect("network port domain wasn't specified");
     "http://".to_string() + &login + "@" + &hostname + ":" + &port
   };
+  // An optional second node, independent of the above, to attest to the blocks we scan
+  let attestation_url = env::var("NETWORK_ATTESTATION_RPC_URL");
   let network_id = match env::var("NETWORK").expect("network wasn't specified").as_str() {
     "bitcoin" => ExternalNetworkId::Bitcoin,
     "ethereum" => ExternalNetworkId::Ethereum,
@@ -744,25 +816,55 @@ async fn main() {
 
   let coordinator = MessageQueue::from_env(Service::Processor(network_id));
 
+  // If we were asked to run the self-test, run it instead of the processor
+  if std::env::args().any(|arg| arg == "--self-test") {
+    self_test::self_test(db, url, network_id, coordinator).await;
+  }
+
+  // If we were asked to backfill the Router's history, do so instead of running the processor
+  #[cfg(feature = "ethereum")]
+  if std::env::args().any(|arg| arg == "--backfill") {
+    assert_eq!(
+      network_id,
+      ExternalNetworkId::Ethereum,
+      "--backfill is only supported for Ethereum"
+    );
+    backfill::backfill(db, url).await;
+  }
+
   // This allow is necessary since each configuration deletes the other networks from the following
   // match arms. So we match all cases but since all cases already there according to the compiler
   // we put this to allow clippy to get pass this.
   #[allow(unreachable_patterns)]
   match network_id {
     #[cfg(feature = "bitcoin")]
-    ExternalNetworkId::Bitcoin => run(db, Bitcoin::new(url).await, coordinator).await,
+    ExternalNetworkId::Bitcoin => {
+      let attestor = match attestation_url {
+        Some(attestation_url) => Some(Bitcoin::new(attestation_url).await),
+        None => None,
+      };
+      run(db, Bitcoin::new(url).await, attestor, coordinator).await
+    }
     #[cfg(feature = "ethereum")]
     ExternalNetworkId::Ethereum => {
-      let relayer_hostname = env::var("ETHEREUM_RELAYER_HOSTNAME")
-        .expect("ethereum relayer hostname wasn't specified")
-        .to_string();
-      let relayer_port =
-        env::var("ETHEREUM_RELAYER_PORT").expect("ethereum relayer port wasn't specified");
-      let relayer_url = relayer_hostname + ":" + &relayer_port;
-      run(db.clone(), Ethereum::new(db, url, relayer_url).await, coordinator).await
+      let relayer_urls = networks::ethereum::relayer_urls_from_env();
+      assert!(!relayer_urls.is_empty(), "ethereum relayer wasn't specified");
+      let attestor = match attestation_url {
+        Some(attestation_url) => {
+          Some(Ethereum::new(db.clone(), attestation_url, relayer_urls.clone()).await)
+        }
+        None => None,
+      };
+      run(db.clone(), Ethereum::new(db, url, relayer_urls).await, attestor, coordinator).await
     }
     #[cfg(feature = "monero")]
-    ExternalNetworkId::Monero => run(db, Monero::new(url).await, coordinator).await,
+    ExternalNetworkId::Monero => {
+      let attestor = match attestation_url {
+        Some(attestation_url) => Some(Monero::new(db.clone(), attestation_url).await),
+        None => None,
+      };
+      run(db.clone(), Monero::new(db, url).await, attestor, coordinator).await
+    }
     _ => panic!("spawning a processor for an unsupported network"),
   }
 }
@@ -0,0 +1,41 @@
+use core::time::Duration;
+
+use serai_client::primitives::ExternalNetworkId;
+
+use serai_telemetry::metrics::{increment_counter, observe_duration};
+
+fn network_label(network: ExternalNetworkId) -> &'static str {
+  match network {
+    ExternalNetworkId::Bitcoin => "bitcoin",
+    ExternalNetworkId::Ethereum => "ethereum",
+    ExternalNetworkId::Monero => "monero",
+  }
+}
+
+/// Note a message from the coordinator was handled, and how long handling it took.
+pub(crate) fn note_coordinator_message(network: ExternalNetworkId, duration: Duration) {
+  observe_duration(
+    "serai_processor_coordinator_message_seconds",
+    "The duration of handling a message from the coordinator.",
+    &[("network", network_label(network))],
+    duration,
+  );
+}
+
+/// Note a Batch was created.
+pub(crate) fn note_batch(network: ExternalNetworkId) {
+  increment_counter(
+    "serai_processor_batches_total",
+    "The amount of Batches created.",
+    &[("network", network_label(network))],
+  );
+}
+
+/// Note an Eventuality was completed on-chain.
+pub(crate) fn note_completion(network: ExternalNetworkId) {
+  increment_counter(
+    "serai_processor_completions_total",
+    "The amount of Eventualities observed as completed on-chain.",
+    &[("network", network_label(network))],
+  );
+}
@@ -0,0 +1,28 @@
+use core::fmt::Debug;
+
+use async_trait::async_trait;
+
+use crate::networks::{NetworkError, Block, Network};
+
+/// An independent source of the blocks on an external network, used to attest to the blocks the
+/// scanner scans.
+///
+/// The scanner will refuse to report deposits within a block which wasn't attested to, mitigating
+/// eclipse attacks against the node the processor uses. Sources may be a second node, a public
+/// header oracle, or headers relayed by other validators.
+#[async_trait]
+pub trait BlockAttestor<N: Network>: Send + Sync + Debug {
+  /// The ID of the block with the specified number, according to this source.
+  async fn block_id(&self, number: usize) -> Result<<N::Block as Block<N>>::Id, NetworkError>;
+}
+
+/// Attest to blocks by fetching them from a second, independent node.
+#[derive(Clone, Debug)]
+pub struct NodeAttestor<N: Network>(pub N);
+
+#[async_trait]
+impl<N: Network> BlockAttestor<N> for NodeAttestor<N> {
+  async fn block_id(&self, number: usize) -> Result<<N::Block as Block<N>>::Id, NetworkError> {
+    Ok(self.0.get_block(number).await?.id())
+  }
+}
@@ -11,7 +11,7 @@ use serai_client::{
 };
 
 use crate::{
-  Get, Plan,
+  Get, Payment, Plan,
   networks::{Output, Transaction, Network},
 };
 
@@ -74,7 +74,8 @@ create_db!(
     ResolvedDb: (tx: &[u8]) -> [u8; 32],
     SigningDb: (key: &[u8]) -> Vec<u8>,
     ForwardedOutputDb: (balance: ExternalBalance) -> Vec<u8>,
-    DelayedOutputDb: () -> Vec<u8>
+    DelayedOutputDb: () -> Vec<u8>,
+    InvalidatedPaymentsDb: () -> Vec<u8>
   }
 );
 
@@ -129,6 +130,18 @@ impl PlanDb {
     res
   }
 
+  /// Stop noting we're signing a plan, as it was invalidated, returning the plan.
+  pub fn invalidate_plan<N: Network>(txn: &mut impl DbTxn, key: &[u8], id: [u8; 32]) -> Plan<N> {
+    let signing = SigningDb::get(txn, key).unwrap_or_default();
+    assert_eq!(signing.len() % 32, 0);
+    let signing = signing.chunks(32).filter(|signing| *signing != id).collect::<Vec<_>>().concat();
+    SigningDb::set(txn, key, &signing);
+
+    let plan = Plan::<N>::read::<&[u8]>(&mut &Self::get(txn, &id).unwrap()[8 ..]).unwrap();
+    assert_eq!(plan.id(), id);
+    plan
+  }
+
   pub fn plan_by_key_with_self_change<N: Network>(
     getter: &impl Get,
     key: <N::Curve as Ciphersuite>::G,
@@ -262,3 +275,25 @@ impl DelayedOutputDb {
     res
   }
 }
+
+impl InvalidatedPaymentsDb {
+  pub fn save_invalidated_payments<N: Network>(txn: &mut impl DbTxn, payments: &[Payment<N>]) {
+    let mut existing = Self::get(txn).unwrap_or_default();
+    for payment in payments {
+      payment.write(&mut existing).unwrap();
+    }
+    Self::set(txn, &existing);
+  }
+
+  pub fn take_invalidated_payments<N: Network>(txn: &mut impl DbTxn) -> Vec<Payment<N>> {
+    let Some(payments) = Self::get(txn) else { return vec![] };
+    txn.del(Self::key());
+
+    let mut payments_ref = payments.as_slice();
+    let mut res = vec![];
+    while !payments_ref.is_empty() {
+      res.push(Payment::<N>::read(&mut payments_ref).unwrap());
+    }
+    res
+  }
+}
@@ -1,5 +1,5 @@
 use core::time::Duration;
-use std::collections::HashSet;
+use std::{sync::Arc, collections::HashSet};
 
 use ciphersuite::{group::GroupEncoding, Ciphersuite};
 
@@ -18,6 +18,9 @@ use log::{info, error};
 
 use tokio::time::sleep;
 
+pub mod attestation;
+use attestation::BlockAttestor;
+
 #[cfg(not(test))]
 mod scanner;
 #[cfg(test)]
@@ -25,7 +28,7 @@ pub mod scanner;
 
 use scanner::{ScannerEvent, ScannerHandle, Scanner};
 
-mod db;
+pub(crate) mod db;
 use db::*;
 
 pub(crate) mod scheduler;
@@ -144,6 +147,8 @@ pub enum MultisigEvent<N: Network> {
   Batches(Option<(<N::Curve as Ciphersuite>::G, <N::Curve as Ciphersuite>::G)>, Vec<Batch>),
   // Eventuality completion found on-chain
   Completed(Vec<u8>, [u8; 32], <N::Eventuality as Eventuality>::Completion),
+  // Eventuality invalidated on-chain, with its payments to be scheduled anew
+  Invalidated(Vec<u8>, [u8; 32]),
 }
 
 pub struct MultisigManager<D: Db, N: Network> {
@@ -156,13 +161,14 @@ impl<D: Db, N: Network> MultisigManager<D, N> {
   pub async fn new(
     raw_db: &D,
     network: &N,
+    attestor: Option<Arc<dyn BlockAttestor<N>>>,
   ) -> (
     Self,
     Vec<<N::Curve as Ciphersuite>::G>,
     Vec<(Plan<N>, N::SignableTransaction, N::Eventuality)>,
   ) {
     // The scanner has no long-standing orders to re-issue
-    let (mut scanner, current_keys) = Scanner::new(network.clone(), raw_db.clone());
+    let (mut scanner, current_keys) = Scanner::new(network.clone(), attestor, raw_db.clone());
 
     let mut schedulers = vec![];
 
@@ -565,6 +571,16 @@ impl<D: Db, N: Network> MultisigManager<D, N> {
   ) -> (bool, Vec<Plan<N>>, HashSet<[u8; 32]>) {
     let (mut existing_payments, mut new_payments) = self.burns_to_payments(txn, *step, burns);
 
+    // Schedule the payments of any invalidated plans anew
+    // Plans are only invalidated once the existing multisig rotates to the new multisig, so these
+    // are scheduled by the new multisig if it's yet to become the existing multisig
+    let invalidated_payments = InvalidatedPaymentsDb::take_invalidated_payments::<N>(txn);
+    if self.new.is_some() {
+      new_payments.extend(invalidated_payments);
+    } else {
+      existing_payments.extend(invalidated_payments);
+    }
+
     let mut plans = vec![];
     let mut plans_from_scanning = HashSet::new();
 
@@ -1029,6 +1045,15 @@ impl<D: Db, N: Network> MultisigManager<D, N> {
         ResolvedDb::resolve_plan::<N>(txn, &key, id, &tx_id);
         (block_number, MultisigEvent::Completed(key, id, completion))
       }
+
+      // This is also emitted before ScannerEvent::Block, for the same reasons as Completed
+      ScannerEvent::Invalidated(key, block_number, id) => {
+        // The payments will be scheduled anew, by the multisig now accepted, when the next
+        // Substrate block is acknowledged
+        let plan = PlanDb::invalidate_plan::<N>(txn, &key, id);
+        InvalidatedPaymentsDb::save_invalidated_payments(txn, &plan.payments);
+        (block_number, MultisigEvent::Invalidated(key, id))
+      }
     };
 
     // If we either received a Block event (which will be the trigger when we have no
@@ -9,7 +9,7 @@ use std::{
 use ciphersuite::group::GroupEncoding;
 use frost::curve::Ciphersuite;
 
-use log::{info, debug, warn};
+use log::{info, debug, warn, error};
 use tokio::{
   sync::{RwLockReadGuard, RwLockWriteGuard, RwLock, mpsc},
   time::sleep,
@@ -18,8 +18,16 @@ use tokio::{
 use crate::{
   Get, DbTxn, Db,
   networks::{Output, Transaction, Eventuality, EventualitiesTracker, Block, Network},
+  multisigs::attestation::BlockAttestor,
+  deadline::{NetworkTime, Deadline},
 };
 
+// The deadline for the Batch for a block to be published by, after which we stop scanning until it
+// is
+fn batch_publication_deadline<N: Network>(block: usize) -> Deadline {
+  Deadline::by_block(block + N::CONFIRMATIONS)
+}
+
 #[derive(Clone, Debug)]
 pub enum ScannerEvent<N: Network> {
   // Block scanned
@@ -37,6 +45,8 @@ pub enum ScannerEvent<N: Network> {
     <N::Transaction as Transaction<N>>::Id,
     <N::Eventuality as Eventuality>::Completion,
   ),
+  // Eventuality invalidated on-chain, as it can no longer be completed
+  Invalidated(Vec<u8>, usize, [u8; 32]),
 }
 
 pub type ScannerEventChannel<N> = mpsc::UnboundedReceiver<ScannerEvent<N>>;
@@ -384,6 +394,7 @@ impl<N: Network, D: Db> Scanner<N, D> {
   #[allow(clippy::type_complexity, clippy::new_ret_no_self)]
   pub fn new(
     network: N,
+    attestor: Option<Arc<dyn BlockAttestor<N>>>,
     db: D,
   ) -> (ScannerHandle<N, D>, Vec<(usize, <N::Curve as Ciphersuite>::G)>) {
     let (events_send, events_recv) = mpsc::unbounded_channel();
@@ -413,7 +424,7 @@ impl<N: Network, D: Db> Scanner<N, D> {
         events: events_send,
       }))),
     };
-    tokio::spawn(Scanner::run(db, network, scanner.clone(), multisig_completed_recv));
+    tokio::spawn(Scanner::run(db, network, attestor, scanner.clone(), multisig_completed_recv));
 
     (
       ScannerHandle {
@@ -438,13 +449,14 @@ impl<N: Network, D: Db> Scanner<N, D> {
   async fn run(
     mut db: D,
     network: N,
+    attestor: Option<Arc<dyn BlockAttestor<N>>>,
     scanner_hold: ScannerHold<N, D>,
     mut multisig_completed: mpsc::UnboundedReceiver<bool>,
   ) {
     loop {
       let (ram_scanned, latest_block_to_scan) = {
-        // Sleep 5 seconds to prevent hammering the node/scanner lock
-        sleep(Duration::from_secs(5)).await;
+        // Wait up to 5 seconds for a new block, to prevent hammering the node/scanner lock
+        network.wait_for_new_block(Duration::from_secs(5)).await;
 
         let ram_scanned = {
           let scanner_lock = scanner_hold.read().await;
@@ -461,10 +473,10 @@ impl<N: Network, D: Db> Scanner<N, D> {
           // Solves a race condition around multisig rotation, documented in the relevant doc
           // and demonstrated with mini
           if let Some(needing_ack) = scanner.need_ack.front() {
-            let next = ram_scanned + 1;
-            let limit = needing_ack + N::CONFIRMATIONS;
-            assert!(next <= limit);
-            if next == limit {
+            let next = NetworkTime::at_block(ram_scanned + 1);
+            let deadline = batch_publication_deadline::<N>(*needing_ack);
+            assert_eq!(deadline.overdue(next).0, Some(0));
+            if deadline.reached(next) {
               continue;
             }
           };
@@ -499,9 +511,10 @@ impl<N: Network, D: Db> Scanner<N, D> {
           };
 
           if let Some(needing_ack) = needing_ack {
-            let limit = needing_ack + N::CONFIRMATIONS;
-            assert!(block_being_scanned <= limit);
-            if block_being_scanned == limit {
+            let now = NetworkTime::at_block(block_being_scanned);
+            let deadline = batch_publication_deadline::<N>(needing_ack);
+            assert_eq!(deadline.overdue(now).0, Some(0));
+            if deadline.reached(now) {
               break;
             }
           }
@@ -513,6 +526,26 @@ impl<N: Network, D: Db> Scanner<N, D> {
         };
         let block_id = block.id();
 
+        // If we have an independent source of blocks, don't scan this block until it attests to it
+        // This prevents an eclipsed node from causing us to report deposits which don't exist
+        if let Some(attestor) = &attestor {
+          match attestor.block_id(block_being_scanned).await {
+            Ok(attested) if attested == block_id => {}
+            Ok(attested) => {
+              error!(
+                "DIVERGENCE: our node has {} as block {block_being_scanned} yet {} was attested to",
+                hex::encode(&block_id),
+                hex::encode(&attested),
+              );
+              break;
+            }
+            Err(_) => {
+              warn!("couldn't get block {block_being_scanned} from the attestor");
+              break;
+            }
+          }
+        }
+
         info!("scanning block: {} ({block_being_scanned})", hex::encode(&block_id));
 
         // These DB calls are safe, despite not having a txn, since they're static values
@@ -566,6 +599,18 @@ impl<N: Network, D: Db> Scanner<N, D> {
             }
           }
 
+          for id in network
+            .get_eventuality_invalidations(scanner.eventualities.get_mut(&key_vec).unwrap(), &block)
+            .await
+          {
+            warn!("eventuality {} was invalidated, as found on chain", hex::encode(id));
+
+            // This must be before the emission of ScannerEvent::Block, as with Completed
+            if !scanner.emit(ScannerEvent::Invalidated(key_vec.clone(), block_being_scanned, id)) {
+              return;
+            }
+          }
+
           for (id, (block_number, tx, completion)) in network
             .get_eventuality_completions(scanner.eventualities.get_mut(&key_vec).unwrap(), &block)
             .await
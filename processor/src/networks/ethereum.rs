@@ -1,6 +1,7 @@
-use core::{fmt, time::Duration};
+use core::{fmt, num::NonZeroU64, ops::RangeInclusive, time::Duration};
 use std::{
   sync::Arc,
+  time::Instant,
   collections::{HashSet, HashMap},
   io,
 };
@@ -13,23 +14,28 @@ use frost::ThresholdKeys;
 use ethereum_serai::{
   alloy::{
     primitives::U256,
+    consensus::TxLegacy,
     rpc_types::{BlockTransactionsKind, BlockNumberOrTag, Transaction},
     simple_request_transport::SimpleRequest,
     rpc_client::ClientBuilder,
     provider::{Provider, RootProvider},
   },
-  crypto::{PublicKey, Signature},
+  crypto::{DOMAIN_VERSION, PublicKey, Signature},
   erc20::Erc20,
   deployer::Deployer,
-  router::{Router, Coin as EthereumCoin, InInstruction as EthereumInInstruction},
+  router::{Router, Coin as EthereumCoin, InInstruction as EthereumInInstruction, KeyUpdate},
+  gas_limit::{DEFAULT_GAS_MULTIPLIER_PERCENT, GasEstimator},
+  publisher::{TransactionSigner, Publisher},
   machine::*,
 };
+#[cfg(not(test))]
+use ethereum_serai::publisher::Publication;
 #[cfg(test)]
 use ethereum_serai::alloy::primitives::B256;
 
 use tokio::{
-  time::sleep,
-  sync::{RwLock, RwLockReadGuard},
+  time::{sleep, timeout},
+  sync::{Mutex, RwLock, RwLockReadGuard, Notify},
 };
 #[cfg(not(test))]
 use tokio::{
@@ -37,18 +43,21 @@ use tokio::{
   net::TcpStream,
 };
 
+use serai_telemetry::websocket;
+
 use serai_client::{
   primitives::{ExternalCoin, Amount, ExternalBalance, ExternalNetworkId},
   validator_sets::primitives::Session,
 };
 
 use crate::{
-  Db, Payment,
+  Db, DbTxn, create_db, Payment,
   networks::{
     OutputType, Output, Transaction as TransactionTrait, SignableTransaction, Block,
     Eventuality as EventualityTrait, EventualitiesTracker, NetworkError, Network,
   },
   key_gen::NetworkKeyDb,
+  indexer::{Indexer, mark_scanned},
   multisigs::scheduler::{
     Scheduler as SchedulerTrait,
     smart_contract::{Addendum, Scheduler},
@@ -56,13 +65,13 @@ use crate::{
 };
 
 #[cfg(not(test))]
-const DAI: [u8; 20] =
+pub(crate) const DAI: [u8; 20] =
   match const_hex::const_decode_to_array(b"0x6B175474E89094C44Da98b954EedeAC495271d0F") {
     Ok(res) => res,
     Err(_) => panic!("invalid non-test DAI hex address"),
   };
 #[cfg(test)] // TODO
-const DAI: [u8; 20] =
+pub(crate) const DAI: [u8; 20] =
   match const_hex::const_decode_to_array(b"0000000000000000000000000000000000000000") {
     Ok(res) => res,
     Err(_) => panic!("invalid test DAI hex address"),
@@ -248,17 +257,53 @@ impl From<&Signature> for Claim {
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
-pub struct Eventuality(PublicKey, RouterCommand);
+pub struct Eventuality(pub(crate) PublicKey, pub(crate) RouterCommand);
+impl Eventuality {
+  fn nonce(&self) -> U256 {
+    match self.1 {
+      RouterCommand::UpdateSeraiKey { nonce, .. } |
+      RouterCommand::Execute { nonce, .. } |
+      RouterCommand::SetPaused { nonce, .. } => nonce,
+    }
+  }
+}
+
+/// Remove the Eventualities invalidated by the specified key updates, returning their plans' IDs.
+///
+/// A key authorizes every command with a nonce greater than the nonce it was set with.
+/// Accordingly, any command with a greater nonce which was signed by another key will never be
+/// executable.
+pub(crate) fn invalidate_eventualities(
+  eventualities: &mut EventualitiesTracker<Eventuality>,
+  key_updates: &[KeyUpdate],
+) -> Vec<[u8; 32]> {
+  let mut res = vec![];
+  for update in key_updates {
+    eventualities.map.retain(|_, (plan_id, eventuality)| {
+      let invalidated =
+        (eventuality.nonce() > U256::from(update.nonce)) && (eventuality.0 != update.key);
+      if invalidated {
+        log::warn!(
+          "command {} for plan {} was invalidated by the key update with nonce {} in block {}",
+          eventuality.nonce(),
+          hex::encode(plan_id),
+          update.nonce,
+          update.block,
+        );
+        res.push(*plan_id);
+      }
+      !invalidated
+    });
+  }
+  res
+}
+
 impl EventualityTrait for Eventuality {
   type Claim = Claim;
   type Completion = SignedRouterCommand;
 
   fn lookup(&self) -> Vec<u8> {
-    match self.1 {
-      RouterCommand::UpdateSeraiKey { nonce, .. } | RouterCommand::Execute { nonce, .. } => {
-        nonce.as_le_bytes().to_vec()
-      }
-    }
+    self.nonce().as_le_bytes().to_vec()
   }
 
   fn read<R: io::Read>(reader: &mut R) -> io::Result<Self> {
@@ -289,6 +334,185 @@ impl EventualityTrait for Eventuality {
   }
 }
 
+create_db!(
+  EthereumProcessor {
+    // The ID of the chain this processor operates on, pinned when it's first started
+    ChainIdDb: () -> u64,
+    // The last epoch the backfill indexed the blocks with events for
+    BackfilledThroughEpochDb: () -> u64,
+    // The blocks within a backfilled epoch with events, if there were any
+    BackfilledEpochDb: (epoch: u64) -> Vec<u64>,
+  }
+);
+
+// How often to check the node is still for the chain we operate on
+const CHAIN_ID_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+// Panic if the node's chain ID isn't the one we operate on
+//
+// Operating on the wrong chain would cause confusing failures at best, and signing commands for
+// the wrong chain at worst.
+pub(crate) fn verify_chain_id(expected: u64, actual: u64) {
+  assert_eq!(
+    actual, expected,
+    "Ethereum node is for chain {actual}, yet this processor operates on chain {expected}",
+  );
+}
+
+// How many blocks a transaction we published may remain unmined before its fee is bumped, by
+// default
+const DEFAULT_PUBLISHER_BUMP_AFTER: u64 = 5;
+
+// The transaction performing a signed Router command
+fn command_transaction(router: &Router, completion: &SignedRouterCommand) -> TxLegacy {
+  match completion.command() {
+    RouterCommand::UpdateSeraiKey { key, .. } => {
+      router.update_serai_key(key, completion.signature())
+    }
+    RouterCommand::Execute { outs, .. } => router
+      .execute(&outs.iter().cloned().map(Into::into).collect::<Vec<_>>(), completion.signature()),
+    RouterCommand::SetPaused { paused, .. } => router.set_paused(*paused, completion.signature()),
+  }
+}
+
+// How long to attempt other relayers before returning to one which failed
+const RELAYER_BACKOFF: Duration = Duration::from_secs(60);
+
+// How long to wait for a relayer to acknowledge a transaction before considering it failed
+#[cfg(not(test))]
+const RELAYER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The relayers specified by the environment, as `host:port` URLs.
+///
+/// `ETHEREUM_RELAYER_URLS` may specify a comma-separated list of relayers. Otherwise, the single
+/// relayer specified by `ETHEREUM_RELAYER_HOSTNAME` and `ETHEREUM_RELAYER_PORT` is used.
+pub(crate) fn relayer_urls_from_env() -> Vec<String> {
+  if let Some(urls) = serai_env::var("ETHEREUM_RELAYER_URLS") {
+    return urls
+      .split(',')
+      .map(str::trim)
+      .filter(|url| !url.is_empty())
+      .map(ToString::to_string)
+      .collect();
+  }
+  serai_env::var("ETHEREUM_RELAYER_HOSTNAME")
+    .zip(serai_env::var("ETHEREUM_RELAYER_PORT"))
+    .map(|(hostname, port)| vec![hostname + ":" + &port])
+    .unwrap_or_default()
+}
+
+/// The relayers transactions are published with, tracking which have recently failed.
+///
+/// Each transaction is offered to the relayers in turn until one acknowledges it, so a single
+/// relayer being unavailable doesn't halt publication.
+#[derive(Clone, Debug)]
+pub(crate) struct Relayers {
+  // Each relayer, with when it last failed if it hasn't succeeded since
+  relayers: Arc<std::sync::Mutex<Vec<(String, Option<Instant>)>>>,
+}
+
+impl Relayers {
+  pub(crate) fn new(urls: Vec<String>) -> Relayers {
+    Relayers {
+      relayers: Arc::new(std::sync::Mutex::new(urls.into_iter().map(|url| (url, None)).collect())),
+    }
+  }
+
+  /// The relayers, in the order they should be attempted.
+  ///
+  /// Relayers which haven't failed within `RELAYER_BACKOFF` are attempted first, in the order
+  /// they were specified. They're followed by those which have, least recently failed first, so
+  /// they're still attempted if no relayer is available.
+  pub(crate) fn order(&self) -> Vec<String> {
+    let relayers = self.relayers.lock().unwrap();
+    let (available, mut failed): (Vec<_>, Vec<_>) =
+      relayers.iter().partition(|(_, failed)| match failed {
+        Some(failed) => failed.elapsed() >= RELAYER_BACKOFF,
+        None => true,
+      });
+    failed.sort_by_key(|(_, failed)| *failed);
+    available.into_iter().chain(failed).map(|(url, _)| url.clone()).collect()
+  }
+
+  /// Note the result of attempting to publish with a relayer.
+  pub(crate) fn note_result(&self, url: &str, success: bool) {
+    for (relayer, failed) in self.relayers.lock().unwrap().iter_mut() {
+      if relayer == url {
+        *failed = (!success).then(Instant::now);
+      }
+    }
+  }
+
+  #[cfg(not(test))]
+  async fn send(url: &str, msg: &[u8]) -> Result<(), &'static str> {
+    let mut socket =
+      TcpStream::connect(url).await.map_err(|_| "couldn't connect to the relayer server")?;
+    socket
+      .write_all(&u32::try_from(msg.len()).unwrap().to_le_bytes())
+      .await
+      .map_err(|_| "couldn't send the message's len to the relayer server")?;
+    socket.write_all(msg).await.map_err(|_| "couldn't write the message to the relayer server")?;
+    if socket.read_u8().await.ok() != Some(1) {
+      Err("didn't get the ack from the relayer server")?;
+    }
+    Ok(())
+  }
+
+  /// Publish a message with the first relayer to acknowledge it.
+  #[cfg(not(test))]
+  async fn publish(&self, msg: &[u8]) -> Result<(), NetworkError> {
+    for url in self.order() {
+      let result = match timeout(RELAYER_TIMEOUT, Self::send(&url, msg)).await {
+        Ok(result) => result,
+        Err(_) => Err("timed out publishing with the relayer server"),
+      };
+      match result {
+        Ok(()) => {
+          self.note_result(&url, true);
+          return Ok(());
+        }
+        Err(e) => {
+          log::warn!("{e} ({url})");
+          self.note_result(&url, false);
+        }
+      }
+    }
+    log::warn!("no relayer server accepted the transaction");
+    Err(NetworkError::ConnectionError)
+  }
+}
+
+// How long to wait before resubscribing to new heads after the subscription dropped
+const NEW_HEADS_RESUBSCRIBE_DELAY: Duration = Duration::from_secs(5);
+
+// The maximum size of a message we'll accept from the node's WebSocket
+const MAX_WS_MESSAGE_SIZE: usize = 1024 * 1024;
+
+// Subscribe to the node's new heads, notifying for each, until the subscription drops
+async fn subscribe_new_heads(url: &str, new_heads: &Notify) -> Result<(), &'static str> {
+  let mut socket = websocket::Client::connect(url, MAX_WS_MESSAGE_SIZE).await?;
+
+  let subscribe = r#"{"jsonrpc":"2.0","id":1,"method":"eth_subscribe","params":["newHeads"]}"#;
+  socket.send(subscribe).await.ok_or("couldn't request the newHeads subscription")?;
+  let response = socket
+    .read()
+    .await
+    .and_then(|response| serde_json::from_slice::<serde_json::Value>(&response).ok())
+    .ok_or("couldn't read the response to the newHeads subscription")?;
+  if !response["result"].is_string() {
+    Err("the node rejected the newHeads subscription")?;
+  }
+  log::info!("subscribed to new heads from the Ethereum node");
+
+  while let Some(message) = socket.read().await {
+    let Ok(message) = serde_json::from_slice::<serde_json::Value>(&message) else { continue };
+    if message["method"] == "eth_subscription" {
+      new_heads.notify_one();
+    }
+  }
+  Err("the newHeads subscription dropped")
+}
+
 #[derive(Clone)]
 pub struct Ethereum<D: Db> {
   // This DB is solely used to access the first key generated, as needed to determine the Router's
@@ -296,10 +520,26 @@ pub struct Ethereum<D: Db> {
   // first key (regardless of local state), and this is safe.
   db: D,
   #[cfg_attr(test, allow(unused))]
-  relayer_url: String,
+  relayers: Relayers,
   provider: Arc<RootProvider<SimpleRequest>>,
+  chain_id: u64,
+  // When the node's chain ID was last checked
+  chain_id_checked: Arc<std::sync::Mutex<Instant>>,
   deployer: Deployer,
   router: Arc<RwLock<Option<Router>>>,
+  // The nonces of commands whose publication was skipped, as the Router had already advanced past
+  // them, to the Router's nonce when they were skipped
+  skipped_publications: Arc<std::sync::Mutex<HashMap<u64, u64>>>,
+  // Notified when the node reports a new head, if we're subscribed to them
+  new_heads: Arc<Notify>,
+  // The index of recent blocks, if blocks are considered final at a configured depth
+  indexer: Option<Indexer>,
+  // The publisher of our transactions, if we publish them ourselves instead of via the relayers
+  #[cfg_attr(test, allow(unused))]
+  publisher: Option<Arc<Mutex<Publisher>>>,
+  // The accepted tokens which support ERC-677, whose deposits made via `transferAndCall` are
+  // credited
+  erc677_tokens: HashSet<[u8; 20]>,
 }
 impl<D: Db> PartialEq for Ethereum<D> {
   fn eq(&self, _other: &Ethereum<D>) -> bool {
@@ -316,11 +556,35 @@ impl<D: Db> fmt::Debug for Ethereum<D> {
   }
 }
 impl<D: Db> Ethereum<D> {
-  pub async fn new(db: D, daemon_url: String, relayer_url: String) -> Self {
+  pub async fn new(mut db: D, daemon_url: String, relayer_urls: Vec<String>) -> Self {
     let provider = Arc::new(RootProvider::new(
       ClientBuilder::default().transport(SimpleRequest::new(daemon_url), true),
     ));
 
+    let chain_id = loop {
+      match provider.get_chain_id().await {
+        Ok(chain_id) => break chain_id,
+        Err(e) => {
+          log::error!("couldn't get the chain ID from the Ethereum node: {e:?}");
+          sleep(Duration::from_secs(5)).await;
+        }
+      }
+    };
+    // The chain we operate on is either explicitly configured, or the one we first operated on
+    let expected_chain_id = serai_env::var("ETHEREUM_CHAIN_ID")
+      .map(|chain_id| chain_id.parse().expect("ETHEREUM_CHAIN_ID wasn't a valid chain ID"));
+    if let Some(expected_chain_id) = expected_chain_id {
+      verify_chain_id(expected_chain_id, chain_id);
+    }
+    match ChainIdDb::get(&db) {
+      Some(pinned_chain_id) => verify_chain_id(pinned_chain_id, chain_id),
+      None => {
+        let mut txn = db.txn();
+        ChainIdDb::set(&mut txn, &chain_id);
+        txn.commit();
+      }
+    }
+
     let mut deployer = Deployer::new(provider.clone()).await;
     while !matches!(deployer, Ok(Some(_))) {
       log::error!("Deployer wasn't deployed yet or networking error");
@@ -329,9 +593,351 @@ impl<D: Db> Ethereum<D> {
     }
     let deployer = deployer.unwrap().unwrap();
 
-    dbg!(&relayer_url);
-    dbg!(relayer_url.len());
-    Ethereum { db, relayer_url, provider, deployer, router: Arc::new(RwLock::new(None)) }
+    // If a WebSocket was specified, subscribe to new heads so we may scan as soon as they're
+    // produced, instead of solely polling
+    let new_heads = Arc::new(Notify::new());
+    if let Some(ws_url) = serai_env::var("ETHEREUM_WS_URL") {
+      websocket::parse_url(&ws_url).expect("ETHEREUM_WS_URL wasn't a valid ws:// URL");
+      let new_heads = new_heads.clone();
+      tokio::spawn(async move {
+        loop {
+          if let Err(e) = subscribe_new_heads(&ws_url, &new_heads).await {
+            log::warn!("{e}, falling back to polling for new blocks");
+          }
+          sleep(NEW_HEADS_RESUBSCRIBE_DELAY).await;
+        }
+      });
+    }
+
+    // If a finality depth was specified, consider blocks final once they're that deep (instead of
+    // once the node considers them finalized), indexing recent blocks to handle reorgs
+    let indexer = serai_env::var("ETHEREUM_FINALITY_DEPTH").map(|depth| {
+      let depth = depth
+        .parse::<NonZeroU64>()
+        .expect("ETHEREUM_FINALITY_DEPTH wasn't a positive integer")
+        .get();
+      let indexer = Indexer::new(provider.clone(), depth);
+      indexer.spawn(db.clone());
+      indexer
+    });
+
+    // If an account was specified, publish transactions from it ourselves, bumping their fees if
+    // they're left unmined, instead of publishing them via the relayers
+    let publisher = serai_env::var("ETHEREUM_PUBLISHER_KEY").map(|key| {
+      let key = hex::decode(key.trim_start_matches("0x"))
+        .ok()
+        .and_then(|key| k256::ecdsa::SigningKey::from_slice(&key).ok())
+        .expect("ETHEREUM_PUBLISHER_KEY wasn't a hex-encoded secp256k1 private key");
+      let bump_after = serai_env::var("ETHEREUM_PUBLISHER_BUMP_AFTER").map_or(
+        DEFAULT_PUBLISHER_BUMP_AFTER,
+        |bump_after| {
+          bump_after.parse().expect("ETHEREUM_PUBLISHER_BUMP_AFTER wasn't an amount of blocks")
+        },
+      );
+      // Estimate the gas limit of transactions, as the gas limits they're constructed with are
+      // the most they may use
+      let gas_multiplier_percent = serai_env::var("ETHEREUM_GAS_MULTIPLIER_PERCENT")
+        .map_or(DEFAULT_GAS_MULTIPLIER_PERCENT, |percent| {
+          percent.parse().expect("ETHEREUM_GAS_MULTIPLIER_PERCENT wasn't a percentage")
+        });
+      Arc::new(Mutex::new(
+        Publisher::new(provider.clone(), TransactionSigner::Account(key), bump_after)
+          .with_gas_estimator(GasEstimator::new(provider.clone(), gas_multiplier_percent)),
+      ))
+    });
+
+    // DAI doesn't support ERC-677, so by default, no deposits made via `transferAndCall` are
+    // credited
+    let erc677_tokens = serai_env::var("ETHEREUM_ERC677_TOKENS").map_or(HashSet::new(), |tokens| {
+      tokens
+        .split(',')
+        .map(|token| {
+          let token = hex::decode(token.trim().trim_start_matches("0x"))
+            .ok()
+            .and_then(|token| <[u8; 20]>::try_from(token).ok())
+            .expect("ETHEREUM_ERC677_TOKENS wasn't a comma-separated list of addresses");
+          assert_eq!(token, DAI, "ETHEREUM_ERC677_TOKENS had a token which isn't accepted");
+          token
+        })
+        .collect()
+    });
+
+    Ethereum {
+      db,
+      relayers: Relayers::new(relayer_urls),
+      provider,
+      chain_id,
+      chain_id_checked: Arc::new(std::sync::Mutex::new(Instant::now())),
+      deployer,
+      router: Arc::new(RwLock::new(None)),
+      skipped_publications: Arc::new(std::sync::Mutex::new(HashMap::new())),
+      new_heads,
+      indexer,
+      publisher,
+      erc677_tokens,
+    }
+  }
+
+  #[cfg(test)]
+  pub(crate) fn with_erc677_tokens(mut self, erc677_tokens: HashSet<[u8; 20]>) -> Self {
+    self.erc677_tokens = erc677_tokens;
+    self
+  }
+
+  // Fund a fresh account with 1.1 ETH, returning its key
+  #[cfg(test)]
+  async fn test_account(&self) -> <Secp256k1 as Ciphersuite>::F {
+    use rand_core::OsRng;
+    use ciphersuite::group::ff::Field;
+
+    let key = <Secp256k1 as Ciphersuite>::F::random(&mut OsRng);
+    let address = ethereum_serai::crypto::address(&(Secp256k1::generator() * key));
+    self
+      .provider
+      .raw_request::<_, ()>(
+        "anvil_setBalance".into(),
+        [Address(address).to_string(), "1100000000000000000".into()],
+      )
+      .await
+      .unwrap();
+    key
+  }
+
+  // Sign and publish a transaction, mining an epoch containing it
+  #[cfg(test)]
+  async fn test_publish(
+    &self,
+    key: <Secp256k1 as Ciphersuite>::F,
+    tx: TxLegacy,
+  ) -> ethereum_serai::alloy::rpc_types::TransactionReceipt {
+    use ethereum_serai::alloy::{
+      primitives::{Parity, Signature},
+      consensus::SignableTransaction,
+    };
+    let sig = k256::ecdsa::SigningKey::from(k256::elliptic_curve::NonZeroScalar::new(key).unwrap())
+      .sign_prehash_recoverable(tx.signature_hash().as_ref())
+      .unwrap();
+
+    let mut bytes = vec![];
+    let parity = Parity::NonEip155(Parity::from(sig.1).y_parity());
+    tx.encode_with_signature_fields(&Signature::from(sig).with_parity(parity), &mut bytes);
+    let pending_tx = self.provider.send_raw_transaction(&bytes).await.ok().unwrap();
+
+    self.mine_block().await;
+    let receipt = pending_tx.get_receipt().await.unwrap();
+    assert!(receipt.status());
+    receipt
+  }
+
+  // Deposit DAI into the Router via ERC-677's `transferAndCall`, yielding the epoch containing the
+  // deposit
+  // As the test DAI isn't a deployed contract, a test ERC-677 token is placed at its address
+  #[cfg(test)]
+  pub(crate) async fn test_send_erc677(&self, amount: U256, data: &[u8]) -> Epoch {
+    use ethereum_serai::alloy::primitives::{TxKind, keccak256};
+
+    let key = self.test_account().await;
+    let tx = |nonce, to, input: Vec<u8>| TxLegacy {
+      chain_id: None,
+      nonce,
+      gas_price: 1_000_000_000u128,
+      gas_limit: 1_000_000,
+      to,
+      value: U256::ZERO,
+      input: input.into(),
+    };
+
+    // Deploy the test token, then move its code to DAI's address
+    let bin = std::fs::read_to_string(concat!(
+      env!("CARGO_MANIFEST_DIR"),
+      "/../networks/ethereum/artifacts/TestERC677.bin"
+    ))
+    .unwrap();
+    let bin = hex::decode(bin.trim().trim_start_matches("0x")).unwrap();
+    let token = self.test_publish(key, tx(0, TxKind::Create, bin)).await.contract_address.unwrap();
+    let code = self.provider.get_code_at(token).await.unwrap();
+    self
+      .provider
+      .raw_request::<_, ()>("anvil_setCode".into(), [Address(DAI).to_string(), code.to_string()])
+      .await
+      .unwrap();
+
+    // Mint the tokens to deposit
+    let address = ethereum_serai::crypto::address(&(Secp256k1::generator() * key));
+    let mut mint = keccak256("mint(address,uint256)")[.. 4].to_vec();
+    mint.extend([0; 12]);
+    mint.extend(address);
+    mint.extend(amount.to_be_bytes::<32>());
+    self.test_publish(key, tx(1, TxKind::Call(DAI.into()), mint)).await;
+
+    // Deposit them, without any approval, by having the token call the Router
+    let router = self.router().await.as_ref().unwrap().address();
+    let transfer_and_call =
+      Erc20::new(self.provider.clone(), DAI).transfer_and_call(router, amount, data);
+    self
+      .test_publish(key, TxLegacy { nonce: 2, gas_price: 1_000_000_000u128, ..transfer_and_call })
+      .await;
+
+    self.get_block(self.get_latest_block_number().await.unwrap()).await.unwrap()
+  }
+
+  // Check the node is still for the chain we operate on, if it hasn't been checked recently
+  async fn check_chain_id(&self) -> Result<(), NetworkError> {
+    if self.chain_id_checked.lock().unwrap().elapsed() < CHAIN_ID_CHECK_INTERVAL {
+      return Ok(());
+    }
+    let chain_id = self.provider.get_chain_id().await.map_err(|_| NetworkError::ConnectionError)?;
+    verify_chain_id(self.chain_id, chain_id);
+    *self.chain_id_checked.lock().unwrap() = Instant::now();
+    Ok(())
+  }
+
+  // If the backfill indexed this block as without any events relevant to us
+  fn backfilled_without_events(&self, block: u64) -> bool {
+    let epoch = block / 32;
+    BackfilledThroughEpochDb::get(&self.db).is_some_and(|through| epoch <= through) &&
+      !BackfilledEpochDb::get(&self.db, epoch).unwrap_or_default().contains(&block)
+  }
+
+  // The blocks from after the epoch the tracker was last updated for, through the end of this epoch
+  async fn unscanned_blocks(
+    &self,
+    eventualities: &EventualitiesTracker<Eventuality>,
+    block: &Epoch,
+  ) -> RangeInclusive<u64> {
+    let past_scanned_epoch = loop {
+      match self.get_block(eventualities.block_number).await {
+        Ok(block) => break block,
+        Err(e) => log::error!("couldn't get the last scanned block in the tracker: {}", e),
+      }
+      sleep(Duration::from_secs(10)).await;
+    };
+    assert_eq!(
+      past_scanned_epoch.start / 32,
+      u64::try_from(eventualities.block_number).unwrap(),
+      "assumption of tracker block number's relation to epoch start is incorrect"
+    );
+    (past_scanned_epoch.end() + 1) ..= block.end()
+  }
+
+  // If we're considering blocks final at a depth, check the epoch is still on the node's chain,
+  // after its events were queried, halting if it was reorged
+  async fn check_epoch(&self, block: &Epoch) {
+    let Some(indexer) = &self.indexer else { return };
+    let mut db = self.db.clone();
+    while let Err(e) = indexer.check_block(&mut db, block.end(), block.end_hash).await {
+      log::error!(
+        "couldn't check the epoch ending with block {} wasn't reorged: {e:?}",
+        block.end()
+      );
+      sleep(Duration::from_secs(5)).await;
+    }
+  }
+
+  // The InInstructions within an epoch
+  async fn in_instructions(&self, block: &Epoch) -> Vec<EthereumInInstruction> {
+    // Skip the blocks the backfill found to be without events
+    let blocks = (block.start .. (block.start + 32))
+      .filter(|block| !self.backfilled_without_events(*block))
+      .collect::<Vec<_>>();
+    if blocks.is_empty() {
+      return vec![];
+    }
+
+    let router = self.router().await;
+    let router = router.as_ref().unwrap();
+    // Grab the key at the end of the epoch
+    let key_at_end_of_block = loop {
+      match router.key_at_end_of_block(block.start + 31).await {
+        Ok(Some(key)) => break key,
+        Ok(None) => return vec![],
+        Err(e) => {
+          log::error!("couldn't connect to router for the key at the end of the block: {e:?}");
+          sleep(Duration::from_secs(5)).await;
+          continue;
+        }
+      }
+    };
+
+    let mut all_events = vec![];
+    let mut top_level_txids = HashSet::new();
+    for erc20_addr in [DAI] {
+      let erc20 = Erc20::new(self.provider.clone(), erc20_addr);
+
+      for block in blocks.iter().copied() {
+        let transfers = loop {
+          match erc20.top_level_transfers(block, router.address()).await {
+            Ok(transfers) => break transfers,
+            Err(e) => {
+              log::error!("couldn't connect to Ethereum node for the top-level transfers: {e:?}");
+              sleep(Duration::from_secs(5)).await;
+              continue;
+            }
+          }
+        };
+
+        for transfer in transfers {
+          top_level_txids.insert(transfer.id);
+          // Top-level transfers don't call the Router, so they can't be rejected while it's
+          // paused. As the funds were already received, they're credited regardless
+          all_events.push(EthereumInInstruction {
+            id: (transfer.id, 0),
+            from: transfer.from,
+            coin: EthereumCoin::Erc20(erc20_addr),
+            amount: transfer.amount,
+            data: transfer.data,
+            key_at_end_of_block,
+          });
+        }
+      }
+    }
+
+    // WETH isn't accepted, so no WETH contract is scanned for
+    for block in blocks.iter().copied() {
+      let mut events =
+        router.in_instructions(block, &HashSet::from([DAI]), &self.erc677_tokens, None).await;
+      while let Err(e) = events {
+        log::error!("couldn't connect to Ethereum node for the Router's events: {e:?}");
+        sleep(Duration::from_secs(5)).await;
+        events =
+          router.in_instructions(block, &HashSet::from([DAI]), &self.erc677_tokens, None).await;
+      }
+      let mut events = events.unwrap();
+      for event in &mut events {
+        // A transaction should either be a top-level transfer or a Router InInstruction
+        if top_level_txids.contains(&event.id.0) {
+          panic!("top-level transfer had {} and router had {:?}", hex::encode(event.id.0), event);
+        }
+        // Overwrite the key at end of block to key at end of epoch
+        event.key_at_end_of_block = key_at_end_of_block;
+      }
+      all_events.extend(events);
+    }
+
+    for event in &all_events {
+      assert!(
+        coin_to_serai_coin(&event.coin).is_some(),
+        "router yielded events for unrecognized coins"
+      );
+    }
+    all_events
+  }
+
+  // Check if the Router has been deployed, without waiting for it to be.
+  // Returns None if we have yet to confirm a key, and accordingly can't look for the Router.
+  pub async fn router_deployed(&self) -> Result<Option<bool>, NetworkError> {
+    if self.router.read().await.is_some() {
+      return Ok(Some(true));
+    }
+    let Some(first_key) = NetworkKeyDb::get(&self.db, Session(0)) else { return Ok(None) };
+    let key = Secp256k1::read_G(&mut first_key.as_slice()).unwrap();
+    let public_key = PublicKey::new(key).unwrap();
+    let router = self
+      .deployer
+      .find_router(self.provider.clone(), &public_key)
+      .await
+      .map_err(|_| NetworkError::ConnectionError)?;
+    Ok(Some(router.is_some()))
   }
 
   // Obtain a reference to the Router, sleeping until it's deployed if it hasn't already been.
@@ -367,8 +973,25 @@ impl<D: Db> Ethereum<D> {
       found = self.deployer.find_router(self.provider.clone(), &public_key).await;
     }
 
+    let found = found.unwrap().unwrap();
+
+    // Ensure the Router verifies signatures with the same domain separation we sign with
+    // A Router predating the domain separation won't have this function, and will never be usable
+    let mut version = found.schnorr_domain_version().await;
+    while version.is_err() {
+      log::error!("couldn't fetch the Router's Schnorr domain version (or the Router predates it)");
+      sleep(Duration::from_secs(5)).await;
+      version = found.schnorr_domain_version().await;
+    }
+    let version = version.unwrap();
+    assert_eq!(
+      version, DOMAIN_VERSION,
+      "Router uses version {version} of the Schnorr domain separation, yet we sign for version {}",
+      DOMAIN_VERSION,
+    );
+
     // Set it
-    *router = Some(found.unwrap().unwrap());
+    *router = Some(found);
 
     // Downgrade to a read lock
     // Explicitly doesn't use `downgrade` so that another pending write txn can realize it's no
@@ -430,14 +1053,20 @@ impl<D: Db> Network for Ethereum<D> {
   }
 
   async fn get_latest_block_number(&self) -> Result<usize, NetworkError> {
-    let actual_number = self
-      .provider
-      .get_block(BlockNumberOrTag::Finalized.into(), BlockTransactionsKind::Hashes)
-      .await
-      .map_err(|_| NetworkError::ConnectionError)?
-      .ok_or(NetworkError::ConnectionError)?
-      .header
-      .number;
+    self.check_chain_id().await?;
+    let actual_number = match &self.indexer {
+      Some(indexer) => indexer.latest_final_block_number(&self.db).await?,
+      None => {
+        self
+          .provider
+          .get_block(BlockNumberOrTag::Finalized.into(), BlockTransactionsKind::Hashes)
+          .await
+          .map_err(|_| NetworkError::ConnectionError)?
+          .ok_or(NetworkError::ConnectionError)?
+          .header
+          .number
+      }
+    };
     // Error if there hasn't been a full epoch yet
     if actual_number < 32 {
       Err(NetworkError::ConnectionError)?
@@ -447,6 +1076,11 @@ impl<D: Db> Network for Ethereum<D> {
     Ok(latest_full_epoch.try_into().unwrap())
   }
 
+  async fn wait_for_new_block(&self, duration: Duration) {
+    // If we aren't subscribed to new heads, or the subscription dropped, this solely times out
+    let _ = timeout(duration, self.new_heads.notified()).await;
+  }
+
   async fn get_block(&self, number: usize) -> Result<Self::Block, NetworkError> {
     let latest_finalized = self.get_latest_block_number().await?;
     if number > latest_finalized {
@@ -481,6 +1115,17 @@ impl<D: Db> Network for Ethereum<D> {
     let end_hash = end_header.hash.into();
     let time = end_header.timestamp;
 
+    // If we've yet to sync the index since a reorg, the node's chain may differ from our index
+    if let Some(indexed) = self
+      .indexer
+      .as_ref()
+      .and_then(|_| Indexer::indexed_block(&self.db, u64::try_from(start + 31).unwrap()))
+    {
+      if indexed != end_hash {
+        Err(NetworkError::ConnectionError)?
+      }
+    }
+
     Ok(Epoch { prior_end_hash, start: start.try_into().unwrap(), end_hash, time })
   }
 
@@ -489,78 +1134,16 @@ impl<D: Db> Network for Ethereum<D> {
     block: &Self::Block,
     _: <Secp256k1 as Ciphersuite>::G,
   ) -> Vec<Self::Output> {
-    let router = self.router().await;
-    let router = router.as_ref().unwrap();
-    // Grab the key at the end of the epoch
-    let key_at_end_of_block = loop {
-      match router.key_at_end_of_block(block.start + 31).await {
-        Ok(Some(key)) => break key,
-        Ok(None) => return vec![],
-        Err(e) => {
-          log::error!("couldn't connect to router for the key at the end of the block: {e:?}");
-          sleep(Duration::from_secs(5)).await;
-          continue;
-        }
-      }
-    };
-
-    let mut all_events = vec![];
-    let mut top_level_txids = HashSet::new();
-    for erc20_addr in [DAI] {
-      let erc20 = Erc20::new(self.provider.clone(), erc20_addr);
-
-      for block in block.start .. (block.start + 32) {
-        let transfers = loop {
-          match erc20.top_level_transfers(block, router.address()).await {
-            Ok(transfers) => break transfers,
-            Err(e) => {
-              log::error!("couldn't connect to Ethereum node for the top-level transfers: {e:?}");
-              sleep(Duration::from_secs(5)).await;
-              continue;
-            }
-          }
-        };
-
-        for transfer in transfers {
-          top_level_txids.insert(transfer.id);
-          all_events.push(EthereumInInstruction {
-            id: (transfer.id, 0),
-            from: transfer.from,
-            coin: EthereumCoin::Erc20(erc20_addr),
-            amount: transfer.amount,
-            data: transfer.data,
-            key_at_end_of_block,
-          });
-        }
-      }
+    let in_instructions = self.in_instructions(block).await;
+    // Check the epoch wasn't reorged while we queried its events, and mark it as scanned
+    self.check_epoch(block).await;
+    if self.indexer.is_some() {
+      let mut db = self.db.clone();
+      let mut txn = db.txn();
+      mark_scanned(&mut txn, block.end());
+      txn.commit();
     }
-
-    for block in block.start .. (block.start + 32) {
-      let mut events = router.in_instructions(block, &HashSet::from([DAI])).await;
-      while let Err(e) = events {
-        log::error!("couldn't connect to Ethereum node for the Router's events: {e:?}");
-        sleep(Duration::from_secs(5)).await;
-        events = router.in_instructions(block, &HashSet::from([DAI])).await;
-      }
-      let mut events = events.unwrap();
-      for event in &mut events {
-        // A transaction should either be a top-level transfer or a Router InInstruction
-        if top_level_txids.contains(&event.id.0) {
-          panic!("top-level transfer had {} and router had {:?}", hex::encode(event.id.0), event);
-        }
-        // Overwrite the key at end of block to key at end of epoch
-        event.key_at_end_of_block = key_at_end_of_block;
-      }
-      all_events.extend(events);
-    }
-
-    for event in &all_events {
-      assert!(
-        coin_to_serai_coin(&event.coin).is_some(),
-        "router yielded events for unrecognized coins"
-      );
-    }
-    all_events
+    in_instructions
   }
 
   async fn get_eventuality_completions(
@@ -583,21 +1166,10 @@ impl<D: Db> Network for Ethereum<D> {
     let router = self.router().await;
     let router = router.as_ref().unwrap();
 
-    let past_scanned_epoch = loop {
-      match self.get_block(eventualities.block_number).await {
-        Ok(block) => break block,
-        Err(e) => log::error!("couldn't get the last scanned block in the tracker: {}", e),
+    for block_num in self.unscanned_blocks(eventualities, block).await {
+      if self.backfilled_without_events(block_num) {
+        continue;
       }
-      sleep(Duration::from_secs(10)).await;
-    };
-    assert_eq!(
-      past_scanned_epoch.start / 32,
-      u64::try_from(eventualities.block_number).unwrap(),
-      "assumption of tracker block number's relation to epoch start is incorrect"
-    );
-
-    // Iterate from after the epoch number in the tracker to the end of this epoch
-    for block_num in (past_scanned_epoch.end() + 1) ..= block.end() {
       let executed = loop {
         match router.executed_commands(block_num).await {
           Ok(executed) => break executed,
@@ -607,6 +1179,16 @@ impl<D: Db> Network for Ethereum<D> {
       };
 
       for executed in executed {
+        if let Some(router_nonce) =
+          self.skipped_publications.lock().unwrap().remove(&executed.nonce)
+        {
+          log::info!(
+            "command {} was executed in block {block_num} (publication skipped at nonce {})",
+            executed.nonce,
+            router_nonce,
+          );
+        }
+
         let lookup = executed.nonce.to_le_bytes().to_vec();
         if let Some((plan_id, eventuality)) = eventualities.map.get(&lookup) {
           if let Some(command) =
@@ -618,11 +1200,37 @@ impl<D: Db> Network for Ethereum<D> {
         }
       }
     }
+    self.check_epoch(block).await;
     eventualities.block_number = (block.start / 32).try_into().unwrap();
 
     res
   }
 
+  async fn get_eventuality_invalidations(
+    &self,
+    eventualities: &mut EventualitiesTracker<Self::Eventuality>,
+    block: &Self::Block,
+  ) -> Vec<[u8; 32]> {
+    if eventualities.map.is_empty() {
+      return vec![];
+    }
+
+    let router = self.router().await;
+    let router = router.as_ref().unwrap();
+
+    let blocks = self.unscanned_blocks(eventualities, block).await;
+    let key_updates = loop {
+      match router.key_history(blocks.clone()).await {
+        Ok(key_updates) => break key_updates,
+        Err(e) => log::error!("couldn't get the key updates in blocks {blocks:?}: {e}"),
+      }
+      sleep(Duration::from_secs(10)).await;
+    };
+    self.check_epoch(block).await;
+
+    invalidate_eventualities(eventualities, &key_updates)
+  }
+
   async fn needed_fee(
     &self,
     _block_number: usize,
@@ -647,7 +1255,9 @@ impl<D: Db> Network for Ethereum<D> {
   ) -> Result<Option<(Self::SignableTransaction, Self::Eventuality)>, NetworkError> {
     assert_eq!(inputs.len(), 0);
     assert!(change.is_none());
-    let chain_id = self.provider.get_chain_id().await.map_err(|_| NetworkError::ConnectionError)?;
+    self.check_chain_id().await?;
+    let chain_id = self.chain_id;
+    let router = self.router().await.as_ref().unwrap().address();
 
     // TODO: Perform fee amortization (in scheduler?
     // TODO: Make this function internal and have needed_fee properly return None as expected?
@@ -657,6 +1267,7 @@ impl<D: Db> Network for Ethereum<D> {
     let command = match scheduler_addendum {
       Addendum::Nonce(nonce) => RouterCommand::Execute {
         chain_id: U256::try_from(chain_id).unwrap(),
+        router,
         nonce: U256::try_from(*nonce).unwrap(),
         outs: payments
           .iter()
@@ -692,6 +1303,7 @@ impl<D: Db> Network for Ethereum<D> {
         assert!(payments.is_empty());
         RouterCommand::UpdateSeraiKey {
           chain_id: U256::try_from(chain_id).unwrap(),
+          router,
           nonce: U256::try_from(*nonce).unwrap(),
           key: PublicKey::new(*new_key).expect("new key wasn't a valid ETH public key"),
         }
@@ -718,35 +1330,83 @@ impl<D: Db> Network for Ethereum<D> {
     &self,
     completion: &<Self::Eventuality as EventualityTrait>::Completion,
   ) -> Result<(), NetworkError> {
-    // Publish this to the dedicated TX server for a solver to actually publish
-    #[cfg(not(test))]
+    let (chain_id, nonce) = match completion.command() {
+      RouterCommand::UpdateSeraiKey { chain_id, nonce, .. } |
+      RouterCommand::Execute { chain_id, nonce, .. } |
+      RouterCommand::SetPaused { chain_id, nonce, .. } => {
+        (*chain_id, u64::try_from(nonce).unwrap())
+      }
+    };
+    // Refuse to publish commands signed for another chain, as may have been persisted before the
+    // node was changed
+    verify_chain_id(self.chain_id, u64::try_from(chain_id).unwrap());
+
+    // Only publish this command if it's the next command the Router will execute
+    // Otherwise, its transaction would predictably fail, wasting the relayer's gas
     {
-      let mut msg = vec![];
-      match completion.command() {
-        RouterCommand::UpdateSeraiKey { nonce, .. } | RouterCommand::Execute { nonce, .. } => {
-          msg.extend(&u32::try_from(nonce).unwrap().to_le_bytes());
+      let router = self.router().await;
+      let router = router.as_ref().unwrap();
+      let router_nonce = router.latest_nonce().await.map_err(|_| NetworkError::ConnectionError)?;
+      let router_nonce = u64::try_from(router_nonce).unwrap();
+      if router_nonce > nonce {
+        // Another validator already published this, which the eventuality tracker will find
+        log::info!("not publishing command {nonce} as the Router's nonce is {router_nonce}");
+        self.skipped_publications.lock().unwrap().insert(nonce, router_nonce);
+        return Ok(());
+      }
+      if router_nonce < nonce {
+        // The rebroadcast task will reattempt this once the prior commands are executed
+        log::warn!("not publishing command {nonce} as the Router's nonce is only {router_nonce}");
+        return Ok(());
+      }
+    }
+
+    // Publish this ourselves, if configured to
+    #[cfg(not(test))]
+    if let Some(publisher) = &self.publisher {
+      let router = self.router().await;
+      let router = router.as_ref().unwrap();
+      let mut publisher = publisher.lock().await;
+
+      // Bump the fee of any transactions we've published which were left unmined
+      for (nonce, publication) in
+        publisher.poll(router).await.map_err(|_| NetworkError::ConnectionError)?
+      {
+        match publication {
+          Publication::Published(hash) => {
+            log::info!("bumped the fee for command {nonce}, republishing as {}", hex::encode(hash))
+          }
+          Publication::Unfunded { signer, amount } => {
+            log::error!("couldn't bump the fee for command {nonce} as {signer} needs {amount} wei")
+          }
         }
       }
-      completion.write(&mut msg).unwrap();
 
-      let Ok(mut socket) = TcpStream::connect(&self.relayer_url).await else {
-        log::warn!("couldn't connect to the relayer server");
-        Err(NetworkError::ConnectionError)?
-      };
-      let Ok(()) = socket.write_all(&u32::try_from(msg.len()).unwrap().to_le_bytes()).await else {
-        log::warn!("couldn't send the message's len to the relayer server");
-        Err(NetworkError::ConnectionError)?
-      };
-      let Ok(()) = socket.write_all(&msg).await else {
-        log::warn!("couldn't write the message to the relayer server");
-        Err(NetworkError::ConnectionError)?
-      };
-      if socket.read_u8().await.ok() != Some(1) {
-        log::warn!("didn't get the ack from the relayer server");
-        Err(NetworkError::ConnectionError)?;
+      if !publisher.pending().contains(&nonce) {
+        match publisher
+          .publish(nonce, command_transaction(router, completion))
+          .await
+          .map_err(|_| NetworkError::ConnectionError)?
+        {
+          Publication::Published(hash) => {
+            log::info!("published command {nonce} as {}", hex::encode(hash))
+          }
+          Publication::Unfunded { signer, amount } => {
+            log::error!("couldn't publish command {nonce} as {signer} needs {amount} wei");
+            Err(NetworkError::ConnectionError)?;
+          }
+        }
       }
+      return Ok(());
+    }
 
-      Ok(())
+    // Publish this to the dedicated TX server for a solver to actually publish
+    #[cfg(not(test))]
+    {
+      let mut msg = vec![];
+      msg.extend(&u32::try_from(nonce).unwrap().to_le_bytes());
+      completion.write(&mut msg).unwrap();
+      self.relayers.publish(&msg).await
     }
 
     // Publish this using a dummy account we fund with magic RPC commands
@@ -755,15 +1415,7 @@ impl<D: Db> Network for Ethereum<D> {
       let router = self.router().await;
       let router = router.as_ref().unwrap();
 
-      let mut tx = match completion.command() {
-        RouterCommand::UpdateSeraiKey { key, .. } => {
-          router.update_serai_key(key, completion.signature())
-        }
-        RouterCommand::Execute { outs, .. } => router.execute(
-          &outs.iter().cloned().map(Into::into).collect::<Vec<_>>(),
-          completion.signature(),
-        ),
-      };
+      let mut tx = command_transaction(router, completion);
       tx.gas_limit = 1_000_000u64;
       tx.gas_price = 1_000_000_000u64.into();
       let tx = ethereum_serai::crypto::deterministically_sign(&tx);
@@ -835,25 +1487,26 @@ impl<D: Db> Network for Ethereum<D> {
     // TODO: Review why this is sub(3) and not sub(2)
     for block in block.saturating_sub(3) ..= block {
       match eventuality.1 {
-        RouterCommand::UpdateSeraiKey { nonce, .. } | RouterCommand::Execute { nonce, .. } => {
+        RouterCommand::UpdateSeraiKey { nonce, .. } |
+        RouterCommand::Execute { nonce, .. } |
+        RouterCommand::SetPaused { nonce, .. } => {
           let router = self.router().await;
           let router = router.as_ref().unwrap();
 
           let block = u64::try_from(block).unwrap();
-          let filter = router
-            .key_updated_filter()
-            .from_block(block * 32)
-            .to_block(((block + 1) * 32) - 1)
-            .topic1(nonce);
-          let logs = self.provider.get_logs(&filter).await.unwrap();
-          if let Some(log) = logs.first() {
-            return self
-              .provider
-              .get_transaction_by_hash(log.clone().transaction_hash.unwrap())
-              .await
-              .unwrap()
-              .unwrap();
-          };
+          for filter in [router.key_updated_filter(), router.paused_updated_filter()] {
+            let filter =
+              filter.from_block(block * 32).to_block(((block + 1) * 32) - 1).topic1(nonce);
+            let logs = self.provider.get_logs(&filter).await.unwrap();
+            if let Some(log) = logs.first() {
+              return self
+                .provider
+                .get_transaction_by_hash(log.clone().transaction_hash.unwrap())
+                .await
+                .unwrap()
+                .unwrap();
+            };
+          }
 
           let filter = router
             .executed_filter()
@@ -883,22 +1536,9 @@ impl<D: Db> Network for Ethereum<D> {
 
   #[cfg(test)]
   async fn test_send(&self, send_to: Self::Address) -> Self::Block {
-    use rand_core::OsRng;
-    use ciphersuite::group::ff::Field;
     use ethereum_serai::alloy::sol_types::SolCall;
 
-    let key = <Secp256k1 as Ciphersuite>::F::random(&mut OsRng);
-    let address = ethereum_serai::crypto::address(&(Secp256k1::generator() * key));
-
-    // Set a 1.1 ETH balance
-    self
-      .provider
-      .raw_request::<_, ()>(
-        "anvil_setBalance".into(),
-        [Address(address).to_string(), "1100000000000000000".into()],
-      )
-      .await
-      .unwrap();
+    let key = self.test_account().await;
 
     let value = U256::from_str_radix("1000000000000000000", 10).unwrap();
     let tx = ethereum_serai::alloy::consensus::TxLegacy {
@@ -918,22 +1558,8 @@ impl<D: Db> Network for Ethereum<D> {
       .into(),
     };
 
-    use ethereum_serai::alloy::{
-      primitives::{Parity, Signature},
-      consensus::SignableTransaction,
-    };
-    let sig = k256::ecdsa::SigningKey::from(k256::elliptic_curve::NonZeroScalar::new(key).unwrap())
-      .sign_prehash_recoverable(tx.signature_hash().as_ref())
-      .unwrap();
-
-    let mut bytes = vec![];
-    let parity = Parity::NonEip155(Parity::from(sig.1).y_parity());
-    tx.encode_with_signature_fields(&Signature::from(sig).with_parity(parity), &mut bytes);
-    let pending_tx = self.provider.send_raw_transaction(&bytes).await.ok().unwrap();
-
     // Mine an epoch containing this TX
-    self.mine_block().await;
-    assert!(pending_tx.get_receipt().await.unwrap().status());
+    self.test_publish(key, tx).await;
     // Yield the freshly mined block
     self.get_block(self.get_latest_block_number().await.unwrap()).await.unwrap()
   }
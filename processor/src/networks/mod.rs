@@ -323,6 +323,14 @@ pub trait Network: 'static + Send + Sync + Clone + PartialEq + Debug {
   /// Get a block by its number.
   async fn get_block(&self, number: usize) -> Result<Self::Block, NetworkError>;
 
+  /// Wait for a new block to potentially be available, for at most the specified duration.
+  ///
+  /// By default, this sleeps for the specified duration, polling. Networks which can be notified
+  /// of new blocks may return as soon as they are.
+  async fn wait_for_new_block(&self, duration: Duration) {
+    sleep(duration).await;
+  }
+
   /// Get the latest block's number, retrying until success.
   async fn get_latest_block_number_with_retries(&self) -> usize {
     loop {
@@ -385,6 +393,22 @@ pub trait Network: 'static + Send + Sync + Clone + PartialEq + Debug {
     ),
   >;
 
+  /// Get the registered eventualities invalidated within this block, and any prior blocks which
+  /// registered eventualities may have been invalidated in, removing them from the tracker.
+  ///
+  /// An eventuality is invalidated if it can never be completed, such as when the key it was
+  /// signed for is no longer accepted. This must be called before `get_eventuality_completions`
+  /// for the same block, as that advances the tracker's block number.
+  ///
+  /// Returns the IDs of the invalidated Plans.
+  async fn get_eventuality_invalidations(
+    &self,
+    _eventualities: &mut EventualitiesTracker<Self::Eventuality>,
+    _block: &Self::Block,
+  ) -> Vec<[u8; 32]> {
+    vec![]
+  }
+
   /// Returns the needed fee to fulfill this Plan at this fee rate.
   ///
   /// Returns None if this Plan isn't fulfillable (such as when the fee exceeds the input value).
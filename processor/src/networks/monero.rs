@@ -1,3 +1,4 @@
+use core::fmt;
 use std::{time::Duration, collections::HashMap, io};
 
 use async_trait::async_trait;
@@ -16,11 +17,12 @@ use frost::{curve::Ed25519, ThresholdKeys};
 use monero_simple_request_rpc::SimpleRequestRpc;
 use monero_wallet::{
   ringct::RctType,
-  transaction::Transaction,
+  transaction::{Timelock, Transaction},
   block::Block,
   rpc::{FeeRate, RpcError, Rpc},
   address::{Network as MoneroNetwork, SubaddressIndex},
   ViewPair, GuaranteedViewPair, WalletOutput, OutputWithDecoys, GuaranteedScanner,
+  OutputIndexError, verify_output_index,
   send::{
     SendError, Change, SignableTransaction as MSignableTransaction, Eventuality, TransactionMachine,
   },
@@ -36,7 +38,7 @@ pub use serai_client::{
 };
 
 use crate::{
-  Payment, additional_key,
+  Db, DbTxn, create_db, Payment, additional_key,
   networks::{
     NetworkError, Block as BlockTrait, OutputType, Output as OutputTrait,
     Transaction as TransactionTrait, SignableTransaction as SignableTransactionTrait,
@@ -53,7 +55,7 @@ const BRANCH_SUBADDRESS: Option<SubaddressIndex> = SubaddressIndex::new(1, 0);
 const CHANGE_SUBADDRESS: Option<SubaddressIndex> = SubaddressIndex::new(2, 0);
 const FORWARD_SUBADDRESS: Option<SubaddressIndex> = SubaddressIndex::new(3, 0);
 
-impl OutputTrait<Monero> for Output {
+impl<D: Db> OutputTrait<Monero<D>> for Output {
   // While we could use (tx, o), using the key ensures we won't be susceptible to the burning bug.
   // While we already are immune, thanks to using featured address, this doesn't hurt and is
   // technically more efficient.
@@ -111,14 +113,14 @@ impl OutputTrait<Monero> for Output {
 
 // TODO: Consider ([u8; 32], TransactionPruned)
 #[async_trait]
-impl TransactionTrait<Monero> for Transaction {
+impl<D: Db> TransactionTrait<Monero<D>> for Transaction {
   type Id = [u8; 32];
   fn id(&self) -> Self::Id {
     self.hash()
   }
 
   #[cfg(test)]
-  async fn fee(&self, _: &Monero) -> u64 {
+  async fn fee(&self, _: &Monero<D>) -> u64 {
     match self {
       Transaction::V1 { .. } => panic!("v1 TX in test-only function"),
       Transaction::V2 { ref proofs, .. } => proofs.as_ref().unwrap().base.fee,
@@ -146,7 +148,7 @@ impl EventualityTrait for Eventuality {
   }
 
   fn claim(tx: &Transaction) -> [u8; 32] {
-    tx.id()
+    tx.hash()
   }
   fn serialize_completion(completion: &Transaction) -> Vec<u8> {
     completion.serialize()
@@ -165,7 +167,7 @@ impl SignableTransactionTrait for SignableTransaction {
 }
 
 #[async_trait]
-impl BlockTrait<Monero> for Block {
+impl<D: Db> BlockTrait<Monero<D>> for Block {
   type Id = [u8; 32];
   fn id(&self) -> Self::Id {
     self.hash()
@@ -175,7 +177,7 @@ impl BlockTrait<Monero> for Block {
     self.header.previous
   }
 
-  async fn time(&self, rpc: &Monero) -> u64 {
+  async fn time(&self, rpc: &Monero<D>) -> u64 {
     // Constant from Monero
     const BLOCKCHAIN_TIMESTAMP_CHECK_WINDOW: usize = 60;
 
@@ -186,7 +188,7 @@ impl BlockTrait<Monero> for Block {
     }
 
     let mut timestamps = vec![self.header.timestamp];
-    let mut parent = self.parent();
+    let mut parent = self.header.previous;
     while timestamps.len() < BLOCKCHAIN_TIMESTAMP_CHECK_WINDOW {
       let mut parent_block;
       while {
@@ -198,7 +200,7 @@ impl BlockTrait<Monero> for Block {
       }
       let parent_block = parent_block.unwrap();
       timestamps.push(parent_block.header.timestamp);
-      parent = parent_block.parent();
+      parent = parent_block.header.previous;
 
       if parent_block.number().unwrap() == 0 {
         break;
@@ -222,18 +224,36 @@ impl BlockTrait<Monero> for Block {
   }
 }
 
-#[derive(Clone, Debug)]
-pub struct Monero {
+create_db!(
+  MoneroProcessor {
+    // Outputs received to a key which are subject to an additional timelock, with the number of
+    // the block they were reported as of (once their timelock was satisfied)
+    AdditionallyLockedOutputs: (key: [u8; 32]) -> Vec<(Vec<u8>, Option<u64>)>,
+  }
+);
+
+#[derive(Clone)]
+pub struct Monero<D: Db> {
+  // This DB is solely used to track outputs which are subject to an additional timelock, so they
+  // may be reported once they unlock
+  db: D,
   rpc: SimpleRequestRpc,
+  // The node to verify the output indexes provided by `rpc` against, if they aren't trusted
+  output_index_verifier: Option<SimpleRequestRpc>,
 }
 // Shim required for testing/debugging purposes due to generic arguments also necessitating trait
 // bounds
-impl PartialEq for Monero {
+impl<D: Db> PartialEq for Monero<D> {
   fn eq(&self, _: &Self) -> bool {
     true
   }
 }
-impl Eq for Monero {}
+impl<D: Db> Eq for Monero<D> {}
+impl<D: Db> fmt::Debug for Monero<D> {
+  fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt.debug_struct("Monero").field("rpc", &self.rpc).finish_non_exhaustive()
+  }
+}
 
 #[allow(clippy::needless_pass_by_value)] // Needed to satisfy API expectations
 fn map_rpc_err(err: RpcError) -> NetworkError {
@@ -250,19 +270,41 @@ enum MakeSignableTransactionResult {
   SignableTransaction(MSignableTransaction),
 }
 
-impl Monero {
-  pub async fn new(url: String) -> Monero {
+impl<D: Db> Monero<D> {
+  async fn connect(url: String) -> SimpleRequestRpc {
     let mut res = SimpleRequestRpc::new(url.clone()).await;
     while let Err(e) = res {
       log::error!("couldn't connect to Monero node: {e:?}");
       tokio::time::sleep(Duration::from_secs(5)).await;
       res = SimpleRequestRpc::new(url.clone()).await;
     }
-    Monero { rpc: res.unwrap() }
+    res.unwrap()
+  }
+
+  pub async fn new(db: D, url: String) -> Monero<D> {
+    let rpc = Self::connect(url).await;
+
+    /*
+      The scanner trusts the node for the output index of the first RingCT output within each
+      block, from which it derives the output index of every output scanned. An incorrect index
+      isn't noticed until we try to spend the output, so optionally verify it, either against the
+      output distribution of this node or of a distinct node.
+    */
+    let output_index_verifier = match serai_env::var("MONERO_OUTPUT_INDEX_VERIFICATION_RPC_URL") {
+      Some(url) => Some(Self::connect(url).await),
+      None => serai_env::var("MONERO_VERIFY_OUTPUT_INDEXES")
+        .map(|verify| {
+          verify.parse::<bool>().expect("MONERO_VERIFY_OUTPUT_INDEXES wasn't true or false")
+        })
+        .unwrap_or(false)
+        .then(|| rpc.clone()),
+    };
+
+    Monero { db, rpc, output_index_verifier }
   }
 
   fn view_pair(spend: EdwardsPoint) -> GuaranteedViewPair {
-    GuaranteedViewPair::new(spend.0, Zeroizing::new(additional_key::<Monero>(0).0)).unwrap()
+    GuaranteedViewPair::new(spend.0, Zeroizing::new(additional_key::<Self>(0).0)).unwrap()
   }
 
   fn address_internal(spend: EdwardsPoint, subaddress: Option<SubaddressIndex>) -> Address {
@@ -455,7 +497,7 @@ impl Monero {
 }
 
 #[async_trait]
-impl Network for Monero {
+impl<D: Db> Network for Monero<D> {
   type Curve = Ed25519;
 
   type Transaction = Transaction;
@@ -466,7 +508,7 @@ impl Network for Monero {
   type Eventuality = Eventuality;
   type TransactionMachine = TransactionMachine;
 
-  type Scheduler = Scheduler<Monero>;
+  type Scheduler = Scheduler<Monero<D>>;
 
   type Address = Address;
 
@@ -520,25 +562,96 @@ impl Network for Monero {
 
   async fn get_outputs(&self, block: &Block, key: EdwardsPoint) -> Vec<Output> {
     let outputs = loop {
-      match self
-        .rpc
-        .get_scannable_block(block.clone())
-        .await
-        .map_err(|e| format!("{e:?}"))
-        .and_then(|block| Self::scanner(key).scan(block).map_err(|e| format!("{e:?}")))
-      {
+      let scannable = match self.rpc.get_scannable_block(block.clone()).await {
+        Ok(scannable) => scannable,
+        Err(e) => {
+          log::error!("couldn't get scannable block {}: {e:?}", hex::encode(block.hash()));
+          sleep(Duration::from_secs(60)).await;
+          continue;
+        }
+      };
+
+      if let Some(verifier) = &self.output_index_verifier {
+        match verify_output_index(verifier, &scannable).await {
+          Ok(()) => {}
+          Err(OutputIndexError::Rpc(e)) => {
+            log::error!(
+              "couldn't verify the output index for block {}: {e:?}",
+              hex::encode(block.hash())
+            );
+            sleep(Duration::from_secs(60)).await;
+            continue;
+          }
+          // Scanning with an incorrect index would produce outputs we're unable to spend
+          Err(e) => panic!(
+            "Monero node provided an incorrect output index for block {}: {e}",
+            hex::encode(block.hash())
+          ),
+        }
+      }
+
+      match Self::scanner(key).scan(scannable) {
         Ok(outputs) => break outputs,
         Err(e) => {
-          log::error!("couldn't scan block {}: {e:?}", hex::encode(block.id()));
+          log::error!("couldn't scan block {}: {e:?}", hex::encode(block.hash()));
           sleep(Duration::from_secs(60)).await;
           continue;
         }
       }
     };
 
-    // Miner transactions are required to explicitly state their timelock, so this does exclude
-    // those (which have an extended timelock we don't want to deal with)
-    let raw_outputs = outputs.not_additionally_locked();
+    /*
+      Outputs subject to an additional timelock can't be spent until it's satisfied, so we don't
+      report them when they're scanned. Instead, we save them and report them as of the first
+      block which satisfies their timelock.
+
+      The block they're reported as of is saved alongside them, and they're retained after being
+      reported. This ensures if this block is rescanned (as happens when rebooting before the
+      scanner saves it), they're reported as of the same block again. Since additional timelocks
+      are effectively unused outside of miner transactions, this shouldn't grow notably large.
+    */
+    let number = block.number().unwrap();
+    let key_bytes = key.compress().to_bytes();
+    let mut locked = AdditionallyLockedOutputs::get(&self.db, key_bytes).unwrap_or(vec![]);
+    let mut updated = false;
+    let mut raw_outputs = vec![];
+    for output in outputs.ignore_additional_timelock() {
+      if output.additional_timelock() == Timelock::None {
+        raw_outputs.push(output);
+        continue;
+      }
+
+      let serialized = output.serialize();
+      if !locked.iter().any(|(existing, _)| *existing == serialized) {
+        log::info!(
+          "found output {} with additional timelock {:?}",
+          hex::encode(output.key().compress().to_bytes()),
+          output.additional_timelock(),
+        );
+        locked.push((serialized, None));
+        updated = true;
+      }
+    }
+    for (output, reported_as_of) in &mut locked {
+      let output = WalletOutput::read::<&[u8]>(&mut output.as_slice()).unwrap();
+      if reported_as_of.is_none() &&
+        ((output.additional_timelock() <= Timelock::Block(number)) ||
+          (output.additional_timelock() <= Timelock::Time(block.header.timestamp)))
+      {
+        *reported_as_of = Some(u64::try_from(number).unwrap());
+        updated = true;
+      }
+      if *reported_as_of == Some(u64::try_from(number).unwrap()) {
+        raw_outputs.push(output);
+      }
+    }
+    if updated {
+      let mut db = self.db.clone();
+      let mut txn = db.txn();
+      AdditionallyLockedOutputs::set(&mut txn, key_bytes, &locked);
+      txn.commit();
+    }
+
     let mut outputs = Vec::with_capacity(raw_outputs.len());
     for output in raw_outputs {
       // This should be pointless as we shouldn't be able to scan for any other subaddress
@@ -563,7 +676,7 @@ impl Network for Monero {
     }
 
     async fn check_block(
-      network: &Monero,
+      network: &Monero<D>,
       eventualities: &mut EventualitiesTracker<Eventuality>,
       block: &Block,
       res: &mut HashMap<[u8; 32], (usize, [u8; 32], Transaction)>,
@@ -585,7 +698,7 @@ impl Network for Monero {
           if eventuality.matches(&tx.clone().into()) {
             res.insert(
               eventualities.map.remove(&tx.prefix().extra).unwrap().0,
-              (block.number().unwrap(), tx.id(), tx),
+              (block.number().unwrap(), tx.hash(), tx),
             );
           }
         }
@@ -752,7 +865,7 @@ impl Network for Monero {
 
     let amount = output.commitment().amount;
     // The dust should always be sufficient for the fee
-    let fee = Monero::DUST;
+    let fee = Self::DUST;
 
     let rct_type = match new_block.header.hardfork_version {
       14 => RctType::ClsagBulletproof,
@@ -798,7 +911,7 @@ impl Network for Monero {
   }
 }
 
-impl UtxoNetwork for Monero {
+impl<D: Db> UtxoNetwork for Monero<D> {
   // wallet2 will not create a transaction larger than 100kb, and Monero won't relay a transaction
   // larger than 150kb. This fits within the 100kb mark
   // Technically, it can be ~124, yet a small bit of buffer is appreciated
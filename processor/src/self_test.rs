@@ -0,0 +1,208 @@
+use core::{future::Future, time::Duration};
+
+use serai_client::primitives::ExternalNetworkId;
+
+use message_queue::client::MessageQueue;
+
+use crate::{
+  Db, DbTxn, Get,
+  networks::{Network, NetworkError},
+};
+#[cfg(feature = "bitcoin")]
+use crate::networks::Bitcoin;
+#[cfg(feature = "ethereum")]
+use crate::networks::Ethereum;
+#[cfg(feature = "monero")]
+use crate::networks::Monero;
+
+// How long to wait for any individual check before considering it failed
+const CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/*
+  A self-test validating the processor's configuration, intended to be run by deployment
+  automation before starting the processor.
+
+  This checks the external network's node is reachable, the message-queue accepts our key, and
+  the DB is writable. For Ethereum, it additionally checks the Deployer and Router are present,
+  and that a relayer is reachable.
+
+  The processor doesn't communicate with the Serai node (solely with the coordinator, via the
+  message-queue), so there is no check for it here.
+*/
+
+struct Check {
+  name: &'static str,
+  result: Result<String, String>,
+}
+
+#[derive(Default)]
+struct Report(Vec<Check>);
+
+impl Report {
+  fn push(&mut self, name: &'static str, result: Result<String, String>) {
+    match &result {
+      Ok(detail) => log::info!("self-test check {name} passed: {detail}"),
+      Err(detail) => log::error!("self-test check {name} failed: {detail}"),
+    }
+    self.0.push(Check { name, result });
+  }
+
+  fn passed(&self) -> bool {
+    self.0.iter().all(|check| check.result.is_ok())
+  }
+
+  fn to_json(&self) -> serde_json::Value {
+    let checks = self
+      .0
+      .iter()
+      .map(|check| {
+        let (passed, detail) = match &check.result {
+          Ok(detail) => (true, detail),
+          Err(detail) => (false, detail),
+        };
+        serde_json::json!({ "name": check.name, "passed": passed, "detail": detail })
+      })
+      .collect::<Vec<_>>();
+    serde_json::json!({ "passed": self.passed(), "checks": checks })
+  }
+}
+
+fn check_db<D: Db>(db: &mut D) -> Result<String, String> {
+  const KEY: &[u8] = b"processor_self_test";
+  const VALUE: &[u8] = b"writable";
+
+  let mut txn = db.txn();
+  txn.put(KEY, VALUE);
+  txn.commit();
+  if db.get(KEY).as_deref() != Some(VALUE) {
+    Err("value written to the DB wasn't read back".to_string())?;
+  }
+
+  let mut txn = db.txn();
+  txn.del(KEY);
+  txn.commit();
+  if db.get(KEY).is_some() {
+    Err("value deleted from the DB was still present".to_string())?;
+  }
+
+  Ok("DB is writable".to_string())
+}
+
+async fn check_message_queue(coordinator: &MessageQueue) -> Result<String, String> {
+  match tokio::time::timeout(CHECK_TIMEOUT, coordinator.check_key()).await {
+    Ok(Some(true)) => Ok("message-queue accepted our key".to_string()),
+    Ok(Some(false)) => Err("message-queue rejected our key".to_string()),
+    Ok(None) => Err("couldn't connect to the message-queue".to_string()),
+    Err(_) => Err("timed out connecting to the message-queue".to_string()),
+  }
+}
+
+async fn connect<N: Network>(report: &mut Report, network: impl Future<Output = N>) -> Option<N> {
+  match tokio::time::timeout(CHECK_TIMEOUT, network).await {
+    Ok(network) => {
+      report.push("network_connection", Ok(format!("connected to the {} node", N::ID)));
+      Some(network)
+    }
+    Err(_) => {
+      report.push("network_connection", Err(format!("couldn't connect to the {} node", N::ID)));
+      None
+    }
+  }
+}
+
+async fn check_network<N: Network>(network: &N) -> Result<String, String> {
+  match tokio::time::timeout(CHECK_TIMEOUT, network.get_latest_block_number()).await {
+    Ok(Ok(number)) => Ok(format!("latest block is #{number}")),
+    Ok(Err(NetworkError::ConnectionError)) => {
+      Err("couldn't get the latest block number".to_string())
+    }
+    Err(_) => Err("timed out getting the latest block number".to_string()),
+  }
+}
+
+#[cfg(feature = "ethereum")]
+async fn check_router<D: Db>(network: &Ethereum<D>) -> Result<String, String> {
+  match tokio::time::timeout(CHECK_TIMEOUT, network.router_deployed()).await {
+    Ok(Ok(Some(true))) => Ok("Router is deployed".to_string()),
+    Ok(Ok(Some(false))) => Err("Router isn't deployed despite having confirmed a key".to_string()),
+    Ok(Ok(None)) => Ok("no key has been confirmed yet, so there's no Router".to_string()),
+    Ok(Err(NetworkError::ConnectionError)) => Err("couldn't search for the Router".to_string()),
+    Err(_) => Err("timed out searching for the Router".to_string()),
+  }
+}
+
+// Check at least one relayer is reachable
+//
+// Unreachable relayers are solely noted, as transactions will be published with the others.
+#[cfg(feature = "ethereum")]
+async fn check_relayers(relayer_urls: &[String]) -> Result<String, String> {
+  let mut unreachable = vec![];
+  for url in relayer_urls {
+    let connection = tokio::time::timeout(CHECK_TIMEOUT, tokio::net::TcpStream::connect(url)).await;
+    if !matches!(connection, Ok(Ok(_))) {
+      unreachable.push(url.as_str());
+    }
+  }
+  let detail = format!(
+    "{} of {} relayers were reachable{}",
+    relayer_urls.len() - unreachable.len(),
+    relayer_urls.len(),
+    if unreachable.is_empty() { String::new() } else { format!(" ({:?} weren't)", unreachable) },
+  );
+  if unreachable.len() == relayer_urls.len() {
+    Err(detail)?;
+  }
+  Ok(detail)
+}
+
+/// Run the self-test, printing a JSON report to stdout and exiting with a non-zero status if any
+/// check failed.
+pub async fn self_test<D: Db>(
+  mut db: D,
+  url: String,
+  network_id: ExternalNetworkId,
+  coordinator: MessageQueue,
+) -> ! {
+  let mut report = Report::default();
+
+  report.push("db", check_db(&mut db));
+  report.push("message_queue", check_message_queue(&coordinator).await);
+
+  #[allow(unreachable_patterns)]
+  match network_id {
+    #[cfg(feature = "bitcoin")]
+    ExternalNetworkId::Bitcoin => {
+      if let Some(network) = connect(&mut report, Bitcoin::new(url)).await {
+        report.push("network_rpc", check_network(&network).await);
+      }
+    }
+    #[cfg(feature = "ethereum")]
+    ExternalNetworkId::Ethereum => {
+      let relayer_urls = crate::networks::ethereum::relayer_urls_from_env();
+      report.push(
+        "ethereum_relayer_config",
+        if relayer_urls.is_empty() {
+          Err("relayer URLs/hostname/port weren't specified".to_string())
+        } else {
+          Ok(format!("{} relayers were specified", relayer_urls.len()))
+        },
+      );
+      report.push("ethereum_relayers", check_relayers(&relayer_urls).await);
+      // Ethereum::new waits for the Deployer to be deployed, so this also checks for it
+      if let Some(network) = connect(&mut report, Ethereum::new(db, url, relayer_urls)).await {
+        report.push("network_rpc", check_network(&network).await);
+        report.push("ethereum_router", check_router(&network).await);
+      }
+    }
+    #[cfg(feature = "monero")]
+    ExternalNetworkId::Monero => {
+      if let Some(network) = connect(&mut report, Monero::new(db, url)).await {
+        report.push("network_rpc", check_network(&network).await);
+      }
+    }
+    _ => report.push("network", Err("processor wasn't built for this network".to_string())),
+  }
+
+  println!("{}", report.to_json());
+  std::process::exit(if report.passed() { 0 } else { 1 });
+}
@@ -1,4 +1,4 @@
-use core::{marker::PhantomData, fmt};
+use core::{marker::PhantomData, fmt, time::Duration};
 use std::collections::HashMap;
 
 use rand_core::OsRng;
@@ -15,10 +15,16 @@ use messages::sign::*;
 pub use serai_db::*;
 
 use crate::{
-  Get, DbTxn, Db,
+  Get, DbTxn, Db, HaltedDb,
   networks::{Eventuality, Network},
+  deadline::{NetworkTime, Deadline},
 };
 
+// How long a transaction may take to be completed on-chain, from when we started signing it,
+// before we alarm, in terms of confirmations of the network and wall-clock time
+const COMPLETION_ALARM_CONFIRMATIONS: usize = 3;
+const COMPLETION_ALARM_DURATION: Duration = Duration::from_secs(2 * 60 * 60);
+
 create_db!(
   SignerDb {
     CompletionsDb: (id: [u8; 32]) -> Vec<u8>,
@@ -26,6 +32,7 @@ create_db!(
     AttemptDb: (id: &SignId) -> (),
     CompletionDb: (claim: &[u8]) -> Vec<u8>,
     ActiveSignsDb: () -> Vec<[u8; 32]>,
+    CompletionDeadlineDb: (id: [u8; 32]) -> Deadline,
     CompletedOnChainDb: (id: &[u8; 32]) -> (),
   }
 );
@@ -44,6 +51,7 @@ impl ActiveSignsDb {
 impl CompletedOnChainDb {
   fn complete_on_chain(txn: &mut impl DbTxn, id: &[u8; 32]) {
     CompletedOnChainDb::set(txn, id, &());
+    CompletionDeadlineDb::del(txn, *id);
     ActiveSignsDb::set(
       txn,
       &ActiveSignsDb::get(txn)
@@ -190,7 +198,38 @@ impl<N: Network, D: Db> Signer<N, D> {
   pub async fn rebroadcast_task(db: D, network: N) {
     log::info!("rebroadcasting transactions for plans whose completions yet to be confirmed...");
     loop {
-      for active in ActiveSignsDb::get(&db).unwrap_or_default() {
+      // If Serai halted this network, don't rebroadcast anything until it's resumed
+      if HaltedDb::halted(&db) {
+        info!("not rebroadcasting transactions as this network is halted");
+        tokio::time::sleep(core::time::Duration::from_secs(60)).await;
+        continue;
+      }
+
+      let active_signs = ActiveSignsDb::get(&db).unwrap_or_default();
+
+      // Alarm on any transactions not completed on-chain by their deadline
+      if !active_signs.is_empty() {
+        match network.get_latest_block_number().await {
+          Ok(latest) => {
+            let now = NetworkTime::at_block(latest);
+            for active in &active_signs {
+              let Some(deadline) = CompletionDeadlineDb::get(&db, *active) else { continue };
+              if deadline.reached(now) {
+                let (blocks, secs) = deadline.overdue(now);
+                warn!(
+                  "plan {} wasn't completed on-chain by its deadline ({} blocks, {}s overdue)",
+                  hex::encode(active),
+                  blocks.unwrap_or(0),
+                  secs.unwrap_or(0),
+                );
+              }
+            }
+          }
+          Err(e) => warn!("couldn't get the latest block to check completion deadlines: {e:?}"),
+        }
+      }
+
+      for active in active_signs {
         for claim in CompletionsDb::completions::<N>(&db, active) {
           log::info!("rebroadcasting completion with claim {}", hex::encode(claim.as_ref()));
           // TODO: Don't drop the error entirely. Check for invariants
@@ -305,6 +344,33 @@ impl<N: Network, D: Db> Signer<N, D> {
     }
   }
 
+  /// Stop signing for a plan which can no longer be completed.
+  ///
+  /// Returns Some if we were signing for it.
+  #[must_use]
+  pub fn invalidated(
+    &mut self,
+    txn: &mut D::Transaction<'_>,
+    id: [u8; 32],
+  ) -> Option<ProcessorMessage> {
+    // Stop rebroadcasting any completion and alarming on it not being completed
+    CompletionDeadlineDb::del(txn, id);
+    ActiveSignsDb::set(
+      txn,
+      &ActiveSignsDb::get(txn)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|active| active != &id)
+        .collect::<Vec<_>>(),
+    );
+
+    self.signable.remove(&id)?;
+    self.attempt.remove(&id);
+    self.preprocessing.remove(&id);
+    self.signing.remove(&id);
+    Some(ProcessorMessage::Invalidated { session: self.session, id })
+  }
+
   /// Returns Some if the first completion.
   // Doesn't use any loops/retries since we'll eventually get this from the Scanner anyways
   #[must_use]
@@ -468,6 +534,21 @@ impl<N: Network, D: Db> Signer<N, D> {
       return None;
     }
 
+    // Set the deadline for this to be completed on-chain by, if this is the first time we were
+    // told to sign it (and not a re-issued sign order after rebooting)
+    if CompletionDeadlineDb::get(txn, id).is_none() {
+      let now = NetworkTime::at_block(self.network.get_latest_block_number_with_retries().await);
+      CompletionDeadlineDb::set(
+        txn,
+        id,
+        &Deadline::after(
+          now,
+          COMPLETION_ALARM_CONFIRMATIONS * N::CONFIRMATIONS,
+          COMPLETION_ALARM_DURATION,
+        ),
+      );
+    }
+
     EventualityDb::save_eventuality::<N>(txn, id, eventuality);
 
     self.signable.insert(id, tx);
@@ -618,8 +699,14 @@ impl<N: Network, D: Db> Signer<N, D> {
         // Save the completion in case it's needed for recovery
         CompletionsDb::complete::<N>(txn, id.id, &completion);
 
-        // Publish it
-        if let Err(e) = self.network.publish_completion(&completion).await {
+        // Publish it, unless Serai halted this network
+        // If halted, the rebroadcast task will publish it once this network is resumed
+        if HaltedDb::halted(txn) {
+          warn!(
+            "not publishing completion for plan {} as this network is halted",
+            hex::encode(id.id)
+          );
+        } else if let Err(e) = self.network.publish_completion(&completion).await {
           error!("couldn't publish completion for plan {}: {:?}", hex::encode(id.id), e);
         } else {
           info!("published completion for plan {}", hex::encode(id.id));
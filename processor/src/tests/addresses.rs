@@ -79,6 +79,9 @@ async fn spend<N: UtxoNetwork, D: Db>(
     ScannerEvent::Completed(_, _, _, _, _) => {
       panic!("unexpectedly got eventuality completion");
     }
+    ScannerEvent::Invalidated(_, _, _) => {
+      panic!("unexpectedly got eventuality invalidation");
+    }
   }
 }
 
@@ -101,7 +104,7 @@ pub async fn test_addresses<N: UtxoNetwork>(
     network.mine_block().await;
   }
 
-  let (mut scanner, current_keys) = Scanner::new(network.clone(), db.clone());
+  let (mut scanner, current_keys) = Scanner::new(network.clone(), None, db.clone());
   assert!(current_keys.is_empty());
   let mut txn = db.txn();
   scanner.register_key(&mut txn, network.get_latest_block_number().await.unwrap(), key).await;
@@ -138,6 +141,9 @@ pub async fn test_addresses<N: UtxoNetwork>(
       ScannerEvent::Completed(_, _, _, _, _) => {
         panic!("unexpectedly got eventuality completion");
       }
+      ScannerEvent::Invalidated(_, _, _) => {
+        panic!("unexpectedly got eventuality invalidation");
+      }
     };
   }
 
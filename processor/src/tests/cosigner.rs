@@ -24,6 +24,7 @@ fn test_cosigner() {
 
   let participant_one = Participant::new(1).unwrap();
 
+  let session_start = [0xbb; 32];
   let block_number = OsRng.next_u64();
   let block = [0xaa; 32];
 
@@ -54,9 +55,16 @@ fn test_cosigner() {
 
     let mut db = MemDb::new();
     let mut txn = db.txn();
-    let (signer, preprocess) =
-      Cosigner::new(&mut txn, Session(0), vec![keys], block_number, block, actual_id.attempt)
-        .unwrap();
+    let (signer, preprocess) = Cosigner::new(
+      &mut txn,
+      Session(0),
+      vec![keys],
+      session_start,
+      block_number,
+      block,
+      actual_id.attempt,
+    )
+    .unwrap();
 
     match preprocess {
       // All participants should emit a preprocess
@@ -114,10 +122,16 @@ fn test_cosigner() {
       )
       .unwrap()
     {
-      ProcessorMessage::CosignedBlock { block_number, block: signed_block, signature } => {
+      ProcessorMessage::CosignedBlock {
+        session_start: signed_session_start,
+        block_number,
+        block: signed_block,
+        signature,
+      } => {
+        assert_eq!(signed_session_start, session_start);
         assert_eq!(signed_block, block);
         assert!(Public::from_raw(keys[&participant_one].group_key().to_bytes()).verify(
-          &cosign_block_msg(block_number, block),
+          &cosign_block_msg(session_start, block_number, block),
           &Signature(signature.try_into().unwrap())
         ));
       }
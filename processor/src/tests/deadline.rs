@@ -0,0 +1,26 @@
+use core::time::Duration;
+
+use crate::deadline::{NetworkTime, Deadline};
+
+#[test]
+fn deadline() {
+  let now = NetworkTime { block: 10, unix: 1000 };
+
+  let by_block = Deadline::by_block(12);
+  assert!(!by_block.reached(now));
+  assert!(by_block.reached(NetworkTime { block: 12, unix: 0 }));
+  assert_eq!(by_block.overdue(now), (Some(0), None));
+  assert_eq!(by_block.overdue(NetworkTime { block: 15, unix: 0 }), (Some(3), None));
+
+  // Deadlines expressed in both terms are reached once either is
+  let deadline = Deadline::after(now, 2, Duration::from_secs(60));
+  assert_eq!(deadline, by_block.or_by_time(1060));
+  assert!(!deadline.reached(now));
+  assert!(deadline.reached(NetworkTime { block: 12, unix: 1000 }));
+  assert!(deadline.reached(NetworkTime { block: 10, unix: 1060 }));
+  assert_eq!(deadline.overdue(NetworkTime { block: 11, unix: 1100 }), (Some(0), Some(40)));
+
+  // Additional terms only ever make a deadline sooner
+  assert_eq!(deadline.or_by_time(2000), deadline);
+  assert_eq!(deadline.or_by_time(1030), by_block.or_by_time(1030));
+}
@@ -0,0 +1,46 @@
+use serai_db::{Db, DbTxn, MemDb};
+
+use crate::indexer::{index_block, roll_back_block, mark_scanned, too_deep_reorg, Indexer};
+
+#[test]
+fn indexer() {
+  let mut db = MemDb::new();
+
+  let mut txn = db.txn();
+  index_block(&mut txn, 10, [10; 32]);
+  index_block(&mut txn, 11, [11; 32]);
+  index_block(&mut txn, 12, [12; 32]);
+  txn.commit();
+
+  // Rolling back a block which wasn't scanned should drop its hash
+  let mut txn = db.txn();
+  assert_eq!(roll_back_block(&mut txn, 12), Some(()));
+  txn.commit();
+  assert_eq!(Indexer::indexed_block(&db, 12), None);
+  assert_eq!(Indexer::indexed_block(&db, 11), Some([11; 32]));
+
+  // Marking blocks as scanned should only retain the hash of the latest scanned block
+  let mut txn = db.txn();
+  mark_scanned(&mut txn, 10);
+  mark_scanned(&mut txn, 11);
+  txn.commit();
+  assert_eq!(Indexer::indexed_block(&db, 10), None);
+  assert_eq!(Indexer::indexed_block(&db, 11), Some([11; 32]));
+  assert_eq!(too_deep_reorg(&db), None);
+}
+
+#[test]
+fn reorg_of_scanned_block() {
+  let mut db = MemDb::new();
+  let mut txn = db.txn();
+  index_block(&mut txn, 10, [10; 32]);
+  mark_scanned(&mut txn, 10);
+  txn.commit();
+
+  // A reorg of a block which was already scanned should halt the indexer
+  let mut txn = db.txn();
+  assert_eq!(roll_back_block(&mut txn, 10), None);
+  txn.commit();
+  assert_eq!(too_deep_reorg(&db), Some(10));
+  assert_eq!(Indexer::indexed_block(&db, 10), Some([10; 32]));
+}
@@ -0,0 +1,88 @@
+use ethereum_serai::{
+  alloy::primitives::U256, crypto::PublicKey, machine::RouterCommand, router::KeyUpdate,
+  tests::key_gen,
+};
+
+use serai_client::primitives::{Amount, ExternalBalance, ExternalCoin};
+
+use serai_db::{Db, DbTxn, MemDb};
+
+use crate::{
+  Payment,
+  networks::{
+    EventualitiesTracker,
+    ethereum::{Address, Eventuality, Ethereum, invalidate_eventualities},
+  },
+  multisigs::db::InvalidatedPaymentsDb,
+};
+
+fn update_serai_key(key: PublicKey, nonce: u64, new_key: PublicKey) -> Eventuality {
+  Eventuality(
+    key,
+    RouterCommand::UpdateSeraiKey {
+      chain_id: U256::from(1),
+      router: [0xaa; 20],
+      nonce: U256::from(nonce),
+      key: new_key,
+    },
+  )
+}
+
+fn execute(key: PublicKey, nonce: u64) -> Eventuality {
+  Eventuality(
+    key,
+    RouterCommand::Execute {
+      chain_id: U256::from(1),
+      router: [0xaa; 20],
+      nonce: U256::from(nonce),
+      outs: vec![],
+    },
+  )
+}
+
+#[test]
+fn key_update_invalidates_later_commands() {
+  let (_, old_key) = key_gen();
+  let (_, new_key) = key_gen();
+
+  let mut eventualities = EventualitiesTracker::new();
+  eventualities.register(0, [1; 32], execute(old_key, 1));
+  eventualities.register(0, [2; 32], update_serai_key(old_key, 2, new_key));
+  eventualities.register(0, [3; 32], execute(old_key, 3));
+  eventualities.register(0, [4; 32], execute(new_key, 4));
+
+  let update = KeyUpdate { block: 10, tx_id: [0xff; 32], nonce: 2, key: new_key };
+  // Only the command signed by the old key with a nonce after the update should be invalidated
+  assert_eq!(invalidate_eventualities(&mut eventualities, &[update]), vec![[3; 32]]);
+  assert_eq!(eventualities.map.len(), 3);
+  // The update itself, and the commands before it, remain for their completions
+  assert!(eventualities.map.values().any(|(id, _)| *id == [1; 32]));
+  assert!(eventualities.map.values().any(|(id, _)| *id == [2; 32]));
+
+  // Seeing the same update again shouldn't invalidate anything further
+  assert!(invalidate_eventualities(&mut eventualities, &[update]).is_empty());
+}
+
+#[test]
+fn invalidated_payments() {
+  let payment = |amount| Payment::<Ethereum<MemDb>> {
+    address: Address([0xbb; 20]),
+    data: None,
+    balance: ExternalBalance { coin: ExternalCoin::Ether, amount: Amount(amount) },
+  };
+
+  let mut db = MemDb::new();
+  let mut txn = db.txn();
+  InvalidatedPaymentsDb::save_invalidated_payments(&mut txn, &[payment(1)]);
+  InvalidatedPaymentsDb::save_invalidated_payments(&mut txn, &[payment(2), payment(3)]);
+  txn.commit();
+
+  // The payments should be returned once
+  let mut txn = db.txn();
+  assert_eq!(
+    InvalidatedPaymentsDb::take_invalidated_payments::<Ethereum<MemDb>>(&mut txn),
+    vec![payment(1), payment(2), payment(3)]
+  );
+  assert!(InvalidatedPaymentsDb::take_invalidated_payments::<Ethereum<MemDb>>(&mut txn).is_empty());
+  txn.commit();
+}
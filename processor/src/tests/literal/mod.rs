@@ -229,6 +229,7 @@ mod bitcoin {
     bitcoin_no_deadlock_in_multisig_completed,
     bitcoin_signer,
     bitcoin_wallet,
+    bitcoin_serialization,
     bitcoin_addresses,
   );
 }
@@ -259,18 +260,18 @@ mod monero {
 
   async fn monero(
     ops: &DockerOperations,
-  ) -> impl Fn(MemDb) -> Pin<Box<dyn Send + Future<Output = Monero>>> {
+  ) -> impl Fn(MemDb) -> Pin<Box<dyn Send + Future<Output = Monero<MemDb>>>> {
     let handle = ops.handle("serai-dev-monero").host_port(18081).unwrap();
     let url = format!("http://serai:seraidex@{}:{}", handle.0, handle.1);
-    let monero = Monero::new(url.clone()).await;
+    let monero = Monero::new(MemDb::new(), url.clone()).await;
     while monero.get_latest_block_number().await.unwrap() < 150 {
       monero.mine_block().await;
     }
-    move |_db| Box::pin(Monero::new(url.clone()))
+    move |db| Box::pin(Monero::new(db, url.clone()))
   }
 
   test_utxo_network!(
-    Monero,
+    Monero<MemDb>,
     spawn_monero,
     monero,
     monero_key_gen,
@@ -278,6 +279,7 @@ mod monero {
     monero_no_deadlock_in_multisig_completed,
     monero_signer,
     monero_wallet,
+    monero_serialization,
     monero_addresses,
   );
 }
@@ -286,11 +288,59 @@ mod monero {
 mod ethereum {
   use super::*;
 
-  use ciphersuite::{Ciphersuite, Secp256k1};
+  use std::collections::HashSet;
 
-  use serai_client::validator_sets::primitives::Session;
+  use rand_core::OsRng;
+
+  use ciphersuite::{group::GroupEncoding, Ciphersuite, Secp256k1};
+  use frost::Participant;
+
+  use ethereum_serai::alloy::primitives::U256;
+
+  use serai_db::{DbTxn, Db};
+  use serai_client::{
+    primitives::{ExternalCoin, Amount, ExternalBalance},
+    validator_sets::primitives::Session,
+  };
 
-  use crate::networks::Ethereum;
+  use crate::{
+    networks::{OutputType, Output, Network, Ethereum, ethereum::DAI},
+    key_gen::NetworkKeyDb,
+  };
+
+  #[test]
+  fn test_erc677_deposit() {
+    let docker = spawn_ethereum();
+    docker.run(|ops| async move {
+      let mut keys = frost::tests::key_gen::<_, Secp256k1>(&mut OsRng)
+        .remove(&Participant::new(1).unwrap())
+        .unwrap();
+      <Ethereum<MemDb> as Network>::tweak_keys(&mut keys);
+      let group_key = keys.group_key();
+
+      let mut db = MemDb::new();
+      {
+        let mut txn = db.txn();
+        NetworkKeyDb::set(&mut txn, Session(0), &group_key.to_bytes().as_ref().to_vec());
+        txn.commit();
+      }
+      let eth = ethereum(&ops).await(db).await.with_erc677_tokens(HashSet::from([DAI]));
+
+      // Deposit 1 DAI via `transferAndCall`
+      let block = eth.test_send_erc677(U256::from(10u64).pow(U256::from(18u64)), &[]).await;
+      let outputs = eth.get_outputs(&block, group_key).await;
+      assert_eq!(outputs.len(), 1);
+      assert_eq!(outputs[0].kind(), OutputType::External);
+      assert_eq!(
+        outputs[0].balance(),
+        ExternalBalance { coin: ExternalCoin::Dai, amount: Amount(100_000_000) }
+      );
+
+      // If DAI isn't registered as supporting ERC-677, the deposit shouldn't be credited
+      let eth = eth.with_erc677_tokens(HashSet::new());
+      assert!(eth.get_outputs(&block, group_key).await.is_empty());
+    });
+  }
 
   fn spawn_ethereum() -> DockerTest {
     serai_docker_tests::build("ethereum".to_string());
@@ -423,7 +473,7 @@ mod ethereum {
           });
         }
 
-        Ethereum::new(db, url.clone(), String::new()).await
+        Ethereum::new(db, url.clone(), vec![]).await
       })
     }
   }
@@ -437,5 +487,6 @@ mod ethereum {
     ethereum_no_deadlock_in_multisig_completed,
     ethereum_signer,
     ethereum_wallet,
+    ethereum_serialization,
   );
 }
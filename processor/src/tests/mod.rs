@@ -14,6 +14,20 @@ mod wallet;
 
 mod addresses;
 
+mod deadline;
+
+#[cfg(feature = "ethereum")]
+mod relayers;
+#[cfg(feature = "ethereum")]
+mod new_heads;
+#[cfg(feature = "ethereum")]
+mod indexer;
+#[cfg(feature = "ethereum")]
+mod invalidations;
+
+mod serialization;
+pub(crate) use serialization::*;
+
 // Effective Once
 static INIT_LOGGER_CELL: OnceLock<()> = OnceLock::new();
 fn init_logger() {
@@ -31,10 +45,11 @@ macro_rules! test_network {
     $no_deadlock_in_multisig_completed: ident,
     $signer: ident,
     $wallet: ident,
+    $serialization: ident,
   ) => {
     use core::{pin::Pin, future::Future};
     use $crate::tests::{
-      init_logger,
+      init_logger, test_network_id_serialization,
       key_gen::test_key_gen,
       scanner::{test_scanner, test_no_deadlock_in_multisig_completed},
       signer::test_signer,
@@ -48,6 +63,14 @@ macro_rules! test_network {
       test_key_gen::<$N>();
     }
 
+    // The serialization of outputs and eventualities is tested by the wallet test, as they
+    // require a node to create
+    #[test]
+    fn $serialization() {
+      init_logger();
+      test_network_id_serialization::<$N>();
+    }
+
     #[test]
     fn $scanner() {
       init_logger();
@@ -101,6 +124,7 @@ macro_rules! test_utxo_network {
     $no_deadlock_in_multisig_completed: ident,
     $signer: ident,
     $wallet: ident,
+    $serialization: ident,
     $addresses: ident,
   ) => {
     use $crate::tests::addresses::test_addresses;
@@ -114,6 +138,7 @@ macro_rules! test_utxo_network {
       $no_deadlock_in_multisig_completed,
       $signer,
       $wallet,
+      $serialization,
     );
 
     #[test]
@@ -0,0 +1,55 @@
+use serai_telemetry::websocket::{Frame, parse_url, encode_frame, read_frame};
+
+#[test]
+fn ws_url() {
+  assert_eq!(
+    parse_url("ws://127.0.0.1:8546"),
+    Some(("127.0.0.1:8546".to_string(), "/".to_string()))
+  );
+  assert_eq!(
+    parse_url("ws://ethereum:8546/ws"),
+    Some(("ethereum:8546".to_string(), "/ws".to_string()))
+  );
+  assert_eq!(parse_url("ws://ethereum"), Some(("ethereum:80".to_string(), "/".to_string())));
+  assert_eq!(parse_url("ws://[::1]"), Some(("[::1]:80".to_string(), "/".to_string())));
+  assert_eq!(parse_url("ws://[::1]:8546"), Some(("[::1]:8546".to_string(), "/".to_string())));
+  assert_eq!(parse_url("wss://ethereum:8546"), None);
+  assert_eq!(parse_url("http://ethereum:8546"), None);
+  assert_eq!(parse_url("ws:///ws"), None);
+}
+
+#[tokio::test]
+async fn ws_frames() {
+  const MAX_SIZE: usize = 1024 * 1024;
+
+  let mask = [1, 2, 3, 4];
+  for len in [0, 125, 126, u16::MAX.into(), usize::from(u16::MAX) + 1] {
+    let payload = (0 .. len).map(|i| u8::try_from(i % 256).unwrap()).collect::<Vec<_>>();
+    let frame = encode_frame(1, &payload, Some(mask));
+    assert_eq!(frame[0], 0b1000_0001);
+    // Frames from clients must be masked
+    assert_eq!(frame[1] & 0b1000_0000, 0b1000_0000);
+    // Which means they're rejected when read as from a server
+    assert_eq!(read_frame(&mut frame.as_slice(), false, MAX_SIZE).await, None);
+
+    // Reading them as from a client should recover the payload
+    let expected = Frame { fin: true, opcode: 1, payload: payload.clone() };
+    assert_eq!(read_frame(&mut frame.as_slice(), true, MAX_SIZE).await, Some(expected.clone()));
+
+    // Frames from servers aren't masked, and are rejected when read as from a client
+    let frame = encode_frame(1, &payload, None);
+    assert_eq!(frame[1] & 0b1000_0000, 0);
+    assert_eq!(read_frame(&mut frame.as_slice(), true, MAX_SIZE).await, None);
+    assert_eq!(read_frame(&mut frame.as_slice(), false, MAX_SIZE).await, Some(expected));
+  }
+
+  // A non-final frame
+  assert_eq!(
+    read_frame(&mut [0b0000_0001, 1, 0xff].as_slice(), false, MAX_SIZE).await,
+    Some(Frame { fin: false, opcode: 1, payload: vec![0xff] })
+  );
+  // A truncated frame
+  assert_eq!(read_frame(&mut [0b1000_0001, 2, 0xff].as_slice(), false, MAX_SIZE).await, None);
+  // A frame exceeding the maximum size
+  assert_eq!(read_frame(&mut [0b1000_0001, 2, 0xff, 0xff].as_slice(), false, 1).await, None);
+}
@@ -0,0 +1,35 @@
+use core::time::Duration;
+
+use crate::networks::ethereum::Relayers;
+
+// Note a relayer failed, ensuring it failed strictly after any prior failures
+fn fail(relayers: &Relayers, url: &str) {
+  std::thread::sleep(Duration::from_millis(1));
+  relayers.note_result(url, false);
+}
+
+#[test]
+fn relayers() {
+  let urls = ["a:1", "b:2", "c:3"].map(ToString::to_string);
+  let relayers = Relayers::new(urls.to_vec());
+  assert_eq!(relayers.order(), urls);
+
+  // A relayer which failed should be attempted after the others
+  fail(&relayers, "a:1");
+  assert_eq!(relayers.order(), ["b:2", "c:3", "a:1"]);
+
+  // Relayers which failed should be attempted in order of how long ago they failed
+  fail(&relayers, "c:3");
+  assert_eq!(relayers.order(), ["b:2", "a:1", "c:3"]);
+
+  // If every relayer failed, they should all still be attempted
+  fail(&relayers, "b:2");
+  assert_eq!(relayers.order(), ["a:1", "c:3", "b:2"]);
+
+  // A relayer which succeeds should return to its place
+  relayers.note_result("c:3", true);
+  assert_eq!(relayers.order(), ["c:3", "a:1", "b:2"]);
+  relayers.note_result("a:1", true);
+  relayers.note_result("b:2", true);
+  assert_eq!(relayers.order(), urls);
+}
@@ -14,7 +14,10 @@ use serai_client::validator_sets::primitives::Session;
 use crate::{
   networks::{OutputType, Output, Block, Network},
   key_gen::NetworkKeyDb,
-  multisigs::scanner::{ScannerEvent, Scanner, ScannerHandle},
+  multisigs::{
+    attestation::NodeAttestor,
+    scanner::{ScannerEvent, Scanner, ScannerHandle},
+  },
 };
 
 pub async fn new_scanner<N: Network, D: Db>(
@@ -25,7 +28,9 @@ pub async fn new_scanner<N: Network, D: Db>(
 ) -> ScannerHandle<N, D> {
   let activation_number = network.get_latest_block_number().await.unwrap();
   let mut db = db.clone();
-  let (mut scanner, current_keys) = Scanner::new(network.clone(), db.clone());
+  // Attest to blocks with the same node, exercising attestation without it ever diverging
+  let attestor = Arc::new(NodeAttestor(network.clone()));
+  let (mut scanner, current_keys) = Scanner::new(network.clone(), Some(attestor), db.clone());
   let mut first = first.lock().await;
   if *first {
     assert!(current_keys.is_empty());
@@ -85,6 +90,9 @@ pub async fn test_scanner<N: Network>(
         ScannerEvent::Completed(_, _, _, _, _) => {
           panic!("unexpectedly got eventuality completion");
         }
+        ScannerEvent::Invalidated(_, _, _) => {
+          panic!("unexpectedly got eventuality invalidation");
+        }
       };
     (scanner, outputs)
   };
@@ -129,7 +137,7 @@ pub async fn test_no_deadlock_in_multisig_completed<N: Network>(
     network.mine_block().await;
   }
 
-  let (mut scanner, current_keys) = Scanner::new(network.clone(), db.clone());
+  let (mut scanner, current_keys) = Scanner::new(network.clone(), None, db.clone());
   assert!(current_keys.is_empty());
 
   // Register keys to cause Block events at CONFIRMATIONS (dropped since first keys),
@@ -179,6 +187,9 @@ pub async fn test_no_deadlock_in_multisig_completed<N: Network>(
       ScannerEvent::Completed(_, _, _, _, _) => {
         panic!("unexpectedly got eventuality completion");
       }
+      ScannerEvent::Invalidated(_, _, _) => {
+        panic!("unexpectedly got eventuality invalidation");
+      }
     };
 
   // Block for the third set of keys registered
@@ -187,6 +198,9 @@ pub async fn test_no_deadlock_in_multisig_completed<N: Network>(
     ScannerEvent::Completed(_, _, _, _, _) => {
       panic!("unexpectedly got eventuality completion");
     }
+    ScannerEvent::Invalidated(_, _, _) => {
+      panic!("unexpectedly got eventuality invalidation");
+    }
   };
 
   // The ack_block acquisition shows the Scanner isn't maintaining the lock on its own thread after
@@ -0,0 +1,91 @@
+use rand_core::{RngCore, OsRng};
+
+use crate::networks::{Id, Output, Eventuality, Network};
+
+// Check mutating each byte of a serialization either fails to deserialize or deserializes to a
+// distinct value, and that truncating it fails to deserialize
+fn test_mutations<T: PartialEq + core::fmt::Debug>(
+  value: &T,
+  serialized: &[u8],
+  read: impl Fn(&mut &[u8]) -> std::io::Result<T>,
+) {
+  for i in 0 .. serialized.len() {
+    let mut mutated = serialized.to_vec();
+    mutated[i] ^= 1;
+    if let Ok(read) = read(&mut mutated.as_slice()) {
+      assert_ne!(&read, value, "mutating byte {i} of the serialization was ignored");
+    }
+  }
+
+  for len in 0 .. serialized.len() {
+    assert!(read(&mut &serialized[.. len]).is_err(), "truncated serialization was read");
+  }
+}
+
+/// Test an ID's serialization.
+///
+/// IDs are persisted as their bytes, so they must be of a fixed size and entirely defined by their
+/// bytes.
+pub fn test_id_serialization<I: Id>() {
+  // The default ID should be the size of every ID
+  let mut id = I::default();
+  let len = id.as_ref().len();
+  OsRng.fill_bytes(id.as_mut());
+  assert_eq!(id.as_ref().len(), len);
+
+  // Round-trip the ID through its bytes
+  let mut read = I::default();
+  read.as_mut().copy_from_slice(id.as_ref());
+  assert_eq!(read, id);
+
+  // Mutating any byte should produce a distinct ID
+  for i in 0 .. len {
+    let mut mutated = id.clone();
+    mutated.as_mut()[i] ^= 1;
+    assert_ne!(mutated, id);
+  }
+}
+
+/// Test the IDs of a network's types.
+pub fn test_network_id_serialization<N: Network>() {
+  test_id_serialization::<<N::Output as Output<N>>::Id>();
+  test_id_serialization::<<N::Transaction as crate::networks::Transaction<N>>::Id>();
+  test_id_serialization::<<N::Block as crate::networks::Block<N>>::Id>();
+  test_id_serialization::<<N::Eventuality as Eventuality>::Claim>();
+}
+
+/// Test an output's serialization.
+pub fn test_output_serialization<N: Network>(output: &N::Output) {
+  let mut serialized = vec![];
+  output.write(&mut serialized).unwrap();
+  let read = N::Output::read::<&[u8]>(&mut serialized.as_ref()).unwrap();
+  assert_eq!(&read, output);
+  assert_eq!(read.id(), output.id());
+
+  // The serialization should be canonical
+  let mut reserialized = vec![];
+  read.write(&mut reserialized).unwrap();
+  assert_eq!(reserialized, serialized);
+
+  test_mutations(output, &serialized, |reader| N::Output::read(reader));
+}
+
+/// Test an eventuality's serialization, along with the serialization of its completion.
+pub fn test_eventuality_serialization<N: Network>(
+  eventuality: &N::Eventuality,
+  completion: &<N::Eventuality as Eventuality>::Completion,
+) {
+  let serialized = eventuality.serialize();
+  let read = N::Eventuality::read::<&[u8]>(&mut serialized.as_ref()).unwrap();
+  assert_eq!(&read, eventuality);
+  assert_eq!(read.lookup(), eventuality.lookup());
+  assert_eq!(read.serialize(), serialized);
+  test_mutations(eventuality, &serialized, |reader| N::Eventuality::read(reader));
+
+  let serialized = N::Eventuality::serialize_completion(completion);
+  let read = N::Eventuality::read_completion::<&[u8]>(&mut serialized.as_ref()).unwrap();
+  assert_eq!(&read, completion);
+  assert_eq!(N::Eventuality::claim(&read), N::Eventuality::claim(completion));
+  assert_eq!(N::Eventuality::serialize_completion(&read), serialized);
+  test_mutations(completion, &serialized, |reader| N::Eventuality::read_completion(reader));
+}
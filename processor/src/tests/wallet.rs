@@ -23,7 +23,7 @@ use crate::{
     scanner::{ScannerEvent, Scanner},
     scheduler::{self, Scheduler},
   },
-  tests::sign,
+  tests::{sign, test_output_serialization, test_eventuality_serialization},
 };
 
 // Tests the Scanner, Scheduler, and Signer together
@@ -49,7 +49,7 @@ pub async fn test_wallet<N: Network>(
     network.mine_block().await;
   }
 
-  let (mut scanner, current_keys) = Scanner::new(network.clone(), db.clone());
+  let (mut scanner, current_keys) = Scanner::new(network.clone(), None, db.clone());
   assert!(current_keys.is_empty());
   let (block_id, outputs) = {
     let mut txn = db.txn();
@@ -73,12 +73,18 @@ pub async fn test_wallet<N: Network>(
       ScannerEvent::Completed(_, _, _, _, _) => {
         panic!("unexpectedly got eventuality completion");
       }
+      ScannerEvent::Invalidated(_, _, _) => {
+        panic!("unexpectedly got eventuality invalidation");
+      }
     }
   };
   let mut txn = db.txn();
   assert_eq!(scanner.ack_block(&mut txn, block_id.clone()).await.1, outputs);
   scanner.release_lock().await;
   txn.commit();
+  for output in &outputs {
+    test_output_serialization::<N>(output);
+  }
 
   let mut txn = db.txn();
   let mut scheduler = N::Scheduler::new::<MemDb>(&mut txn, key, N::NETWORK);
@@ -175,6 +181,7 @@ pub async fn test_wallet<N: Network>(
   for eventuality in eventualities {
     let completion = network.confirm_completion(&eventuality, &claim).await.unwrap().unwrap();
     assert_eq!(N::Eventuality::claim(&completion), claim);
+    test_eventuality_serialization::<N>(&eventuality, &completion);
   }
 
   for _ in 1 .. N::CONFIRMATIONS {
@@ -192,6 +199,9 @@ pub async fn test_wallet<N: Network>(
       ScannerEvent::Completed(_, _, _, _, _) => {
         panic!("unexpectedly got eventuality completion");
       }
+      ScannerEvent::Invalidated(_, _, _) => {
+        panic!("unexpectedly got eventuality invalidation");
+      }
     }
 
     // Check the Scanner DB can reload the outputs
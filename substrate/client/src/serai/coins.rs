@@ -1,13 +1,9 @@
-use scale::Encode;
-
 use serai_abi::primitives::{SeraiAddress, Amount, Coin, Balance};
 pub use serai_abi::coins::primitives;
 use primitives::OutInstructionWithBalance;
 
 use crate::{TemporalSerai, SeraiError};
 
-const PALLET: &str = "Coins";
-
 pub type CoinsEvent = serai_abi::coins::Event;
 
 #[derive(Clone, Copy)]
@@ -47,26 +43,13 @@ impl<'a> SeraiCoins<'a> {
       .await
   }
 
-  pub async fn coin_supply(&self, coin: Coin) -> Result<Amount, SeraiError> {
-    Ok(self.0.storage(PALLET, "Supply", coin).await?.unwrap_or(Amount(0)))
-  }
+  storage! {
+    "Coins";
+
+    pub async fn coin_supply(coin: Coin) -> Amount = "Supply" [Identity(coin)] default Amount(0);
 
-  pub async fn coin_balance(
-    &self,
-    coin: Coin,
-    address: SeraiAddress,
-  ) -> Result<Amount, SeraiError> {
-    Ok(
-      self
-        .0
-        .storage(
-          PALLET,
-          "Balances",
-          (sp_core::hashing::blake2_128(&address.encode()), &address.0, coin),
-        )
-        .await?
-        .unwrap_or(Amount(0)),
-    )
+    pub async fn coin_balance(coin: Coin, address: SeraiAddress) -> Amount =
+      "Balances" [Blake2_128Concat(address)] [Identity(coin)] default Amount(0);
   }
 
   pub fn transfer(to: SeraiAddress, balance: Balance) -> serai_abi::Call {
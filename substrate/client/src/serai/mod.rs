@@ -18,6 +18,9 @@ use abi::*;
 pub use primitives::{SeraiAddress, Signature, Amount};
 use primitives::{Header, NetworkId};
 
+#[macro_use]
+mod storage;
+
 pub mod coins;
 pub use coins::SeraiCoins;
 pub mod dex;
@@ -31,6 +34,11 @@ pub use genesis_liquidity::SeraiGenesisLiquidity;
 pub mod liquidity_tokens;
 pub use liquidity_tokens::SeraiLiquidityTokens;
 
+/// The version of the runtime this library was written for.
+///
+/// Blocks produced under a distinct runtime version may not be interpretable by this library.
+pub const SPEC_VERSION: u32 = 1;
+
 #[derive(Clone, PartialEq, Eq, Debug, scale::Encode, scale::Decode)]
 pub struct Block {
   pub header: Header,
@@ -170,7 +178,6 @@ impl Serai {
   }
 
   pub fn sign(&self, signer: &Pair, call: Call, nonce: u32, tip: u64) -> Transaction {
-    const SPEC_VERSION: u32 = 1;
     const TX_VERSION: u32 = 1;
 
     let extra = Extra { era: sp_runtime::generic::Era::Immortal, nonce, tip };
@@ -344,16 +351,39 @@ impl<'a> TemporalSerai<'a> {
     Ok(res)
   }
 
+  /// The version of the runtime this block was produced under.
+  pub async fn spec_version(&self) -> Result<u32, SeraiError> {
+    #[derive(Deserialize)]
+    struct RuntimeVersion {
+      #[serde(rename = "specVersion")]
+      spec_version: u32,
+    }
+
+    let version: RuntimeVersion =
+      self.serai.call("state_getRuntimeVersion", [hex::encode(self.block)]).await?;
+    Ok(version.spec_version)
+  }
+
   async fn storage<K: Encode, R: Decode>(
     &self,
     pallet: &'static str,
     name: &'static str,
     key: K,
+  ) -> Result<Option<R>, SeraiError> {
+    self.hashed_storage(pallet, name, key.encode()).await
+  }
+
+  // Fetch a storage item, with the key already hashed as the storage item's hashers specify
+  async fn hashed_storage<R: Decode>(
+    &self,
+    pallet: &'static str,
+    name: &'static str,
+    key: Vec<u8>,
   ) -> Result<Option<R>, SeraiError> {
     // TODO: Make this const?
     let mut full_key = sp_core::hashing::twox_128(pallet.as_bytes()).to_vec();
     full_key.extend(sp_core::hashing::twox_128(name.as_bytes()));
-    full_key.extend(key.encode());
+    full_key.extend(key);
 
     let res: Option<String> =
       self.serai.call("state_getStorage", [hex::encode(full_key), hex::encode(self.block)]).await?;
@@ -0,0 +1,68 @@
+/// A hasher for a storage key, mirroring the hasher of the same name within FRAME.
+pub(crate) trait StorageHasher {
+  fn hash(encoded: &[u8]) -> Vec<u8>;
+}
+
+pub(crate) enum Identity {}
+impl StorageHasher for Identity {
+  fn hash(encoded: &[u8]) -> Vec<u8> {
+    encoded.to_vec()
+  }
+}
+
+#[allow(non_camel_case_types)]
+pub(crate) enum Blake2_128Concat {}
+impl StorageHasher for Blake2_128Concat {
+  fn hash(encoded: &[u8]) -> Vec<u8> {
+    [sp_core::hashing::blake2_128(encoded).as_ref(), encoded].concat()
+  }
+}
+
+pub(crate) enum Twox64Concat {}
+impl StorageHasher for Twox64Concat {
+  fn hash(encoded: &[u8]) -> Vec<u8> {
+    [sp_core::hashing::twox_64(encoded).as_ref(), encoded].concat()
+  }
+}
+
+/// Define typed accessors for a pallet's storage.
+///
+/// Each accessor declares the name of the storage item, followed by its keys, each bracketed
+/// with the hasher it's hashed with (in order). This mirrors the pallet's declaration of the
+/// storage item, so the hashing of keys is never written by hand.
+///
+/// Accessors return `None` if the value isn't present, as with FRAME's `OptionQuery`, unless they
+/// specify a default, which is returned instead, as with FRAME's `ValueQuery`.
+macro_rules! storage {
+  (@get $serai: expr, $pallet: literal, $item: literal, $key: ident) => {
+    $serai.hashed_storage($pallet, $item, $key).await
+  };
+  (@get $serai: expr, $pallet: literal, $item: literal, $key: ident, $default: expr) => {
+    Ok($serai.hashed_storage($pallet, $item, $key).await?.unwrap_or($default))
+  };
+
+  (
+    $pallet: literal;
+    $(
+      $(#[$attr: meta])*
+      $vis: vis async fn $fn_name: ident($($arg: ident: $arg_ty: ty),* $(,)?) -> $value: ty =
+        $item: literal $([$hasher: ident($($key: expr),+ $(,)?)])* $(default $default: expr)?;
+    )*
+  ) => {
+    $(
+      $(#[$attr])*
+      $vis async fn $fn_name(&self, $($arg: $arg_ty),*) -> Result<$value, SeraiError> {
+        #[allow(unused_mut)]
+        let mut key = vec![];
+        $(
+          key.extend(
+            <crate::serai::storage::$hasher as crate::serai::storage::StorageHasher>::hash(
+              &scale::Encode::encode(&($(&$key,)+)),
+            ),
+          );
+        )*
+        storage!(@get self.0, $pallet, $item, key $(, $default)?)
+      }
+    )*
+  };
+}
@@ -1,5 +1,3 @@
-use scale::Encode;
-
 use sp_core::sr25519::{Public, Signature};
 
 use serai_abi::{primitives::Amount, validator_sets::primitives::ExternalValidatorSet};
@@ -11,8 +9,6 @@ use crate::{
   Transaction, Serai, TemporalSerai, SeraiError,
 };
 
-const PALLET: &str = "ValidatorSets";
-
 pub type ValidatorSetsEvent = serai_abi::validator_sets::Event;
 
 #[derive(Clone, Copy)]
@@ -103,87 +99,47 @@ impl<'a> SeraiValidatorSets<'a> {
       .await
   }
 
-  pub async fn session(&self, network: NetworkId) -> Result<Option<Session>, SeraiError> {
-    self.0.storage(PALLET, "CurrentSession", network).await
-  }
+  storage! {
+    "ValidatorSets";
 
-  pub async fn participants(
-    &self,
-    network: NetworkId,
-  ) -> Result<Option<Vec<(Public, u64)>>, SeraiError> {
-    self.0.storage(PALLET, "Participants", network).await
-  }
+    pub async fn session(network: NetworkId) -> Option<Session> =
+      "CurrentSession" [Identity(network)];
 
-  pub async fn allocation_per_key_share(
-    &self,
-    network: NetworkId,
-  ) -> Result<Option<Amount>, SeraiError> {
-    self.0.storage(PALLET, "AllocationPerKeyShare", network).await
-  }
+    pub async fn participants(network: NetworkId) -> Option<Vec<(Public, u64)>> =
+      "Participants" [Identity(network)];
 
-  pub async fn total_allocated_stake(
-    &self,
-    network: NetworkId,
-  ) -> Result<Option<Amount>, SeraiError> {
-    self.0.storage(PALLET, "TotalAllocatedStake", network).await
-  }
+    pub async fn allocation_per_key_share(network: NetworkId) -> Option<Amount> =
+      "AllocationPerKeyShare" [Identity(network)];
 
-  pub async fn allocation(
-    &self,
-    network: NetworkId,
-    key: Public,
-  ) -> Result<Option<Amount>, SeraiError> {
-    self
-      .0
-      .storage(
-        PALLET,
-        "Allocations",
-        (sp_core::hashing::blake2_128(&(network, key).encode()), (network, key)),
-      )
-      .await
-  }
+    pub async fn total_allocated_stake(network: NetworkId) -> Option<Amount> =
+      "TotalAllocatedStake" [Identity(network)];
 
-  pub async fn pending_deallocations(
-    &self,
-    network: NetworkId,
-    account: Public,
-    session: Session,
-  ) -> Result<Option<Amount>, SeraiError> {
-    self
-      .0
-      .storage(
-        PALLET,
-        "PendingDeallocations",
-        (sp_core::hashing::blake2_128(&(network, account).encode()), (network, account, session)),
-      )
-      .await
-  }
+    pub async fn allocation(network: NetworkId, key: Public) -> Option<Amount> =
+      "Allocations" [Blake2_128Concat(network, key)];
 
-  pub async fn active_network_validators(
-    &self,
-    network: NetworkId,
-  ) -> Result<Vec<Public>, SeraiError> {
-    self.0.runtime_api("SeraiRuntimeApi_validators", network).await
-  }
+    pub async fn pending_deallocations(
+      network: NetworkId,
+      account: Public,
+      session: Session,
+    ) -> Option<Amount> =
+      "PendingDeallocations" [Blake2_128Concat(network, account)] [Identity(session)];
 
-  // TODO: Store these separately since we almost never need both at once?
-  pub async fn keys(&self, set: ExternalValidatorSet) -> Result<Option<KeyPair>, SeraiError> {
-    self.0.storage(PALLET, "Keys", (sp_core::hashing::twox_64(&set.encode()), set)).await
-  }
+    // TODO: Store these separately since we almost never need both at once?
+    pub async fn keys(set: ExternalValidatorSet) -> Option<KeyPair> =
+      "Keys" [Twox64Concat(set)];
 
-  pub async fn key_pending_slash_report(
-    &self,
-    network: ExternalNetworkId,
-  ) -> Result<Option<Public>, SeraiError> {
-    self.0.storage(PALLET, "PendingSlashReport", network).await
+    pub async fn key_pending_slash_report(network: ExternalNetworkId) -> Option<Public> =
+      "PendingSlashReport" [Identity(network)];
+
+    pub async fn session_begin_block(network: NetworkId, session: Session) -> Option<u64> =
+      "SessionBeginBlock" [Identity(network)] [Identity(session)];
   }
 
-  pub async fn session_begin_block(
+  pub async fn active_network_validators(
     &self,
     network: NetworkId,
-    session: Session,
-  ) -> Result<Option<u64>, SeraiError> {
-    self.0.storage(PALLET, "SessionBeginBlock", (network, session)).await
+  ) -> Result<Vec<Public>, SeraiError> {
+    self.0.runtime_api("SeraiRuntimeApi_validators", network).await
   }
 
   pub fn set_keys(
@@ -61,6 +61,7 @@ pub mod pallet {
     Batch { network: ExternalNetworkId, id: u32, block: BlockHash, instructions_hash: [u8; 32] },
     InstructionFailure { network: ExternalNetworkId, id: u32, index: u32 },
     Halt { network: ExternalNetworkId },
+    Resume { network: ExternalNetworkId },
   }
 
   #[pallet::error]
@@ -236,6 +237,12 @@ pub mod pallet {
       Self::deposit_event(Event::Halt { network });
       Ok(())
     }
+
+    pub fn resume(network: ExternalNetworkId) -> Result<(), DispatchError> {
+      Halted::<T>::remove(network);
+      Self::deposit_event(Event::Resume { network });
+      Ok(())
+    }
   }
 
   fn keys_for_network<T: Config>(
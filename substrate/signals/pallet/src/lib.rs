@@ -1,5 +1,11 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
 #[allow(
   deprecated,
   unreachable_patterns,
@@ -234,6 +240,18 @@ pub mod pallet {
         (total_allocated_stake * REQUIREMENT_NUMERATOR).div_ceil(REQUIREMENT_DIVISOR)
     }
 
+    fn clear_favor(signal_id: SignalId) {
+      for network in serai_primitives::NETWORKS {
+        // Favors may be left over from prior sets, so this isn't bounded by the set size
+        let _ = Favors::<T>::clear_prefix((signal_id, network), u32::MAX, None);
+        let Some(session) = VsPallet::<T>::latest_decided_session(network) else { continue };
+        let set = ValidatorSet { network, session };
+        if SetsInFavor::<T>::take((signal_id, set)).is_some() {
+          Self::deposit_event(Event::SetNoLongerInFavor { signal_id, set });
+        }
+      }
+    }
+
     fn revoke_favor_internal(
       account: T::AccountId,
       signal_id: SignalId,
@@ -342,8 +360,8 @@ pub mod pallet {
         }
 
         // Make sure this is a registered retirement
-        // We don't have to do this for a `Halt` signal as `Halt` doesn't have the registration
-        // process
+        // We don't have to do this for a `Halt`/`Resume` signal as they don't have the
+        // registration process
         let Some(registered_signal) = RegisteredRetirementSignals::<T>::get(signal_id) else {
           return Err::<(), _>(Error::<T>::NonExistentRetirementSignal.into());
         };
@@ -399,8 +417,16 @@ pub mod pallet {
               )));
               Self::deposit_event(Event::RetirementSignalLockedIn { signal_id });
             }
+            // Halting/resuming clears the favor for the opposing signal, so a single favor for
+            // the opposing signal, which would re-tally its stale favors, can't immediately undo
+            // this
             SignalId::Halt(network) => {
               InInstructions::<T>::halt(network)?;
+              Self::clear_favor(SignalId::Resume(network));
+            }
+            SignalId::Resume(network) => {
+              InInstructions::<T>::resume(network)?;
+              Self::clear_favor(SignalId::Halt(network));
             }
           }
         }
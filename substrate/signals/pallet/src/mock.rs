@@ -0,0 +1,193 @@
+//! Test environment for the Signals pallet.
+
+use super::*;
+use crate as signals;
+
+use frame_support::{
+  construct_runtime,
+  traits::{ConstU16, ConstU32, ConstU64},
+};
+
+use sp_core::{H256, sr25519::Public};
+use sp_runtime::{
+  traits::{BlakeTwo256, IdentityLookup},
+  BuildStorage,
+};
+
+use serai_primitives::{Amount, ExternalNetworkId};
+use validator_sets::{primitives::MAX_KEY_SHARES_PER_SET, MembershipProof};
+
+pub use coins_pallet as coins;
+pub use dex_pallet as dex;
+pub use validator_sets_pallet as validator_sets;
+pub use genesis_liquidity_pallet as genesis_liquidity;
+pub use emissions_pallet as emissions;
+pub use economic_security_pallet as economic_security;
+pub use in_instructions_pallet as in_instructions;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// The network validators are signaling for in these tests
+pub const NETWORK: ExternalNetworkId = ExternalNetworkId::Bitcoin;
+pub const STAKE: Amount = Amount(1_000_000 * 10_u64.pow(8));
+
+construct_runtime!(
+  pub enum Test
+  {
+    System: frame_system,
+    Timestamp: pallet_timestamp,
+
+    Coins: coins,
+    LiquidityTokens: coins::<Instance1>::{Pallet, Call, Storage, Event<T>},
+    Dex: dex,
+
+    ValidatorSets: validator_sets,
+
+    GenesisLiquidity: genesis_liquidity,
+    Emissions: emissions,
+    EconomicSecurity: economic_security,
+
+    InInstructions: in_instructions,
+
+    Signals: signals,
+
+    Babe: pallet_babe,
+    Grandpa: pallet_grandpa,
+  }
+);
+
+impl frame_system::Config for Test {
+  type BaseCallFilter = frame_support::traits::Everything;
+  type BlockWeights = ();
+  type BlockLength = ();
+  type RuntimeOrigin = RuntimeOrigin;
+  type RuntimeCall = RuntimeCall;
+  type Nonce = u64;
+  type Hash = H256;
+  type Hashing = BlakeTwo256;
+  type AccountId = Public;
+  type Lookup = IdentityLookup<Self::AccountId>;
+  type Block = Block;
+  type RuntimeEvent = RuntimeEvent;
+  type BlockHashCount = ConstU64<250>;
+  type DbWeight = ();
+  type Version = ();
+  type PalletInfo = PalletInfo;
+  type AccountData = ();
+  type OnNewAccount = ();
+  type OnKilledAccount = ();
+  type SystemWeightInfo = ();
+  type SS58Prefix = ();
+  type OnSetCode = ();
+  type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet_timestamp::Config for Test {
+  type Moment = u64;
+  type OnTimestampSet = Babe;
+  type MinimumPeriod = ConstU64<3000>;
+  type WeightInfo = ();
+}
+
+impl pallet_babe::Config for Test {
+  type EpochDuration = ConstU64<100>;
+  type ExpectedBlockTime = ConstU64<6000>;
+  type EpochChangeTrigger = pallet_babe::ExternalTrigger;
+  type DisabledValidators = ();
+
+  type WeightInfo = ();
+  type MaxAuthorities = ConstU32<MAX_KEY_SHARES_PER_SET>;
+
+  type KeyOwnerProof = MembershipProof<Self>;
+  type EquivocationReportSystem = ();
+}
+
+impl pallet_grandpa::Config for Test {
+  type RuntimeEvent = RuntimeEvent;
+
+  type WeightInfo = ();
+  type MaxAuthorities = ConstU32<MAX_KEY_SHARES_PER_SET>;
+
+  type MaxSetIdSessionEntries = ConstU64<0>;
+  type KeyOwnerProof = MembershipProof<Self>;
+  type EquivocationReportSystem = ();
+}
+
+impl coins::Config for Test {
+  type RuntimeEvent = RuntimeEvent;
+  type AllowMint = ();
+}
+
+impl coins::Config<coins::Instance1> for Test {
+  type RuntimeEvent = RuntimeEvent;
+  type AllowMint = ();
+}
+
+impl dex::Config for Test {
+  type RuntimeEvent = RuntimeEvent;
+
+  type LPFee = ConstU32<3>;
+  type MintMinLiquidity = ConstU64<10000>;
+
+  type MaxSwapPathLength = ConstU32<3>;
+
+  type MedianPriceWindowLength = ConstU16<10>;
+
+  type WeightInfo = ();
+}
+
+impl validator_sets::Config for Test {
+  type RuntimeEvent = RuntimeEvent;
+
+  type ShouldEndSession = Babe;
+}
+
+impl genesis_liquidity::Config for Test {
+  type RuntimeEvent = RuntimeEvent;
+}
+
+impl emissions::Config for Test {
+  type RuntimeEvent = RuntimeEvent;
+}
+
+impl economic_security::Config for Test {
+  type RuntimeEvent = RuntimeEvent;
+}
+
+impl in_instructions::Config for Test {
+  type RuntimeEvent = RuntimeEvent;
+}
+
+impl Config for Test {
+  type RuntimeEvent = RuntimeEvent;
+
+  type RetirementValidityDuration = ConstU32<10>;
+  type RetirementLockInDuration = ConstU32<20>;
+}
+
+pub(crate) fn validators() -> Vec<Public> {
+  (1 ..= 5).map(|i| Public::from_raw([i; 32])).collect()
+}
+
+pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
+  let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+
+  validator_sets::GenesisConfig::<Test> {
+    networks: vec![(NETWORK.into(), STAKE)],
+    participants: validators(),
+  }
+  .assimilate_storage(&mut t)
+  .unwrap();
+  signals::GenesisConfig::<Test>::default().assimilate_storage(&mut t).unwrap();
+
+  let mut ext = sp_io::TestExternalities::new(t);
+  ext.execute_with(|| {
+    System::set_block_number(1);
+    // This is set once the set's handover completes, which these tests don't perform
+    validator_sets::TotalAllocatedStake::<Test>::set(
+      NETWORK.into(),
+      Some(Amount(STAKE.0 * u64::try_from(validators().len()).unwrap())),
+    );
+  });
+  ext
+}
@@ -0,0 +1,99 @@
+use crate::{mock::*, *};
+
+use sp_core::sr25519::Public;
+
+use frame_support::assert_ok;
+
+use serai_primitives::NetworkId;
+use serai_signals_primitives::SignalId;
+use validator_sets::primitives::{Session, ValidatorSet};
+
+fn favor(validator: Public, signal_id: SignalId) {
+  assert_ok!(Signals::favor(RuntimeOrigin::signed(validator), signal_id, NETWORK.into()));
+}
+
+fn in_instructions_events() -> Vec<in_instructions::Event<Test>> {
+  System::events()
+    .into_iter()
+    .filter_map(
+      |r| if let RuntimeEvent::InInstructions(inner) = r.event { Some(inner) } else { None },
+    )
+    .collect()
+}
+
+fn halts() -> usize {
+  in_instructions_events()
+    .into_iter()
+    .filter(|e| matches!(e, in_instructions::Event::Halt { network } if *network == NETWORK))
+    .count()
+}
+
+fn resumes() -> usize {
+  in_instructions_events()
+    .into_iter()
+    .filter(|e| matches!(e, in_instructions::Event::Resume { network } if *network == NETWORK))
+    .count()
+}
+
+fn set() -> ValidatorSet {
+  ValidatorSet { network: NetworkId::from(NETWORK), session: Session(0) }
+}
+
+#[test]
+fn resume_clears_halt_favor() {
+  new_test_ext().execute_with(|| {
+    let validators = validators();
+
+    // 80% of the stake is required, which is four of the five validators
+    for validator in &validators[.. 3] {
+      favor(*validator, SignalId::Halt(NETWORK));
+    }
+    assert_eq!(halts(), 0);
+    favor(validators[3], SignalId::Halt(NETWORK));
+    assert_eq!(halts(), 1);
+
+    for validator in &validators[.. 4] {
+      favor(*validator, SignalId::Resume(NETWORK));
+    }
+    assert_eq!(resumes(), 1);
+    assert!(!SetsInFavor::<Test>::contains_key((SignalId::Halt(NETWORK), set())));
+    assert_eq!(Favors::<Test>::iter_prefix((SignalId::Halt(NETWORK), set().network)).count(), 0);
+
+    // A single validator favoring the halt again doesn't re-tally the prior favor
+    favor(validators[0], SignalId::Halt(NETWORK));
+    assert_eq!(halts(), 1);
+    assert!(!SetsInFavor::<Test>::contains_key((SignalId::Halt(NETWORK), set())));
+
+    // The network can still be halted again
+    for validator in &validators[1 .. 4] {
+      favor(*validator, SignalId::Halt(NETWORK));
+    }
+    assert_eq!(halts(), 2);
+  });
+}
+
+#[test]
+fn halt_clears_resume_favor() {
+  new_test_ext().execute_with(|| {
+    let validators = validators();
+
+    for validator in &validators[.. 4] {
+      favor(*validator, SignalId::Halt(NETWORK));
+    }
+    for validator in &validators[.. 4] {
+      favor(*validator, SignalId::Resume(NETWORK));
+    }
+    assert_eq!((halts(), resumes()), (1, 1));
+
+    // Halt again
+    for validator in &validators[.. 4] {
+      favor(*validator, SignalId::Halt(NETWORK));
+    }
+    assert_eq!(halts(), 2);
+    assert!(!SetsInFavor::<Test>::contains_key((SignalId::Resume(NETWORK), set())));
+
+    // A single validator favoring the resume again doesn't re-tally the prior favor
+    favor(validators[0], SignalId::Resume(NETWORK));
+    assert_eq!(resumes(), 1);
+  });
+}
@@ -14,4 +14,5 @@ use serai_primitives::ExternalNetworkId;
 pub enum SignalId {
   Retirement([u8; 32]),
   Halt(ExternalNetworkId),
+  Resume(ExternalNetworkId),
 }
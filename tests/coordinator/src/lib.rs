@@ -234,6 +234,7 @@ impl Processor {
           struct CurrentCosign {
             block_number: u64,
             block: [u8; 32],
+            session_start: [u8; 32],
           }
           static CURRENT_COSIGN: OnceLock<AsyncMutex<Option<CurrentCosign>>> = OnceLock::new();
           let mut current_cosign =
@@ -243,7 +244,11 @@ impl Processor {
             // While technically, each processor should individually track the current cosign,
             // this is fine for current testing purposes
             CoordinatorMessage::Coordinator(
-              messages::coordinator::CoordinatorMessage::CosignSubstrateBlock { id, block_number },
+              messages::coordinator::CoordinatorMessage::CosignSubstrateBlock {
+                id,
+                session_start,
+                block_number,
+              },
             ) => {
               let SubstrateSignId {
                 id: SubstrateSignableId::CosigningSubstrateBlock(block), ..
@@ -252,7 +257,7 @@ impl Processor {
                 panic!("CosignSubstrateBlock didn't have CosigningSubstrateBlock ID")
               };
 
-              let new_cosign = CurrentCosign { block_number, block };
+              let new_cosign = CurrentCosign { block_number, block, session_start };
               if current_cosign.is_none() || (current_cosign.as_ref().unwrap().block != block) {
                 *current_cosign = Some(new_cosign);
               }
@@ -287,6 +292,7 @@ impl Processor {
 
               let block_number = current_cosign.as_ref().unwrap().block_number;
               let block = current_cosign.as_ref().unwrap().block;
+              let session_start = current_cosign.as_ref().unwrap().session_start;
 
               let substrate_key = substrate_key.lock().await.clone().unwrap();
 
@@ -301,12 +307,13 @@ impl Processor {
               let signature = Signature(
                 schnorrkel::keys::Keypair::from_bytes(&schnorrkel_key_pair)
                   .unwrap()
-                  .sign_simple(b"substrate", &cosign_block_msg(block_number, block))
+                  .sign_simple(b"substrate", &cosign_block_msg(session_start, block_number, block))
                   .to_bytes(),
               );
 
               send_message(
                 messages::coordinator::ProcessorMessage::CosignedBlock {
+                  session_start,
                   block_number,
                   block,
                   signature: signature.0.to_vec(),
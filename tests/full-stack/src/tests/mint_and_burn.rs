@@ -388,7 +388,10 @@ async fn mint_and_burn_test() {
             AddressType::Featured { guaranteed: true, subaddress: false, payment_id: None },
             decompress_point(monero_key_pair.1.to_vec().try_into().unwrap()).unwrap(),
             ED25519_BASEPOINT_POINT *
-              processor::additional_key::<processor::networks::monero::Monero>(0).0,
+              processor::additional_key::<processor::networks::monero::Monero<serai_db::MemDb>>(
+                0,
+              )
+              .0,
           ),
           1_100_000_000_000,
         )],
@@ -77,7 +77,7 @@ pub fn confirmations(network: ExternalNetworkId) -> usize {
   match network {
     ExternalNetworkId::Bitcoin => Bitcoin::CONFIRMATIONS,
     ExternalNetworkId::Ethereum => Ethereum::<serai_db::MemDb>::CONFIRMATIONS,
-    ExternalNetworkId::Monero => Monero::CONFIRMATIONS,
+    ExternalNetworkId::Monero => Monero::<serai_db::MemDb>::CONFIRMATIONS,
   }
 }
 
@@ -450,7 +450,7 @@ impl Wallet {
         }
 
         let to_spend_key = decompress_point(<[u8; 32]>::try_from(to.as_ref()).unwrap()).unwrap();
-        let to_view_key = additional_key::<Monero>(0);
+        let to_view_key = additional_key::<Monero<serai_db::MemDb>>(0);
         let to_addr = Address::new(
           Network::Mainnet,
           AddressType::Featured { subaddress: false, payment_id: None, guaranteed: true },
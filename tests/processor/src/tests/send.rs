@@ -204,7 +204,7 @@ fn send_test() {
           (2 * match network {
             ExternalNetworkId::Bitcoin => Bitcoin::COST_TO_AGGREGATE,
             ExternalNetworkId::Ethereum => Ethereum::<MemDb>::COST_TO_AGGREGATE,
-            ExternalNetworkId::Monero => Monero::COST_TO_AGGREGATE,
+            ExternalNetworkId::Monero => Monero::<MemDb>::COST_TO_AGGREGATE,
           }),
       );
 